@@ -28,8 +28,8 @@
 //! - **内存安全**: 利用Rust的所有权系统确保内存安全
 use std::sync::Arc;
 use std::collections::HashMap;
-use sea_orm::{DatabaseConnection, EntityTrait, ColumnTrait, QueryFilter, ActiveModelTrait, Statement, ConnectionTrait};
-use crate::models::entities::{channel_point_definition, channel_test_instance, test_batch_info};
+use sea_orm::{DatabaseConnection, EntityTrait, ColumnTrait, QueryFilter, ActiveModelTrait, Statement, ConnectionTrait, TransactionTrait};
+use crate::models::entities::{channel_point_definition, test_batch_info};
 use crate::models::structs::{ChannelPointDefinition, ChannelTestInstance, TestBatchInfo};
 use crate::models::enums::ModuleType;
 use crate::error::AppError;
@@ -38,6 +38,8 @@ use uuid::Uuid;
 use serde_json;
 use log::{info, warn, error};
 use crate::domain::services::channel_state_manager::IChannelStateManager;
+use crate::domain::services::persistence_service::TransactionOperation;
+use crate::infrastructure::persistence::transaction_writer;
 
 /// 分配策略
 #[derive(Debug, Clone)]
@@ -164,8 +166,13 @@ impl BatchAllocationService {
         // 2. 根据策略分组通道
         let grouped_definitions = self.group_definitions_by_strategy(&available_definitions, &strategy);
 
+        // 批次、测试实例与分配记录在同一事务内写入，出错时事务随 txn 释放自动回滚
+        let txn = self.db.begin().await
+            .map_err(|e| AppError::persistence_error(format!("开启批次分配事务失败: {}", e)))?;
+
         // 3. 创建测试批次信息
         let batch_info = self.create_batch_info(
+            &txn,
             batch_name,
             product_model,
             operator_name.clone(),
@@ -174,6 +181,7 @@ impl BatchAllocationService {
 
         // 4. 创建测试实例
         let test_instances = self.create_test_instances(
+            &txn,
             &batch_info,
             &grouped_definitions,
         ).await?;
@@ -189,7 +197,9 @@ impl BatchAllocationService {
         );
 
         // 保存分配记录到数据库
-        self.save_allocation_record(&batch_info.batch_id, &strategy, &allocation_summary, operator_name.as_deref()).await?;
+        self.save_allocation_record(&txn, &batch_info.batch_id, &strategy, &allocation_summary, operator_name.as_deref()).await?;
+        txn.commit().await
+            .map_err(|e| AppError::persistence_error(format!("提交批次分配事务失败: {}", e)))?;
 
         Ok(AllocationResult {
             batch_info,
@@ -244,10 +254,14 @@ impl BatchAllocationService {
             }
         }
 
+        // 批次、测试实例与分配记录在同一事务内写入，出错时事务随 txn 释放自动回滚
+        let txn = self.db.begin().await
+            .map_err(|e| AppError::persistence_error(format!("开启批次分配事务失败: {}", e)))?;
+
         // 5. 保存批次信息到数据库
         info!("🔥 [BATCH_ALLOCATION_FULL] 步骤2: 保存批次信息到数据库");
         let batch_entity: crate::models::entities::test_batch_info::ActiveModel = (&batch_info).into();
-        let saved_batch = batch_entity.insert(&*self.db).await
+        let saved_batch = batch_entity.insert(&txn).await
             .map_err(|e| AppError::persistence_error(format!("保存批次信息失败: {}", e)))?;
 
         let final_batch_info: TestBatchInfo = (&saved_batch).into();
@@ -256,6 +270,7 @@ impl BatchAllocationService {
 
         // 6. 创建测试实例
         let test_instances = self.create_test_instances(
+            &txn,
             &final_batch_info,
             &grouped_definitions,
         ).await?;
@@ -271,7 +286,9 @@ impl BatchAllocationService {
         );
 
         // 保存分配记录到数据库
-        self.save_allocation_record(&final_batch_info.batch_id, &strategy, &allocation_summary, final_batch_info.operator_name.as_deref()).await?;
+        self.save_allocation_record(&txn, &final_batch_info.batch_id, &strategy, &allocation_summary, final_batch_info.operator_name.as_deref()).await?;
+        txn.commit().await
+            .map_err(|e| AppError::persistence_error(format!("提交批次分配事务失败: {}", e)))?;
 
         Ok(AllocationResult {
             batch_info: final_batch_info,
@@ -375,8 +392,9 @@ impl BatchAllocationService {
     }
 
     /// 创建批次信息
-    async fn create_batch_info(
+    async fn create_batch_info<C: ConnectionTrait>(
         &self,
+        conn: &C,
         batch_name: String,
         product_model: Option<String>,
         operator_name: Option<String>,
@@ -401,15 +419,16 @@ impl BatchAllocationService {
 
         // 保存到数据库
         let active_model: test_batch_info::ActiveModel = (&batch_info).into();
-        let saved_model = active_model.insert(&*self.db).await
+        let saved_model = active_model.insert(conn).await
             .map_err(|e| AppError::persistence_error(format!("保存批次信息失败: {}", e)))?;
 
         Ok((&saved_model).into())
     }
 
-    /// 创建测试实例
-    async fn create_test_instances(
+    /// 创建测试实例，全部初始化后批量写入
+    async fn create_test_instances<C: ConnectionTrait>(
         &self,
+        conn: &C,
         batch_info: &TestBatchInfo,
         grouped_definitions: &[Vec<ChannelPointDefinition>],
     ) -> Result<Vec<ChannelTestInstance>, AppError> {
@@ -428,16 +447,19 @@ impl BatchAllocationService {
                 
                 test_instance.test_batch_name = batch_info.batch_name.clone();
 
-                // 保存到数据库
-                let active_model: channel_test_instance::ActiveModel = (&test_instance).into();
-                let _saved_model = active_model.insert(&*self.db).await
-                    .map_err(|e| AppError::persistence_error(format!("保存测试实例失败: {}", e)))?;
-
                 // 使用原始实例而不是从数据库读取的版本，以保留完整的跳过状态
                 test_instances.push(test_instance);
             }
         }
 
+        // 保存到数据库
+        let operations: Vec<_> = test_instances.iter()
+            .cloned()
+            .map(TransactionOperation::SaveTestInstance)
+            .collect();
+        transaction_writer::apply(conn, &operations).await
+            .map_err(|e| AppError::persistence_error(format!("保存测试实例失败: {}", e)))?;
+
         info!("创建了{}个测试实例", test_instances.len());
         Ok(test_instances)
     }
@@ -455,7 +477,8 @@ impl BatchAllocationService {
     }
 
     /// 保存批次分配记录
-    async fn save_allocation_record(&self,
+    async fn save_allocation_record<C: ConnectionTrait>(&self,
+        conn: &C,
         batch_id: &str,
         strategy: &AllocationStrategy,
         summary: &AllocationSummary,
@@ -469,7 +492,7 @@ impl BatchAllocationService {
         let sql = r#"INSERT INTO allocation_records (id, batch_id, strategy, summary_json, operator_name, created_time)
                      VALUES (?, ?, ?, ?, ?, ?)"#;
 
-        self.db.execute(Statement::from_sql_and_values(
+        conn.execute(Statement::from_sql_and_values(
             sea_orm::DatabaseBackend::Sqlite,
             sql,
             vec![
@@ -33,13 +33,19 @@
 //! - **错误处理**: 完善的Result链式错误处理
 //! - **内存管理**: 大文件的流式处理，避免内存溢出
 use std::sync::Arc;
-use sea_orm::{DatabaseConnection, EntityTrait, Set, ActiveModelTrait, QueryFilter, ColumnTrait, PaginatorTrait};
+use std::collections::HashSet;
+use sea_orm::{DatabaseConnection, ConnectionTrait, TransactionTrait, EntityTrait, Set, ActiveModelTrait, QueryFilter, ColumnTrait, PaginatorTrait};
 use crate::models::entities::channel_point_definition::{Entity as ChannelPointDefinitionEntity, ActiveModel as ChannelPointDefinitionActiveModel};
 use crate::models::structs::ChannelPointDefinition;
+use crate::domain::services::persistence_service::TransactionOperation;
 use crate::infrastructure::excel::ExcelImporter;
+use crate::infrastructure::persistence::transaction_writer;
 use crate::error::AppError;
 use log::{info, warn, error};
 
+/// 按位号查询已存在记录时每条语句的位号个数
+const TAG_QUERY_CHUNK: usize = 500;
+
 /// 数据导入结果
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ImportResult {
@@ -110,20 +116,7 @@ impl DataImportService {
     ) -> Result<ImportResult, AppError> {
         let mut result = ImportResult::new();
 
-        // 1. 如果需要替换现有数据，先清空数据库
-        if replace_existing {
-            match self.clear_all_data().await {
-                Ok(_deleted_count) => {
-                    // 清空成功，不记录日志
-                }
-                Err(e) => {
-                    error!("清空现有数据失败: {:?}", e);
-                    return Err(e);
-                }
-            }
-        }
-
-        // 2. 解析Excel文件
+        // 1. 解析Excel文件
         let definitions = match ExcelImporter::parse_excel_file(file_path).await {
             Ok(defs) => defs,
             Err(e) => {
@@ -134,21 +127,53 @@ impl DataImportService {
 
         result.total_rows = definitions.len();
 
+        // 清空、验证与导入在同一事务内完成，导入失败时原有数据保持不变
+        let txn = self.db.begin()
+            .await
+            .map_err(|e| AppError::persistence_error(format!("开启导入事务失败: {}", e)))?;
+
+        match self.import_in_transaction(&txn, definitions, replace_existing, &mut result).await {
+            Ok(()) => {
+                txn.commit()
+                    .await
+                    .map_err(|e| AppError::persistence_error(format!("提交导入事务失败: {}", e)))?;
+                Ok(result)
+            }
+            Err(e) => {
+                if let Err(rollback_err) = txn.rollback().await {
+                    warn!("回滚导入事务失败: {}", rollback_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    async fn import_in_transaction<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        definitions: Vec<ChannelPointDefinition>,
+        replace_existing: bool,
+        result: &mut ImportResult,
+    ) -> Result<(), AppError> {
+        // 2. 如果需要替换现有数据，先清空数据库
+        if replace_existing {
+            if let Err(e) = Self::delete_all_definitions(conn).await {
+                error!("清空现有数据失败: {:?}", e);
+                return Err(e);
+            }
+        }
 
         // 3. 验证数据
-        let validated_definitions = self.validate_definitions(definitions, &mut result).await?;
+        let validated_definitions = self.validate_definitions(conn, definitions, result).await?;
 
         // 4. 导入到数据库
-        self.import_to_database(validated_definitions, false, &mut result).await?; // 由于已经清空，这里不需要再检查重复
-
-
-
-        Ok(result)
+        self.import_to_database(conn, validated_definitions, false, result).await // 由于已经清空，这里不需要再检查重复
     }
 
     /// 验证通道定义数据
-    async fn validate_definitions(
+    async fn validate_definitions<C: ConnectionTrait>(
         &self,
+        conn: &C,
         definitions: Vec<ChannelPointDefinition>,
         result: &mut ImportResult,
     ) -> Result<Vec<ChannelPointDefinition>, AppError> {
@@ -184,7 +209,7 @@ impl DataImportService {
             // 检查数据库中是否已存在
             let existing = ChannelPointDefinitionEntity::find()
                 .filter(crate::models::entities::channel_point_definition::Column::Tag.eq(&definition.tag))
-                .one(&*self.db)
+                .one(conn)
                 .await
                 .map_err(|e| AppError::persistence_error(format!("查询数据库失败: {}", e)))?;
 
@@ -201,14 +226,33 @@ impl DataImportService {
     }
 
     /// 导入数据到数据库
-    async fn import_to_database(
+    ///
+    /// 位号已存在的记录逐条处理，其余新记录合并为批量插入
+    async fn import_to_database<C: ConnectionTrait>(
         &self,
+        conn: &C,
         definitions: Vec<ChannelPointDefinition>,
         replace_existing: bool,
         result: &mut ImportResult,
     ) -> Result<(), AppError> {
+        let mut existing_tags = HashSet::new();
+        let tags: Vec<String> = definitions.iter().map(|definition| definition.tag.clone()).collect();
+        for chunk in tags.chunks(TAG_QUERY_CHUNK) {
+            let models = ChannelPointDefinitionEntity::find()
+                .filter(crate::models::entities::channel_point_definition::Column::Tag.is_in(chunk.iter().cloned()))
+                .all(conn)
+                .await
+                .map_err(|e| AppError::persistence_error(format!("查询数据库失败: {}", e)))?;
+            existing_tags.extend(models.into_iter().map(|model| model.tag));
+        }
+
+        let mut new_definitions = Vec::new();
         for definition in definitions {
-            match self.import_single_definition(&definition, replace_existing).await {
+            if !existing_tags.contains(&definition.tag) {
+                new_definitions.push(definition);
+                continue;
+            }
+            match self.import_single_definition(conn, &definition, replace_existing).await {
                 Ok(_) => {
                     result.add_success(definition);
                 }
@@ -219,19 +263,32 @@ impl DataImportService {
             }
         }
 
+        // 批量插入失败时返回错误，由调用方回滚整个导入
+        let operations: Vec<_> = new_definitions.iter()
+            .cloned()
+            .map(TransactionOperation::SaveChannelDefinition)
+            .collect();
+        transaction_writer::validate(&operations)?;
+        transaction_writer::apply(conn, &operations).await?;
+        info!("批量插入新通道定义{}条", new_definitions.len());
+        for definition in new_definitions {
+            result.add_success(definition);
+        }
+
         Ok(())
     }
 
     /// 导入单个通道定义
-    async fn import_single_definition(
+    async fn import_single_definition<C: ConnectionTrait>(
         &self,
+        conn: &C,
         definition: &ChannelPointDefinition,
         replace_existing: bool,
     ) -> Result<(), AppError> {
         // 检查是否已存在
         let existing = ChannelPointDefinitionEntity::find()
             .filter(crate::models::entities::channel_point_definition::Column::Tag.eq(&definition.tag))
-            .one(&*self.db)
+            .one(conn)
             .await
             .map_err(|e| AppError::persistence_error(format!("查询数据库失败: {}", e)))?;
 
@@ -260,7 +317,7 @@ impl DataImportService {
                     active_model.read_write_property = Set(Some(prop.clone()));
                 }
 
-                active_model.update(&*self.db).await
+                active_model.update(conn).await
                     .map_err(|e| AppError::persistence_error(format!("更新数据库失败: {}", e)))?;
 
                 info!("更新通道定义: {}", definition.tag);
@@ -271,7 +328,7 @@ impl DataImportService {
             // 插入新记录
             let active_model: ChannelPointDefinitionActiveModel = definition.into();
 
-            active_model.insert(&*self.db).await
+            active_model.insert(conn).await
                 .map_err(|e| AppError::persistence_error(format!("插入数据库失败: {}", e)))?;
 
             info!("插入新通道定义: {}", definition.tag);
@@ -292,8 +349,12 @@ impl DataImportService {
 
     /// 清空所有通道定义数据
     pub async fn clear_all_data(&self) -> Result<u64, AppError> {
+        Self::delete_all_definitions(&*self.db).await
+    }
+
+    async fn delete_all_definitions<C: ConnectionTrait>(conn: &C) -> Result<u64, AppError> {
         let result = ChannelPointDefinitionEntity::delete_many()
-            .exec(&*self.db)
+            .exec(conn)
             .await
             .map_err(|e| AppError::persistence_error(format!("删除数据失败: {}", e)))?;
 
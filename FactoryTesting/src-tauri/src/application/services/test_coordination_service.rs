@@ -42,7 +42,7 @@ use crate::infrastructure::{
 use crate::utils::error::{AppError, AppResult};
 use async_trait::async_trait;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::{mpsc, Mutex, Semaphore};
 use serde::{Serialize, Deserialize};
 use crate::{log_test_failure, log_user_operation, log_communication_failure};
@@ -266,6 +266,9 @@ pub trait ITestCoordinationService: Send + Sync {
     /// 停止指定批次的测试
     async fn stop_batch_testing(&self, batch_id: &str) -> AppResult<()>;
 
+    /// 获取正在运行的批次ID
+    async fn running_batch_ids(&self) -> Vec<String>;
+
     /// 获取批次测试进度
     async fn get_batch_progress(&self, batch_id: &str) -> AppResult<Vec<TestProgressUpdate>>;
 
@@ -288,6 +291,97 @@ pub trait ITestCoordinationService: Send + Sync {
     async fn get_manual_test_status(&self, instance_id: &str) -> AppResult<Option<crate::models::structs::ManualTestStatus>>;
 }
 
+/// 以“是否有批次正在测试”作为后台维护任务的繁忙判断，供过期数据清理在测试期间推迟使用
+pub fn batches_running_probe(
+    coordination: Arc<dyn ITestCoordinationService>,
+) -> crate::infrastructure::persistence::data_retention::BusyProbe {
+    Arc::new(move || {
+        let coordination = coordination.clone();
+        Box::pin(async move { !coordination.running_batch_ids().await.is_empty() })
+    })
+}
+
+/// 掉线自动暂停的记录：被自动暂停的批次，以及暂停期间断开、尚未恢复的连接
+///
+/// 只等待触发暂停的连接恢复，与本次掉线无关、原本就断开的连接不阻止自动继续
+#[derive(Debug, Default)]
+struct AutoPauseTracker {
+    paused_batches: HashSet<String>,
+    down_connections: HashSet<String>,
+}
+
+impl AutoPauseTracker {
+    /// 连接断开；`paused` 为本次因此暂停的批次。没有被自动暂停的批次时不记录
+    fn connection_lost(&mut self, connection_id: &str, paused: impl IntoIterator<Item = String>) {
+        self.paused_batches.extend(paused);
+        if !self.paused_batches.is_empty() {
+            self.down_connections.insert(connection_id.to_string());
+        }
+    }
+
+    /// 连接恢复；触发暂停的连接全部恢复后返回需要继续的批次
+    fn connection_restored(&mut self, connection_id: &str) -> Vec<String> {
+        self.down_connections.remove(connection_id);
+        if self.down_connections.is_empty() {
+            self.paused_batches.drain().collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// PLC掉线时自动暂停测试、恢复后自动继续
+///
+/// 订阅连接管理器的连接事件：任一连接断开时暂停所有运行中的批次并记下断开的连接；
+/// 这些连接全部恢复后只继续被自动暂停的批次，用户手动暂停的批次不受影响。
+pub fn spawn_connection_auto_pause(
+    coordination: Arc<dyn ITestCoordinationService>,
+    manager: Arc<crate::domain::impls::plc_connection_manager::PlcConnectionManager>,
+) -> tokio::task::JoinHandle<()> {
+    use crate::domain::impls::connection_quality::ConnectionEventKind;
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut events = manager.subscribe_connection_events();
+    tokio::spawn(async move {
+        let mut tracker = AutoPauseTracker::default();
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("[TestCoordination] 连接事件积压，跳过 {} 条", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            match event.kind {
+                ConnectionEventKind::Disconnected => {
+                    let mut paused = Vec::new();
+                    for batch_id in coordination.running_batch_ids().await {
+                        match coordination.pause_batch_testing(&batch_id).await {
+                            Ok(()) => {
+                                warn!("[TestCoordination] PLC连接 {} 断开，已自动暂停批次 {}", event.connection_id, batch_id);
+                                paused.push(batch_id);
+                            }
+                            Err(e) => warn!("[TestCoordination] 自动暂停批次 {} 失败: {}", batch_id, e),
+                        }
+                    }
+                    tracker.connection_lost(&event.connection_id, paused);
+                }
+                ConnectionEventKind::Connected => {
+                    for batch_id in tracker.connection_restored(&event.connection_id) {
+                        match coordination.resume_batch_testing(&batch_id).await {
+                            Ok(()) => info!("[TestCoordination] PLC连接已恢复，自动继续批次 {}", batch_id),
+                            Err(e) => warn!("[TestCoordination] 自动继续批次 {} 失败: {}", batch_id, e),
+                        }
+                    }
+                }
+                ConnectionEventKind::ReconnectFailed => {}
+            }
+        }
+    })
+}
+
 /// 测试协调服务实现
 ///
 /// 负责协调整个测试流程，包括批次管理、任务调度、状态监控等
@@ -346,6 +440,34 @@ impl TestCoordinationService {
         }
     }
 
+    /// 将批次保存的模拟量测试配置方案与通信点测试配置下发到测试执行引擎
+    /// 每次启动测试前从数据库读取，确保使用最新保存的配置；未配置模拟量方案时执行引擎使用系统默认方案
+    async fn sync_batch_test_settings(&self, batch_id: &str) -> AppResult<()> {
+        let Some(batch) = self.persistence_service.load_batch_info(batch_id).await? else {
+            return Ok(());
+        };
+
+        if let Some(profile) = batch.analog_test_profile {
+            self.test_execution_engine
+                .set_batch_analog_test_profile(batch_id, profile)
+                .await?;
+        }
+
+        // 程序重启后源连接尚未建立，先连接；连接失败时通信点测试按通信故障记录，不阻止批次启动
+        if let Some(config) = &batch.communication_test_config {
+            use crate::infrastructure::plc_communication::IPlcCommunicationService;
+            let plc_service = crate::infrastructure::plc_communication::global_plc_service();
+            if plc_service.default_handle_by_id(&config.source_connection.id).await.is_none() {
+                if let Err(e) = plc_service.connect(&config.source_connection).await {
+                    warn!("⚠️ [TEST_COORDINATION] 通信测试源连接失败: {} - {}", config.source_connection.id, e);
+                }
+            }
+        }
+        self.test_execution_engine
+            .set_batch_communication_test_config(batch_id, batch.communication_test_config)
+            .await
+    }
+
     /// 启动结果收集任务
     async fn start_result_collection(&self, batch_id: String) -> AppResult<()> {
         let active_batches = self.active_batches.clone();
@@ -373,6 +495,11 @@ impl TestCoordinationService {
                         // 🔧 移除 [TestCoordination] 日志
                     }
 
+                    // 将自动重试的失败结果仅做记录，等待最终结果再更新状态与统计
+                    if result.retry_pending {
+                        continue;
+                    }
+
                     // ===== 关键修复：更新 ChannelStateManager 中的测试实例状态 =====
                     if let Err(e) = channel_state_manager.update_test_result(result.clone()).await {
                         log_test_failure!("更新通道状态失败: {}", e);
@@ -472,6 +599,9 @@ impl TestCoordinationService {
                             if tested_instances + skipped_instances >= total_instances {
                                 batch_info.status = BatchExecutionStatus::Completed;
                                 batch_info.completed_at = Some(Utc::now());
+                                if let Some(recorder) = crate::infrastructure::modbus_capture::frame_recorder() {
+                                    recorder.finish_batch(&batch_id);
+                                }
                                 // 🔧 移除 [TestCoordination] 日志
                             }
                         }
@@ -740,6 +870,12 @@ impl ITestCoordinationService for TestCoordinationService {
             )
         };
 
+        // 下发批次的测试配置和通道定义
+        self.sync_batch_test_settings(batch_id).await?;
+        self.test_execution_engine
+            .set_batch_channel_definitions(batch_id, definitions.clone())
+            .await?;
+
         // 为每个测试实例提交执行任务
         for instance in instances {
             // 查找对应的定义
@@ -834,6 +970,15 @@ impl ITestCoordinationService for TestCoordinationService {
         self.start_batch_testing(batch_id).await
     }
 
+    /// 获取正在运行的批次ID
+    async fn running_batch_ids(&self) -> Vec<String> {
+        let batches = self.active_batches.lock().await;
+        batches.iter()
+            .filter(|(_, info)| info.status == BatchExecutionStatus::Running)
+            .map(|(batch_id, _)| batch_id.clone())
+            .collect()
+    }
+
     /// 停止指定批次的测试
     async fn stop_batch_testing(&self, batch_id: &str) -> AppResult<()> {
         info!("[TestCoordination] 停止批次测试: {}", batch_id);
@@ -856,6 +1001,9 @@ impl ITestCoordinationService for TestCoordinationService {
 
         batch_info.status = BatchExecutionStatus::Stopped;
         batch_info.completed_at = Some(Utc::now());
+        if let Some(recorder) = crate::infrastructure::modbus_capture::frame_recorder() {
+            recorder.finish_batch(batch_id);
+        }
         info!("[TestCoordination] 批次 {} 已停止", batch_id);
         Ok(())
     }
@@ -1000,13 +1148,19 @@ impl ITestCoordinationService for TestCoordinationService {
         }
 
         // 4. 获取结果发送器
-        let result_sender = {
+        let (result_sender, batch_definitions) = {
             let batches = self.active_batches.lock().await;
             let batch_info = batches.get(batch_id)
                 .ok_or_else(|| AppError::not_found_error("批次", batch_id))?;
-            batch_info.result_sender.clone()
+            (batch_info.result_sender.clone(), batch_info.channel_definitions.clone())
         };
 
+        // 下发批次的测试配置和通道定义
+        self.sync_batch_test_settings(batch_id).await?;
+        self.test_execution_engine
+            .set_batch_channel_definitions(batch_id, batch_definitions)
+            .await?;
+
         // 5. 发布测试开始事件
         if let Err(e) = self.event_publisher.publish_test_status_changed(
             instance_id,
@@ -1258,4 +1412,29 @@ impl ITestCoordinationService for TestCoordinationService {
         }
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AutoPauseTracker;
+
+    #[test]
+    fn resumes_once_the_connections_that_paused_the_batches_are_back() {
+        let mut tracker = AutoPauseTracker::default();
+
+        // 没有运行中批次时的掉线不记录，恢复时也无需继续
+        tracker.connection_lost("spare_plc", Vec::new());
+        assert!(tracker.connection_restored("spare_plc").is_empty());
+
+        tracker.connection_lost("rig_plc", vec!["batch1".to_string()]);
+        // 暂停期间另一连接也断开，需等两者都恢复
+        tracker.connection_lost("target_plc", Vec::new());
+        assert!(tracker.connection_restored("rig_plc").is_empty());
+        // 与暂停无关的连接恢复不影响判断
+        assert!(tracker.connection_restored("spare_plc").is_empty());
+        assert_eq!(tracker.connection_restored("target_plc"), vec!["batch1".to_string()]);
+
+        // 已继续的批次不会被重复继续
+        assert!(tracker.connection_restored("target_plc").is_empty());
+    }
+}
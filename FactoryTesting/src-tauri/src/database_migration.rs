@@ -57,6 +57,11 @@ use sea_orm::ActiveValue::Set;
 use crate::error::AppError;
 use crate::{log_file_parsing_failure, log_user_operation};
 
+/// 当前数据库结构版本，写入 `PRAGMA user_version`
+///
+/// 表结构变化时需要递增，备份恢复据此拒绝来自更新版本软件的备份
+pub const SCHEMA_VERSION: i32 = 1;
+
 /// 数据库迁移管理器
 ///
 /// 业务说明：
@@ -250,6 +255,14 @@ impl DatabaseMigration {
         // 通过测试实例找回关联关系
         Self::recover_missing_batch_associations(db).await?;
 
+        // 记录结构版本，备份文件会带上该版本号
+        db.execute(Statement::from_string(
+            sea_orm::DatabaseBackend::Sqlite,
+            format!("PRAGMA user_version = {}", SCHEMA_VERSION),
+        ))
+        .await
+        .map_err(|e| AppError::persistence_error(format!("写入数据库结构版本失败: {}", e)))?;
+
         // 数据库迁移完成
         Ok(())
     }
@@ -805,6 +818,7 @@ impl DatabaseMigration {
                 retries_count INTEGER DEFAULT 0,
                 sub_test_results_json TEXT,
                 hardpoint_readings_json TEXT,
+                analog_accuracy_json TEXT,
                 digital_test_steps_json TEXT,
                 transient_data_json TEXT,
                 integration_error_notes TEXT,
@@ -889,6 +903,7 @@ impl DatabaseMigration {
             ("updated_time", "TEXT"),
             ("sub_test_results_json", "TEXT"),
             ("hardpoint_readings_json", "TEXT"),
+            ("analog_accuracy_json", "TEXT"),                 // 模拟量精度指标（最大误差/线性拟合/回差）
             ("digital_test_steps_json", "TEXT"),
             ("transient_data_json", "TEXT"),
             // 错误备注字段 - 用于人工记录测试失败原因
@@ -1005,6 +1020,7 @@ impl DatabaseMigration {
                 progress_percentage REAL DEFAULT 0.0,
                 current_testing_channel TEXT,
                 test_configuration TEXT,
+                communication_test_config TEXT,
                 import_source TEXT,
                 custom_data_json TEXT
             )
@@ -1033,7 +1049,7 @@ impl DatabaseMigration {
     /// - 状态跟踪：overall_status、status_summary、error_message
     /// - 统计数据：total_points、tested_points、passed_points、failed_points等
     /// - 进度管理：progress_percentage、current_testing_channel
-    /// - 配置信息：test_configuration、import_source
+    /// - 配置信息：test_configuration、communication_test_config、import_source
     /// - 扩展数据：custom_data_json
     /// 
     /// 特殊处理：
@@ -1074,6 +1090,7 @@ impl DatabaseMigration {
             ("current_testing_channel", "TEXT"),           // 当前正在测试的通道
             // 配置和来源信息
             ("test_configuration", "TEXT"),                // 测试配置JSON
+            ("communication_test_config", "TEXT"),         // 通信点测试配置JSON
             ("import_source", "TEXT"),                     // 数据导入来源(Excel/手动等)
             ("custom_data_json", "TEXT"),                  // 自定义扩展数据
             // 时间戳字段
@@ -1244,7 +1261,10 @@ impl DatabaseMigration {
                 test_result_50_percent REAL,
                 test_result_75_percent REAL,
                 test_result_100_percent REAL,
-                details_json TEXT
+                details_json TEXT,
+                attempt INTEGER NOT NULL DEFAULT 1,
+                retry_pending BOOLEAN NOT NULL DEFAULT 0,
+                failure_kind TEXT
             )
         "#;
 
@@ -1270,6 +1290,9 @@ impl DatabaseMigration {
     /// - test_result_50_percent: 50%量程点的测试结果
     /// - test_result_75_percent: 75%量程点的测试结果
     /// - test_result_100_percent: 100%量程点的测试结果
+    /// - attempt: 第几次执行（自动重试时大于1）
+    /// - retry_pending: 本次失败后是否进行了自动重试
+    /// - failure_kind: 失败原因分类（配置/通信/信号）
     /// 
     /// 应用场景：
     /// - 模拟量通道需要在不同量程点进行测试
@@ -1289,6 +1312,9 @@ impl DatabaseMigration {
             ("test_result_50_percent", "REAL"),
             ("test_result_75_percent", "REAL"),
             ("test_result_100_percent", "REAL"),
+            ("attempt", "INTEGER NOT NULL DEFAULT 1"),
+            ("retry_pending", "BOOLEAN NOT NULL DEFAULT 0"),
+            ("failure_kind", "TEXT"),
         ];
 
         for (column_name, column_def) in new_columns {
@@ -1636,6 +1662,11 @@ impl DatabaseMigration {
     /// - zero_based_address: 地址基数配置
     ///   - 0: 地址从0开始（默认值）
     ///   - 1: 地址从1开始（某些PLC使用）
+    /// - serial_settings: Modbus RTU串口参数（JSON），TCP连接为空
+    /// - s7_settings: 西门子S7机架/插槽参数（JSON），非S7连接为空
+    /// - opcua_settings: OPC UA端点路径/认证参数（JSON），非OPC UA连接为空
+    /// - modbus_data_settings: Modbus 64位字序/字符串编码参数（JSON），可为空
+    /// - modbus_scheduler_settings: Modbus请求调度参数（JSON），可为空
     /// 
     /// 特殊处理：
     /// - 如果表不存在，跳过处理（由SeaORM迁移器负责创建）
@@ -1661,6 +1692,11 @@ impl DatabaseMigration {
         let new_columns = vec![
             ("byte_order", "TEXT DEFAULT 'CDAB'"),         // Modbus字节序
             ("zero_based_address", "INTEGER DEFAULT 0"),   // 地址基数(0或1)
+            ("serial_settings", "TEXT"),                   // 串口参数JSON(Modbus RTU)
+            ("s7_settings", "TEXT"),                       // 机架/插槽参数JSON(西门子S7)
+            ("opcua_settings", "TEXT"),                    // 端点/认证参数JSON(OPC UA)
+            ("modbus_data_settings", "TEXT"),              // 字序/字符串参数JSON(Modbus)
+            ("modbus_scheduler_settings", "TEXT"),         // 请求调度参数JSON(Modbus)
         ];
 
         // 遍历并添加缺失的列
@@ -30,15 +30,16 @@
 
 use crate::models::{
     ChannelTestInstance, ChannelPointDefinition, RawTestOutcome, 
-    OverallTestStatus, SubTestStatus, SubTestItem, ModuleType, SubTestExecutionResult
+    OverallTestStatus, SubTestStatus, SubTestItem, ModuleType, SubTestExecutionResult, FailureKind
 };
 use crate::infrastructure::IPersistenceService;
+use crate::domain::services::persistence_service::TransactionOperation;
 use crate::utils::error::{AppError, AppResult};
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::collections::HashMap;
 use chrono::Utc;
-use log::{info, error, warn, trace};
+use log::{info, error, warn, debug, trace};
 
 /// 通道状态管理器接口
 #[async_trait]
@@ -63,6 +64,10 @@ pub trait IChannelStateManager: Send + Sync {
     /// 准备接线确认
     async fn prepare_for_wiring_confirmation(&self, instance: &mut ChannelTestInstance) -> AppResult<()>;
 
+    /// 根据接线检查报告，将接线有问题的实例置为待接线确认并记录调整建议
+    /// 返回被标记的实例ID
+    async fn apply_wiring_check(&self, report: &crate::models::WiringCheckReport) -> AppResult<Vec<String>>;
+
     /// 开始硬点测试
     async fn begin_hard_point_test(&self, instance: &mut ChannelTestInstance) -> AppResult<()>;
 
@@ -438,12 +443,26 @@ impl IChannelStateManager for ChannelStateManager {
             sub_result.timestamp = outcome.end_time;
             sub_result.actual_value = outcome.raw_value_read.clone();
             sub_result.expected_value = outcome.eng_value_calculated.clone();
-            sub_result.details = outcome.message.clone();
+            sub_result.details = match outcome.retry_note() {
+                Some(note) => Some(format!("{} ({})", outcome.message.clone().unwrap_or_default(), note)),
+                None => outcome.message.clone(),
+            };
+            // 失败原因分类：由执行器或引擎按错误类型给出，未分类的失败（如手动判定不合格）按信号异常处理
+            sub_result.failure_kind = if outcome.success {
+                None
+            } else {
+                Some(outcome.failure_kind.unwrap_or(FailureKind::Signal))
+            };
             trace!("🔍 [APPLY_OUTCOME] 子测试状态已更新为: {:?}", sub_result.status);
         } else {
             error!("❌ [APPLY_OUTCOME] 这不应该发生：仍然找不到子测试项: {:?}", outcome.sub_test_item);
         }
 
+        // 记录自动重试次数
+        if outcome.attempt > 1 {
+            instance.retries_count = instance.retries_count.max(outcome.attempt - 1);
+        }
+
         // ===== AO 百分比测试结果统一处理 =====
         {
             use crate::models::enums::SubTestItem::*;
@@ -480,36 +499,41 @@ impl IChannelStateManager for ChannelStateManager {
                     }
                 }
 
-                // 2. 如仍未写入且 readings 足够，尝试从 readings 推断
+                // 2. 如仍未写入，尝试从 readings 中按设定百分比匹配推断
+                //    （测试点可按批次配置，不能假设 readings 固定为 0/25/50/75/100 五点）
                 if !any_written {
                     if let Some(readings) = &outcome.readings {
-                        if readings.len() >= 5 {
-                            // 🔧 修复：同时更新transient_data和结构体字段
-                            let reading_values = [
-                                readings[0].actual_reading_eng.map(|v| v as f64),
-                                readings[1].actual_reading_eng.map(|v| v as f64),
-                                readings[2].actual_reading_eng.map(|v| v as f64),
-                                readings[3].actual_reading_eng.map(|v| v as f64),
-                                readings[4].actual_reading_eng.map(|v| v as f64),
-                            ];
-                            
-                            let keys = ["test_result_0_percent", "test_result_25_percent", 
-                                       "test_result_50_percent", "test_result_75_percent", 
-                                       "test_result_100_percent"];
-                            
-                            for (i, key) in keys.iter().enumerate() {
-                                if let Some(value) = reading_values[i] {
-                                    instance.transient_data.insert(key.to_string(), serde_json::json!(value));
-                                    
-                                    // 🔧 新增：同时更新结构体字段
-                                    match *key {
-                                        "test_result_0_percent" => instance.test_result_0_percent = Some(value),
-                                        "test_result_25_percent" => instance.test_result_25_percent = Some(value),
-                                        "test_result_50_percent" => instance.test_result_50_percent = Some(value),
-                                        "test_result_75_percent" => instance.test_result_75_percent = Some(value),
-                                        "test_result_100_percent" => instance.test_result_100_percent = Some(value),
-                                        _ => {}
-                                    }
+                        // 🔧 修复：同时更新transient_data和结构体字段
+                        let reading_at = |percent: f32| {
+                            readings
+                                .iter()
+                                .find(|r| (r.set_percentage - percent).abs() < 1e-4)
+                                .and_then(|r| r.actual_reading_eng.map(|v| v as f64))
+                        };
+                        let reading_values = [
+                            reading_at(0.0),
+                            reading_at(0.25),
+                            reading_at(0.5),
+                            reading_at(0.75),
+                            reading_at(1.0),
+                        ];
+                        
+                        let keys = ["test_result_0_percent", "test_result_25_percent", 
+                                   "test_result_50_percent", "test_result_75_percent", 
+                                   "test_result_100_percent"];
+                        
+                        for (i, key) in keys.iter().enumerate() {
+                            if let Some(value) = reading_values[i] {
+                                instance.transient_data.insert(key.to_string(), serde_json::json!(value));
+                                
+                                // 🔧 新增：同时更新结构体字段
+                                match *key {
+                                    "test_result_0_percent" => instance.test_result_0_percent = Some(value),
+                                    "test_result_25_percent" => instance.test_result_25_percent = Some(value),
+                                    "test_result_50_percent" => instance.test_result_50_percent = Some(value),
+                                    "test_result_75_percent" => instance.test_result_75_percent = Some(value),
+                                    "test_result_100_percent" => instance.test_result_100_percent = Some(value),
+                                    _ => {}
                                 }
                             }
                         }
@@ -526,6 +550,11 @@ impl IChannelStateManager for ChannelStateManager {
                 trace!("🔍 [APPLY_OUTCOME] 已存储硬点读数数据");
             }
 
+            // 存储模拟量精度指标（最大误差、线性拟合、回差）
+            if let Some(accuracy) = outcome.details.get("accuracy") {
+                instance.analog_accuracy = serde_json::from_value(accuracy.clone()).ok();
+            }
+
             // 存储数字量测试步骤到实例中（DI/DO点位）- 累积添加而不是覆盖
             if let Some(new_digital_steps) = &outcome.digital_steps {
                 let mut existing_steps = instance.digital_test_steps.clone().unwrap_or_default();
@@ -572,6 +601,42 @@ impl IChannelStateManager for ChannelStateManager {
         Ok(())
     }
 
+    /// 根据接线检查报告标记待接线确认的实例
+    async fn apply_wiring_check(&self, report: &crate::models::WiringCheckReport) -> AppResult<Vec<String>> {
+        let mut marked = Vec::new();
+
+        for channel in report.channels.iter().filter(|c| c.needs_attention()) {
+            let cached = {
+                let cache = self.test_instances_cache.read().unwrap();
+                cache.get(&channel.instance_id).cloned()
+            };
+            let mut instance = match cached {
+                Some(instance) => instance,
+                None => match self.persistence_service.load_test_instance(&channel.instance_id).await? {
+                    Some(instance) => instance,
+                    None => {
+                        warn!("⚠️ [STATE_MANAGER] 接线检查结果对应的实例不存在: {}", channel.instance_id);
+                        continue;
+                    }
+                },
+            };
+
+            self.prepare_for_wiring_confirmation(&mut instance).await?;
+            instance.current_step_details = report.hint_for(&channel.instance_id);
+            instance.last_updated_time = Utc::now();
+
+            {
+                let mut cache = self.test_instances_cache.write().unwrap();
+                cache.insert(instance.instance_id.clone(), instance.clone());
+            }
+            self.persistence_service.save_test_instance(&instance).await?;
+            marked.push(instance.instance_id.clone());
+        }
+
+        info!("🔌 [STATE_MANAGER] 接线检查标记待确认实例 {} 个", marked.len());
+        Ok(marked)
+    }
+
     /// 开始硬点测试
     async fn begin_hard_point_test(&self, instance: &mut ChannelTestInstance) -> AppResult<()> {
         instance.overall_status = OverallTestStatus::HardPointTestInProgress;
@@ -708,15 +773,18 @@ impl IChannelStateManager for ChannelStateManager {
     }
 
     /// 更新测试结果
+    ///
+    /// RawTestOutcome 与更新后的测试实例在同一事务内写入，避免只有结果而实例状态未更新
     async fn update_test_result(&self, outcome: RawTestOutcome) -> AppResult<()> {
-        // 先持久化 RawTestOutcome 记录，便于排错
-        //info!("🔧 [STATE_MANAGER] persistence_service type: {}", std::any::type_name::<dyn crate::domain::services::persistence_service::IPersistenceService>());
-        if let Err(e) = self.persistence_service.save_test_outcomes(&[outcome.clone()]).await {
-            error!("❌ [STATE_MANAGER] save_test_outcomes 失败: {}", e);
-        } else {
-            trace!("💾 [STATE_MANAGER] RawTestOutcome 已保存到数据库");
-        }
         let instance_id = outcome.channel_instance_id.clone();
+
+        // 引擎将自动重试的失败结果只做记录，不更新子测试状态
+        if outcome.retry_pending {
+            self.save_outcome_only(&outcome).await;
+            debug!("🔁 [STATE_MANAGER] 第{}次执行失败，等待自动重试: {} -> {:?}", outcome.attempt, instance_id, outcome.sub_test_item);
+            return Ok(());
+        }
+
         // 完全移除状态管理器的冗余日志
         trace!("🔍 [STATE_MANAGER] 尝试更新测试结果: {} -> {:?}", instance_id, outcome.success);
 
@@ -764,10 +832,12 @@ impl IChannelStateManager for ChannelStateManager {
                         }
                     }
 
+                    self.save_outcome_only(&outcome).await;
                     return Err(AppError::not_found_error("测试实例", &format!("实例ID: {}", instance_id)));
                 }
                 Err(e) => {
                     error!("❌ [STATE_MANAGER] 加载测试实例失败: {} - {}", instance_id, e);
+                    self.save_outcome_only(&outcome).await;
                     return Err(e);
                 }
             }
@@ -778,17 +848,20 @@ impl IChannelStateManager for ChannelStateManager {
         // 🔧 第三步：更新测试实例状态
         if let Some(mut instance) = instance_from_cache {
             // 应用测试结果
-            self.apply_raw_outcome(&mut instance, outcome).await?;
+            self.apply_raw_outcome(&mut instance, outcome.clone()).await?;
+
+            // 🔧 第四步：结果与实例一起写入数据库，成功后再更新内存缓存
+            self.persistence_service.execute_transaction(vec![
+                TransactionOperation::SaveTestOutcome(outcome),
+                TransactionOperation::SaveTestInstance(instance.clone()),
+            ]).await?;
+            trace!("💾 [STATE_MANAGER] RawTestOutcome 与测试实例已保存到数据库");
 
-            // 🔧 第四步：同时更新内存缓存和数据库
             {
                 let mut cache = self.test_instances_cache.write().unwrap();
                 cache.insert(instance_id.to_string(), instance.clone());
             }
 
-            // 保存到数据库
-            self.persistence_service.save_test_instance(&instance).await?;
-
             // 🔧 性能优化：移除详细验证日志，只保留关键错误检查
             if let Some(ref digital_steps) = instance.digital_test_steps {
                 // 简化验证：只在出现问题时记录错误
@@ -885,118 +958,57 @@ impl IChannelStateManager for ChannelStateManager {
         &self,
         allocation_result: crate::commands::data_management::AllocationResult,
     ) -> AppResult<()> {
-        // 首先保存通道定义到数据库
-
-        // 从分配结果中获取所有通道定义
-        if let Some(ref channel_definitions) = allocation_result.channel_definitions {
-
-
-            let mut saved_count = 0;
-            let mut failed_count = 0;
-
-            for definition in channel_definitions.iter() {
-                match self.persistence_service.save_channel_definition(definition).await {
-                    Ok(_) => {
-                        saved_count += 1;
-                    }
-                    Err(e) => {
-                        failed_count += 1;
-                        error!("❌ [STATE_MANAGER] 保存通道定义到数据库失败: ID={}, Tag={} - {}",
-                            definition.id, definition.tag, e);
-                        // 不要因为单个定义失败而中断整个流程
-                    }
-                }
+        // 通道定义、批次、测试实例在同一事务内写入，任一失败整体回滚，不会留下半个批次
+        let mut operations = Vec::new();
+        match allocation_result.channel_definitions {
+            Some(ref channel_definitions) => {
+                operations.extend(channel_definitions.iter().cloned().map(TransactionOperation::SaveChannelDefinition));
             }
-
-    
-
-            if failed_count > 0 {
-                warn!("⚠️ [STATE_MANAGER] 有{}个通道定义保存失败，但继续处理", failed_count);
-            }
-        } else {
-            warn!("⚠️ [STATE_MANAGER] 分配结果中没有通道定义数据");
+            None => warn!("⚠️ [STATE_MANAGER] 分配结果中没有通道定义数据"),
         }
-
-        // 步骤2: 将通道定义存储到内存缓存中
-
-        // 从测试实例中提取所有相关的通道定义ID
-        let definition_ids: std::collections::HashSet<String> = allocation_result.allocated_instances
+        operations.extend(allocation_result.batches.iter().cloned().map(TransactionOperation::SaveBatchInfo));
+        operations.extend(allocation_result.allocated_instances.iter().cloned().map(TransactionOperation::SaveTestInstance));
+
+        let result = self.persistence_service.execute_transaction(operations).await.map_err(|e| {
+            error!("🔥 [STATE_MANAGER] 保存批次分配结果失败: {}", e);
+            e
+        })?;
+        debug!("💾 [STATE_MANAGER] 批次分配结果已保存: {}个操作，耗时{}ms",
+            result.operations_executed, result.duration_ms);
+
+        // 将实例引用的通道定义存储到内存缓存中，分配结果中没有的再从数据库加载
+        let mut definitions: HashMap<String, ChannelPointDefinition> = allocation_result.channel_definitions
+            .iter()
+            .flatten()
+            .map(|definition| (definition.id.clone(), definition.clone()))
+            .collect();
+        let missing_ids: std::collections::HashSet<String> = allocation_result.allocated_instances
             .iter()
             .map(|instance| instance.definition_id.clone())
+            .filter(|definition_id| !definitions.contains_key(definition_id))
             .collect();
-
-
-
-        // 从数据库加载这些通道定义并存储到缓存中
-        let mut loaded_definitions = Vec::new();
-        let mut loaded_count = 0;
-        let mut not_found_count = 0;
-        let mut error_count = 0;
-
-        for definition_id in &definition_ids {
-            match self.persistence_service.load_channel_definition(definition_id).await {
+        for definition_id in missing_ids {
+            match self.persistence_service.load_channel_definition(&definition_id).await {
                 Ok(Some(definition)) => {
-                    loaded_count += 1;
-                    loaded_definitions.push((definition_id.clone(), definition));
-                }
-                Ok(None) => {
-                    not_found_count += 1;
-                    // 🔧 移除 [STATE_MANAGER] 日志
-                }
-                Err(e) => {
-                    error_count += 1;
-                    // 🔧 移除 [STATE_MANAGER] 日志
+                    definitions.insert(definition_id, definition);
                 }
+                Ok(None) => warn!("⚠️ [STATE_MANAGER] 未找到通道定义: {}", definition_id),
+                Err(e) => error!("❌ [STATE_MANAGER] 加载通道定义失败: {} - {}", definition_id, e),
             }
         }
 
-
-
-        // 将加载的定义存储到缓存中（避免跨await持有锁）
+        // 避免跨await持有锁
         {
             let mut cache = self.channel_definitions_cache.write().unwrap();
-            for (definition_id, definition) in loaded_definitions {
-                cache.insert(definition_id, definition);
-            }
-
-        }
-
-        // 将批次信息保存到持久化服务
-        let mut batch_saved_count = 0;
-        let mut batch_failed_count = 0;
-
-        for batch in &allocation_result.batches {
-            if let Err(e) = self.persistence_service.save_batch_info(batch).await {
-                batch_failed_count += 1;
-                error!("🔥 [STATE_MANAGER] 保存批次信息失败: {} - {}", batch.batch_id, e);
-            } else {
-                batch_saved_count += 1;
-            }
+            cache.extend(definitions);
         }
-
-        // 🔧 将测试实例保存到持久化服务和内存缓存
-        let mut instance_saved_count = 0;
-        let mut instance_failed_count = 0;
-
-        for instance in &allocation_result.allocated_instances {
-            // 保存到数据库
-            if let Err(e) = self.persistence_service.save_test_instance(instance).await {
-                instance_failed_count += 1;
-                error!("🔥 [STATE_MANAGER] 保存测试实例到数据库失败: {} - {}", instance.instance_id, e);
-            } else {
-                instance_saved_count += 1;
-
-                // 🔧 同时保存到内存缓存
-                {
-                    let mut cache = self.test_instances_cache.write().unwrap();
-                    cache.insert(instance.instance_id.clone(), instance.clone());
-                }
-
-                //info!("✅ [STATE_MANAGER] 测试实例已保存到数据库和缓存: {}", instance.instance_id);
+        {
+            let mut cache = self.test_instances_cache.write().unwrap();
+            for instance in &allocation_result.allocated_instances {
+                cache.insert(instance.instance_id.clone(), instance.clone());
             }
         }
 
-
         Ok(())
     }
 
@@ -1066,6 +1078,13 @@ impl ChannelStateManager {
         }
     }
 
+    /// 无法更新测试实例时单独保存测试结果，保证每次执行都有记录可查
+    async fn save_outcome_only(&self, outcome: &RawTestOutcome) {
+        if let Err(e) = self.persistence_service.save_test_outcomes(std::slice::from_ref(outcome)).await {
+            error!("❌ [STATE_MANAGER] save_test_outcomes 失败: {}", e);
+        }
+    }
+
     /// 恢复所有批次数据到缓存（同步私有实现）
     async fn do_restore_all_batches(&self) -> AppResult<Vec<crate::models::TestBatchInfo>> {
         // 1. 清空旧缓存
@@ -1102,3 +1121,52 @@ impl ChannelStateManager {
         IChannelStateManager::apply_raw_outcome(self, instance, outcome).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::extra::infrastructure::{PersistenceConfig, SqliteOrmPersistenceService};
+
+    async fn manager_with_db(dir: &tempfile::TempDir) -> (ChannelStateManager, Arc<dyn IPersistenceService>) {
+        let db_path = dir.path().join("state_manager.sqlite");
+        std::fs::write(&db_path, "").unwrap();
+        let persistence: Arc<dyn IPersistenceService> = Arc::new(
+            SqliteOrmPersistenceService::new(PersistenceConfig::default(), Some(&db_path)).await.unwrap(),
+        );
+        (ChannelStateManager::new(persistence.clone()), persistence)
+    }
+
+    #[tokio::test]
+    async fn keeps_the_outcome_when_the_instance_is_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, persistence) = manager_with_db(&dir).await;
+        let instance_id = uuid::Uuid::new_v4().to_string();
+        let outcome = RawTestOutcome::failure(instance_id.clone(), SubTestItem::HardPoint, "读数偏差".to_string());
+
+        let result = manager.update_test_result(outcome).await;
+        assert!(matches!(result, Err(AppError::NotFoundError { .. })));
+
+        let saved = persistence.load_test_outcomes_by_instance(&instance_id).await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].message.as_deref(), Some("读数偏差"));
+    }
+
+    #[tokio::test]
+    async fn retry_pending_outcomes_are_recorded_without_touching_the_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let (manager, persistence) = manager_with_db(&dir).await;
+        let instance = ChannelTestInstance::new("def-1".to_string(), "batch-1".to_string());
+        persistence.save_test_instance(&instance).await.unwrap();
+
+        let mut pending = RawTestOutcome::failure(instance.instance_id.clone(), SubTestItem::HardPoint, "第1次读数偏差".to_string());
+        pending.retry_pending = true;
+        manager.update_test_result(pending).await.unwrap();
+
+        let saved = persistence.load_test_outcomes_by_instance(&instance.instance_id).await.unwrap();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].retry_pending);
+        let reloaded = persistence.load_test_instance(&instance.instance_id).await.unwrap().unwrap();
+        assert_eq!(reloaded.overall_status, instance.overall_status);
+        assert_eq!(reloaded.sub_test_results, instance.sub_test_results, "等待重试的结果不应更新子测试状态");
+    }
+}
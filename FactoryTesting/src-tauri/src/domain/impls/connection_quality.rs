@@ -0,0 +1,374 @@
+//! # PLC连接质量记录模块
+//!
+//! ## 业务作用
+//! 批次测试中途失败时，需要知道当时连接是否掉线、掉线多久。本模块为每个连接ID维护：
+//! - **事件环形缓冲**: 最近的连接/断开/重连失败事件
+//! - **时延直方图**: 每个Modbus请求的应答时延分布
+//! - **错误率窗口**: 最近一段时间内通信失败请求的占比
+//! - **重连退避**: 指数退避加随机抖动，避免多台上位机同时冲击PLC
+//!
+//! 连接/断开事件同时广播给订阅方，用于掉线时自动暂停测试、恢复后自动继续。
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use super::plc_connection_manager::{PlcConnection, PlcConnectionState};
+
+/// 每个连接保留的事件条数
+pub const EVENT_CAPACITY: usize = 200;
+
+/// 错误率统计窗口
+pub const ERROR_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// 时延直方图的桶上界（毫秒），超过最后一个上界的计入溢出桶
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [5, 10, 20, 50, 100, 200, 500, 1000];
+
+/// 连接事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionEventKind {
+    /// 连接建立或恢复
+    Connected,
+    /// 连接断开
+    Disconnected,
+    /// 重连尝试失败
+    ReconnectFailed,
+}
+
+/// 连接事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionEvent {
+    pub connection_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub kind: ConnectionEventKind,
+    pub message: Option<String>,
+}
+
+/// 重连退避策略
+///
+/// 第n次失败后等待 `initial * multiplier^(n-1)`，不超过 `max`，再叠加 ±`jitter` 比例的随机抖动
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectBackoff {
+    pub initial: Duration,
+    pub max: Duration,
+    pub multiplier: f64,
+    pub jitter: f64,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(10), // 与原固定重连间隔一致
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl ReconnectBackoff {
+    /// 不含抖动的等待时间，`attempt` 从1开始
+    pub fn base_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let secs = self.initial.as_secs_f64() * self.multiplier.powi(exponent);
+        Duration::from_secs_f64(secs.min(self.max.as_secs_f64()))
+    }
+
+    /// 叠加随机抖动后的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let unit = if self.jitter > 0.0 { rand::thread_rng().gen_range(-1.0..=1.0) } else { 0.0 };
+        self.delay_with(attempt, unit)
+    }
+
+    /// `unit` 取 [-1, 1]，-1/1 对应抖动的下限/上限
+    fn delay_with(&self, attempt: u32, unit: f64) -> Duration {
+        let factor = 1.0 + self.jitter * unit.clamp(-1.0, 1.0);
+        Duration::from_secs_f64(self.base_delay(attempt).as_secs_f64() * factor.max(0.0))
+    }
+}
+
+/// 时延直方图的一个桶
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyBucket {
+    /// 桶上界（毫秒），溢出桶为空
+    pub upper_ms: Option<u64>,
+    pub count: u64,
+}
+
+/// 单个连接的质量快照，用于命令返回与批次报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionQualitySnapshot {
+    pub connection_id: String,
+    pub connection_name: String,
+    pub state: PlcConnectionState,
+    pub events: Vec<ConnectionEvent>,
+    pub latency_histogram: Vec<LatencyBucket>,
+    pub window_seconds: u64,
+    pub window_requests: u64,
+    pub window_errors: u64,
+    /// 窗口内错误率（0~1）
+    pub error_rate: f64,
+    pub total_requests: u64,
+    pub total_errors: u64,
+    pub disconnect_count: u32,
+    /// 累计掉线时长（毫秒），包含尚未恢复的本次掉线
+    pub total_downtime_ms: i64,
+}
+
+/// 单个连接的质量记录
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionQuality {
+    events: VecDeque<ConnectionEvent>,
+    latency_counts: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    window: VecDeque<(Instant, bool)>,
+    total_requests: u64,
+    total_errors: u64,
+    disconnect_count: u32,
+    disconnected_at: Option<DateTime<Utc>>,
+    downtime_ms: i64,
+}
+
+impl ConnectionQuality {
+    pub fn record_event(&mut self, event: ConnectionEvent) {
+        match event.kind {
+            ConnectionEventKind::Disconnected => {
+                self.disconnect_count += 1;
+                self.disconnected_at.get_or_insert(event.timestamp);
+            }
+            ConnectionEventKind::Connected => {
+                if let Some(since) = self.disconnected_at.take() {
+                    self.downtime_ms += (event.timestamp - since).num_milliseconds().max(0);
+                }
+            }
+            ConnectionEventKind::ReconnectFailed => {}
+        }
+        if self.events.len() == EVENT_CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// 记录一次请求；`success` 为假表示超时或链路错误，从站异常应答不计入
+    pub fn record_request(&mut self, latency: Duration, success: bool, now: Instant) {
+        let latency_ms = latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS.iter().position(|upper| latency_ms <= *upper).unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.latency_counts[bucket] += 1;
+        self.total_requests += 1;
+        if !success {
+            self.total_errors += 1;
+        }
+        self.window.push_back((now, success));
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while matches!(self.window.front(), Some((at, _)) if now.duration_since(*at) > ERROR_RATE_WINDOW) {
+            self.window.pop_front();
+        }
+    }
+
+    pub fn snapshot(&self, connection_id: &str, connection_name: &str, state: PlcConnectionState, now: Instant) -> ConnectionQualitySnapshot {
+        let in_window: Vec<bool> = self.window.iter()
+            .filter(|(at, _)| now.duration_since(*at) <= ERROR_RATE_WINDOW)
+            .map(|(_, success)| *success)
+            .collect();
+        let window_errors = in_window.iter().filter(|success| !**success).count() as u64;
+        let ongoing_ms = self.disconnected_at.map(|since| (Utc::now() - since).num_milliseconds().max(0)).unwrap_or(0);
+
+        ConnectionQualitySnapshot {
+            connection_id: connection_id.to_string(),
+            connection_name: connection_name.to_string(),
+            state,
+            events: self.events.iter().cloned().collect(),
+            latency_histogram: self.latency_counts.iter().enumerate()
+                .map(|(index, count)| LatencyBucket { upper_ms: LATENCY_BUCKETS_MS.get(index).copied(), count: *count })
+                .collect(),
+            window_seconds: ERROR_RATE_WINDOW.as_secs(),
+            window_requests: in_window.len() as u64,
+            window_errors,
+            error_rate: if in_window.is_empty() { 0.0 } else { window_errors as f64 / in_window.len() as f64 },
+            total_requests: self.total_requests,
+            total_errors: self.total_errors,
+            disconnect_count: self.disconnect_count,
+            total_downtime_ms: self.downtime_ms + ongoing_ms,
+        }
+    }
+}
+
+/// 连接质量监视器
+///
+/// 由连接管理器持有；通信层在每次请求后上报时延，连接状态变化统一经 `transition` 记录并广播
+pub struct ConnectionMonitor {
+    quality: Mutex<HashMap<String, ConnectionQuality>>,
+    events: broadcast::Sender<ConnectionEvent>,
+    backoff: ReconnectBackoff,
+}
+
+impl ConnectionMonitor {
+    pub fn new(backoff: ReconnectBackoff) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self { quality: Mutex::new(HashMap::new()), events, backoff }
+    }
+
+    fn with_quality<R>(&self, connection_id: &str, f: impl FnOnce(&mut ConnectionQuality) -> R) -> R {
+        let mut quality = self.quality.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(quality.entry(connection_id.to_string()).or_default())
+    }
+
+    /// 订阅连接/断开事件
+    pub fn subscribe(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// 记录一次请求的时延与结果
+    pub fn record_request(&self, connection_id: &str, latency: Duration, success: bool) {
+        self.with_quality(connection_id, |quality| quality.record_request(latency, success, Instant::now()));
+    }
+
+    fn record_event(&self, connection_id: &str, kind: ConnectionEventKind, message: Option<String>) {
+        let event = ConnectionEvent { connection_id: connection_id.to_string(), timestamp: Utc::now(), kind, message };
+        self.with_quality(connection_id, |quality| quality.record_event(event.clone()));
+        if kind != ConnectionEventKind::ReconnectFailed {
+            // 没有订阅方时发送失败，忽略即可
+            let _ = self.events.send(event);
+        }
+    }
+
+    /// 切换连接状态；跨越“已连接”边界时记录并广播连接/断开事件
+    pub fn transition(&self, connection: &mut PlcConnection, state: PlcConnectionState, message: Option<String>) {
+        let was_connected = connection.state == PlcConnectionState::Connected;
+        let is_connected = state == PlcConnectionState::Connected;
+        connection.state = state;
+        if is_connected {
+            connection.reconnect_attempts = 0;
+            connection.next_retry_at = None;
+        }
+        match (was_connected, is_connected) {
+            (false, true) => self.record_event(&connection.config.id, ConnectionEventKind::Connected, message),
+            (true, false) => self.record_event(&connection.config.id, ConnectionEventKind::Disconnected, message),
+            _ => {}
+        }
+    }
+
+    /// 记录一次重连失败，并按退避策略安排下一次重连时间
+    pub fn schedule_retry(&self, connection: &mut PlcConnection, error: String) -> Duration {
+        connection.reconnect_attempts += 1;
+        let delay = self.backoff.delay(connection.reconnect_attempts);
+        connection.next_retry_at = Some(Instant::now() + delay);
+        connection.error_message = Some(error.clone());
+        self.record_event(
+            &connection.config.id,
+            ConnectionEventKind::ReconnectFailed,
+            Some(format!("第{}次重连失败: {}，{}ms后重试", connection.reconnect_attempts, error, delay.as_millis())),
+        );
+        self.transition(connection, PlcConnectionState::Reconnecting, None);
+        delay
+    }
+
+    /// 生成指定连接的质量快照
+    pub fn snapshot(&self, connection_id: &str, connection_name: &str, state: PlcConnectionState) -> ConnectionQualitySnapshot {
+        self.with_quality(connection_id, |quality| quality.snapshot(connection_id, connection_name, state, Instant::now()))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: ConnectionEventKind, timestamp: DateTime<Utc>) -> ConnectionEvent {
+        ConnectionEvent { connection_id: "plc1".to_string(), timestamp, kind, message: None }
+    }
+
+    #[test]
+    fn backoff_grows_to_the_cap_and_jitter_stays_in_bounds() {
+        let backoff = ReconnectBackoff::default();
+        let secs = |attempt| backoff.base_delay(attempt).as_secs_f64();
+        assert_eq!([secs(0), secs(1), secs(2), secs(3), secs(4)], [1.0, 1.0, 2.0, 4.0, 8.0]);
+        assert_eq!(secs(5), 10.0);
+        assert_eq!(secs(u32::MAX), 10.0);
+
+        for attempt in [1, 3, 8] {
+            let base = backoff.base_delay(attempt).as_secs_f64();
+            assert!((backoff.delay_with(attempt, -1.0).as_secs_f64() - base * 0.8).abs() < 1e-9);
+            assert!((backoff.delay_with(attempt, 1.0).as_secs_f64() - base * 1.2).abs() < 1e-9);
+            assert_eq!(backoff.delay_with(attempt, 5.0), backoff.delay_with(attempt, 1.0), "超出范围的抖动按边界处理");
+            for _ in 0..50 {
+                let delay = backoff.delay(attempt).as_secs_f64();
+                assert!(delay >= base * 0.8 - 1e-9 && delay <= base * 1.2 + 1e-9, "{} 超出抖动范围", delay);
+            }
+        }
+
+        let wild = ReconnectBackoff { jitter: 2.0, ..backoff };
+        assert_eq!(wild.delay_with(1, -1.0), Duration::ZERO, "抖动比例过大时不得出现负等待");
+    }
+
+    #[test]
+    fn error_rate_only_counts_requests_inside_the_window() {
+        let mut quality = ConnectionQuality::default();
+        let start = Instant::now();
+        quality.record_request(Duration::from_millis(3), false, start);
+        quality.record_request(Duration::from_millis(3), false, start + Duration::from_secs(30));
+        quality.record_request(Duration::from_millis(3), true, start + Duration::from_secs(45));
+
+        let snapshot = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Connected, start + Duration::from_secs(45));
+        assert_eq!((snapshot.window_requests, snapshot.window_errors), (3, 2));
+
+        // 第一条请求滑出窗口后只统计后两条，累计值不受影响
+        let later = start + ERROR_RATE_WINDOW + Duration::from_secs(1);
+        let snapshot = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Connected, later);
+        assert_eq!((snapshot.window_requests, snapshot.window_errors), (2, 1));
+        assert!((snapshot.error_rate - 0.5).abs() < 1e-9);
+        assert_eq!((snapshot.total_requests, snapshot.total_errors), (3, 2));
+
+        let idle = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Connected, start + Duration::from_secs(200));
+        assert_eq!((idle.window_requests, idle.error_rate), (0, 0.0));
+    }
+
+    #[test]
+    fn latency_lands_in_the_first_bucket_that_covers_it() {
+        let mut quality = ConnectionQuality::default();
+        let now = Instant::now();
+        for ms in [0, 5, 6, 1000, 1001, 60_000] {
+            quality.record_request(Duration::from_millis(ms), true, now);
+        }
+        let histogram = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Connected, now).latency_histogram;
+        let counts: Vec<(Option<u64>, u64)> = histogram.iter().map(|b| (b.upper_ms, b.count)).collect();
+        assert_eq!(counts, vec![
+            (Some(5), 2), (Some(10), 1), (Some(20), 0), (Some(50), 0),
+            (Some(100), 0), (Some(200), 0), (Some(500), 0), (Some(1000), 1), (None, 2),
+        ]);
+    }
+
+    #[test]
+    fn downtime_runs_from_the_first_disconnect_until_reconnect() {
+        let mut quality = ConnectionQuality::default();
+        let down = Utc::now() - chrono::Duration::seconds(60);
+        quality.record_event(event(ConnectionEventKind::Disconnected, down));
+        quality.record_event(event(ConnectionEventKind::ReconnectFailed, down + chrono::Duration::seconds(1)));
+        // 重复的断开事件不重置掉线起点
+        quality.record_event(event(ConnectionEventKind::Disconnected, down + chrono::Duration::seconds(2)));
+        quality.record_event(event(ConnectionEventKind::Connected, down + chrono::Duration::seconds(3)));
+
+        let snapshot = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Connected, Instant::now());
+        assert_eq!(snapshot.total_downtime_ms, 3000);
+        assert_eq!(snapshot.disconnect_count, 2);
+        assert_eq!(snapshot.events.len(), 4);
+
+        // 尚未恢复的掉线计入当前时长
+        quality.record_event(event(ConnectionEventKind::Disconnected, Utc::now() - chrono::Duration::seconds(10)));
+        let snapshot = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Disconnected, Instant::now());
+        assert!((13_000..20_000).contains(&snapshot.total_downtime_ms), "{}", snapshot.total_downtime_ms);
+
+        for _ in 0..EVENT_CAPACITY {
+            quality.record_event(event(ConnectionEventKind::ReconnectFailed, Utc::now()));
+        }
+        let snapshot = quality.snapshot("plc1", "测试PLC", PlcConnectionState::Disconnected, Instant::now());
+        assert_eq!(snapshot.events.len(), EVENT_CAPACITY);
+        assert_eq!(snapshot.events[0].kind, ConnectionEventKind::ReconnectFailed, "最旧的事件被挤出");
+    }
+}
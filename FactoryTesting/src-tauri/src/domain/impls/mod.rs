@@ -13,6 +13,8 @@
 //! - **通道状态管理**: ChannelStateManager - 管理测试通道的状态转换
 //! - **测试执行引擎**: TestExecutionEngine - 执行具体的测试任务
 //! - **特定测试器**: Specific Test Executors - AI/AO/DI/DO各类型的专用执行器
+//! - **信号稳定等待**: Signal Settling - 硬点测试的轮询稳定判定
+//! - **接线检查**: WiringDiscoverySweep - 测试前扫描识别交叉接线
 //! - **PLC连接管理**: PlcConnectionManager - 管理PLC设备连接
 //! - **批次分配服务**: RealBatchAllocationService - 处理测试批次的分配逻辑
 //! - **测试编排服务**: RealTestOrchestrationService - 协调整个测试流程
@@ -30,7 +32,10 @@
 pub mod channel_state_manager;
 pub mod test_execution_engine;
 pub mod specific_test_executors;
+pub mod signal_settling;
+pub mod wiring_check;
 pub mod plc_connection_manager;
+pub mod connection_quality;
 // pub mod stub_test_orchestration_service; // retired after real implementation
 pub mod real_test_orchestration_service;
 // pub mod stub_batch_allocation_service; // retired after real implementation
@@ -43,17 +48,23 @@ pub use channel_state_manager::ChannelStateManager;
 pub use specific_test_executors::{
     AIHardPointPercentExecutor,
     AIAlarmTestExecutor,
+    AlarmTestMode,
     DIHardPointTestExecutor,
     DOHardPointTestExecutor,
     AOHardPointTestExecutor,
+    CommunicationTestExecutor,
+    CrosstalkNeighbor,
 };
 pub use test_execution_engine::{
     TestExecutionEngine,
     TaskStatus,
+    TestRetryPolicy,
     TestTask,
 };
 pub use test_plc_config_service::TestPlcConfigService;
+pub use wiring_check::{WiringDiscoverySweep, WiringRigOutput, WiringTargetChannel};
 // pub use stub_batch_allocation_service::StubBatchAllocationService;
 pub use real_test_orchestration_service::RealTestOrchestrationService;
 pub use real_batch_allocation_service::RealBatchAllocationService;
-pub use plc_connection_manager::{PlcConnectionManager, PlcConnectionState}; 
+pub use plc_connection_manager::{PlcConnectionManager, PlcConnectionState};
+pub use connection_quality::{ConnectionEvent, ConnectionEventKind, ConnectionQualitySnapshot, ReconnectBackoff}; 
@@ -4,7 +4,8 @@
 //! 本模块实现了PLC连接的统一管理，提供：
 //! - **连接池管理**: 统一管理多个PLC设备的连接
 //! - **状态监控**: 实时监控所有PLC连接的状态
-//! - **自动重连**: 连接断开时自动尝试重新连接
+//! - **自动重连**: 连接断开时按指数退避（带随机抖动）自动重新连接
+//! - **质量记录**: 连接/断开事件、请求时延与错误率，见 `connection_quality` 模块
 //! - **心跳检测**: 定期检测连接健康状态
 //! - **故障恢复**: 智能的故障检测和恢复机制
 //!
@@ -37,8 +38,12 @@ use log::{info, warn, error, debug};
 use serde::{Serialize, Deserialize};
 
 use crate::models::test_plc_config::PlcConnectionConfig;
+use super::connection_quality::{ConnectionEvent, ConnectionMonitor, ConnectionQualitySnapshot, ReconnectBackoff};
 use crate::domain::test_plc_config_service::ITestPlcConfigService;
-use crate::error::AppError;
+use crate::error::{AppError, AppResult};
+
+/// 连续心跳失败达到此次数后判定连接断开并触发重连
+const HEARTBEAT_FAILURE_THRESHOLD: u32 = 3;
 
 /// PLC连接状态枚举
 ///
@@ -140,6 +145,11 @@ pub struct PlcConnection {
     /// **故障判定**: 达到阈值时触发重连机制
     /// **重置条件**: 心跳成功时重置为0
     pub heart_failure_count: u32,
+
+    /// 下一次允许重连的时间
+    /// **业务含义**: 按退避策略计算，未到时间的重连尝试被跳过
+    /// **重置条件**: 连接成功时清空
+    pub next_retry_at: Option<Instant>,
 }
 
 /// PLC连接管理器
@@ -176,10 +186,10 @@ pub struct PlcConnectionManager {
     /// **性能平衡**: 间隔太短影响性能，太长影响故障检测及时性
     heartbeat_interval: Duration,
 
-    /// 重连间隔
-    /// **业务含义**: 连接断开后重新尝试连接的时间间隔
-    /// **避免频繁重连**: 防止对PLC设备造成过大压力
-    reconnect_interval: Duration,
+    /// 连接质量监视器
+    /// **业务含义**: 计算重连退避，记录连接事件、请求时延与错误率
+    /// **避免频繁重连**: 连续失败时重连间隔指数增长，防止对PLC设备造成过大压力
+    monitor: Arc<ConnectionMonitor>,
 
     /// 最大重连尝试次数
     /// **业务含义**: 单次重连周期中的最大尝试次数
@@ -191,6 +201,11 @@ pub struct PlcConnectionManager {
     /// **并发控制**: Mutex确保状态变更的原子性
     /// **生命周期**: 控制心跳和重连任务的启停
     is_running: Arc<Mutex<bool>>,
+
+    /// 端点覆盖表
+    /// **数据结构**: HashMap<连接ID, (主机, 端口)>
+    /// **业务含义**: Mock模式下将测试PLC/被测PLC连接指向内置仿真器，不修改数据库中的连接配置
+    endpoint_overrides: Arc<RwLock<HashMap<String, (String, u16)>>>,
 }
 
 impl PlcConnectionManager {
@@ -220,7 +235,7 @@ impl PlcConnectionManager {
     ///
     /// **默认配置**:
     /// - 心跳间隔: 1秒 - 平衡及时性和性能
-    /// - 重连间隔: 10秒 - 避免频繁重连对设备的冲击
+    /// - 重连间隔: 1秒起指数退避，最长10秒，±20%随机抖动 - 避免频繁重连对设备的冲击
     /// - 重连次数: 无限 - 确保连接的持久性
     /// - 初始状态: 未运行 - 需要显式启动
     ///
@@ -237,12 +252,42 @@ impl PlcConnectionManager {
             connections: Arc::new(RwLock::new(HashMap::new())), // 初始化空连接池
             test_plc_config_service,                            // 注入配置服务
             heartbeat_interval: Duration::from_secs(1),         // 每1秒心跳检测
-            reconnect_interval: Duration::from_secs(10),        // 每10秒重连尝试
+            monitor: Arc::new(ConnectionMonitor::new(ReconnectBackoff::default())), // 重连退避与质量记录
             max_reconnect_attempts: 0,                          // 无限重连
             is_running: Arc::new(Mutex::new(false)),            // 初始状态为未运行
+            endpoint_overrides: Arc::new(RwLock::new(HashMap::new())), // 默认不覆盖端点
         }
     }
 
+    /// 将指定连接改为连接到给定的Modbus TCP端点
+    ///
+    /// **使用场景**: Mock模式下由内置PLC仿真器接管测试PLC与被测PLC连接
+    /// **生效时机**: 下一次 `start_connections` 建立连接时
+    pub async fn override_endpoint(&self, connection_id: &str, host: &str, port: u16) {
+        info!("🔀 PLC连接 {} 端点覆盖为 {}:{}", connection_id, host, port);
+        self.endpoint_overrides.write().await.insert(connection_id.to_string(), (host.to_string(), port));
+    }
+
+    /// 连接质量监视器，通信层通过它上报请求时延
+    pub fn monitor(&self) -> &Arc<ConnectionMonitor> {
+        &self.monitor
+    }
+
+    /// 订阅连接/断开事件
+    pub fn subscribe_connection_events(&self) -> tokio::sync::broadcast::Receiver<ConnectionEvent> {
+        self.monitor.subscribe()
+    }
+
+    /// 获取所有连接的质量历史（事件、时延直方图、错误率）
+    pub async fn connection_history(&self) -> Vec<ConnectionQualitySnapshot> {
+        let connections = self.connections.read().await;
+        let mut history: Vec<ConnectionQualitySnapshot> = connections.values()
+            .map(|c| self.monitor.snapshot(&c.config.id, &c.config.name, c.state.clone()))
+            .collect();
+        history.sort_by(|a, b| a.connection_name.cmp(&b.connection_name));
+        history
+    }
+
     /// 开始连接所有启用的PLC
     pub async fn start_connections(&self) -> Result<(), AppError> {
         info!("🔗 开始连接所有启用的PLC");
@@ -260,33 +305,25 @@ impl PlcConnectionManager {
         
         let mut connections = self.connections.write().await;
         
+        let endpoint_overrides = self.endpoint_overrides.read().await.clone();
+
         // 初始化连接
-        for config in plc_configs {
+        for mut config in plc_configs {
             if !config.is_enabled {
                 debug!("⏭️ 跳过未启用的PLC: {}", config.name);
                 continue;
             }
+
+            if let Some((host, port)) = endpoint_overrides.get(&config.id) {
+                config.plc_type = crate::models::test_plc_config::PlcType::ModbusTcp;
+                config.ip_address = host.clone();
+                config.port = *port as i32;
+            }
             
-            info!("🔗 初始化PLC连接: {} ({}:{})", config.name, config.ip_address, config.port);
+            info!("🔗 初始化PLC连接: {} ({})", config.name, config.endpoint());
 
             // 调用全局 PLC 服务建立连接，确保句柄注册到 default_handles
-            use crate::domain::services::plc_communication_service::{PlcConnectionConfig as ServicePlcConfig, PlcProtocol};
-            use std::collections::HashMap;
-            let svc_cfg = ServicePlcConfig {
-                id: config.id.clone(),
-                name: config.name.clone(),
-                protocol: PlcProtocol::ModbusTcp,
-                host: config.ip_address.clone(),
-                port: config.port as u16,
-                timeout_ms: config.timeout as u64,
-                read_timeout_ms: config.timeout as u64,
-                write_timeout_ms: config.timeout as u64,
-                retry_count: config.retry_count as u32,
-                retry_interval_ms: 500,
-                byte_order: config.byte_order.clone(),
-                zero_based_address: config.zero_based_address,
-                protocol_params: HashMap::new(),
-            };
+            let svc_cfg = config.to_service_config();
             let plc_service = crate::infrastructure::plc_communication::global_plc_service();
             let mut connection_state = PlcConnectionState::Disconnected;
             match plc_service.connect(&svc_cfg).await {
@@ -298,16 +335,18 @@ impl PlcConnectionManager {
                     error!("❌ PLC连接失败: {} - {}", config.name, err);
                 }
             }
-            let connection = PlcConnection {
+            let mut connection = PlcConnection {
                 config: config.clone(),
-                state: connection_state,
+                state: PlcConnectionState::Disconnected,
                 context: None,
                 last_heartbeat: None,
                 error_message: None,
                 reconnect_attempts: 0,
                 heart_failure_count: 0,
+                next_retry_at: None,
             };
-            
+            self.monitor.transition(&mut connection, connection_state, Some("初始连接".to_string()));
+
             connections.insert(config.id.clone(), connection);
         }
         
@@ -333,7 +372,7 @@ impl PlcConnectionManager {
             if connection.state == PlcConnectionState::Connected {
                 info!("🔌 断开PLC连接: {}", connection.config.name);
                 connection.context = None;
-                connection.state = PlcConnectionState::Disconnected;
+                self.monitor.transition(connection, PlcConnectionState::Disconnected, Some("主动停止连接".to_string()));
             }
         }
         
@@ -405,7 +444,7 @@ impl PlcConnectionManager {
         let connections = self.connections.clone();
         let is_running = self.is_running.clone();
         let heartbeat_interval = self.heartbeat_interval;
-        let reconnect_interval = self.reconnect_interval;
+        let monitor = self.monitor.clone();
 
         // 启动连接任务
         let connections_for_connection_task = connections.clone();
@@ -428,10 +467,10 @@ impl PlcConnectionManager {
 
                 for connection_id in connection_ids {
                     let connections_clone = connections_for_connection_task.clone();
-                    let reconnect_interval_clone = reconnect_interval;
+                    let monitor_clone = monitor.clone();
 
                     tokio::spawn(async move {
-                        Self::handle_connection_task(connections_clone, connection_id, reconnect_interval_clone).await;
+                        Self::handle_connection_task(connections_clone, connection_id, monitor_clone).await;
                     });
                 }
 
@@ -446,23 +485,24 @@ impl PlcConnectionManager {
 
     }
 
-    /// 处理单个连接任务
+    /// 处理单个连接任务，退避时间未到的连接跳过本轮
     async fn handle_connection_task(
         connections: Arc<RwLock<HashMap<String, PlcConnection>>>,
         connection_id: String,
-        reconnect_interval: Duration,
+        monitor: Arc<ConnectionMonitor>,
     ) {
         let should_connect = {
             let connections_read = connections.read().await;
             if let Some(connection) = connections_read.get(&connection_id) {
                 matches!(connection.state, PlcConnectionState::Disconnected | PlcConnectionState::Reconnecting)
+                    && !matches!(connection.next_retry_at, Some(at) if Instant::now() < at)
             } else {
                 false
             }
         };
         
         if should_connect {
-            Self::attempt_connection(connections, connection_id, reconnect_interval).await;
+            Self::attempt_connection(connections, connection_id, monitor).await;
         }
     }
 
@@ -470,7 +510,7 @@ impl PlcConnectionManager {
     async fn attempt_connection(
         connections: Arc<RwLock<HashMap<String, PlcConnection>>>,
         connection_id: String,
-        reconnect_interval: Duration,
+        monitor: Arc<ConnectionMonitor>,
     ) {
         let config = {
             let mut connections_write = connections.write().await;
@@ -482,8 +522,14 @@ impl PlcConnectionManager {
             }
         };
         
-        info!("🔗 尝试连接PLC: {} ({}:{})", config.name, config.ip_address, config.port);
-        
+        info!("🔗 尝试连接PLC: {} ({})", config.name, config.endpoint());
+
+        // 串口为独占设备、S7/OPC UA需要建立会话，不能再单独打开一次：由全局 PLC 服务持有连接，这里只同步其状态
+        if !config.is_modbus_tcp() {
+            Self::attempt_service_connection(&connections, &connection_id, &config, &monitor).await;
+            return;
+        }
+
         // 尝试建立连接
         let socket_addr = format!("{}:{}", config.ip_address, config.port);
         match socket_addr.parse::<std::net::SocketAddr>() {
@@ -495,24 +541,17 @@ impl PlcConnectionManager {
                         let mut connections_write = connections.write().await;
                         if let Some(connection) = connections_write.get_mut(&connection_id) {
                             connection.context = Some(Arc::new(Mutex::new(context)));
-                            connection.state = PlcConnectionState::Connected;
                             connection.last_heartbeat = Some(chrono::Utc::now());
                             connection.error_message = None;
-                            connection.reconnect_attempts = 0;
+                            monitor.transition(connection, PlcConnectionState::Connected, None);
                         }
                     }
                     Err(e) => {
-                        error!("❌ PLC连接失败: {} - {}", config.name, e);
-                        
                         let mut connections_write = connections.write().await;
                         if let Some(connection) = connections_write.get_mut(&connection_id) {
-                            connection.state = PlcConnectionState::Reconnecting;
-                            connection.error_message = Some(e.to_string());
-                            connection.reconnect_attempts += 1;
+                            let delay = monitor.schedule_retry(connection, e.to_string());
+                            error!("❌ PLC连接失败: {} - {}，{}ms后重试", config.name, e, delay.as_millis());
                         }
-                        
-                        // 等待后重试
-                        sleep(reconnect_interval).await;
                     }
                 }
             }
@@ -521,15 +560,95 @@ impl PlcConnectionManager {
                 
                 let mut connections_write = connections.write().await;
                 if let Some(connection) = connections_write.get_mut(&connection_id) {
-                    connection.state = PlcConnectionState::Error;
-                    connection.error_message = Some(format!("无效地址: {}", e));
+                    let message = format!("无效地址: {}", e);
+                    connection.error_message = Some(message.clone());
+                    monitor.transition(connection, PlcConnectionState::Error, Some(message));
+                }
+            }
+        }
+    }
+
+    /// 通过全局 PLC 服务建立（或复用）串口/S7/OPC UA连接，并同步连接状态
+    async fn attempt_service_connection(
+        connections: &Arc<RwLock<HashMap<String, PlcConnection>>>,
+        connection_id: &str,
+        config: &PlcConnectionConfig,
+        monitor: &ConnectionMonitor,
+    ) {
+        let plc_service = crate::infrastructure::plc_communication::global_plc_service();
+        let result = plc_service.connect(&config.to_service_config()).await;
+
+        let mut connections_write = connections.write().await;
+        let Some(connection) = connections_write.get_mut(connection_id) else {
+            return;
+        };
+        match result {
+            Ok(_) => {
+                info!("✅ PLC连接成功: {} ({})", config.name, config.endpoint());
+                connection.last_heartbeat = Some(chrono::Utc::now());
+                connection.error_message = None;
+                monitor.transition(connection, PlcConnectionState::Connected, None);
+            }
+            Err(e) => {
+                let delay = monitor.schedule_retry(connection, e.to_string());
+                error!("❌ PLC连接失败: {} ({}) - {}，{}ms后重试", config.name, config.endpoint(), e, delay.as_millis());
+            }
+        }
+    }
+
+    /// 通过全局 PLC 服务探测串口/S7/OPC UA连接，连续失败达到阈值时切换为 Reconnecting 以触发重连
+    async fn sync_service_connection_state(
+        connections: &Arc<RwLock<HashMap<String, PlcConnection>>>,
+        connection_id: &str,
+        config_name: &str,
+        monitor: &ConnectionMonitor,
+    ) {
+        let plc_service = crate::infrastructure::plc_communication::global_plc_service();
+        let probe = match plc_service.default_handle_by_id(connection_id).await {
+            Some(handle) => plc_service.probe(&handle).await,
+            None => Err(AppError::plc_communication_error("通信服务中没有该连接")),
+        };
+        Self::apply_service_probe(connections, connection_id, config_name, monitor, probe).await;
+    }
+
+    /// 按探测结果更新串口/S7/OPC UA连接的状态
+    async fn apply_service_probe(
+        connections: &Arc<RwLock<HashMap<String, PlcConnection>>>,
+        connection_id: &str,
+        config_name: &str,
+        monitor: &ConnectionMonitor,
+        probe: AppResult<()>,
+    ) {
+        let mut connections_write = connections.write().await;
+        let Some(connection) = connections_write.get_mut(connection_id) else {
+            return;
+        };
+        match probe {
+            Ok(()) => {
+                connection.last_heartbeat = Some(chrono::Utc::now());
+                connection.error_message = None;
+                connection.heart_failure_count = 0;
+                if connection.state != PlcConnectionState::Connected {
+                    debug!("🔄 状态修正: {} -> Connected", config_name);
+                    monitor.transition(connection, PlcConnectionState::Connected, None);
+                }
+            }
+            Err(e) => {
+                warn!("💔 PLC心跳失败: {} - {}", config_name, e);
+                connection.error_message = Some(format!("心跳失败: {}", e));
+                connection.heart_failure_count += 1;
+                if connection.heart_failure_count >= HEARTBEAT_FAILURE_THRESHOLD && connection.state == PlcConnectionState::Connected {
+                    warn!("🔄 连续心跳失败达到阈值，切换为 Reconnecting: {}", config_name);
+                    let message = connection.error_message.clone();
+                    monitor.transition(connection, PlcConnectionState::Reconnecting, message);
+                    connection.heart_failure_count = 0;
                 }
             }
         }
     }
 
     /// 执行心跳检测
-    async fn perform_heartbeat_check(connections: Arc<RwLock<HashMap<String, PlcConnection>>>) {
+    async fn perform_heartbeat_check(connections: Arc<RwLock<HashMap<String, PlcConnection>>>, monitor: Arc<ConnectionMonitor>) {
         let connection_ids: Vec<String> = {
             let connections_read = connections.read().await;
             connections_read.keys().cloned().collect()
@@ -538,9 +657,10 @@ impl PlcConnectionManager {
         
         for connection_id in connection_ids {
             let connections_clone = connections.clone();
+            let monitor_clone = monitor.clone();
             
             tokio::spawn(async move {
-                Self::check_single_connection_heartbeat(connections_clone, connection_id).await;
+                Self::check_single_connection_heartbeat(connections_clone, connection_id, monitor_clone).await;
             });
         }
     }
@@ -549,19 +669,27 @@ impl PlcConnectionManager {
     async fn check_single_connection_heartbeat(
         connections: Arc<RwLock<HashMap<String, PlcConnection>>>,
         connection_id: String,
+        monitor: Arc<ConnectionMonitor>,
     ) {
-        let (context, config_name, current_state) = {
+        let (context, config_name, current_state, is_modbus_tcp) = {
             let connections_read = connections.read().await;
             if let Some(connection) = connections_read.get(&connection_id) {
                 (
                     connection.context.clone(),
                     connection.config.name.clone(),
                     connection.state.clone(),
+                    connection.config.is_modbus_tcp(),
                 )
             } else {
                 return;
             }
         };
+
+        // 串口/S7/OPC UA连接的通信由全局 PLC 服务负责，这里通过服务执行一次探测
+        if !is_modbus_tcp {
+            Self::sync_service_connection_state(&connections, &connection_id, &config_name, &monitor).await;
+            return;
+        }
         
         if let Some(context_arc) = context {
             // 尝试读取线圈03001 (地址3000，因为Modbus地址从0开始)
@@ -581,8 +709,7 @@ impl PlcConnectionManager {
                         connection.heart_failure_count = 0;
                         if connection.state != PlcConnectionState::Connected {
                             debug!("🔄 状态修正: {} -> Connected", config_name);
-                            connection.state = PlcConnectionState::Connected;
-                            connection.reconnect_attempts = 0;
+                            monitor.transition(connection, PlcConnectionState::Connected, None);
                         }
                     }
                 }
@@ -593,9 +720,10 @@ impl PlcConnectionManager {
                     if let Some(connection) = connections_write.get_mut(&connection_id) {
                         connection.error_message = Some(format!("心跳失败: {}", e));
                         connection.heart_failure_count += 1;
-                        if connection.heart_failure_count >= 3 {
+                        if connection.heart_failure_count >= HEARTBEAT_FAILURE_THRESHOLD {
                             warn!("🔄 连续心跳失败达到阈值，切换为 Reconnecting: {}", config_name);
-                            connection.state = PlcConnectionState::Reconnecting;
+                            let message = connection.error_message.clone();
+                            monitor.transition(connection, PlcConnectionState::Reconnecting, message);
                             connection.context = None;
                             connection.heart_failure_count = 0;
                         }
@@ -608,10 +736,10 @@ impl PlcConnectionManager {
             let mut connections_write = connections.write().await;
             if let Some(connection) = connections_write.get_mut(&connection_id) {
                 connection.heart_failure_count += 1;
-                if connection.heart_failure_count >= 3 {
+                if connection.heart_failure_count >= HEARTBEAT_FAILURE_THRESHOLD {
                     warn!("🔄 连续缺失 context 达到阈值，切换为 Reconnecting: {}", config_name);
-                    connection.state = PlcConnectionState::Reconnecting;
                     connection.error_message = Some("Modbus context lost".to_string());
+                    monitor.transition(connection, PlcConnectionState::Reconnecting, Some("Modbus context lost".to_string()));
                     connection.heart_failure_count = 0;
                 }
             }
@@ -0,0 +1,374 @@
+//! # 信号稳定等待 (Signal Settling)
+//!
+//! ## 业务说明
+//! 硬点测试在写入激励后需要等待信号稳定再读取结果。固定延时会让快速通道白白等待，
+//! 而慢速通道又可能读到未稳定的值。本模块以轮询方式等待信号稳定，分两种判定：
+//! - 模拟量：相邻采样的变化量连续 N 次不超过稳定带，且读数已到位即视为稳定。
+//!   给定期望值时读数须落在期望值的允许偏差内，偏离目标的稳定读数会等到超时；
+//!   没有期望值时须观察到读数变化或已等待最短停留时间，避免把"尚未开始变化"当作稳定
+//! - 开关量/回读值：信号只有"到位"与"未到位"，无法用变化量区分"尚未动作"和"卡死"，
+//!   连续 N 次采样等于期望状态即视为到位
+//!
+//! 超过等待上限则返回最后一次采样值。
+//!
+//! ## 使用方式
+//! ```ignore
+//! let policy = SettlePolicy::with_timeout(3000)
+//!     .with_band(settings.settle_band(range_span))
+//!     .with_expected(expected_value, settings.tolerance_band(range_span));
+//! let settled = wait_for_settle(&policy, || read_value()).await?;
+//! let reached = wait_for_state(&SettlePolicy::with_timeout(1000), || read_bool(), |v| *v).await?;
+//! ```
+
+use crate::utils::error::AppResult;
+use serde_json::json;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// 默认采样间隔（毫秒）
+pub const DEFAULT_SETTLE_POLL_INTERVAL_MS: u64 = 100;
+/// 默认连续稳定采样次数
+pub const DEFAULT_SETTLE_STABLE_SAMPLES: u32 = 3;
+/// 默认最短停留时间（毫秒），没有期望值且读数未变化时，至少等待这么久才判定稳定
+pub const DEFAULT_SETTLE_MIN_DWELL_MS: u64 = 500;
+
+/// 稳定等待策略
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettlePolicy {
+    /// 采样间隔（毫秒）
+    pub poll_interval_ms: u64,
+    /// 判定稳定所需的连续有效采样次数
+    pub stable_samples: u32,
+    /// 等待上限（毫秒），超时后以最后一次采样作为结果
+    pub timeout_ms: u64,
+    /// 稳定带：相邻两次采样允许的最大变化量（工程单位），仅用于 `wait_for_settle`
+    pub settle_band: f64,
+    /// 期望值与允许偏差（工程单位），仅用于 `wait_for_settle`
+    pub expected: Option<(f64, f64)>,
+    /// 没有期望值且读数未变化时的最短停留时间（毫秒），仅用于 `wait_for_settle`
+    pub min_dwell_ms: u64,
+}
+
+impl SettlePolicy {
+    /// 使用默认采样参数和指定的等待上限创建策略（稳定带为0，即要求读数不再变化）
+    pub fn with_timeout(timeout_ms: u64) -> Self {
+        Self {
+            poll_interval_ms: DEFAULT_SETTLE_POLL_INTERVAL_MS,
+            stable_samples: DEFAULT_SETTLE_STABLE_SAMPLES,
+            timeout_ms,
+            settle_band: 0.0,
+            expected: None,
+            min_dwell_ms: DEFAULT_SETTLE_MIN_DWELL_MS,
+        }
+    }
+
+    /// 设置稳定带
+    pub fn with_band(mut self, settle_band: f64) -> Self {
+        self.settle_band = settle_band.max(0.0);
+        self
+    }
+
+    /// 设置期望值，读数落在 `expected ± tolerance` 内才计为稳定
+    pub fn with_expected(mut self, expected: f64, tolerance: f64) -> Self {
+        self.expected = Some((expected, tolerance.max(0.0)));
+        self
+    }
+}
+
+/// 稳定等待结果
+#[derive(Debug, Clone)]
+pub struct SettleResult<T> {
+    /// 最后一次采样值（稳定时即为稳定值）
+    pub value: T,
+    /// 是否在等待上限内达到稳定
+    pub settled: bool,
+    /// 从开始等待到判定稳定（或超时）的时间（毫秒）
+    pub elapsed_ms: u64,
+    /// 采样次数
+    pub samples: u32,
+}
+
+/// 可用于变化量稳定判定的采样值
+pub trait SettleSample {
+    /// 采样值（工程单位）
+    fn as_f64(&self) -> f64;
+
+    /// 与上一次采样相比的变化量
+    fn change_from(&self, previous: &Self) -> f64 {
+        (self.as_f64() - previous.as_f64()).abs()
+    }
+}
+
+impl SettleSample for f32 {
+    fn as_f64(&self) -> f64 {
+        *self as f64
+    }
+}
+
+impl SettleSample for f64 {
+    fn as_f64(&self) -> f64 {
+        *self
+    }
+}
+
+/// 轮询读取直到相邻采样的变化量连续 `stable_samples` 次不超过 `settle_band` 且读数已到位，或达到等待上限
+///
+/// - 设置了 `expected` 时，读数须在期望值的允许偏差内才计为到位
+/// - 未设置时，观察到读数变化或等待满 `min_dwell_ms` 后才计为到位
+/// - 判定稳定至少需要 `stable_samples + 1` 次采样，`elapsed_ms` 为最后一次采样完成的时刻
+/// - 读取失败直接返回错误，由调用方按通信故障处理
+/// - 超时不视为错误，返回 `settled = false` 和最后一次采样值，由调用方判定测试结果
+pub async fn wait_for_settle<T, F, Fut>(
+    policy: &SettlePolicy,
+    read: F,
+) -> AppResult<SettleResult<T>>
+where
+    T: SettleSample + Clone,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+{
+    let started = Instant::now();
+    let min_dwell = Duration::from_millis(policy.min_dwell_ms);
+    let mut first: Option<T> = None;
+    let mut previous: Option<T> = None;
+    let mut moved = false;
+    poll_until(policy, read, |value: &T| {
+        let stable = previous.as_ref().is_some_and(|p| value.change_from(p) <= policy.settle_band);
+        match &first {
+            Some(first) => moved |= value.change_from(first) > 0.0,
+            None => first = Some(value.clone()),
+        }
+        previous = Some(value.clone());
+        let in_place = match policy.expected {
+            Some((expected, tolerance)) => (value.as_f64() - expected).abs() <= tolerance,
+            None => moved || started.elapsed() >= min_dwell,
+        };
+        stable && in_place
+    }).await
+}
+
+/// 轮询读取直到连续 `stable_samples` 次采样满足 `expected`，或达到等待上限
+///
+/// 用于开关量反馈、报警状态和通信回读等只有"到位/未到位"之分的信号，
+/// `elapsed_ms` 为连续到位的最后一次采样完成的时刻
+pub async fn wait_for_state<T, F, Fut, P>(
+    policy: &SettlePolicy,
+    read: F,
+    expected: P,
+) -> AppResult<SettleResult<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+    P: Fn(&T) -> bool,
+{
+    poll_until(policy, read, expected).await
+}
+
+/// 轮询读取，`accept` 连续返回 `stable_samples` 次 true 即判定稳定
+async fn poll_until<T, F, Fut, A>(
+    policy: &SettlePolicy,
+    mut read: F,
+    mut accept: A,
+) -> AppResult<SettleResult<T>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+    A: FnMut(&T) -> bool,
+{
+    let started = Instant::now();
+    let timeout = Duration::from_millis(policy.timeout_ms);
+    let poll_interval = Duration::from_millis(policy.poll_interval_ms);
+    let required = policy.stable_samples.max(1);
+
+    let mut samples = 0u32;
+    let mut consecutive = 0u32;
+
+    loop {
+        let value = read().await?;
+        samples += 1;
+
+        if accept(&value) {
+            consecutive += 1;
+        } else {
+            consecutive = 0;
+        }
+
+        let settled = consecutive >= required;
+        if settled || started.elapsed() >= timeout {
+            return Ok(SettleResult {
+                value,
+                settled,
+                elapsed_ms: started.elapsed().as_millis() as u64,
+                samples,
+            });
+        }
+
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+/// 单个测试步骤的稳定等待记录，用于写入 `RawTestOutcome.details`
+#[derive(Debug, Clone)]
+pub struct SettleRecord {
+    /// 步骤标识（例如："50%"、"步骤2"、"触发"）
+    pub step: String,
+    /// 实际稳定时间（毫秒）
+    pub elapsed_ms: u64,
+    /// 是否在等待上限内稳定
+    pub settled: bool,
+}
+
+impl SettleRecord {
+    pub fn new<T>(step: impl Into<String>, result: &SettleResult<T>) -> Self {
+        Self {
+            step: step.into(),
+            elapsed_ms: result.elapsed_ms,
+            settled: result.settled,
+        }
+    }
+}
+
+/// 将各步骤的稳定等待记录写入测试结果详情
+///
+/// - `settle_times_ms`: 各步骤实际稳定时间（按执行顺序）
+/// - `settle_timeouts`: 未在等待上限内稳定的步骤
+/// - `settle_max_ms`: 最长稳定时间
+pub fn record_settle_times(
+    details: &mut std::collections::HashMap<String, serde_json::Value>,
+    records: &[SettleRecord],
+) {
+    let times: Vec<serde_json::Value> = records
+        .iter()
+        .map(|r| json!({ "step": r.step, "elapsed_ms": r.elapsed_ms, "settled": r.settled }))
+        .collect();
+    let timeouts: Vec<&str> = records
+        .iter()
+        .filter(|r| !r.settled)
+        .map(|r| r.step.as_str())
+        .collect();
+    let max_ms = records.iter().map(|r| r.elapsed_ms).max().unwrap_or(0);
+
+    details.insert("settle_times_ms".to_string(), json!(times));
+    details.insert("settle_timeouts".to_string(), json!(timeouts));
+    details.insert("settle_max_ms".to_string(), json!(max_ms));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::error::AppError;
+
+    /// 按脚本依次返回采样值，脚本用完后保持最后一个值
+    fn scripted<T: Clone>(values: Vec<T>) -> impl FnMut() -> std::future::Ready<AppResult<T>> {
+        let mut next = 0;
+        move || {
+            let value = values[next.min(values.len() - 1)].clone();
+            next += 1;
+            std::future::ready(Ok(value))
+        }
+    }
+
+    fn fast_policy(timeout_ms: u64, settle_band: f64) -> SettlePolicy {
+        SettlePolicy { poll_interval_ms: 2, ..SettlePolicy::with_timeout(timeout_ms) }.with_band(settle_band)
+    }
+
+    #[tokio::test]
+    async fn settles_once_readings_stop_moving() {
+        let policy = fast_policy(1000, 0.05);
+        let result = wait_for_settle(&policy, scripted(vec![0.0f32, 4.0, 8.0, 9.9, 10.0, 10.02, 10.01, 10.0]))
+            .await
+            .unwrap();
+
+        assert!(result.settled);
+        assert_eq!(result.samples, 8);
+        assert_eq!(result.value, 10.0);
+        // 稳定时间为稳定段结束的时刻，而不是第一次接近目标的时刻
+        assert!(result.elapsed_ms >= 7 * policy.poll_interval_ms, "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn off_target_steady_signal_waits_until_timeout() {
+        // 读数不再变化但偏离期望值，不能判定为稳定
+        let policy = fast_policy(30, 0.05).with_expected(10.0, 0.5);
+        let result = wait_for_settle(&policy, scripted(vec![7.0f32])).await.unwrap();
+
+        assert!(!result.settled);
+        assert_eq!(result.value, 7.0);
+        assert!(result.elapsed_ms >= 30, "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn on_target_steady_signal_settles_immediately() {
+        let policy = fast_policy(1000, 0.05).with_expected(10.0, 0.5);
+        let result = wait_for_settle(&policy, scripted(vec![10.2f32])).await.unwrap();
+
+        assert!(result.settled);
+        assert_eq!(result.samples, DEFAULT_SETTLE_STABLE_SAMPLES + 1);
+    }
+
+    #[tokio::test]
+    async fn unchanged_signal_without_expected_value_waits_the_minimum_dwell() {
+        let policy = SettlePolicy { min_dwell_ms: 20, ..fast_policy(1000, 0.05) };
+        let result = wait_for_settle(&policy, scripted(vec![7.0f32])).await.unwrap();
+
+        assert!(result.settled);
+        assert!(result.elapsed_ms >= 20, "{:?}", result);
+        assert!(result.samples > DEFAULT_SETTLE_STABLE_SAMPLES + 1);
+
+        let never = wait_for_settle(&SettlePolicy { min_dwell_ms: 2000, ..fast_policy(30, 0.05) }, scripted(vec![7.0f32])).await.unwrap();
+        assert!(!never.settled);
+    }
+
+    #[tokio::test]
+    async fn ramping_signal_never_settles() {
+        let ramp: Vec<f32> = (0..1000).map(|i| i as f32).collect();
+        let result = wait_for_settle(&fast_policy(30, 0.5), scripted(ramp)).await.unwrap();
+
+        assert!(!result.settled);
+        assert!(result.samples > 1);
+        assert_eq!(result.value, (result.samples - 1) as f32);
+        assert!(result.elapsed_ms >= 30);
+    }
+
+    #[tokio::test]
+    async fn noise_wider_than_the_band_prevents_settling() {
+        let noisy: Vec<f32> = (0..1000).map(|i| if i % 2 == 0 { 10.0 } else { 10.3 }).collect();
+
+        let strict = wait_for_settle(&fast_policy(30, 0.05), scripted(noisy.clone())).await.unwrap();
+        assert!(!strict.settled);
+
+        let tolerant = wait_for_settle(&fast_policy(1000, 0.5), scripted(noisy)).await.unwrap();
+        assert!(tolerant.settled);
+        assert_eq!(tolerant.samples, DEFAULT_SETTLE_STABLE_SAMPLES + 1);
+    }
+
+    #[tokio::test]
+    async fn state_wait_reports_when_the_state_was_confirmed() {
+        let policy = fast_policy(1000, 0.0);
+        let result = wait_for_state(&policy, scripted(vec![false, false, true, false, true]), |v| *v)
+            .await
+            .unwrap();
+
+        assert!(result.settled);
+        assert_eq!(result.samples, 7);
+        assert!(result.elapsed_ms >= 6 * policy.poll_interval_ms, "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn state_wait_times_out_on_a_stuck_signal() {
+        let result = wait_for_state(&fast_policy(20, 0.0), scripted(vec![false]), |v| *v).await.unwrap();
+
+        assert!(!result.settled);
+        assert!(!result.value);
+    }
+
+    #[tokio::test]
+    async fn read_errors_are_returned() {
+        let result = wait_for_settle(&fast_policy(1000, 0.05), || async {
+            Err::<f32, _>(AppError::plc_communication_error("连接已断开"))
+        })
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -10,6 +10,7 @@
 //! - **DIHardPointTestExecutor**: DI数字量输入硬点测试
 //! - **DOHardPointTestExecutor**: DO数字量输出硬点测试
 //! - **AIAlarmTestExecutor**: AI报警功能测试执行器
+//! - **CommunicationTestExecutor**: 通信模块点位读回测试执行器
 //!
 //! ## 设计模式
 //! - **策略模式**: 每个执行器实现相同的接口，可以动态选择
@@ -18,7 +19,7 @@
 //!
 //! ## 测试流程
 //! ```
-//! 预处理 → PLC写入 → 延时等待 → PLC读取 → 结果验证 → 后处理
+//! 预处理 → PLC写入 → 轮询等待稳定 → 结果验证 → 后处理
 //! ```
 //!
 //! ## Rust知识点
@@ -28,7 +29,9 @@
 
 use crate::models::{
     ChannelTestInstance, ChannelPointDefinition, RawTestOutcome, SubTestItem,
-    AnalogReadingPoint, DigitalTestStep, ModuleType, SubTestStatus, PointDataType
+    AnalogReadingPoint, DigitalTestStep, ModuleType, SubTestStatus, PointDataType,
+    AnalogTestSettings, AnalogAccuracyMetrics, AlarmRampConfig, CommunicationTestConfig,
+    AnalogCrosstalkReading, FailureKind
 };
 use crate::infrastructure::plc_communication::IPlcCommunicationService;
 use crate::utils::error::{AppError, AppResult};
@@ -41,6 +44,7 @@ use std::time::Duration;
 use tokio::time::sleep;
 use uuid::Uuid;
 use crate::domain::services::plc_comm_extension::PlcServiceLegacyExt;
+use super::signal_settling::{wait_for_settle, wait_for_state, record_settle_times, SettlePolicy, SettleRecord};
 
 /// 特定测试步骤执行器接口
 ///
@@ -68,20 +72,94 @@ pub trait ISpecificTestStepExecutor: Send + Sync {
     fn supports_definition(&self, definition: &ChannelPointDefinition) -> bool;
 }
 
+/// 从读数中按设定百分比提取标准百分比测试结果（0%, 25%, 50%, 75%, 100%）
+/// 自定义测试点方案中不包含的百分比返回 None
+fn extract_standard_percent_results(readings: &[AnalogReadingPoint]) -> [Option<f64>; 5] {
+    [0.0f32, 0.25, 0.5, 0.75, 1.0].map(|percentage| {
+        readings.iter()
+            .find(|r| (r.set_percentage - percentage).abs() < 1e-4)
+            .and_then(|r| r.actual_reading_eng.map(|v| v as f64))
+    })
+}
+
+/// 将本次模拟量测试使用的参数记录到测试结果详情中，便于报告追溯
+fn record_analog_settings(outcome: &mut RawTestOutcome, settings: &AnalogTestSettings) {
+    outcome.details.insert("test_points".to_string(), serde_json::json!(settings.test_points));
+    outcome.details.insert("tolerance".to_string(), serde_json::json!(settings.tolerance_description()));
+    outcome.details.insert("settle_timeout_ms".to_string(), serde_json::json!(settings.settle_time_ms));
+}
+
+/// 计算模拟量精度指标并写入测试结果详情（`accuracy` 键）
+/// 返回计算结果，读数不足时返回 None
+fn record_accuracy_metrics(
+    details: &mut HashMap<String, serde_json::Value>,
+    readings: &[AnalogReadingPoint],
+    range_span: f32,
+) -> Option<AnalogAccuracyMetrics> {
+    let metrics = AnalogAccuracyMetrics::compute(readings, range_span)?;
+    details.insert("accuracy".to_string(), serde_json::json!(metrics));
+    Some(metrics)
+}
+
+/// AI串扰检查的邻近通道（同一模块内的其他AI通道）
+#[derive(Debug, Clone)]
+pub struct CrosstalkNeighbor {
+    /// 点位位号
+    pub tag: String,
+    /// 模块内通道号
+    pub channel_tag_in_module: String,
+    /// 被测PLC通信地址
+    pub address: String,
+    /// 量程下限
+    pub range_low: f32,
+    /// 量程上限
+    pub range_high: f32,
+}
+
+impl CrosstalkNeighbor {
+    pub fn from_definition(definition: &ChannelPointDefinition) -> Self {
+        Self {
+            tag: definition.tag.clone(),
+            channel_tag_in_module: definition.channel_tag_in_module.clone(),
+            address: definition.plc_communication_address.clone(),
+            range_low: definition.range_low_limit.unwrap_or(0.0),
+            range_high: definition.range_high_limit.unwrap_or(100.0),
+        }
+    }
+}
+
 /// AI点硬点百分比测试执行器
-/// 负责AI点的硬接线测试，包括0%, 25%, 50%, 75%, 100%的多点测试
+/// 负责AI点的硬接线测试，按测试方案执行多点测试（默认0%, 25%, 50%, 75%, 100%）
+/// 测试方案配置了串扰阈值且提供了邻近通道时，多点测试后追加串扰检查
 pub struct AIHardPointPercentExecutor {
     /// 测试步骤执行器ID
     pub id: String,
+    /// 模拟量测试参数（测试点、容差、稳定时间）
+    pub settings: AnalogTestSettings,
+    /// 串扰检查的邻近通道
+    pub crosstalk_neighbors: Vec<CrosstalkNeighbor>,
 }
 
 impl AIHardPointPercentExecutor {
     pub fn new() -> Self {
+        Self::with_settings(AnalogTestSettings::default())
+    }
+
+    /// 使用指定测试参数创建执行器
+    pub fn with_settings(settings: AnalogTestSettings) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
+            settings,
+            crosstalk_neighbors: Vec::new(),
         }
     }
 
+    /// 指定串扰检查的邻近通道
+    pub fn with_crosstalk_neighbors(mut self, neighbors: Vec<CrosstalkNeighbor>) -> Self {
+        self.crosstalk_neighbors = neighbors;
+        self
+    }
+
     /// 获取通道对应的真实测试台架地址
     /// 从通道实例中获取已分配的测试PLC通道地址
     fn get_test_rig_address_for_channel(&self, instance: &ChannelTestInstance) -> AppResult<String> {
@@ -179,8 +257,112 @@ impl AIHardPointPercentExecutor {
         Ok(())
     }
 
+    /// AI串扰检查
+    ///
+    /// 被测通道先输出0%读取同模块其他AI通道基线，再输出100%读取偏移，
+    /// 偏移超过阈值（占邻近通道量程百分比）的通道判定为串扰（常见于共地、屏蔽层故障）
+    async fn execute_crosstalk_check(
+        &self,
+        instance: &ChannelTestInstance,
+        definition: &ChannelPointDefinition,
+        test_rig_conn_id: &str,
+        target_conn_id: &str,
+        test_rig_plc: &Arc<dyn IPlcCommunicationService>,
+        target_plc: &Arc<dyn IPlcCommunicationService>,
+        threshold_percent: f32,
+    ) -> AppResult<Vec<AnalogCrosstalkReading>> {
+        let test_rig_address = self.get_test_rig_address_for_channel(instance)?;
+        let range_lower = definition.range_low_limit.unwrap_or(0.0);
+        let range_span = definition.range_high_limit.unwrap_or(100.0) - range_lower;
+        let settle_policy = SettlePolicy::with_timeout(self.settings.settle_time_ms)
+            .with_band(self.settings.settle_band(range_span));
+        info!("🔀 串扰检查: {} 邻近通道 {} 个, 阈值 {}%", definition.tag, self.crosstalk_neighbors.len(), threshold_percent);
+
+        let drive_and_read = |percentage: f32| {
+            let test_rig_address = test_rig_address.clone();
+            let settle_policy = settle_policy.clone();
+            async move {
+                test_rig_plc.write_float32_by_id(test_rig_conn_id, &test_rig_address, percentage * 100.0).await
+                    .map_err(|e| e.with_context("设置测试台架输出失败"))?;
+                // 等待被测通道自身稳定后再读取邻近通道
+                wait_for_settle(
+                    &settle_policy,
+                    || target_plc.read_float32_by_id(target_conn_id, &definition.plc_communication_address),
+                ).await?;
+                self.read_crosstalk_neighbors(target_conn_id, target_plc).await
+            }
+        };
+
+        let baseline = drive_and_read(0.0).await?;
+        let driven = drive_and_read(1.0).await?;
+
+        // 任一邻近通道读取失败即视为检查未完成，不能把读不到的通道当作无串扰
+        let unread: Vec<&str> = self.crosstalk_neighbors
+            .iter()
+            .filter(|n| !baseline.contains_key(&n.address) || !driven.contains_key(&n.address))
+            .map(|n| n.tag.as_str())
+            .collect();
+        if !unread.is_empty() {
+            return Err(AppError::plc_communication_error(format!("读取邻近通道失败: {}", unread.join(", "))));
+        }
+
+        let readings: Vec<AnalogCrosstalkReading> = self.crosstalk_neighbors
+            .iter()
+            .filter_map(|neighbor| {
+                let (Some(before), Some(after)) = (baseline.get(&neighbor.address), driven.get(&neighbor.address)) else {
+                    return None;
+                };
+                let span = neighbor.range_high - neighbor.range_low;
+                let shift_percent = if span > 0.0 { (after - before).abs() / span * 100.0 } else { 0.0 };
+                Some(AnalogCrosstalkReading {
+                    tag: neighbor.tag.clone(),
+                    channel_tag_in_module: neighbor.channel_tag_in_module.clone(),
+                    baseline_value: *before,
+                    driven_value: *after,
+                    shift_percent,
+                    flagged: shift_percent > threshold_percent,
+                })
+            })
+            .collect();
+
+        for reading in readings.iter().filter(|r| r.flagged) {
+            warn!("❌ 串扰: {} 驱动100%时 {} 偏移 {:.3}%", definition.tag, reading.tag, reading.shift_percent);
+        }
+        Ok(readings)
+    }
+
+    /// 批量读取串扰检查的邻近通道，返回 通信地址 → 读数
+    async fn read_crosstalk_neighbors(
+        &self,
+        target_conn_id: &str,
+        target_plc: &Arc<dyn IPlcCommunicationService>,
+    ) -> AppResult<HashMap<String, f32>> {
+        use crate::domain::services::plc_communication_service::{PlcDataType, PlcValue, ReadRequest};
+
+        let handle = target_plc.default_handle_by_id(target_conn_id).await
+            .ok_or_else(|| AppError::plc_communication_error(format!("PLC连接未建立: {}", target_conn_id)))?;
+        let requests: Vec<ReadRequest> = self.crosstalk_neighbors
+            .iter()
+            .map(|n| ReadRequest {
+                id: n.address.clone(),
+                address: n.address.clone(),
+                data_type: PlcDataType::Float32,
+                array_length: None,
+            })
+            .collect();
+        let results = target_plc.batch_read(&handle, &requests).await?;
+
+        Ok(results
+            .into_iter()
+            .filter_map(|r| match r.value {
+                Some(PlcValue::Float32(v)) if r.success => Some((r.request_id, v)),
+                _ => None,
+            })
+            .collect())
+    }
+
     /// 执行AI点的完整硬点测试流程
-    /// 包括多点测试、线性度检查、报警功能验证等
+    /// 包括多点测试、线性度检查、串扰检查、报警功能验证等
     async fn execute_complete_ai_hardpoint_test(
         &self,
         instance: &ChannelTestInstance,
@@ -191,7 +373,8 @@ impl AIHardPointPercentExecutor {
         target_plc: Arc<dyn IPlcCommunicationService>,
     ) -> Result<RawTestOutcome, AppError> {
         let mut readings = Vec::new();
-        let test_percentages = vec![0.0, 0.25, 0.5, 0.75, 1.0];
+        let test_percentages = self.settings.sweep_points();
+        let mut settle_records = Vec::new();
 
         let is_ai_test = matches!(definition.module_type, ModuleType::AI | ModuleType::AINone);
         let test_type = if is_ai_test { "AI硬点测试" } else { "AO硬点测试" };
@@ -203,15 +386,17 @@ impl AIHardPointPercentExecutor {
         let range_span = range_upper - range_lower;
 
         if range_span <= 0.0 {
-            return Ok(RawTestOutcome::failure(
+            return Ok(RawTestOutcome::configuration_failure(
                 instance.instance_id.clone(),
                 SubTestItem::HardPoint,
                 "AI点量程配置无效".to_string(),
             ));
         }
+        let settle_policy = SettlePolicy::with_timeout(self.settings.settle_time_ms)
+            .with_band(self.settings.settle_band(range_span));
 
         // 执行多点测试
-        for percentage in test_percentages {
+        for (step, percentage) in test_percentages.into_iter().enumerate() {
             let eng_value = range_lower + (range_span * percentage);
 
             // 获取真实的测试台架地址
@@ -225,45 +410,44 @@ impl AIHardPointPercentExecutor {
                     "变量:{}, 写[{}] 失败: {}",
                     definition.tag, test_rig_address, e
                 );
-                return Err(AppError::plc_communication_error(format!("设置测试台架输出失败: {}", e)));
+                return Err(e.with_context("设置测试台架输出失败"));
             }
 
-            // 等待信号稳定时间 - 调整为2秒
-            tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
-
-            // 读取被测PLC的实际值
+            // 轮询等待被测PLC读数稳定在期望值附近，超时后仍按最后读数判定是否合格
             info!(
-                "变量:{}, 读[{}] 请求开始",
+                "变量:{}, 读[{}] 等待稳定",
                 definition.tag, definition.plc_communication_address
             );
-            let actual_raw = match target_plc
-                .read_float32_by_id(target_conn_id, &definition.plc_communication_address)
-                .await
+            let step_policy = settle_policy.clone().with_expected(eng_value as f64, self.settings.tolerance_band(range_span));
+            let settle = match wait_for_settle(
+                &step_policy,
+                || target_plc.read_float32_by_id(target_conn_id, &definition.plc_communication_address),
+            )
+            .await
             {
-                Ok(v) => {
-                    info!(
-                        "变量:{}, 读[{}]={:.2}",
-                        definition.tag, definition.plc_communication_address, v
-                    );
-                    v
-                }
+                Ok(settle) => settle,
                 Err(e) => {
                     error!(
                         "变量:{}, 读[{}] 失败: {}",
                         definition.tag, definition.plc_communication_address, e
                     );
-                    return Err(AppError::plc_communication_error(format!(
-                        "读取被测PLC值失败: {}",
-                        e
-                    )));
+                    return Err(e.with_context("读取被测PLC值失败"));
                 }
             };
+            let actual_raw = settle.value;
+            info!(
+                "变量:{}, 读[{}]={:.2} (稳定耗时{}ms{})",
+                definition.tag, definition.plc_communication_address, actual_raw,
+                settle.elapsed_ms, if settle.settled { "" } else { ", 超时" }
+            );
+            let direction = if step < self.settings.test_points.len() { "" } else { "↓" };
+            settle_records.push(SettleRecord::new(format!("{}%{}", percentage * 100.0, direction), &settle));
 
                // 计算误差
             let error_percentage = Some(((actual_raw - eng_value).abs() / (range_upper - range_lower)) * 100.0);
 
-            // 判断测试状态（误差容忍度3%）
-                let test_status = if error_percentage.unwrap_or(100.0) <= 3.0 {
+            // 判断测试状态（按测试方案容差）
+                let test_status = if self.settings.is_within_tolerance(eng_value, actual_raw, range_span) {
                     SubTestStatus::Passed
                 } else {
                     SubTestStatus::Failed
@@ -292,18 +476,47 @@ impl AIHardPointPercentExecutor {
                 }
         }
 
+        // 串扰检查（可选）
+        // 检查本身出错时保留已完成的多点测试结果，串扰检查记为异常并判定不通过
+        let mut crosstalk = None;
+        let mut crosstalk_error = None;
+        if let Some(threshold) = self.settings.crosstalk_threshold_percent {
+            if is_ai_test && !self.crosstalk_neighbors.is_empty() {
+                match self.execute_crosstalk_check(
+                    instance, definition, test_rig_conn_id, target_conn_id, &test_rig_plc, &target_plc, threshold,
+                ).await {
+                    Ok(neighbors) => crosstalk = Some((threshold, neighbors)),
+                    Err(e) => {
+                        warn!("⚠️ 串扰检查异常: {} - {}", definition.tag, e);
+                        crosstalk_error = Some((threshold, e));
+                    }
+                }
+            }
+        }
+        let crosstalk_flagged: Vec<&AnalogCrosstalkReading> = crosstalk
+            .iter()
+            .flat_map(|(_, readings)| readings.iter().filter(|r| r.flagged))
+            .collect();
+
         // 检查是否有任何测试点失败
         let has_failed_tests = readings.iter().any(|r| r.status == SubTestStatus::Failed);
-        let overall_success = !has_failed_tests;
-
-        // 检查线性度（可选的高级检查）
-        let linearity_check = self.check_linearity(&readings);
+        let overall_success = !has_failed_tests && crosstalk_flagged.is_empty() && crosstalk_error.is_none();
 
-        let status_msg = if overall_success {
-            "AI硬点5点测试全部通过"
+        let mut status_msg = if !has_failed_tests {
+            format!("AI硬点{}点测试全部通过", readings.len())
         } else {
-            "AI硬点测试部分失败"
+            format!("AI硬点测试部分失败 (容差{})", self.settings.tolerance_description())
         };
+        if !crosstalk_flagged.is_empty() {
+            let neighbors: Vec<String> = crosstalk_flagged
+                .iter()
+                .map(|r| format!("{} 偏移{:.2}%", r.tag, r.shift_percent))
+                .collect();
+            status_msg.push_str(&format!("; 串扰: {}", neighbors.join(", ")));
+        }
+        if let Some((_, e)) = &crosstalk_error {
+            status_msg.push_str(&format!("; 串扰检查未完成: {}", e));
+        }
 
         // 🔧 精简日志：只保留最终结果
         debug!("✅ 结果: {} - {}",
@@ -331,20 +544,17 @@ impl AIHardPointPercentExecutor {
 
         // 提取百分比测试结果 - 存储实际工程量 (转换f32到f64)
         // 🔧 关键修复：无论测试成功还是失败，都要保存过程数据
-        let test_result_0_percent = readings.get(0).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        let test_result_25_percent = readings.get(1).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        let test_result_50_percent = readings.get(2).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        let test_result_75_percent = readings.get(3).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        let test_result_100_percent = readings.get(4).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
+        let [test_result_0_percent, test_result_25_percent, test_result_50_percent, test_result_75_percent, test_result_100_percent] =
+            extract_standard_percent_results(&readings);
 
         // 返回测试结果（成功或失败都包含完整的过程数据）
-        Ok(RawTestOutcome {
+        let mut outcome = RawTestOutcome {
             channel_instance_id: instance.instance_id.clone(),
             sub_test_item: SubTestItem::HardPoint,
             success: overall_success,
             raw_value_read: Some("多点测试".to_string()),
             eng_value_calculated: Some(format!("{:.2}-{:.2}", range_lower, range_upper)),
-            message: Some(status_msg.to_string()),
+            message: Some(status_msg),
             start_time: Utc::now(),
             end_time: Utc::now(),
             readings: Some(readings),
@@ -355,31 +565,30 @@ impl AIHardPointPercentExecutor {
             test_result_75_percent,
             test_result_100_percent,
             details: HashMap::new(),
-        })
-    }
-
-    /// 检查线性度
-    fn check_linearity(&self, readings: &[AnalogReadingPoint]) -> bool {
-        if readings.len() < 3 {
-            return true; // 数据点太少，无法检查线性度
-        }
-
-        // 简单的线性度检查：计算R²
-        let n = readings.len() as f32;
-        let sum_x: f32 = readings.iter().map(|r| r.set_percentage).sum();
-        let sum_y: f32 = readings.iter().map(|r| r.actual_reading_raw.unwrap_or(0.0)).sum();
-        let sum_xy: f32 = readings.iter().map(|r| r.set_percentage * r.actual_reading_raw.unwrap_or(0.0)).sum();
-        let sum_x2: f32 = readings.iter().map(|r| r.set_percentage * r.set_percentage).sum();
-
-        let r_squared = if n * sum_x2 - sum_x * sum_x != 0.0 {
-            let correlation = (n * sum_xy - sum_x * sum_y) /
-                ((n * sum_x2 - sum_x * sum_x).sqrt() * (n * sum_y.powi(2) - sum_y * sum_y).sqrt());
-            correlation * correlation
-        } else {
-            0.0
+            attempt: 1,
+            retry_pending: false,
+            failure_kind: (!overall_success).then_some(FailureKind::Signal),
         };
+        record_analog_settings(&mut outcome, &self.settings);
+        record_settle_times(&mut outcome.details, &settle_records);
+        if let Some(metrics) = record_accuracy_metrics(&mut outcome.details, outcome.readings.as_deref().unwrap_or(&[]), range_span) {
+            info!("📐 精度指标: {} - {}", definition.tag, metrics.summary());
+        }
+        if let Some((threshold, neighbors)) = &crosstalk {
+            outcome.details.insert("crosstalk".to_string(), serde_json::json!({
+                "threshold_percent": threshold,
+                "neighbors": neighbors,
+            }));
+        }
+        if let Some((threshold, e)) = &crosstalk_error {
+            outcome.details.insert("crosstalk".to_string(), serde_json::json!({
+                "threshold_percent": threshold,
+                "error": e.to_string(),
+            }));
+            outcome.failure_kind = Some(e.failure_kind());
+        }
 
-        r_squared >= 0.95 // 线性度要求R²≥0.95
+        Ok(outcome)
     }
 }
 
@@ -417,16 +626,27 @@ impl ISpecificTestStepExecutor for AIHardPointPercentExecutor {
     }
 }
 
+/// AI报警测试模式
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlarmTestMode {
+    /// 写入报警设定值后读取反馈位（仅验证反馈位可读写）
+    SetpointFeedback,
+    /// 通过测试PLC AO逐步改变输入信号穿越设定值，搜索实际触发点与恢复点
+    TripPointRamp(AlarmRampConfig),
+}
+
 /// AI报警测试执行器
 ///
 /// 执行AI点某个报警项的测试（如设置高报触发条件，验证报警是否产生）
 pub struct AIAlarmTestExecutor {
     /// 报警类型
     pub alarm_type: SubTestItem,
-    /// 触发延时 (毫秒)
+    /// 等待报警触发的上限时间 (毫秒)
     pub trigger_delay_ms: u64,
-    /// 复位延时 (毫秒)
+    /// 等待报警复位的上限时间 (毫秒)
     pub reset_delay_ms: u64,
+    /// 测试模式
+    pub mode: AlarmTestMode,
 }
 
 impl AIAlarmTestExecutor {
@@ -436,67 +656,33 @@ impl AIAlarmTestExecutor {
             alarm_type,
             trigger_delay_ms,
             reset_delay_ms,
+            mode: AlarmTestMode::SetpointFeedback,
         }
     }
 
-    /// 获取报警设定值和反馈地址
-    fn get_alarm_config(&self, definition: &ChannelPointDefinition) -> AppResult<(f32, String, String)> {
-        match self.alarm_type {
-            SubTestItem::LowLowAlarm => {
-                let set_value = definition.sll_set_value.ok_or_else(||
-                    AppError::validation_error("未配置低低报设定值"))?;
-                let set_address = definition.sll_set_point_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置低低报设定地址"))?;
-                let feedback_address = definition.sll_feedback_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置低低报反馈地址"))?;
-                Ok((set_value, set_address.clone(), feedback_address.clone()))
-            },
-            SubTestItem::LowAlarm => {
-                let set_value = definition.sl_set_value.ok_or_else(||
-                    AppError::validation_error("未配置低报设定值"))?;
-                let set_address = definition.sl_set_point_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置低报设定地址"))?;
-                let feedback_address = definition.sl_feedback_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置低报反馈地址"))?;
-                Ok((set_value, set_address.clone(), feedback_address.clone()))
-            },
-            SubTestItem::HighAlarm => {
-                let set_value = definition.sh_set_value.ok_or_else(||
-                    AppError::validation_error("未配置高报设定值"))?;
-                let set_address = definition.sh_set_point_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置高报设定地址"))?;
-                let feedback_address = definition.sh_feedback_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置高报反馈地址"))?;
-                Ok((set_value, set_address.clone(), feedback_address.clone()))
-            },
-            SubTestItem::HighHighAlarm => {
-                let set_value = definition.shh_set_value.ok_or_else(||
-                    AppError::validation_error("未配置高高报设定值"))?;
-                let set_address = definition.shh_set_point_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置高高报设定地址"))?;
-                let feedback_address = definition.shh_feedback_address.as_ref().ok_or_else(||
-                    AppError::validation_error("未配置高高报反馈地址"))?;
-                Ok((set_value, set_address.clone(), feedback_address.clone()))
-            },
-            _ => Err(AppError::validation_error(
-                format!("不支持的报警类型: {:?}", self.alarm_type)
-            ))
+    /// 创建触发点搜索模式的AI报警测试执行器
+    pub fn with_trip_point_ramp(alarm_type: SubTestItem, config: AlarmRampConfig) -> Self {
+        Self {
+            alarm_type,
+            trigger_delay_ms: config.dwell_ms,
+            reset_delay_ms: config.reset_delay_ms,
+            mode: AlarmTestMode::TripPointRamp(config),
         }
     }
-}
 
-#[async_trait]
-impl ISpecificTestStepExecutor for AIAlarmTestExecutor {
-    async fn execute(
+    /// 高报/高高报为上行触发，低报/低低报为下行触发
+    fn is_high_alarm(&self) -> bool {
+        matches!(self.alarm_type, SubTestItem::HighAlarm | SubTestItem::HighHighAlarm)
+    }
+
+    /// 设定值反馈模式：写入报警设定值后读取反馈位，再写入安全值确认复位
+    async fn execute_setpoint_feedback(
         &self,
         instance: &ChannelTestInstance,
         definition: &ChannelPointDefinition,
-        test_rig_conn_id: &str,
         target_conn_id: &str,
-        plc_service_test_rig: Arc<dyn IPlcCommunicationService>,
         plc_service_target: Arc<dyn IPlcCommunicationService>,
     ) -> AppResult<RawTestOutcome> {
-        // 🔧 精简日志：移除详细调试信息
         let (alarm_set_value, set_address, feedback_address) = self.get_alarm_config(definition)?;
         let start_time = Utc::now();
 
@@ -505,12 +691,15 @@ impl ISpecificTestStepExecutor for AIAlarmTestExecutor {
 
         crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_float32(&plc_service_target, &set_address, alarm_set_value).await?;
 
-        // 步骤2: 等待报警触发 - 统一设置为3秒
-        tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
-
-        // 步骤3: 读取报警反馈状态
+        // 步骤2/3: 轮询读取报警反馈，直到报警稳定激活或达到触发等待上限
         info!("📖 读取报警反馈 [{}]", feedback_address);
-        let alarm_active = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, &feedback_address).await?;
+        let trigger_settle = wait_for_state(
+            &SettlePolicy::with_timeout(self.trigger_delay_ms),
+            || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, &feedback_address),
+            |active| *active,
+        ).await?;
+        let alarm_active = trigger_settle.value;
+        let mut settle_records = vec![SettleRecord::new("触发", &trigger_settle)];
 
         // 步骤4: 复位报警（设置安全值）
         let safe_value = match self.alarm_type {
@@ -529,12 +718,15 @@ impl ISpecificTestStepExecutor for AIAlarmTestExecutor {
               set_address, safe_value);
         crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_float32(&plc_service_target, &set_address, safe_value).await?;
 
-        // 步骤5: 等待报警复位 - 统一设置为3秒
-        tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
-
-        // 步骤6: 确认报警已复位
+        // 步骤5/6: 轮询读取报警反馈，直到报警稳定复位或达到复位等待上限
         info!("📖 读取报警复位状态 [{}]", feedback_address);
-        let alarm_reset = !crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, &feedback_address).await?;
+        let reset_settle = wait_for_state(
+            &SettlePolicy::with_timeout(self.reset_delay_ms),
+            || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, &feedback_address),
+            |active| !*active,
+        ).await?;
+        let alarm_reset = !reset_settle.value;
+        settle_records.push(SettleRecord::new("复位", &reset_settle));
 
         let end_time = Utc::now();
 
@@ -554,15 +746,238 @@ impl ISpecificTestStepExecutor for AIAlarmTestExecutor {
             is_success,
         );
 
+        outcome.failure_kind = (!is_success).then_some(FailureKind::Signal);
         outcome.message = Some(message);
         outcome.start_time = start_time;
         outcome.end_time = end_time;
         outcome.raw_value_read = Some(if alarm_active { "1" } else { "0" }.to_string());
         outcome.eng_value_calculated = Some(alarm_set_value.to_string());
+        record_settle_times(&mut outcome.details, &settle_records);
 
         Ok(outcome)
     }
 
+    /// 触发点搜索模式：以测试PLC AO逐步改变输入信号，搜索实际报警触发点与恢复点
+    ///
+    /// 流程：
+    /// 1. 确认被测PLC报警设定值为点表配置值
+    /// 2. 输入信号置于非报警侧起点（设定值 ∓ 搜索范围），确认报警未激活
+    /// 3. 按步长向报警方向推进，首次读到反馈位激活的输入值即为触发点
+    /// 4. 从触发点反向推进，首次读到反馈位复位的输入值即为恢复点
+    /// 5. 触发点与设定值偏差在允许范围内判定通过，触发点与恢复点之差即为死区
+    async fn execute_trip_point_ramp(
+        &self,
+        instance: &ChannelTestInstance,
+        definition: &ChannelPointDefinition,
+        config: &AlarmRampConfig,
+        test_rig_conn_id: &str,
+        target_conn_id: &str,
+        plc_service_test_rig: Arc<dyn IPlcCommunicationService>,
+        plc_service_target: Arc<dyn IPlcCommunicationService>,
+    ) -> AppResult<RawTestOutcome> {
+        let (alarm_set_value, set_address, feedback_address) = self.get_alarm_config(definition)?;
+        config.validate().map_err(AppError::validation_error)?;
+        let test_rig_address = instance.test_plc_communication_address.clone()
+            .ok_or_else(|| AppError::validation_error("测试实例未分配测试PLC通道地址"))?;
+
+        let range_lower = definition.range_low_limit.unwrap_or(0.0);
+        let range_upper = definition.range_high_limit.unwrap_or(100.0);
+        let range_span = range_upper - range_lower;
+        if range_span <= 0.0 {
+            return Err(AppError::validation_error(format!("无效的量程范围: {} - {}", range_lower, range_upper)));
+        }
+
+        let start_time = Utc::now();
+        let direction: f32 = if self.is_high_alarm() { 1.0 } else { -1.0 };
+        let step = range_span * config.step_percent / 100.0;
+        let margin = range_span * config.search_margin_percent / 100.0;
+        let clamp = |v: f32| v.max(range_lower).min(range_upper);
+        let ramp_start = clamp(alarm_set_value - direction * margin);
+        let ramp_end = clamp(alarm_set_value + direction * margin);
+        let dwell = Duration::from_millis(config.dwell_ms);
+
+        // 测试PLC AO以量程百分比(0-100)输出
+        let write_input = |value: f32| {
+            let percent = (value - range_lower) / range_span * 100.0;
+            let plc = plc_service_test_rig.clone();
+            let address = test_rig_address.clone();
+            let conn_id = test_rig_conn_id.to_string();
+            async move {
+                plc.write_float32_by_id(&conn_id, &address, percent).await
+                    .map_err(|e| e.with_context("设置测试PLC AO输出失败"))
+            }
+        };
+        let read_feedback = || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, &feedback_address);
+
+        info!("🔍 报警触发点搜索 {:?}: {} 设定值={:.3}, 搜索区间 {:.3} → {:.3}, 步长 {:.4}",
+              self.alarm_type, definition.tag, alarm_set_value, ramp_start, ramp_end, step);
+
+        // 步骤1: 确认被测PLC报警设定值
+        plc_service_target.write_float32_by_id(target_conn_id, &set_address, alarm_set_value).await
+            .map_err(|e| e.with_context("写入报警设定值失败"))?;
+
+        // 步骤2~4 期间测试PLC AO处于非零输出，任何出口都要先复位再返回
+        let searched = async {
+            // 步骤2: 输入置于非报警侧起点，等待报警复位
+            write_input(ramp_start).await?;
+            let initial = wait_for_state(&SettlePolicy::with_timeout(self.reset_delay_ms), read_feedback, |active| !*active).await?;
+
+            let mut trip_point: Option<f32> = None;
+            let mut clear_point: Option<f32> = None;
+
+            if initial.settled {
+                // 步骤3: 向报警方向推进，搜索触发点
+                let mut value = ramp_start;
+                while (ramp_end - value) * direction > 0.0 {
+                    value = clamp(value + direction * step);
+                    write_input(value).await?;
+                    sleep(dwell).await;
+                    if read_feedback().await? {
+                        trip_point = Some(value);
+                        break;
+                    }
+                }
+
+                // 步骤4: 从触发点反向推进，搜索恢复点
+                if let Some(trip) = trip_point {
+                    let mut value = trip;
+                    while (value - ramp_start) * direction > 0.0 {
+                        value = clamp(value - direction * step);
+                        write_input(value).await?;
+                        sleep(dwell).await;
+                        if !read_feedback().await? {
+                            clear_point = Some(value);
+                            break;
+                        }
+                    }
+                }
+            }
+            AppResult::Ok((initial.settled, trip_point, clear_point))
+        }
+        .await;
+
+        // 测试完成（或中途出错）后复位测试PLC输出为0%
+        if let Err(e) = plc_service_test_rig.write_float32_by_id(test_rig_conn_id, &test_rig_address, 0.0).await {
+            warn!("⚠️ 测试PLC复位失败: {}", e);
+        }
+        let (initial_settled, trip_point, clear_point) = searched?;
+
+        let end_time = Utc::now();
+        let trip_error_percent = trip_point.map(|trip| (trip - alarm_set_value).abs() / range_span * 100.0);
+        let deadband = trip_point.zip(clear_point).map(|(trip, clear)| (trip - clear).abs());
+
+        let (is_success, message) = match (initial_settled, trip_point, clear_point) {
+            (false, _, _) => (false, format!("报警测试 {:?}: 输入位于非报警侧起点 {:.3} 时报警仍激活", self.alarm_type, ramp_start)),
+            (true, None, _) => (false, format!("报警测试 {:?}: 在 {:.3} → {:.3} 区间内未找到触发点", self.alarm_type, ramp_start, ramp_end)),
+            (true, Some(trip), None) => (false, format!("报警测试 {:?}: 触发点={:.3}, 反向回到 {:.3} 仍未复位", self.alarm_type, trip, ramp_start)),
+            (true, Some(trip), Some(clear)) => {
+                let error = trip_error_percent.unwrap_or_default();
+                let passed = error <= config.trip_tolerance_percent;
+                (passed, format!("报警测试 {:?}: 设定值={:.3}, 触发点={:.3}, 恢复点={:.3}, 死区={:.3}, 偏差={:.3}% (允许±{}%) - {}",
+                                 self.alarm_type, alarm_set_value, trip, clear, (trip - clear).abs(), error,
+                                 config.trip_tolerance_percent, if passed { "通过" } else { "失败" }))
+            }
+        };
+        let status_icon = if is_success { "✅" } else { "❌" };
+        info!("{} {}", status_icon, message);
+
+        let mut outcome = if is_success {
+            RawTestOutcome::success(instance.instance_id.clone(), self.alarm_type.clone())
+        } else {
+            RawTestOutcome::failure(instance.instance_id.clone(), self.alarm_type.clone(), message.clone())
+        };
+        outcome.message = Some(message);
+        outcome.start_time = start_time;
+        outcome.end_time = end_time;
+        outcome.raw_value_read = trip_point.map(|v| format!("{:.3}", v));
+        outcome.eng_value_calculated = Some(alarm_set_value.to_string());
+        outcome.details.insert("mode".to_string(), serde_json::json!("trip_point_ramp"));
+        outcome.details.insert("setpoint".to_string(), serde_json::json!(alarm_set_value));
+        outcome.details.insert("trip_point".to_string(), serde_json::json!(trip_point));
+        outcome.details.insert("clear_point".to_string(), serde_json::json!(clear_point));
+        outcome.details.insert("deadband".to_string(), serde_json::json!(deadband));
+        outcome.details.insert("trip_error_percent".to_string(), serde_json::json!(trip_error_percent));
+        outcome.details.insert("step".to_string(), serde_json::json!(step));
+        outcome.details.insert("trip_tolerance_percent".to_string(), serde_json::json!(config.trip_tolerance_percent));
+
+        Ok(outcome)
+    }
+
+    /// 获取报警设定值和反馈地址
+    fn get_alarm_config(&self, definition: &ChannelPointDefinition) -> AppResult<(f32, String, String)> {
+        match self.alarm_type {
+            SubTestItem::LowLowAlarm => {
+                let set_value = definition.sll_set_value.ok_or_else(||
+                    AppError::validation_error("未配置低低报设定值"))?;
+                let set_address = definition.sll_set_point_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置低低报设定地址"))?;
+                let feedback_address = definition.sll_feedback_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置低低报反馈地址"))?;
+                Ok((set_value, set_address.clone(), feedback_address.clone()))
+            },
+            SubTestItem::LowAlarm => {
+                let set_value = definition.sl_set_value.ok_or_else(||
+                    AppError::validation_error("未配置低报设定值"))?;
+                let set_address = definition.sl_set_point_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置低报设定地址"))?;
+                let feedback_address = definition.sl_feedback_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置低报反馈地址"))?;
+                Ok((set_value, set_address.clone(), feedback_address.clone()))
+            },
+            SubTestItem::HighAlarm => {
+                let set_value = definition.sh_set_value.ok_or_else(||
+                    AppError::validation_error("未配置高报设定值"))?;
+                let set_address = definition.sh_set_point_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置高报设定地址"))?;
+                let feedback_address = definition.sh_feedback_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置高报反馈地址"))?;
+                Ok((set_value, set_address.clone(), feedback_address.clone()))
+            },
+            SubTestItem::HighHighAlarm => {
+                let set_value = definition.shh_set_value.ok_or_else(||
+                    AppError::validation_error("未配置高高报设定值"))?;
+                let set_address = definition.shh_set_point_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置高高报设定地址"))?;
+                let feedback_address = definition.shh_feedback_address.as_ref().ok_or_else(||
+                    AppError::validation_error("未配置高高报反馈地址"))?;
+                Ok((set_value, set_address.clone(), feedback_address.clone()))
+            },
+            _ => Err(AppError::validation_error(
+                format!("不支持的报警类型: {:?}", self.alarm_type)
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl ISpecificTestStepExecutor for AIAlarmTestExecutor {
+    async fn execute(
+        &self,
+        instance: &ChannelTestInstance,
+        definition: &ChannelPointDefinition,
+        test_rig_conn_id: &str,
+        target_conn_id: &str,
+        plc_service_test_rig: Arc<dyn IPlcCommunicationService>,
+        plc_service_target: Arc<dyn IPlcCommunicationService>,
+    ) -> AppResult<RawTestOutcome> {
+        match &self.mode {
+            AlarmTestMode::SetpointFeedback => {
+                self.execute_setpoint_feedback(instance, definition, target_conn_id, plc_service_target).await
+            }
+            AlarmTestMode::TripPointRamp(config) => {
+                self.execute_trip_point_ramp(
+                    instance,
+                    definition,
+                    config,
+                    test_rig_conn_id,
+                    target_conn_id,
+                    plc_service_test_rig,
+                    plc_service_target,
+                ).await
+            }
+        }
+    }
+
     fn item_type(&self) -> SubTestItem {
         self.alarm_type.clone()
     }
@@ -593,7 +1008,7 @@ impl ISpecificTestStepExecutor for AIAlarmTestExecutor {
 /// 4. 等待信号稳定
 /// 5. 测试PLC DO通道输出低电平 → 检查被测PLC DI通道显示"断开"
 pub struct DIHardPointTestExecutor {
-    /// 测试步骤间隔时间 (毫秒)
+    /// 每个步骤等待信号稳定的上限时间 (毫秒)
     pub step_interval_ms: u64,
 }
 
@@ -634,19 +1049,26 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
 
         // 创建数字量测试步骤记录
         let mut digital_steps = Vec::new();
+        let settle_policy = SettlePolicy::with_timeout(self.step_interval_ms);
+        let mut settle_records = Vec::new();
 
         // 步骤1: 测试PLC DO输出低电平
         info!("变量:{}, 写[{}]=false", definition.tag, test_rig_do_address);
         crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_do_address, false).await
-            .map_err(|e| AppError::plc_communication_error(format!("设置测试PLC DO低电平失败: {}", e)))?;
-
-        // 等待信号稳定
-        tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
+            .map_err(|e| e.with_context("设置测试PLC DO低电平失败"))?;
 
         // 步骤2: 检查被测PLC DI是否显示"断开"
-        let di_state_1 = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, target_di_address).await
-            .map_err(|e| AppError::plc_communication_error(format!("读取被测PLC DI状态失败: {}", e)))?;
-        info!("变量:{}, 读[{}]={}", definition.tag, target_di_address, di_state_1);
+        // 轮询等待被测PLC DI状态跟随DO输出并保持稳定
+        let settle = wait_for_state(
+            &settle_policy,
+            || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, target_di_address),
+            |v| !*v,
+        ).await
+            .map_err(|e| e.with_context("读取被测PLC DI状态失败"))?;
+        let di_state_1 = settle.value;
+        info!("变量:{}, 读[{}]={} (稳定耗时{}ms{})", definition.tag, target_di_address, di_state_1,
+              settle.elapsed_ms, if settle.settled { "" } else { ", 超时" });
+        settle_records.push(SettleRecord::new("步骤1", &settle));
 
         // 记录步骤1结果
         let step1_status = if di_state_1 {
@@ -673,6 +1095,7 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
                 error_msg,
             );
             outcome.digital_steps = Some(digital_steps);
+            record_settle_times(&mut outcome.details, &settle_records);
             return Ok(outcome);
         }
         debug!("✅ 低电平: {}", di_state_1);
@@ -680,14 +1103,20 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
         // 步骤3: 测试PLC DO输出高电平
         info!("变量:{}, 写[{}]=true", definition.tag, test_rig_do_address);
         crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_do_address, true).await
-            .map_err(|e| AppError::plc_communication_error(format!("设置测试PLC DO高电平失败: {}", e)))?;
-
-        // 等待信号稳定
-        tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
+            .map_err(|e| e.with_context("设置测试PLC DO高电平失败"))?;
 
         // 步骤4: 检查被测PLC DI是否显示"接通"
-        let di_state_2 = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, target_di_address).await
-            .map_err(|e| AppError::plc_communication_error(format!("读取被测PLC DI状态失败: {}", e)))?;
+        // 轮询等待被测PLC DI状态跟随DO输出并保持稳定
+        let settle = wait_for_state(
+            &settle_policy,
+            || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, target_di_address),
+            |v| *v,
+        ).await
+            .map_err(|e| e.with_context("读取被测PLC DI状态失败"))?;
+        let di_state_2 = settle.value;
+        info!("变量:{}, 读[{}]={} (稳定耗时{}ms{})", definition.tag, target_di_address, di_state_2,
+              settle.elapsed_ms, if settle.settled { "" } else { ", 超时" });
+        settle_records.push(SettleRecord::new("步骤2", &settle));
 
         // 记录步骤2结果
         let step2_status = if !di_state_2 {
@@ -714,6 +1143,7 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
                 error_msg,
             );
             outcome.digital_steps = Some(digital_steps);
+            record_settle_times(&mut outcome.details, &settle_records);
             return Ok(outcome);
         }
         debug!("✅ 高电平: {}", di_state_2);
@@ -721,16 +1151,20 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
         // 步骤5: 测试PLC DO输出低电平(复位)
         info!("变量:{}, 写[{}]=false", definition.tag, test_rig_do_address);
         crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_do_address, false).await
-            .map_err(|e| AppError::plc_communication_error(format!("复位测试PLC DO低电平失败: {}", e)))?;
-
-        // 等待信号稳定
-        tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
+            .map_err(|e| e.with_context("复位测试PLC DO低电平失败"))?;
 
         // 步骤6: 最终检查被测PLC DI是否显示"断开"
-        let di_state_3;
-        di_state_3 = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, target_di_address).await
-            .map_err(|e| AppError::plc_communication_error(format!("读取被测PLC DI状态失败: {}", e)))?;
-        info!("变量:{}, 读[{}]={}", definition.tag, target_di_address, di_state_3);
+        // 轮询等待被测PLC DI状态跟随DO输出并保持稳定
+        let settle = wait_for_state(
+            &settle_policy,
+            || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_target, target_conn_id, target_di_address),
+            |v| !*v,
+        ).await
+            .map_err(|e| e.with_context("读取被测PLC DI状态失败"))?;
+        let di_state_3 = settle.value;
+        info!("变量:{}, 读[{}]={} (稳定耗时{}ms{})", definition.tag, target_di_address, di_state_3,
+              settle.elapsed_ms, if settle.settled { "" } else { ", 超时" });
+        settle_records.push(SettleRecord::new("步骤3", &settle));
 
         // 记录步骤3结果
         let step3_status = if di_state_3 {
@@ -757,6 +1191,7 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
                 error_msg,
             );
             outcome.digital_steps = Some(digital_steps);
+            record_settle_times(&mut outcome.details, &settle_records);
             return Ok(outcome);
         }
         debug!("✅ 复位: {}", di_state_3);
@@ -776,6 +1211,7 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
         outcome.end_time = end_time;
         outcome.digital_steps = Some(digital_steps);
         outcome.raw_value_read = Some(format!("状态序列: {} → {} → {}", di_state_1, di_state_2, di_state_3));
+        record_settle_times(&mut outcome.details, &settle_records);
 
         Ok(outcome)
     }
@@ -803,7 +1239,7 @@ impl ISpecificTestStepExecutor for DIHardPointTestExecutor {
 /// 4. 等待信号稳定
 /// 5. 被测PLC DO通道输出低电平 → 检查测试PLC DI通道显示"断开"
 pub struct DOHardPointTestExecutor {
-    /// 测试步骤间隔时间 (毫秒)
+    /// 每个步骤等待信号稳定的上限时间 (毫秒)
     pub step_interval_ms: u64,
 }
 
@@ -822,6 +1258,19 @@ impl DOHardPointTestExecutor {
             .ok_or_else(|| AppError::validation_error("测试实例未分配测试PLC通道地址"))
             .map(|addr| addr.clone())
     }
+
+    /// 复位被测PLC DO为低电平
+    /// 复位失败不影响测试结果，只记录警告
+    async fn reset_target_do(
+        &self,
+        definition: &ChannelPointDefinition,
+        target_conn_id: &str,
+        plc_service_target: &Arc<dyn IPlcCommunicationService>,
+    ) {
+        if let Err(e) = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(plc_service_target, target_conn_id, &definition.plc_communication_address, false).await {
+            warn!("⚠️ 被测PLC DO复位失败: {}", e);
+        }
+    }
 }
 
 #[async_trait]
@@ -842,152 +1291,102 @@ impl ISpecificTestStepExecutor for DOHardPointTestExecutor {
         let test_rig_di_address = self.get_test_rig_di_address(instance)?;
         let target_do_address = &definition.plc_communication_address;
 
+        // 低 → 高 → 低 三步切换，覆盖上升沿与下降沿
+        // 任一步骤失败都继续执行后续步骤，以区分"常1"(卡死接通)与"常0"(卡死断开)故障
+        let step_plan = [
+            (1u32, false, "被测PLC DO输出低电平，检查测试PLC DI显示断开"),
+            (2u32, true, "被测PLC DO输出高电平，检查测试PLC DI显示接通"),
+            (3u32, false, "被测PLC DO复位低电平，检查测试PLC DI显示断开"),
+        ];
+
         // 创建数字量测试步骤记录
         let mut digital_steps = Vec::new();
+        let settle_policy = SettlePolicy::with_timeout(self.step_interval_ms);
+        let mut settle_records = Vec::new();
+
+        for (step_number, set_value, step_description) in step_plan {
+            // 设置被测PLC DO输出
+            info!("变量:{}, 写[{}]={}", definition.tag, target_do_address, set_value);
+            if let Err(e) = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_target, target_conn_id, target_do_address, set_value).await {
+                // 通信失败时尽量复位被测PLC DO，避免现场输出保持接通
+                self.reset_target_do(definition, target_conn_id, &plc_service_target).await;
+                return Err(e.with_context(format!("设置被测PLC DO{}失败", if set_value { "高电平" } else { "低电平" })));
+            }
 
-        // 步骤1: 被测PLC DO输出低电平
-        crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_target, target_conn_id, target_do_address, false).await
-            .map_err(|e| AppError::plc_communication_error(format!("设置被测PLC DO低电平失败: {}", e)))?;
-
-        // 等待信号稳定
-        tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
-
-        // 步骤2: 检查测试PLC DI是否显示"断开"
-        let di_state_1 = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_di_address).await
-            .map_err(|e| AppError::plc_communication_error(format!("读取测试PLC DI状态失败: {}", e)))?;
-
-        // 记录步骤1结果
-        let step1_status = if di_state_1 {
-            SubTestStatus::Failed
-        } else {
-            SubTestStatus::Passed
-        };
-        digital_steps.push(DigitalTestStep {
-            step_number: 1,
-            step_description: "被测PLC DO输出低电平，检查测试PLC DI显示断开".to_string(),
-            set_value: false,
-            expected_reading: false,
-            actual_reading: di_state_1,
-            status: step1_status.clone(),
-            timestamp: Utc::now(),
-        });
+            // 轮询等待测试PLC DI状态跟随DO输出并保持稳定
+            let settle = match wait_for_state(
+                &settle_policy,
+                || crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_di_address),
+                |v| *v == set_value,
+            ).await {
+                Ok(settle) => settle,
+                Err(e) => {
+                    self.reset_target_do(definition, target_conn_id, &plc_service_target).await;
+                    return Err(e.with_context("读取测试PLC DI状态失败"));
+                }
+            };
+            let di_state = settle.value;
+            info!("变量:{}, 读[{}]={} (稳定耗时{}ms{})", definition.tag, test_rig_di_address, di_state,
+                  settle.elapsed_ms, if settle.settled { "" } else { ", 超时" });
+            settle_records.push(SettleRecord::new(format!("步骤{}", step_number), &settle));
 
-        if di_state_1 {
-            let error_msg = format!("❌ DO硬点测试失败: DO低电平时测试PLC DI应为false，实际为true");
-            info!("{}", error_msg);
-            let mut outcome = RawTestOutcome::failure(
-                instance.instance_id.clone(),
-                SubTestItem::HardPoint,
-                error_msg,
-            );
-            outcome.digital_steps = Some(digital_steps);
-            return Ok(outcome);
+            let status = if di_state == set_value {
+                SubTestStatus::Passed
+            } else {
+                SubTestStatus::Failed
+            };
+            let status_icon = if status == SubTestStatus::Passed { "✅" } else { "❌" };
+            debug!("{} 步骤{}: DO={}, DI={}", status_icon, step_number, set_value, di_state);
+
+            digital_steps.push(DigitalTestStep {
+                step_number,
+                step_description: step_description.to_string(),
+                set_value,
+                expected_reading: set_value,
+                actual_reading: di_state,
+                status,
+                timestamp: Utc::now(),
+            });
         }
-        debug!("✅ 低电平测试通过: DO=false, DI={}", di_state_1);
 
-        // 步骤3: 被测PLC DO输出高电平
-        info!("变量:{}, 写[{}]=true", definition.tag, target_do_address);
-        crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_target, target_conn_id, target_do_address, true).await
-            .map_err(|e| AppError::plc_communication_error(format!("设置被测PLC DO高电平失败: {}", e)))?;
-
-        // 等待信号稳定
-        tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
+        let end_time = Utc::now();
+        let states: Vec<bool> = digital_steps.iter().map(|s| s.actual_reading).collect();
+        let raw_value_read = format!("状态序列: {} → {} → {}", states[0], states[1], states[2]);
 
-        // 步骤4: 检查测试PLC DI是否显示"接通"
-        let di_state_2;
-        // 读取后再记录
-        // 读取测试PLC DI
-        di_state_2 = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_di_address).await
-            .map_err(|e| AppError::plc_communication_error(format!("读取测试PLC DI状态失败: {}", e)))?;
-        info!("变量:{}, 读[{}]={}", definition.tag, test_rig_di_address, di_state_2);
+        // 故障分类：DO低电平时DI为true → 常1；DO高电平时DI为false → 常0
+        let stuck_on = digital_steps.iter().any(|s| !s.set_value && s.actual_reading);
+        let stuck_off = digital_steps.iter().any(|s| s.set_value && !s.actual_reading);
 
-        // 记录步骤2结果
-        let step2_status = if !di_state_2 {
-            SubTestStatus::Failed
-        } else {
-            SubTestStatus::Passed
-        };
-        digital_steps.push(DigitalTestStep {
-            step_number: 2,
-            step_description: "被测PLC DO输出高电平，检查测试PLC DI显示接通".to_string(),
-            set_value: true,
-            expected_reading: true,
-            actual_reading: di_state_2,
-            status: step2_status.clone(),
-            timestamp: Utc::now(),
-        });
-
-        if !di_state_2 {
-            let error_msg = format!("❌ DO硬点测试失败: DO高电平时测试PLC DI应为true，实际为false");
-            info!("{}", error_msg);
-            let mut outcome = RawTestOutcome::failure(
+        let mut outcome = if !stuck_on && !stuck_off {
+            debug!("🎯 DO硬点测试完成: {} - 通过", raw_value_read);
+            let mut outcome = RawTestOutcome::success(
                 instance.instance_id.clone(),
                 SubTestItem::HardPoint,
-                error_msg,
             );
-            outcome.digital_steps = Some(digital_steps);
-            return Ok(outcome);
-        }
-        debug!("✅ 高电平测试通过: DO=true, DI={}", di_state_2);
-
-        // 步骤5: 被测PLC DO输出低电平(复位)
-        info!("变量:{}, 写[{}]=false", definition.tag, target_do_address);
-        crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::write_bool_by_id(&plc_service_target, target_conn_id, target_do_address, false).await
-            .map_err(|e| AppError::plc_communication_error(format!("复位被测PLC DO低电平失败: {}", e)))?;
-
-        // 等待信号稳定
-        tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
-
-        // 步骤6: 最终检查测试PLC DI是否显示"断开"
-        let di_state_3;
-        di_state_3 = crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service_test_rig, test_rig_conn_id, &test_rig_di_address).await
-            .map_err(|e| AppError::plc_communication_error(format!("读取测试PLC DI状态失败: {}", e)))?;
-        info!("变量:{}, 读[{}]={}", definition.tag, test_rig_di_address, di_state_3);
-
-        // 记录步骤3结果
-        let step3_status = if di_state_3 {
-            SubTestStatus::Failed
+            outcome.message = Some("DO硬点测试成功: 低→高→低电平切换，测试PLC DI状态正确响应".to_string());
+            outcome
         } else {
-            SubTestStatus::Passed
-        };
-        digital_steps.push(DigitalTestStep {
-            step_number: 3,
-            step_description: "被测PLC DO复位低电平，检查测试PLC DI显示断开".to_string(),
-            set_value: false,
-            expected_reading: false,
-            actual_reading: di_state_3,
-            status: step3_status.clone(),
-            timestamp: Utc::now(),
-        });
-
-        if di_state_3 {
-            let error_msg = format!("❌ DO硬点测试失败: 复位后测试PLC DI应为false，实际为true");
+            let fault = match (stuck_on, stuck_off) {
+                (true, true) => "DI状态与DO输出相反，疑似接线反接或信号取反",
+                (true, false) => "DO低电平时测试PLC DI仍为true，疑似常1(卡死接通)",
+                _ => "DO高电平时测试PLC DI仍为false，疑似常0(卡死断开)或断线",
+            };
+            let error_msg = format!("❌ DO硬点测试失败: {} ({})", fault, raw_value_read);
             info!("{}", error_msg);
-            let mut outcome = RawTestOutcome::failure(
+            RawTestOutcome::failure(
                 instance.instance_id.clone(),
                 SubTestItem::HardPoint,
                 error_msg,
-            );
-            outcome.digital_steps = Some(digital_steps);
-            return Ok(outcome);
-        }
-        debug!("✅ 复位测试通过: DO=false, DI={}", di_state_3);
-
-        let end_time = Utc::now();
-        debug!("🎯 DO硬点测试完成: 低({}) → 高({}) → 低({}) - 通过",
-                                 di_state_1, di_state_2, di_state_3);
+            )
+        };
 
-        // 构造成功的测试结果
-        let success_msg = format!("DO硬点测试成功: 低→高→低电平切换，测试PLC DI状态正确响应");
-        let mut outcome = RawTestOutcome::success(
-            instance.instance_id.clone(),
-            SubTestItem::HardPoint,
-        );
-        outcome.message = Some(success_msg);
         outcome.start_time = start_time;
         outcome.end_time = end_time;
         outcome.digital_steps = Some(digital_steps);
-        outcome.raw_value_read = Some(format!("状态序列: {} → {} → {}", di_state_1, di_state_2, di_state_3));
+        outcome.raw_value_read = Some(raw_value_read);
+        outcome.details.insert("stuck_on".to_string(), serde_json::json!(stuck_on));
+        outcome.details.insert("stuck_off".to_string(), serde_json::json!(stuck_off));
+        record_settle_times(&mut outcome.details, &settle_records);
 
         Ok(outcome)
     }
@@ -1009,19 +1408,27 @@ impl ISpecificTestStepExecutor for DOHardPointTestExecutor {
 ///
 /// 执行AO点的完整硬点测试：被测PLC的AO通道输出 → 测试PLC的AI通道采集
 /// 测试步骤：
-/// 1. 被测PLC AO按序输出: 0%, 25%, 50%, 75%, 100%
+/// 1. 被测PLC AO按测试方案的测试点依次输出（默认0%, 25%, 50%, 75%, 100%）
 /// 2. 测试PLC AI采集对应数值
 /// 3. 验证采集值与期望值的偏差在允许范围内
 pub struct AOHardPointTestExecutor {
-    /// 测试步骤间隔时间 (毫秒)
-    pub step_interval_ms: u64,
+    /// 模拟量测试参数（测试点、容差、稳定时间）
+    pub settings: AnalogTestSettings,
 }
 
 impl AOHardPointTestExecutor {
     /// 创建新的AO硬点测试执行器
     pub fn new(step_interval_ms: u64) -> Self {
+        Self::with_settings(AnalogTestSettings {
+            settle_time_ms: step_interval_ms,
+            ..AnalogTestSettings::default()
+        })
+    }
+
+    /// 使用指定测试参数创建执行器
+    pub fn with_settings(settings: AnalogTestSettings) -> Self {
         Self {
-            step_interval_ms,
+            settings,
         }
     }
 
@@ -1065,8 +1472,11 @@ impl ISpecificTestStepExecutor for AOHardPointTestExecutor {
         info!("🔧 AO硬点测试开始 - 被测PLC AO: {}, 测试PLC AI: {}, 量程: {}-{}",
               target_ao_address, test_rig_ai_address, range_lower, range_upper);
 
-        let test_percentages = vec![0.0, 0.25, 0.5, 0.75, 1.0];
+        let test_percentages = self.settings.sweep_points();
         let mut readings = Vec::new();
+        let settle_policy = SettlePolicy::with_timeout(self.settings.settle_time_ms)
+            .with_band(self.settings.settle_band(range_upper - range_lower));
+        let mut settle_records = Vec::new();
 
         for (step, percentage) in test_percentages.iter().enumerate() {
             let output_value = range_lower + (range_upper - range_lower) * percentage;
@@ -1075,19 +1485,26 @@ impl ISpecificTestStepExecutor for AOHardPointTestExecutor {
 
             // 设置被测PLC AO输出
             plc_service_target.write_float32_by_id(target_conn_id, target_ao_address, output_value).await
-                .map_err(|e| AppError::plc_communication_error(format!("设置被测PLC AO输出失败: {}", e)))?;
-
-            // 等待信号稳定
-            tokio::time::sleep(tokio::time::Duration::from_millis(self.step_interval_ms)).await;
-
-            // 读取测试PLC AI采集值
-            let read_value = plc_service_test_rig.read_float32_by_id(test_rig_conn_id, &test_rig_ai_address).await
-                .map_err(|e| AppError::plc_communication_error(format!("读取测试PLC AI值失败: {}", e)))?;
-            info!("📖 读取 [{}]: {:.2}", test_rig_ai_address, read_value);
+                .map_err(|e| e.with_context("设置被测PLC AO输出失败"))?;
+
+            // 轮询等待测试PLC AI采集值稳定在输出值附近，超时后仍按最后读数判定是否合格
+            let step_policy = settle_policy.clone()
+                .with_expected(output_value as f64, self.settings.tolerance_band(range_upper - range_lower));
+            let settle = wait_for_settle(
+                &step_policy,
+                || plc_service_test_rig.read_float32_by_id(test_rig_conn_id, &test_rig_ai_address),
+            )
+            .await
+            .map_err(|e| e.with_context("读取测试PLC AI值失败"))?;
+            let read_value = settle.value;
+            info!("📖 读取 [{}]: {:.2} (稳定耗时{}ms{})", test_rig_ai_address, read_value,
+                  settle.elapsed_ms, if settle.settled { "" } else { ", 超时" });
+            let direction = if step < self.settings.test_points.len() { "" } else { "↓" };
+            settle_records.push(SettleRecord::new(format!("{}%{}", percentage * 100.0, direction), &settle));
 
             // 计算偏差
-            let deviation = ((read_value - output_value) / (range_upper - range_lower) * 100.0).abs();
-            let is_within_tolerance = deviation <= 3.0; // 3%偏差容限
+            let deviation = self.settings.error_percentage(output_value, read_value, range_upper - range_lower);
+            let is_within_tolerance = self.settings.is_within_tolerance(output_value, read_value, range_upper - range_lower);
 
             let status_icon = if is_within_tolerance { "✅" } else { "❌" };
             info!("{} {}%: {:.2}", status_icon, percentage * 100.0, read_value);
@@ -1110,11 +1527,11 @@ impl ISpecificTestStepExecutor for AOHardPointTestExecutor {
 
         let (success_msg, outcome) = if overall_success {
             info!("✅ 结果: {} - 通过", definition.tag);
-            let msg = format!("AO硬点测试成功: 所有{}个测试点偏差均在3%以内", readings.len());
+            let msg = format!("AO硬点测试成功: 所有{}个测试点偏差均在{}以内", readings.len(), self.settings.tolerance_description());
             (msg.clone(), RawTestOutcome::success(instance.instance_id.clone(), SubTestItem::HardPoint))
         } else {
             info!("❌ 结果: {} - 失败", definition.tag);
-            let msg = format!("AO硬点测试失败: {}个测试点偏差过大", failed_points.len());
+            let msg = format!("AO硬点测试失败: {}个测试点偏差超出{}", failed_points.len(), self.settings.tolerance_description());
             (msg.clone(), RawTestOutcome::failure(instance.instance_id.clone(), SubTestItem::HardPoint, msg))
         };
 
@@ -1140,11 +1557,17 @@ impl ISpecificTestStepExecutor for AOHardPointTestExecutor {
 
         // 🔧 关键修复：无论测试成功还是失败，都要保存百分比测试结果
         // 提取百分比测试结果 - 存储实际工程量 (转换f32到f64)
-        outcome.test_result_0_percent = analog_readings.get(0).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        outcome.test_result_25_percent = analog_readings.get(1).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        outcome.test_result_50_percent = analog_readings.get(2).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        outcome.test_result_75_percent = analog_readings.get(3).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
-        outcome.test_result_100_percent = analog_readings.get(4).and_then(|r| r.actual_reading_eng.map(|v| v as f64));
+        let [p0, p25, p50, p75, p100] = extract_standard_percent_results(&analog_readings);
+        outcome.test_result_0_percent = p0;
+        outcome.test_result_25_percent = p25;
+        outcome.test_result_50_percent = p50;
+        outcome.test_result_75_percent = p75;
+        outcome.test_result_100_percent = p100;
+        record_analog_settings(&mut outcome, &self.settings);
+        record_settle_times(&mut outcome.details, &settle_records);
+        if let Some(metrics) = record_accuracy_metrics(&mut outcome.details, &analog_readings, range_upper - range_lower) {
+            info!("📐 精度指标: {} - {}", definition.tag, metrics.summary());
+        }
 
         // 🔄 测试完成后复位被测PLC的AO输出为0%
         let reset_value = range_lower; // 复位为量程下限
@@ -1174,6 +1597,261 @@ impl ISpecificTestStepExecutor for AOHardPointTestExecutor {
     }
 }
 
+/// 通信点测试数据的取值类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CommunicationValueKind {
+    Bool,
+    Float32,
+    Int32,
+}
+
+impl CommunicationValueKind {
+    fn from_data_type(data_type: &PointDataType) -> Option<Self> {
+        match data_type {
+            PointDataType::Bool => Some(Self::Bool),
+            PointDataType::Float => Some(Self::Float32),
+            PointDataType::Int | PointDataType::Int32 | PointDataType::UInt32 => Some(Self::Int32),
+            _ => None,
+        }
+    }
+
+    /// 测试数据样式：数值类型的每个字节互不相同，便于识别字节序错误
+    fn test_patterns(&self) -> Vec<f64> {
+        match self {
+            Self::Bool => vec![1.0, 0.0],
+            Self::Float32 => vec![1234.5677, -98.7654],
+            Self::Int32 => vec![0x1234_5678 as f64, -0x0765_4321 as f64],
+        }
+    }
+}
+
+/// 检查实际读数是否为期望数据经过字节序调换后的结果，返回被测侧实际使用的字节序
+fn detect_byte_order_mismatch(expected_bits: u32, actual_bits: u32) -> Option<&'static str> {
+    if expected_bits == actual_bits {
+        return None;
+    }
+    let candidates = [
+        ("CDAB", expected_bits.rotate_left(16)),
+        ("BADC", ((expected_bits & 0x00FF_00FF) << 8) | ((expected_bits & 0xFF00_FF00) >> 8)),
+        ("DCBA", expected_bits.swap_bytes()),
+    ];
+    candidates
+        .iter()
+        .find(|(_, swapped)| *swapped == actual_bits)
+        .map(|(name, _)| *name)
+}
+
+/// 通信点测试执行器
+///
+/// 通信模块点位没有硬接线：通过配置的源连接（例如Modbus网关）写入测试数据，
+/// 再从被测PLC的点位通信地址读回，验证数据类型、字节序与缩放系数
+/// 测试步骤：
+/// 1. 按点位数据类型选取测试数据（布尔 true/false，数值类型各字节互不相同）
+/// 2. 源连接写入测试数据
+/// 3. 轮询读取被测PLC点位，直到读数等于期望值（写入值 × 缩放 + 偏移）或超时
+/// 4. 读数不一致时诊断是字节序调换还是缩放系数不一致
+/// 5. 源侧测试数据复位为0
+pub struct CommunicationTestExecutor {
+    /// 通信点测试配置
+    pub config: CommunicationTestConfig,
+}
+
+impl CommunicationTestExecutor {
+    /// 创建新的通信点测试执行器
+    pub fn new(config: CommunicationTestConfig) -> Self {
+        Self { config }
+    }
+
+    async fn write_source(
+        &self,
+        plc_service: &Arc<dyn IPlcCommunicationService>,
+        kind: CommunicationValueKind,
+        address: &str,
+        value: f64,
+    ) -> AppResult<()> {
+        let conn_id = &self.config.source_connection.id;
+        let result = match kind {
+            CommunicationValueKind::Bool => PlcServiceLegacyExt::write_bool_by_id(plc_service, conn_id, address, value != 0.0).await,
+            CommunicationValueKind::Float32 => plc_service.write_float32_by_id(conn_id, address, value as f32).await,
+            CommunicationValueKind::Int32 => {
+                let handle = plc_service.default_handle_by_id(conn_id).await
+                    .ok_or_else(|| AppError::plc_communication_error(format!("PLC连接未建立: {}", conn_id)))?;
+                plc_service.write_i32(&handle, address, value as i32).await
+            }
+        };
+        result.map_err(|e| e.with_context(format!("源连接写入[{}]失败", address)))
+    }
+
+    async fn read_target(
+        plc_service: &Arc<dyn IPlcCommunicationService>,
+        kind: CommunicationValueKind,
+        conn_id: &str,
+        address: &str,
+    ) -> AppResult<(f64, u32)> {
+        match kind {
+            CommunicationValueKind::Bool => {
+                let v = PlcServiceLegacyExt::read_bool_by_id(plc_service, conn_id, address).await?;
+                Ok((if v { 1.0 } else { 0.0 }, v as u32))
+            }
+            CommunicationValueKind::Float32 => {
+                let v = plc_service.read_float32_by_id(conn_id, address).await?;
+                Ok((v as f64, v.to_bits()))
+            }
+            CommunicationValueKind::Int32 => {
+                let handle = plc_service.default_handle_by_id(conn_id).await
+                    .ok_or_else(|| AppError::plc_communication_error(format!("PLC连接未建立: {}", conn_id)))?;
+                let v = plc_service.read_i32(&handle, address).await?;
+                Ok((v as f64, v as u32))
+            }
+        }
+    }
+
+    /// 数值按数据类型编码后的位模式，用于字节序诊断
+    fn value_bits(kind: CommunicationValueKind, value: f64) -> u32 {
+        match kind {
+            CommunicationValueKind::Bool => (value != 0.0) as u32,
+            CommunicationValueKind::Float32 => (value as f32).to_bits(),
+            CommunicationValueKind::Int32 => value.round() as i32 as u32,
+        }
+    }
+}
+
+#[async_trait]
+impl ISpecificTestStepExecutor for CommunicationTestExecutor {
+    async fn execute(
+        &self,
+        instance: &ChannelTestInstance,
+        definition: &ChannelPointDefinition,
+        _test_rig_conn_id: &str,
+        target_conn_id: &str,
+        _plc_service_test_rig: Arc<dyn IPlcCommunicationService>,
+        plc_service_target: Arc<dyn IPlcCommunicationService>,
+    ) -> AppResult<RawTestOutcome> {
+        let start_time = Utc::now();
+
+        let kind = match CommunicationValueKind::from_data_type(&definition.data_type) {
+            Some(kind) => kind,
+            None => {
+                return Ok(RawTestOutcome::configuration_failure(
+                    instance.instance_id.clone(),
+                    SubTestItem::CommunicationTest,
+                    format!("通信点测试暂不支持数据类型: {:?}", definition.data_type),
+                ));
+            }
+        };
+
+        let source_address = self.config.source_address_for(&definition.tag, &definition.plc_communication_address);
+        let target_address = &definition.plc_communication_address;
+        info!("🔧 通信点测试开始: {} 源[{}:{}] → 被测[{}], 类型 {:?}",
+              definition.tag, self.config.source_connection.id, source_address, target_address, kind);
+
+        let settle_policy = SettlePolicy::with_timeout(self.config.settle_timeout_ms);
+        let mut settle_records = Vec::new();
+        let mut pattern_results = Vec::new();
+        let mut byte_order_mismatch: Option<&'static str> = None;
+        let mut observed_scales = Vec::new();
+        let mut all_passed = true;
+
+        for written in kind.test_patterns() {
+            // 布尔量不参与缩放
+            let expected = if kind == CommunicationValueKind::Bool { written } else { self.config.expected_value(written) };
+
+            if let Err(e) = self.write_source(&plc_service_target, kind, &source_address, written).await {
+                error!("❌ {}", e);
+                return Err(e);
+            }
+
+            let settle = wait_for_state(
+                &settle_policy,
+                || Self::read_target(&plc_service_target, kind, target_conn_id, target_address),
+                |(actual, _)| (actual - expected).abs() <= self.config.tolerance,
+            ).await
+                .map_err(|e| e.with_context(format!("读取被测PLC[{}]失败", target_address)))?;
+            let (actual, actual_bits) = settle.value;
+            settle_records.push(SettleRecord::new(format!("{}", written), &settle));
+
+            let passed = settle.settled;
+            info!("{} 写 {} → 读 {} (期望 {})", if passed { "✅" } else { "❌" }, written, actual, expected);
+
+            if !passed {
+                all_passed = false;
+                if kind != CommunicationValueKind::Bool {
+                    byte_order_mismatch = byte_order_mismatch
+                        .or_else(|| detect_byte_order_mismatch(Self::value_bits(kind, written), actual_bits))
+                        .or_else(|| detect_byte_order_mismatch(Self::value_bits(kind, expected), actual_bits));
+                    if written != 0.0 {
+                        observed_scales.push(actual / written);
+                    }
+                }
+            }
+
+            pattern_results.push(serde_json::json!({
+                "written": written,
+                "expected": expected,
+                "actual": actual,
+                "passed": passed,
+            }));
+        }
+
+        // 源侧测试数据复位，复位失败不影响测试结果
+        if let Err(e) = self.write_source(&plc_service_target, kind, &source_address, 0.0).await {
+            warn!("⚠️ 通信点测试源数据复位失败: {}", e);
+        }
+
+        let end_time = Utc::now();
+
+        // 所有样式的读数/写入比值一致时，判定为缩放系数不一致
+        let detected_scale = match observed_scales.as_slice() {
+            [first, rest @ ..] if !rest.is_empty()
+                && rest.iter().all(|s| (s - first).abs() <= first.abs() * 1e-3) => Some(*first),
+            _ => None,
+        };
+
+        let message = if all_passed {
+            format!("通信点测试通过: {}组测试数据读回一致", pattern_results.len())
+        } else if let Some(order) = byte_order_mismatch {
+            format!("❌ 通信点测试失败: 字节序不一致，被测侧读数符合 {} 字节序调换", order)
+        } else if let Some(scale) = detected_scale {
+            format!("❌ 通信点测试失败: 缩放系数不一致，期望 {}，实际约 {:.4}", self.config.scale, scale)
+        } else if kind == CommunicationValueKind::Bool {
+            "❌ 通信点测试失败: 布尔量读回与写入不一致".to_string()
+        } else {
+            "❌ 通信点测试失败: 读回数值与期望值不一致".to_string()
+        };
+        info!("{}", message);
+
+        let mut outcome = if all_passed {
+            RawTestOutcome::success(instance.instance_id.clone(), SubTestItem::CommunicationTest)
+        } else {
+            RawTestOutcome::failure(instance.instance_id.clone(), SubTestItem::CommunicationTest, message.clone())
+        };
+        outcome.message = Some(message);
+        outcome.start_time = start_time;
+        outcome.end_time = end_time;
+        outcome.raw_value_read = pattern_results.last().map(|r| r["actual"].to_string());
+        outcome.details.insert("source_connection".to_string(), serde_json::json!(self.config.source_connection.id));
+        outcome.details.insert("source_address".to_string(), serde_json::json!(source_address));
+        outcome.details.insert("patterns".to_string(), serde_json::json!(pattern_results));
+        outcome.details.insert("byte_order_mismatch".to_string(), serde_json::json!(byte_order_mismatch));
+        outcome.details.insert("detected_scale".to_string(), serde_json::json!(detected_scale));
+        record_settle_times(&mut outcome.details, &settle_records);
+
+        Ok(outcome)
+    }
+
+    fn item_type(&self) -> SubTestItem {
+        SubTestItem::CommunicationTest
+    }
+
+    fn executor_name(&self) -> &'static str {
+        "CommunicationTestExecutor"
+    }
+
+    fn supports_definition(&self, definition: &ChannelPointDefinition) -> bool {
+        matches!(definition.module_type, ModuleType::Communication)
+    }
+}
+
 /// DI状态读取执行器
 /// 仅读取一次 DI 状态并与期望值比较（如有提供）。
 pub struct DIStateReadExecutor {
@@ -1237,3 +1915,37 @@ impl ISpecificTestStepExecutor for DIStateReadExecutor {
         matches!(definition.module_type, ModuleType::DI | ModuleType::DINone)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_each_byte_order_swap() {
+        let expected = 0x1122_3344;
+        assert_eq!(detect_byte_order_mismatch(expected, expected), None);
+        assert_eq!(detect_byte_order_mismatch(expected, 0x3344_1122), Some("CDAB"));
+        assert_eq!(detect_byte_order_mismatch(expected, 0x2211_4433), Some("BADC"));
+        assert_eq!(detect_byte_order_mismatch(expected, 0x4433_2211), Some("DCBA"));
+        // 缩放错误或无关数据不判定为字节序问题
+        assert_eq!(detect_byte_order_mismatch(expected, expected.wrapping_mul(10)), None);
+        assert_eq!(detect_byte_order_mismatch(expected, 0), None);
+    }
+
+    #[test]
+    fn test_patterns_identify_byte_order_unambiguously() {
+        for kind in [CommunicationValueKind::Float32, CommunicationValueKind::Int32] {
+            for written in kind.test_patterns() {
+                let bits = CommunicationTestExecutor::value_bits(kind, written);
+                let swaps = [
+                    ("CDAB", bits.rotate_left(16)),
+                    ("BADC", ((bits & 0x00FF_00FF) << 8) | ((bits & 0xFF00_FF00) >> 8)),
+                    ("DCBA", bits.swap_bytes()),
+                ];
+                for (order, swapped) in swaps {
+                    assert_eq!(detect_byte_order_mismatch(bits, swapped), Some(order), "{:?} {} {}", kind, written, order);
+                }
+            }
+        }
+    }
+}
@@ -28,21 +28,25 @@
 //! - **异步编程**: async/await、Future、tokio运行时
 //! - **类型系统**: trait对象、泛型约束、动态分发
 
-use crate::models::{ChannelTestInstance, ChannelPointDefinition, RawTestOutcome, ModuleType, SubTestItem};
+use crate::models::{ChannelTestInstance, ChannelPointDefinition, RawTestOutcome, ModuleType, AnalogTestProfile, AnalogTestSettings, CommunicationTestConfig};
+use crate::models::enums::SubTestItem;
 use crate::infrastructure::plc_communication::IPlcCommunicationService;
+use crate::infrastructure::modbus_capture::with_capture_instance;
 use crate::domain::specific_test_executors::{
     ISpecificTestStepExecutor, AIHardPointPercentExecutor,
-    DIHardPointTestExecutor, DOHardPointTestExecutor, AOHardPointTestExecutor
+    DIHardPointTestExecutor, DOHardPointTestExecutor, AOHardPointTestExecutor,
+    CommunicationTestExecutor, CrosstalkNeighbor, AIAlarmTestExecutor
 };
+use crate::utils::config::TestConfig;
 use crate::utils::error::{AppError, AppResult};
 use async_trait::async_trait;
 use std::sync::Arc;
 use std::collections::HashMap;
-use tokio::sync::{mpsc, Semaphore, RwLock};
+use tokio::sync::{mpsc, Semaphore, RwLock, Mutex};
 use tokio_util::sync::CancellationToken;
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
-use log::{debug, info, warn, error};
+use log::{debug, info, error};
 
 /// 任务状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -64,6 +68,52 @@ pub struct TestTask {
     pub cancellation_token: CancellationToken,
 }
 
+/// 自动重试退避时间上限（毫秒）
+const MAX_RETRY_BACKOFF_MS: u64 = 10_000;
+
+/// 测试步骤失败自动重试策略
+///
+/// 来自全局配置 `TestConfig.auto_retry_on_failure / auto_retry_count / auto_retry_backoff_ms`，
+/// 重试间隔按指数退避：backoff, 2×backoff, 4×backoff ...（上限 10 秒）
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestRetryPolicy {
+    /// 失败后最多重试次数（0 表示不重试）
+    pub max_retries: u32,
+    /// 首次重试前的等待时间（毫秒）
+    pub backoff_ms: u64,
+}
+
+impl TestRetryPolicy {
+    /// 不重试
+    pub fn disabled() -> Self {
+        Self { max_retries: 0, backoff_ms: 0 }
+    }
+
+    /// 根据测试配置创建重试策略
+    pub fn from_test_config(test_config: &TestConfig) -> Self {
+        if !test_config.auto_retry_on_failure {
+            return Self::disabled();
+        }
+        Self {
+            max_retries: test_config.auto_retry_count,
+            backoff_ms: test_config.auto_retry_backoff_ms,
+        }
+    }
+
+    /// 系统默认策略：优先读取全局配置，未初始化时不重试
+    pub fn system_default() -> Self {
+        crate::utils::config::get_global_config()
+            .map(|config| Self::from_test_config(&config.test_config))
+            .unwrap_or_else(|_| Self::disabled())
+    }
+
+    /// 第 `attempt` 次执行失败后、下一次重试前的等待时间
+    pub fn backoff_for(&self, attempt: u32) -> std::time::Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        std::time::Duration::from_millis(self.backoff_ms.saturating_mul(factor).min(MAX_RETRY_BACKOFF_MS))
+    }
+}
+
 /// 测试执行引擎接口
 #[async_trait]
 pub trait ITestExecutionEngine: Send + Sync {
@@ -86,6 +136,21 @@ pub trait ITestExecutionEngine: Send + Sync {
 
     /// 停止所有任务
     async fn stop_all_tasks(&self) -> AppResult<()>;
+
+    /// 设置批次的模拟量测试配置方案
+    /// 未设置的批次使用系统默认方案
+    async fn set_batch_analog_test_profile(&self, batch_id: &str, profile: AnalogTestProfile) -> AppResult<()>;
+
+    /// 设置批次的通信点测试配置
+    /// 传入 None 时该批次的通信模块点位不参与自动测试
+    async fn set_batch_communication_test_config(&self, batch_id: &str, config: Option<CommunicationTestConfig>) -> AppResult<()>;
+
+    /// 获取批次当前生效的通信点测试配置
+    async fn get_batch_communication_test_config(&self, batch_id: &str) -> Option<CommunicationTestConfig>;
+
+    /// 登记批次的通道定义
+    /// 用于AI串扰检查时查找同模块的邻近通道
+    async fn set_batch_channel_definitions(&self, batch_id: &str, definitions: Vec<ChannelPointDefinition>) -> AppResult<()>;
 }
 
 /// 测试执行引擎实现
@@ -104,6 +169,17 @@ pub struct TestExecutionEngine {
     active_tasks: Arc<RwLock<HashMap<String, TestTask>>>,
     /// 全局取消令牌
     global_cancellation_token: CancellationToken,
+    /// 批次模拟量测试配置方案 (batch_id -> profile)
+    analog_test_profiles: Arc<RwLock<HashMap<String, AnalogTestProfile>>>,
+    /// 批次通信点测试配置 (batch_id -> config)，未配置的批次跳过通信模块点位
+    communication_test_configs: Arc<RwLock<HashMap<String, CommunicationTestConfig>>>,
+    /// 测试步骤失败自动重试策略
+    retry_policy: TestRetryPolicy,
+    /// 批次通道定义 (batch_id -> definitions)，用于查找串扰检查的邻近通道
+    batch_channel_definitions: Arc<RwLock<HashMap<String, Vec<ChannelPointDefinition>>>>,
+    /// 模块互斥锁 (batch_id::module_name -> lock)
+    /// 启用串扰检查时同一模块的AI通道依次测试，避免邻近通道同时被驱动
+    module_locks: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
 }
 
 impl TestExecutionEngine {
@@ -123,19 +199,97 @@ impl TestExecutionEngine {
             target_conn_id,
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
             global_cancellation_token: CancellationToken::new(),
+            analog_test_profiles: Arc::new(RwLock::new(HashMap::new())),
+            communication_test_configs: Arc::new(RwLock::new(HashMap::new())),
+            retry_policy: TestRetryPolicy::system_default(),
+            batch_channel_definitions: Arc::new(RwLock::new(HashMap::new())),
+            module_locks: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 指定自动重试策略（默认读取全局测试配置）
+    pub fn with_retry_policy(mut self, retry_policy: TestRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// 解析测试实例所在批次对该点位使用的模拟量测试参数
+    async fn resolve_analog_settings(&self, instance: &ChannelTestInstance, definition: &ChannelPointDefinition) -> AnalogTestSettings {
+        let profiles = self.analog_test_profiles.read().await;
+        match profiles.get(&instance.test_batch_id) {
+            Some(profile) => profile.resolve(definition),
+            None => AnalogTestProfile::system_default().resolve(definition),
         }
     }
 
+    /// 查找AI串扰检查的邻近通道：同批次、同模块的其他AI通道，按模块内通道号排序
+    /// 测试方案未配置串扰阈值或点位没有模块名时返回空列表
+    async fn resolve_crosstalk_neighbors(
+        &self,
+        instance: &ChannelTestInstance,
+        definition: &ChannelPointDefinition,
+        analog_settings: &AnalogTestSettings,
+    ) -> Vec<CrosstalkNeighbor> {
+        if analog_settings.crosstalk_threshold_percent.is_none()
+            || definition.module_name.trim().is_empty()
+            || !matches!(definition.module_type, ModuleType::AI | ModuleType::AINone)
+        {
+            return Vec::new();
+        }
+
+        let batch_definitions = self.batch_channel_definitions.read().await;
+        let mut neighbors: Vec<&ChannelPointDefinition> = batch_definitions
+            .get(&instance.test_batch_id)
+            .map(|definitions| {
+                definitions
+                    .iter()
+                    .filter(|d| d.id != definition.id
+                        && d.module_name == definition.module_name
+                        && matches!(d.module_type, ModuleType::AI | ModuleType::AINone))
+                    .collect()
+            })
+            .unwrap_or_default();
+        neighbors.sort_by(|a, b| a.channel_tag_in_module.cmp(&b.channel_tag_in_module));
+        neighbors.into_iter().map(CrosstalkNeighbor::from_definition).collect()
+    }
+
+    /// 获取模块互斥锁
+    async fn module_lock(&self, batch_id: &str, module_name: &str) -> Arc<Mutex<()>> {
+        let mut locks = self.module_locks.lock().await;
+        locks
+            .entry(format!("{}::{}", batch_id, module_name))
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone()
+    }
+
     /// 根据点位定义确定测试步骤
-    fn determine_test_steps(&self, definition: &ChannelPointDefinition) -> Vec<Box<dyn ISpecificTestStepExecutor>> {
+    fn determine_test_steps(
+        &self,
+        definition: &ChannelPointDefinition,
+        analog_settings: &AnalogTestSettings,
+        communication_config: Option<&CommunicationTestConfig>,
+        crosstalk_neighbors: Vec<CrosstalkNeighbor>,
+    ) -> Vec<Box<dyn ISpecificTestStepExecutor>> {
         let mut executors: Vec<Box<dyn ISpecificTestStepExecutor>> = Vec::new();
 
         match definition.module_type {
             ModuleType::AI | ModuleType::AINone => {
                 // AI点硬点测试：测试PLC的AO通道输出 → 被测PLC的AI通道采集
-                // AI点硬点测试不需要时间间隔参数
-                executors.push(Box::new(AIHardPointPercentExecutor::new()));
-                //TODO:(DJ)这里没有项下面的3个测试项添加测试的时间间隔。我需要编写方式保持一致
+                // 测试点、容差与稳定时间来自批次的模拟量测试配置方案
+                // 配置了串扰阈值时追加同模块邻近通道的串扰检查
+                executors.push(Box::new(
+                    AIHardPointPercentExecutor::with_settings(analog_settings.clone())
+                        .with_crosstalk_neighbors(crosstalk_neighbors),
+                ));
+                // 配置了触发点搜索时，对点表中已配置设定地址的报警项依次搜索触发点
+                if let Some(ramp) = &analog_settings.alarm_trip_point_ramp {
+                    for alarm_type in [SubTestItem::LowLowAlarm, SubTestItem::LowAlarm, SubTestItem::HighAlarm, SubTestItem::HighHighAlarm] {
+                        let executor = AIAlarmTestExecutor::with_trip_point_ramp(alarm_type, ramp.clone());
+                        if executor.supports_definition(definition) {
+                            executors.push(Box::new(executor));
+                        }
+                    }
+                }
             },
             ModuleType::DI | ModuleType::DINone => {
                 // DI点硬点测试：测试PLC的DO通道输出 → 被测PLC的DI通道检测
@@ -147,11 +301,14 @@ impl TestExecutionEngine {
             },
             ModuleType::AO | ModuleType::AONone => {
                 // AO点硬点测试：被测PLC的AO通道输出 → 测试PLC的AI通道采集
-                executors.push(Box::new(AOHardPointTestExecutor::new(3000))); // 3秒间隔
+                executors.push(Box::new(AOHardPointTestExecutor::with_settings(analog_settings.clone())));
             },
             ModuleType::Communication => {
-                // TODO: 实现通信模块测试执行器
-                debug!("通信模块测试执行器尚未实现: {}", definition.tag);
+                // 通信点测试：源连接写入测试数据 → 被测PLC通信地址读回
+                match communication_config {
+                    Some(config) => executors.push(Box::new(CommunicationTestExecutor::new(config.clone()))),
+                    None => debug!("未配置通信测试源连接，跳过通信点: {}", definition.tag),
+                }
             },
             ModuleType::Other(_) => {
                 // TODO: 实现其他类型模块测试执行器
@@ -181,29 +338,26 @@ impl TestExecutionEngine {
             }
         }
 
-        // 针对 DO/AO 点位，在自动测试阶段直接判定硬点测试通过，避免真正下发PLC操作，
-        // 同时保留 RawTestOutcome 供前端状态机与报告使用。
-        if matches!(definition.module_type, ModuleType::AO | ModuleType::AONone | ModuleType::DO | ModuleType::DONone) {
-            let mut auto_pass_outcome = RawTestOutcome::success(
-                instance.instance_id.clone(),
-                SubTestItem::HardPoint,
-            );
-            auto_pass_outcome.message = Some("自动测试阶段默认通过".to_string());
-            if let Err(e) = result_sender.send(auto_pass_outcome).await {
-                warn!("发送自动通过结果失败: {}", e);
-            }
-            {
-                let mut tasks = self.active_tasks.write().await;
-                if let Some(task) = tasks.get_mut(&task_id) {
-                    task.status = TaskStatus::Completed;
-                }
+        // 确定测试步骤
+        let analog_settings = self.resolve_analog_settings(&instance, &definition).await;
+        let communication_config = self.communication_test_configs.read().await.get(&instance.test_batch_id).cloned();
+        let crosstalk_neighbors = self.resolve_crosstalk_neighbors(&instance, &definition, &analog_settings).await;
+
+        // 串扰检查期间同模块其他AI通道不能被驱动，同一模块的AI测试依次执行
+        let module_lock = if crosstalk_neighbors.is_empty() {
+            None
+        } else {
+            Some(self.module_lock(&instance.test_batch_id, &definition.module_name).await)
+        };
+        let _module_guard = match &module_lock {
+            Some(lock) => {
+                debug!("等待模块 {} 空闲: {}", definition.module_name, definition.tag);
+                Some(lock.lock().await)
             }
-            info!("✅ 自动跳过 DO/AO 硬点测试: {}", definition.tag);
-            return;
-        }
+            None => None,
+        };
 
-        // 确定测试步骤
-        let executors = self.determine_test_steps(&definition);
+        let executors = self.determine_test_steps(&definition, &analog_settings, communication_config.as_ref(), crosstalk_neighbors);
 
         if executors.is_empty() {
             // 🔧 移除 [TestEngine] 日志
@@ -250,50 +404,73 @@ impl TestExecutionEngine {
 
             // 🔧 移除 [TestEngine] 日志
 
-            // 执行测试步骤
-            match executor.execute(
-                &instance,
-                &definition,
-                &self.test_rig_conn_id,
-                &self.target_conn_id,
-                self.plc_service_test_rig.clone(),
-                self.plc_service_target.clone(),
-            ).await {
-                Ok(outcome) => {
-                    // 减少冗余日志 - 只在debug模式下显示步骤完成信息
-                    // 🔧 移除 [TestEngine] 日志
-
-                    // 发送测试结果
-                    if let Err(e) = result_sender.send(outcome.clone()).await {
-                        // 🔧 移除 [TestEngine] 日志
+            // 执行测试步骤，失败时按重试策略自动重试，每次执行的结果都单独上报
+            let max_attempts = self.retry_policy.max_retries + 1;
+            let mut attempt = 1;
+            loop {
+                let mut outcome = match executor.execute(
+                    &instance,
+                    &definition,
+                    &self.test_rig_conn_id,
+                    &self.target_conn_id,
+                    self.plc_service_test_rig.clone(),
+                    self.plc_service_target.clone(),
+                ).await {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        // 创建失败的测试结果（PLC通信异常等）
+                        let mut failed_outcome = RawTestOutcome::new(
+                            instance.instance_id.clone(),
+                            executor.item_type(),
+                            false,
+                        );
+                        failed_outcome.message = Some(format!("执行失败: {}", e));
+                        failed_outcome.failure_kind = Some(e.failure_kind());
+                        failed_outcome
                     }
+                };
+                outcome.attempt = attempt;
 
+                let cancelled = task_cancellation_token.is_cancelled() || self.global_cancellation_token.is_cancelled();
+                let will_retry = !outcome.success && attempt < max_attempts && !cancelled;
+                outcome.retry_pending = will_retry;
+
+                if outcome.success && attempt > 1 {
+                    info!("🔁 {} [{}] 第{}次重试通过", definition.tag, executor.executor_name(), attempt - 1);
+                }
+
+                // 发送测试结果
+                if let Err(e) = result_sender.send(outcome.clone()).await {
+                    // 🔧 移除 [TestEngine] 日志
+                }
+
+                if !will_retry {
                     // 记录失败状态，但继续执行后续步骤以获得完整测试数据
                     if !outcome.success {
-                        // 🔧 移除 [TestEngine] 日志
                         has_failure = true;
-                        // 不再break，继续执行后续测试步骤
                     }
-                },
-                Err(e) => {
-                    // 🔧 移除 [TestEngine] 日志
+                    break;
+                }
 
-                    // 创建失败的测试结果
-                    let mut failed_outcome = RawTestOutcome::new(
-                        instance.instance_id.clone(),
-                        executor.item_type(),
-                        false,
-                    );
-                    failed_outcome.message = Some(format!("执行失败: {}", e));
-
-                    // 发送失败结果
-                    if let Err(send_err) = result_sender.send(failed_outcome).await {
-                        // 🔧 移除 [TestEngine] 日志
-                    }
+                let backoff = self.retry_policy.backoff_for(attempt);
+                info!("🔁 {} [{}] 第{}次执行失败，{}ms后重试: {}",
+                      definition.tag, executor.executor_name(), attempt, backoff.as_millis(),
+                      outcome.message.as_deref().unwrap_or(""));
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = task_cancellation_token.cancelled() => {}
+                    _ = self.global_cancellation_token.cancelled() => {}
+                }
 
-                    has_failure = true;
-                    // 继续执行后续步骤以获得完整测试数据
+                // 等待重试期间被取消
+                if task_cancellation_token.is_cancelled() || self.global_cancellation_token.is_cancelled() {
+                    let mut tasks = self.active_tasks.write().await;
+                    if let Some(task) = tasks.get_mut(&task_id) {
+                        task.status = TaskStatus::Cancelled;
+                    }
+                    return;
                 }
+                attempt += 1;
             }
         }
 
@@ -356,6 +533,11 @@ impl ITestExecutionEngine for TestExecutionEngine {
             target_conn_id: self.target_conn_id.clone(),
             active_tasks: active_tasks.clone(),
             global_cancellation_token: global_token,
+            analog_test_profiles: self.analog_test_profiles.clone(),
+            communication_test_configs: self.communication_test_configs.clone(),
+            retry_policy: self.retry_policy.clone(),
+            batch_channel_definitions: self.batch_channel_definitions.clone(),
+            module_locks: self.module_locks.clone(),
         };
 
         // 克隆task_id用于返回
@@ -379,14 +561,15 @@ impl ITestExecutionEngine for TestExecutionEngine {
                 }
             };
 
-            // 执行测试序列
-            engine_clone.execute_test_sequence(
+            // 执行测试序列；期间的Modbus报文录制标记为该测试实例
+            let (batch_id, instance_id) = (instance.test_batch_id.clone(), instance.instance_id.clone());
+            with_capture_instance(&batch_id, &instance_id, engine_clone.execute_test_sequence(
                 task_id.clone(),
                 instance,
                 definition,
                 result_sender,
                 task_cancellation_token,
-            ).await;
+            )).await;
 
             // 任务完成后从活动任务列表中移除
             {
@@ -459,4 +642,119 @@ impl ITestExecutionEngine for TestExecutionEngine {
 
         Ok(())
     }
+    /// 设置批次的模拟量测试配置方案
+    async fn set_batch_analog_test_profile(&self, batch_id: &str, profile: AnalogTestProfile) -> AppResult<()> {
+        profile.validate().map_err(AppError::validation_error)?;
+        debug!("设置批次模拟量测试方案: {} -> {}", batch_id, profile.name);
+        let mut profiles = self.analog_test_profiles.write().await;
+        profiles.insert(batch_id.to_string(), profile);
+        Ok(())
+    }
+
+    /// 设置批次的通信点测试配置
+    async fn set_batch_communication_test_config(&self, batch_id: &str, config: Option<CommunicationTestConfig>) -> AppResult<()> {
+        let mut configs = self.communication_test_configs.write().await;
+        match config {
+            Some(config) => {
+                config.validate().map_err(AppError::validation_error)?;
+                debug!("设置批次通信点测试源连接: {} -> {} ({}:{})", batch_id, config.source_connection.id, config.source_connection.host, config.source_connection.port);
+                configs.insert(batch_id.to_string(), config);
+            }
+            None => {
+                configs.remove(batch_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取批次当前生效的通信点测试配置
+    async fn get_batch_communication_test_config(&self, batch_id: &str) -> Option<CommunicationTestConfig> {
+        self.communication_test_configs.read().await.get(batch_id).cloned()
+    }
+
+    /// 登记批次的通道定义
+    async fn set_batch_channel_definitions(&self, batch_id: &str, definitions: Vec<ChannelPointDefinition>) -> AppResult<()> {
+        debug!("登记批次通道定义: {} ({} 个)", batch_id, definitions.len());
+        let mut batch_definitions = self.batch_channel_definitions.write().await;
+        batch_definitions.insert(batch_id.to_string(), definitions);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::plc_communication::ModbusTcpPlcService;
+    use crate::models::{AlarmRampConfig, PointDataType};
+
+    fn engine() -> TestExecutionEngine {
+        let plc: Arc<dyn IPlcCommunicationService> = Arc::new(ModbusTcpPlcService::new());
+        TestExecutionEngine::new(1, plc.clone(), plc, "rig".to_string(), "target".to_string())
+    }
+
+    fn ai_definition() -> ChannelPointDefinition {
+        let mut definition = ChannelPointDefinition::new(
+            "PT201".to_string(), "PT201".to_string(), String::new(), "站1".to_string(), "M1".to_string(),
+            ModuleType::AI, "1".to_string(), PointDataType::Float, "30001".to_string(),
+        );
+        definition.sh_set_value = Some(150.0);
+        definition.sh_set_point_address = Some("40201".to_string());
+        definition.sh_feedback_address = Some("00201".to_string());
+        definition.sl_set_value = Some(20.0);
+        definition.sl_set_point_address = Some("40203".to_string());
+        definition.sl_feedback_address = Some("00202".to_string());
+        definition
+    }
+
+    fn step_items(executors: &[Box<dyn ISpecificTestStepExecutor>]) -> Vec<SubTestItem> {
+        executors.iter().map(|e| e.item_type()).collect()
+    }
+
+    #[test]
+    fn alarm_trip_point_ramp_runs_only_when_configured() {
+        let engine = engine();
+        let definition = ai_definition();
+
+        let hard_point_only = engine.determine_test_steps(&definition, &AnalogTestSettings::default(), None, Vec::new());
+        assert_eq!(step_items(&hard_point_only), vec![SubTestItem::HardPoint]);
+
+        let settings = AnalogTestSettings { alarm_trip_point_ramp: Some(AlarmRampConfig::default()), ..AnalogTestSettings::default() };
+        let with_ramp = engine.determine_test_steps(&definition, &settings, None, Vec::new());
+        // 只为点表中配置了设定地址的报警项追加触发点搜索
+        assert_eq!(step_items(&with_ramp), vec![SubTestItem::HardPoint, SubTestItem::LowAlarm, SubTestItem::HighAlarm]);
+        assert!(with_ramp[1..].iter().all(|e| e.executor_name() == "AIAlarmTestExecutor"));
+    }
+
+    #[test]
+    fn retry_backoff_doubles_per_attempt_up_to_the_cap() {
+        let policy = TestRetryPolicy { max_retries: 3, backoff_ms: 500 };
+        let backoffs: Vec<u64> = (0..=5).map(|attempt| policy.backoff_for(attempt).as_millis() as u64).collect();
+        // 第0次与第1次相同，之后每次翻倍
+        assert_eq!(backoffs, vec![500, 500, 1000, 2000, 4000, 8000]);
+        assert_eq!(policy.backoff_for(6).as_millis() as u64, MAX_RETRY_BACKOFF_MS);
+        // 极大的重试次数与等待时间不会溢出
+        assert_eq!(policy.backoff_for(u32::MAX).as_millis() as u64, MAX_RETRY_BACKOFF_MS);
+        let huge = TestRetryPolicy { max_retries: 1, backoff_ms: u64::MAX };
+        assert_eq!(huge.backoff_for(2).as_millis() as u64, MAX_RETRY_BACKOFF_MS);
+        assert_eq!(TestRetryPolicy::disabled().backoff_for(3), std::time::Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn communication_test_config_is_scoped_to_batch() {
+        let engine = engine();
+        let config: CommunicationTestConfig = serde_json::from_value(serde_json::json!({
+            "source_connection": {
+                "id": "gateway", "name": "网关", "protocol": "ModbusTcp", "host": "127.0.0.1", "port": 502,
+                "timeout_ms": 1000, "read_timeout_ms": 1000, "write_timeout_ms": 1000, "byte_order": "ABCD",
+                "zero_based_address": false, "retry_count": 0, "retry_interval_ms": 0, "protocol_params": {}
+            }
+        })).unwrap();
+
+        engine.set_batch_communication_test_config("batch-1", Some(config.clone())).await.unwrap();
+        assert_eq!(engine.get_batch_communication_test_config("batch-1").await, Some(config));
+        assert_eq!(engine.get_batch_communication_test_config("batch-2").await, None);
+
+        engine.set_batch_communication_test_config("batch-1", None).await.unwrap();
+        assert_eq!(engine.get_batch_communication_test_config("batch-1").await, None);
+    }
 }
@@ -10,16 +10,8 @@ use crate::models::test_plc_config::*;
 use crate::domain::services::BaseService;
 use crate::infrastructure::IPersistenceService;
 use crate::utils::error::{AppError, AppResult};
-use crate::domain::services::plc_communication_service::{IPlcCommunicationService, PlcConnectionConfig as DomainPlcConnectionConfig, PlcProtocol, ConnectionTestResult};
-
-/// Modbus寄存器类型枚举
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum ModbusRegisterType {
-    Coil,                  // 线圈 (0xxxxx)
-    DiscreteInput,         // 离散输入 (1xxxxx)
-    InputRegister,         // 输入寄存器 (3xxxxx)
-    HoldingRegister,       // 保持寄存器 (4xxxxx)
-}
+use crate::domain::services::plc_communication_service::{IPlcCommunicationService, PlcConnectionConfig as DomainPlcConnectionConfig, PlcProtocol, ConnectionTestResult, PlcDataType};
+use crate::infrastructure::plc_communication::{ModbusAddress, ModbusRegisterType};
 
 /// 测试PLC配置管理服务接口
 #[async_trait]
@@ -207,6 +199,11 @@ impl TestPlcConfigService {
                 is_enabled: true,
                 last_connected: None,
                 connection_status: ConnectionStatus::Disconnected,
+                serial_settings: None,
+                s7_settings: None,
+                opcua_settings: None,
+                modbus_data_settings: None,
+                modbus_scheduler_settings: None,
             },
             PlcConnectionConfig {
                 id: "target_plc_1".to_string(),
@@ -223,6 +220,11 @@ impl TestPlcConfigService {
                 is_enabled: true,
                 last_connected: None,
                 connection_status: ConnectionStatus::Disconnected,
+                serial_settings: None,
+                s7_settings: None,
+                opcua_settings: None,
+                modbus_data_settings: None,
+                modbus_scheduler_settings: None,
             },
         ]
     }
@@ -330,18 +332,26 @@ impl ITestPlcConfigService for TestPlcConfigService {
         Ok(connections)
     }
 
-    async fn save_plc_connection(&self, connection: PlcConnectionConfig) -> AppResult<PlcConnectionConfig> {
+    async fn save_plc_connection(&self, mut connection: PlcConnectionConfig) -> AppResult<PlcConnectionConfig> {
         debug!("保存PLC连接配置: {:?}", connection.name);
-        
+
         // 验证必填字段
         if connection.name.is_empty() {
             return Err(AppError::validation_error("连接名称不能为空".to_string()));
         }
-        
-        if connection.ip_address.is_empty() {
-            return Err(AppError::validation_error("IP地址不能为空".to_string()));
+
+        connection.validate_endpoint().map_err(AppError::validation_error)?;
+
+        // 界面不会拿到已保存的OPC UA密码，未重新填写时沿用数据库中的密码
+        if let Some(opcua) = connection.opcua_settings.as_mut() {
+            if opcua.auth_mode == OpcUaAuthMode::UserName && opcua.password.is_none() {
+                opcua.password = self.persistence_service.load_plc_connection(&connection.id).await?
+                    .and_then(|stored| stored.opcua_settings)
+                    .and_then(|stored| stored.password);
+            }
+            opcua.password_saved = opcua.password.is_some();
         }
-        
+
         // 保存到数据库
         self.persistence_service.save_plc_connection(&connection).await?;
         
@@ -363,6 +373,15 @@ impl ITestPlcConfigService for TestPlcConfigService {
             crate::models::test_plc_config::PlcType::ModbusTcp => {
                 self.test_modbus_tcp_connection(&connection_config).await
             }
+            crate::models::test_plc_config::PlcType::ModbusRtu => {
+                self.test_modbus_rtu_connection(&connection_config).await
+            }
+            crate::models::test_plc_config::PlcType::SiemensS7 => {
+                self.test_s7_connection(&connection_config).await
+            }
+            crate::models::test_plc_config::PlcType::OpcUa => {
+                self.test_opcua_connection(&connection_config).await
+            }
 
             _ => {
                 // 其他协议暂未实现
@@ -387,6 +406,15 @@ impl ITestPlcConfigService for TestPlcConfigService {
             crate::models::test_plc_config::PlcType::ModbusTcp => {
                 self.test_modbus_tcp_connection(connection).await
             }
+            crate::models::test_plc_config::PlcType::ModbusRtu => {
+                self.test_modbus_rtu_connection(connection).await
+            }
+            crate::models::test_plc_config::PlcType::SiemensS7 => {
+                self.test_s7_connection(connection).await
+            }
+            crate::models::test_plc_config::PlcType::OpcUa => {
+                self.test_opcua_connection(connection).await
+            }
 
             _ => {
                 // 其他协议暂未实现
@@ -408,9 +436,15 @@ impl ITestPlcConfigService for TestPlcConfigService {
 
         // 根据PLC类型进行地址读取测试
         match connection.plc_type {
-            crate::models::test_plc_config::PlcType::ModbusTcp => {
+            crate::models::test_plc_config::PlcType::ModbusTcp | crate::models::test_plc_config::PlcType::ModbusRtu => {
                 self.test_modbus_address_read(connection, address, data_type).await
             }
+            crate::models::test_plc_config::PlcType::SiemensS7 => {
+                self.test_s7_address_read(connection, address, data_type).await
+            }
+            crate::models::test_plc_config::PlcType::OpcUa => {
+                self.test_opcua_address_read(connection, address, data_type).await
+            }
 
             _ => {
                 // 其他协议暂未实现
@@ -607,14 +641,15 @@ impl TestPlcConfigService {
         use tokio_modbus::prelude::*;
 
         let start_time = Instant::now();
-        debug!("开始测试Modbus地址读取: {}:{} - 地址: {}, 类型: {}", connection.ip_address, connection.port, address, data_type);
+        debug!("开始测试Modbus地址读取: {} - 地址: {}, 类型: {}", connection.endpoint(), address, data_type);
 
         // 设置超时时间
         let timeout_duration = Duration::from_millis(connection.timeout as u64);
 
         // 解析地址
-        let (register_type, register_offset) = match Self::parse_modbus_address(address, connection.zero_based_address) {
-            Ok(result) => result,
+        let m_area = connection.modbus_data_settings.as_ref().map(|d| d.m_area_addressing).unwrap_or_default();
+        let parsed = match ModbusAddress::parse_with(address, connection.zero_based_address, m_area) {
+            Ok(parsed) => parsed,
             Err(e) => {
                 return Ok(crate::models::test_plc_config::AddressReadTestResponse {
                     success: false,
@@ -625,27 +660,53 @@ impl TestPlcConfigService {
             }
         };
 
-        // 建立连接并读取数据
-        let socket_addr = format!("{}:{}", connection.ip_address, connection.port);
-        let socket_addr = match socket_addr.parse::<std::net::SocketAddr>() {
-            Ok(addr) => addr,
-            Err(e) => {
-                return Ok(crate::models::test_plc_config::AddressReadTestResponse {
-                    success: false,
-                    value: None,
-                    error: Some(format!("无效的地址格式: {}", e)),
-                    read_time_ms: Some(start_time.elapsed().as_millis() as u64),
-                });
-            }
+        // 建立连接并读取数据（TCP或RTU串口）
+        if let Err(e) = connection.validate_endpoint() {
+            return Ok(crate::models::test_plc_config::AddressReadTestResponse {
+                success: false,
+                value: None,
+                error: Some(format!("无效的连接参数: {}", e)),
+                read_time_ms: Some(start_time.elapsed().as_millis() as u64),
+            });
+        }
+        let service_config = connection.to_service_config();
+        let (register_type, register_offset) = (parsed.register_type, parsed.offset);
+        // 位地址按布尔读取，类型后缀覆盖界面选择的数据类型
+        let data_type = match (parsed.bit, parsed.data_type) {
+            (Some(_), _) => "bool".to_string(),
+            (None, Some(PlcDataType::Float32)) => "float".to_string(),
+            (None, Some(PlcDataType::Float64)) => "double".to_string(),
+            (None, Some(PlcDataType::Int32)) => "dint".to_string(),
+            _ => data_type.to_lowercase(),
         };
+        let byte_order = <crate::models::ByteOrder as std::str::FromStr>::from_str(&connection.byte_order).unwrap_or_default();
+        let word_swap_64 = connection.modbus_data_settings.as_ref().map(|d| d.word_swap_64).unwrap_or(false);
 
         let read_result = timeout(timeout_duration, async {
             // 建立Modbus连接
-            let mut ctx = tokio_modbus::client::tcp::connect_slave(socket_addr, Slave(1)).await
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::ConnectionRefused, format!("Modbus连接失败: {:?}", e)))?;
+            let mut ctx = crate::infrastructure::plc_communication::open_modbus_context(&service_config).await
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::ConnectionRefused, format!("Modbus连接失败: {}", e)))?;
 
             // 根据寄存器类型和数据类型进行读取
-            match (register_type, data_type.to_lowercase().as_str()) {
+            match (register_type, data_type.as_str()) {
+                (ModbusRegisterType::HoldingRegister | ModbusRegisterType::InputRegister, "bool") => {
+                    let regs_vec = Self::read_modbus_registers(&mut ctx, register_type, register_offset, 1).await?;
+                    let bit = parsed.bit.unwrap_or(0);
+                    Ok(serde_json::Value::Bool(regs_vec[0] >> bit & 1 == 1))
+                }
+                (ModbusRegisterType::HoldingRegister | ModbusRegisterType::InputRegister, "double") => {
+                    let regs_vec = Self::read_modbus_registers(&mut ctx, register_type, register_offset, 4).await?;
+                    let bytes = crate::infrastructure::plc_communication::ByteOrderConverter::registers_to_bytes(&regs_vec, byte_order, word_swap_64);
+                    let value = f64::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]);
+                    let number = serde_json::Number::from_f64(value)
+                        .unwrap_or_else(|| serde_json::Number::from(0i64));
+                    Ok(serde_json::Value::Number(number))
+                }
+                (ModbusRegisterType::HoldingRegister | ModbusRegisterType::InputRegister, "dint") => {
+                    let regs_vec = Self::read_modbus_registers(&mut ctx, register_type, register_offset, 2).await?;
+                    let value = crate::infrastructure::plc_communication::ByteOrderConverter::registers_to_i32(regs_vec[0], regs_vec[1], byte_order);
+                    Ok(serde_json::Value::Number(serde_json::Number::from(value)))
+                }
                 (ModbusRegisterType::Coil, "bool") => {
                     // 先获取线圈向量，再取首值
                     let coils_vec: Vec<bool> = ctx.read_coils(register_offset, 1).await
@@ -759,34 +820,23 @@ impl TestPlcConfigService {
         }
     }
 
-    /// 解析Modbus地址
-    fn parse_modbus_address(address: &str, zero_based: bool) -> Result<(ModbusRegisterType, u16), String> {
-        if address.len() < 2 {
-            return Err("地址长度不足".to_string());
-        }
-
-        let prefix = &address[..1];
-        let number_part = &address[1..];
-        
-        let number: u16 = number_part.parse()
-            .map_err(|_| format!("无效的地址数字: {}", number_part))?;
-
-        // 根据是否为0基地址计算偏移量
-        // 1基地址(标准Modbus): 地址1对应寄存器0, 所以偏移量是地址-1
-        // 0基地址: 地址0对应寄存器0, 所以偏移量就是地址本身
-        let offset = if zero_based {
-            number
-        } else {
-            number.saturating_sub(1)
+    /// 读取指定数量的输入/保持寄存器，应答不足时返回错误
+    async fn read_modbus_registers(ctx: &mut tokio_modbus::client::Context, register_type: ModbusRegisterType, offset: u16, count: u16) -> std::io::Result<Vec<u16>> {
+        use tokio_modbus::prelude::*;
+        let response = match register_type {
+            ModbusRegisterType::InputRegister => ctx.read_input_registers(offset, count).await,
+            _ => ctx.read_holding_registers(offset, count).await,
         };
-
-        match prefix {
-            "0" => Ok((ModbusRegisterType::Coil, offset)),
-            "1" => Ok((ModbusRegisterType::DiscreteInput, offset)),
-            "3" => Ok((ModbusRegisterType::InputRegister, offset)),
-            "4" => Ok((ModbusRegisterType::HoldingRegister, offset)),
-            _ => Err(format!("不支持的地址前缀: {}", prefix)),
+        let regs_vec: Vec<u16> = response
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("读取寄存器IO失败: {:?}", e)))?
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, format!("读取寄存器Modbus异常: {:?}", e)))?;
+        if regs_vec.len() < count as usize {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "读取的寄存器数据不足",
+            ));
         }
+        Ok(regs_vec)
     }
 
     /// 将寄存器数据转换为float32
@@ -898,6 +948,255 @@ impl TestPlcConfigService {
         }
     }
 
+    /// 测试Modbus RTU串口连接：打开串口并读取一个保持寄存器
+    async fn test_modbus_rtu_connection(&self, config: &crate::models::test_plc_config::PlcConnectionConfig) -> AppResult<TestPlcConnectionResponse> {
+        use std::time::Instant;
+        use tokio::time::{timeout, Duration};
+        use tokio_modbus::prelude::*;
+
+        let start_time = Instant::now();
+        debug!("开始测试Modbus RTU连接: {}", config.endpoint());
+
+        if let Err(e) = config.validate_endpoint() {
+            return Ok(TestPlcConnectionResponse {
+                success: false,
+                message: e,
+                connection_time_ms: Some(start_time.elapsed().as_millis() as u64),
+            });
+        }
+
+        // 串口打开成功不代表从站在线，需读取一次确认从站应答
+        let mut ctx = match crate::infrastructure::plc_communication::open_modbus_context(&config.to_service_config()).await {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                warn!("Modbus RTU串口打开失败: {}", e);
+                return Ok(TestPlcConnectionResponse {
+                    success: false,
+                    message: format!("串口打开失败: {}", e),
+                    connection_time_ms: Some(start_time.elapsed().as_millis() as u64),
+                });
+            }
+        };
+        let read_result = timeout(Duration::from_millis(config.timeout as u64), ctx.read_holding_registers(0, 1)).await;
+        let elapsed_ms = start_time.elapsed().as_millis() as u64;
+
+        let (success, message) = match read_result {
+            Ok(Ok(Ok(_))) => (true, format!("Modbus RTU连接测试成功 ({}ms)", elapsed_ms)),
+            // 从站返回异常码说明通信正常，只是地址不支持
+            Ok(Ok(Err(exception))) => (true, format!("Modbus RTU从站已应答，测试读取返回异常: {:?} ({}ms)", exception, elapsed_ms)),
+            Ok(Err(e)) => (false, format!("连接测试失败: {}", e)),
+            Err(_) => (false, format!("从站无应答 ({}ms)，请检查从站地址、波特率和校验位", elapsed_ms)),
+        };
+        if success {
+            debug!("{}", message);
+        } else {
+            warn!("Modbus RTU连接测试失败: {}", message);
+        }
+
+        Ok(TestPlcConnectionResponse {
+            success,
+            message,
+            connection_time_ms: Some(elapsed_ms),
+        })
+    }
+
+    /// 测试西门子S7连接：完成COTP连接与S7通信参数协商
+    async fn test_s7_connection(&self, config: &crate::models::test_plc_config::PlcConnectionConfig) -> AppResult<TestPlcConnectionResponse> {
+        debug!("开始测试S7连接: {}", config.endpoint());
+
+        if let Err(e) = config.validate_endpoint() {
+            return Ok(TestPlcConnectionResponse {
+                success: false,
+                message: e,
+                connection_time_ms: None,
+            });
+        }
+
+        let s7_service = crate::infrastructure::S7PlcService::new();
+        let result = s7_service.test_connection(&config.to_service_config()).await?;
+        let message = if result.success {
+            format!("{}连接测试成功 ({}ms)", result.protocol_info.unwrap_or_default(), result.connection_time_ms)
+        } else {
+            warn!("S7连接测试失败: {:?}", result.error_message);
+            format!("连接测试失败: {}", result.error_message.unwrap_or_default())
+        };
+
+        Ok(TestPlcConnectionResponse {
+            success: result.success,
+            message,
+            connection_time_ms: Some(result.connection_time_ms),
+        })
+    }
+
+    /// 测试西门子S7地址读取，地址格式如 DB1.DBD0、%MD100、%I0.0
+    async fn test_s7_address_read(&self, connection: &PlcConnectionConfig, address: &str, data_type: &str) -> AppResult<crate::models::test_plc_config::AddressReadTestResponse> {
+        use crate::domain::services::plc_communication_service::PlcDataType;
+        use crate::infrastructure::{S7Address, S7PlcService};
+        use crate::infrastructure::s7_communication::S7Width;
+
+        let start_time = std::time::Instant::now();
+        debug!("开始测试S7地址读取: {} - 地址: {}, 类型: {}", connection.endpoint(), address, data_type);
+
+        let failed = |error: String, read_time_ms: Option<u64>| crate::models::test_plc_config::AddressReadTestResponse {
+            success: false,
+            value: None,
+            error: Some(error),
+            read_time_ms,
+        };
+
+        let parsed = match S7Address::parse(address) {
+            Ok(parsed) => parsed,
+            Err(e) => return Ok(failed(format!("地址解析失败: {}", e), None)),
+        };
+        let plc_data_type = match (data_type, parsed.width) {
+            ("bool", _) => PlcDataType::Bool,
+            ("float", _) => PlcDataType::Float32,
+            ("int", S7Width::DWord) => PlcDataType::Int32,
+            ("int", _) => PlcDataType::Int16,
+            _ => return Ok(failed(format!("不支持的数据类型: {}", data_type), None)),
+        };
+
+        let s7_service = S7PlcService::new();
+        let read_result = async {
+            let handle = s7_service.connect(&connection.to_service_config()).await?;
+            let request = crate::domain::services::plc_communication_service::ReadRequest {
+                id: address.to_string(),
+                address: address.to_string(),
+                data_type: plc_data_type,
+                array_length: None,
+            };
+            let result = s7_service.batch_read(&handle, &[request]).await?;
+            let _ = s7_service.disconnect(&handle).await;
+            Ok::<_, AppError>(result.into_iter().next())
+        }.await;
+        let elapsed_ms = start_time.elapsed().as_millis() as u64;
+
+        let value = match read_result {
+            Ok(Some(result)) if result.success => result.value,
+            Ok(Some(result)) => return Ok(failed(format!("读取失败: {}", result.error_message.unwrap_or_default()), Some(elapsed_ms))),
+            Ok(None) => return Ok(failed("读取失败: 无应答数据".to_string(), Some(elapsed_ms))),
+            Err(e) => {
+                warn!("S7地址读取失败: {} - {}", address, e);
+                return Ok(failed(format!("读取失败: {}", e), Some(elapsed_ms)));
+            }
+        };
+
+        let json_value = match value {
+            Some(crate::domain::services::plc_communication_service::PlcValue::Bool(v)) => serde_json::Value::Bool(v),
+            Some(crate::domain::services::plc_communication_service::PlcValue::Int16(v)) => serde_json::Value::from(v),
+            Some(crate::domain::services::plc_communication_service::PlcValue::Int32(v)) => serde_json::Value::from(v),
+            Some(crate::domain::services::plc_communication_service::PlcValue::Float32(v)) => serde_json::Number::from_f64(v as f64)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            other => serde_json::Value::String(format!("{:?}", other)),
+        };
+        info!("地址读取成功: {} = {:?}", address, json_value);
+        Ok(crate::models::test_plc_config::AddressReadTestResponse {
+            success: true,
+            value: Some(json_value),
+            error: None,
+            read_time_ms: Some(elapsed_ms),
+        })
+    }
+
+    /// 测试OPC UA连接：打开安全通道并以配置的身份激活会话
+    async fn test_opcua_connection(&self, config: &crate::models::test_plc_config::PlcConnectionConfig) -> AppResult<TestPlcConnectionResponse> {
+        debug!("开始测试OPC UA连接: {}", config.endpoint());
+
+        if let Err(e) = config.validate_endpoint() {
+            return Ok(TestPlcConnectionResponse {
+                success: false,
+                message: e,
+                connection_time_ms: None,
+            });
+        }
+
+        let opcua_service = crate::infrastructure::OpcUaPlcService::new();
+        let result = opcua_service.test_connection(&config.to_service_config()).await?;
+        let message = if result.success {
+            format!("{}连接测试成功 ({}ms)", result.protocol_info.unwrap_or_default(), result.connection_time_ms)
+        } else {
+            warn!("OPC UA连接测试失败: {:?}", result.error_message);
+            format!("连接测试失败: {}", result.error_message.unwrap_or_default())
+        };
+
+        Ok(TestPlcConnectionResponse {
+            success: result.success,
+            message,
+            connection_time_ms: Some(result.connection_time_ms),
+        })
+    }
+
+    /// 测试OPC UA地址读取，地址为NodeId（如 ns=2;s=Tank.Level）或浏览路径（如 Tank/Level）
+    async fn test_opcua_address_read(&self, connection: &PlcConnectionConfig, address: &str, data_type: &str) -> AppResult<crate::models::test_plc_config::AddressReadTestResponse> {
+        use crate::domain::services::plc_communication_service::{PlcDataType, PlcValue};
+        use crate::infrastructure::OpcUaPlcService;
+
+        let start_time = std::time::Instant::now();
+        debug!("开始测试OPC UA地址读取: {} - 地址: {}, 类型: {}", connection.endpoint(), address, data_type);
+
+        let failed = |error: String, read_time_ms: Option<u64>| crate::models::test_plc_config::AddressReadTestResponse {
+            success: false,
+            value: None,
+            error: Some(error),
+            read_time_ms,
+        };
+
+        let plc_data_type = match data_type {
+            "bool" => PlcDataType::Bool,
+            "float" => PlcDataType::Float32,
+            "double" => PlcDataType::Float64,
+            "int" => PlcDataType::Int32,
+            "string" => PlcDataType::String,
+            _ => return Ok(failed(format!("不支持的数据类型: {}", data_type), None)),
+        };
+
+        let opcua_service = OpcUaPlcService::new();
+        let read_result = async {
+            let handle = opcua_service.connect(&connection.to_service_config()).await?;
+            let request = crate::domain::services::plc_communication_service::ReadRequest {
+                id: address.to_string(),
+                address: address.to_string(),
+                data_type: plc_data_type,
+                array_length: None,
+            };
+            let result = opcua_service.batch_read(&handle, &[request]).await?;
+            let _ = opcua_service.disconnect(&handle).await;
+            Ok::<_, AppError>(result.into_iter().next())
+        }.await;
+        let elapsed_ms = start_time.elapsed().as_millis() as u64;
+
+        let value = match read_result {
+            Ok(Some(result)) if result.success => result.value,
+            Ok(Some(result)) => return Ok(failed(format!("读取失败: {}", result.error_message.unwrap_or_default()), Some(elapsed_ms))),
+            Ok(None) => return Ok(failed("读取失败: 无应答数据".to_string(), Some(elapsed_ms))),
+            Err(e) => {
+                warn!("OPC UA地址读取失败: {} - {}", address, e);
+                return Ok(failed(format!("读取失败: {}", e), Some(elapsed_ms)));
+            }
+        };
+
+        let json_value = match value {
+            Some(PlcValue::Bool(v)) => serde_json::Value::Bool(v),
+            Some(PlcValue::Int32(v)) => serde_json::Value::from(v),
+            Some(PlcValue::Float32(v)) => serde_json::Number::from_f64(v as f64)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Some(PlcValue::Float64(v)) => serde_json::Number::from_f64(v)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Some(PlcValue::String(v)) => serde_json::Value::String(v),
+            other => serde_json::Value::String(format!("{:?}", other)),
+        };
+        info!("地址读取成功: {} = {:?}", address, json_value);
+        Ok(crate::models::test_plc_config::AddressReadTestResponse {
+            success: true,
+            value: Some(json_value),
+            error: None,
+            read_time_ms: Some(elapsed_ms),
+        })
+    }
+
     /// 智能映射生成：实现有源/无源匹配逻辑
     fn generate_intelligent_mappings(
         target_definitions: &[crate::models::ChannelPointDefinition],
@@ -0,0 +1,372 @@
+//! # 接线检查扫描 (Wiring Discovery Sweep)
+//!
+//! ## 业务说明
+//! 接线交叉时，硬点测试只会在两个通道上分别报出笼统的失败。接线检查在正式测试前
+//! 逐个驱动测试PLC的输出通道，同时批量读取批次内全部被测输入通道，得到
+//! "期望连接的测试PLC通道 vs 实际响应的测试PLC通道"对照表，并给出互换建议。
+//!
+//! ## 扫描方式
+//! 只驱动批次内被测通道期望连接的测试PLC通道（通道映射或批次分配），
+//! 不动其他批次或未使用的测试PLC输出；扫描结束或中途出错时全部复位。
+//! - **DI**: 测试PLC DO逐个置位，读取全部被测DI，由低变高的通道即为响应通道
+//! - **AI**: 测试PLC AO逐个输出互不相同的百分比，读取全部被测AI，
+//!   换算为量程百分比后落在容差内的通道即为响应通道
+//!
+//! ## 调用链
+//! ```
+//! run_wiring_check_cmd → WiringDiscoverySweep::run → batch_read → WiringCheckReport
+//! ```
+
+use crate::domain::services::plc_communication_service::{
+    ConnectionHandle, PlcDataType, PlcValue, ReadRequest, WriteRequest,
+};
+use crate::infrastructure::plc_communication::IPlcCommunicationService;
+use crate::models::{ModuleType, WiringChannelResult, WiringCheckReport, WiringCheckSettings};
+use crate::utils::error::{AppError, AppResult};
+use chrono::Utc;
+use log::{debug, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 参与接线检查的被测输入通道（DI/AI）
+#[derive(Debug, Clone)]
+pub struct WiringTargetChannel {
+    pub instance_id: String,
+    pub tag: String,
+    pub module_type: ModuleType,
+    /// 被测PLC通信地址
+    pub target_address: String,
+    /// 期望连接的测试PLC通道位号
+    pub expected_test_rig_channel: Option<String>,
+    /// 量程下限（AI）
+    pub range_low: f64,
+    /// 量程上限（AI）
+    pub range_high: f64,
+}
+
+impl WiringTargetChannel {
+    fn is_analog(&self) -> bool {
+        matches!(self.module_type, ModuleType::AI | ModuleType::AINone)
+    }
+}
+
+/// 参与接线检查的测试PLC输出通道（DO/AO）
+#[derive(Debug, Clone)]
+pub struct WiringRigOutput {
+    /// 测试PLC通道位号（如 DO1_1、AO1_2）
+    pub channel_tag: String,
+    /// 测试PLC通信地址
+    pub address: String,
+    /// 是否为模拟量输出
+    pub is_analog: bool,
+}
+
+/// 接线检查扫描器
+pub struct WiringDiscoverySweep {
+    plc_service: Arc<dyn IPlcCommunicationService>,
+    test_rig_conn_id: String,
+    target_conn_id: String,
+    settings: WiringCheckSettings,
+}
+
+impl WiringDiscoverySweep {
+    pub fn new(
+        plc_service: Arc<dyn IPlcCommunicationService>,
+        test_rig_conn_id: String,
+        target_conn_id: String,
+        settings: WiringCheckSettings,
+    ) -> Self {
+        Self {
+            plc_service,
+            test_rig_conn_id,
+            target_conn_id,
+            settings,
+        }
+    }
+
+    /// 执行接线检查扫描
+    pub async fn run(
+        &self,
+        batch_id: &str,
+        targets: &[WiringTargetChannel],
+        rig_outputs: &[WiringRigOutput],
+    ) -> AppResult<WiringCheckReport> {
+        let started_at = Utc::now();
+        let rig_handle = self.handle(&self.test_rig_conn_id).await?;
+        let target_handle = self.handle(&self.target_conn_id).await?;
+
+        let rig_outputs = Self::batch_outputs(targets, rig_outputs);
+        let (analog_targets, digital_targets): (Vec<&WiringTargetChannel>, Vec<&WiringTargetChannel>) =
+            targets.iter().partition(|t| t.is_analog());
+        let (analog_outputs, digital_outputs): (Vec<&WiringRigOutput>, Vec<&WiringRigOutput>) =
+            rig_outputs.iter().copied().partition(|o| o.is_analog);
+
+        info!("🔌 接线检查开始: 批次 {}，被测DI {} 个 / AI {} 个，测试PLC DO {} 个 / AO {} 个",
+              batch_id, digital_targets.len(), analog_targets.len(), digital_outputs.len(), analog_outputs.len());
+
+        let mut reset_guard = OutputResetGuard::new(self.plc_service.clone(), rig_handle.clone(), &rig_outputs);
+        let mut responses: HashMap<String, Vec<String>> = HashMap::new();
+        let swept = async {
+            if !digital_targets.is_empty() && !digital_outputs.is_empty() {
+                self.sweep_digital(&rig_handle, &target_handle, &digital_targets, &digital_outputs, &mut responses).await?;
+            }
+            if !analog_targets.is_empty() && !analog_outputs.is_empty() {
+                self.sweep_analog(&rig_handle, &target_handle, &analog_targets, &analog_outputs, &mut responses).await?;
+            }
+            AppResult::Ok(())
+        }
+        .await;
+        // 无论扫描成功与否都复位参与扫描的输出，复位失败时保留扫描本身的错误
+        let reset = reset_guard.reset().await;
+        swept?;
+        reset?;
+
+        let channels: Vec<WiringChannelResult> = targets
+            .iter()
+            .map(|t| {
+                let responding = responses.remove(&t.instance_id).unwrap_or_default();
+                WiringChannelResult {
+                    instance_id: t.instance_id.clone(),
+                    tag: t.tag.clone(),
+                    module_type: t.module_type.clone(),
+                    status: WiringChannelResult::classify(t.expected_test_rig_channel.as_deref(), &responding),
+                    expected_test_rig_channel: t.expected_test_rig_channel.clone(),
+                    responding_test_rig_channels: responding,
+                }
+            })
+            .collect();
+
+        let report = WiringCheckReport::new(batch_id.to_string(), started_at, channels);
+        info!("✅ 接线检查完成: 正确 {} 个，需确认 {} 个，互换建议 {} 条",
+              report.correct_count, report.problem_count, report.swap_suggestions.len());
+        Ok(report)
+    }
+
+    /// DO逐个置位，记录由低变高的被测DI
+    async fn sweep_digital(
+        &self,
+        rig_handle: &ConnectionHandle,
+        target_handle: &ConnectionHandle,
+        targets: &[&WiringTargetChannel],
+        outputs: &[&WiringRigOutput],
+        responses: &mut HashMap<String, Vec<String>>,
+    ) -> AppResult<()> {
+        // 全部DO复位，读取基线
+        let reset: Vec<(&WiringRigOutput, PlcValue)> = outputs.iter().map(|o| (*o, PlcValue::Bool(false))).collect();
+        self.write_outputs(rig_handle, &reset).await?;
+        self.dwell().await;
+        let baseline = self.read_targets(target_handle, targets, PlcDataType::Bool).await?;
+        for target in targets {
+            if matches!(baseline.get(&target.instance_id), Some(PlcValue::Bool(true))) {
+                warn!("⚠️ 接线检查: {} 在全部DO复位时仍为高电平", target.tag);
+            }
+        }
+
+        for output in outputs {
+            debug!("接线检查: 置位 {} [{}]", output.channel_tag, output.address);
+            self.write_outputs(rig_handle, &[(*output, PlcValue::Bool(true))]).await?;
+            self.dwell().await;
+            let values = self.read_targets(target_handle, targets, PlcDataType::Bool).await?;
+
+            for target in targets {
+                let was_low = !matches!(baseline.get(&target.instance_id), Some(PlcValue::Bool(true)));
+                if was_low && matches!(values.get(&target.instance_id), Some(PlcValue::Bool(true))) {
+                    responses.entry(target.instance_id.clone()).or_default().push(output.channel_tag.clone());
+                }
+            }
+
+            self.write_outputs(rig_handle, &[(*output, PlcValue::Bool(false))]).await?;
+        }
+        Ok(())
+    }
+
+    /// AO逐个输出互不相同的百分比，记录读数落在容差内的被测AI
+    async fn sweep_analog(
+        &self,
+        rig_handle: &ConnectionHandle,
+        target_handle: &ConnectionHandle,
+        targets: &[&WiringTargetChannel],
+        outputs: &[&WiringRigOutput],
+        responses: &mut HashMap<String, Vec<String>>,
+    ) -> AppResult<()> {
+        let reset: Vec<(&WiringRigOutput, PlcValue)> = outputs.iter().map(|o| (*o, PlcValue::Float32(0.0))).collect();
+        self.write_outputs(rig_handle, &reset).await?;
+
+        for (index, output) in outputs.iter().enumerate() {
+            let percent = Self::distinct_percent(index, outputs.len());
+            debug!("接线检查: {} [{}] 输出 {:.1}%", output.channel_tag, output.address, percent);
+            self.write_outputs(rig_handle, &[(*output, PlcValue::Float32(percent as f32))]).await?;
+            self.dwell().await;
+            let values = self.read_targets(target_handle, targets, PlcDataType::Float32).await?;
+
+            for target in targets {
+                let span = target.range_high - target.range_low;
+                if span <= 0.0 {
+                    continue;
+                }
+                if let Some(PlcValue::Float32(reading)) = values.get(&target.instance_id) {
+                    let reading_percent = (*reading as f64 - target.range_low) / span * 100.0;
+                    if (reading_percent - percent).abs() <= self.settings.analog_tolerance_percent {
+                        responses.entry(target.instance_id.clone()).or_default().push(output.channel_tag.clone());
+                    }
+                }
+            }
+
+            self.write_outputs(rig_handle, &[(*output, PlcValue::Float32(0.0))]).await?;
+        }
+        Ok(())
+    }
+
+    /// 只保留批次内被测通道期望连接的测试PLC输出，保持测试PLC通道表顺序
+    fn batch_outputs<'a>(targets: &[WiringTargetChannel], rig_outputs: &'a [WiringRigOutput]) -> Vec<&'a WiringRigOutput> {
+        let expected: HashSet<&str> = targets
+            .iter()
+            .filter_map(|t| t.expected_test_rig_channel.as_deref())
+            .collect();
+        let scoped: Vec<&WiringRigOutput> = rig_outputs
+            .iter()
+            .filter(|o| expected.contains(o.channel_tag.as_str()))
+            .collect();
+        if scoped.len() < rig_outputs.len() {
+            debug!("接线检查: 跳过 {} 个未分配给本批次的测试PLC输出", rig_outputs.len() - scoped.len());
+        }
+        scoped
+    }
+
+    /// 第 index 个AO的输出百分比：在 25%~75% 之间均匀分布，避免与复位值混淆
+    fn distinct_percent(index: usize, count: usize) -> f64 {
+        if count <= 1 {
+            return 50.0;
+        }
+        25.0 + 50.0 * index as f64 / (count - 1) as f64
+    }
+
+    async fn handle(&self, connection_id: &str) -> AppResult<ConnectionHandle> {
+        self.plc_service
+            .default_handle_by_id(connection_id)
+            .await
+            .ok_or_else(|| AppError::plc_communication_error(format!("PLC连接未建立: {}", connection_id)))
+    }
+
+    async fn dwell(&self) {
+        tokio::time::sleep(Duration::from_millis(self.settings.dwell_ms)).await;
+    }
+
+    async fn write_outputs(&self, handle: &ConnectionHandle, writes: &[(&WiringRigOutput, PlcValue)]) -> AppResult<()> {
+        let requests: Vec<WriteRequest> = writes
+            .iter()
+            .map(|(output, value)| WriteRequest {
+                id: output.channel_tag.clone(),
+                address: output.address.clone(),
+                value: value.clone(),
+            })
+            .collect();
+        write_all(self.plc_service.as_ref(), handle, &requests).await
+    }
+
+    /// 批量读取被测通道，返回 instance_id → 读数；读取失败的通道不计入
+    async fn read_targets(
+        &self,
+        handle: &ConnectionHandle,
+        targets: &[&WiringTargetChannel],
+        data_type: PlcDataType,
+    ) -> AppResult<HashMap<String, PlcValue>> {
+        let requests: Vec<ReadRequest> = targets
+            .iter()
+            .map(|t| ReadRequest {
+                id: t.instance_id.clone(),
+                address: t.target_address.clone(),
+                data_type: data_type.clone(),
+                array_length: None,
+            })
+            .collect();
+        let results = self.plc_service.batch_read(handle, &requests).await?;
+
+        let mut values = HashMap::with_capacity(results.len());
+        for result in results {
+            match result.value {
+                Some(value) if result.success => {
+                    values.insert(result.request_id, value);
+                }
+                _ => warn!("⚠️ 接线检查读取失败: {} - {}", result.request_id, result.error_message.unwrap_or_default()),
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// 批量写入，任一点写入失败即返回错误
+async fn write_all(
+    plc_service: &dyn IPlcCommunicationService,
+    handle: &ConnectionHandle,
+    requests: &[WriteRequest],
+) -> AppResult<()> {
+    let results = plc_service.batch_write(handle, requests).await?;
+    if let Some(failed) = results.iter().find(|r| !r.success) {
+        return Err(AppError::plc_communication_error(format!(
+            "写入测试PLC通道 {} 失败: {}",
+            failed.request_id,
+            failed.error_message.clone().unwrap_or_default()
+        )));
+    }
+    Ok(())
+}
+
+/// 扫描期间驱动过的测试PLC输出复位守卫
+///
+/// 正常结束和出错时由 `reset` 显式复位；扫描任务被取消（例如命令被中断）而未复位时，
+/// 在析构时另起任务补做复位，避免测试PLC输出停留在置位状态
+struct OutputResetGuard {
+    plc_service: Arc<dyn IPlcCommunicationService>,
+    handle: ConnectionHandle,
+    requests: Vec<WriteRequest>,
+    armed: bool,
+}
+
+impl OutputResetGuard {
+    fn new(plc_service: Arc<dyn IPlcCommunicationService>, handle: ConnectionHandle, outputs: &[&WiringRigOutput]) -> Self {
+        let requests = outputs
+            .iter()
+            .map(|o| WriteRequest {
+                id: o.channel_tag.clone(),
+                address: o.address.clone(),
+                value: if o.is_analog { PlcValue::Float32(0.0) } else { PlcValue::Bool(false) },
+            })
+            .collect();
+        Self { plc_service, handle, requests, armed: true }
+    }
+
+    async fn reset(&mut self) -> AppResult<()> {
+        self.armed = false;
+        if self.requests.is_empty() {
+            return Ok(());
+        }
+        let result = write_all(self.plc_service.as_ref(), &self.handle, &self.requests).await;
+        if let Err(e) = &result {
+            warn!("⚠️ 接线检查复位测试PLC输出失败: {}", e);
+        }
+        result
+    }
+}
+
+impl Drop for OutputResetGuard {
+    fn drop(&mut self) {
+        if !self.armed || self.requests.is_empty() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("⚠️ 接线检查中断且无法复位测试PLC输出");
+            return;
+        };
+        let plc_service = self.plc_service.clone();
+        let handle = self.handle.clone();
+        let requests = std::mem::take(&mut self.requests);
+        warn!("⚠️ 接线检查中断，复位测试PLC输出 {} 个", requests.len());
+        runtime.spawn(async move {
+            if let Err(e) = write_all(plc_service.as_ref(), &handle, &requests).await {
+                warn!("⚠️ 接线检查复位测试PLC输出失败: {}", e);
+            }
+        });
+    }
+}
@@ -575,6 +575,18 @@ pub struct RetentionPolicy {
     
     /// 最大存储大小（字节）
     pub max_storage_size_bytes: Option<u64>,
+
+    /// 监控采样数据（读数序列、临时数据）保留天数
+    #[serde(default)]
+    pub monitoring_samples_retention_days: u32,
+
+    /// 过期批次只保留最终结果（批次、实例及每个子测试的最后一次结果），不整体删除
+    #[serde(default)]
+    pub keep_final_results_only: bool,
+
+    /// 删除前把记录归档到独立的SQLite文件
+    #[serde(default)]
+    pub archive_before_delete: bool,
 }
 
 /// 清理结果
@@ -594,4 +606,16 @@ pub struct CleanupResult {
     
     /// 清理时间
     pub cleanup_time: DateTime<Utc>,
+
+    /// 归档的记录数
+    #[serde(default)]
+    pub archived_records: u64,
+
+    /// 清除了采样数据的记录数
+    #[serde(default)]
+    pub cleared_samples: u64,
+
+    /// 归档文件路径
+    #[serde(default)]
+    pub archive_path: Option<String>,
 }
@@ -30,6 +30,8 @@ use std::collections::HashMap;
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
 use crate::domain::impls::plc_connection_manager::PlcConnectionManager;
+use crate::models::FailureKind;
+use crate::error::AppError;
 
 /// 全局PLC连接管理器
 ///
@@ -158,6 +160,24 @@ pub trait IPlcCommunicationService: BaseService {
     /// **返回值**:
     /// * `bool` - true表示已连接，false表示未连接
     async fn is_connected(&self, handle: &ConnectionHandle) -> AppResult<bool>;
+
+    /// 探测连接
+    ///
+    /// **业务逻辑**: 执行一次轻量通信确认链路可用，供心跳检测发现空闲期间断开的连接
+    /// **默认实现**: 按 `is_connected` 判断，能主动通信的协议应覆盖此方法
+    ///
+    /// **参数**:
+    /// * `handle` - 要探测的连接句柄
+    ///
+    /// **返回值**:
+    /// * 链路不可用时返回通信错误
+    async fn probe(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        if self.is_connected(handle).await? {
+            Ok(())
+        } else {
+            Err(AppError::plc_communication_error(format!("连接已断开: {}", handle.connection_id)))
+        }
+    }
     
     /// 读取布尔值
     ///
@@ -299,7 +319,7 @@ pub trait IPlcCommunicationService: BaseService {
 }
 
 /// PLC连接配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlcConnectionConfig {
     /// 连接ID
     pub id: String,
@@ -338,13 +358,36 @@ pub struct PlcConnectionConfig {
     pub retry_interval_ms: u64,
     
     /// 协议特定参数
+    /// Modbus: slave_id；Modbus RTU: device_path、baud_rate、data_bits、parity、stop_bits；
+    /// 西门子S7: rack、slot、connection_type；OPC UA: endpoint_path、auth_mode、username、password
     pub protocol_params: HashMap<String, serde_json::Value>,
 }
 
+impl PlcConnectionConfig {
+    /// 连接端点描述，用于日志：TCP 为 host:port，RTU 为 串口设备@波特率，OPC UA 为 opc.tcp 端点URL
+    pub fn endpoint(&self) -> String {
+        match self.protocol {
+            PlcProtocol::OpcUa => format!(
+                "opc.tcp://{}:{}{}",
+                self.host,
+                self.port,
+                self.protocol_params.get("endpoint_path").and_then(|v| v.as_str()).unwrap_or("")
+            ),
+            PlcProtocol::ModbusRtu => format!(
+                "{}@{}",
+                self.protocol_params.get("device_path").and_then(|v| v.as_str()).unwrap_or("-"),
+                self.protocol_params.get("baud_rate").and_then(|v| v.as_u64()).unwrap_or(0)
+            ),
+            _ => format!("{}:{}", self.host, self.port),
+        }
+    }
+}
+
 /// PLC协议类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlcProtocol {
     ModbusTcp,
+    ModbusRtu,
     SiemensS7,
     OpcUa,
     EthernetIp,
@@ -546,6 +589,10 @@ pub struct ReadResult {
     /// 错误信息
     pub error_message: Option<String>,
     
+    /// 失败原因分类
+    #[serde(default)]
+    pub failure_kind: Option<FailureKind>,
+    
     /// 执行时间（毫秒）
     pub execution_time_ms: u64,
 }
@@ -562,6 +609,10 @@ pub struct WriteResult {
     /// 错误信息
     pub error_message: Option<String>,
     
+    /// 失败原因分类
+    #[serde(default)]
+    pub failure_kind: Option<FailureKind>,
+    
     /// 执行时间（毫秒）
     pub execution_time_ms: u64,
 }
@@ -572,7 +623,11 @@ pub enum PlcDataType {
     Bool,
     Int16,
     Int32,
+    UInt32,
+    Int64,
+    UInt64,
     Float32,
+    Float64,
     String,
     ByteArray,
 }
@@ -595,7 +650,9 @@ pub enum PlcDataType {
 /// - **Bool**: 对应PLC中的位/线圈/离散量
 /// - **Int16**: 对应PLC中的16位整数/字
 /// - **Int32**: 对应PLC中的32位整数/双字
+/// - **UInt32/Int64/UInt64**: 对应PLC中的无符号双字、64位计数器/累计值
 /// - **Float32**: 对应PLC中的32位浮点数/实数
+/// - **Float64**: 对应PLC中的64位浮点数/长实数
 /// - **String**: 对应PLC中的字符串数据
 /// - **ByteArray**: 对应PLC中的原始字节数据
 /// - **Array**: 对应PLC中的数组数据
@@ -631,6 +688,22 @@ pub enum PlcValue {
     /// **用途**: 大数值、时间戳、累计值
     Int32(i32),
 
+    /// 32位无符号整数
+    /// **PLC对应**: 无符号双字(UDInt)
+    /// **范围**: 0 到 4,294,967,295
+    /// **用途**: 计数器、运行时间
+    UInt32(u32),
+
+    /// 64位有符号整数
+    /// **PLC对应**: 长整数(LInt)，占4个寄存器
+    /// **用途**: 累计量、高分辨率计数
+    Int64(i64),
+
+    /// 64位无符号整数
+    /// **PLC对应**: 无符号长整数(ULInt)，占4个寄存器
+    /// **用途**: 总累计量、能耗累计
+    UInt64(u64),
+
     /// 32位浮点数
     /// **PLC对应**: 实数(Real)、浮点寄存器
     /// **精度**: IEEE 754单精度浮点数
@@ -638,6 +711,12 @@ pub enum PlcValue {
     /// **示例**: 温度、压力、流量等物理量
     Float32(f32),
 
+    /// 64位浮点数
+    /// **PLC对应**: 长实数(LReal)、OPC UA Double 变量
+    /// **精度**: IEEE 754双精度浮点数
+    /// **用途**: 累计量、高精度测量值
+    Float64(f64),
+
     /// 字符串类型
     /// **PLC对应**: 字符串变量、文本数据
     /// **编码**: 通常使用ASCII或UTF-8编码
@@ -690,6 +769,26 @@ pub struct ConnectionStats {
     
     /// 连接错误次数
     pub connection_errors: u64,
+
+    /// 当前排队等待的请求数
+    #[serde(default)]
+    pub queue_depth: u64,
+
+    /// 排队深度峰值
+    #[serde(default)]
+    pub max_queue_depth: u64,
+
+    /// 平均排队等待时间（毫秒）
+    #[serde(default)]
+    pub average_queue_wait_ms: f64,
+
+    /// 最长排队等待时间（毫秒）
+    #[serde(default)]
+    pub max_queue_wait_ms: f64,
+
+    /// 超过排队期限而放弃的请求数
+    #[serde(default)]
+    pub expired_requests: u64,
 }
 
 /// 连接测试结果
@@ -4,11 +4,13 @@ use chrono::{Local};
 use crate::utils::time_utils;
 use rust_xlsxwriter::{Workbook, Format, FormatAlign, FormatBorder, Color};
 
-use crate::models::{ChannelPointDefinition, ModuleType, ChannelTestInstance};
-use crate::models::enums::{SubTestItem, OverallTestStatus, SubTestStatus};
+use crate::models::{ChannelPointDefinition, ModuleType, ChannelTestInstance, SubTestExecutionResult};
+use crate::models::enums::{SubTestItem, OverallTestStatus, SubTestStatus, FailureKind};
 use crate::utils::error::{AppResult, AppError};
 use crate::infrastructure::IPersistenceService;
 use crate::domain::services::IChannelStateManager;
+use crate::domain::impls::{ConnectionEvent, ConnectionEventKind, PlcConnectionState};
+use crate::domain::impls::connection_quality::LATENCY_BUCKETS_MS;
 
 /// 颜色常量（柔和不刺眼）
 fn color_for_module(module_type: &ModuleType) -> Color {
@@ -22,6 +24,11 @@ fn color_for_module(module_type: &ModuleType) -> Color {
     }
 }
 
+/// 失败子测试的故障类型；未记录分类的结果按信号异常统计
+fn failure_kind_of(result: &SubTestExecutionResult) -> FailureKind {
+    result.failure_kind.unwrap_or(FailureKind::Signal)
+}
+
 /// Excel 导出服务
 pub struct ExcelExportService {
     persistence_service: Arc<dyn IPersistenceService>,
@@ -150,7 +157,15 @@ impl ExcelExportService {
         // 9. 创建错误汇总工作表
         self.create_error_summary_worksheet(&mut workbook, &instances, &def_map).await?;
 
-        // 10. 保存工作簿
+        // 10. 创建模拟量测试配置工作表（记录各批次实际使用的测试点/容差/稳定时间）
+        if !analog_instances.is_empty() {
+            self.create_analog_profile_worksheet(&mut workbook, &analog_instances).await?;
+        }
+
+        // 11. 创建连接质量工作表（测试期间的PLC掉线记录与通信质量）
+        self.create_connection_quality_worksheet(&mut workbook, &instances).await?;
+
+        // 12. 保存工作簿
         workbook.save(&output_file_path).map_err(|e| AppError::IoError { 
             message: format!("无法保存Excel文件到 {:?}: {}", output_file_path, e),
             kind: "WriteError".to_string()
@@ -197,6 +212,15 @@ impl ExcelExportService {
         }
     }
 
+    /// 格式化硬点测试结果，自动重试后的结果附带重试说明（例如"PASS (第2次重试通过)"）
+    fn format_hardpoint_result(&self, oc: &crate::models::structs::RawTestOutcome) -> String {
+        let result = if oc.success { "PASS" } else { "FAIL" };
+        match oc.retry_note() {
+            Some(note) => format!("{} ({})", result, note),
+            None => result.to_string(),
+        }
+    }
+
     /// 格式化整体测试状态
     fn format_overall_status(&self, inst: &ChannelTestInstance) -> (String, bool) {
         match inst.overall_status {
@@ -241,7 +265,8 @@ impl ExcelExportService {
             "测试ID", "测试批次", "变量名称", "点表类型", "数据类型", "测试PLC通道位号", "被测PLC通道位号", 
             "行程最小值", "行程最大值", "0%对比值", "25%对比值", "50%对比值", "75%对比值", "100%对比值", 
             "低低报反馈状态", "低报反馈状态", "高报反馈状态", "高高报反馈状态", "维护功能检测", "显示值核对",
-            "开始测试时间", "最终测试时间", "测试时长", "通道硬点测试结果", "测试结果"
+            "开始测试时间", "最终测试时间", "测试时长", "通道硬点测试结果", "测试结果",
+            "最大误差(%量程)", "增益", "零点偏移", "线性度R²", "最大回差(%量程)"
         ];
         for (col, title) in headers.iter().enumerate() {
             sheet.write_with_format(0, col as u16, *title, &header_fmt)?;
@@ -287,7 +312,7 @@ impl ExcelExportService {
                 use crate::models::enums::SubTestItem;
                 match oc.sub_test_item {
                     SubTestItem::HardPoint => {
-                        hardpoint_result = if oc.success { "PASS".into() } else { "FAIL".into() };
+                        hardpoint_result = self.format_hardpoint_result(oc);
                     }
                     // 维护功能检测可能映射为 Maintenance 或 MaintenanceFunction
                     SubTestItem::Maintenance | SubTestItem::MaintenanceFunction => {
@@ -411,6 +436,18 @@ impl ExcelExportService {
 
             needs_yellow_bg[24] = overall_yellow || overall_status == "FAIL"; // 最终测试结果列位置 (FAIL 高亮)
 
+            // 精度指标（由硬点测试扫描读数计算）
+            let accuracy_vals: Vec<String> = match &inst.analog_accuracy {
+                Some(m) => vec![
+                    format!("{:.3}", m.max_error_percent),
+                    format!("{:.4}", m.gain),
+                    format!("{:.3}", m.offset),
+                    format!("{:.5}", m.r_squared),
+                    m.max_hysteresis_percent.map(|h| format!("{:.3}", h)).unwrap_or_else(|| "-".into()),
+                ],
+                None => vec!["-".to_string(); 5],
+            };
+
             // 写入单元格 - 注意增加了显示值核对列
             let values: Vec<String> = vec![
                 test_id.to_string(),
@@ -434,6 +471,7 @@ impl ExcelExportService {
                 hardpoint_result,
                 overall_status.to_string(),
             ])
+            .chain(accuracy_vals.into_iter())
             .collect();
 
             for (col, val) in values.iter().enumerate() {
@@ -530,7 +568,7 @@ impl ExcelExportService {
                 use crate::models::enums::SubTestItem;
                 match oc.sub_test_item {
                     SubTestItem::HardPoint => {
-                        hardpoint_result = if oc.success { "PASS".into() } else { "FAIL".into() };
+                        hardpoint_result = self.format_hardpoint_result(oc);
                     }
                     SubTestItem::StateDisplay => {
                         signal_test_result = if oc.success { "PASS".into() } else { "FAIL".into() };
@@ -639,6 +677,159 @@ impl ExcelExportService {
         Ok(())
     }
 
+    /// 创建模拟量测试配置工作表 - 打印各批次的模拟量测试方案
+    async fn create_analog_profile_worksheet(
+        &self,
+        workbook: &mut Workbook,
+        analog_instances: &[&ChannelTestInstance],
+    ) -> AppResult<()> {
+        let mut sheet = workbook.add_worksheet().set_name("模拟量测试配置")?;
+
+        let header_fmt = Format::new().set_bold().set_align(FormatAlign::Center).set_border(FormatBorder::Thin);
+        let default_fmt = Format::new().set_align(FormatAlign::Center).set_border(FormatBorder::Thin);
+
+        let headers = ["测试批次", "方案名称", "适用范围", "测试点", "容差", "稳定等待上限(ms)", "串扰阈值(%量程)"];
+        for (col, title) in headers.iter().enumerate() {
+            sheet.write_with_format(0, col as u16, *title, &header_fmt)?;
+            sheet.set_column_width(col as u16, 20)?;
+        }
+
+        // 按批次去重，保持实例出现顺序
+        let mut batch_ids: Vec<&str> = Vec::new();
+        for inst in analog_instances {
+            if !batch_ids.contains(&inst.test_batch_id.as_str()) {
+                batch_ids.push(inst.test_batch_id.as_str());
+            }
+        }
+
+        let mut row = 1u32;
+        for batch_id in batch_ids {
+            let batch_info = self.persistence_service.load_batch_info(batch_id).await.ok().flatten();
+            let batch_name = batch_info
+                .as_ref()
+                .map(|b| b.batch_name.clone())
+                .unwrap_or_else(|| batch_id.to_string());
+            let profile = batch_info
+                .and_then(|b| b.analog_test_profile)
+                .unwrap_or_else(crate::models::AnalogTestProfile::system_default);
+
+            let mut scopes: Vec<(String, &crate::models::AnalogTestSettings)> =
+                vec![("默认".to_string(), &profile.default_settings)];
+            let mut module_overrides: Vec<_> = profile.module_type_overrides.iter().collect();
+            module_overrides.sort_by(|a, b| a.0.cmp(b.0));
+            scopes.extend(module_overrides.into_iter().map(|(k, v)| (format!("模块类型 {}", k), v)));
+            let mut point_overrides: Vec<_> = profile.point_overrides.iter().collect();
+            point_overrides.sort_by(|a, b| a.0.cmp(b.0));
+            scopes.extend(point_overrides.into_iter().map(|(k, v)| (format!("点位 {}", k), v)));
+
+            for (scope, settings) in scopes {
+                sheet.write_with_format(row, 0, &batch_name, &default_fmt)?;
+                sheet.write_with_format(row, 1, &profile.name, &default_fmt)?;
+                sheet.write_with_format(row, 2, &scope, &default_fmt)?;
+                sheet.write_with_format(row, 3, &settings.test_points_description(), &default_fmt)?;
+                sheet.write_with_format(row, 4, &settings.tolerance_description(), &default_fmt)?;
+                sheet.write_with_format(row, 5, settings.settle_time_ms as f64, &default_fmt)?;
+                let crosstalk = settings.crosstalk_threshold_percent
+                    .map(|t| format!("{}", t))
+                    .unwrap_or_else(|| "不检查".to_string());
+                sheet.write_with_format(row, 6, &crosstalk, &default_fmt)?;
+                row += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 创建连接质量工作表 - 各PLC连接的掉线次数、掉线时长、错误率、时延分布及测试期间的连接事件
+    async fn create_connection_quality_worksheet(
+        &self,
+        workbook: &mut Workbook,
+        instances: &[ChannelTestInstance],
+    ) -> AppResult<()> {
+        let Some(manager) = crate::infrastructure::plc_communication::get_global_plc_manager() else {
+            return Ok(());
+        };
+        let history = manager.connection_history().await;
+        if history.is_empty() {
+            return Ok(());
+        }
+
+        // 测试期间：最早开始时间到最晚完成时间，未完成的算到当前
+        let period_start = instances.iter().filter_map(|inst| inst.start_time).min();
+        let period_end = instances.iter().filter_map(|inst| inst.final_test_time).max()
+            .filter(|_| instances.iter().all(|inst| inst.final_test_time.is_some()))
+            .unwrap_or_else(chrono::Utc::now);
+
+        let mut sheet = workbook.add_worksheet().set_name("连接质量")?;
+        let header_fmt = Format::new().set_bold().set_align(FormatAlign::Center).set_border(FormatBorder::Thin);
+        let default_fmt = Format::new().set_align(FormatAlign::Center).set_border(FormatBorder::Thin);
+        let text_fmt = Format::new().set_border(FormatBorder::Thin);
+
+        let headers = ["连接名称", "当前状态", "掉线次数", "累计掉线时长(s)", "近期错误率", "请求总数", "失败请求数", "时延分布"];
+        for (col, title) in headers.iter().enumerate() {
+            sheet.write_with_format(0, col as u16, *title, &header_fmt)?;
+            sheet.set_column_width(col as u16, if col == headers.len() - 1 { 80 } else { 16 })?;
+        }
+
+        let mut row = 1u32;
+        for snapshot in &history {
+            let histogram = snapshot.latency_histogram.iter()
+                .map(|bucket| match bucket.upper_ms {
+                    Some(upper) => format!("≤{}ms:{}", upper, bucket.count),
+                    None => format!(">{}ms:{}", LATENCY_BUCKETS_MS.last().copied().unwrap_or_default(), bucket.count),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            sheet.write_with_format(row, 0, &snapshot.connection_name, &default_fmt)?;
+            let state = match snapshot.state {
+                PlcConnectionState::Disconnected => "未连接",
+                PlcConnectionState::Connecting => "连接中",
+                PlcConnectionState::Connected => "已连接",
+                PlcConnectionState::Reconnecting => "重连中",
+                PlcConnectionState::Error => "错误",
+            };
+            sheet.write_with_format(row, 1, state, &default_fmt)?;
+            sheet.write_with_format(row, 2, snapshot.disconnect_count as f64, &default_fmt)?;
+            sheet.write_with_format(row, 3, format!("{:.1}", snapshot.total_downtime_ms as f64 / 1000.0), &default_fmt)?;
+            sheet.write_with_format(row, 4, format!("{:.1}%（{}s内{}次）", snapshot.error_rate * 100.0, snapshot.window_seconds, snapshot.window_requests), &default_fmt)?;
+            sheet.write_with_format(row, 5, snapshot.total_requests as f64, &default_fmt)?;
+            sheet.write_with_format(row, 6, snapshot.total_errors as f64, &default_fmt)?;
+            sheet.write_with_format(row, 7, &histogram, &text_fmt)?;
+            row += 1;
+        }
+
+        // 测试期间的连接事件
+        row += 1;
+        for (col, title) in ["时间", "连接名称", "事件", "说明"].iter().enumerate() {
+            sheet.write_with_format(row, col as u16, *title, &header_fmt)?;
+        }
+        row += 1;
+
+        let mut events: Vec<(&str, &ConnectionEvent)> = history.iter()
+            .flat_map(|snapshot| snapshot.events.iter().map(move |event| (snapshot.connection_name.as_str(), event)))
+            .filter(|(_, event)| !matches!(period_start, Some(start) if event.timestamp < start) && event.timestamp <= period_end)
+            .collect();
+        events.sort_by_key(|(_, event)| event.timestamp);
+
+        if events.is_empty() {
+            sheet.write_with_format(row, 0, "测试期间无连接事件", &text_fmt)?;
+        }
+        for (name, event) in events {
+            let kind = match event.kind {
+                ConnectionEventKind::Connected => "连接",
+                ConnectionEventKind::Disconnected => "断开",
+                ConnectionEventKind::ReconnectFailed => "重连失败",
+            };
+            sheet.write_with_format(row, 0, time_utils::format_bj(event.timestamp, "%Y-%m-%d %H:%M:%S"), &default_fmt)?;
+            sheet.write_with_format(row, 1, name, &default_fmt)?;
+            sheet.write_with_format(row, 2, kind, &default_fmt)?;
+            sheet.write_with_format(row, 3, event.message.as_deref().unwrap_or(""), &text_fmt)?;
+            row += 1;
+        }
+
+        Ok(())
+    }
+
     /// 创建错误信息汇总工作表 - 以点位为基线的错误信息汇总
     async fn create_error_summary_sheet(
         &self,
@@ -831,9 +1022,21 @@ impl ExcelExportService {
             false
         }).count();
 
+        // 按故障类型统计失败的子测试
+        let mut kind_counts: std::collections::HashMap<FailureKind, usize> = std::collections::HashMap::new();
+        for result in instances.iter().flat_map(|i| i.sub_test_results.values()) {
+            if matches!(result.status, SubTestStatus::Failed) {
+                *kind_counts.entry(failure_kind_of(result)).or_insert(0) += 1;
+            }
+        }
+        let kind_count = |kind: FailureKind| kind_counts.get(&kind).copied().unwrap_or(0);
+
         let stats = vec![
             ("硬点测试失败", hardpoint_failed),
             ("手动测试失败", manual_failed),
+            ("配置错误(子测试)", kind_count(FailureKind::Configuration)),
+            ("通信故障(子测试)", kind_count(FailureKind::Communication)),
+            ("信号异常(子测试)", kind_count(FailureKind::Signal)),
         ];
 
         for (i, (label, count)) in stats.iter().enumerate() {
@@ -888,7 +1091,10 @@ impl ExcelExportService {
         for (test_item, result) in &instance.sub_test_results {
             if test_item == &SubTestItem::HardPoint && matches!(result.status, SubTestStatus::Failed) {
                 let mut summary = String::new();
-                
+
+                // 故障类型
+                summary.push_str(&format!("故障类型: {}\n", failure_kind_of(result)));
+
                 // 错误详情
                 if let Some(details) = &result.details {
                     summary.push_str(&format!("错误信息: {}\n", details));
@@ -1002,7 +1208,7 @@ impl ExcelExportService {
                     };
                     
                     let details = result.details.as_ref().map(|d| format!(" ({})", d)).unwrap_or_default();
-                    failed_items.push(format!("{}{}", item_name, details));
+                    failed_items.push(format!("[{}] {}{}", failure_kind_of(result), item_name, details));
                 }
             }
         }
@@ -1,9 +1,6 @@
 /// 基础设施层服务模块
 /// 负责与外部系统的交互，如PLC通信、数据持久化等
 
-/// PLC通信相关模块
-//pub mod plc;
-
 /// 数据持久化相关模块
 pub mod persistence;
 
@@ -24,19 +21,6 @@ pub mod excel_export_service;
 // 为后续步骤准备的模块（暂时注释）
 // pub mod excel;
 
-// 重新导出PLC相关接口和实现
-/*pub use plc::{
-    // PlcCommunicationService as IPlcCommunicationService, // 已在 crate::infrastructure::plc_communication 中导出，避免名称冲突
-    PlcTag,
-    PlcDataType,
-    PlcConnectionStatus,
-    PlcCommunicationStats,
-
-    ModbusPlcService,
-    ModbusConfig,
-};
-*/
-
 // 重新导出PLC监控服务
 
 pub use plc_monitoring_service::{IPlcMonitoringService, PlcMonitoringService};
@@ -59,6 +43,7 @@ pub use persistence::{
     JsonAppSettingsService,
     AppSettingsConfig,
     AppSettingsServiceFactory,
+    record_last_backup_time,
 };
 
 // 重新导出Excel相关服务
@@ -72,12 +57,9 @@ pub use event_publisher::{
 };
 
 // 或者更明确地导出需要的类型，例如：
-// pub use plc::plc_communication_service::IPlcCommunicationService;
-// pub use plc::mock_plc_service::MockPlcService;
 // pub use persistence::persistence_service::IPersistenceService;
 // pub use persistence::sqlite_orm_persistence_service::SqliteOrmPersistenceService;
 // pub use persistence::json_persistence_service::JsonPersistenceService;
 
 // 暂时先不 re-export，让调用方使用完整路径，或者只导出模块
-// pub use plc; // 移除或改为 pub use plc::SpecificType;
 // pub use persistence; // 移除或改为 pub use persistence::SpecificType;
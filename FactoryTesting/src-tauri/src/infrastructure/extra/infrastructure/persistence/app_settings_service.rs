@@ -197,3 +197,23 @@ impl AppSettingsServiceFactory {
         JsonAppSettingsService::new_default()
     }
 }
+
+/// 把最近一次数据库备份时间写入应用配置
+///
+/// 只在新时间更晚时覆盖；写入失败仅记录警告，不影响备份本身
+pub async fn record_last_backup_time(settings_service: &dyn AppSettingsService, backup_time: chrono::DateTime<chrono::Utc>) {
+    let mut settings = match settings_service.load_settings().await {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            log::warn!("读取应用配置失败，未记录备份时间: {}", e);
+            return;
+        }
+    };
+    if settings.last_backup_time.is_some_and(|last| last >= backup_time) {
+        return;
+    }
+    settings.last_backup_time = Some(backup_time);
+    if let Err(e) = settings_service.save_settings(&settings).await {
+        log::warn!("记录最近备份时间失败: {}", e);
+    }
+}
@@ -0,0 +1,546 @@
+//! # 过期测试数据清理模块
+//!
+//! ## 业务作用
+//! 长期运行的测试站数据库会涨到GB级别，主要来自原始测试结果、读数采样和历史批次。本模块按保留策略清理：
+//! - **已完成批次**: 超过保留期且所有实例都已结束的批次。`keep_final_results_only` 时保留批次与实例，
+//!   只把原始结果精简为每个子测试的最后一次；否则连同通道定义、实例、结果一起删除
+//! - **原始测试结果**: 超过保留期的结果。`keep_final_results_only` 时只删除被重测覆盖的历史尝试
+//! - **监控采样数据**: 超过保留期的读数序列与临时数据置空，测试结论保持不变
+//! - **失败结果**: `keep_failed_tests` 时失败的结果及其采样数据不参与清理
+//!
+//! 各项保留天数为0表示不清理该类数据。
+//!
+//! ## 执行方式
+//! 所有删除与更新在一个固定连接的单个事务内完成；开启归档时先 `ATTACH` 归档文件，
+//! 删除前把同一批记录 `INSERT ... SELECT` 到归档库的同名表，归档与删除一起提交或回滚。
+//! 清理事务与VACUUM都会长时间持有数据库写锁，有批次正在测试时不执行，避免测试结果写入失败。
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use futures::future::BoxFuture;
+use chrono::{DateTime, Duration, Utc};
+use log::{info, warn};
+use sea_orm::sqlx::{self, Connection, SqliteConnection};
+use sea_orm::{ConnectionTrait, DatabaseConnection, Statement};
+
+use crate::domain::services::persistence_service::{CleanupResult, RetentionPolicy};
+use crate::utils::config::DataRetentionConfig;
+use crate::utils::error::{AppError, AppResult};
+use super::persistence_service::ExtendedPersistenceService;
+
+/// 归档文件所在目录名，与数据库文件同级
+pub const ARCHIVE_DIR_NAME: &str = "_archive";
+
+/// 清理时挂载归档库使用的别名
+const ARCHIVE_SCHEMA: &str = "retention_archive";
+
+/// 本次清理中判定为过期的批次，只在清理事务内使用
+const EXPIRED_BATCHES: &str = "retention_expired_batches";
+
+/// 测试已结束的实例状态
+const FINISHED_STATUSES: &str = "'TestCompletedPassed', 'TestCompletedFailed', 'Skipped'";
+
+const BATCHES: &str = "test_batch_info";
+const DEFINITIONS: &str = "channel_point_definitions";
+const INSTANCES: &str = "channel_test_instances";
+const OUTCOMES: &str = "raw_test_outcomes";
+const ALLOCATION_RECORDS: &str = "allocation_records";
+
+/// 查询当前是否有批次正在测试
+pub type BusyProbe = Arc<dyn Fn() -> BoxFuture<'static, bool> + Send + Sync>;
+
+/// 保留天数对应的截止时间，0天表示不清理
+fn cutoff(now: DateTime<Utc>, days: u32) -> Option<DateTime<Utc>> {
+    (days > 0).then(|| now - Duration::days(days as i64))
+}
+
+impl From<&DataRetentionConfig> for RetentionPolicy {
+    fn from(config: &DataRetentionConfig) -> Self {
+        Self {
+            test_outcomes_retention_days: config.test_outcomes_retention_days,
+            batch_info_retention_days: config.batch_retention_days,
+            // 日志文件由日志模块按大小轮转，不在数据库清理范围内
+            logs_retention_days: 0,
+            keep_failed_tests: config.keep_failed_tests,
+            max_storage_size_bytes: None,
+            monitoring_samples_retention_days: config.monitoring_samples_retention_days,
+            keep_final_results_only: config.keep_final_results_only,
+            archive_before_delete: config.archive_before_delete,
+        }
+    }
+}
+
+/// 归档文件名：`retention_<时间戳>.sqlite`
+pub fn archive_file_name(at: DateTime<Utc>) -> String {
+    format!("retention_{}.sqlite", at.format("%Y%m%d_%H%M%S"))
+}
+
+/// 一次清理在事务内的累计结果
+#[derive(Debug, Default)]
+struct PurgeStats {
+    deleted_by_table: HashMap<String, u64>,
+    archived_records: u64,
+    cleared_samples: u64,
+}
+
+/// 执行一条清理语句，`cutoff` 存在时绑定为 `?1`
+async fn execute(conn: &mut SqliteConnection, sql: &str, cutoff: Option<DateTime<Utc>>) -> AppResult<u64> {
+    let mut query = sqlx::query(sql);
+    if let Some(cutoff) = cutoff {
+        query = query.bind(cutoff);
+    }
+    query.execute(&mut *conn)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| AppError::persistence_error(format!("执行数据清理语句失败 [{}]: {}", sql, e)))
+}
+
+async fn table_exists(conn: &mut SqliteConnection, table: &str) -> AppResult<bool> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM main.sqlite_master WHERE type = 'table' AND name = ?")
+        .bind(table)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::persistence_error(format!("检查表 {} 是否存在失败: {}", table, e)))?;
+    Ok(count > 0)
+}
+
+/// 删除满足条件的记录，开启归档时先复制到归档库
+async fn remove_rows(
+    conn: &mut SqliteConnection,
+    table: &str,
+    condition: &str,
+    cutoff: Option<DateTime<Utc>>,
+    archive: bool,
+    stats: &mut PurgeStats,
+) -> AppResult<()> {
+    if archive {
+        execute(conn, &format!(
+            "CREATE TABLE IF NOT EXISTS {schema}.{table} AS SELECT * FROM main.{table} WHERE 0",
+            schema = ARCHIVE_SCHEMA, table = table
+        ), None).await?;
+        stats.archived_records += execute(conn, &format!(
+            "INSERT INTO {schema}.{table} SELECT * FROM main.{table} WHERE {condition}",
+            schema = ARCHIVE_SCHEMA, table = table, condition = condition
+        ), cutoff).await?;
+    }
+    let deleted = execute(conn, &format!("DELETE FROM main.{} WHERE {}", table, condition), cutoff).await?;
+    if deleted > 0 {
+        *stats.deleted_by_table.entry(table.to_string()).or_default() += deleted;
+    }
+    Ok(())
+}
+
+/// 在 `scope` 范围内，存在同一实例同一子测试更晚结果的记录即为被覆盖的历史尝试
+fn superseded_outcomes(scope: &str) -> String {
+    format!(
+        "({scope}) AND EXISTS (SELECT 1 FROM main.{t} later \
+         WHERE later.channel_instance_id = {t}.channel_instance_id \
+         AND later.sub_test_item = {t}.sub_test_item \
+         AND (later.end_time > {t}.end_time OR (later.end_time = {t}.end_time AND later.id > {t}.id)))",
+        scope = scope, t = OUTCOMES
+    )
+}
+
+/// 清空结果的读数序列，返回受影响的记录数
+async fn clear_outcome_samples(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    cutoff: Option<DateTime<Utc>>,
+    keep_failed: bool,
+) -> AppResult<u64> {
+    let failed_clause = if keep_failed { " AND success = 1" } else { "" };
+    execute(conn, &format!(
+        "UPDATE main.{} SET readings_json = NULL WHERE readings_json IS NOT NULL AND ({}){}",
+        OUTCOMES, scope, failed_clause
+    ), cutoff).await
+}
+
+/// 清空已结束实例的临时数据，返回受影响的记录数
+async fn clear_instance_samples(
+    conn: &mut SqliteConnection,
+    scope: &str,
+    cutoff: Option<DateTime<Utc>>,
+    keep_failed: bool,
+) -> AppResult<u64> {
+    let failed_clause = if keep_failed { " AND overall_status <> 'TestCompletedFailed'" } else { "" };
+    execute(conn, &format!(
+        "UPDATE main.{} SET transient_data_json = NULL \
+         WHERE transient_data_json IS NOT NULL AND overall_status IN ({}) AND ({}){}",
+        INSTANCES, FINISHED_STATUSES, scope, failed_clause
+    ), cutoff).await
+}
+
+/// 在事务内依次清理过期批次、原始结果和采样数据
+async fn purge(
+    conn: &mut SqliteConnection,
+    policy: &RetentionPolicy,
+    archive: bool,
+    now: DateTime<Utc>,
+) -> AppResult<PurgeStats> {
+    let mut stats = PurgeStats::default();
+    let keep_failed = policy.keep_failed_tests;
+    let mut tx = conn.begin().await
+        .map_err(|e| AppError::persistence_error(format!("开启清理事务失败: {}", e)))?;
+
+    // 1. 已完成批次：批次本身与所有实例都在截止时间前更新，且没有未结束的实例
+    if let Some(batch_cutoff) = cutoff(now, policy.batch_info_retention_days) {
+        let failed_clause = if keep_failed { " OR i.overall_status = 'TestCompletedFailed'" } else { "" };
+        execute(&mut tx, &format!("DROP TABLE IF EXISTS temp.{}", EXPIRED_BATCHES), None).await?;
+        execute(&mut tx, &format!(
+            "CREATE TEMP TABLE {expired} AS SELECT b.batch_id FROM main.{batches} b \
+             WHERE b.updated_time < ?1 \
+             AND (b.overall_status IN ({finished}) OR EXISTS (SELECT 1 FROM main.{instances} i WHERE i.test_batch_id = b.batch_id)) \
+             AND NOT EXISTS (SELECT 1 FROM main.{instances} i WHERE i.test_batch_id = b.batch_id \
+                 AND (i.overall_status NOT IN ({finished}) OR i.updated_time >= ?1{failed}))",
+            expired = EXPIRED_BATCHES, batches = BATCHES, instances = INSTANCES,
+            finished = FINISHED_STATUSES, failed = failed_clause
+        ), Some(batch_cutoff)).await?;
+
+        let in_expired = format!("SELECT batch_id FROM temp.{}", EXPIRED_BATCHES);
+        let expired_outcomes = format!(
+            "channel_instance_id IN (SELECT instance_id FROM main.{} WHERE test_batch_id IN ({}))",
+            INSTANCES, in_expired
+        );
+        if policy.keep_final_results_only {
+            let scope = if keep_failed { format!("{} AND success = 1", expired_outcomes) } else { expired_outcomes.clone() };
+            remove_rows(&mut tx, OUTCOMES, &superseded_outcomes(&scope), None, archive, &mut stats).await?;
+            stats.cleared_samples += clear_outcome_samples(&mut tx, &expired_outcomes, None, keep_failed).await?;
+            stats.cleared_samples += clear_instance_samples(
+                &mut tx, &format!("test_batch_id IN ({})", in_expired), None, keep_failed,
+            ).await?;
+        } else {
+            remove_rows(&mut tx, OUTCOMES, &expired_outcomes, None, archive, &mut stats).await?;
+            remove_rows(&mut tx, INSTANCES, &format!("test_batch_id IN ({})", in_expired), None, archive, &mut stats).await?;
+            remove_rows(&mut tx, DEFINITIONS, &format!("batch_id IN ({})", in_expired), None, archive, &mut stats).await?;
+            if table_exists(&mut tx, ALLOCATION_RECORDS).await? {
+                remove_rows(&mut tx, ALLOCATION_RECORDS, &format!("batch_id IN ({})", in_expired), None, archive, &mut stats).await?;
+            }
+            remove_rows(&mut tx, BATCHES, &format!("batch_id IN ({})", in_expired), None, archive, &mut stats).await?;
+        }
+        execute(&mut tx, &format!("DROP TABLE temp.{}", EXPIRED_BATCHES), None).await?;
+    }
+
+    // 2. 原始测试结果：只保留最终结果时删除被覆盖的历史尝试，否则删除全部过期结果
+    if let Some(outcome_cutoff) = cutoff(now, policy.test_outcomes_retention_days) {
+        let scope = if keep_failed { "end_time < ?1 AND success = 1" } else { "end_time < ?1" };
+        let condition = if policy.keep_final_results_only { superseded_outcomes(scope) } else { scope.to_string() };
+        remove_rows(&mut tx, OUTCOMES, &condition, Some(outcome_cutoff), archive, &mut stats).await?;
+    }
+
+    // 3. 监控采样数据：读数序列与临时数据置空
+    if let Some(sample_cutoff) = cutoff(now, policy.monitoring_samples_retention_days) {
+        stats.cleared_samples += clear_outcome_samples(&mut tx, "end_time < ?1", Some(sample_cutoff), keep_failed).await?;
+        stats.cleared_samples += clear_instance_samples(&mut tx, "updated_time < ?1", Some(sample_cutoff), keep_failed).await?;
+    }
+
+    tx.commit().await
+        .map_err(|e| AppError::persistence_error(format!("提交清理事务失败: {}", e)))?;
+    Ok(stats)
+}
+
+/// 读取整数类 PRAGMA
+async fn pragma_value(db: &DatabaseConnection, pragma: &str) -> AppResult<u64> {
+    let row = db.query_one(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, format!("PRAGMA {}", pragma)))
+        .await
+        .map_err(|e| AppError::persistence_error(format!("读取 PRAGMA {} 失败: {}", pragma, e)))?;
+    let value = match row {
+        Some(row) => row.try_get_by_index::<i64>(0)
+            .map_err(|e| AppError::persistence_error(format!("读取 PRAGMA {} 失败: {}", pragma, e)))?,
+        None => 0,
+    };
+    Ok(value.max(0) as u64)
+}
+
+/// 数据库文件中的空闲页字节数
+pub async fn free_bytes(db: &DatabaseConnection) -> AppResult<u64> {
+    Ok(pragma_value(db, "freelist_count").await? * pragma_value(db, "page_size").await?)
+}
+
+/// 数据库文件按页计算的总字节数
+pub async fn database_bytes(db: &DatabaseConnection) -> AppResult<u64> {
+    Ok(pragma_value(db, "page_count").await? * pragma_value(db, "page_size").await?)
+}
+
+/// 按保留策略清理过期数据
+///
+/// `archive_file` 存在时删除的记录先写入该归档文件；没有记录被归档时不保留空文件。
+/// `freed_space_bytes` 为本次清理新增的可复用空间，需要 [`compact`] 才会缩小数据库文件
+pub async fn apply_retention(
+    db: &DatabaseConnection,
+    policy: &RetentionPolicy,
+    archive_file: Option<&Path>,
+) -> AppResult<CleanupResult> {
+    let started = std::time::Instant::now();
+    let now = Utc::now();
+    let free_before = free_bytes(db).await?;
+
+    let mut conn = db.get_sqlite_connection_pool().acquire().await
+        .map_err(|e| AppError::persistence_error(format!("获取数据库连接失败: {}", e)))?;
+
+    if let Some(file) = archive_file {
+        if let Some(parent) = file.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e|
+                AppError::io_error(format!("创建归档目录失败: {:?}", parent), e.kind().to_string())
+            )?;
+        }
+        sqlx::query(&format!("ATTACH DATABASE ? AS {}", ARCHIVE_SCHEMA))
+            .bind(file.to_string_lossy().to_string())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AppError::persistence_error(format!("挂载归档文件 {:?} 失败: {}", file, e)))?;
+    }
+
+    let purged = purge(&mut conn, policy, archive_file.is_some(), now).await;
+
+    if archive_file.is_some() {
+        // 无论清理成功与否都要分离归档库，该连接随后会回到连接池
+        if let Err(e) = sqlx::query(&format!("DETACH DATABASE {}", ARCHIVE_SCHEMA)).execute(&mut *conn).await {
+            warn!("[RETENTION] 分离归档库失败，关闭该连接: {}", e);
+            let _ = conn.detach().close().await;
+        }
+    }
+    let stats = purged?;
+
+    let archive_path = match archive_file {
+        Some(file) if stats.archived_records > 0 => Some(file.to_string_lossy().to_string()),
+        Some(file) => {
+            let _ = tokio::fs::remove_file(file).await;
+            None
+        }
+        None => None,
+    };
+
+    let deleted_records = stats.deleted_by_table.values().sum();
+    let freed_space_bytes = free_bytes(db).await?.saturating_sub(free_before);
+    info!(
+        "[RETENTION] 清理完成: 删除 {} 条 {:?}，归档 {} 条，清除采样 {} 条，新增可复用空间 {} 字节",
+        deleted_records, stats.deleted_by_table, stats.archived_records, stats.cleared_samples, freed_space_bytes
+    );
+
+    Ok(CleanupResult {
+        deleted_records,
+        deleted_by_table: stats.deleted_by_table,
+        freed_space_bytes,
+        duration_ms: started.elapsed().as_millis() as u64,
+        cleanup_time: now,
+        archived_records: stats.archived_records,
+        cleared_samples: stats.cleared_samples,
+        archive_path,
+    })
+}
+
+/// 执行 `VACUUM` 收缩数据库文件，返回减少的字节数
+pub async fn compact(db: &DatabaseConnection) -> AppResult<u64> {
+    let before = database_bytes(db).await?;
+    db.execute(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, "VACUUM"))
+        .await
+        .map_err(|e| AppError::persistence_error(format!("压缩数据库失败: {}", e)))?;
+    let after = database_bytes(db).await?;
+    info!("[RETENTION] VACUUM 完成: {} → {} 字节", before, after);
+    Ok(before.saturating_sub(after))
+}
+
+/// 执行 `REINDEX` 重建全部索引，并更新查询优化统计
+pub async fn reindex(db: &DatabaseConnection) -> AppResult<()> {
+    for sql in ["REINDEX", "ANALYZE"] {
+        db.execute(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, sql))
+            .await
+            .map_err(|e| AppError::persistence_error(format!("执行 {} 失败: {}", sql, e)))?;
+    }
+    Ok(())
+}
+
+/// 按策略清理，有数据变化且 `compact` 时再执行VACUUM
+///
+/// 压缩后 `freed_space_bytes` 为数据库文件实际减少的字节数
+pub async fn run_cleanup(
+    service: &dyn ExtendedPersistenceService,
+    policy: &RetentionPolicy,
+    compact: bool,
+    busy: &BusyProbe,
+) -> AppResult<CleanupResult> {
+    if busy().await {
+        return Err(AppError::concurrency_error("有批次正在测试，测试结束后再清理过期数据"));
+    }
+    let mut result = crate::domain::services::IPersistenceService::cleanup_expired_data(service, policy).await?;
+    if compact && (result.deleted_records > 0 || result.cleared_samples > 0) {
+        // 清理期间可能有批次开始测试，VACUUM前再确认一次
+        if busy().await {
+            warn!("🗄️ [RETENTION] 有批次正在测试，跳过本次VACUUM");
+        } else {
+            result.freed_space_bytes = service.compact_storage().await?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{ConnectOptions, Database};
+
+    async fn file_db(path: &Path) -> DatabaseConnection {
+        let url = format!("sqlite://{}?mode=rwc", path.to_string_lossy());
+        let mut options = ConnectOptions::new(url);
+        options.max_connections(2).sqlx_logging(false);
+        Database::connect(options).await.unwrap()
+    }
+
+    async fn exec(db: &DatabaseConnection, sql: &str) {
+        db.execute_unprepared(sql).await.unwrap();
+    }
+
+    async fn scalar(db: &DatabaseConnection, sql: &str) -> i64 {
+        let row = db.query_one(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, sql.to_string()))
+            .await.unwrap().unwrap();
+        row.try_get_by_index::<i64>(0).unwrap()
+    }
+
+    fn policy(batch_days: u32, outcome_days: u32, sample_days: u32) -> RetentionPolicy {
+        RetentionPolicy {
+            test_outcomes_retention_days: outcome_days,
+            batch_info_retention_days: batch_days,
+            logs_retention_days: 0,
+            keep_failed_tests: true,
+            max_storage_size_bytes: None,
+            monitoring_samples_retention_days: sample_days,
+            keep_final_results_only: false,
+            archive_before_delete: false,
+        }
+    }
+
+    /// 两个批次：old 早已完成（含一个失败实例的批次 failed），recent 刚完成
+    async fn seed(db: &DatabaseConnection) {
+        exec(db, "CREATE TABLE test_batch_info (batch_id TEXT PRIMARY KEY, overall_status TEXT NOT NULL, updated_time TEXT NOT NULL)").await;
+        exec(db, "CREATE TABLE channel_point_definitions (id TEXT PRIMARY KEY, batch_id TEXT)").await;
+        exec(db, "CREATE TABLE channel_test_instances (instance_id TEXT PRIMARY KEY, test_batch_id TEXT NOT NULL, \
+                  overall_status TEXT NOT NULL, updated_time TEXT NOT NULL, transient_data_json TEXT)").await;
+        exec(db, "CREATE TABLE raw_test_outcomes (id TEXT PRIMARY KEY, channel_instance_id TEXT NOT NULL, \
+                  sub_test_item TEXT NOT NULL, success INTEGER NOT NULL, end_time TEXT NOT NULL, readings_json TEXT)").await;
+
+        let now = Utc::now();
+        let old = now - Duration::days(400);
+        let first = now - Duration::days(401);
+        let recent = now - Duration::days(1);
+        for (batch, time) in [("old", old), ("failed", old), ("recent", recent)] {
+            insert(db, "INSERT INTO test_batch_info VALUES (?, 'NotTested', ?)", vec![batch.into(), time.into()]).await;
+            insert(db, "INSERT INTO channel_point_definitions VALUES (?, ?)", vec![format!("{}_def", batch).into(), batch.into()]).await;
+        }
+        for (instance, batch, status, time, transient) in [
+            ("old_i", "old", "TestCompletedPassed", old, Some("{}")),
+            ("failed_i", "failed", "TestCompletedFailed", old, None),
+            ("recent_i", "recent", "TestCompletedPassed", recent, Some("{}")),
+        ] {
+            insert(db, "INSERT INTO channel_test_instances VALUES (?, ?, ?, ?, ?)",
+                   vec![instance.into(), batch.into(), status.into(), time.into(), transient.into()]).await;
+        }
+        // old_i 的 HighAlarm 重测过一次：第一次失败，第二次通过
+        for (id, instance, item, success, time) in [
+            ("o1", "old_i", "HighAlarm", false, first),
+            ("o2", "old_i", "HighAlarm", true, old),
+            ("o3", "old_i", "LowAlarm", true, first),
+            ("o4", "old_i", "LowAlarm", true, old),
+            ("f1", "failed_i", "HighAlarm", false, old),
+            ("r1", "recent_i", "HighAlarm", true, recent),
+        ] {
+            insert(db, "INSERT INTO raw_test_outcomes VALUES (?, ?, ?, ?, ?, '[1]')",
+                   vec![id.into(), instance.into(), item.into(), success.into(), time.into()]).await;
+        }
+    }
+
+    async fn insert(db: &DatabaseConnection, sql: &str, values: Vec<sea_orm::Value>) {
+        db.execute(Statement::from_sql_and_values(sea_orm::DatabaseBackend::Sqlite, sql, values)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cleanup_is_refused_while_batches_are_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("retention.sqlite");
+        std::fs::write(&db_path, "").unwrap();
+        let service = super::super::SqliteOrmPersistenceService::new(
+            super::super::PersistenceConfig::default(),
+            Some(&db_path),
+        ).await.unwrap();
+
+        let busy: BusyProbe = Arc::new(|| Box::pin(async { true }));
+        let refused = run_cleanup(&service, &policy(1, 1, 1), true, &busy).await;
+        assert!(matches!(refused, Err(AppError::ConcurrencyError { .. })));
+
+        let idle: BusyProbe = Arc::new(|| Box::pin(async { false }));
+        assert!(run_cleanup(&service, &policy(1, 1, 1), true, &idle).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn expired_batches_are_archived_and_deleted_except_failed_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = file_db(&dir.path().join("live.sqlite")).await;
+        seed(&db).await;
+        exec(&db, "CREATE TABLE allocation_records (id TEXT PRIMARY KEY, batch_id TEXT NOT NULL)").await;
+        exec(&db, "INSERT INTO allocation_records VALUES ('a1', 'old'), ('a2', 'recent')").await;
+
+        let archive = dir.path().join(ARCHIVE_DIR_NAME).join(archive_file_name(Utc::now()));
+        let mut retention = policy(365, 0, 0);
+        retention.archive_before_delete = true;
+        let result = apply_retention(&db, &retention, Some(&archive)).await.unwrap();
+
+        assert_eq!(result.deleted_by_table.get(BATCHES), Some(&1));
+        assert_eq!(result.deleted_by_table.get(OUTCOMES), Some(&4));
+        assert_eq!(result.deleted_by_table.get(ALLOCATION_RECORDS), Some(&1));
+        assert_eq!(result.deleted_records, 1 + 1 + 1 + 1 + 4);
+        assert_eq!(result.archived_records, result.deleted_records);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM test_batch_info WHERE batch_id IN ('failed', 'recent')").await, 2);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM channel_point_definitions").await, 2);
+
+        // 归档文件可以独立打开并查到被删除的记录
+        assert_eq!(result.archive_path.as_deref(), Some(archive.to_string_lossy().as_ref()));
+        let archived = file_db(&archive).await;
+        assert_eq!(scalar(&archived, "SELECT COUNT(*) FROM raw_test_outcomes WHERE channel_instance_id = 'old_i'").await, 4);
+
+        // 再次清理没有可归档记录，不留下空归档文件
+        let second = dir.path().join(ARCHIVE_DIR_NAME).join("second.sqlite");
+        let result = apply_retention(&db, &retention, Some(&second)).await.unwrap();
+        assert_eq!(result.deleted_records, 0);
+        assert!(result.archive_path.is_none());
+        assert!(!second.exists());
+    }
+
+    #[tokio::test]
+    async fn keep_final_results_prunes_superseded_attempts_and_samples() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = file_db(&dir.path().join("live.sqlite")).await;
+        seed(&db).await;
+
+        let mut retention = policy(365, 0, 0);
+        retention.keep_final_results_only = true;
+        retention.keep_failed_tests = false;
+        let result = apply_retention(&db, &retention, None).await.unwrap();
+
+        // 批次与实例保留，只删除被重测覆盖的 o1 和 o3
+        assert_eq!(result.deleted_by_table.len(), 1);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM raw_test_outcomes WHERE id IN ('o1', 'o3')").await, 0);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM test_batch_info").await, 3);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM raw_test_outcomes WHERE readings_json IS NOT NULL").await, 1);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM channel_test_instances WHERE transient_data_json IS NOT NULL").await, 1);
+        assert_eq!(result.cleared_samples, 3 + 1);
+    }
+
+    #[tokio::test]
+    async fn outcome_and_sample_retention_respect_cutoff_and_failed_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = file_db(&dir.path().join("live.sqlite")).await;
+        seed(&db).await;
+
+        let result = apply_retention(&db, &policy(0, 90, 7), None).await.unwrap();
+
+        // 失败的 o1、f1 与最近的 r1 保留；采样只清理通过的旧结果
+        assert_eq!(result.deleted_by_table.get(OUTCOMES), Some(&3));
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM raw_test_outcomes").await, 3);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM raw_test_outcomes WHERE readings_json IS NULL").await, 0);
+        assert_eq!(scalar(&db, "SELECT COUNT(*) FROM channel_test_instances WHERE transient_data_json IS NULL").await, 2);
+        assert_eq!(result.cleared_samples, 1);
+
+        assert!(compact(&db).await.is_ok());
+        reindex(&db).await.unwrap();
+    }
+}
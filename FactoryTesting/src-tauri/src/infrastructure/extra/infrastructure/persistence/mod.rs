@@ -10,6 +10,18 @@ pub mod app_settings_service;
 /// SQLite ORM 持久化实现
 pub mod sqlite_orm_persistence_service;
 
+/// 查询条件到SeaORM条件的翻译
+pub mod query_builder;
+
+/// SQLite在线备份与恢复
+pub mod sqlite_backup;
+
+/// 过期测试数据的保留清理
+pub mod data_retention;
+
+/// 多实体写入的事务执行与批量插入
+pub mod transaction_writer;
+
 /// 单元测试模块
 //#[cfg(test)]
 //pub mod tests;
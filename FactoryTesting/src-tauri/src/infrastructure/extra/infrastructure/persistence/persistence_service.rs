@@ -50,8 +50,21 @@ impl Default for PersistenceConfig {
 }
 
 /// 查询条件
+///
+/// 各条件之间为“且”关系，未指定的条件不参与过滤；具体作用到哪些列见 `query_builder`
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct QueryCriteria {
+    /// 按站场过滤
+    pub station_name: Option<String>,
+    /// 按批次ID过滤
+    pub batch_id: Option<String>,
+    /// 按模块类型过滤（AI/AO/DI/DO）
+    pub module_type: Option<String>,
+    /// 按整体测试状态过滤（OverallTestStatus 名称，如 TestCompletedFailed）
+    pub status: Option<String>,
+    /// 位号匹配模式，`*` 匹配任意个字符、`?` 匹配单个字符，不含通配符时按包含匹配
+    pub tag_pattern: Option<String>,
     /// 按创建时间范围过滤
     pub created_after: Option<chrono::DateTime<chrono::Utc>>,
     pub created_before: Option<chrono::DateTime<chrono::Utc>>,
@@ -247,6 +260,12 @@ pub struct BackupInfo {
     pub description: Option<String>,
     /// 是否是自动备份
     pub is_auto_backup: bool,
+    /// 备份时的数据库结构版本（PRAGMA user_version）
+    #[serde(default)]
+    pub schema_version: i32,
+    /// 各业务表的记录数，键为表名
+    #[serde(default)]
+    pub record_counts: std::collections::BTreeMap<String, u64>,
 }
 
 /// 数据完整性报告
@@ -0,0 +1,390 @@
+//! # 查询条件构建模块
+//!
+//! ## 业务作用
+//! 把查询条件翻译为SeaORM条件，让 `SqliteOrmPersistenceService` 的 `query_*` 方法在数据库端完成过滤、排序与分页，
+//! 避免各界面先 `load_all_*` 再在内存中筛选：
+//! - **基础设施层 QueryCriteria**: 站场、批次、模块类型、状态、位号模式、时间范围、排序与偏移/条数
+//! - **领域层 QueryCriteria**: 按字段名给出的通用过滤、排序与页码分页
+//!
+//! ## 跨表条件
+//! 测试实例表中的位号、模块类型在保存时留空，站场只记录在通道定义上，
+//! 因此这几类条件通过 `definition_id IN (子查询)` 作用到通道定义表；测试结果再经测试实例表关联到批次。
+
+use std::str::FromStr;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::{LikeExpr, Query, SimpleExpr};
+use sea_orm::{
+    ColumnTrait, ColumnType, Condition, ConnectionTrait, EntityTrait, Iterable, Order, PaginatorTrait,
+    PrimaryKeyToColumn, QueryOrder, QuerySelect, Select, Value,
+};
+
+use crate::domain::services::persistence_service::{
+    FilterCondition, FilterOperator, QueryCriteria as DomainQueryCriteria, SortDirection,
+};
+use crate::models::entities::{channel_point_definition, channel_test_instance, raw_test_outcome, test_batch_info};
+use crate::utils::error::{AppError, AppResult};
+use super::persistence_service::{QueryCriteria, QueryResult};
+
+/// 取出非空的文本条件，前端未填写的输入框会以空字符串传入
+fn text(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// 把位号匹配模式转换为LIKE表达式
+///
+/// `*` 匹配任意个字符、`?` 匹配单个字符；不含通配符时按包含匹配。
+/// 位号中常见的 `_` 按字面值处理，不作为LIKE通配符
+pub fn tag_like(pattern: &str) -> LikeExpr {
+    let mut like = String::with_capacity(pattern.len() + 2);
+    let mut has_wildcard = false;
+    for c in pattern.trim().chars() {
+        match c {
+            '*' => { has_wildcard = true; like.push('%'); }
+            '?' => { has_wildcard = true; like.push('_'); }
+            '%' | '_' | '\\' => { like.push('\\'); like.push(c); }
+            _ => like.push(c),
+        }
+    }
+    if !has_wildcard {
+        like = format!("%{}%", like);
+    }
+    LikeExpr::new(like).escape('\\')
+}
+
+/// 非空条件才参与组合，避免生成多余的 `TRUE`
+fn non_empty(condition: Condition) -> Option<Condition> {
+    (!condition.is_empty()).then_some(condition)
+}
+
+/// 时间范围条件（两端均包含）；两端均未指定时返回 `None`
+fn within<C: ColumnTrait, V: Into<Value>>(column: C, after: Option<V>, before: Option<V>) -> Option<Condition> {
+    non_empty(
+        Condition::all()
+            .add_option(after.map(|v| column.gte(v)))
+            .add_option(before.map(|v| column.lte(v))),
+    )
+}
+
+/// 通道定义上的站场/模块类型/位号条件；均未指定时返回 `None`
+fn definition_scope(criteria: &QueryCriteria) -> Option<Condition> {
+    use channel_point_definition::Column;
+
+    non_empty(
+        Condition::all()
+            .add_option(text(&criteria.station_name).map(|station| Column::StationName.eq(station)))
+            .add_option(text(&criteria.module_type).map(|module| Column::ModuleType.eq(module)))
+            .add_option(text(&criteria.tag_pattern).map(|pattern| Column::Tag.like(tag_like(pattern)))),
+    )
+}
+
+/// 测试实例的归属条件：批次，以及经通道定义表关联的站场/模块类型/位号；均未指定时返回 `None`
+fn instance_scope(criteria: &QueryCriteria) -> Option<Condition> {
+    use channel_test_instance::Column;
+
+    non_empty(
+        Condition::all()
+            .add_option(text(&criteria.batch_id).map(|batch| Column::TestBatchId.eq(batch)))
+            .add_option(definition_scope(criteria).map(|scope| {
+                Column::DefinitionId.in_subquery(
+                    Query::select()
+                        .column(channel_point_definition::Column::Id)
+                        .from(channel_point_definition::Entity)
+                        .cond_where(scope)
+                        .to_owned(),
+                )
+            })),
+    )
+}
+
+/// 通道定义查询条件；状态条件对通道定义不适用
+///
+/// 通道定义的时间戳以RFC3339文本保存，时间范围按同格式的文本比较
+pub fn channel_definition_condition(criteria: &QueryCriteria) -> Condition {
+    use channel_point_definition::Column;
+
+    let rfc3339 = |time: DateTime<Utc>| time.to_rfc3339();
+    Condition::all()
+        .add_option(definition_scope(criteria))
+        .add_option(text(&criteria.batch_id).map(|batch| Column::BatchId.eq(batch)))
+        .add_option(within(Column::CreatedTime, criteria.created_after.map(rfc3339), criteria.created_before.map(rfc3339)))
+        .add_option(within(Column::UpdatedTime, criteria.updated_after.map(rfc3339), criteria.updated_before.map(rfc3339)))
+}
+
+/// 测试实例查询条件，状态对应 `overall_status`
+pub fn test_instance_condition(criteria: &QueryCriteria) -> Condition {
+    use channel_test_instance::Column;
+
+    Condition::all()
+        .add_option(instance_scope(criteria))
+        .add_option(text(&criteria.status).map(|status| Column::OverallStatus.eq(status)))
+        .add_option(within(Column::CreatedTime, criteria.created_after, criteria.created_before))
+        .add_option(within(Column::UpdatedTime, criteria.updated_after, criteria.updated_before))
+}
+
+/// 测试批次查询条件；位号模式用于匹配批次名称，模块类型对批次不适用
+pub fn test_batch_condition(criteria: &QueryCriteria) -> Condition {
+    use test_batch_info::Column;
+
+    Condition::all()
+        .add_option(text(&criteria.station_name).map(|station| Column::StationName.eq(station)))
+        .add_option(text(&criteria.batch_id).map(|batch| Column::BatchId.eq(batch)))
+        .add_option(text(&criteria.status).map(|status| Column::OverallStatus.eq(status)))
+        .add_option(text(&criteria.tag_pattern).map(|pattern| Column::BatchName.like(tag_like(pattern))))
+        .add_option(within(Column::CreatedTime, criteria.created_after, criteria.created_before))
+        .add_option(within(Column::UpdatedTime, criteria.updated_after, criteria.updated_before))
+}
+
+/// 测试结果查询条件
+///
+/// 站场/批次/模块类型/位号经测试实例表关联；创建/更新时间范围分别作用于开始/结束时间，状态条件不适用
+pub fn test_outcome_condition(criteria: &QueryCriteria) -> Condition {
+    use raw_test_outcome::Column;
+
+    Condition::all()
+        .add_option(instance_scope(criteria).map(|scope| {
+            Column::ChannelInstanceId.in_subquery(
+                Query::select()
+                    .column(channel_test_instance::Column::InstanceId)
+                    .from(channel_test_instance::Entity)
+                    .cond_where(scope)
+                    .to_owned(),
+            )
+        }))
+        .add_option(within(Column::StartTime, criteria.created_after, criteria.created_before))
+        .add_option(within(Column::EndTime, criteria.updated_after, criteria.updated_before))
+}
+
+/// 按字段名解析列，支持蛇形与驼峰写法
+fn column_by_name<C: FromStr>(field: &str) -> AppResult<C> {
+    C::from_str(field.trim()).map_err(|_| AppError::validation_error(format!("未知的查询字段: {}", field)))
+}
+
+/// 按主键追加排序，保证分页结果稳定
+fn order_by_primary_key<E: EntityTrait>(select: Select<E>, order: Order) -> Select<E> {
+    E::PrimaryKey::iter().fold(select, |select, key| select.order_by(key.into_column(), order.clone()))
+}
+
+/// 统计总数后按排序、偏移与条数取出一页
+///
+/// 返回本页模型与满足条件的总记录数
+pub async fn fetch_page<E, D>(db: &D, select: Select<E>, criteria: &QueryCriteria) -> AppResult<(Vec<E::Model>, usize)>
+where
+    E: EntityTrait,
+    E::Column: FromStr,
+    E::Model: Sync,
+    D: ConnectionTrait,
+{
+    let total_count = select.clone().count(db).await
+        .map_err(|e| AppError::persistence_error(format!("统计查询结果失败: {}", e)))? as usize;
+
+    let order = if criteria.sort_desc { Order::Desc } else { Order::Asc };
+    let mut select = select;
+    if let Some(field) = text(&criteria.sort_by) {
+        select = select.order_by(column_by_name::<E::Column>(field)?, order.clone());
+    }
+    let mut select = order_by_primary_key(select, order);
+    if let Some(offset) = criteria.offset {
+        select = select.offset(offset as u64);
+    }
+    if let Some(limit) = criteria.limit {
+        select = select.limit(limit as u64);
+    }
+
+    let models = select.all(db).await
+        .map_err(|e| AppError::persistence_error(format!("执行分页查询失败: {}", e)))?;
+    Ok((models, total_count))
+}
+
+/// 组装分页查询结果
+pub fn page_result<T>(items: Vec<T>, total_count: usize, criteria: &QueryCriteria) -> QueryResult<T> {
+    let has_more = criteria.offset.unwrap_or(0) + items.len() < total_count;
+    QueryResult { items, total_count, has_more }
+}
+
+/// 把JSON过滤值转换为列值；时间列上的RFC3339字符串按时间比较
+fn column_value<C: ColumnTrait>(column: &C, field: &str, value: &serde_json::Value) -> AppResult<Value> {
+    match value {
+        serde_json::Value::String(s) => {
+            let is_time = matches!(
+                column.def().get_column_type(),
+                ColumnType::TimestampWithTimeZone | ColumnType::DateTime | ColumnType::Timestamp
+            );
+            match DateTime::parse_from_rfc3339(s) {
+                Ok(time) if is_time => Ok(time.with_timezone(&Utc).into()),
+                _ => Ok(s.clone().into()),
+            }
+        }
+        serde_json::Value::Bool(b) => Ok((*b).into()),
+        serde_json::Value::Number(n) => n.as_i64().map(Value::from)
+            .or_else(|| n.as_f64().map(Value::from))
+            .ok_or_else(|| AppError::validation_error(format!("字段 {} 的数值无法识别: {}", field, n))),
+        serde_json::Value::Null => Ok(Value::String(None)),
+        other => Err(AppError::validation_error(format!("字段 {} 的过滤值不是单个值: {}", field, other))),
+    }
+}
+
+/// 把JSON数组过滤值转换为列值列表
+fn column_values<C: ColumnTrait>(column: &C, filter: &FilterCondition) -> AppResult<Vec<Value>> {
+    match &filter.value {
+        serde_json::Value::Array(values) => values.iter().map(|v| column_value(column, &filter.field, v)).collect(),
+        other => Err(AppError::validation_error(format!("字段 {} 的 {:?} 条件需要数组值: {}", filter.field, filter.operator, other))),
+    }
+}
+
+/// LIKE条件的模式串原样交给数据库，调用方自行书写 `%`/`_`
+fn like_value(filter: &FilterCondition) -> AppResult<String> {
+    filter.value.as_str().map(str::to_string)
+        .ok_or_else(|| AppError::validation_error(format!("字段 {} 的 {:?} 条件需要字符串值", filter.field, filter.operator)))
+}
+
+/// 把一条领域层过滤条件翻译为列表达式
+pub fn filter_expr<C: ColumnTrait>(column: C, filter: &FilterCondition) -> AppResult<SimpleExpr> {
+    let value = || column_value(&column, &filter.field, &filter.value);
+    Ok(match filter.operator {
+        FilterOperator::Equal => column.eq(value()?),
+        FilterOperator::NotEqual => column.ne(value()?),
+        FilterOperator::GreaterThan => column.gt(value()?),
+        FilterOperator::GreaterThanOrEqual => column.gte(value()?),
+        FilterOperator::LessThan => column.lt(value()?),
+        FilterOperator::LessThanOrEqual => column.lte(value()?),
+        FilterOperator::Like => column.like(like_value(filter)?),
+        FilterOperator::NotLike => column.not_like(like_value(filter)?),
+        FilterOperator::In => column.is_in(column_values(&column, filter)?),
+        FilterOperator::NotIn => column.is_not_in(column_values(&column, filter)?),
+        FilterOperator::IsNull => column.is_null(),
+        FilterOperator::IsNotNull => column.is_not_null(),
+        FilterOperator::Between => match column_values(&column, filter)?.as_slice() {
+            [low, high] => column.between(low.clone(), high.clone()),
+            _ => return Err(AppError::validation_error(format!("字段 {} 的 Between 条件需要两个值", filter.field))),
+        },
+    })
+}
+
+/// 领域层过滤条件（全部满足），字段必须是实体自身的列
+pub fn domain_condition<C: ColumnTrait + FromStr>(criteria: &DomainQueryCriteria) -> AppResult<Condition> {
+    criteria.filters.iter().try_fold(Condition::all(), |condition, filter| {
+        Ok(condition.add(filter_expr(column_by_name::<C>(&filter.field)?, filter)?))
+    })
+}
+
+/// 测试实例的领域层过滤条件
+///
+/// 实例表没有的字段（如 `station_name`、`tag`）回落到通道定义表，经 `definition_id` 子查询过滤
+pub fn test_instance_domain_condition(criteria: &DomainQueryCriteria) -> AppResult<Condition> {
+    criteria.filters.iter().try_fold(Condition::all(), |condition, filter| {
+        if let Ok(column) = channel_test_instance::Column::from_str(filter.field.trim()) {
+            return Ok(condition.add(filter_expr(column, filter)?));
+        }
+        let definition_column = column_by_name::<channel_point_definition::Column>(&filter.field)?;
+        Ok(condition.add(channel_test_instance::Column::DefinitionId.in_subquery(
+            Query::select()
+                .column(channel_point_definition::Column::Id)
+                .from(channel_point_definition::Entity)
+                .and_where(filter_expr(definition_column, filter)?)
+                .to_owned(),
+        )))
+    })
+}
+
+/// 按领域层的排序与页码分页取出结果；页码从1开始
+pub async fn fetch_domain_page<E, D>(db: &D, select: Select<E>, criteria: &DomainQueryCriteria) -> AppResult<Vec<E::Model>>
+where
+    E: EntityTrait,
+    E::Column: FromStr,
+    D: ConnectionTrait,
+{
+    let mut select = select;
+    for sort in &criteria.sort_by {
+        let order = match sort.direction {
+            SortDirection::Ascending => Order::Asc,
+            SortDirection::Descending => Order::Desc,
+        };
+        select = select.order_by(column_by_name::<E::Column>(&sort.field)?, order);
+    }
+    let mut select = order_by_primary_key(select, Order::Asc);
+    if let Some(pagination) = &criteria.pagination {
+        let page_size = pagination.page_size.max(1) as u64;
+        select = select
+            .offset(pagination.page.saturating_sub(1) as u64 * page_size)
+            .limit(page_size);
+    }
+
+    select.all(db).await
+        .map_err(|e| AppError::persistence_error(format!("执行条件查询失败: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, QueryFilter, QueryTrait};
+
+    fn sql<E: EntityTrait>(condition: Condition) -> String {
+        E::find().filter(condition).build(DbBackend::Sqlite).to_string()
+    }
+
+    #[test]
+    fn tag_pattern_maps_wildcards_and_escapes_literals() {
+        let contains = sql::<channel_point_definition::Entity>(
+            Condition::all().add(channel_point_definition::Column::Tag.like(tag_like("PT_21"))),
+        );
+        assert!(contains.contains(r"LIKE '%PT\_21%' ESCAPE '\'"), "{}", contains);
+
+        let wildcard = sql::<channel_point_definition::Entity>(
+            Condition::all().add(channel_point_definition::Column::Tag.like(tag_like("PT*0?"))),
+        );
+        assert!(wildcard.contains("LIKE 'PT%0_'"), "{}", wildcard);
+    }
+
+    #[test]
+    fn empty_criteria_adds_no_conditions() {
+        let criteria = QueryCriteria { station_name: Some("  ".to_string()), ..Default::default() };
+        assert!(channel_definition_condition(&criteria).is_empty());
+        assert!(test_instance_condition(&criteria).is_empty());
+        assert!(test_outcome_condition(&criteria).is_empty());
+    }
+
+    #[test]
+    fn instance_station_filters_through_definitions() {
+        let criteria = QueryCriteria {
+            station_name: Some("樟洋电厂".to_string()),
+            batch_id: Some("batch-1".to_string()),
+            status: Some("TestCompletedFailed".to_string()),
+            ..Default::default()
+        };
+        let query = sql::<channel_test_instance::Entity>(test_instance_condition(&criteria));
+        assert!(query.contains(r#""test_batch_id" = 'batch-1'"#), "{}", query);
+        assert!(query.contains(r#""definition_id" IN (SELECT "id" FROM "channel_point_definitions""#), "{}", query);
+        assert!(query.contains(r#""station_name" = '樟洋电厂'"#), "{}", query);
+        assert!(query.contains(r#""overall_status" = 'TestCompletedFailed'"#), "{}", query);
+    }
+
+    #[test]
+    fn outcomes_reach_batch_through_instances() {
+        let criteria = QueryCriteria { batch_id: Some("batch-1".to_string()), ..Default::default() };
+        let query = sql::<raw_test_outcome::Entity>(test_outcome_condition(&criteria));
+        assert!(query.contains(r#""channel_instance_id" IN (SELECT "instance_id" FROM "channel_test_instances""#), "{}", query);
+    }
+
+    #[test]
+    fn domain_filters_resolve_columns_and_fall_back_to_definitions() {
+        let criteria = DomainQueryCriteria {
+            filters: vec![
+                FilterCondition { field: "overall_status".to_string(), operator: FilterOperator::In, value: serde_json::json!(["NotTested", "Retesting"]) },
+                FilterCondition { field: "stationName".to_string(), operator: FilterOperator::Equal, value: serde_json::json!("樟洋电厂") },
+            ],
+            sort_by: Vec::new(),
+            pagination: None,
+            include_fields: None,
+            exclude_fields: None,
+        };
+        let query = sql::<channel_test_instance::Entity>(test_instance_domain_condition(&criteria).unwrap());
+        assert!(query.contains(r#""overall_status" IN ('NotTested', 'Retesting')"#), "{}", query);
+        assert!(query.contains(r#""definition_id" IN (SELECT"#), "{}", query);
+
+        let unknown = DomainQueryCriteria {
+            filters: vec![FilterCondition { field: "no_such_field".to_string(), operator: FilterOperator::IsNull, value: serde_json::Value::Null }],
+            ..criteria
+        };
+        assert!(domain_condition::<test_batch_info::Column>(&unknown).is_err());
+    }
+}
@@ -0,0 +1,502 @@
+//! # SQLite在线备份与恢复模块
+//!
+//! ## 业务作用
+//! FAT进行中笔记本上的数据库一旦损坏，整场测试记录都会丢失。本模块提供不停机的备份与可校验的恢复：
+//! - **热备份**: `VACUUM INTO` 生成一致性快照，只持有读事务，不阻塞测试写入
+//! - **备份信息**: 读取备份文件的结构版本（`PRAGMA user_version`）与各业务表记录数
+//! - **校验恢复**: 先对备份执行 `integrity_check`，再在单个事务内按列交集替换各表数据，恢复后对当前库再做一次检查
+//! - **自动备份清理**: 只保留最近若干份自动备份，手动备份不受影响
+//! - **恢复前备份**: 每次恢复前自动保存当前数据，只保留最近 [`MAX_PRE_RESTORE_BACKUPS`] 份
+//!
+//! ## 恢复方式
+//! 连接池中的连接不能直接替换数据库文件，因此恢复时在一个固定连接上 `ATTACH` 备份库，
+//! 用 `INSERT INTO main.表 SELECT ... FROM 备份.表` 覆盖数据；旧版本备份缺少的列使用当前表的默认值。
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use log::{info, warn};
+use sea_orm::sqlx::{self, Connection, SqliteConnection};
+use sea_orm::{ConnectOptions, ConnectionTrait, Database, DatabaseConnection, EntityName, Statement};
+
+use crate::models::entities;
+use crate::utils::error::{AppError, AppResult};
+use super::persistence_service::BackupInfo;
+
+/// 备份文件扩展名
+pub const BACKUP_EXTENSION: &str = "sqlite";
+
+/// 自动备份的名称前缀，清理旧备份时只处理带此前缀的文件
+pub const AUTO_BACKUP_PREFIX: &str = "auto";
+
+/// 恢复前备份的名称前缀，按 [`MAX_PRE_RESTORE_BACKUPS`] 单独清理
+pub const PRE_RESTORE_BACKUP_PREFIX: &str = "pre_restore";
+
+/// 保留的恢复前备份份数
+pub const MAX_PRE_RESTORE_BACKUPS: usize = 3;
+
+/// 恢复时挂载备份库使用的别名
+const RESTORE_SCHEMA: &str = "restore_src";
+
+/// 备份信息中统计记录数的业务表
+fn counted_tables() -> [&'static str; 4] {
+    [
+        entities::channel_point_definition::Entity.table_name(),
+        entities::test_batch_info::Entity.table_name(),
+        entities::channel_test_instance::Entity.table_name(),
+        entities::raw_test_outcome::Entity.table_name(),
+    ]
+}
+
+/// 把数据库文件路径转换为SeaORM连接地址
+pub fn sqlite_url(path: &Path, read_only: bool) -> AppResult<String> {
+    let absolute_path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|e| AppError::io_error("获取当前目录失败".to_string(), e.kind().to_string()))?
+            .join(path)
+    };
+
+    // 在Windows上，需要使用正确的路径格式
+    #[cfg(windows)]
+    let url = format!("sqlite:///{}", absolute_path.to_string_lossy().replace('\\', "/"));
+    #[cfg(not(windows))]
+    let url = format!("sqlite://{}", absolute_path.to_string_lossy());
+
+    Ok(if read_only { format!("{}?mode=ro", url) } else { url })
+}
+
+fn timestamped_file_name(name: &str, at: DateTime<Utc>) -> String {
+    format!("{}_{}.{}", name, at.format("%Y%m%d_%H%M%S_%3f"), BACKUP_EXTENSION)
+}
+
+/// 生成手动备份文件名：`<名称>_<UTC时间>.sqlite`，名称中的非法字符替换为 `_`
+///
+/// 以 `auto` 或 `pre_restore` 开头的名称加 `manual_` 前缀，避免手动备份被当作自动生成的备份清理
+pub fn backup_file_name(name: &str, at: DateTime<Utc>) -> String {
+    let mut safe: String = name.trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if safe.is_empty() {
+        safe = "manual".to_string();
+    } else if [AUTO_BACKUP_PREFIX, PRE_RESTORE_BACKUP_PREFIX].iter().any(|prefix| safe.to_lowercase().starts_with(prefix)) {
+        safe = format!("manual_{}", safe);
+    }
+    timestamped_file_name(&safe, at)
+}
+
+/// 生成自动备份文件名：`auto_<UTC时间>.sqlite`
+pub fn auto_backup_file_name(at: DateTime<Utc>) -> String {
+    timestamped_file_name(AUTO_BACKUP_PREFIX, at)
+}
+
+/// 生成恢复前备份文件名：`pre_restore_<UTC时间>.sqlite`
+pub fn pre_restore_backup_file_name(at: DateTime<Utc>) -> String {
+    timestamped_file_name(PRE_RESTORE_BACKUP_PREFIX, at)
+}
+
+fn has_prefix(path: &Path, prefix: &str) -> bool {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .is_some_and(|stem| stem.starts_with(&format!("{}_", prefix)))
+}
+
+/// 是否为自动备份文件
+pub fn is_auto_backup(path: &Path) -> bool {
+    has_prefix(path, AUTO_BACKUP_PREFIX)
+}
+
+/// 是否为恢复前备份文件
+pub fn is_pre_restore_backup(path: &Path) -> bool {
+    has_prefix(path, PRE_RESTORE_BACKUP_PREFIX)
+}
+
+/// 用 `VACUUM INTO` 把当前库写入目标文件，目标文件必须不存在
+pub async fn vacuum_into(db: &DatabaseConnection, target: &Path) -> AppResult<()> {
+    if let Some(parent) = target.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|e|
+            AppError::io_error(format!("创建备份目录失败: {:?}", parent), e.kind().to_string())
+        )?;
+    }
+    db.execute(Statement::from_sql_and_values(
+        sea_orm::DatabaseBackend::Sqlite,
+        "VACUUM INTO ?",
+        vec![target.to_string_lossy().to_string().into()],
+    ))
+    .await
+    .map_err(|e| AppError::persistence_error(format!("写入备份文件 {:?} 失败: {}", target, e)))?;
+    Ok(())
+}
+
+/// 执行 `PRAGMA integrity_check`，返回发现的问题；为空表示数据库完好
+pub async fn integrity_problems<C: ConnectionTrait>(db: &C) -> AppResult<Vec<String>> {
+    let rows = db.query_all(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, "PRAGMA integrity_check"))
+        .await
+        .map_err(|e| AppError::persistence_error(format!("数据库完整性检查失败: {}", e)))?;
+    let messages = rows.iter()
+        .map(|row| row.try_get_by_index::<String>(0))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| AppError::persistence_error(format!("读取完整性检查结果失败: {}", e)))?;
+    Ok(messages.into_iter().filter(|message| message != "ok").collect())
+}
+
+/// 读取数据库结构版本（`PRAGMA user_version`）
+pub async fn schema_version<C: ConnectionTrait>(db: &C) -> AppResult<i32> {
+    let row = db.query_one(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, "PRAGMA user_version"))
+        .await
+        .map_err(|e| AppError::persistence_error(format!("读取数据库结构版本失败: {}", e)))?;
+    Ok(match row {
+        Some(row) => row.try_get_by_index::<i32>(0)
+            .map_err(|e| AppError::persistence_error(format!("读取数据库结构版本失败: {}", e)))?,
+        None => 0,
+    })
+}
+
+/// 以只读方式打开备份文件
+async fn open_backup(path: &Path) -> AppResult<DatabaseConnection> {
+    if !path.is_file() {
+        return Err(AppError::not_found_error("Backup", format!("备份文件不存在: {:?}", path)));
+    }
+    let mut options = ConnectOptions::new(sqlite_url(path, true)?);
+    options.max_connections(1).min_connections(1).sqlx_logging(false);
+    Database::connect(options)
+        .await
+        .map_err(|e| AppError::persistence_error(format!("打开备份文件 {:?} 失败: {}", path, e)))
+}
+
+/// 读取已打开备份库的信息
+async fn read_backup_info(db: &DatabaseConnection, path: &Path) -> AppResult<BackupInfo> {
+    let metadata = tokio::fs::metadata(path).await.map_err(|e|
+        AppError::io_error(format!("获取备份文件 {:?} 元数据失败", path), e.kind().to_string())
+    )?;
+
+    let mut record_counts = BTreeMap::new();
+    for table in counted_tables() {
+        let sql = format!("SELECT COUNT(*) FROM \"{}\"", table);
+        // 旧版本备份可能缺少个别表，跳过即可
+        let row = db.query_one(Statement::from_string(sea_orm::DatabaseBackend::Sqlite, sql)).await;
+        if let Some(count) = row.ok().flatten().and_then(|row| row.try_get_by_index::<i64>(0).ok()) {
+            record_counts.insert(table.to_string(), count.max(0) as u64);
+        }
+    }
+
+    Ok(BackupInfo {
+        name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+        path: path.to_path_buf(),
+        created_at: metadata.modified().map(DateTime::<Utc>::from).unwrap_or_else(|_| Utc::now()),
+        size_bytes: metadata.len(),
+        description: None,
+        is_auto_backup: is_auto_backup(path),
+        schema_version: schema_version(db).await?,
+        record_counts,
+    })
+}
+
+/// 读取备份文件的大小、结构版本与各表记录数
+pub async fn inspect_backup(path: &Path) -> AppResult<BackupInfo> {
+    let db = open_backup(path).await?;
+    let info = read_backup_info(&db, path).await;
+    let _ = db.close().await;
+    info
+}
+
+/// 列出目录下的备份，按创建时间从新到旧排列；无法读取的文件记录警告后跳过
+pub async fn list_backups(dir: &Path) -> AppResult<Vec<BackupInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut entries = tokio::fs::read_dir(dir).await.map_err(|e|
+        AppError::io_error(format!("读取备份目录 {:?} 失败", dir), e.kind().to_string())
+    )?;
+
+    let mut backups = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(|e|
+        AppError::io_error("读取备份目录条目失败".to_string(), e.kind().to_string())
+    )? {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some(BACKUP_EXTENSION) {
+            continue;
+        }
+        match inspect_backup(&path).await {
+            Ok(info) => backups.push(info),
+            Err(e) => warn!("[BACKUP] 跳过无法读取的备份 {:?}: {}", path, e),
+        }
+    }
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// 只保留最近 `keep` 份自动备份，返回删除的文件数
+pub async fn prune_auto_backups(dir: &Path, keep: usize) -> AppResult<u32> {
+    prune_backups(dir, keep, is_auto_backup).await
+}
+
+/// 只保留最近 `keep` 份恢复前备份，返回删除的文件数
+pub async fn prune_pre_restore_backups(dir: &Path, keep: usize) -> AppResult<u32> {
+    prune_backups(dir, keep, is_pre_restore_backup).await
+}
+
+async fn prune_backups(dir: &Path, keep: usize, is_kind: fn(&Path) -> bool) -> AppResult<u32> {
+    let mut removed = 0;
+    for backup in list_backups(dir).await?.into_iter().filter(|b| is_kind(&b.path)).skip(keep) {
+        match tokio::fs::remove_file(&backup.path).await {
+            Ok(()) => removed += 1,
+            Err(e) => warn!("[BACKUP] 删除旧备份 {:?} 失败: {}", backup.path, e),
+        }
+    }
+    Ok(removed)
+}
+
+/// 校验备份文件：完整性检查通过且结构版本不高于当前版本
+pub async fn verify_backup(path: &Path, current_schema_version: i32) -> AppResult<BackupInfo> {
+    let db = open_backup(path).await?;
+    let verified = async {
+        let problems = integrity_problems(&db).await?;
+        if !problems.is_empty() {
+            return Err(AppError::persistence_error(format!(
+                "备份文件 {:?} 未通过完整性检查: {}", path, problems.join("; ")
+            )));
+        }
+        let info = read_backup_info(&db, path).await?;
+        if info.schema_version > current_schema_version {
+            return Err(AppError::validation_error(format!(
+                "备份结构版本 {} 高于当前版本 {}，请使用更新的软件恢复", info.schema_version, current_schema_version
+            )));
+        }
+        Ok(info)
+    }.await;
+    let _ = db.close().await;
+    verified
+}
+
+fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+async fn table_names(conn: &mut SqliteConnection, schema: &str) -> AppResult<Vec<String>> {
+    let sql = format!(
+        "SELECT name FROM {}.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        schema
+    );
+    sqlx::query_scalar::<_, String>(&sql)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::persistence_error(format!("读取 {} 表清单失败: {}", schema, e)))
+}
+
+async fn column_names(conn: &mut SqliteConnection, schema: &str, table: &str) -> AppResult<Vec<String>> {
+    let sql = format!("SELECT name FROM pragma_table_info(?, '{}') ORDER BY cid", schema);
+    sqlx::query_scalar::<_, String>(&sql)
+        .bind(table)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| AppError::persistence_error(format!("读取 {}.{} 列信息失败: {}", schema, table, e)))
+}
+
+/// 在一个事务内用备份库的数据替换当前库中同名表的数据
+async fn copy_tables_from_backup(conn: &mut SqliteConnection) -> AppResult<Vec<String>> {
+    let backup_tables: HashSet<String> = table_names(conn, RESTORE_SCHEMA).await?.into_iter().collect();
+    let tables: Vec<String> = table_names(conn, "main").await?
+        .into_iter()
+        .filter(|table| backup_tables.contains(table))
+        .collect();
+
+    let mut plan = Vec::with_capacity(tables.len());
+    for table in &tables {
+        let backup_columns: HashSet<String> = column_names(conn, RESTORE_SCHEMA, table).await?.into_iter().collect();
+        let columns: Vec<String> = column_names(conn, "main", table).await?
+            .into_iter()
+            .filter(|column| backup_columns.contains(column))
+            .map(|column| quote(&column))
+            .collect();
+        plan.push((table.clone(), columns.join(", ")));
+    }
+
+    let mut tx = conn.begin().await
+        .map_err(|e| AppError::persistence_error(format!("开启恢复事务失败: {}", e)))?;
+    // 外键检查推迟到提交时，避免按表顺序删除/插入时出现中间态冲突
+    sqlx::query("PRAGMA defer_foreign_keys = ON").execute(&mut *tx).await
+        .map_err(|e| AppError::persistence_error(format!("设置外键延迟检查失败: {}", e)))?;
+    for (table, columns) in &plan {
+        let table = quote(table);
+        sqlx::query(&format!("DELETE FROM main.{}", table)).execute(&mut *tx).await
+            .map_err(|e| AppError::persistence_error(format!("清空表 {} 失败: {}", table, e)))?;
+        if columns.is_empty() {
+            continue;
+        }
+        sqlx::query(&format!(
+            "INSERT INTO main.{table} ({columns}) SELECT {columns} FROM {schema}.{table}",
+            table = table, columns = columns, schema = RESTORE_SCHEMA
+        ))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| AppError::persistence_error(format!("从备份恢复表 {} 失败: {}", table, e)))?;
+    }
+    tx.commit().await
+        .map_err(|e| AppError::persistence_error(format!("提交恢复事务失败: {}", e)))?;
+
+    Ok(tables)
+}
+
+/// 用备份文件的数据覆盖当前库，返回恢复的表名
+///
+/// 调用方应先用 [`verify_backup`] 校验备份；任何一步失败都会整体回滚，当前数据保持不变
+pub async fn restore_from_file(db: &DatabaseConnection, backup: &Path) -> AppResult<Vec<String>> {
+    let mut conn = db.get_sqlite_connection_pool().acquire().await
+        .map_err(|e| AppError::persistence_error(format!("获取数据库连接失败: {}", e)))?;
+
+    sqlx::query(&format!("ATTACH DATABASE ? AS {}", RESTORE_SCHEMA))
+        .bind(backup.to_string_lossy().to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AppError::persistence_error(format!("挂载备份文件 {:?} 失败: {}", backup, e)))?;
+
+    let restored = copy_tables_from_backup(&mut conn).await;
+
+    // 无论恢复成功与否都要分离备份库，该连接随后会回到连接池
+    if let Err(e) = sqlx::query(&format!("DETACH DATABASE {}", RESTORE_SCHEMA)).execute(&mut *conn).await {
+        warn!("[BACKUP] 分离备份库失败，关闭该连接: {}", e);
+        let _ = conn.detach().close().await;
+    }
+
+    let restored = restored?;
+    info!("[BACKUP] 已从 {:?} 恢复 {} 张表", backup, restored.len());
+    Ok(restored)
+}
+
+/// 备份目录下按文件名定位备份；备份ID只能是文件名，不能包含路径分隔符或 `..`
+pub fn backup_path(dir: &Path, backup_id: &str) -> AppResult<PathBuf> {
+    if backup_id.trim().is_empty() || backup_id.contains(['/', '\\']) || backup_id.contains("..") {
+        return Err(AppError::validation_error(format!("无效的备份ID: {}", backup_id)));
+    }
+    let file = Path::new(backup_id);
+    Ok(if file.extension().and_then(|ext| ext.to_str()) == Some(BACKUP_EXTENSION) {
+        dir.join(file)
+    } else {
+        dir.join(format!("{}.{}", backup_id, BACKUP_EXTENSION))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    async fn file_db(path: &Path) -> DatabaseConnection {
+        let mut options = ConnectOptions::new(format!("{}?mode=rwc", sqlite_url(path, false).unwrap()));
+        options.max_connections(2).sqlx_logging(false);
+        Database::connect(options).await.unwrap()
+    }
+
+    async fn exec(db: &DatabaseConnection, sql: &str) {
+        db.execute_unprepared(sql).await.unwrap();
+    }
+
+    async fn count(db: &DatabaseConnection, table: &str) -> i64 {
+        let row = db.query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Sqlite,
+            format!("SELECT COUNT(*) FROM \"{}\"", table),
+        )).await.unwrap().unwrap();
+        row.try_get_by_index::<i64>(0).unwrap()
+    }
+
+    #[test]
+    fn backup_file_names_are_sanitized_and_timestamped() {
+        let at = Utc.with_ymd_and_hms(2024, 5, 6, 7, 8, 9).unwrap();
+        assert_eq!(backup_file_name("FAT 前/备份", at), "FAT_前_备份_20240506_070809_000.sqlite");
+        assert_eq!(backup_file_name("  ", at), "manual_20240506_070809_000.sqlite");
+        assert!(is_auto_backup(Path::new(&auto_backup_file_name(at))));
+        assert!(!is_auto_backup(Path::new("automatic_20240506.sqlite")));
+        // 用户命名的备份不会被当作自动备份清理
+        assert_eq!(backup_file_name("auto", at), "manual_auto_20240506_070809_000.sqlite");
+        assert!(!is_auto_backup(Path::new(&backup_file_name("auto", at))));
+        assert!(!is_auto_backup(Path::new(&backup_file_name("Auto 升级前", at))));
+        assert!(is_pre_restore_backup(Path::new(&pre_restore_backup_file_name(at))));
+        assert!(!is_pre_restore_backup(Path::new(&backup_file_name("pre_restore", at))));
+    }
+
+    #[test]
+    fn backup_ids_cannot_leave_the_backup_directory() {
+        let dir = Path::new("b");
+        assert_eq!(backup_path(dir, "x_1").unwrap(), dir.join("x_1.sqlite"));
+        assert_eq!(backup_path(dir, "x_1.sqlite").unwrap(), dir.join("x_1.sqlite"));
+        for backup_id in ["", "../live", "..", "sub/x_1", "..\\live.sqlite", "/etc/passwd"] {
+            assert!(matches!(backup_path(dir, backup_id), Err(AppError::ValidationError { .. })), "{}", backup_id);
+        }
+    }
+
+    #[tokio::test]
+    async fn backup_inspect_and_restore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = file_db(&dir.path().join("live.sqlite")).await;
+        let batches = entities::test_batch_info::Entity.table_name();
+        exec(&db, &format!("CREATE TABLE \"{}\" (batch_id TEXT PRIMARY KEY, batch_name TEXT NOT NULL)", batches)).await;
+        exec(&db, &format!("INSERT INTO \"{}\" VALUES ('b1', '批次1'), ('b2', '批次2')", batches)).await;
+        exec(&db, "PRAGMA user_version = 3").await;
+
+        let backup = dir.path().join("backups").join(auto_backup_file_name(Utc::now()));
+        vacuum_into(&db, &backup).await.unwrap();
+
+        let info = verify_backup(&backup, 3).await.unwrap();
+        assert_eq!(info.schema_version, 3);
+        assert_eq!(info.record_counts.get(batches), Some(&2));
+        assert!(info.is_auto_backup);
+        assert!(verify_backup(&backup, 2).await.is_err(), "较新结构的备份不能恢复到旧版本");
+
+        // 备份之后新增列并改动数据，恢复后数据回到备份时刻，新增列取默认值
+        exec(&db, &format!("ALTER TABLE \"{}\" ADD COLUMN station_name TEXT DEFAULT '未知'", batches)).await;
+        exec(&db, &format!("DELETE FROM \"{}\" WHERE batch_id = 'b1'", batches)).await;
+        exec(&db, &format!("INSERT INTO \"{}\" (batch_id, batch_name) VALUES ('b3', '批次3')", batches)).await;
+
+        let restored = restore_from_file(&db, &backup).await.unwrap();
+        assert_eq!(restored, vec![batches.to_string()]);
+        assert_eq!(count(&db, batches).await, 2);
+        let row = db.query_one(Statement::from_string(
+            sea_orm::DatabaseBackend::Sqlite,
+            format!("SELECT station_name FROM \"{}\" WHERE batch_id = 'b1'", batches),
+        )).await.unwrap().unwrap();
+        assert_eq!(row.try_get_by_index::<String>(0).unwrap(), "未知");
+        assert!(integrity_problems(&db).await.unwrap().is_empty());
+
+        // 恢复后连接已分离备份库，可以再次挂载
+        restore_from_file(&db, &backup).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn corrupted_backup_fails_verification_and_old_auto_backups_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let corrupted = dir.path().join("manual_20240101_000000_000.sqlite");
+        tokio::fs::write(&corrupted, b"not a sqlite database").await.unwrap();
+        assert!(verify_backup(&corrupted, 1).await.is_err());
+        tokio::fs::remove_file(&corrupted).await.unwrap();
+
+        let db = file_db(&dir.path().join("live.sqlite")).await;
+        exec(&db, "CREATE TABLE t (id INTEGER)").await;
+        for second in 0..3 {
+            let at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, second).unwrap();
+            vacuum_into(&db, &dir.path().join(auto_backup_file_name(at))).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        vacuum_into(&db, &dir.path().join(backup_file_name("manual", Utc::now()))).await.unwrap();
+
+        // live.sqlite 扩展名相同也会被列出，清理只针对自动备份
+        assert_eq!(prune_auto_backups(dir.path(), 1).await.unwrap(), 2);
+        let remaining = list_backups(dir.path()).await.unwrap();
+        assert_eq!(remaining.iter().filter(|b| b.is_auto_backup).count(), 1);
+        assert!(remaining.iter().any(|b| b.name.starts_with("manual_")));
+
+        // 恢复前备份单独计数清理，不影响自动备份与手动备份
+        for second in 0..3 {
+            let at = Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, second).unwrap();
+            vacuum_into(&db, &dir.path().join(pre_restore_backup_file_name(at))).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(prune_pre_restore_backups(dir.path(), 2).await.unwrap(), 1);
+        let remaining = list_backups(dir.path()).await.unwrap();
+        assert_eq!(remaining.iter().filter(|b| is_pre_restore_backup(&b.path)).count(), 2);
+        assert_eq!(remaining.iter().filter(|b| b.is_auto_backup).count(), 1);
+        assert!(remaining.iter().any(|b| b.name.starts_with("manual_")));
+    }
+}
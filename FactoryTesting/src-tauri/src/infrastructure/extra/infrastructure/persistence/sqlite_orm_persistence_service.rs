@@ -2,7 +2,7 @@
 // 详细注释：使用SeaORM和SQLite实现数据持久化服务
 
 use async_trait::async_trait;
-use sea_orm::{Database, DatabaseConnection, Schema, ConnectionTrait, EntityTrait, QueryFilter, ColumnTrait, PaginatorTrait, ActiveModelTrait, Set, ConnectOptions, TransactionTrait};
+use sea_orm::{Database, DatabaseConnection, Schema, ConnectionTrait, EntityTrait, QueryFilter, ColumnTrait, PaginatorTrait, ActiveModelTrait, Set, ConnectOptions, TransactionTrait, QueryOrder};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex}; // 使用 Mutex
 use chrono::Utc;
@@ -28,6 +28,12 @@ use crate::infrastructure::persistence::persistence_service::{
     IntegrityStatus, // 导入 IntegrityStatus
     IntegrityCheckResult // 导入 IntegrityCheckResult
 };
+use super::query_builder;
+use super::sqlite_backup;
+use super::data_retention;
+use super::transaction_writer;
+use crate::domain::services::persistence_service::TransactionOperation;
+use super::app_settings_service::{AppSettingsService, record_last_backup_time};
 use crate::utils::error::{AppError, AppResult};
 use log::{info, warn, error, debug, trace};
 use uuid::Uuid;
@@ -36,6 +42,14 @@ use uuid::Uuid;
 const DEFAULT_DB_FILE: &str = "factory_testing_data.sqlite";
 const SQLITE_URL_PREFIX: &str = "sqlite://";
 const BACKUPS_DIR_NAME: &str = "_backups"; // 修改常量名并统一为 _backups
+/// 应用启动后首次检查自动备份前的等待时间
+const AUTO_BACKUP_STARTUP_DELAY: Duration = Duration::from_secs(60);
+/// 自动备份失败后的重试间隔
+const AUTO_BACKUP_RETRY_DELAY: Duration = Duration::from_secs(600);
+/// 应用启动后首次执行过期数据清理前的等待时间，排在首次自动备份之后
+const RETENTION_STARTUP_DELAY: Duration = Duration::from_secs(300);
+/// 到点时有批次正在测试，推迟清理后再次检查的间隔
+const RETENTION_BUSY_RETRY_DELAY: Duration = Duration::from_secs(1800);
 
 /// 基于SeaORM和SQLite的持久化服务实现
 #[derive(Clone)]
@@ -276,6 +290,129 @@ impl SqliteOrmPersistenceService {
     pub fn get_database_connection(&self) -> &DatabaseConnection {
         self.db_conn.as_ref()
     }
+
+    /// 在一个事务内批量写入，供各批量保存接口复用
+    async fn write_in_transaction(&self, operations: Vec<TransactionOperation>) -> AppResult<()> {
+        transaction_writer::execute(self.db_conn.as_ref(), &operations).await.map(|_| ())
+    }
+
+    /// 数据库文件所在目录，内存库使用存储根目录
+    fn data_dir(&self) -> &Path {
+        if self.db_file_path.to_str() == Some(":memory:") {
+            self.config.storage_root_dir.as_path()
+        } else {
+            self.db_file_path.parent().unwrap_or(self.config.storage_root_dir.as_path())
+        }
+    }
+
+    /// 备份目录：与数据库文件同级的 `_backups`
+    pub fn backups_dir(&self) -> PathBuf {
+        self.data_dir().join(BACKUPS_DIR_NAME)
+    }
+
+    /// 把当前库热备份为备份目录下的指定文件
+    async fn write_backup(&self, file_name: String) -> AppResult<BackupInfo> {
+        let target = self.backups_dir().join(file_name);
+        sqlite_backup::vacuum_into(self.db_conn.as_ref(), &target).await?;
+
+        let mut info = sqlite_backup::inspect_backup(&target).await?;
+        info.description = Some(format!("数据库热备份，结构版本 {}", info.schema_version));
+        info!("🗄️ [PERSIST] 数据库已备份到 {:?} ({} 字节)", info.path, info.size_bytes);
+        Ok(info)
+    }
+
+    /// 归档目录：与数据库文件同级的 `_archive`，存放清理前归档的过期数据
+    pub fn archive_dir(&self) -> PathBuf {
+        self.data_dir().join(data_retention::ARCHIVE_DIR_NAME)
+    }
+
+    /// 按保留配置启动定时清理，未启用时返回 None
+    ///
+    /// 每次清理后若有数据被删除或精简，按配置执行VACUUM回收磁盘空间；
+    /// 到点时 `busy` 报告有批次正在测试则推迟，直到测试结束后再清理
+    pub fn spawn_retention_cleanup(
+        &self,
+        schedule: &crate::utils::config::DataRetentionConfig,
+        busy: data_retention::BusyProbe,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        if !schedule.enabled {
+            info!("🗄️ [PERSIST] 过期数据定时清理未启用");
+            return None;
+        }
+
+        let interval = Duration::from_secs(schedule.cleanup_interval_hours.max(1) * 3600);
+        let policy = crate::domain::services::persistence_service::RetentionPolicy::from(schedule);
+        let compact = schedule.compact_after_cleanup;
+        let service = self.clone();
+        info!("🗄️ [PERSIST] 过期数据定时清理已启用: 每 {} 小时一次，策略 {:?}", interval.as_secs() / 3600, policy);
+
+        Some(tokio::spawn(async move {
+            tokio::time::sleep(RETENTION_STARTUP_DELAY).await;
+            loop {
+                if busy().await {
+                    info!("🗄️ [PERSIST] 有批次正在测试，过期数据清理推迟 {:?}", RETENTION_BUSY_RETRY_DELAY);
+                    tokio::time::sleep(RETENTION_BUSY_RETRY_DELAY).await;
+                    continue;
+                }
+                if let Err(e) = data_retention::run_cleanup(&service, &policy, compact, &busy).await {
+                    error!("🗄️ [PERSIST] 过期数据清理失败: {}", e);
+                }
+                tokio::time::sleep(interval).await;
+            }
+        }))
+    }
+
+    /// 按持久化配置启动定时自动备份，未启用 `auto_backup` 时返回 None
+    ///
+    /// 以最近一次自动备份的时间为计时起点，应用重启不会推迟或重复备份；
+    /// 每次备份后只保留最近 `max_backups` 份自动备份，并记录到应用配置的 `last_backup_time`
+    pub fn spawn_auto_backup(
+        &self,
+        settings_service: Arc<dyn AppSettingsService>,
+        schedule: &crate::utils::config::PersistenceConfig,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        if !schedule.auto_backup {
+            info!("🗄️ [PERSIST] 自动备份未启用");
+            return None;
+        }
+
+        let interval = chrono::Duration::hours(schedule.backup_interval_hours.max(1) as i64);
+        let keep = schedule.max_backups.max(1);
+        let service = self.clone();
+        info!("🗄️ [PERSIST] 自动备份已启用: 每 {} 小时一次，保留 {} 份", interval.num_hours(), keep);
+
+        Some(tokio::spawn(async move {
+            // 避开启动阶段的迁移与数据加载
+            tokio::time::sleep(AUTO_BACKUP_STARTUP_DELAY).await;
+            loop {
+                let last_auto_backup = match sqlite_backup::list_backups(&service.backups_dir()).await {
+                    Ok(backups) => backups.into_iter().find(|b| b.is_auto_backup).map(|b| b.created_at),
+                    Err(e) => {
+                        warn!("🗄️ [PERSIST] 读取备份列表失败: {}", e);
+                        None
+                    }
+                };
+                if let Some(last) = last_auto_backup {
+                    // 时间已过（负值）时立即备份
+                    let wait = (last + interval - Utc::now()).to_std().unwrap_or_default();
+                    tokio::time::sleep(wait).await;
+                }
+
+                match service.write_backup(sqlite_backup::auto_backup_file_name(Utc::now())).await {
+                    Ok(info) => {
+                        record_last_backup_time(settings_service.as_ref(), info.created_at).await;
+                        if let Err(e) = sqlite_backup::prune_auto_backups(&service.backups_dir(), keep).await {
+                            warn!("🗄️ [PERSIST] 清理过期自动备份失败: {}", e);
+                        }
+                    }
+                    Err(e) => {
+                        error!("🗄️ [PERSIST] 自动备份失败，{:?} 后重试: {}", AUTO_BACKUP_RETRY_DELAY, e);
+                        tokio::time::sleep(AUTO_BACKUP_RETRY_DELAY).await;
+                    }
+                }
+            }
+        }))
+    }
 }
 
 #[async_trait]
@@ -379,13 +516,9 @@ impl PersistenceService for SqliteOrmPersistenceService {
         Ok(())
     }
 
-    /// 批量保存通道点位定义
+    /// 批量保存通道点位定义，全部成功或全部回滚
     async fn save_channel_definitions(&self, definitions: &[ChannelPointDefinition]) -> AppResult<()> {
-        if definitions.is_empty() { return Ok(()); }
-        for def in definitions {
-            self.save_channel_definition(def).await?;
-        }
-        Ok(())
+        self.write_in_transaction(definitions.iter().cloned().map(TransactionOperation::SaveChannelDefinition).collect()).await
     }
 
     async fn load_channel_definition(&self, id: &str) -> AppResult<Option<ChannelPointDefinition>> {
@@ -584,6 +717,8 @@ impl PersistenceService for SqliteOrmPersistenceService {
     async fn load_test_outcomes_by_instance(&self, instance_id: &str) -> AppResult<Vec<RawTestOutcome>> {
         let models = entities::raw_test_outcome::Entity::find()
             .filter(entities::raw_test_outcome::Column::ChannelInstanceId.eq(instance_id))
+            // 按执行时间排序，同一测试项的自动重试结果以最后一次为准
+            .order_by_asc(entities::raw_test_outcome::Column::EndTime)
             .all(self.db_conn.as_ref())
             .await
             .map_err(|e| AppError::persistence_error(format!("加载实例 {} 的测试结果失败: {}", instance_id, e)))?;
@@ -1007,12 +1142,15 @@ impl PersistenceService for SqliteOrmPersistenceService {
         Ok(())
     }
 
-    async fn query_channel_definitions(&self, _criteria: &crate::domain::services::persistence_service::QueryCriteria) -> AppResult<Vec<ChannelPointDefinition>> {
-        Err(AppError::not_implemented_error("query_channel_definitions"))
+    async fn query_channel_definitions(&self, criteria: &crate::domain::services::persistence_service::QueryCriteria) -> AppResult<Vec<ChannelPointDefinition>> {
+        use entities::channel_point_definition::{Entity, Column};
+        let condition = query_builder::domain_condition::<Column>(criteria)?;
+        let models = query_builder::fetch_domain_page(self.db_conn.as_ref(), Entity::find().filter(condition), criteria).await?;
+        Ok(models.iter().map(|m| m.into()).collect())
     }
 
-    async fn save_test_instances(&self, _instances: &[ChannelTestInstance]) -> AppResult<()> {
-        Err(AppError::not_implemented_error("save_test_instances (bulk)"))
+    async fn save_test_instances(&self, instances: &[ChannelTestInstance]) -> AppResult<()> {
+        self.write_in_transaction(instances.iter().cloned().map(TransactionOperation::SaveTestInstance).collect()).await
     }
 
     async fn update_instance_error_notes(
@@ -1062,40 +1200,57 @@ impl PersistenceService for SqliteOrmPersistenceService {
         }
     }
 
-    async fn query_test_instances(&self, _criteria: &crate::domain::services::persistence_service::QueryCriteria) -> AppResult<Vec<ChannelTestInstance>> {
-        Err(AppError::not_implemented_error("query_test_instances"))
+    async fn query_test_instances(&self, criteria: &crate::domain::services::persistence_service::QueryCriteria) -> AppResult<Vec<ChannelTestInstance>> {
+        use entities::channel_test_instance::Entity;
+        let condition = query_builder::test_instance_domain_condition(criteria)?;
+        let models = query_builder::fetch_domain_page(self.db_conn.as_ref(), Entity::find().filter(condition), criteria).await?;
+        Ok(models.iter().map(|m| m.into()).collect())
     }
 
-    async fn query_batch_info(&self, _criteria: &crate::domain::services::persistence_service::QueryCriteria) -> AppResult<Vec<TestBatchInfo>> {
-        Err(AppError::not_implemented_error("query_batch_info"))
+    async fn query_batch_info(&self, criteria: &crate::domain::services::persistence_service::QueryCriteria) -> AppResult<Vec<TestBatchInfo>> {
+        use entities::test_batch_info::{Entity, Column};
+        let condition = query_builder::domain_condition::<Column>(criteria)?;
+        let models = query_builder::fetch_domain_page(self.db_conn.as_ref(), Entity::find().filter(condition), criteria).await?;
+        Ok(models.iter().map(|m| m.into()).collect())
     }
 
     async fn save_test_outcomes(&self, outcomes: &[RawTestOutcome]) -> AppResult<()> {
         trace!("🛢️ [PERSIST] 批量保存 {} 条 RawTestOutcome", outcomes.len());
-        for outcome in outcomes {
-            self.save_test_outcome(outcome).await?;
-        }
-        Ok(())
+        self.write_in_transaction(outcomes.iter().cloned().map(TransactionOperation::SaveTestOutcome).collect()).await
     }
 
-    async fn execute_transaction(&self, _operations: Vec<crate::domain::services::persistence_service::TransactionOperation>) -> AppResult<crate::domain::services::persistence_service::TransactionResult> {
-        Err(AppError::not_implemented_error("execute_transaction"))
+    /// 在一个SQLite事务内按顺序执行全部操作，任一失败整体回滚并返回错误
+    async fn execute_transaction(&self, operations: Vec<TransactionOperation>) -> AppResult<crate::domain::services::persistence_service::TransactionResult> {
+        transaction_writer::execute(self.db_conn.as_ref(), &operations).await
     }
 
-    async fn create_backup(&self, _backup_name: &str) -> AppResult<crate::domain::services::persistence_service::BackupInfo> {
-        Err(AppError::not_implemented_error("create_backup"))
+    async fn create_backup(&self, backup_name: &str) -> AppResult<crate::domain::services::persistence_service::BackupInfo> {
+        use crate::domain::services::persistence_service::{BackupInfo as DomainBackupInfo, BackupType};
+        let info = ExtendedPersistenceService::backup(self, backup_name).await?;
+        Ok(DomainBackupInfo {
+            backup_id: info.name,
+            backup_name: backup_name.to_string(),
+            file_path: info.path.to_string_lossy().to_string(),
+            size_bytes: info.size_bytes,
+            created_at: info.created_at,
+            backup_type: BackupType::Full,
+            compression_ratio: None,
+        })
     }
 
-    async fn restore_backup(&self, _backup_id: &str) -> AppResult<()> {
-        Err(AppError::not_implemented_error("restore_backup"))
+    async fn restore_backup(&self, backup_id: &str) -> AppResult<()> {
+        let backup_path = sqlite_backup::backup_path(&self.backups_dir(), backup_id)?;
+        self.restore_from_backup(&backup_path).await
     }
 
     async fn get_storage_statistics(&self) -> AppResult<crate::domain::services::persistence_service::StorageStatistics> {
         Err(AppError::not_implemented_error("get_storage_statistics"))
     }
 
-    async fn cleanup_expired_data(&self, _retention_policy: &crate::domain::services::persistence_service::RetentionPolicy) -> AppResult<crate::domain::services::persistence_service::CleanupResult> {
-        Err(AppError::not_implemented_error("cleanup_expired_data"))
+    async fn cleanup_expired_data(&self, retention_policy: &crate::domain::services::persistence_service::RetentionPolicy) -> AppResult<crate::domain::services::persistence_service::CleanupResult> {
+        let archive_file = retention_policy.archive_before_delete
+            .then(|| self.archive_dir().join(data_retention::archive_file_name(Utc::now())));
+        data_retention::apply_retention(self.db_conn.as_ref(), retention_policy, archive_file.as_deref()).await
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -1106,93 +1261,86 @@ impl PersistenceService for SqliteOrmPersistenceService {
 #[async_trait]
 impl ExtendedPersistenceService for SqliteOrmPersistenceService {
     async fn backup(&self, backup_name: &str) -> AppResult<BackupInfo> {
-        log::warn!("SqliteOrmPersistenceService::backup is temporarily not implemented.");
-        // 暂时移除具体实现，直到依赖问题解决
-        Err(AppError::not_implemented_error(format!("Backup functionality for '{}' is temporarily disabled due to dependency issues.", backup_name)))
+        self.write_backup(sqlite_backup::backup_file_name(backup_name, Utc::now())).await
     }
 
     async fn restore_from_backup(&self, backup_path: &PathBuf) -> AppResult<()> {
-        log::warn!("SqliteOrmPersistenceService::restore_from_backup is temporarily not implemented.");
-        Err(AppError::not_implemented_error(format!("Restore from backup functionality for '{:?}' is temporarily disabled.", backup_path)))
-    }
-
-    async fn list_backups(&self) -> AppResult<Vec<BackupInfo>> {
-        log::warn!("SqliteOrmPersistenceService::list_backups is temporarily not implemented.");
-        Err(AppError::not_implemented_error("List backups functionality is temporarily disabled.".to_string()))
-        // 之前的实现：
-        // let backup_dir = self.config.storage_root_dir.join(BACKUPS_DIR_NAME);
-        // if !backup_dir.exists() {
-        //     return Ok(Vec::new());
-        // }
-        // let mut backups = Vec::new();
-        // let mut entries = tokio::fs::read_dir(backup_dir).await.map_err(|e|
-        //     AppError::io_error("读取备份目录失败".to_string(), e.kind().to_string())
-        // )?;
-        // while let Some(entry) = entries.next_entry().await.map_err(|e|
-        //     AppError::io_error("读取备份目录条目失败".to_string(), e.kind().to_string()))? {
-        //     let path = entry.path();
-        //     if path.is_file() && path.extension().map_or(false, |ext| ext == "sqlite") {
-        //         let metadata = tokio::fs::metadata(&path).await.map_err(|e|
-        //             AppError::io_error(format!("获取备份文件 {:?} 元数据失败", path), e.kind().to_string()))?;
-        //         let name_cow = path.file_stem().unwrap_or_default().to_string_lossy();
-        //         let is_auto = name_cow.starts_with("auto_");
-        //         let name_owned = name_cow.into_owned();
-        //         backups.push(BackupInfo {
-        //             name: name_owned,
-        //             path,
-        //             size_bytes: metadata.len(),
-        //             created_at: metadata.created().map(DateTime::from).unwrap_or_else(|_| Utc::now()),
-        //             description: Some(format!("SQLite backup created on {}", Utc::now().to_rfc2822())),
-        //             is_auto_backup: is_auto,
-        //         });
-        //     }
-        // }
-        // backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-        // Ok(backups)
-    }
-
-    // --- Placeholder implementations for other ExtendedPersistenceService methods ---
-    async fn query_channel_definitions(&self, _criteria: &QueryCriteria) -> AppResult<QueryResult<ChannelPointDefinition>> {
-        Err(AppError::not_implemented_error("query_channel_definitions not implemented for SqliteOrmPersistenceService".to_string()))
-    }
-    async fn query_test_instances(&self, _criteria: &QueryCriteria) -> AppResult<QueryResult<ChannelTestInstance>> {
-        Err(AppError::not_implemented_error("query_test_instances not implemented for SqliteOrmPersistenceService".to_string()))
-    }
-
-
-
-    
-
-
-    async fn query_test_batches(&self, _criteria: &QueryCriteria) -> AppResult<QueryResult<TestBatchInfo>> {
-        Err(AppError::not_implemented_error("query_test_batches not implemented for SqliteOrmPersistenceService".to_string()))
-    }
-    async fn query_test_outcomes(&self, _criteria: &QueryCriteria) -> AppResult<QueryResult<RawTestOutcome>> {
-        Err(AppError::not_implemented_error("query_test_outcomes not implemented for SqliteOrmPersistenceService".to_string()))
-    }
-    async fn batch_save_channel_definitions(&self, _definitions: &[ChannelPointDefinition]) -> AppResult<()> {
-        Err(AppError::not_implemented_error("batch_save_channel_definitions not implemented for SqliteOrmPersistenceService".to_string()))
-    }
-    async fn batch_save_test_instances(&self, instances: &[ChannelTestInstance]) -> AppResult<()> {
-        if instances.is_empty() {
-            return Ok(());
+        let verified = sqlite_backup::verify_backup(backup_path, crate::database_migration::SCHEMA_VERSION).await?;
+        info!("🗄️ [PERSIST] 备份 {:?} 校验通过，结构版本 {}，记录数 {:?}",
+              backup_path, verified.schema_version, verified.record_counts);
+
+        // 覆盖前先保留当前数据，恢复结果不符合预期时可以退回；只保留最近几份恢复前备份
+        let safety = self.write_backup(sqlite_backup::pre_restore_backup_file_name(Utc::now())).await?;
+        if let Err(e) = sqlite_backup::prune_pre_restore_backups(&self.backups_dir(), sqlite_backup::MAX_PRE_RESTORE_BACKUPS).await {
+            warn!("🗄️ [PERSIST] 清理旧的恢复前备份失败: {}", e);
         }
 
-        // 逐个保存，使用 save_test_instance 的 upsert 逻辑
-        for instance in instances {
-            self.save_test_instance(instance).await?;
+        sqlite_backup::restore_from_file(self.db_conn.as_ref(), backup_path).await?;
+
+        // 旧版本备份缺少的表结构与数据关联由迁移补齐
+        crate::database_migration::DatabaseMigration::migrate(self.db_conn.as_ref()).await?;
+
+        let problems = sqlite_backup::integrity_problems(self.db_conn.as_ref()).await?;
+        if !problems.is_empty() {
+            return Err(AppError::persistence_error(format!(
+                "恢复后数据库完整性检查未通过: {}；恢复前的数据保存在 {:?}",
+                problems.join("; "), safety.path
+            )));
         }
 
+        info!("🗄️ [PERSIST] 已从备份 {:?} 恢复数据库，恢复前数据保存在 {:?}", backup_path, safety.path);
         Ok(())
     }
-    async fn batch_save_test_outcomes(&self, _outcomes: &[RawTestOutcome]) -> AppResult<()> {
-        Err(AppError::not_implemented_error("batch_save_test_outcomes not implemented for SqliteOrmPersistenceService".to_string()))
+
+    async fn list_backups(&self) -> AppResult<Vec<BackupInfo>> {
+        sqlite_backup::list_backups(&self.backups_dir()).await
+    }
+
+    // --- 条件查询：过滤、排序与分页均在数据库端完成 ---
+    async fn query_channel_definitions(&self, criteria: &QueryCriteria) -> AppResult<QueryResult<ChannelPointDefinition>> {
+        use entities::channel_point_definition::Entity;
+        let select = Entity::find().filter(query_builder::channel_definition_condition(criteria));
+        let (models, total_count) = query_builder::fetch_page(self.db_conn.as_ref(), select, criteria).await?;
+        Ok(query_builder::page_result(models.iter().map(|m| m.into()).collect(), total_count, criteria))
+    }
+    async fn query_test_instances(&self, criteria: &QueryCriteria) -> AppResult<QueryResult<ChannelTestInstance>> {
+        use entities::channel_test_instance::Entity;
+        let select = Entity::find().filter(query_builder::test_instance_condition(criteria));
+        let (models, total_count) = query_builder::fetch_page(self.db_conn.as_ref(), select, criteria).await?;
+        Ok(query_builder::page_result(models.iter().map(|m| m.into()).collect(), total_count, criteria))
+    }
+    async fn query_test_batches(&self, criteria: &QueryCriteria) -> AppResult<QueryResult<TestBatchInfo>> {
+        use entities::test_batch_info::Entity;
+        let select = Entity::find().filter(query_builder::test_batch_condition(criteria));
+        let (models, total_count) = query_builder::fetch_page(self.db_conn.as_ref(), select, criteria).await?;
+        Ok(query_builder::page_result(models.iter().map(|m| m.into()).collect(), total_count, criteria))
+    }
+    async fn query_test_outcomes(&self, criteria: &QueryCriteria) -> AppResult<QueryResult<RawTestOutcome>> {
+        use entities::raw_test_outcome::Entity;
+        let select = Entity::find().filter(query_builder::test_outcome_condition(criteria));
+        let (models, total_count) = query_builder::fetch_page(self.db_conn.as_ref(), select, criteria).await?;
+        Ok(query_builder::page_result(models.iter().map(|m| m.into()).collect(), total_count, criteria))
+    }
+    async fn batch_save_channel_definitions(&self, definitions: &[ChannelPointDefinition]) -> AppResult<()> {
+        self.write_in_transaction(definitions.iter().cloned().map(TransactionOperation::SaveChannelDefinition).collect()).await
+    }
+    async fn batch_save_test_instances(&self, instances: &[ChannelTestInstance]) -> AppResult<()> {
+        self.write_in_transaction(instances.iter().cloned().map(TransactionOperation::SaveTestInstance).collect()).await
+    }
+    async fn batch_save_test_outcomes(&self, outcomes: &[RawTestOutcome]) -> AppResult<()> {
+        self.write_in_transaction(outcomes.iter().cloned().map(TransactionOperation::SaveTestOutcome).collect()).await
     }
     async fn batch_delete_by_ids(&self, _entity_type: &str, _ids: &[String]) -> AppResult<()> {
         Err(AppError::not_implemented_error("batch_delete_by_ids not implemented for SqliteOrmPersistenceService".to_string()))
     }
     async fn cleanup_old_backups(&self) -> AppResult<u32> {
-        Err(AppError::not_implemented_error("cleanup_old_backups not implemented for SqliteOrmPersistenceService".to_string()))
+        let keep = crate::utils::config::effective_persistence_config().max_backups.max(1);
+        let removed = sqlite_backup::prune_auto_backups(&self.backups_dir(), keep).await?;
+        if removed > 0 {
+            info!("🗄️ [PERSIST] 已清理 {} 份过期自动备份，保留最近 {} 份", removed, keep);
+        }
+        let pre_restore_removed = sqlite_backup::prune_pre_restore_backups(&self.backups_dir(), sqlite_backup::MAX_PRE_RESTORE_BACKUPS).await?;
+        Ok(removed + pre_restore_removed)
     }
     async fn verify_data_integrity(&self) -> AppResult<IntegrityReport> {
         // 可以提供一个简单的实现，例如检查数据库连接是否健康
@@ -1248,14 +1396,23 @@ impl ExtendedPersistenceService for SqliteOrmPersistenceService {
             }
         };
 
-        // last_backup_time 和 last_integrity_check_time 暂时为 None
+        // 备份列表按时间倒序，第一份即最近一次备份
+        let last_backup_time = match sqlite_backup::list_backups(&self.backups_dir()).await {
+            Ok(backups) => backups.first().map(|b| b.created_at),
+            Err(e) => {
+                log::warn!("读取备份列表失败: {}", e);
+                None
+            }
+        };
+
+        // last_integrity_check_time 暂时为 None
         Ok(PersistenceStats {
             channel_definitions_count,
             test_instances_count,
             test_batches_count,
             test_outcomes_count,
             total_storage_size_bytes,
-            last_backup_time: None,
+            last_backup_time,
             last_integrity_check_time: None,
         })
     }
@@ -1265,17 +1422,22 @@ impl ExtendedPersistenceService for SqliteOrmPersistenceService {
     async fn update_config(&mut self, _config: PersistenceConfig) -> AppResult<()> {
         Err(AppError::not_implemented_error("update_config not implemented for SqliteOrmPersistenceService".to_string()))
     }
-    async fn cleanup_expired_data(&self, _retention_days: u32) -> AppResult<u32> {
-        Err(AppError::not_implemented_error("cleanup_expired_data not implemented for SqliteOrmPersistenceService".to_string()))
+    async fn cleanup_expired_data(&self, retention_days: u32) -> AppResult<u32> {
+        // 其余选项沿用配置中的保留策略，三类数据统一使用传入的天数
+        let mut policy = crate::domain::services::persistence_service::RetentionPolicy::from(
+            &crate::utils::config::effective_persistence_config().retention,
+        );
+        policy.test_outcomes_retention_days = retention_days;
+        policy.batch_info_retention_days = retention_days;
+        policy.monitoring_samples_retention_days = retention_days;
+        let result = PersistenceService::cleanup_expired_data(self, &policy).await?;
+        Ok(u32::try_from(result.deleted_records).unwrap_or(u32::MAX))
     }
     async fn compact_storage(&self) -> AppResult<u64> {
-        // 对于 SQLite，可以使用 VACUUM 命令
-        // self.db_conn.execute_unprepared("VACUUM;").await.map_err(|e| AppError::db_error(e.to_string()))?;
-        // Ok(0) // VACUUM 不直接返回释放的空间
-        Err(AppError::not_implemented_error("compact_storage (VACUUM) not fully implemented for SqliteOrmPersistenceService".to_string()))
+        data_retention::compact(self.db_conn.as_ref()).await
     }
     async fn rebuild_indexes(&self) -> AppResult<()> {
-        Err(AppError::not_implemented_error("rebuild_indexes not implemented for SqliteOrmPersistenceService".to_string()))
+        data_retention::reindex(self.db_conn.as_ref()).await
     }
 
     fn get_database_connection(&self) -> sea_orm::DatabaseConnection {
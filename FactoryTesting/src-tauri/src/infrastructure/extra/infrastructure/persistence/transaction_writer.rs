@@ -0,0 +1,333 @@
+//! # 多实体事务写入模块
+//!
+//! ## 业务作用
+//! 批次分配、点表导入、测试结果回写都会同时写多张表。逐行写入时若中途崩溃或某行失败，
+//! 数据库里会留下只有一半实例的批次，或者有结果却没有更新状态的实例。本模块把一组
+//! `TransactionOperation` 放进同一个SQLite事务执行：全部成功才提交，任一失败整体回滚。
+//!
+//! ## 执行方式
+//! - 按原顺序把连续的同类操作合并为一组，保证先删后写等组合与逐条执行的结果一致
+//! - 保存操作合并为多行 `INSERT ... ON CONFLICT DO UPDATE`，按SQLite绑定参数上限分块；
+//!   已存在的记录保留 `created_time`，`updated_time` 刷新为当前时间
+//! - 原始测试结果只追加，不做更新
+//! - 删除操作合并为 `DELETE ... WHERE id IN (...)`，记录不存在时不视为错误
+
+use std::mem;
+use std::time::Instant;
+use chrono::Utc;
+use log::{debug, error, warn};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IdenStatic, IntoActiveModel, Iterable, PrimaryKeyToColumn, QueryFilter, TransactionTrait, Value,
+};
+use uuid::Uuid;
+
+use crate::domain::services::persistence_service::{TransactionOperation, TransactionResult};
+use crate::models::entities::{channel_point_definition, channel_test_instance, raw_test_outcome, test_batch_info};
+use crate::utils::error::{AppError, AppResult};
+
+/// 单条语句允许的绑定参数个数，低于SQLite默认上限32766并留出余量
+const MAX_BIND_PARAMS: usize = 32_000;
+
+/// 冲突更新时保持不变的列
+const CREATED_TIME: &str = "created_time";
+
+/// 在提交前检查操作列表，避免明显无效的数据进入事务
+pub fn validate(operations: &[TransactionOperation]) -> AppResult<()> {
+    for operation in operations {
+        match operation {
+            TransactionOperation::SaveChannelDefinition(definition) if definition.id.len() < 36 => {
+                return Err(AppError::validation_error(format!("无效的UUID格式: '{}'", definition.id)));
+            }
+            TransactionOperation::Custom { operation_type, .. } => {
+                return Err(AppError::validation_error(format!("事务不支持自定义操作: {}", operation_type)));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 在一个事务内执行全部操作
+///
+/// 成功时返回执行的操作数；失败时事务已回滚，错误信息中带有事务ID便于对照日志
+pub async fn execute(db: &DatabaseConnection, operations: &[TransactionOperation]) -> AppResult<TransactionResult> {
+    validate(operations)?;
+
+    let transaction_id = Uuid::new_v4().to_string();
+    let started = Instant::now();
+    if !operations.is_empty() {
+        let txn = db.begin()
+            .await
+            .map_err(|e| AppError::persistence_error(format!("开启事务失败: {}", e)))?;
+
+        if let Err(e) = apply(&txn, operations).await {
+            if let Err(rollback_err) = txn.rollback().await {
+                warn!("⚠️ [TRANSACTION] 事务 {} 回滚失败: {}", transaction_id, rollback_err);
+            }
+            error!("❌ [TRANSACTION] 事务 {} 已回滚（{}个操作）: {}", transaction_id, operations.len(), e);
+            return Err(AppError::persistence_error(format!("事务 {} 执行失败，已回滚: {}", transaction_id, e)));
+        }
+
+        txn.commit()
+            .await
+            .map_err(|e| AppError::persistence_error(format!("提交事务 {} 失败: {}", transaction_id, e)))?;
+    }
+
+    let duration_ms = started.elapsed().as_millis() as u64;
+    debug!("💾 [TRANSACTION] 事务 {} 提交 {} 个操作，耗时 {}ms", transaction_id, operations.len(), duration_ms);
+    Ok(TransactionResult {
+        success: true,
+        operations_executed: operations.len() as u32,
+        operations_failed: 0,
+        errors: Vec::new(),
+        duration_ms,
+        transaction_id,
+    })
+}
+
+/// 取出一组同类操作的数据
+macro_rules! group_items {
+    ($group:expr, $variant:ident) => {
+        $group.iter()
+            .filter_map(|operation| match operation {
+                TransactionOperation::$variant(item) => Some(item),
+                _ => None,
+            })
+    };
+}
+
+/// 按顺序执行操作，调用方负责事务的提交与回滚
+pub async fn apply<C: ConnectionTrait>(conn: &C, operations: &[TransactionOperation]) -> AppResult<()> {
+    for group in operations.chunk_by(|a, b| mem::discriminant(a) == mem::discriminant(b)) {
+        match &group[0] {
+            TransactionOperation::SaveChannelDefinition(_) => {
+                let models = group_items!(group, SaveChannelDefinition)
+                    .map(channel_point_definition::ActiveModel::from)
+                    .collect();
+                upsert_many(conn, models, channel_point_definition::Column::UpdatedTime, Utc::now().to_rfc3339().into()).await?;
+            }
+            TransactionOperation::SaveBatchInfo(_) => {
+                let models = group_items!(group, SaveBatchInfo)
+                    .map(test_batch_info::ActiveModel::from)
+                    .collect();
+                upsert_many(conn, models, test_batch_info::Column::UpdatedTime, Utc::now().into()).await?;
+            }
+            TransactionOperation::SaveTestInstance(_) => {
+                let models = group_items!(group, SaveTestInstance)
+                    .map(channel_test_instance::ActiveModel::from)
+                    .collect();
+                upsert_many(conn, models, channel_test_instance::Column::UpdatedTime, Utc::now().into()).await?;
+            }
+            TransactionOperation::SaveTestOutcome(_) => {
+                let models = group_items!(group, SaveTestOutcome)
+                    .map(raw_test_outcome::ActiveModel::from)
+                    .collect();
+                insert_many(conn, models).await?;
+            }
+            TransactionOperation::DeleteChannelDefinition(_) => {
+                let ids = group_items!(group, DeleteChannelDefinition).cloned().collect();
+                delete_many::<channel_point_definition::Entity, _>(conn, channel_point_definition::Column::Id, ids).await?;
+            }
+            TransactionOperation::DeleteTestInstance(_) => {
+                let ids = group_items!(group, DeleteTestInstance).cloned().collect();
+                delete_many::<channel_test_instance::Entity, _>(conn, channel_test_instance::Column::InstanceId, ids).await?;
+            }
+            TransactionOperation::DeleteBatchInfo(_) => {
+                let ids = group_items!(group, DeleteBatchInfo).cloned().collect();
+                delete_many::<test_batch_info::Entity, _>(conn, test_batch_info::Column::BatchId, ids).await?;
+            }
+            TransactionOperation::Custom { operation_type, .. } => {
+                return Err(AppError::validation_error(format!("事务不支持自定义操作: {}", operation_type)));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 每条多行插入语句的行数
+fn rows_per_statement<E: EntityTrait>() -> usize {
+    (MAX_BIND_PARAMS / E::Column::iter().count().max(1)).max(1)
+}
+
+fn table_name<E: EntityTrait>() -> String {
+    E::default().table_name().to_string()
+}
+
+/// 多行插入，主键冲突时更新除主键和创建时间外的所有列，`touched` 列写入 `now`
+async fn upsert_many<A, C>(
+    conn: &C,
+    models: Vec<A>,
+    touched: <A::Entity as EntityTrait>::Column,
+    now: Value,
+) -> AppResult<()>
+where
+    A: ActiveModelTrait + Send,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+    C: ConnectionTrait,
+{
+    let primary_keys: Vec<_> = <A::Entity as EntityTrait>::PrimaryKey::iter()
+        .map(|key| key.into_column())
+        .collect();
+    let update_columns: Vec<_> = <A::Entity as EntityTrait>::Column::iter()
+        .filter(|column| {
+            !primary_keys.iter().any(|key| key.as_str() == column.as_str())
+                && column.as_str() != CREATED_TIME
+                && column.as_str() != touched.as_str()
+        })
+        .collect();
+    let mut on_conflict = OnConflict::columns(primary_keys);
+    on_conflict.update_columns(update_columns).value(touched, now);
+
+    for chunk in models.chunks(rows_per_statement::<A::Entity>()) {
+        <A::Entity as EntityTrait>::insert_many(chunk.to_vec())
+            .on_conflict(on_conflict.clone())
+            .exec_without_returning(conn)
+            .await
+            .map_err(|e| AppError::persistence_error(format!("批量保存 {} 失败: {}", table_name::<A::Entity>(), e)))?;
+    }
+    Ok(())
+}
+
+/// 多行追加插入
+async fn insert_many<A, C>(conn: &C, models: Vec<A>) -> AppResult<()>
+where
+    A: ActiveModelTrait + Send,
+    <A::Entity as EntityTrait>::Model: IntoActiveModel<A>,
+    C: ConnectionTrait,
+{
+    for chunk in models.chunks(rows_per_statement::<A::Entity>()) {
+        <A::Entity as EntityTrait>::insert_many(chunk.to_vec())
+            .exec_without_returning(conn)
+            .await
+            .map_err(|e| AppError::persistence_error(format!("批量插入 {} 失败: {}", table_name::<A::Entity>(), e)))?;
+    }
+    Ok(())
+}
+
+/// 按主键批量删除
+async fn delete_many<E, C>(conn: &C, id_column: E::Column, ids: Vec<String>) -> AppResult<()>
+where
+    E: EntityTrait,
+    C: ConnectionTrait,
+{
+    for chunk in ids.chunks(MAX_BIND_PARAMS) {
+        E::delete_many()
+            .filter(id_column.is_in(chunk.iter().cloned()))
+            .exec(conn)
+            .await
+            .map_err(|e| AppError::persistence_error(format!("批量删除 {} 失败: {}", table_name::<E>(), e)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use sea_orm::{ConnectOptions, Database, PaginatorTrait, Schema};
+    use crate::models::{
+        ChannelPointDefinition, ChannelTestInstance, ModuleType, PointDataType, RawTestOutcome, SubTestItem,
+        TestBatchInfo,
+    };
+
+    async fn file_db(path: &Path) -> DatabaseConnection {
+        let url = format!("sqlite://{}?mode=rwc", path.to_string_lossy());
+        let mut options = ConnectOptions::new(url);
+        options.max_connections(2).sqlx_logging(false);
+        let db = Database::connect(options).await.unwrap();
+
+        let builder = db.get_database_backend();
+        let schema = Schema::new(builder);
+        for statement in [
+            builder.build(&schema.create_table_from_entity(channel_point_definition::Entity)),
+            builder.build(&schema.create_table_from_entity(test_batch_info::Entity)),
+            builder.build(&schema.create_table_from_entity(channel_test_instance::Entity)),
+            builder.build(&schema.create_table_from_entity(raw_test_outcome::Entity)),
+        ] {
+            db.execute(statement).await.unwrap();
+        }
+        db
+    }
+
+    fn definition(tag: &str, batch_id: &str) -> ChannelPointDefinition {
+        let mut definition = ChannelPointDefinition::new(
+            tag.to_string(), tag.to_string(), String::new(), "站A".to_string(), "M1".to_string(),
+            ModuleType::AI, "1_1_AI_0".to_string(), PointDataType::Float, "40001".to_string(),
+        );
+        definition.batch_id = Some(batch_id.to_string());
+        definition
+    }
+
+    /// 一个批次、两个通道定义及其实例和结果
+    fn allocation() -> (TestBatchInfo, Vec<TransactionOperation>) {
+        let batch = TestBatchInfo::new(Some("P1".to_string()), None);
+        let mut operations = vec![TransactionOperation::SaveBatchInfo(batch.clone())];
+        let definitions: Vec<_> = ["AI_1", "AI_2"].iter().map(|tag| definition(tag, &batch.batch_id)).collect();
+        for definition in &definitions {
+            operations.push(TransactionOperation::SaveChannelDefinition(definition.clone()));
+        }
+        for definition in &definitions {
+            let instance = ChannelTestInstance::new(definition.id.clone(), batch.batch_id.clone());
+            operations.push(TransactionOperation::SaveTestInstance(instance.clone()));
+            operations.push(TransactionOperation::SaveTestOutcome(
+                RawTestOutcome::new(instance.instance_id.clone(), SubTestItem::HardPoint, true),
+            ));
+        }
+        (batch, operations)
+    }
+
+    #[tokio::test]
+    async fn commits_all_operations_and_upserts_existing_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = file_db(&dir.path().join("tx.sqlite")).await;
+
+        let (batch, operations) = allocation();
+        let result = execute(&db, &operations).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.operations_executed, operations.len() as u32);
+        assert_eq!(channel_point_definition::Entity::find().count(&db).await.unwrap(), 2);
+        assert_eq!(channel_test_instance::Entity::find().count(&db).await.unwrap(), 2);
+        assert_eq!(raw_test_outcome::Entity::find().count(&db).await.unwrap(), 2);
+
+        // 再次保存同一定义：更新内容，保留创建时间
+        let TransactionOperation::SaveChannelDefinition(mut changed) = operations[1].clone() else { unreachable!() };
+        let before = channel_point_definition::Entity::find_by_id(changed.id.clone()).one(&db).await.unwrap().unwrap();
+        changed.variable_name = "改名".to_string();
+        execute(&db, &[
+            TransactionOperation::SaveChannelDefinition(changed.clone()),
+            TransactionOperation::DeleteBatchInfo(batch.batch_id.clone()),
+        ]).await.unwrap();
+
+        let after = channel_point_definition::Entity::find_by_id(changed.id.clone()).one(&db).await.unwrap().unwrap();
+        assert_eq!(after.variable_name, "改名");
+        assert_eq!(after.created_time, before.created_time);
+        assert_eq!(channel_point_definition::Entity::find().count(&db).await.unwrap(), 2);
+        assert!(test_batch_info::Entity::find_by_id(batch.batch_id).one(&db).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rolls_back_everything_when_one_statement_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = file_db(&dir.path().join("tx.sqlite")).await;
+        // 实例表缺失时，前面已写入的批次与定义必须一起回滚
+        db.execute_unprepared("DROP TABLE channel_test_instances").await.unwrap();
+
+        let (_, operations) = allocation();
+        assert!(execute(&db, &operations).await.is_err());
+        assert_eq!(test_batch_info::Entity::find().count(&db).await.unwrap(), 0);
+        assert_eq!(channel_point_definition::Entity::find().count(&db).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_operations_before_writing() {
+        let mut invalid = definition("AI_1", "b1");
+        invalid.id = "short".to_string();
+        assert!(validate(&[TransactionOperation::SaveChannelDefinition(invalid)]).is_err());
+        assert!(validate(&[TransactionOperation::Custom {
+            operation_type: "raw_sql".to_string(),
+            data: serde_json::Value::Null,
+        }]).is_err());
+    }
+}
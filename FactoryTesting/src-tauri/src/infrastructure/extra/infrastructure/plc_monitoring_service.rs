@@ -13,8 +13,9 @@ use crate::models::structs::{
 };
 use crate::domain::services::BaseService;
 use crate::utils::error::AppResult;
-use crate::infrastructure::plc_communication::{IPlcCommunicationService, get_global_plc_manager};
-use crate::domain::services::plc_comm_extension::PlcServiceLegacyExt;
+use crate::infrastructure::plc_communication::IPlcCommunicationService;
+use crate::infrastructure::modbus_scheduler::{with_request_source, RequestSource};
+use crate::domain::services::plc_communication_service::{PlcDataType, PlcValue, ReadRequest};
 use crate::domain::services::EventPublisher;
 
 /// PLC监控服务接口
@@ -222,79 +223,91 @@ impl PlcMonitoringService {
             }
         }
 
-        for address in valid_addresses {
-            let value_key = if let Some(map) = address_key_map {
-                if let Some(k) = map.get(address) {
-                    k.clone()
-                } else {
-                    Self::get_value_key(address, module_type)
-                }
-            } else {
-                Self::get_value_key(address, module_type)
-            };
+        if valid_addresses.is_empty() {
+            return Self::publish_values(event_publisher, instance_id, values).await;
+        }
 
-            // 根据模块类型选择 PLC 连接 ID
-            let connection_id = if let Some(cid) = connection_id { cid } else { match module_type {
-                crate::models::enums::ModuleType::AI | crate::models::enums::ModuleType::DI |
-                crate::models::enums::ModuleType::DINone | crate::models::enums::ModuleType::AINone => "target_plc",
-                crate::models::enums::ModuleType::DO | crate::models::enums::ModuleType::AO |
-                crate::models::enums::ModuleType::DONone | crate::models::enums::ModuleType::AONone => "manual_test_plc",
-                crate::models::enums::ModuleType::Communication | crate::models::enums::ModuleType::Other(_) => "manual_test_plc",
-            } };
+        // 根据模块类型选择 PLC 连接 ID
+        let connection_id = if let Some(cid) = connection_id { cid } else { match module_type {
+            crate::models::enums::ModuleType::AI | crate::models::enums::ModuleType::DI |
+            crate::models::enums::ModuleType::DINone | crate::models::enums::ModuleType::AINone => "target_plc",
+            crate::models::enums::ModuleType::DO | crate::models::enums::ModuleType::AO |
+            crate::models::enums::ModuleType::DONone | crate::models::enums::ModuleType::AONone => "manual_test_plc",
+            crate::models::enums::ModuleType::Communication | crate::models::enums::ModuleType::Other(_) => "manual_test_plc",
+        } };
 
-            // 🐞 调试日志：记录当前读取所使用的连接ID及其对应的 PLC 端点（IP:Port）
-            let endpoint_info = if let Some(mgr) = get_global_plc_manager() {
-                match mgr.endpoint_by_id(connection_id).await {
-                    Some(ep) => ep,
-                    None => "unknown".to_string(),
-                }
-            } else {
-                "n/a".to_string()
-            };
+        let data_type = match module_type {
+            crate::models::enums::ModuleType::AI | crate::models::enums::ModuleType::AO |
+            crate::models::enums::ModuleType::AINone | crate::models::enums::ModuleType::AONone => PlcDataType::Float32,
+            crate::models::enums::ModuleType::DI | crate::models::enums::ModuleType::DO |
+            crate::models::enums::ModuleType::DINone | crate::models::enums::ModuleType::DONone => PlcDataType::Bool,
+            crate::models::enums::ModuleType::Communication => {
+                // 通信模块，读取状态信息
+                values.insert("status".to_string(), serde_json::Value::String("connected".to_string()));
+                return Self::publish_values(event_publisher, instance_id, values).await;
+            }
+            crate::models::enums::ModuleType::Other(_) => {
+                // 其他类型模块，读取通用状态
+                values.insert("status".to_string(), serde_json::Value::String("unknown".to_string()));
+                return Self::publish_values(event_publisher, instance_id, values).await;
+            }
+        };
 
+        // 整个模块的地址合并为一次批量读取，由通信层按连续地址拆成块请求
+        let requests: Vec<ReadRequest> = valid_addresses.iter().map(|address| ReadRequest {
+            id: (*address).clone(),
+            address: (*address).clone(),
+            data_type,
+            array_length: None,
+        }).collect();
 
-            match module_type {
-                crate::models::enums::ModuleType::AI | crate::models::enums::ModuleType::AO |
-                crate::models::enums::ModuleType::AINone | crate::models::enums::ModuleType::AONone => {
-                    // 读取浮点数值
-                    match crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_float32_by_id(&plc_service, connection_id, address).await {
-                        Ok(value) => {
-                            // log::debug!("✅ [PLC_MONITORING] 读取成功: {} = {}", address, value);
-                            if let Some(number) = serde_json::Number::from_f64(value as f64) {
-                                values.insert(value_key, serde_json::Value::Number(number));
-                            }
-                        }
-                        Err(e) => {
-                            log::warn!("⚠️ [PLC_MONITORING] 读取地址失败: {} - {}", address, e);
-                            values.insert(value_key, serde_json::Value::Null);
-                        }
-                    }
-                }
-                crate::models::enums::ModuleType::DI | crate::models::enums::ModuleType::DO |
-                crate::models::enums::ModuleType::DINone | crate::models::enums::ModuleType::DONone => {
-                    // 读取布尔值
-                    match crate::domain::services::plc_comm_extension::PlcServiceLegacyExt::read_bool_by_id(&plc_service, connection_id, address).await {
-                        Ok(value) => {
-                            // log::debug!("✅ [PLC_MONITORING] 读取成功: {} = {}", address, value);
-                            values.insert(value_key, serde_json::Value::Bool(value));
-                        }
-                        Err(e) => {
-                            log::warn!("⚠️ [PLC_MONITORING] 读取地址失败: {} - {}", address, e);
-                            values.insert(value_key, serde_json::Value::Null);
-                        }
-                    }
-                }
-                crate::models::enums::ModuleType::Communication => {
-                    // 通信模块，读取状态信息
-                    values.insert("status".to_string(), serde_json::Value::String("connected".to_string()));
-                }
-                crate::models::enums::ModuleType::Other(_) => {
-                    // 其他类型模块，读取通用状态
-                    values.insert("status".to_string(), serde_json::Value::String("unknown".to_string()));
+        let results = match plc_service.default_handle_by_id(connection_id).await {
+            // 监控轮询以最低优先级排队，不推迟测试请求
+            Some(handle) => match with_request_source(RequestSource::Monitoring, plc_service.batch_read(&handle, &requests)).await {
+                Ok(results) => results,
+                Err(e) => {
+                    log::warn!("⚠️ [PLC_MONITORING] 批量读取失败: {} - {}", connection_id, e);
+                    Vec::new()
                 }
+            },
+            None => {
+                log::warn!("⚠️ [PLC_MONITORING] PLC连接未建立: {}", connection_id);
+                Vec::new()
             }
+        };
+
+        for (index, address) in valid_addresses.iter().enumerate() {
+            let value_key = address_key_map
+                .and_then(|map| map.get(*address).cloned())
+                .unwrap_or_else(|| Self::get_value_key(address, module_type));
+
+            let value = match results.get(index) {
+                Some(result) if result.success => match &result.value {
+                    Some(PlcValue::Float32(v)) => serde_json::Number::from_f64(*v as f64)
+                        .map(serde_json::Value::Number)
+                        .unwrap_or(serde_json::Value::Null),
+                    Some(PlcValue::Bool(v)) => serde_json::Value::Bool(*v),
+                    _ => serde_json::Value::Null,
+                },
+                Some(result) => {
+                    log::warn!("⚠️ [PLC_MONITORING] 读取地址失败: {} - {}", address,
+                               result.error_message.as_deref().unwrap_or("未知错误"));
+                    serde_json::Value::Null
+                }
+                None => serde_json::Value::Null,
+            };
+            values.insert(value_key, value);
         }
 
+        Self::publish_values(event_publisher, instance_id, values).await
+    }
+
+    /// 发布一次监控读取得到的数值
+    async fn publish_values(
+        event_publisher: Arc<dyn EventPublisher>,
+        instance_id: &str,
+        values: HashMap<String, serde_json::Value>,
+    ) -> AppResult<()> {
         // log::debug!("📊 [PLC_MONITORING] 读取完成，共获得 {} 个值: {:?}", values.len(), values);
 
         // 创建监控数据
@@ -6,7 +6,7 @@
 //!
 //! ## 架构职责
 //! - **依赖注入容器**: 管理系统中各种服务的生命周期和依赖关系
-//! - **PLC通信**: 处理与工业设备的通信协议（Modbus、S7等）
+//! - **PLC通信**: 处理与工业设备的通信协议（Modbus、S7、OPC UA等）
 //! - **数据存储**: 提供数据库访问、文件操作等持久化功能
 //! - **外部系统集成**: Excel导入导出、报告生成等
 //!
@@ -22,6 +22,11 @@
 
 pub mod di_container;
 pub mod plc_communication;
+pub mod modbus_scheduler;
+pub mod modbus_capture;
+pub mod s7_communication;
+pub mod opcua_communication;
+pub mod plc_simulator;
 pub mod range_register_repository;
 // pub mod plc_compat; // 已迁移到 domain::services::plc_comm_extension
 pub mod extra; // 临时迁移的基础设施代码，后续合并重构
@@ -29,6 +34,11 @@ pub mod extra; // 临时迁移的基础设施代码，后续合并重构
 // 重新导出基础设施组件
 pub use di_container::*;
 pub use plc_communication::*;
+pub use s7_communication::{S7Address, S7PlcService};
+pub use opcua_communication::OpcUaPlcService;
+pub use modbus_scheduler::{with_request_deadline, with_request_source, RequestPriority, RequestSource};
+pub use modbus_capture::{with_capture_instance, CapturedFrame, FrameOutcome, ReplayScript};
+pub use plc_simulator::{PlcSimulator, SimulatedFault, SimulatedSide, SimulatorModel, SimulatorStatus};
 pub use range_register_repository::*;
 // 兼容层仅供过渡使用，保持显式路径引用，避免重复导出造成歧义
 // pub use plc_compat::*;
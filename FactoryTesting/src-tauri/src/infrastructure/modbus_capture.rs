@@ -0,0 +1,728 @@
+//! # Modbus报文录制与回放
+//!
+//! ## 业务说明
+//! 客户对失败点位有异议时，日志里只有“变量:X, 读[40001]=…”这样的结论。开启录制后，
+//! Modbus传输层的每个请求与应答都会连同时间、连接ID、触发它的测试实例一起写入批次录制文件：
+//! - **录制**: 每个批次一个紧凑的二进制文件（`<批次ID>.mbcap`），由后台写入线程成批追加并落盘，
+//!   通信路径上只做入队；异常退出最多丢失最后一批尚未落盘的报文
+//! - **导出**: 转换为可读的CSV，便于与客户逐帧核对
+//! - **回放**: 把录制的应答装入内置仿真器，按相同请求顺序返回当时的数据，离线复现一次测试过程
+//!
+//! ## 测试实例标记
+//! 通信接口不携带测试实例，调用方用 [`with_capture_instance`] 声明一段操作所属的批次与实例；
+//! 声明通过 tokio 任务本地变量传递。未声明的请求（监控轮询等）仅在只有一个批次正在录制时
+//! 记入该批次；多个批次同时测试时无法判断归属，不予录制。
+//!
+//! ## 文件格式
+//! ```text
+//! 文件头: "MBCAP\0\0\x01"
+//! 记录:   u32 记录长度 | i64 时间戳(微秒) | u32 耗时(微秒) | 连接ID | 实例ID(可空)
+//!         | u8 功能码 | u16 起始地址 | u16 数量 | 请求数据 | 应答
+//! ```
+//! 整数均为小端，字符串为 u32 长度 + UTF-8。
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc as std_mpsc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::utils::config::FrameCaptureConfig;
+use crate::utils::error::{AppError, AppResult};
+use crate::utils::time_utils;
+
+/// 录制文件头
+const MAGIC: &[u8; 8] = b"MBCAP\0\0\x01";
+
+/// 录制文件扩展名
+pub const CAPTURE_EXTENSION: &str = "mbcap";
+
+#[derive(Debug, Clone)]
+struct CaptureTag {
+    batch_id: String,
+    instance_id: String,
+}
+
+tokio::task_local! {
+    static CAPTURE_TAG: CaptureTag;
+}
+
+/// 以指定批次与测试实例执行一段PLC操作，期间录制的报文都标记为该实例
+pub async fn with_capture_instance<F: Future>(batch_id: &str, instance_id: &str, future: F) -> F::Output {
+    let tag = CaptureTag { batch_id: batch_id.to_string(), instance_id: instance_id.to_string() };
+    CAPTURE_TAG.scope(tag, future).await
+}
+
+fn current_tag() -> Option<CaptureTag> {
+    CAPTURE_TAG.try_with(|tag| tag.clone()).ok()
+}
+
+/// 请求或应答携带的数据
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FrameValues {
+    /// 读请求、写应答等不携带数据的帧
+    None,
+    /// 线圈/离散输入
+    Bits(Vec<bool>),
+    /// 寄存器
+    Words(Vec<u16>),
+}
+
+impl FrameValues {
+    /// CSV中的可读形式，如 "1 0 1" 或 "16256 0"
+    fn describe(&self) -> String {
+        match self {
+            FrameValues::None => String::new(),
+            FrameValues::Bits(bits) => bits.iter().map(|b| if *b { "1" } else { "0" }).collect::<Vec<_>>().join(" "),
+            FrameValues::Words(words) => words.iter().map(|w| w.to_string()).collect::<Vec<_>>().join(" "),
+        }
+    }
+}
+
+/// 可录制的应答值
+pub trait FrameResponse {
+    fn frame_values(&self) -> FrameValues;
+}
+
+impl FrameResponse for Vec<bool> {
+    fn frame_values(&self) -> FrameValues {
+        FrameValues::Bits(self.clone())
+    }
+}
+
+impl FrameResponse for Vec<u16> {
+    fn frame_values(&self) -> FrameValues {
+        FrameValues::Words(self.clone())
+    }
+}
+
+impl FrameResponse for () {
+    fn frame_values(&self) -> FrameValues {
+        FrameValues::None
+    }
+}
+
+/// 一次Modbus请求
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModbusRequestFrame {
+    pub function_code: u8,
+    /// 协议地址（已按连接的地址编码换算）
+    pub start: u16,
+    pub quantity: u16,
+    pub values: FrameValues,
+}
+
+impl ModbusRequestFrame {
+    pub fn read(function_code: u8, start: u16, quantity: u16) -> Self {
+        Self { function_code, start, quantity, values: FrameValues::None }
+    }
+
+    pub fn write_bits(function_code: u8, start: u16, bits: &[bool]) -> Self {
+        Self { function_code, start, quantity: bits.len() as u16, values: FrameValues::Bits(bits.to_vec()) }
+    }
+
+    pub fn write_words(function_code: u8, start: u16, words: &[u16]) -> Self {
+        Self { function_code, start, quantity: words.len() as u16, values: FrameValues::Words(words.to_vec()) }
+    }
+}
+
+/// 请求结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FrameOutcome {
+    /// 正常应答
+    Response(FrameValues),
+    /// 从站异常应答（异常码）
+    Exception(u8),
+    /// 超时未应答
+    Timeout,
+    /// 传输层错误
+    LinkError(String),
+}
+
+impl FrameOutcome {
+    /// 由一次请求的结果得到录制的应答
+    pub fn of<T: FrameResponse>(result: &AppResult<T>) -> Self {
+        match result {
+            Ok(value) => FrameOutcome::Response(value.frame_values()),
+            Err(AppError::ModbusException { exception_code, .. }) => FrameOutcome::Exception(*exception_code),
+            Err(AppError::ModbusTimeout { .. }) => FrameOutcome::Timeout,
+            Err(e) => FrameOutcome::LinkError(e.to_string()),
+        }
+    }
+
+    fn describe(&self) -> (String, String) {
+        match self {
+            FrameOutcome::Response(values) => (values.describe(), String::new()),
+            FrameOutcome::Exception(code) => (String::new(), format!("异常码 0x{:02X}", code)),
+            FrameOutcome::Timeout => (String::new(), "超时".to_string()),
+            FrameOutcome::LinkError(message) => (String::new(), message.clone()),
+        }
+    }
+}
+
+/// 录制的一帧
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedFrame {
+    /// 请求发出时间
+    pub timestamp: DateTime<Utc>,
+    pub connection_id: String,
+    pub instance_id: Option<String>,
+    pub request: ModbusRequestFrame,
+    pub outcome: FrameOutcome,
+    /// 请求耗时（微秒）
+    pub latency_us: u32,
+}
+
+// ----------------------------------------------------------------------------
+// 编解码
+// ----------------------------------------------------------------------------
+
+struct Encoder(Vec<u8>);
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    fn values(&mut self, values: &FrameValues) {
+        match values {
+            FrameValues::None => self.u8(0),
+            FrameValues::Bits(bits) => {
+                self.u8(1);
+                self.u16(bits.len() as u16);
+                let mut packed = vec![0u8; bits.len().div_ceil(8)];
+                for (i, bit) in bits.iter().enumerate() {
+                    packed[i / 8] |= (*bit as u8) << (i % 8);
+                }
+                self.0.extend(packed);
+            }
+            FrameValues::Words(words) => {
+                self.u8(2);
+                self.u16(words.len() as u16);
+                for word in words {
+                    self.u16(*word);
+                }
+            }
+        }
+    }
+}
+
+struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.take(8).map(|b| i64::from_le_bytes(b.try_into().expect("8字节")))
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        self.take(len).map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+
+    fn values(&mut self) -> Option<FrameValues> {
+        match self.u8()? {
+            0 => Some(FrameValues::None),
+            1 => {
+                let count = self.u16()? as usize;
+                let packed = self.take(count.div_ceil(8))?;
+                Some(FrameValues::Bits((0..count).map(|i| packed[i / 8] & (1 << (i % 8)) != 0).collect()))
+            }
+            2 => {
+                let count = self.u16()? as usize;
+                (0..count).map(|_| self.u16()).collect::<Option<Vec<_>>>().map(FrameValues::Words)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn encode_frame(frame: &CapturedFrame) -> Vec<u8> {
+    let mut body = Encoder(Vec::with_capacity(64));
+    body.0.extend_from_slice(&frame.timestamp.timestamp_micros().to_le_bytes());
+    body.u32(frame.latency_us);
+    body.str(&frame.connection_id);
+    match &frame.instance_id {
+        Some(instance_id) => {
+            body.u8(1);
+            body.str(instance_id);
+        }
+        None => body.u8(0),
+    }
+    body.u8(frame.request.function_code);
+    body.u16(frame.request.start);
+    body.u16(frame.request.quantity);
+    body.values(&frame.request.values);
+    match &frame.outcome {
+        FrameOutcome::Response(values) => {
+            body.u8(0);
+            body.values(values);
+        }
+        FrameOutcome::Exception(code) => {
+            body.u8(1);
+            body.u8(*code);
+        }
+        FrameOutcome::Timeout => body.u8(2),
+        FrameOutcome::LinkError(message) => {
+            body.u8(3);
+            body.str(message);
+        }
+    }
+
+    let mut record = Encoder(Vec::with_capacity(body.0.len() + 4));
+    record.u32(body.0.len() as u32);
+    record.0.extend(body.0);
+    record.0
+}
+
+fn decode_frame(body: &[u8]) -> Option<CapturedFrame> {
+    let mut d = Decoder(body);
+    let timestamp = Utc.timestamp_micros(d.i64()?).single()?;
+    let latency_us = d.u32()?;
+    let connection_id = d.str()?;
+    let instance_id = match d.u8()? {
+        0 => None,
+        _ => Some(d.str()?),
+    };
+    let request = ModbusRequestFrame {
+        function_code: d.u8()?,
+        start: d.u16()?,
+        quantity: d.u16()?,
+        values: d.values()?,
+    };
+    let outcome = match d.u8()? {
+        0 => FrameOutcome::Response(d.values()?),
+        1 => FrameOutcome::Exception(d.u8()?),
+        2 => FrameOutcome::Timeout,
+        3 => FrameOutcome::LinkError(d.str()?),
+        _ => return None,
+    };
+    Some(CapturedFrame { timestamp, connection_id, instance_id, request, outcome, latency_us })
+}
+
+fn io_error(path: &Path, e: impl std::fmt::Display, kind: &str) -> AppError {
+    AppError::IoError { message: format!("Modbus录制文件 {:?}: {}", path, e), kind: kind.to_string() }
+}
+
+/// 读取录制文件；末尾写了一半的记录（异常退出）被忽略
+pub fn read_capture(path: &Path) -> AppResult<Vec<CapturedFrame>> {
+    let bytes = std::fs::read(path).map_err(|e| io_error(path, e, "ReadError"))?;
+    if !bytes.starts_with(MAGIC) {
+        return Err(AppError::validation_error(format!("不是Modbus录制文件: {:?}", path)));
+    }
+
+    let mut frames = Vec::new();
+    let mut rest = Decoder(&bytes[MAGIC.len()..]);
+    while let Some(len) = rest.u32() {
+        let Some(body) = rest.take(len as usize) else {
+            log::warn!("⚠️ [MODBUS_CAPTURE] 录制文件末尾记录不完整，已忽略: {:?}", path);
+            break;
+        };
+        let frame = decode_frame(body)
+            .ok_or_else(|| AppError::validation_error(format!("Modbus录制文件第{}帧损坏: {:?}", frames.len() + 1, path)))?;
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+/// 按CSV转义单个字段
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 把录制的报文导出为CSV（带BOM，Excel可直接打开）
+pub fn export_capture_csv(frames: &[CapturedFrame], target: &Path) -> AppResult<()> {
+    let mut out = String::from("\u{FEFF}时间,连接ID,测试实例ID,功能码,起始地址,数量,请求数据,应答数据,错误,耗时(ms)\n");
+    for frame in frames {
+        let (response, error) = frame.outcome.describe();
+        let fields = [
+            time_utils::format_bj(frame.timestamp, "%Y-%m-%d %H:%M:%S%.3f"),
+            frame.connection_id.clone(),
+            frame.instance_id.clone().unwrap_or_default(),
+            format!("0x{:02X}", frame.request.function_code),
+            frame.request.start.to_string(),
+            frame.request.quantity.to_string(),
+            frame.request.values.describe(),
+            response,
+            error,
+            format!("{:.3}", frame.latency_us as f64 / 1000.0),
+        ];
+        out.push_str(&fields.iter().map(|field| csv_field(field)).collect::<Vec<_>>().join(","));
+        out.push('\n');
+    }
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).ok();
+    }
+    std::fs::write(target, out).map_err(|e| io_error(target, e, "WriteError"))
+}
+
+// ----------------------------------------------------------------------------
+// 录制
+// ----------------------------------------------------------------------------
+
+/// 发给后台写入线程的指令
+enum WriterCommand {
+    /// 追加一帧到批次录制文件
+    Frame { batch_id: String, frame: CapturedFrame },
+    /// 落盘并关闭批次录制文件
+    Finish(String),
+    /// 落盘全部已收到的报文后应答，供读取方获得完整的录制文件
+    Sync(std_mpsc::Sender<()>),
+}
+
+fn capture_file(directory: &Path, batch_id: &str) -> PathBuf {
+    directory.join(format!("{}.{}", batch_id, CAPTURE_EXTENSION))
+}
+
+fn open_capture(directory: &Path, batch_id: &str) -> std::io::Result<BufWriter<File>> {
+    std::fs::create_dir_all(directory)?;
+    let file = OpenOptions::new().create(true).append(true).open(capture_file(directory, batch_id))?;
+    let mut writer = BufWriter::new(file);
+    if writer.get_ref().metadata()?.len() == 0 {
+        writer.write_all(MAGIC)?;
+    }
+    Ok(writer)
+}
+
+/// 落盘全部打开的录制文件，写入失败的文件被关闭，下一帧到来时重新打开
+fn flush_all(writers: &mut HashMap<String, BufWriter<File>>) {
+    writers.retain(|batch_id, writer| match writer.flush() {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("⚠️ [MODBUS_CAPTURE] 写入批次 {} 的录制文件失败: {}", batch_id, e);
+            false
+        }
+    });
+}
+
+/// 后台写入线程：每次取出队列中已有的全部指令，写完后统一落盘
+fn run_writer(directory: PathBuf, mut receiver: mpsc::UnboundedReceiver<WriterCommand>) {
+    let mut writers: HashMap<String, BufWriter<File>> = HashMap::new();
+    while let Some(first) = receiver.blocking_recv() {
+        let mut next = Some(first);
+        while let Some(command) = next.take().or_else(|| receiver.try_recv().ok()) {
+            match command {
+                WriterCommand::Frame { batch_id, frame } => {
+                    if !writers.contains_key(&batch_id) {
+                        match open_capture(&directory, &batch_id) {
+                            Ok(writer) => {
+                                writers.insert(batch_id.clone(), writer);
+                            }
+                            Err(e) => {
+                                log::warn!("⚠️ [MODBUS_CAPTURE] 打开批次 {} 的录制文件失败: {}", batch_id, e);
+                                continue;
+                            }
+                        }
+                    }
+                    let writer = writers.get_mut(&batch_id).expect("已打开");
+                    if let Err(e) = writer.write_all(&encode_frame(&frame)) {
+                        log::warn!("⚠️ [MODBUS_CAPTURE] 写入批次 {} 的录制文件失败: {}", batch_id, e);
+                        writers.remove(&batch_id);
+                    }
+                }
+                WriterCommand::Finish(batch_id) => {
+                    if let Some(mut writer) = writers.remove(&batch_id) {
+                        if let Err(e) = writer.flush() {
+                            log::warn!("⚠️ [MODBUS_CAPTURE] 写入批次 {} 的录制文件失败: {}", batch_id, e);
+                        }
+                    }
+                }
+                WriterCommand::Sync(done) => {
+                    flush_all(&mut writers);
+                    let _ = done.send(());
+                }
+            }
+        }
+        flush_all(&mut writers);
+    }
+}
+
+#[derive(Default)]
+struct RecorderState {
+    /// 正在录制、尚未结束的批次；只有一个时未标记的请求记入该批次
+    active_batches: Vec<String>,
+}
+
+/// 按批次写入录制文件的报文录制器
+///
+/// 通信路径上只判断报文归属并入队，文件写入与落盘由后台线程完成
+pub struct FrameRecorder {
+    directory: PathBuf,
+    state: Mutex<RecorderState>,
+    sender: mpsc::UnboundedSender<WriterCommand>,
+}
+
+impl FrameRecorder {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        let directory = directory.into();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let writer_directory = directory.clone();
+        // 录制器释放后通道关闭，写入线程落盘后退出
+        if let Err(e) = std::thread::Builder::new()
+            .name("modbus-capture".to_string())
+            .spawn(move || run_writer(writer_directory, receiver))
+        {
+            log::warn!("⚠️ [MODBUS_CAPTURE] 启动录制写入线程失败，报文将不被录制: {}", e);
+        }
+        Self { directory, state: Mutex::new(RecorderState::default()), sender }
+    }
+
+    /// 批次录制文件路径
+    pub fn capture_path(&self, batch_id: &str) -> PathBuf {
+        capture_file(&self.directory, batch_id)
+    }
+
+    /// 记录一次请求；按当前任务声明的批次与实例归档，`sent_at` 为请求发出的时间
+    pub fn record(&self, connection_id: &str, request: ModbusRequestFrame, outcome: FrameOutcome, sent_at: DateTime<Utc>, latency: Duration) {
+        let tag = current_tag();
+        let batch_id = {
+            let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            match &tag {
+                Some(tag) => {
+                    if !state.active_batches.contains(&tag.batch_id) {
+                        state.active_batches.push(tag.batch_id.clone());
+                    }
+                    tag.batch_id.clone()
+                }
+                // 没有批次或多个批次同时测试时无法判断未标记请求的归属
+                None => match state.active_batches.as_slice() {
+                    [batch_id] => batch_id.clone(),
+                    _ => return,
+                },
+            }
+        };
+
+        let frame = CapturedFrame {
+            timestamp: sent_at,
+            connection_id: connection_id.to_string(),
+            instance_id: tag.map(|tag| tag.instance_id),
+            request,
+            outcome,
+            latency_us: latency.as_micros().min(u32::MAX as u128) as u32,
+        };
+        // 写入线程已退出时静默丢弃，录制不影响通信
+        let _ = self.sender.send(WriterCommand::Frame { batch_id, frame });
+    }
+
+    /// 批次结束后落盘并关闭其录制文件；之后再有该批次的报文（如手动测试）会重新以追加方式打开
+    pub fn finish_batch(&self, batch_id: &str) {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.active_batches.retain(|active| active != batch_id);
+        let _ = self.sender.send(WriterCommand::Finish(batch_id.to_string()));
+    }
+
+    /// 等待写入线程把已入队的报文全部落盘
+    fn sync(&self) {
+        let (done, wait) = std_mpsc::channel();
+        if self.sender.send(WriterCommand::Sync(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+
+    /// 读取批次的全部录制报文
+    pub fn load_batch(&self, batch_id: &str) -> AppResult<Vec<CapturedFrame>> {
+        self.sync();
+        let path = self.capture_path(batch_id);
+        if !path.exists() {
+            return Err(AppError::not_found_error("Modbus录制文件", path.to_string_lossy()));
+        }
+        read_capture(&path)
+    }
+}
+
+static FRAME_RECORDER: Lazy<Option<FrameRecorder>> = Lazy::new(|| {
+    let FrameCaptureConfig { enabled, directory } = crate::utils::config::effective_plc_config().frame_capture;
+    enabled.then(|| {
+        log::info!("📼 [MODBUS_CAPTURE] Modbus报文录制已启用，目录: {:?}", directory);
+        FrameRecorder::new(directory)
+    })
+});
+
+/// 全局报文录制器；配置未启用录制时为 None
+pub fn frame_recorder() -> Option<&'static FrameRecorder> {
+    FRAME_RECORDER.as_ref()
+}
+
+/// 批次录制文件所在位置，未启用录制时同样按配置目录计算，便于导出历史录制
+pub fn batch_capture_path(batch_id: &str) -> PathBuf {
+    match frame_recorder() {
+        Some(recorder) => recorder.capture_path(batch_id),
+        None => FrameRecorder::new(crate::utils::config::effective_plc_config().frame_capture.directory).capture_path(batch_id),
+    }
+}
+
+// ----------------------------------------------------------------------------
+// 回放
+// ----------------------------------------------------------------------------
+
+/// 回放脚本：按（功能码, 起始地址, 数量）分组、保持录制顺序的应答队列
+///
+/// 回放端收到请求时取出同一请求的下一条录制应答；读请求据此返回当时读到的数据
+#[derive(Debug, Default)]
+pub struct ReplayScript {
+    queues: HashMap<(u8, u16, u16), VecDeque<FrameOutcome>>,
+}
+
+impl ReplayScript {
+    pub fn from_frames<'a>(frames: impl IntoIterator<Item = &'a CapturedFrame>) -> Self {
+        let mut script = Self::default();
+        for frame in frames {
+            let key = (frame.request.function_code, frame.request.start, frame.request.quantity);
+            script.queues.entry(key).or_default().push_back(frame.outcome.clone());
+        }
+        script
+    }
+
+    /// 取出该请求的下一条录制应答，已用尽或未录制时为 None
+    pub fn next_outcome(&mut self, function_code: u8, start: u16, quantity: u16) -> Option<FrameOutcome> {
+        self.queues.get_mut(&(function_code, start, quantity))?.pop_front()
+    }
+
+    /// 尚未回放的应答数
+    pub fn remaining(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(instance_id: Option<&str>, request: ModbusRequestFrame, outcome: FrameOutcome) -> CapturedFrame {
+        CapturedFrame {
+            timestamp: Utc.timestamp_micros(1_700_000_000_123_456).unwrap(),
+            connection_id: "test_rig".to_string(),
+            instance_id: instance_id.map(str::to_string),
+            request,
+            outcome,
+            latency_us: 1500,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_frames_per_batch_and_reads_them_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = FrameRecorder::new(dir.path());
+        let sent_at = Utc.timestamp_micros(1_700_000_000_123_456).unwrap();
+
+        // 尚未出现任何批次时，未标记的请求不录制
+        recorder.record("rig", ModbusRequestFrame::read(0x03, 0, 2), FrameOutcome::Timeout, sent_at, Duration::from_millis(3000));
+
+        with_capture_instance("batch1", "inst1", async {
+            recorder.record("rig", ModbusRequestFrame::write_words(0x10, 100, &[0x4248, 0]), FrameOutcome::Response(FrameValues::None), sent_at, Duration::from_millis(2));
+            recorder.record("target", ModbusRequestFrame::read(0x02, 7, 3), FrameOutcome::Response(FrameValues::Bits(vec![true, false, true])), sent_at, Duration::from_millis(3));
+        }).await;
+        // 监控轮询等未标记的请求记入唯一正在录制的批次
+        recorder.record("target", ModbusRequestFrame::read(0x04, 0, 2), FrameOutcome::Exception(0x02), sent_at, Duration::from_millis(1));
+
+        // 两个批次同时测试时，未标记的请求无法判断归属，不录制
+        with_capture_instance("batch2", "inst2", async {
+            recorder.record("rig", ModbusRequestFrame::read(0x01, 0, 1), FrameOutcome::Timeout, sent_at, Duration::from_millis(1));
+        }).await;
+        recorder.record("target", ModbusRequestFrame::read(0x04, 2, 2), FrameOutcome::Timeout, sent_at, Duration::from_millis(1));
+        recorder.finish_batch("batch2");
+        recorder.record("target", ModbusRequestFrame::read(0x04, 4, 2), FrameOutcome::Timeout, sent_at, Duration::from_millis(1));
+
+        let frames = recorder.load_batch("batch1").unwrap();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].timestamp, sent_at, "时间戳为请求发出时间");
+        assert_eq!(frames[0].instance_id.as_deref(), Some("inst1"));
+        assert_eq!(frames[0].request.values, FrameValues::Words(vec![0x4248, 0]));
+        assert_eq!(frames[1].outcome, FrameOutcome::Response(FrameValues::Bits(vec![true, false, true])));
+        assert_eq!(frames[2].instance_id, None);
+        assert_eq!(frames[2].outcome, FrameOutcome::Exception(0x02));
+        assert_eq!(frames[2].latency_us, 1000);
+        assert_eq!(frames[3].request.start, 4);
+        assert_eq!(recorder.load_batch("batch2").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn ignores_truncated_tail_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("batch.mbcap");
+        let first = frame(Some("inst"), ModbusRequestFrame::write_bits(0x0F, 10, &[true; 9]), FrameOutcome::LinkError("连接被重置".to_string()));
+        let second = encode_frame(&frame(None, ModbusRequestFrame::read(0x03, 0, 1), FrameOutcome::Response(FrameValues::Words(vec![7]))));
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(encode_frame(&first));
+        bytes.extend(&second[..second.len() - 3]);
+        std::fs::write(&path, bytes).unwrap();
+
+        assert_eq!(read_capture(&path).unwrap(), vec![first]);
+    }
+
+    #[test]
+    fn exports_readable_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("batch.csv");
+        let frames = [
+            frame(Some("inst"), ModbusRequestFrame::read(0x03, 0, 2), FrameOutcome::Response(FrameValues::Words(vec![16256, 0]))),
+            frame(None, ModbusRequestFrame::write_bits(0x05, 3, &[true]), FrameOutcome::LinkError("broken pipe, os error 32".to_string())),
+        ];
+        export_capture_csv(&frames, &path).unwrap();
+
+        let csv = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with(",test_rig,inst,0x03,0,2,,16256 0,,1.500"), "{}", lines[1]);
+        assert!(lines[2].ends_with(",test_rig,,0x05,3,1,1,,\"broken pipe, os error 32\",1.500"), "{}", lines[2]);
+    }
+
+    #[test]
+    fn replay_script_returns_outcomes_in_recorded_order() {
+        let read = |value: u16| frame(None, ModbusRequestFrame::read(0x03, 0, 1), FrameOutcome::Response(FrameValues::Words(vec![value])));
+        let frames = [read(1), frame(None, ModbusRequestFrame::read(0x01, 0, 1), FrameOutcome::Timeout), read(2)];
+        let mut script = ReplayScript::from_frames(&frames);
+
+        assert_eq!(script.remaining(), 3);
+        assert_eq!(script.next_outcome(0x03, 0, 1), Some(FrameOutcome::Response(FrameValues::Words(vec![1]))));
+        assert_eq!(script.next_outcome(0x03, 0, 1), Some(FrameOutcome::Response(FrameValues::Words(vec![2]))));
+        assert_eq!(script.next_outcome(0x03, 0, 1), None);
+        assert_eq!(script.next_outcome(0x03, 0, 2), None);
+        assert_eq!(script.next_outcome(0x01, 0, 1), Some(FrameOutcome::Timeout));
+    }
+}
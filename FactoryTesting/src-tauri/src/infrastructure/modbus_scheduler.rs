@@ -0,0 +1,407 @@
+//! # Modbus连接请求调度器
+//!
+//! ## 业务说明
+//! 测试执行器、PLC监控轮询与手动测试命令共用同一个池化连接。若不加约束，
+//! 监控流量会把硬点测试的读取推迟到时序之外。调度器位于每个连接之前：
+//! - **优先级队列**: 测试写 > 测试读 > 手动命令 > 监控轮询，同优先级按到达顺序
+//! - **在途上限**: 同时放行的请求数，默认1，保证严格按优先级执行
+//! - **帧间隔**: 相邻两帧之间的最小间隔，用于响应较慢的从站或串口网关
+//! - **排队期限**: 请求在队列中等待超过期限即以超时失败，不再占用连接
+//!
+//! ## 请求来源
+//! 通信接口不携带优先级，调用方用 [`with_request_source`] 声明一段操作的来源，
+//! 用 [`with_request_deadline`] 收紧排队期限；未声明时视为测试流量。
+//! 声明通过 tokio 任务本地变量传递，只对当前任务内的调用生效。
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+use crate::domain::services::plc_communication_service::ConnectionStats;
+use crate::models::ModbusSchedulerSettings;
+use crate::utils::error::{AppError, AppResult};
+
+/// 请求来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestSource {
+    /// 自动测试执行器
+    Test,
+    /// 手动测试命令
+    Manual,
+    /// 监控轮询与心跳
+    Monitoring,
+}
+
+/// 请求优先级，越靠前越先放行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RequestPriority {
+    TestWrite,
+    TestRead,
+    Manual,
+    Monitoring,
+}
+
+impl RequestPriority {
+    /// 按来源与读写方向确定优先级；手动与监控请求不区分读写
+    pub fn classify(source: RequestSource, is_write: bool) -> Self {
+        match source {
+            RequestSource::Test if is_write => RequestPriority::TestWrite,
+            RequestSource::Test => RequestPriority::TestRead,
+            RequestSource::Manual => RequestPriority::Manual,
+            RequestSource::Monitoring => RequestPriority::Monitoring,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RequestContext {
+    source: RequestSource,
+    deadline: Option<Instant>,
+}
+
+tokio::task_local! {
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+fn current_context() -> Option<RequestContext> {
+    REQUEST_CONTEXT.try_with(|context| *context).ok()
+}
+
+/// 以指定来源执行一段PLC操作，期间发出的Modbus请求都按该来源排队
+pub async fn with_request_source<F: Future>(source: RequestSource, future: F) -> F::Output {
+    let deadline = current_context().and_then(|context| context.deadline);
+    REQUEST_CONTEXT.scope(RequestContext { source, deadline }, future).await
+}
+
+/// 为一段PLC操作设置排队期限，期限从调用时刻起算；到期仍未放行的请求以超时失败
+pub async fn with_request_deadline<F: Future>(deadline: Duration, future: F) -> F::Output {
+    let source = current_context().map(|context| context.source).unwrap_or(RequestSource::Test);
+    let context = RequestContext { source, deadline: Some(Instant::now() + deadline) };
+    REQUEST_CONTEXT.scope(context, future).await
+}
+
+/// 排队中的请求；堆顶为优先级最高、到达最早的请求
+struct Waiter {
+    priority: RequestPriority,
+    seq: u64,
+    grant: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+#[derive(Default)]
+struct SchedulerState {
+    in_flight: usize,
+    waiters: BinaryHeap<Waiter>,
+    next_seq: u64,
+    last_frame_at: Option<Instant>,
+    max_queue_depth: usize,
+    scheduled: u64,
+    total_wait_ms: f64,
+    max_wait_ms: f64,
+    expired: u64,
+}
+
+impl SchedulerState {
+    /// 当前仍在等待的请求数（已取消的请求在出队时才移除）
+    fn queue_depth(&self) -> usize {
+        self.waiters.iter().filter(|waiter| !waiter.grant.is_closed()).count()
+    }
+}
+
+/// 单个连接的请求调度器
+pub struct RequestScheduler {
+    max_in_flight: usize,
+    inter_frame_gap: Duration,
+    queue_deadline: Duration,
+    state: Mutex<SchedulerState>,
+}
+
+impl RequestScheduler {
+    /// 创建调度器；排队期限未配置时使用连接超时
+    pub fn new(settings: &ModbusSchedulerSettings, connection_timeout: Duration) -> Self {
+        let queue_deadline = match settings.queue_deadline_ms {
+            0 => connection_timeout,
+            ms => Duration::from_millis(ms),
+        };
+        Self {
+            max_in_flight: settings.max_in_flight.max(1) as usize,
+            inter_frame_gap: Duration::from_millis(settings.inter_frame_gap_ms),
+            queue_deadline,
+            state: Mutex::new(SchedulerState::default()),
+        }
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, SchedulerState> {
+        // 状态只做计数与入队出队，持锁期间不会panic，中毒时沿用原数据
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 按当前任务声明的来源排队，放行后返回许可；许可释放时放行下一个请求
+    pub async fn acquire(self: &Arc<Self>, is_write: bool) -> AppResult<SchedulerPermit> {
+        let context = current_context();
+        let source = context.map(|c| c.source).unwrap_or(RequestSource::Test);
+        let deadline = context.and_then(|c| c.deadline);
+        self.acquire_as(RequestPriority::classify(source, is_write), deadline).await
+    }
+
+    /// 以指定优先级排队；`deadline` 为空时使用调度器的排队期限
+    pub async fn acquire_as(self: &Arc<Self>, priority: RequestPriority, deadline: Option<Instant>) -> AppResult<SchedulerPermit> {
+        let enqueued_at = Instant::now();
+        let deadline = deadline.unwrap_or(enqueued_at + self.queue_deadline);
+
+        let receiver = {
+            let mut state = self.lock_state();
+            if state.in_flight < self.max_in_flight && state.queue_depth() == 0 {
+                state.in_flight += 1;
+                None
+            } else {
+                let (grant, receiver) = oneshot::channel();
+                let seq = state.next_seq;
+                state.next_seq += 1;
+                state.waiters.push(Waiter { priority, seq, grant });
+                state.max_queue_depth = state.max_queue_depth.max(state.queue_depth());
+                Some(receiver)
+            }
+        };
+
+        if let Some(receiver) = receiver {
+            let mut pending = PendingGrant { scheduler: self, receiver, granted: false };
+            if matches!(tokio::time::timeout_at(deadline.into(), &mut pending.receiver).await, Ok(Ok(()))) {
+                pending.granted = true;
+            } else {
+                drop(pending);
+                let mut state = self.lock_state();
+                state.waiters.retain(|waiter| !waiter.grant.is_closed());
+                state.expired += 1;
+                return Err(AppError::timeout_error(
+                    "Modbus请求排队",
+                    format!("{:?} 请求等待 {}ms 仍未放行", priority, enqueued_at.elapsed().as_millis()),
+                ));
+            }
+        }
+
+        // 预约发送时刻，保证相邻两帧之间至少间隔 inter_frame_gap
+        let send_at = {
+            let mut state = self.lock_state();
+            let now = Instant::now();
+            let send_at = state.last_frame_at.map(|last| last + self.inter_frame_gap).filter(|t| *t > now).unwrap_or(now);
+            state.last_frame_at = Some(send_at);
+            send_at
+        };
+        let permit = SchedulerPermit { scheduler: self.clone() };
+        if send_at > Instant::now() {
+            tokio::time::sleep_until(send_at.into()).await;
+        }
+
+        let waited_ms = enqueued_at.elapsed().as_secs_f64() * 1000.0;
+        let mut state = self.lock_state();
+        state.scheduled += 1;
+        state.total_wait_ms += waited_ms;
+        state.max_wait_ms = state.max_wait_ms.max(waited_ms);
+        Ok(permit)
+    }
+
+    /// 归还名额：直接转交给队列中优先级最高的请求，队列为空时减少在途计数
+    fn release(&self) {
+        let mut state = self.lock_state();
+        let now = Instant::now();
+        state.last_frame_at = Some(state.last_frame_at.map_or(now, |last| last.max(now)));
+        while let Some(waiter) = state.waiters.pop() {
+            if waiter.grant.send(()).is_ok() {
+                return;
+            }
+        }
+        state.in_flight = state.in_flight.saturating_sub(1);
+    }
+
+    /// 将队列统计写入连接统计
+    pub fn fill_stats(&self, stats: &mut ConnectionStats) {
+        let state = self.lock_state();
+        stats.queue_depth = state.queue_depth() as u64;
+        stats.max_queue_depth = state.max_queue_depth as u64;
+        stats.average_queue_wait_ms = if state.scheduled > 0 { state.total_wait_ms / state.scheduled as f64 } else { 0.0 };
+        stats.max_queue_wait_ms = state.max_wait_ms;
+        stats.expired_requests = state.expired;
+    }
+}
+
+impl std::fmt::Debug for RequestScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestScheduler")
+            .field("max_in_flight", &self.max_in_flight)
+            .field("inter_frame_gap", &self.inter_frame_gap)
+            .field("queue_deadline", &self.queue_deadline)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 排队中的放行通知；调用方在收到放行前被丢弃（超时、`select!`、任务取消）时，
+/// 析构中关闭通道并检查是否已被放行，已放行则把名额交还给下一个请求
+struct PendingGrant<'a> {
+    scheduler: &'a RequestScheduler,
+    receiver: oneshot::Receiver<()>,
+    granted: bool,
+}
+
+impl Drop for PendingGrant<'_> {
+    fn drop(&mut self) {
+        if self.granted {
+            return;
+        }
+        // 关闭后再检查一次，避免与释放方同时放行时丢失名额
+        self.receiver.close();
+        if self.receiver.try_recv().is_ok() {
+            self.scheduler.release();
+        }
+    }
+}
+
+/// 调度许可，持有期间占用一个在途名额
+pub struct SchedulerPermit {
+    scheduler: Arc<RequestScheduler>,
+}
+
+impl Drop for SchedulerPermit {
+    fn drop(&mut self) {
+        self.scheduler.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler(max_in_flight: u32, inter_frame_gap_ms: u64) -> Arc<RequestScheduler> {
+        let settings = ModbusSchedulerSettings { max_in_flight, inter_frame_gap_ms, queue_deadline_ms: 0 };
+        Arc::new(RequestScheduler::new(&settings, Duration::from_secs(2)))
+    }
+
+    fn empty_stats() -> ConnectionStats {
+        ConnectionStats {
+            connection_id: "test".to_string(),
+            total_reads: 0,
+            total_writes: 0,
+            successful_reads: 0,
+            successful_writes: 0,
+            average_read_time_ms: 0.0,
+            average_write_time_ms: 0.0,
+            connection_established_at: chrono::Utc::now(),
+            last_communication: chrono::Utc::now(),
+            connection_errors: 0,
+            queue_depth: 0,
+            max_queue_depth: 0,
+            average_queue_wait_ms: 0.0,
+            max_queue_wait_ms: 0.0,
+            expired_requests: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn queued_requests_are_released_by_priority() {
+        let scheduler = scheduler(1, 0);
+        let held = scheduler.acquire(false).await.unwrap();
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let mut tasks = Vec::new();
+        for (source, is_write) in [
+            (RequestSource::Monitoring, false),
+            (RequestSource::Manual, true),
+            (RequestSource::Test, false),
+            (RequestSource::Test, true),
+        ] {
+            let (scheduler, order) = (scheduler.clone(), order.clone());
+            tasks.push(tokio::spawn(with_request_source(source, async move {
+                let _permit = scheduler.acquire(is_write).await.unwrap();
+                order.lock().unwrap().push(RequestPriority::classify(source, is_write));
+            })));
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        let mut stats = empty_stats();
+        scheduler.fill_stats(&mut stats);
+        assert_eq!(stats.queue_depth, 4);
+
+        drop(held);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(
+            *order.lock().unwrap(),
+            vec![RequestPriority::TestWrite, RequestPriority::TestRead, RequestPriority::Manual, RequestPriority::Monitoring]
+        );
+        scheduler.fill_stats(&mut stats);
+        assert_eq!((stats.queue_depth, stats.max_queue_depth), (0, 4));
+        assert!(stats.max_queue_wait_ms >= 15.0);
+    }
+
+    #[tokio::test]
+    async fn expired_requests_give_up_their_place() {
+        let scheduler = scheduler(1, 0);
+        let held = scheduler.acquire(true).await.unwrap();
+
+        let result = with_request_deadline(Duration::from_millis(20), scheduler.acquire(false)).await;
+        assert!(matches!(result, Err(AppError::TimeoutError { .. })));
+
+        let mut stats = empty_stats();
+        scheduler.fill_stats(&mut stats);
+        assert_eq!((stats.queue_depth, stats.expired_requests), (0, 1));
+
+        // 过期请求不再占用名额，释放后新请求立即放行
+        drop(held);
+        let next = tokio::time::timeout(Duration::from_millis(100), scheduler.acquire(false)).await;
+        assert!(matches!(next, Ok(Ok(_))));
+    }
+
+    #[tokio::test]
+    async fn dropped_request_returns_a_grant_it_never_used() {
+        let scheduler = scheduler(1, 0);
+        let held = scheduler.acquire(true).await.unwrap();
+
+        // 请求入队后不再被轮询，模拟 select! 的另一分支先完成
+        let mut abandoned = Box::pin(scheduler.acquire(false));
+        assert!(tokio::time::timeout(Duration::from_millis(10), &mut abandoned).await.is_err());
+
+        // 放行信号送达后调用方才被丢弃，名额必须归还
+        drop(held);
+        drop(abandoned);
+
+        let next = tokio::time::timeout(Duration::from_millis(100), scheduler.acquire(false)).await;
+        assert!(matches!(next, Ok(Ok(_))));
+    }
+
+    #[tokio::test]
+    async fn frames_respect_inter_frame_gap_and_in_flight_limit() {
+        let scheduler = scheduler(2, 30);
+        let first = scheduler.acquire(false).await.unwrap();
+        drop(first);
+        let started = Instant::now();
+        let _second = scheduler.acquire(false).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(25));
+
+        let _third = scheduler.acquire(false).await.unwrap();
+        let blocked = with_request_deadline(Duration::from_millis(20), scheduler.acquire(false)).await;
+        assert!(blocked.is_err());
+    }
+}
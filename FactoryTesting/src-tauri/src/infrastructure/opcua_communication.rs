@@ -0,0 +1,2615 @@
+//! # OPC UA 客户端通信服务实现模块 (UA Binary / opc.tcp)
+//!
+//! ## 业务作用
+//! 部分被测系统只通过OPC UA开放变量，本模块提供原生OPC UA客户端实现：
+//! - 通过 opc.tcp 建立安全通道与会话，支持匿名与用户名/密码两种登录方式
+//! - 按点表 `plc_communication_address` 中的NodeId字符串读写变量，如 `ns=2;s=Tank.Level`、`i=2259`
+//! - 地址也可写成相对 Objects 文件夹的浏览路径，如 `Tank/Level`、`2:Tank/2:Level`，首次访问时浏览解析并缓存
+//! - 读写值在 Bool/Float/Int/Double/String 点位类型与OPC UA内置类型之间转换，写入时按变量自身类型编码
+//! - 安全令牌到期前自动续订，会话空闲接近超时前重建；服务器丢弃会话或安全通道时重建会话并重试一次
+//!
+//! ## 协议分层
+//! ```text
+//! UA TCP (HEL/ACK, OPN/MSG/CLO 消息块) → 安全通道 (SecurityPolicy#None) → 会话服务 (Create/ActivateSession, Read, Write, Browse)
+//! ```
+//!
+//! ## 安全限制
+//! 仅支持 SecurityPolicy None（不签名、不加密）。用户名登录时密码以明文令牌发送，
+//! 要求服务器的用户名令牌策略允许在None策略下使用，需要加密令牌的服务器会给出明确的错误提示
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::Utc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use crate::domain::services::{
+    BaseService, ConnectionHandle, ConnectionStats, ConnectionTestResult, PlcConnectionConfig,
+    PlcDataType, PlcProtocol, PlcValue, ReadRequest, ReadResult, WriteRequest, WriteResult,
+};
+use crate::domain::services::plc_communication_service::IPlcCommunicationService;
+use crate::infrastructure::plc_communication::{update_read_stats, update_write_stats};
+use crate::utils::error::{AppError, AppResult};
+
+const SECURITY_POLICY_NONE: &str = "http://opcfoundation.org/UA/SecurityPolicy#None";
+const CLIENT_APPLICATION_URI: &str = "urn:FactoryTesting:Client";
+const CLIENT_PRODUCT_URI: &str = "urn:FactoryTesting";
+const CLIENT_APPLICATION_NAME: &str = "FactoryTesting";
+
+/// 本地收发缓冲区大小
+const BUFFER_SIZE: u32 = 65_535;
+/// 接收消息块的长度上限，防止异常长度导致大块内存分配
+const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+/// 单个Read/Write请求最多包含的节点数
+const MAX_NODES_PER_REQUEST: usize = 100;
+/// 请求的会话超时（毫秒）
+const SESSION_TIMEOUT_MS: f64 = 60_000.0;
+/// 请求的安全令牌有效期（毫秒）
+const CHANNEL_LIFETIME_MS: u32 = 3_600_000;
+/// OpenSecureChannel 请求类型: 新建通道 / 续订令牌
+const REQUEST_TYPE_ISSUE: u32 = 0;
+const REQUEST_TYPE_RENEW: u32 = 1;
+/// Value 属性ID
+const ATTRIBUTE_VALUE: u32 = 13;
+/// 读取时不返回时间戳
+const TIMESTAMPS_NEITHER: u32 = 3;
+/// 1601-01-01 到 1970-01-01 的100纳秒间隔数
+const UA_EPOCH_OFFSET: i64 = 116_444_736_000_000_000;
+/// 服务器运行状态变量 Server_ServerStatus_State，心跳探测时读取
+const SERVER_STATE_NODE: &str = "i=2259";
+
+/// 命名空间0中的标准节点与服务编码ID
+mod ids {
+    pub const HIERARCHICAL_REFERENCES: u32 = 33;
+    pub const OBJECTS_FOLDER: u32 = 85;
+    pub const ANONYMOUS_IDENTITY_TOKEN: u32 = 321;
+    pub const USER_NAME_IDENTITY_TOKEN: u32 = 324;
+    pub const SERVICE_FAULT: u32 = 397;
+    pub const OPEN_SECURE_CHANNEL_REQUEST: u32 = 446;
+    pub const OPEN_SECURE_CHANNEL_RESPONSE: u32 = 449;
+    pub const CLOSE_SECURE_CHANNEL_REQUEST: u32 = 452;
+    pub const CREATE_SESSION_REQUEST: u32 = 461;
+    pub const CREATE_SESSION_RESPONSE: u32 = 464;
+    pub const ACTIVATE_SESSION_REQUEST: u32 = 467;
+    pub const ACTIVATE_SESSION_RESPONSE: u32 = 470;
+    pub const CLOSE_SESSION_REQUEST: u32 = 473;
+    pub const CLOSE_SESSION_RESPONSE: u32 = 476;
+    pub const BROWSE_REQUEST: u32 = 527;
+    pub const BROWSE_RESPONSE: u32 = 530;
+    pub const BROWSE_NEXT_REQUEST: u32 = 533;
+    pub const BROWSE_NEXT_RESPONSE: u32 = 536;
+    pub const READ_REQUEST: u32 = 631;
+    pub const READ_RESPONSE: u32 = 634;
+    pub const WRITE_REQUEST: u32 = 673;
+    pub const WRITE_RESPONSE: u32 = 676;
+}
+
+/// 状态码最高位为1表示失败
+fn is_bad(status: u32) -> bool {
+    status & 0x8000_0000 != 0
+}
+
+/// 常见状态码名称，便于现场排查
+fn status_code_name(status: u32) -> String {
+    let name = match status & 0xFFFF_0000 {
+        0x800A_0000 => "BadTimeout",
+        0x801F_0000 => "BadUserAccessDenied",
+        0x8020_0000 => "BadIdentityTokenInvalid",
+        0x8021_0000 => "BadIdentityTokenRejected",
+        0x8022_0000 => "BadSecureChannelIdInvalid",
+        0x8025_0000 => "BadSessionIdInvalid",
+        0x8026_0000 => "BadSessionClosed",
+        0x8032_0000 => "BadWaitingForInitialData",
+        0x8033_0000 => "BadNodeIdInvalid",
+        0x8034_0000 => "BadNodeIdUnknown",
+        0x8035_0000 => "BadAttributeIdInvalid",
+        0x803A_0000 => "BadNotReadable",
+        0x803B_0000 => "BadNotWritable",
+        0x8055_0000 => "BadSecurityPolicyRejected",
+        0x8074_0000 => "BadTypeMismatch",
+        _ => return format!("0x{:08X}", status),
+    };
+    format!("{} (0x{:08X})", name, status)
+}
+
+/// 单个节点的失败状态码：节点不存在、不可读写、类型不匹配、无权限属于配置问题，其余按通信故障处理
+fn status_error(message: String, status: u32) -> AppError {
+    match status & 0xFFFF_0000 {
+        0x801F_0000 | 0x8033_0000 | 0x8034_0000 | 0x8035_0000 | 0x803A_0000 | 0x803B_0000 | 0x8074_0000 => {
+            AppError::configuration_error(message)
+        }
+        _ => AppError::plc_communication_error(message),
+    }
+}
+
+/// 服务器已丢弃会话或安全通道（超时、服务器重启），重建会话后可直接重试
+fn is_session_lost(status: u32) -> bool {
+    matches!(status & 0xFFFF_0000, 0x8022_0000 | 0x8025_0000 | 0x8026_0000)
+}
+
+/// 变量未得到应答数据
+fn missing_response() -> AppError {
+    AppError::plc_communication_error("OPC UA无应答数据")
+}
+
+/// 内置类型名称
+fn type_name(type_id: u8) -> String {
+    const NAMES: [&str; 26] = [
+        "Null", "Boolean", "SByte", "Byte", "Int16", "UInt16", "Int32", "UInt32", "Int64", "UInt64",
+        "Float", "Double", "String", "DateTime", "Guid", "ByteString", "XmlElement", "NodeId",
+        "ExpandedNodeId", "StatusCode", "QualifiedName", "LocalizedText", "ExtensionObject",
+        "DataValue", "Variant", "DiagnosticInfo",
+    ];
+    NAMES.get(type_id as usize).map(|n| n.to_string()).unwrap_or_else(|| format!("未知类型({})", type_id))
+}
+
+/// 当前时间的UA DateTime（自1601年起的100纳秒数）
+fn ua_now() -> i64 {
+    Utc::now().timestamp_millis() * 10_000 + UA_EPOCH_OFFSET
+}
+
+/// NodeId标识符
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Identifier {
+    Numeric(u32),
+    String(String),
+    Guid([u8; 16]),
+    Opaque(Vec<u8>),
+}
+
+/// OPC UA 节点ID
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NodeId {
+    pub namespace: u16,
+    pub identifier: Identifier,
+}
+
+impl NodeId {
+    pub fn numeric(namespace: u16, id: u32) -> Self {
+        Self { namespace, identifier: Identifier::Numeric(id) }
+    }
+
+    fn null() -> Self {
+        Self::numeric(0, 0)
+    }
+
+    /// 地址是否为NodeId字符串（否则按浏览路径处理）
+    pub fn looks_like_node_id(address: &str) -> bool {
+        let trimmed = address.trim();
+        ["ns=", "i=", "s=", "g=", "b="].iter().any(|prefix| trimmed.starts_with(prefix))
+    }
+
+    /// 解析NodeId字符串，如 `ns=2;s=Tank.Level`、`i=2259`、`ns=1;g=...`、`ns=1;b=...`
+    pub fn parse(text: &str) -> AppResult<Self> {
+        let trimmed = text.trim();
+        let invalid = |reason: &str| AppError::validation_error(format!("无效的OPC UA NodeId '{}': {}", text, reason));
+
+        let (namespace, rest) = match trimmed.strip_prefix("ns=") {
+            Some(rest) => {
+                let (namespace, rest) = rest.split_once(';').ok_or_else(|| invalid("缺少 ';' 分隔符"))?;
+                (namespace.parse::<u16>().map_err(|_| invalid("命名空间索引无效"))?, rest)
+            }
+            None => (0, trimmed),
+        };
+        let (kind, value) = rest.split_once('=').ok_or_else(|| invalid("缺少标识符类型 i/s/g/b"))?;
+        let identifier = match kind {
+            "i" => Identifier::Numeric(value.parse().map_err(|_| invalid("数字标识符无效"))?),
+            "s" if !value.is_empty() => Identifier::String(value.to_string()),
+            "g" => Identifier::Guid(parse_guid(value).ok_or_else(|| invalid("GUID格式无效"))?),
+            "b" => Identifier::Opaque(BASE64.decode(value).map_err(|_| invalid("Base64格式无效"))?),
+            _ => return Err(invalid("不支持的标识符类型，应为 i/s/g/b")),
+        };
+        Ok(Self { namespace, identifier })
+    }
+}
+
+impl fmt::Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.namespace != 0 {
+            write!(f, "ns={};", self.namespace)?;
+        }
+        match &self.identifier {
+            Identifier::Numeric(id) => write!(f, "i={}", id),
+            Identifier::String(id) => write!(f, "s={}", id),
+            Identifier::Guid(bytes) => write!(f, "g={}", format_guid(bytes)),
+            Identifier::Opaque(bytes) => write!(f, "b={}", BASE64.encode(bytes)),
+        }
+    }
+}
+
+/// GUID文本转为UA二进制编码（前三段小端）
+fn parse_guid(text: &str) -> Option<[u8; 16]> {
+    let parts: Vec<&str> = text.split('-').collect();
+    let lengths: Vec<usize> = parts.iter().map(|p| p.len()).collect();
+    if lengths != [8, 4, 4, 4, 12] || !text.chars().all(|c| c.is_ascii_hexdigit() || c == '-') {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    bytes[..4].copy_from_slice(&u32::from_str_radix(parts[0], 16).ok()?.to_le_bytes());
+    bytes[4..6].copy_from_slice(&u16::from_str_radix(parts[1], 16).ok()?.to_le_bytes());
+    bytes[6..8].copy_from_slice(&u16::from_str_radix(parts[2], 16).ok()?.to_le_bytes());
+    let tail = format!("{}{}", parts[3], parts[4]);
+    for (index, byte) in bytes[8..].iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&tail[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn format_guid(bytes: &[u8; 16]) -> String {
+    let data1 = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let data2 = u16::from_le_bytes([bytes[4], bytes[5]]);
+    let data3 = u16::from_le_bytes([bytes[6], bytes[7]]);
+    let tail: String = bytes[8..].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{:08x}-{:04x}-{:04x}-{}-{}", data1, data2, data3, &tail[..4], &tail[4..])
+}
+
+/// OPC UA 变量值（Variant）
+#[derive(Debug, Clone, PartialEq)]
+pub enum Variant {
+    Empty,
+    Boolean(bool),
+    SByte(i8),
+    Byte(u8),
+    Int16(i16),
+    UInt16(u16),
+    Int32(i32),
+    UInt32(u32),
+    Int64(i64),
+    UInt64(u64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    ByteString(Vec<u8>),
+    Array(Vec<Variant>),
+    /// 不参与转换的内置类型，仅保留类型ID用于提示
+    Unsupported(u8),
+}
+
+impl Variant {
+    /// 内置类型ID，数组返回元素类型
+    fn type_id(&self) -> u8 {
+        match self {
+            Variant::Empty => 0,
+            Variant::Boolean(_) => 1,
+            Variant::SByte(_) => 2,
+            Variant::Byte(_) => 3,
+            Variant::Int16(_) => 4,
+            Variant::UInt16(_) => 5,
+            Variant::Int32(_) => 6,
+            Variant::UInt32(_) => 7,
+            Variant::Int64(_) => 8,
+            Variant::UInt64(_) => 9,
+            Variant::Float(_) => 10,
+            Variant::Double(_) => 11,
+            Variant::String(_) => 12,
+            Variant::ByteString(_) => 15,
+            Variant::Array(items) => items.first().map(|item| item.type_id()).unwrap_or(0),
+            Variant::Unsupported(type_id) => *type_id,
+        }
+    }
+
+    /// 整数（含布尔）值
+    fn as_integer(&self) -> Option<i64> {
+        match self {
+            Variant::Boolean(v) => Some(*v as i64),
+            Variant::SByte(v) => Some(*v as i64),
+            Variant::Byte(v) => Some(*v as i64),
+            Variant::Int16(v) => Some(*v as i64),
+            Variant::UInt16(v) => Some(*v as i64),
+            Variant::Int32(v) => Some(*v as i64),
+            Variant::UInt32(v) => Some(*v as i64),
+            Variant::Int64(v) => Some(*v),
+            Variant::UInt64(v) => i64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    /// 数值（不含布尔）
+    fn as_f64(&self) -> Option<f64> {
+        match self {
+            Variant::Float(v) => Some(*v as f64),
+            Variant::Double(v) => Some(*v),
+            Variant::Boolean(_) => None,
+            other => other.as_integer().map(|v| v as f64),
+        }
+    }
+}
+
+/// 带状态的变量值
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataValue {
+    pub value: Variant,
+    pub status: u32,
+}
+
+/// 浏览得到的子节点引用
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowseReference {
+    pub node_id: NodeId,
+    pub browse_namespace: u16,
+    pub browse_name: String,
+    pub display_name: String,
+    /// 节点类别: 1=Object, 2=Variable, 4=Method ...
+    pub node_class: u32,
+}
+
+/// 端点的用户令牌策略
+#[derive(Debug, Clone)]
+struct UserTokenPolicy {
+    policy_id: String,
+    /// 令牌类型: 0=匿名, 1=用户名, 2=证书, 3=签发令牌
+    token_type: u32,
+    /// 令牌加密策略，为空时沿用端点的策略
+    security_policy_uri: Option<String>,
+}
+
+/// UA Binary 编码器（小端）
+#[derive(Debug, Default)]
+struct UaWriter {
+    buf: Vec<u8>,
+}
+
+impl UaWriter {
+    fn bool(&mut self, value: bool) {
+        self.buf.push(value as u8);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i64(&mut self, value: i64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// 字符串，None 编码为空值(-1)
+    fn string(&mut self, value: Option<&str>) {
+        self.bytes(value.map(str::as_bytes));
+    }
+
+    fn bytes(&mut self, value: Option<&[u8]>) {
+        match value {
+            Some(bytes) => {
+                self.i32(bytes.len() as i32);
+                self.buf.extend_from_slice(bytes);
+            }
+            None => self.i32(-1),
+        }
+    }
+
+    fn localized_text(&mut self, text: &str) {
+        self.u8(0x02);
+        self.string(Some(text));
+    }
+
+    fn node_id(&mut self, node: &NodeId) {
+        let namespace = node.namespace;
+        match &node.identifier {
+            Identifier::Numeric(id) if namespace == 0 && *id <= 0xFF => {
+                self.u8(0x00);
+                self.u8(*id as u8);
+            }
+            Identifier::Numeric(id) if namespace <= 0xFF && *id <= 0xFFFF => {
+                self.u8(0x01);
+                self.u8(namespace as u8);
+                self.u16(*id as u16);
+            }
+            Identifier::Numeric(id) => {
+                self.u8(0x02);
+                self.u16(namespace);
+                self.u32(*id);
+            }
+            Identifier::String(id) => {
+                self.u8(0x03);
+                self.u16(namespace);
+                self.string(Some(id));
+            }
+            Identifier::Guid(bytes) => {
+                self.u8(0x04);
+                self.u16(namespace);
+                self.buf.extend_from_slice(bytes);
+            }
+            Identifier::Opaque(bytes) => {
+                self.u8(0x05);
+                self.u16(namespace);
+                self.bytes(Some(bytes));
+            }
+        }
+    }
+
+    fn extension_object(&mut self, type_id: &NodeId, body: &[u8]) {
+        self.node_id(type_id);
+        self.u8(0x01);
+        self.bytes(Some(body));
+    }
+
+    fn null_extension_object(&mut self) {
+        self.node_id(&NodeId::null());
+        self.u8(0x00);
+    }
+
+    fn variant(&mut self, value: &Variant) {
+        match value {
+            Variant::Array(items) => {
+                self.u8(0x80 | value.type_id());
+                self.i32(items.len() as i32);
+                for item in items {
+                    self.variant_body(item);
+                }
+            }
+            _ => {
+                self.u8(value.type_id());
+                self.variant_body(value);
+            }
+        }
+    }
+
+    fn variant_body(&mut self, value: &Variant) {
+        match value {
+            Variant::Boolean(v) => self.bool(*v),
+            Variant::SByte(v) => self.u8(*v as u8),
+            Variant::Byte(v) => self.u8(*v),
+            Variant::Int16(v) => self.u16(*v as u16),
+            Variant::UInt16(v) => self.u16(*v),
+            Variant::Int32(v) => self.i32(*v),
+            Variant::UInt32(v) => self.u32(*v),
+            Variant::Int64(v) => self.i64(*v),
+            Variant::UInt64(v) => self.i64(*v as i64),
+            Variant::Float(v) => self.f32(*v),
+            Variant::Double(v) => self.f64(*v),
+            Variant::String(v) => self.string(Some(v)),
+            Variant::ByteString(v) => self.bytes(Some(v)),
+            Variant::Empty | Variant::Array(_) | Variant::Unsupported(_) => {}
+        }
+    }
+
+    fn data_value(&mut self, value: &DataValue) {
+        let has_value = value.value != Variant::Empty;
+        self.u8(has_value as u8 | if value.status != 0 { 0x02 } else { 0 });
+        if has_value {
+            self.variant(&value.value);
+        }
+        if value.status != 0 {
+            self.u32(value.status);
+        }
+    }
+}
+
+/// UA Binary 解码器
+struct UaReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> UaReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> AppResult<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or_else(|| AppError::plc_communication_error("OPC UA应答数据不完整"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    fn u8(&mut self) -> AppResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bool(&mut self) -> AppResult<bool> {
+        Ok(self.u8()? != 0)
+    }
+
+    fn u16(&mut self) -> AppResult<u16> {
+        let b = self.take(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> AppResult<u32> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn i32(&mut self) -> AppResult<i32> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn i64(&mut self) -> AppResult<i64> {
+        let b = self.take(8)?;
+        Ok(i64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+
+    fn f32(&mut self) -> AppResult<f32> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn f64(&mut self) -> AppResult<f64> {
+        Ok(f64::from_bits(self.i64()? as u64))
+    }
+
+    fn bytes(&mut self) -> AppResult<Option<Vec<u8>>> {
+        let len = self.i32()?;
+        if len < 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.take(len as usize)?.to_vec()))
+    }
+
+    fn string(&mut self) -> AppResult<Option<String>> {
+        Ok(self.bytes()?.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    /// 数组长度，空数组(-1)按0处理
+    fn array_len(&mut self) -> AppResult<usize> {
+        Ok(self.i32()?.max(0) as usize)
+    }
+
+    /// 读取NodeId，同时返回 ExpandedNodeId 的扩展标志位
+    fn node_id_with_flags(&mut self) -> AppResult<(NodeId, u8)> {
+        let mask = self.u8()?;
+        let node = match mask & 0x0F {
+            0x00 => NodeId::numeric(0, self.u8()? as u32),
+            0x01 => {
+                let namespace = self.u8()? as u16;
+                NodeId::numeric(namespace, self.u16()? as u32)
+            }
+            0x02 => {
+                let namespace = self.u16()?;
+                NodeId::numeric(namespace, self.u32()?)
+            }
+            0x03 => {
+                let namespace = self.u16()?;
+                NodeId { namespace, identifier: Identifier::String(self.string()?.unwrap_or_default()) }
+            }
+            0x04 => {
+                let namespace = self.u16()?;
+                let mut guid = [0u8; 16];
+                guid.copy_from_slice(self.take(16)?);
+                NodeId { namespace, identifier: Identifier::Guid(guid) }
+            }
+            0x05 => {
+                let namespace = self.u16()?;
+                NodeId { namespace, identifier: Identifier::Opaque(self.bytes()?.unwrap_or_default()) }
+            }
+            other => {
+                return Err(AppError::plc_communication_error(format!("无效的NodeId编码: 0x{:02X}", other)));
+            }
+        };
+        Ok((node, mask & 0xC0))
+    }
+
+    fn node_id(&mut self) -> AppResult<NodeId> {
+        Ok(self.node_id_with_flags()?.0)
+    }
+
+    fn expanded_node_id(&mut self) -> AppResult<NodeId> {
+        let (node, flags) = self.node_id_with_flags()?;
+        if flags & 0x80 != 0 {
+            self.string()?; // 命名空间URI
+        }
+        if flags & 0x40 != 0 {
+            self.u32()?; // 服务器索引
+        }
+        Ok(node)
+    }
+
+    fn localized_text(&mut self) -> AppResult<Option<String>> {
+        let mask = self.u8()?;
+        if mask & 0x01 != 0 {
+            self.string()?; // 语言
+        }
+        if mask & 0x02 != 0 {
+            return self.string();
+        }
+        Ok(None)
+    }
+
+    fn qualified_name(&mut self) -> AppResult<(u16, String)> {
+        let namespace = self.u16()?;
+        Ok((namespace, self.string()?.unwrap_or_default()))
+    }
+
+    fn extension_object(&mut self) -> AppResult<(NodeId, Vec<u8>)> {
+        let type_id = self.node_id()?;
+        let body = match self.u8()? {
+            0x00 => Vec::new(),
+            _ => self.bytes()?.unwrap_or_default(),
+        };
+        Ok((type_id, body))
+    }
+
+    fn skip_diagnostic_info(&mut self) -> AppResult<()> {
+        let mask = self.u8()?;
+        for flag in [0x01, 0x02, 0x04, 0x08] {
+            if mask & flag != 0 {
+                self.i32()?;
+            }
+        }
+        if mask & 0x10 != 0 {
+            self.string()?;
+        }
+        if mask & 0x20 != 0 {
+            self.u32()?;
+        }
+        if mask & 0x40 != 0 {
+            self.skip_diagnostic_info()?;
+        }
+        Ok(())
+    }
+
+    fn variant(&mut self) -> AppResult<Variant> {
+        let mask = self.u8()?;
+        let type_id = mask & 0x3F;
+        if mask & 0x80 == 0 {
+            return self.variant_body(type_id);
+        }
+
+        let count = self.array_len()?;
+        let mut items = Vec::new();
+        for _ in 0..count {
+            items.push(self.variant_body(type_id)?);
+        }
+        if mask & 0x40 != 0 {
+            for _ in 0..self.array_len()? {
+                self.i32()?; // 数组维度
+            }
+        }
+        if items.iter().any(|item| matches!(item, Variant::Unsupported(_))) {
+            return Ok(Variant::Unsupported(type_id));
+        }
+        Ok(Variant::Array(items))
+    }
+
+    fn variant_body(&mut self, type_id: u8) -> AppResult<Variant> {
+        let value = match type_id {
+            0 => Variant::Empty,
+            1 => Variant::Boolean(self.bool()?),
+            2 => Variant::SByte(self.u8()? as i8),
+            3 => Variant::Byte(self.u8()?),
+            4 => Variant::Int16(self.u16()? as i16),
+            5 => Variant::UInt16(self.u16()?),
+            6 => Variant::Int32(self.i32()?),
+            7 => Variant::UInt32(self.u32()?),
+            8 => Variant::Int64(self.i64()?),
+            9 => Variant::UInt64(self.i64()? as u64),
+            10 => Variant::Float(self.f32()?),
+            11 => Variant::Double(self.f64()?),
+            12 => Variant::String(self.string()?.unwrap_or_default()),
+            15 => Variant::ByteString(self.bytes()?.unwrap_or_default()),
+            // 其余类型只跳过内容
+            13 => {
+                self.take(8)?;
+                Variant::Unsupported(type_id)
+            }
+            14 => {
+                self.take(16)?;
+                Variant::Unsupported(type_id)
+            }
+            16 => {
+                self.bytes()?;
+                Variant::Unsupported(type_id)
+            }
+            17 => {
+                self.node_id()?;
+                Variant::Unsupported(type_id)
+            }
+            18 => {
+                self.expanded_node_id()?;
+                Variant::Unsupported(type_id)
+            }
+            19 => {
+                self.u32()?;
+                Variant::Unsupported(type_id)
+            }
+            20 => {
+                self.qualified_name()?;
+                Variant::Unsupported(type_id)
+            }
+            21 => {
+                self.localized_text()?;
+                Variant::Unsupported(type_id)
+            }
+            22 => {
+                self.extension_object()?;
+                Variant::Unsupported(type_id)
+            }
+            23 => {
+                self.data_value()?;
+                Variant::Unsupported(type_id)
+            }
+            24 => {
+                self.variant()?;
+                Variant::Unsupported(type_id)
+            }
+            25 => {
+                self.skip_diagnostic_info()?;
+                Variant::Unsupported(type_id)
+            }
+            other => {
+                return Err(AppError::plc_communication_error(format!("未知的OPC UA数据类型: {}", other)));
+            }
+        };
+        Ok(value)
+    }
+
+    fn data_value(&mut self) -> AppResult<DataValue> {
+        let mask = self.u8()?;
+        let value = if mask & 0x01 != 0 { self.variant()? } else { Variant::Empty };
+        let status = if mask & 0x02 != 0 { self.u32()? } else { 0 };
+        if mask & 0x04 != 0 {
+            self.i64()?; // 源时间戳
+        }
+        if mask & 0x10 != 0 {
+            self.u16()?;
+        }
+        if mask & 0x08 != 0 {
+            self.i64()?; // 服务器时间戳
+        }
+        if mask & 0x20 != 0 {
+            self.u16()?;
+        }
+        Ok(DataValue { value, status })
+    }
+
+    /// 读取端点描述，仅返回安全模式为None的端点上的用户令牌策略
+    fn endpoint_token_policies(&mut self) -> AppResult<Vec<UserTokenPolicy>> {
+        self.string()?; // 端点URL
+        self.string()?; // 服务器应用URI
+        self.string()?; // 产品URI
+        self.localized_text()?; // 应用名称
+        self.u32()?; // 应用类型
+        self.string()?; // 网关服务器URI
+        self.string()?; // 发现配置URI
+        for _ in 0..self.array_len()? {
+            self.string()?; // 发现URL
+        }
+        self.bytes()?; // 服务器证书
+        let security_mode = self.u32()?;
+        let endpoint_policy = self.string()?;
+
+        let mut policies = Vec::new();
+        for _ in 0..self.array_len()? {
+            let policy_id = self.string()?.unwrap_or_default();
+            let token_type = self.u32()?;
+            self.string()?; // 签发令牌类型
+            self.string()?; // 签发端点URL
+            let security_policy_uri = self.string()?.filter(|uri| !uri.is_empty()).or_else(|| endpoint_policy.clone());
+            policies.push(UserTokenPolicy { policy_id, token_type, security_policy_uri });
+        }
+        self.string()?; // 传输协议URI
+        self.u8()?; // 安全等级
+
+        // 安全模式: 1=None
+        Ok(if security_mode == 1 { policies } else { Vec::new() })
+    }
+}
+
+/// 读取一个UA TCP消息块，返回 (消息类型+块类型, 去掉8字节消息头后的内容)
+async fn read_chunk(stream: &mut TcpStream) -> std::io::Result<([u8; 4], Vec<u8>)> {
+    let mut header = [0u8; 8];
+    stream.read_exact(&mut header).await?;
+    let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    if !(8..=MAX_CHUNK_SIZE).contains(&size) {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "OPC UA消息长度无效"));
+    }
+    let mut body = vec![0u8; size - 8];
+    stream.read_exact(&mut body).await?;
+    Ok(([header[0], header[1], header[2], header[3]], body))
+}
+
+fn build_chunk(kind: &[u8; 3], chunk_type: u8, body: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(body.len() + 8);
+    frame.extend_from_slice(kind);
+    frame.push(chunk_type);
+    frame.extend_from_slice(&((body.len() + 8) as u32).to_le_bytes());
+    frame.extend_from_slice(body);
+    frame
+}
+
+/// 解析 ERR 消息或中止块中的状态码与原因
+fn error_from_err_message(body: &[u8]) -> AppError {
+    let mut reader = UaReader::new(body);
+    let status = reader.u32().unwrap_or(0);
+    let reason = reader.string().ok().flatten().unwrap_or_default();
+    AppError::plc_communication_error(format!("OPC UA服务器返回错误 {}: {}", status_code_name(status), reason))
+}
+
+/// 校验服务应答的类型与应答头，读取位置移动到应答头之后
+fn check_response(reader: &mut UaReader, expected: u32) -> AppResult<()> {
+    let (type_id, service_result) = read_response_header(reader)?;
+    check_service_result(type_id, service_result, expected)
+}
+
+/// 读取应答类型与应答头，返回 (应答类型ID, 服务结果)
+fn read_response_header(reader: &mut UaReader) -> AppResult<(u32, u32)> {
+    let type_id = match reader.node_id()? {
+        NodeId { namespace: 0, identifier: Identifier::Numeric(id) } => id,
+        other => {
+            return Err(AppError::plc_communication_error(format!("OPC UA应答类型无效: {}", other)));
+        }
+    };
+    reader.i64()?; // 时间戳
+    reader.u32()?; // 请求句柄
+    let service_result = reader.u32()?;
+    reader.skip_diagnostic_info()?;
+    for _ in 0..reader.array_len()? {
+        reader.string()?; // 字符串表
+    }
+    reader.extension_object()?;
+    Ok((type_id, service_result))
+}
+
+fn check_service_result(type_id: u32, service_result: u32, expected: u32) -> AppResult<()> {
+    if type_id == ids::SERVICE_FAULT || is_bad(service_result) {
+        return Err(AppError::plc_communication_error(format!(
+            "OPC UA服务调用失败: {}",
+            status_code_name(service_result)
+        )));
+    }
+    if type_id != expected {
+        return Err(AppError::plc_communication_error(format!(
+            "OPC UA应答类型不匹配: 期望 i={}，实际 i={}",
+            expected, type_id
+        )));
+    }
+    Ok(())
+}
+
+/// 用户身份
+#[derive(Clone, PartialEq, Eq)]
+pub enum OpcUaIdentity {
+    Anonymous,
+    UserName { username: String, password: String },
+}
+
+/// 日志中不输出密码
+impl std::fmt::Debug for OpcUaIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpcUaIdentity::Anonymous => f.write_str("Anonymous"),
+            OpcUaIdentity::UserName { username, .. } => {
+                f.debug_struct("UserName").field("username", username).field("password", &"***").finish()
+            }
+        }
+    }
+}
+
+impl OpcUaIdentity {
+    fn token_type(&self) -> u32 {
+        match self {
+            OpcUaIdentity::Anonymous => 0,
+            OpcUaIdentity::UserName { .. } => 1,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            OpcUaIdentity::Anonymous => "匿名",
+            OpcUaIdentity::UserName { .. } => "用户名",
+        }
+    }
+}
+
+/// OPC UA连接参数，来自 `protocol_params` 的 endpoint_path / auth_mode / username / password /
+/// allow_plaintext_password
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpcUaConnectParams {
+    pub endpoint_url: String,
+    pub identity: OpcUaIdentity,
+    /// 用户是否确认允许在None安全策略下明文发送密码
+    pub allow_plaintext_password: bool,
+}
+
+impl OpcUaConnectParams {
+    pub fn from_config(config: &PlcConnectionConfig) -> Self {
+        let param = |key: &str| {
+            config.protocol_params.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string()
+        };
+        let identity = match param("auth_mode").as_str() {
+            "UserName" => OpcUaIdentity::UserName { username: param("username"), password: param("password") },
+            _ => OpcUaIdentity::Anonymous,
+        };
+        let allow_plaintext_password = config.protocol_params
+            .get("allow_plaintext_password")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        Self { endpoint_url: config.endpoint(), identity, allow_plaintext_password }
+    }
+}
+
+/// 单个OPC UA会话
+#[derive(Debug)]
+pub struct OpcUaClient {
+    stream: TcpStream,
+    endpoint_url: String,
+    io_timeout: Duration,
+    /// 服务器接收缓冲区大小，单个请求消息块不得超过该值
+    send_buffer_size: usize,
+    channel_id: u32,
+    token_id: u32,
+    sequence_number: u32,
+    request_id: u32,
+    request_handle: u32,
+    auth_token: NodeId,
+    /// 安全令牌使用超过服务器确认有效期的 3/4 后续订
+    token_renew_at: Instant,
+    /// 服务器确认的会话超时，空闲超过该时间的会话已被服务器关闭
+    session_timeout: Duration,
+    last_used: Instant,
+    /// 最近一次失败是服务器丢弃了会话或安全通道
+    session_lost: bool,
+}
+
+impl OpcUaClient {
+    /// 建立TCP连接，依次完成 Hello、打开安全通道、创建并激活会话
+    pub async fn connect(host: &str, port: u16, params: &OpcUaConnectParams, timeout_ms: u64) -> AppResult<Self> {
+        // 本客户端只实现None安全策略，用户名令牌会以明文经过网络，必须由用户显式允许
+        if matches!(params.identity, OpcUaIdentity::UserName { .. }) && !params.allow_plaintext_password {
+            return Err(AppError::configuration_error(
+                "OPC UA用户名登录需在未加密通道上明文发送密码，连接配置未允许明文密码，已拒绝连接",
+            ));
+        }
+        let io_timeout = Duration::from_millis(timeout_ms.max(100));
+        let address = format!("{}:{}", host, port);
+        let stream = timeout(io_timeout, TcpStream::connect(&address))
+            .await
+            .map_err(|_| AppError::timeout_error("OPC UA连接", format!("连接 {} 超时", address)))?
+            .map_err(|e| AppError::plc_communication_error(format!("OPC UA TCP连接失败 {}: {}", address, e)))?;
+        let _ = stream.set_nodelay(true);
+
+        let mut client = Self {
+            stream,
+            endpoint_url: params.endpoint_url.clone(),
+            io_timeout,
+            send_buffer_size: BUFFER_SIZE as usize,
+            channel_id: 0,
+            token_id: 0,
+            sequence_number: 0,
+            request_id: 0,
+            request_handle: 0,
+            auth_token: NodeId::null(),
+            token_renew_at: Instant::now(),
+            session_timeout: Duration::from_millis(SESSION_TIMEOUT_MS as u64),
+            last_used: Instant::now(),
+            session_lost: false,
+        };
+        client.hello().await?;
+        client.open_secure_channel(REQUEST_TYPE_ISSUE).await?;
+        let policies = client.create_session().await?;
+        client.activate_session(&params.identity, &policies).await?;
+        log::info!("✅ OPC UA会话建立成功: {} ({}登录)", params.endpoint_url, params.identity.label());
+        Ok(client)
+    }
+
+    async fn send(&mut self, frame: &[u8]) -> AppResult<()> {
+        timeout(self.io_timeout, self.stream.write_all(frame))
+            .await
+            .map_err(|_| AppError::timeout_error("OPC UA写入", "发送请求超时"))?
+            .map_err(|e| AppError::plc_communication_error(format!("OPC UA发送失败: {}", e)))
+    }
+
+    async fn recv_chunk(&mut self) -> AppResult<([u8; 4], Vec<u8>)> {
+        let (kind, body) = timeout(self.io_timeout, read_chunk(&mut self.stream))
+            .await
+            .map_err(|_| AppError::timeout_error("OPC UA读取", "等待应答超时"))?
+            .map_err(|e| AppError::plc_communication_error(format!("OPC UA接收失败: {}", e)))?;
+        if &kind[..3] == b"ERR" {
+            self.session_lost = UaReader::new(&body).u32().is_ok_and(is_session_lost);
+            return Err(error_from_err_message(&body));
+        }
+        Ok((kind, body))
+    }
+
+    async fn hello(&mut self) -> AppResult<()> {
+        let mut hello = UaWriter::default();
+        hello.u32(0); // 协议版本
+        hello.u32(BUFFER_SIZE); // 接收缓冲区
+        hello.u32(BUFFER_SIZE); // 发送缓冲区
+        hello.u32(0); // 最大消息长度（不限）
+        hello.u32(0); // 最大块数（不限）
+        hello.string(Some(&self.endpoint_url));
+        self.send(&build_chunk(b"HEL", b'F', &hello.buf)).await?;
+
+        let (kind, body) = self.recv_chunk().await?;
+        if &kind[..3] != b"ACK" {
+            return Err(AppError::plc_communication_error("OPC UA握手失败: 未收到ACK"));
+        }
+        let mut reader = UaReader::new(&body);
+        reader.u32()?; // 协议版本
+        let server_receive_buffer = reader.u32()? as usize;
+        if server_receive_buffer > 0 {
+            self.send_buffer_size = server_receive_buffer;
+        }
+        Ok(())
+    }
+
+    /// 写入序号头，返回本次请求ID
+    fn write_sequence_header(&mut self, writer: &mut UaWriter) -> u32 {
+        self.sequence_number = self.sequence_number.wrapping_add(1);
+        self.request_id = self.request_id.wrapping_add(1);
+        writer.u32(self.sequence_number);
+        writer.u32(self.request_id);
+        self.request_id
+    }
+
+    fn write_request_header(&mut self, writer: &mut UaWriter) {
+        self.request_handle = self.request_handle.wrapping_add(1);
+        writer.node_id(&self.auth_token);
+        writer.i64(ua_now());
+        writer.u32(self.request_handle);
+        writer.u32(0); // 不返回诊断信息
+        writer.string(None); // 审计条目ID
+        writer.u32(self.io_timeout.as_millis() as u32);
+        writer.null_extension_object();
+    }
+
+    /// 新建安全通道，或在已有通道上续订安全令牌
+    async fn open_secure_channel(&mut self, request_type: u32) -> AppResult<()> {
+        let mut body = UaWriter::default();
+        body.node_id(&NodeId::numeric(0, ids::OPEN_SECURE_CHANNEL_REQUEST));
+        self.write_request_header(&mut body);
+        body.u32(0); // 客户端协议版本
+        body.u32(request_type);
+        body.u32(1); // 安全模式: None
+        body.bytes(None); // 客户端随机数
+        body.u32(CHANNEL_LIFETIME_MS);
+
+        let mut payload = UaWriter::default();
+        payload.u32(self.channel_id); // 安全通道ID，新建时为0
+        payload.string(Some(SECURITY_POLICY_NONE));
+        payload.bytes(None); // 发送方证书
+        payload.bytes(None); // 接收方证书指纹
+        let request_id = self.write_sequence_header(&mut payload);
+        payload.buf.extend_from_slice(&body.buf);
+        self.send(&build_chunk(b"OPN", b'F', &payload.buf)).await?;
+
+        let (kind, response) = self.recv_chunk().await?;
+        if &kind[..3] != b"OPN" {
+            return Err(AppError::plc_communication_error("OPC UA打开安全通道失败: 应答类型无效"));
+        }
+        let mut reader = UaReader::new(&response);
+        let channel_id = reader.u32()?;
+        reader.string()?; // 安全策略
+        reader.bytes()?;
+        reader.bytes()?;
+        reader.u32()?; // 序号
+        if reader.u32()? != request_id {
+            return Err(AppError::plc_communication_error("OPC UA应答序号不匹配"));
+        }
+        check_response(&mut reader, ids::OPEN_SECURE_CHANNEL_RESPONSE)?;
+        reader.u32()?; // 服务器协议版本
+        reader.u32()?; // 安全令牌中的通道ID
+        self.channel_id = channel_id;
+        self.token_id = reader.u32()?;
+        reader.i64()?; // 令牌创建时间
+        let lifetime = match reader.u32()? {
+            0 => CHANNEL_LIFETIME_MS,
+            revised => revised,
+        };
+        self.token_renew_at = Instant::now() + Duration::from_millis(lifetime as u64 * 3 / 4);
+        Ok(())
+    }
+
+    /// 安全令牌到期前续订，服务器不会在令牌过期后继续接受该通道上的消息
+    async fn renew_channel_if_due(&mut self) -> AppResult<()> {
+        if Instant::now() >= self.token_renew_at {
+            log::debug!("🔑 OPC UA续订安全令牌: {}", self.endpoint_url);
+            self.open_secure_channel(REQUEST_TYPE_RENEW).await?;
+        }
+        Ok(())
+    }
+
+    /// 会话已空闲超过服务器确认超时的 3/4，应在服务器关闭它之前重建
+    fn session_expiring(&self) -> bool {
+        self.last_used.elapsed() >= self.session_timeout * 3 / 4
+    }
+
+    /// 会话已空闲超过服务器确认的超时，服务器已将其关闭
+    fn session_expired(&self) -> bool {
+        self.last_used.elapsed() >= self.session_timeout
+    }
+
+    /// 发送一条对称加密头的消息（MSG/CLO），返回请求ID
+    async fn send_message(&mut self, kind: &[u8; 3], body: &[u8]) -> AppResult<u32> {
+        let mut payload = UaWriter::default();
+        payload.u32(self.channel_id);
+        payload.u32(self.token_id);
+        let request_id = self.write_sequence_header(&mut payload);
+        payload.buf.extend_from_slice(body);
+        if payload.buf.len() + 8 > self.send_buffer_size {
+            return Err(AppError::plc_communication_error(format!(
+                "OPC UA请求长度 {} 超过服务器缓冲区 {}",
+                payload.buf.len() + 8,
+                self.send_buffer_size
+            )));
+        }
+        self.send(&build_chunk(kind, b'F', &payload.buf)).await?;
+        Ok(request_id)
+    }
+
+    /// 接收一条完整的MSG应答（合并中间块）
+    async fn recv_message(&mut self, request_id: u32) -> AppResult<Vec<u8>> {
+        let mut message = Vec::new();
+        loop {
+            let (kind, chunk) = self.recv_chunk().await?;
+            if &kind[..3] != b"MSG" {
+                return Err(AppError::plc_communication_error("OPC UA应答类型无效"));
+            }
+            let mut reader = UaReader::new(&chunk);
+            reader.u32()?; // 通道ID
+            reader.u32()?; // 令牌ID
+            reader.u32()?; // 序号
+            if reader.u32()? != request_id {
+                return Err(AppError::plc_communication_error("OPC UA应答序号不匹配"));
+            }
+            match kind[3] {
+                b'F' => {
+                    message.extend_from_slice(reader.remaining());
+                    return Ok(message);
+                }
+                b'C' => message.extend_from_slice(reader.remaining()),
+                _ => return Err(error_from_err_message(reader.remaining())),
+            }
+        }
+    }
+
+    /// 调用一个会话服务，返回应答头之后的内容
+    async fn call(&mut self, request_type: u32, response_type: u32, encode: impl FnOnce(&mut UaWriter)) -> AppResult<Vec<u8>> {
+        let mut body = UaWriter::default();
+        body.node_id(&NodeId::numeric(0, request_type));
+        self.write_request_header(&mut body);
+        encode(&mut body);
+
+        let request_id = self.send_message(b"MSG", &body.buf).await?;
+        let response = self.recv_message(request_id).await?;
+        let mut reader = UaReader::new(&response);
+        let (type_id, service_result) = read_response_header(&mut reader)?;
+        self.session_lost = is_session_lost(service_result);
+        check_service_result(type_id, service_result, response_type)?;
+        self.last_used = Instant::now();
+        Ok(reader.remaining().to_vec())
+    }
+
+    async fn create_session(&mut self) -> AppResult<Vec<UserTokenPolicy>> {
+        let endpoint_url = self.endpoint_url.clone();
+        let session_name = format!("{} {}", CLIENT_APPLICATION_NAME, Uuid::new_v4());
+        let nonce: [u8; 32] = rand::random();
+        let response = self
+            .call(ids::CREATE_SESSION_REQUEST, ids::CREATE_SESSION_RESPONSE, |w| {
+                w.string(Some(CLIENT_APPLICATION_URI));
+                w.string(Some(CLIENT_PRODUCT_URI));
+                w.localized_text(CLIENT_APPLICATION_NAME);
+                w.u32(1); // 应用类型: Client
+                w.string(None); // 网关服务器URI
+                w.string(None); // 发现配置URI
+                w.i32(-1); // 发现URL
+                w.string(None); // 服务器URI
+                w.string(Some(&endpoint_url));
+                w.string(Some(&session_name));
+                w.bytes(Some(&nonce));
+                w.bytes(None); // 客户端证书
+                w.f64(SESSION_TIMEOUT_MS);
+                w.u32(0); // 最大应答长度（不限）
+            })
+            .await?;
+
+        let mut reader = UaReader::new(&response);
+        reader.node_id()?; // 会话ID
+        self.auth_token = reader.node_id()?;
+        let revised_timeout = reader.f64()?;
+        if revised_timeout.is_finite() && revised_timeout > 0.0 {
+            self.session_timeout = Duration::from_millis(revised_timeout as u64);
+        }
+        reader.bytes()?; // 服务器随机数
+        reader.bytes()?; // 服务器证书
+        let mut policies = Vec::new();
+        for _ in 0..reader.array_len()? {
+            policies.extend(reader.endpoint_token_policies()?);
+        }
+        Ok(policies)
+    }
+
+    async fn activate_session(&mut self, identity: &OpcUaIdentity, policies: &[UserTokenPolicy]) -> AppResult<()> {
+        let policy_id = match policies.iter().find(|p| p.token_type == identity.token_type()) {
+            Some(policy) => {
+                let plain_text_allowed =
+                    !matches!(policy.security_policy_uri.as_deref(), Some(uri) if uri != SECURITY_POLICY_NONE);
+                if identity.token_type() == 1 && !plain_text_allowed {
+                    return Err(AppError::plc_communication_error(format!(
+                        "OPC UA服务器要求用户名令牌按 {} 加密，当前仅支持None策略下的明文令牌",
+                        policy.security_policy_uri.as_deref().unwrap_or_default()
+                    )));
+                }
+                policy.policy_id.clone()
+            }
+            // 服务器未返回端点信息时使用常见的默认策略ID
+            None if policies.is_empty() => match identity {
+                OpcUaIdentity::Anonymous => "Anonymous".to_string(),
+                OpcUaIdentity::UserName { .. } => "UserName".to_string(),
+            },
+            None => {
+                return Err(AppError::plc_communication_error(format!(
+                    "OPC UA服务器未在None安全策略下开放{}登录",
+                    identity.label()
+                )));
+            }
+        };
+
+        let mut token = UaWriter::default();
+        token.string(Some(&policy_id));
+        let token_type = match identity {
+            OpcUaIdentity::Anonymous => ids::ANONYMOUS_IDENTITY_TOKEN,
+            OpcUaIdentity::UserName { username, password } => {
+                token.string(Some(username));
+                token.bytes(Some(password.as_bytes()));
+                token.string(None); // 加密算法: 明文
+                ids::USER_NAME_IDENTITY_TOKEN
+            }
+        };
+
+        self.call(ids::ACTIVATE_SESSION_REQUEST, ids::ACTIVATE_SESSION_RESPONSE, |w| {
+            w.string(None); // 客户端签名算法
+            w.bytes(None); // 客户端签名
+            w.i32(-1); // 客户端软件证书
+            w.i32(-1); // 语言
+            w.extension_object(&NodeId::numeric(0, token_type), &token.buf);
+            w.string(None); // 用户令牌签名算法
+            w.bytes(None); // 用户令牌签名
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// 读取多个节点的Value属性，按 `MAX_NODES_PER_REQUEST` 拆分请求
+    pub async fn read_values(&mut self, nodes: &[NodeId]) -> AppResult<Vec<DataValue>> {
+        let mut values = Vec::with_capacity(nodes.len());
+        for chunk in nodes.chunks(MAX_NODES_PER_REQUEST) {
+            let response = self
+                .call(ids::READ_REQUEST, ids::READ_RESPONSE, |w| {
+                    w.f64(0.0); // 最大缓存时间: 读取设备当前值
+                    w.u32(TIMESTAMPS_NEITHER);
+                    w.i32(chunk.len() as i32);
+                    for node in chunk {
+                        w.node_id(node);
+                        w.u32(ATTRIBUTE_VALUE);
+                        w.string(None); // 索引范围
+                        w.u16(0); // 数据编码
+                        w.string(None);
+                    }
+                })
+                .await?;
+
+            let mut reader = UaReader::new(&response);
+            if reader.array_len()? != chunk.len() {
+                return Err(AppError::plc_communication_error("OPC UA读取应答变量数不一致"));
+            }
+            for _ in 0..chunk.len() {
+                values.push(reader.data_value()?);
+            }
+        }
+        Ok(values)
+    }
+
+    /// 写入多个节点的Value属性，返回每个节点的状态码
+    pub async fn write_values(&mut self, items: &[(NodeId, Variant)]) -> AppResult<Vec<u32>> {
+        let mut statuses = Vec::with_capacity(items.len());
+        for chunk in items.chunks(MAX_NODES_PER_REQUEST) {
+            let response = self
+                .call(ids::WRITE_REQUEST, ids::WRITE_RESPONSE, |w| {
+                    w.i32(chunk.len() as i32);
+                    for (node, value) in chunk {
+                        w.node_id(node);
+                        w.u32(ATTRIBUTE_VALUE);
+                        w.string(None); // 索引范围
+                        w.data_value(&DataValue { value: value.clone(), status: 0 });
+                    }
+                })
+                .await?;
+
+            let mut reader = UaReader::new(&response);
+            if reader.array_len()? != chunk.len() {
+                return Err(AppError::plc_communication_error("OPC UA写入应答变量数不一致"));
+            }
+            for _ in 0..chunk.len() {
+                statuses.push(reader.u32()?);
+            }
+        }
+        Ok(statuses)
+    }
+
+    /// 浏览节点的层级子节点
+    ///
+    /// 外层错误表示通信失败，内层错误为该节点的浏览失败（如节点不存在）
+    pub async fn browse(&mut self, node: &NodeId) -> AppResult<AppResult<Vec<BrowseReference>>> {
+        let response = self
+            .call(ids::BROWSE_REQUEST, ids::BROWSE_RESPONSE, |w| {
+                w.node_id(&NodeId::null()); // 视图: 默认地址空间
+                w.i64(0);
+                w.u32(0);
+                w.u32(0); // 每个节点返回的引用数（不限）
+                w.i32(1);
+                w.node_id(node);
+                w.u32(0); // 方向: Forward
+                w.node_id(&NodeId::numeric(0, ids::HIERARCHICAL_REFERENCES));
+                w.bool(true); // 包含子类型
+                w.u32(0); // 节点类别: 全部
+                w.u32(0x3F); // 返回全部字段
+            })
+            .await?;
+
+        let mut references = Vec::new();
+        let mut continuation = match Self::parse_browse_result(&response, &mut references)? {
+            Ok(continuation) => continuation,
+            Err(status) => return Ok(Err(status_error(format!("浏览 {} 失败: {}", node, status_code_name(status)), status))),
+        };
+        while let Some(point) = continuation {
+            let response = self
+                .call(ids::BROWSE_NEXT_REQUEST, ids::BROWSE_NEXT_RESPONSE, |w| {
+                    w.bool(false); // 不释放续传点
+                    w.i32(1);
+                    w.bytes(Some(&point));
+                })
+                .await?;
+            continuation = match Self::parse_browse_result(&response, &mut references)? {
+                Ok(continuation) => continuation,
+                Err(status) => return Ok(Err(status_error(format!("浏览 {} 失败: {}", node, status_code_name(status)), status))),
+            };
+        }
+        Ok(Ok(references))
+    }
+
+    /// 解析单个浏览结果，返回续传点；节点级失败返回其状态码
+    fn parse_browse_result(response: &[u8], references: &mut Vec<BrowseReference>) -> AppResult<Result<Option<Vec<u8>>, u32>> {
+        let mut reader = UaReader::new(response);
+        if reader.array_len()? != 1 {
+            return Err(AppError::plc_communication_error("OPC UA浏览应答结果数不一致"));
+        }
+        let status = reader.u32()?;
+        if is_bad(status) {
+            return Ok(Err(status));
+        }
+        let continuation = reader.bytes()?.filter(|point| !point.is_empty());
+        for _ in 0..reader.array_len()? {
+            reader.node_id()?; // 引用类型
+            reader.bool()?; // 是否正向
+            let node_id = reader.expanded_node_id()?;
+            let (browse_namespace, browse_name) = reader.qualified_name()?;
+            let display_name = reader.localized_text()?.unwrap_or_default();
+            let node_class = reader.u32()?;
+            reader.expanded_node_id()?; // 类型定义
+            references.push(BrowseReference { node_id, browse_namespace, browse_name, display_name, node_class });
+        }
+        Ok(Ok(continuation))
+    }
+
+    /// 沿浏览路径从 Objects 文件夹逐级查找节点，路径段可写成 `名称` 或 `命名空间:名称`
+    pub async fn resolve_path(&mut self, path: &str) -> AppResult<AppResult<NodeId>> {
+        let trimmed = path.trim().trim_start_matches('/');
+        let trimmed = trimmed.strip_prefix("Objects/").unwrap_or(trimmed);
+        let segments: Vec<&str> = trimmed.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.is_empty() {
+            return Ok(Err(AppError::configuration_error(format!("浏览路径为空: '{}'", path))));
+        }
+
+        let mut current = NodeId::numeric(0, ids::OBJECTS_FOLDER);
+        for segment in segments {
+            let (namespace, name) = match segment.split_once(':') {
+                Some((namespace, name)) if namespace.parse::<u16>().is_ok() => (namespace.parse::<u16>().ok(), name),
+                _ => (None, segment),
+            };
+            let children = match self.browse(&current).await? {
+                Ok(children) => children,
+                Err(e) => return Ok(Err(e)),
+            };
+            let found = children.into_iter().find(|child| match namespace {
+                Some(namespace) => child.browse_namespace == namespace && child.browse_name == name,
+                None => child.browse_name == name || child.display_name == name,
+            });
+            current = match found {
+                Some(child) => child.node_id,
+                None => return Ok(Err(AppError::configuration_error(format!("浏览路径 '{}' 中找不到节点 '{}'", path, segment)))),
+            };
+        }
+        Ok(Ok(current))
+    }
+
+    /// 关闭会话与安全通道，失败时忽略（连接即将释放）
+    pub async fn close(mut self) {
+        let _ = self.call(ids::CLOSE_SESSION_REQUEST, ids::CLOSE_SESSION_RESPONSE, |w| w.bool(true)).await;
+        let mut body = UaWriter::default();
+        body.node_id(&NodeId::numeric(0, ids::CLOSE_SECURE_CHANNEL_REQUEST));
+        self.write_request_header(&mut body);
+        let _ = self.send_message(b"CLO", &body.buf).await;
+        let _ = self.stream.shutdown().await;
+    }
+}
+
+/// 将OPC UA值按请求的数据类型转换
+fn variant_to_plc_value(value: &Variant, data_type: PlcDataType) -> Result<PlcValue, String> {
+    let mismatch = || format!("值 {:?} 无法转换为 {:?}", value, data_type);
+    // 整数点位允许读取浮点变量，按四舍五入取整
+    let integer = || {
+        value
+            .as_integer()
+            .or_else(|| value.as_f64().filter(|v| v.is_finite()).map(|v| v.round() as i64))
+    };
+    match data_type {
+        PlcDataType::Bool => match value {
+            Variant::Boolean(v) => Ok(PlcValue::Bool(*v)),
+            other => other.as_integer().map(|v| PlcValue::Bool(v != 0)).ok_or_else(mismatch),
+        },
+        PlcDataType::Int16 => integer().and_then(|v| i16::try_from(v).ok()).map(PlcValue::Int16).ok_or_else(mismatch),
+        PlcDataType::Int32 => integer().and_then(|v| i32::try_from(v).ok()).map(PlcValue::Int32).ok_or_else(mismatch),
+        PlcDataType::UInt32 => integer().and_then(|v| u32::try_from(v).ok()).map(PlcValue::UInt32).ok_or_else(mismatch),
+        PlcDataType::Int64 => integer().map(PlcValue::Int64).ok_or_else(mismatch),
+        PlcDataType::UInt64 => match value {
+            Variant::UInt64(v) => Ok(PlcValue::UInt64(*v)),
+            _ => integer().and_then(|v| u64::try_from(v).ok()).map(PlcValue::UInt64).ok_or_else(mismatch),
+        },
+        PlcDataType::Float32 => value.as_f64().map(|v| PlcValue::Float32(v as f32)).ok_or_else(mismatch),
+        PlcDataType::Float64 => value.as_f64().map(PlcValue::Float64).ok_or_else(mismatch),
+        PlcDataType::String => match value {
+            Variant::String(v) => Ok(PlcValue::String(v.clone())),
+            Variant::Boolean(v) => Ok(PlcValue::String(v.to_string())),
+            other => other.as_f64().map(|v| PlcValue::String(v.to_string())).ok_or_else(mismatch),
+        },
+        PlcDataType::ByteArray => match value {
+            Variant::ByteString(v) => Ok(PlcValue::ByteArray(v.clone())),
+            Variant::String(v) => Ok(PlcValue::ByteArray(v.as_bytes().to_vec())),
+            _ => Err(mismatch()),
+        },
+    }
+}
+
+/// 将写入值编码为变量自身的内置类型，`target_type` 为0（类型未知）时按值本身的类型编码
+fn plc_value_to_variant(value: &PlcValue, target_type: u8) -> Result<Variant, String> {
+    let mismatch = || format!("写入值 {:?} 无法转换为 {}", value, type_name(target_type));
+    let number = match value {
+        PlcValue::Bool(v) => Some(*v as i64 as f64),
+        PlcValue::Int16(v) => Some(*v as f64),
+        PlcValue::Int32(v) => Some(*v as f64),
+        PlcValue::UInt32(v) => Some(*v as f64),
+        PlcValue::Int64(v) => Some(*v as f64),
+        PlcValue::UInt64(v) => Some(*v as f64),
+        PlcValue::Float32(v) => Some(*v as f64),
+        PlcValue::Float64(v) => Some(*v),
+        _ => None,
+    };
+    // 整数变量只接受整数值，避免静默截断
+    let integer = |min: i64, max: i64| -> Result<i64, String> {
+        let n = number.ok_or_else(mismatch)?;
+        if n.is_finite() && n.fract() == 0.0 && n >= min as f64 && n <= max as f64 {
+            Ok(n as i64)
+        } else {
+            Err(format!("写入值 {} 不是 {} 范围内的整数", n, type_name(target_type)))
+        }
+    };
+
+    let variant = match target_type {
+        0 => match value {
+            PlcValue::Bool(v) => Variant::Boolean(*v),
+            PlcValue::Int16(v) => Variant::Int16(*v),
+            PlcValue::Int32(v) => Variant::Int32(*v),
+            PlcValue::UInt32(v) => Variant::UInt32(*v),
+            PlcValue::Int64(v) => Variant::Int64(*v),
+            PlcValue::UInt64(v) => Variant::UInt64(*v),
+            PlcValue::Float32(v) => Variant::Float(*v),
+            PlcValue::Float64(v) => Variant::Double(*v),
+            PlcValue::String(v) => Variant::String(v.clone()),
+            PlcValue::ByteArray(v) => Variant::ByteString(v.clone()),
+            PlcValue::Array(_) => return Err(mismatch()),
+        },
+        1 => Variant::Boolean(integer(0, 1)? == 1),
+        2 => Variant::SByte(integer(i8::MIN as i64, i8::MAX as i64)? as i8),
+        3 => Variant::Byte(integer(0, u8::MAX as i64)? as u8),
+        4 => Variant::Int16(integer(i16::MIN as i64, i16::MAX as i64)? as i16),
+        5 => Variant::UInt16(integer(0, u16::MAX as i64)? as u16),
+        6 => Variant::Int32(integer(i32::MIN as i64, i32::MAX as i64)? as i32),
+        7 => Variant::UInt32(integer(0, u32::MAX as i64)? as u32),
+        // 64位整数值直接写入，避免经浮点数中转丢失精度
+        8 => match value {
+            PlcValue::Int64(v) => Variant::Int64(*v),
+            _ => Variant::Int64(integer(i64::MIN, i64::MAX)?),
+        },
+        9 => match value {
+            PlcValue::UInt64(v) => Variant::UInt64(*v),
+            _ => Variant::UInt64(integer(0, i64::MAX)? as u64),
+        },
+        10 => Variant::Float(number.ok_or_else(mismatch)? as f32),
+        11 => Variant::Double(number.ok_or_else(mismatch)?),
+        12 => match value {
+            PlcValue::String(v) => Variant::String(v.clone()),
+            _ => return Err(mismatch()),
+        },
+        15 => match value {
+            PlcValue::ByteArray(v) => Variant::ByteString(v.clone()),
+            PlcValue::String(v) => Variant::ByteString(v.as_bytes().to_vec()),
+            _ => return Err(mismatch()),
+        },
+        other => return Err(format!("不支持写入 {} 类型的变量", type_name(other))),
+    };
+    Ok(variant)
+}
+
+/// 解析地址为NodeId：NodeId字符串直接解析，其余按浏览路径查找，结果缓存
+async fn resolve_address(
+    client: &mut OpcUaClient,
+    node_ids: &mut HashMap<String, NodeId>,
+    address: &str,
+) -> AppResult<AppResult<NodeId>> {
+    if let Some(node) = node_ids.get(address) {
+        return Ok(Ok(node.clone()));
+    }
+    let resolved = if NodeId::looks_like_node_id(address) {
+        NodeId::parse(address)
+    } else {
+        client.resolve_path(address).await?
+    };
+    if let Ok(node) = &resolved {
+        node_ids.insert(address.to_string(), node.clone());
+    }
+    Ok(resolved)
+}
+
+/// 单个OPC UA连接的会话状态
+#[derive(Debug, Default)]
+struct OpcUaSession {
+    /// 通信失败后置为None，下次读写时自动重连
+    client: Option<OpcUaClient>,
+    /// 地址 → NodeId（浏览路径解析结果）
+    node_ids: HashMap<String, NodeId>,
+    /// NodeId → 变量的内置类型ID，写入时按该类型编码
+    value_types: HashMap<NodeId, u8>,
+}
+
+impl OpcUaSession {
+    /// 会话已建立且未因空闲被服务器关闭
+    fn is_active(&self) -> bool {
+        self.client.as_ref().is_some_and(|client| !client.session_expired())
+    }
+
+    /// 本次失败是否因服务器丢弃了会话或安全通道，此时重建会话后可重试一次
+    fn session_lost(&self) -> bool {
+        self.client.as_ref().is_some_and(|client| client.session_lost)
+    }
+
+    /// 读取多个地址，外层错误表示通信失败，内层错误为单个变量的读取失败
+    async fn read(&mut self, addresses: &[&str]) -> AppResult<Vec<AppResult<Variant>>> {
+        let client = self.client.as_mut().ok_or_else(|| AppError::plc_communication_error("OPC UA会话未建立"))?;
+        let mut resolved = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            resolved.push(resolve_address(client, &mut self.node_ids, address).await?);
+        }
+        let nodes: Vec<NodeId> = resolved.iter().filter_map(|r| r.as_ref().ok().cloned()).collect();
+        let mut values = client.read_values(&nodes).await?.into_iter();
+
+        let value_types = &mut self.value_types;
+        Ok(resolved
+            .into_iter()
+            .map(|node| {
+                let node = node?;
+                let data = values.next().ok_or_else(missing_response)?;
+                if is_bad(data.status) {
+                    return Err(status_error(format!("读取 {} 失败: {}", node, status_code_name(data.status)), data.status));
+                }
+                value_types.insert(node, data.value.type_id());
+                Ok(data.value)
+            })
+            .collect())
+    }
+
+    /// 写入多个地址，类型未知的变量先读取一次以确定编码类型
+    async fn write(&mut self, items: &[(&str, &PlcValue)]) -> AppResult<Vec<AppResult<()>>> {
+        let client = self.client.as_mut().ok_or_else(|| AppError::plc_communication_error("OPC UA会话未建立"))?;
+        let mut resolved = Vec::with_capacity(items.len());
+        for (address, _) in items {
+            resolved.push(resolve_address(client, &mut self.node_ids, address).await?);
+        }
+
+        let mut unknown: Vec<NodeId> = Vec::new();
+        for node in resolved.iter().flatten() {
+            if !self.value_types.contains_key(node) && !unknown.contains(node) {
+                unknown.push(node.clone());
+            }
+        }
+        if !unknown.is_empty() {
+            let values = client.read_values(&unknown).await?;
+            for (node, data) in unknown.into_iter().zip(values) {
+                if !is_bad(data.status) {
+                    self.value_types.insert(node, data.value.type_id());
+                }
+            }
+        }
+
+        let planned: Vec<AppResult<(NodeId, Variant)>> = resolved
+            .into_iter()
+            .zip(items)
+            .map(|(node, (_, value))| {
+                let node = node?;
+                let target_type = self.value_types.get(&node).copied().unwrap_or(0);
+                plc_value_to_variant(value, target_type)
+                    .map(|variant| (node, variant))
+                    .map_err(AppError::validation_error)
+            })
+            .collect();
+        let writes: Vec<(NodeId, Variant)> = planned.iter().filter_map(|p| p.as_ref().ok().cloned()).collect();
+        let mut statuses = client.write_values(&writes).await?.into_iter();
+
+        let value_types = &mut self.value_types;
+        Ok(planned
+            .into_iter()
+            .map(|plan| {
+                let (node, _) = plan?;
+                let status = statuses.next().ok_or_else(missing_response)?;
+                if is_bad(status) {
+                    // 类型不匹配时丢弃缓存，下次写入前重新读取变量类型
+                    value_types.remove(&node);
+                    return Err(status_error(format!("写入 {} 失败: {}", node, status_code_name(status)), status));
+                }
+                Ok(())
+            })
+            .collect())
+    }
+}
+
+/// 单个OPC UA连接
+#[derive(Debug)]
+struct OpcUaConnection {
+    config: PlcConnectionConfig,
+    params: OpcUaConnectParams,
+    handle: ConnectionHandle,
+    session: Mutex<OpcUaSession>,
+    stats: Arc<Mutex<ConnectionStats>>,
+}
+
+impl OpcUaConnection {
+    /// 确保会话可用：空闲接近超时的会话先重建，安全令牌到期前续订，通信失败后重新连接
+    async fn ensure_client(&self, session: &mut OpcUaSession) -> AppResult<()> {
+        if session.client.as_ref().is_some_and(OpcUaClient::session_expiring) {
+            log::info!("⌛ OPC UA会话空闲接近超时，重建会话: {}", self.config.name);
+            if let Some(client) = session.client.take() {
+                // 已超时的会话服务器已关闭，无需再发送关闭请求
+                if !client.session_expired() {
+                    client.close().await;
+                }
+            }
+        }
+        if let Some(client) = session.client.as_mut() {
+            if let Err(e) = client.renew_channel_if_due().await {
+                log::warn!("❌ OPC UA续订安全令牌失败，会话将重建: {} - {}", self.config.name, e);
+                session.client = None;
+                self.stats.lock().await.connection_errors += 1;
+            }
+        }
+        if session.client.is_none() {
+            log::info!("🔗 OPC UA重新连接: {} ({})", self.config.name, self.params.endpoint_url);
+            match OpcUaClient::connect(&self.config.host, self.config.port, &self.params, self.config.timeout_ms).await {
+                Ok(client) => session.client = Some(client),
+                Err(e) => {
+                    self.stats.lock().await.connection_errors += 1;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// OPC UA 通信服务
+#[derive(Debug, Default)]
+pub struct OpcUaPlcService {
+    connections: RwLock<HashMap<String, Arc<OpcUaConnection>>>,
+    default_handle: Mutex<Option<ConnectionHandle>>,
+}
+
+impl OpcUaPlcService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn get_connection(&self, handle: &ConnectionHandle) -> AppResult<Arc<OpcUaConnection>> {
+        self.connections
+            .read()
+            .await
+            .get(&handle.connection_id)
+            .cloned()
+            .ok_or_else(|| AppError::not_found_error("OPC UA连接", &handle.connection_id))
+    }
+
+    async fn read_variants(&self, handle: &ConnectionHandle, addresses: &[&str]) -> AppResult<Vec<AppResult<Variant>>> {
+        let connection = self.get_connection(handle).await?;
+        let start_time = Utc::now();
+        let mut session = connection.session.lock().await;
+        let mut retried = false;
+        loop {
+            connection.ensure_client(&mut session).await?;
+            match session.read(addresses).await {
+                Ok(values) => {
+                    update_read_stats(&connection.stats, start_time).await;
+                    return Ok(values);
+                }
+                Err(e) => {
+                    let retry = !retried && session.session_lost();
+                    session.client = None;
+                    connection.stats.lock().await.connection_errors += 1;
+                    if retry {
+                        log::info!("🔄 OPC UA会话已被服务器丢弃，重建后重试读取: {} - {}", connection.config.name, e);
+                        retried = true;
+                        continue;
+                    }
+                    log::warn!("❌ OPC UA读取失败，会话将重建: {} - {}", connection.config.name, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    async fn write_variants(&self, handle: &ConnectionHandle, items: &[(&str, &PlcValue)]) -> AppResult<Vec<AppResult<()>>> {
+        let connection = self.get_connection(handle).await?;
+        let start_time = Utc::now();
+        let mut session = connection.session.lock().await;
+        let mut retried = false;
+        loop {
+            connection.ensure_client(&mut session).await?;
+            match session.write(items).await {
+                Ok(results) => {
+                    update_write_stats(&connection.stats, start_time).await;
+                    return Ok(results);
+                }
+                Err(e) => {
+                    // 会话失效时服务器不执行该请求，重试写入的仍是同一组值
+                    let retry = !retried && session.session_lost();
+                    session.client = None;
+                    connection.stats.lock().await.connection_errors += 1;
+                    if retry {
+                        log::info!("🔄 OPC UA会话已被服务器丢弃，重建后重试写入: {} - {}", connection.config.name, e);
+                        retried = true;
+                        continue;
+                    }
+                    log::warn!("❌ OPC UA写入失败，会话将重建: {} - {}", connection.config.name, e);
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    async fn read_single(&self, handle: &ConnectionHandle, address: &str, data_type: PlcDataType) -> AppResult<PlcValue> {
+        self.read_variants(handle, &[address])
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(missing_response()))
+            .and_then(|value| variant_to_plc_value(&value, data_type).map_err(AppError::validation_error))
+            .map_err(|e| e.with_context(format!("OPC UA读取 {} 失败", address)))
+    }
+
+    async fn write_single(&self, handle: &ConnectionHandle, address: &str, value: PlcValue) -> AppResult<()> {
+        self.write_variants(handle, &[(address, &value)])
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(missing_response()))
+            .map_err(|e| e.with_context(format!("OPC UA写入 {} 失败", address)))
+    }
+
+    /// 浏览节点的子节点，`node` 为NodeId字符串或浏览路径，空字符串表示 Objects 文件夹
+    pub async fn browse(&self, handle: &ConnectionHandle, node: &str) -> AppResult<Vec<BrowseReference>> {
+        let connection = self.get_connection(handle).await?;
+        let mut guard = connection.session.lock().await;
+        connection.ensure_client(&mut guard).await?;
+        let session = &mut *guard;
+        let client = session.client.as_mut().ok_or_else(|| AppError::plc_communication_error("OPC UA会话未建立"))?;
+
+        let result = async {
+            let target = if node.trim().is_empty() {
+                Ok(NodeId::numeric(0, ids::OBJECTS_FOLDER))
+            } else {
+                resolve_address(client, &mut session.node_ids, node).await?
+            };
+            match target {
+                Ok(target) => client.browse(&target).await,
+                Err(e) => Ok(Err(e)),
+            }
+        }
+        .await;
+
+        match result {
+            Ok(references) => references,
+            Err(e) => {
+                log::warn!("❌ OPC UA浏览失败，会话将重建: {} - {}", connection.config.name, e);
+                session.client = None;
+                connection.stats.lock().await.connection_errors += 1;
+                Err(e)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseService for OpcUaPlcService {
+    fn service_name(&self) -> &'static str {
+        "OpcUaPlcService"
+    }
+
+    async fn initialize(&mut self) -> AppResult<()> {
+        log::info!("初始化OPC UA通信服务");
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> AppResult<()> {
+        log::info!("关闭OPC UA通信服务");
+        let connections: Vec<Arc<OpcUaConnection>> = self.connections.write().await.drain().map(|(_, c)| c).collect();
+        for connection in connections {
+            if let Some(client) = connection.session.lock().await.client.take() {
+                client.close().await;
+            }
+        }
+        *self.default_handle.lock().await = None;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AppResult<()> {
+        log::debug!("OPC UA服务健康检查: 连接数 = {}", self.connections.read().await.len());
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl IPlcCommunicationService for OpcUaPlcService {
+    async fn connect(&self, config: &PlcConnectionConfig) -> AppResult<ConnectionHandle> {
+        if let Some(existing) = self.connections.read().await.get(&config.id) {
+            if existing.session.lock().await.is_active() {
+                return Ok(existing.handle.clone());
+            }
+        }
+
+        let params = OpcUaConnectParams::from_config(config);
+        log::info!("连接到OPC UA服务器: {} ({})", config.name, params.endpoint_url);
+        let client = OpcUaClient::connect(&config.host, config.port, &params, config.timeout_ms).await?;
+
+        let now = Utc::now();
+        let handle = ConnectionHandle {
+            connection_id: config.id.clone(),
+            handle_id: Uuid::new_v4().to_string(),
+            protocol: PlcProtocol::OpcUa,
+            created_at: now,
+            last_activity: now,
+        };
+        let connection = OpcUaConnection {
+            config: config.clone(),
+            params,
+            handle: handle.clone(),
+            session: Mutex::new(OpcUaSession { client: Some(client), ..Default::default() }),
+            stats: Arc::new(Mutex::new(ConnectionStats {
+                connection_id: config.id.clone(),
+                total_reads: 0,
+                total_writes: 0,
+                successful_reads: 0,
+                successful_writes: 0,
+                average_read_time_ms: 0.0,
+                average_write_time_ms: 0.0,
+                connection_established_at: now,
+                last_communication: now,
+                connection_errors: 0,
+                queue_depth: 0,
+                max_queue_depth: 0,
+                average_queue_wait_ms: 0.0,
+                max_queue_wait_ms: 0.0,
+                expired_requests: 0,
+            })),
+        };
+        let previous = self.connections.write().await.insert(config.id.clone(), Arc::new(connection));
+        if let Some(previous) = previous {
+            if let Some(client) = previous.session.lock().await.client.take() {
+                client.close().await;
+            }
+        }
+        self.default_handle.lock().await.get_or_insert_with(|| handle.clone());
+        Ok(handle)
+    }
+
+    async fn disconnect(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        log::info!("断开OPC UA连接: {}", handle.connection_id);
+        let removed = self.connections.write().await.remove(&handle.connection_id);
+        if let Some(connection) = removed {
+            if let Some(client) = connection.session.lock().await.client.take() {
+                client.close().await;
+            }
+        }
+        Ok(())
+    }
+
+    async fn is_connected(&self, handle: &ConnectionHandle) -> AppResult<bool> {
+        let connection = self.get_connection(handle).await?;
+        let connected = connection.session.lock().await.is_active();
+        Ok(connected)
+    }
+
+    async fn probe(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        // 会话或安全通道失效时读取失败，会话随之重建；变量级错误同样说明链路正常
+        self.read_variants(handle, &[SERVER_STATE_NODE]).await.map(|_| ())
+    }
+
+    async fn read_bool(&self, handle: &ConnectionHandle, address: &str) -> AppResult<bool> {
+        match self.read_single(handle, address, PlcDataType::Bool).await? {
+            PlcValue::Bool(value) => Ok(value),
+            other => Err(AppError::plc_communication_error(format!("OPC UA读取类型不匹配: {:?}", other))),
+        }
+    }
+
+    async fn write_bool(&self, handle: &ConnectionHandle, address: &str, value: bool) -> AppResult<()> {
+        self.write_single(handle, address, PlcValue::Bool(value)).await
+    }
+
+    async fn read_f32(&self, handle: &ConnectionHandle, address: &str) -> AppResult<f32> {
+        match self.read_single(handle, address, PlcDataType::Float32).await? {
+            PlcValue::Float32(value) => Ok(value),
+            other => Err(AppError::plc_communication_error(format!("OPC UA读取类型不匹配: {:?}", other))),
+        }
+    }
+
+    async fn write_f32(&self, handle: &ConnectionHandle, address: &str, value: f32) -> AppResult<()> {
+        self.write_single(handle, address, PlcValue::Float32(value)).await
+    }
+
+    async fn read_i32(&self, handle: &ConnectionHandle, address: &str) -> AppResult<i32> {
+        match self.read_single(handle, address, PlcDataType::Int32).await? {
+            PlcValue::Int32(value) => Ok(value),
+            other => Err(AppError::plc_communication_error(format!("OPC UA读取类型不匹配: {:?}", other))),
+        }
+    }
+
+    async fn write_i32(&self, handle: &ConnectionHandle, address: &str, value: i32) -> AppResult<()> {
+        self.write_single(handle, address, PlcValue::Int32(value)).await
+    }
+
+    async fn batch_read(&self, handle: &ConnectionHandle, requests: &[ReadRequest]) -> AppResult<Vec<ReadResult>> {
+        let start_time = Utc::now();
+        let addresses: Vec<&str> = requests.iter().map(|r| r.address.as_str()).collect();
+        let values = self.read_variants(handle, &addresses).await?;
+        let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+
+        Ok(requests
+            .iter()
+            .zip(values)
+            .map(|(request, value)| {
+                match value.and_then(|v| variant_to_plc_value(&v, request.data_type).map_err(AppError::validation_error)) {
+                    Ok(value) => ReadResult {
+                        request_id: request.id.clone(),
+                        success: true,
+                        value: Some(value),
+                        error_message: None,
+                        failure_kind: None,
+                        execution_time_ms: elapsed,
+                    },
+                    Err(e) => ReadResult {
+                        request_id: request.id.clone(),
+                        success: false,
+                        value: None,
+                        error_message: Some(e.to_string()),
+                        failure_kind: Some(e.failure_kind()),
+                        execution_time_ms: elapsed,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    async fn batch_write(&self, handle: &ConnectionHandle, requests: &[WriteRequest]) -> AppResult<Vec<WriteResult>> {
+        let start_time = Utc::now();
+        let items: Vec<(&str, &PlcValue)> = requests.iter().map(|r| (r.address.as_str(), &r.value)).collect();
+        let outcomes = self.write_variants(handle, &items).await?;
+        let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+
+        Ok(requests
+            .iter()
+            .zip(outcomes)
+            .map(|(request, outcome)| WriteResult {
+                request_id: request.id.clone(),
+                success: outcome.is_ok(),
+                error_message: outcome.as_ref().err().map(|e| e.to_string()),
+                failure_kind: outcome.err().map(|e| e.failure_kind()),
+                execution_time_ms: elapsed,
+            })
+            .collect())
+    }
+
+    async fn get_connection_stats(&self, handle: &ConnectionHandle) -> AppResult<ConnectionStats> {
+        let connection = self.get_connection(handle).await?;
+        let stats = connection.stats.lock().await.clone();
+        Ok(stats)
+    }
+
+    async fn test_connection(&self, config: &PlcConnectionConfig) -> AppResult<ConnectionTestResult> {
+        let start_time = Utc::now();
+        let params = OpcUaConnectParams::from_config(config);
+        let result = OpcUaClient::connect(&config.host, config.port, &params, config.timeout_ms).await;
+        let connection_time = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+        let device_info = Some(format!("{} ({}登录)", params.endpoint_url, params.identity.label()));
+
+        Ok(match result {
+            Ok(client) => {
+                client.close().await;
+                ConnectionTestResult {
+                    success: true,
+                    connection_time_ms: connection_time,
+                    error_message: None,
+                    protocol_info: Some("OPC UA (SecurityPolicy None)".to_string()),
+                    device_info,
+                }
+            }
+            Err(e) => ConnectionTestResult {
+                success: false,
+                connection_time_ms: connection_time,
+                error_message: Some(e.to_string()),
+                protocol_info: Some("OPC UA".to_string()),
+                device_info,
+            },
+        })
+    }
+
+    async fn default_handle_by_id(&self, connection_id: &str) -> Option<ConnectionHandle> {
+        self.connections.read().await.get(connection_id).map(|c| c.handle.clone())
+    }
+
+    async fn default_handle(&self) -> Option<ConnectionHandle> {
+        self.default_handle.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::net::TcpListener;
+
+    type AddressSpace = Arc<std::sync::Mutex<HashMap<NodeId, Variant>>>;
+
+    const BAD_SECURE_CHANNEL_ID_INVALID: u32 = 0x8022_0000;
+    const BAD_SESSION_ID_INVALID: u32 = 0x8025_0000;
+    const BAD_NODE_ID_UNKNOWN: u32 = 0x8034_0000;
+    const BAD_TYPE_MISMATCH: u32 = 0x8074_0000;
+    const BAD_USER_ACCESS_DENIED: u32 = 0x801F_0000;
+
+    fn tank() -> NodeId {
+        NodeId { namespace: 2, identifier: Identifier::String("Tank".to_string()) }
+    }
+
+    fn tank_variable(name: &str) -> NodeId {
+        NodeId { namespace: 2, identifier: Identifier::String(format!("Tank.{}", name)) }
+    }
+
+    /// 替身服务器确认的会话超时与令牌有效期，以及供测试检查的计数
+    #[derive(Debug)]
+    struct StandInServer {
+        port: u16,
+        session_timeout_ms: f64,
+        token_lifetime_ms: u32,
+        sessions_created: AtomicU32,
+        token_renewals: AtomicU32,
+        /// 递增后此前创建的会话全部失效，模拟服务器重启或主动清理会话
+        session_epoch: AtomicU32,
+    }
+
+    impl StandInServer {
+        fn drop_sessions(&self) {
+            self.session_epoch.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// 单个TCP连接上的安全令牌与会话状态
+    struct StandInChannel {
+        token_id: u32,
+        token_issued: Instant,
+        /// (创建时的会话纪元, 最近一次使用时间)
+        session: Option<(u32, Instant)>,
+    }
+
+    async fn start_stand_in_server() -> u16 {
+        start_stand_in_server_with(SESSION_TIMEOUT_MS, CHANNEL_LIFETIME_MS).await.port
+    }
+
+    /// 进程内OPC UA服务器替身：Objects/Tank 下挂 Level(Double)、Running(Boolean)、Count(Int32)、Name(String)，
+    /// 匿名登录与 operator/secret 用户名登录均可用；会话空闲超时与安全令牌过期后拒绝请求
+    async fn start_stand_in_server_with(session_timeout_ms: f64, token_lifetime_ms: u32) -> Arc<StandInServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = Arc::new(StandInServer {
+            port: listener.local_addr().unwrap().port(),
+            session_timeout_ms,
+            token_lifetime_ms,
+            sessions_created: AtomicU32::new(0),
+            token_renewals: AtomicU32::new(0),
+            session_epoch: AtomicU32::new(0),
+        });
+        let space: AddressSpace = Arc::new(std::sync::Mutex::new(
+            [
+                ("Level", Variant::Double(12.5)),
+                ("Running", Variant::Boolean(true)),
+                ("Count", Variant::Int32(7)),
+                ("Name", Variant::String("TK-101".to_string())),
+            ]
+            .into_iter()
+            .map(|(name, value)| (tank_variable(name), value))
+            .collect(),
+        ));
+        let accepting = server.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, space.clone(), accepting.clone()));
+            }
+        });
+        server
+    }
+
+    async fn serve(mut stream: TcpStream, space: AddressSpace, server: Arc<StandInServer>) {
+        let mut channel = StandInChannel { token_id: 0, token_issued: Instant::now(), session: None };
+        while let Ok((kind, body)) = read_chunk(&mut stream).await {
+            let mut reader = UaReader::new(&body);
+            let mut writer = UaWriter::default();
+            let reply = match &kind[..3] {
+                b"HEL" => {
+                    for value in [0, BUFFER_SIZE, BUFFER_SIZE, 0, 0] {
+                        writer.u32(value);
+                    }
+                    build_chunk(b"ACK", b'F', &writer.buf)
+                }
+                b"OPN" => {
+                    reader.u32().unwrap();
+                    reader.string().unwrap();
+                    reader.bytes().unwrap();
+                    reader.bytes().unwrap();
+                    let (sequence, request_id) = (reader.u32().unwrap(), reader.u32().unwrap());
+                    reader.node_id().unwrap();
+                    skip_request_header(&mut reader);
+                    reader.u32().unwrap(); // 客户端协议版本
+                    if reader.u32().unwrap() == REQUEST_TYPE_RENEW {
+                        server.token_renewals.fetch_add(1, Ordering::SeqCst);
+                    }
+                    channel.token_id += 1;
+                    channel.token_issued = Instant::now();
+
+                    writer.u32(1);
+                    writer.string(Some(SECURITY_POLICY_NONE));
+                    writer.bytes(None);
+                    writer.bytes(None);
+                    writer.u32(sequence);
+                    writer.u32(request_id);
+                    write_response_header(&mut writer, ids::OPEN_SECURE_CHANNEL_RESPONSE, 0);
+                    writer.u32(0);
+                    writer.u32(1); // 通道ID
+                    writer.u32(channel.token_id);
+                    writer.i64(ua_now());
+                    writer.u32(server.token_lifetime_ms);
+                    writer.bytes(None);
+                    build_chunk(b"OPN", b'F', &writer.buf)
+                }
+                b"MSG" => {
+                    reader.u32().unwrap();
+                    let token_id = reader.u32().unwrap();
+                    // 令牌过期（含25%宽限期）后服务器关闭安全通道
+                    let token_expired = channel.token_issued.elapsed()
+                        > Duration::from_millis(server.token_lifetime_ms as u64 * 5 / 4);
+                    if token_id != channel.token_id || token_expired {
+                        writer.u32(BAD_SECURE_CHANNEL_ID_INVALID);
+                        writer.string(Some("安全令牌已过期"));
+                        let _ = stream.write_all(&build_chunk(b"ERR", b'F', &writer.buf)).await;
+                        return;
+                    }
+                    let (sequence, request_id) = (reader.u32().unwrap(), reader.u32().unwrap());
+                    writer.u32(1);
+                    writer.u32(token_id);
+                    writer.u32(sequence);
+                    writer.u32(request_id);
+                    handle_service(&mut reader, &mut writer, &space, &server, &mut channel);
+                    build_chunk(b"MSG", b'F', &writer.buf)
+                }
+                _ => return,
+            };
+            if stream.write_all(&reply).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn write_response_header(writer: &mut UaWriter, type_id: u32, status: u32) {
+        writer.node_id(&NodeId::numeric(0, type_id));
+        writer.i64(ua_now());
+        writer.u32(0);
+        writer.u32(status);
+        writer.u8(0); // 诊断信息
+        writer.i32(-1); // 字符串表
+        writer.null_extension_object();
+    }
+
+    fn skip_request_header(reader: &mut UaReader) {
+        reader.node_id().unwrap();
+        reader.i64().unwrap();
+        reader.u32().unwrap();
+        reader.u32().unwrap();
+        reader.string().unwrap();
+        reader.u32().unwrap();
+        reader.extension_object().unwrap();
+    }
+
+    fn handle_service(
+        reader: &mut UaReader,
+        writer: &mut UaWriter,
+        space: &AddressSpace,
+        server: &StandInServer,
+        channel: &mut StandInChannel,
+    ) {
+        let Identifier::Numeric(request_type) = reader.node_id().unwrap().identifier else {
+            panic!("请求类型应为数字NodeId");
+        };
+        skip_request_header(reader);
+
+        let epoch = server.session_epoch.load(Ordering::SeqCst);
+        if request_type == ids::CREATE_SESSION_REQUEST {
+            server.sessions_created.fetch_add(1, Ordering::SeqCst);
+            channel.session = Some((epoch, Instant::now()));
+        }
+        let session_timeout = Duration::from_millis(server.session_timeout_ms as u64);
+        match &mut channel.session {
+            Some((created_in, last_used)) if *created_in == epoch && last_used.elapsed() <= session_timeout => {
+                *last_used = Instant::now();
+            }
+            _ => {
+                channel.session = None;
+                write_response_header(writer, ids::SERVICE_FAULT, BAD_SESSION_ID_INVALID);
+                return;
+            }
+        }
+
+        let mut space = space.lock().unwrap();
+        match request_type {
+            ids::CREATE_SESSION_REQUEST => {
+                write_response_header(writer, ids::CREATE_SESSION_RESPONSE, 0);
+                writer.node_id(&NodeId::numeric(1, 1)); // 会话ID
+                writer.node_id(&NodeId::numeric(1, 2)); // 认证令牌
+                writer.f64(server.session_timeout_ms);
+                writer.bytes(None);
+                writer.bytes(None);
+                writer.i32(1);
+                writer.string(Some("opc.tcp://127.0.0.1"));
+                writer.string(Some("urn:stand-in"));
+                writer.string(None);
+                writer.localized_text("Stand-in");
+                writer.u32(0);
+                writer.string(None);
+                writer.string(None);
+                writer.i32(-1);
+                writer.bytes(None);
+                writer.u32(1); // 安全模式: None
+                writer.string(Some(SECURITY_POLICY_NONE));
+                writer.i32(2);
+                for (policy_id, token_type) in [("anonymous-policy", 0), ("username-policy", 1)] {
+                    writer.string(Some(policy_id));
+                    writer.u32(token_type);
+                    writer.string(None);
+                    writer.string(None);
+                    writer.string(None);
+                }
+                writer.string(None);
+                writer.u8(0);
+                writer.i32(-1);
+                writer.string(None);
+                writer.bytes(None);
+                writer.u32(0);
+            }
+            ids::ACTIVATE_SESSION_REQUEST => {
+                reader.string().unwrap();
+                reader.bytes().unwrap();
+                reader.array_len().unwrap();
+                reader.array_len().unwrap();
+                let (token_type, token) = reader.extension_object().unwrap();
+                let mut token = UaReader::new(&token);
+                let policy_id = token.string().unwrap().unwrap_or_default();
+                let accepted = match token_type {
+                    t if t == NodeId::numeric(0, ids::ANONYMOUS_IDENTITY_TOKEN) => policy_id == "anonymous-policy",
+                    t if t == NodeId::numeric(0, ids::USER_NAME_IDENTITY_TOKEN) => {
+                        policy_id == "username-policy"
+                            && token.string().unwrap().as_deref() == Some("operator")
+                            && token.bytes().unwrap().as_deref() == Some(b"secret".as_slice())
+                    }
+                    _ => false,
+                };
+                if accepted {
+                    write_response_header(writer, ids::ACTIVATE_SESSION_RESPONSE, 0);
+                    writer.bytes(None);
+                    writer.i32(0);
+                    writer.i32(0);
+                } else {
+                    write_response_header(writer, ids::SERVICE_FAULT, BAD_USER_ACCESS_DENIED);
+                }
+            }
+            ids::READ_REQUEST => {
+                reader.f64().unwrap();
+                reader.u32().unwrap();
+                let count = reader.array_len().unwrap();
+                write_response_header(writer, ids::READ_RESPONSE, 0);
+                writer.i32(count as i32);
+                for _ in 0..count {
+                    let node = reader.node_id().unwrap();
+                    reader.u32().unwrap();
+                    reader.string().unwrap();
+                    reader.qualified_name().unwrap();
+                    writer.data_value(&match space.get(&node) {
+                        Some(value) => DataValue { value: value.clone(), status: 0 },
+                        None => DataValue { value: Variant::Empty, status: BAD_NODE_ID_UNKNOWN },
+                    });
+                }
+                writer.i32(-1);
+            }
+            ids::WRITE_REQUEST => {
+                let count = reader.array_len().unwrap();
+                write_response_header(writer, ids::WRITE_RESPONSE, 0);
+                writer.i32(count as i32);
+                for _ in 0..count {
+                    let node = reader.node_id().unwrap();
+                    reader.u32().unwrap();
+                    reader.string().unwrap();
+                    let data = reader.data_value().unwrap();
+                    writer.u32(match space.get_mut(&node) {
+                        Some(current) if current.type_id() == data.value.type_id() => {
+                            *current = data.value;
+                            0
+                        }
+                        Some(_) => BAD_TYPE_MISMATCH,
+                        None => BAD_NODE_ID_UNKNOWN,
+                    });
+                }
+                writer.i32(-1);
+            }
+            ids::BROWSE_REQUEST => {
+                reader.node_id().unwrap();
+                reader.i64().unwrap();
+                reader.u32().unwrap();
+                reader.u32().unwrap();
+                let count = reader.array_len().unwrap();
+                write_response_header(writer, ids::BROWSE_RESPONSE, 0);
+                writer.i32(count as i32);
+                for _ in 0..count {
+                    let node = reader.node_id().unwrap();
+                    reader.u32().unwrap();
+                    reader.node_id().unwrap();
+                    reader.bool().unwrap();
+                    reader.u32().unwrap();
+                    reader.u32().unwrap();
+
+                    let mut children: Vec<(NodeId, String, u32)> = Vec::new();
+                    if node == NodeId::numeric(0, ids::OBJECTS_FOLDER) {
+                        children.push((tank(), "Tank".to_string(), 1));
+                    } else if node == tank() {
+                        for child in space.keys() {
+                            if let Identifier::String(id) = &child.identifier {
+                                children.push((child.clone(), id.trim_start_matches("Tank.").to_string(), 2));
+                            }
+                        }
+                    }
+                    writer.u32(0);
+                    writer.bytes(None);
+                    writer.i32(children.len() as i32);
+                    for (child, name, node_class) in children {
+                        writer.node_id(&NodeId::numeric(0, 47)); // HasComponent
+                        writer.bool(true);
+                        writer.node_id(&child);
+                        writer.u16(2);
+                        writer.string(Some(&name));
+                        writer.localized_text(&name);
+                        writer.u32(node_class);
+                        writer.node_id(&NodeId::null());
+                    }
+                }
+                writer.i32(-1);
+            }
+            ids::CLOSE_SESSION_REQUEST => write_response_header(writer, ids::CLOSE_SESSION_RESPONSE, 0),
+            other => panic!("替身服务器不支持的服务: {}", other),
+        }
+    }
+
+    fn config(port: u16, params: &[(&str, &str)]) -> PlcConnectionConfig {
+        PlcConnectionConfig {
+            id: "opcua_stand_in".to_string(),
+            name: "OPC UA 替身".to_string(),
+            protocol: PlcProtocol::OpcUa,
+            host: "127.0.0.1".to_string(),
+            port,
+            timeout_ms: 2000,
+            read_timeout_ms: 2000,
+            write_timeout_ms: 2000,
+            byte_order: "ABCD".to_string(),
+            zero_based_address: false,
+            retry_count: 0,
+            retry_interval_ms: 0,
+            protocol_params: params.iter().map(|(k, v)| (k.to_string(), serde_json::json!(v))).collect(),
+        }
+    }
+
+    #[test]
+    fn parses_node_id_strings() {
+        assert_eq!(NodeId::parse("ns=2;s=Tank.Level").unwrap(), tank_variable("Level"));
+        assert_eq!(NodeId::parse("i=2259").unwrap(), NodeId::numeric(0, 2259));
+
+        let guid = "ns=1;g=09087e75-8e5e-499b-954f-f2a9603db28a";
+        assert_eq!(NodeId::parse(guid).unwrap().to_string(), guid);
+
+        assert!(NodeId::parse("ns=x;i=1").is_err());
+        assert!(NodeId::parse("ns=2;s=").is_err());
+        assert!(NodeId::looks_like_node_id("ns=2;s=Tank"));
+        assert!(!NodeId::looks_like_node_id("Tank/Level"));
+    }
+
+    #[test]
+    fn converts_values_to_node_types() {
+        assert_eq!(plc_value_to_variant(&PlcValue::Float32(30.5), 11), Ok(Variant::Double(30.5)));
+        assert_eq!(plc_value_to_variant(&PlcValue::Int32(42), 5), Ok(Variant::UInt16(42)));
+        assert!(plc_value_to_variant(&PlcValue::Float32(1.5), 6).is_err());
+        assert!(plc_value_to_variant(&PlcValue::Int32(-1), 7).is_err());
+
+        assert!(matches!(variant_to_plc_value(&Variant::Double(12.5), PlcDataType::Float32), Ok(PlcValue::Float32(v)) if v == 12.5));
+        assert!(matches!(variant_to_plc_value(&Variant::UInt16(1), PlcDataType::Bool), Ok(PlcValue::Bool(true))));
+        assert!(variant_to_plc_value(&Variant::String("x".into()), PlcDataType::Int32).is_err());
+    }
+
+    #[tokio::test]
+    async fn reads_and_writes_against_stand_in_server() {
+        let port = start_stand_in_server().await;
+        let service = OpcUaPlcService::new();
+        let handle = service.connect(&config(port, &[])).await.unwrap();
+
+        assert_eq!(service.read_f32(&handle, "ns=2;s=Tank.Level").await.unwrap(), 12.5);
+        assert!(service.read_bool(&handle, "Tank/Running").await.unwrap());
+        assert_eq!(service.read_i32(&handle, "2:Tank/2:Count").await.unwrap(), 7);
+
+        // Float点位写入Double变量时按变量类型编码
+        service.write_f32(&handle, "ns=2;s=Tank.Level", 30.25).await.unwrap();
+        service.write_i32(&handle, "Tank/Count", 42).await.unwrap();
+
+        let request = |id: &str, address: &str, data_type| ReadRequest {
+            id: id.to_string(),
+            address: address.to_string(),
+            data_type,
+            array_length: None,
+        };
+        let results = service
+            .batch_read(&handle, &[
+                request("level", "ns=2;s=Tank.Level", PlcDataType::Float64),
+                request("count", "Tank/Count", PlcDataType::Int32),
+                request("name", "Tank/Name", PlcDataType::String),
+                request("missing", "ns=2;s=Tank.Missing", PlcDataType::Bool),
+                request("bad_path", "Tank/Missing", PlcDataType::Bool),
+            ])
+            .await
+            .unwrap();
+        assert!(matches!(results[0].value, Some(PlcValue::Float64(v)) if v == 30.25));
+        assert!(matches!(results[1].value, Some(PlcValue::Int32(42))));
+        assert!(matches!(&results[2].value, Some(PlcValue::String(name)) if name == "TK-101"));
+        assert!(results[3].error_message.as_deref().unwrap().contains("BadNodeIdUnknown"));
+        assert_eq!(results[3].failure_kind, Some(crate::models::FailureKind::Configuration));
+        assert!(!results[4].success);
+
+        // 非整数写入整数变量应被拒绝，且不影响会话
+        let writes = service
+            .batch_write(&handle, &[WriteRequest {
+                id: "count".to_string(),
+                address: "Tank/Count".to_string(),
+                value: PlcValue::Float32(1.5),
+            }])
+            .await
+            .unwrap();
+        assert!(!writes[0].success);
+        assert!(service.is_connected(&handle).await.unwrap());
+
+        let children = service.browse(&handle, "Tank").await.unwrap();
+        assert_eq!(children.len(), 4);
+        service.disconnect(&handle).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn authenticates_with_username_token() {
+        let port = start_stand_in_server().await;
+        let service = OpcUaPlcService::new();
+
+        let user = |password: &str, allow_plaintext: bool| {
+            let mut config = config(port, &[("auth_mode", "UserName"), ("username", "operator"), ("password", password)]);
+            config.protocol_params.insert("allow_plaintext_password".to_string(), serde_json::json!(allow_plaintext));
+            config
+        };
+        let handle = service.connect(&user("secret", true)).await.unwrap();
+        assert_eq!(service.read_i32(&handle, "ns=2;s=Tank.Count").await.unwrap(), 7);
+
+        let result = service.test_connection(&user("wrong", true)).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error_message.unwrap().contains("BadUserAccessDenied"));
+    }
+
+    #[tokio::test]
+    async fn refuses_plaintext_password_without_opt_in() {
+        let port = start_stand_in_server().await;
+        let service = OpcUaPlcService::new();
+
+        let config = config(port, &[("auth_mode", "UserName"), ("username", "operator"), ("password", "secret")]);
+        let error = service.connect(&config).await.unwrap_err();
+        assert!(error.to_string().contains("明文密码"), "{}", error);
+
+        let params = OpcUaConnectParams::from_config(&config);
+        assert!(!format!("{:?}", params).contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn rebuilds_a_session_left_idle_past_its_timeout() {
+        let server = start_stand_in_server_with(300.0, CHANNEL_LIFETIME_MS).await;
+        let service = OpcUaPlcService::new();
+        let handle = service.connect(&config(server.port, &[])).await.unwrap();
+        assert_eq!(service.read_i32(&handle, "Tank/Count").await.unwrap(), 7);
+
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(!service.is_connected(&handle).await.unwrap(), "空闲超时后会话已被服务器关闭");
+        assert_eq!(service.read_i32(&handle, "Tank/Count").await.unwrap(), 7);
+        assert!(service.is_connected(&handle).await.unwrap());
+        assert_eq!(server.sessions_created.load(Ordering::SeqCst), 2);
+        assert_eq!(service.get_connection_stats(&handle).await.unwrap().connection_errors, 0);
+    }
+
+    #[tokio::test]
+    async fn renews_the_security_token_before_it_expires() {
+        let server = start_stand_in_server_with(SESSION_TIMEOUT_MS, 600).await;
+        let service = OpcUaPlcService::new();
+        let handle = service.connect(&config(server.port, &[])).await.unwrap();
+
+        for _ in 0..10 {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            assert_eq!(service.read_f32(&handle, "ns=2;s=Tank.Level").await.unwrap(), 12.5);
+        }
+        assert!(server.token_renewals.load(Ordering::SeqCst) >= 2);
+        assert_eq!(server.sessions_created.load(Ordering::SeqCst), 1);
+        assert_eq!(service.get_connection_stats(&handle).await.unwrap().connection_errors, 0);
+    }
+
+    #[tokio::test]
+    async fn retries_once_when_the_server_drops_the_session() {
+        let server = start_stand_in_server_with(SESSION_TIMEOUT_MS, CHANNEL_LIFETIME_MS).await;
+        let service = OpcUaPlcService::new();
+        let handle = service.connect(&config(server.port, &[])).await.unwrap();
+
+        server.drop_sessions();
+        assert_eq!(service.read_i32(&handle, "Tank/Count").await.unwrap(), 7);
+        server.drop_sessions();
+        service.write_i32(&handle, "Tank/Count", 8).await.unwrap();
+        assert_eq!(service.read_i32(&handle, "Tank/Count").await.unwrap(), 8);
+        assert_eq!(server.sessions_created.load(Ordering::SeqCst), 3);
+        assert_eq!(service.get_connection_stats(&handle).await.unwrap().connection_errors, 2);
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! ## 业务作用
 //! 本模块是工厂测试系统中PLC通信的核心基础设施层实现，负责：
-//! - 提供统一的PLC通信接口，支持Modbus TCP协议
+//! - 提供统一的PLC通信接口，支持Modbus TCP与Modbus RTU（RS-485串口）协议，
+//!   西门子S7与OPC UA连接按句柄协议分派给 `S7PlcService` / `OpcUaPlcService`
 //! - 管理PLC连接池，实现连接复用和资源优化
 //! - 处理PLC数据的读写操作，支持多种数据类型
 //! - 提供连接状态监控和故障恢复机制
@@ -10,7 +11,7 @@
 //!
 //! ## 架构设计
 //! 采用分层架构设计：
-//! - **连接池层**: `ModbusTcpConnectionPool` 管理底层TCP连接
+//! - **连接池层**: `ModbusTcpConnectionPool` 管理底层TCP/串口连接（`open_modbus_context` 按协议建立传输）
 //! - **服务层**: `ModbusTcpPlcService` 提供高级业务接口
 //! - **全局管理**: 通过单例模式提供全局访问点
 //!
@@ -44,6 +45,11 @@ use crate::domain::services::{
     PlcDataType, PlcValue, ConnectionStats, ConnectionTestResult
 };
 use crate::utils::error::{AppError, AppResult};
+use crate::models::{FailureKind, ModbusMAreaAddressing};
+use crate::infrastructure::s7_communication::S7PlcService;
+use crate::infrastructure::opcua_communication::OpcUaPlcService;
+use crate::infrastructure::modbus_scheduler::{RequestPriority, RequestScheduler};
+use crate::infrastructure::modbus_capture::{frame_recorder, FrameOutcome, FrameResponse, ModbusRequestFrame};
 
 // 复用领域层定义的通信服务接口，避免重复定义造成类型不一致
 pub use crate::domain::services::plc_communication_service::IPlcCommunicationService;
@@ -100,7 +106,9 @@ pub fn set_global_plc_manager(mgr: Arc<PlcConnectionManager>) {
 /// - `Option<T>`: 表示可能存在或不存在的值，避免空指针异常
 /// - `cloned()`: 对Option内的Arc进行克隆，增加引用计数
 pub fn get_global_plc_manager() -> Option<Arc<PlcConnectionManager>> {
+    // 应用启动时注册在领域层，这里未单独设置时沿用领域层的管理器
     GLOBAL_PLC_MANAGER.get().cloned()
+        .or_else(crate::domain::services::plc_communication_service::get_global_plc_manager)
 }
 
 /// Modbus TCP连接池管理器
@@ -157,11 +165,23 @@ struct ModbusTcpConnection {
     /// **重要性**: 不同PLC厂商可能使用不同的字节序，影响数据解析正确性
     byte_order: crate::models::ByteOrder,
 
+    /// 64位字序与字符串编码参数
+    /// **业务含义**: 多寄存器数值/字符串的存放方式，来自连接的 `protocol_params`
+    data_settings: crate::models::ModbusDataSettings,
+
     /// 地址是否从0开始
     /// **业务含义**: 某些PLC地址从1开始，需要进行地址偏移转换
     /// **兼容性**: 支持不同PLC厂商的地址编码方式
     zero_based_address: bool,
 
+    /// 单次请求的应答超时
+    /// **业务含义**: 超时后返回 `ModbusTimeout`，与从站的异常应答区分开
+    request_timeout: Duration,
+
+    /// 请求调度器
+    /// **业务含义**: 测试、手动命令与监控共用连接时按优先级放行请求
+    scheduler: Arc<RequestScheduler>,
+
     /// 连接句柄
     /// **业务含义**: 连接的唯一标识符和元数据
     /// **生命周期**: 贯穿整个连接的生命周期，用于追踪和管理
@@ -193,6 +213,13 @@ struct ModbusTcpConnection {
     last_heartbeat: Arc<Mutex<DateTime<Utc>>>,
 }
 
+impl ModbusTcpConnection {
+    /// 按连接的地址基数与M区编址方式解析地址
+    fn parse_address(&self, address: &str) -> AppResult<ModbusAddress> {
+        ModbusAddress::parse_with(address, self.zero_based_address, self.data_settings.m_area_addressing)
+    }
+}
+
 /// 全局连接统计信息
 ///
 /// **业务作用**:
@@ -217,6 +244,80 @@ struct GlobalConnectionStats {
     successful_operations: u64,
 }
 
+/// 按连接配置建立Modbus客户端上下文
+///
+/// - **ModbusTcp**: 连接 `host:port`，从站ID取自 `protocol_params.slave_id`（默认1）
+/// - **ModbusRtu**: 打开 `protocol_params` 中的串口设备，按波特率/数据位/校验位/停止位配置
+///
+/// 两种传输得到的都是同一种 tokio_modbus 客户端上下文，地址解析与字节序处理完全一致
+pub async fn open_modbus_context(config: &PlcConnectionConfig) -> AppResult<tokio_modbus::client::Context> {
+    match config.protocol {
+        PlcProtocol::ModbusTcp => {
+            // 解析网络地址
+            let socket_addr = format!("{}:{}", config.host, config.port)
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| AppError::configuration_error(
+                    format!("无效的地址格式: {}:{}, 错误: {}", config.host, config.port, e)
+                ))?;
+
+            let slave_id = config.protocol_params
+                .get("slave_id")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(1) as u8;
+
+            timeout(
+                Duration::from_millis(config.timeout_ms),
+                tcp::connect_slave(socket_addr, Slave(slave_id))
+            ).await
+            .map_err(|_| AppError::timeout_error("PLC连接", "连接超时"))?
+            .map_err(|e| AppError::plc_communication_error(format!("Modbus连接失败: {}", e)))
+        }
+        PlcProtocol::ModbusRtu => {
+            let serial = crate::models::SerialPortSettings::from_protocol_params(&config.protocol_params)
+                .map_err(AppError::configuration_error)?;
+            let stream = open_serial_port(&serial, config.timeout_ms)?;
+            Ok(rtu::attach_slave(stream, Slave(serial.slave_id)))
+        }
+        other => Err(AppError::configuration_error(
+            format!("不支持的协议类型: {:?}", other)
+        )),
+    }
+}
+
+/// 按串口参数打开串口设备
+fn open_serial_port(settings: &crate::models::SerialPortSettings, timeout_ms: u64) -> AppResult<tokio_serial::SerialStream> {
+    use crate::models::SerialParity;
+    use tokio_serial::SerialPortBuilderExt;
+
+    let data_bits = match settings.data_bits {
+        5 => tokio_serial::DataBits::Five,
+        6 => tokio_serial::DataBits::Six,
+        7 => tokio_serial::DataBits::Seven,
+        _ => tokio_serial::DataBits::Eight,
+    };
+    let parity = match settings.parity {
+        SerialParity::None => tokio_serial::Parity::None,
+        SerialParity::Odd => tokio_serial::Parity::Odd,
+        SerialParity::Even => tokio_serial::Parity::Even,
+    };
+    let stop_bits = if settings.stop_bits == 2 {
+        tokio_serial::StopBits::Two
+    } else {
+        tokio_serial::StopBits::One
+    };
+
+    tokio_serial::new(&settings.device_path, settings.baud_rate)
+        .data_bits(data_bits)
+        .parity(parity)
+        .stop_bits(stop_bits)
+        .flow_control(tokio_serial::FlowControl::None)
+        .timeout(Duration::from_millis(timeout_ms))
+        .open_native_async()
+        .map_err(|e| AppError::plc_communication_error(
+            format!("打开串口失败: {} - {}", settings.device_path, e)
+        ))
+}
+
 impl ModbusTcpConnectionPool {
     /// 创建新的连接池实例
     ///
@@ -266,64 +367,30 @@ impl ModbusTcpConnectionPool {
         self.create_new_connection(config).await
     }
 
-    /// 创建新的Modbus TCP连接
+    /// 创建新的Modbus连接（TCP或RTU串口）
     ///
     /// **业务流程**:
-    /// 1. 验证协议类型是否支持
-    /// 2. 解析和验证网络地址
-    /// 3. 获取Modbus从站ID
-    /// 4. 建立TCP连接
-    /// 5. 执行初始心跳测试
-    /// 6. 创建连接对象并缓存
+    /// 1. 按协议类型建立传输（TCP连接或打开串口），见 `open_modbus_context`
+    /// 2. 执行初始心跳测试
+    /// 3. 创建连接对象并缓存
     ///
     /// **错误处理**: 每个步骤都有详细的错误处理和日志记录
     /// **超时控制**: 使用tokio::time::timeout防止连接挂起
     async fn create_new_connection(&self, config: &PlcConnectionConfig) -> AppResult<Arc<ModbusTcpConnection>> {
-        // 协议类型验证
-        // **业务规则**: 当前实现只支持Modbus TCP协议
-        if config.protocol != PlcProtocol::ModbusTcp {
-            return Err(AppError::configuration_error(
-                format!("不支持的协议类型: {:?}", config.protocol)
-            ));
-        }
-
-        // 解析网络地址
-        // **数据验证**: 确保地址格式正确，避免运行时错误
-        let socket_addr = format!("{}:{}", config.host, config.port)
-            .parse::<std::net::SocketAddr>()  // 解析为SocketAddr类型
-            .map_err(|e| AppError::configuration_error(
-                format!("无效的地址格式: {}:{}, 错误: {}", config.host, config.port, e)
-            ))?;
-
-        // 获取Modbus从站ID
-        // **协议参数**: 从配置的协议参数中提取从站ID
-        // **默认值**: 如果未配置，使用默认值1
-        // **Rust知识点**: 链式调用 - get() -> and_then() -> unwrap_or()
-        let slave_id = config.protocol_params
-            .get("slave_id")                    // 获取参数值
-            .and_then(|v| v.as_u64())          // 转换为u64类型
-            .unwrap_or(1) as u8;               // 默认值1，转换为u8
-
-        let slave = Slave(slave_id); // 创建Modbus从站对象
-
-        // 建立TCP连接
-        // **超时控制**: 防止连接操作无限期阻塞
-        // **异步操作**: 使用tokio的异步TCP连接
-        let mut context = timeout(
-            Duration::from_millis(config.timeout_ms), // 连接超时时间
-            tcp::connect_slave(socket_addr, slave)     // 异步连接操作
-        ).await
-        .map_err(|_| AppError::timeout_error("PLC连接", "连接超时"))? // 超时错误处理
-        .map_err(|e| AppError::plc_communication_error(
-            format!("Modbus连接失败: {}", e)
-        ))?; // 连接失败错误处理
+        // 建立传输
+        // **协议支持**: Modbus TCP 与 Modbus RTU（串口），其他协议返回配置错误
+        let mut context = open_modbus_context(config).await?;
 
         // 初次连接后立即验证心跳（读取线圈 03001 / 地址3000）
         // **连接验证**: 确保连接不仅建立成功，而且PLC响应正常
         // **业务规则**: 使用标准的心跳地址进行连接测试
         // **地址说明**: 3000对应Modbus地址03001（线圈地址）
-        if let Err(e) = context.read_coils(3000, 1).await {
-            log::warn!("初次心跳失败，连接视为无效: {}:{} - {}", config.host, config.port, e);
+        // **超时控制**: RTU 从站无应答时不会断开传输，需限定等待时间
+        let first_heartbeat = timeout(Duration::from_millis(config.timeout_ms), context.read_coils(3000, 1))
+            .await
+            .unwrap_or_else(|_| Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "心跳无应答").into()));
+        if let Err(e) = first_heartbeat {
+            log::warn!("初次心跳失败，连接视为无效: {} - {}", config.endpoint(), e);
             return Err(AppError::plc_communication_error(format!("初次心跳失败: {}", e)));
         }
 
@@ -352,6 +419,11 @@ impl ModbusTcpConnectionPool {
             connection_established_at: Utc::now(),     // 连接建立时间
             last_communication: Utc::now(),            // 最后通信时间
             connection_errors: 0,                      // 连接错误计数
+            queue_depth: 0,                            // 当前排队请求数
+            max_queue_depth: 0,                        // 排队深度峰值
+            average_queue_wait_ms: 0.0,                // 平均排队等待时间（毫秒）
+            max_queue_wait_ms: 0.0,                    // 最长排队等待时间（毫秒）
+            expired_requests: 0,                       // 排队过期请求数
         };
 
         // 创建连接对象
@@ -365,7 +437,13 @@ impl ModbusTcpConnectionPool {
             stats: Arc::new(Mutex::new(stats)),         // 统计信息
             last_heartbeat: Arc::new(Mutex::new(Utc::now())), // 最后心跳时间
             byte_order: byte_order_enum,                // 字节序配置
+            data_settings: crate::models::ModbusDataSettings::from_protocol_params(&config.protocol_params), // 多寄存器数据参数
             zero_based_address: config.zero_based_address, // 地址模式配置
+            request_timeout: Duration::from_millis(config.timeout_ms), // 请求应答超时
+            scheduler: Arc::new(RequestScheduler::new(   // 请求调度器
+                &crate::models::ModbusSchedulerSettings::from_protocol_params(&config.protocol_params),
+                Duration::from_millis(config.timeout_ms),
+            )),
         };
 
         // 存储连接和配置到连接池
@@ -416,11 +494,16 @@ impl ModbusTcpConnectionPool {
                     // 执行心跳读取操作
                     // **心跳机制**: 读取线圈地址03001（内部地址3000）
                     // **故障检测**: 通过读取操作的成功与否判断连接状态
+                    // 心跳按监控优先级排队；排队超期说明连接正忙于其他请求，跳过本轮
+                    let Ok(_permit) = conn.scheduler.acquire_as(RequestPriority::Monitoring, None).await else {
+                        continue;
+                    };
                     let heartbeat_ok = {
                         let mut ctx_guard = conn.context.lock().await; // 获取上下文互斥锁
                         if let Some(ctx) = ctx_guard.as_mut() {
                             // 尝试读取1个线圈，地址3000对应Modbus地址03001
-                            ctx.read_coils(3000, 1).await.is_ok()
+                            // 限定等待时间，避免RTU从站无应答时长期占用上下文锁
+                            matches!(timeout(interval, ctx.read_coils(3000, 1)).await, Ok(Ok(_)))
                         } else {
                             false // 上下文不存在，视为心跳失败
                         }
@@ -438,7 +521,7 @@ impl ModbusTcpConnectionPool {
                         if let Some(mgr) = crate::infrastructure::plc_communication::get_global_plc_manager() {
                             let mut mgr_conns = mgr.connections.write().await;
                             if let Some(mgr_conn) = mgr_conns.get_mut(&conn_id) {
-                                mgr_conn.state = crate::domain::impls::plc_connection_manager::PlcConnectionState::Connected;
+                                mgr.monitor().transition(mgr_conn, crate::domain::impls::plc_connection_manager::PlcConnectionState::Connected, Some("心跳恢复".to_string()));
                                 mgr_conn.last_heartbeat = Some(Utc::now());
                                 mgr_conn.error_message = None;           // 清除错误信息
                                 mgr_conn.heart_failure_count = 0;       // 重置失败计数
@@ -458,24 +541,19 @@ impl ModbusTcpConnectionPool {
                     if let Some(mgr) = crate::infrastructure::plc_communication::get_global_plc_manager() {
                         let mut mgr_conns = mgr.connections.write().await;
                         if let Some(mgr_conn) = mgr_conns.get_mut(&conn_id) {
-                            mgr_conn.state = crate::domain::impls::plc_connection_manager::PlcConnectionState::Reconnecting;
+                            mgr.monitor().transition(mgr_conn, crate::domain::impls::plc_connection_manager::PlcConnectionState::Reconnecting, Some("心跳失败".to_string()));
                             mgr_conn.error_message = Some("Heartbeat failed, reconnecting".to_string());
                         }
                     }
 
                     if let Some(cfg) = cfg_opt {
-                        if let Ok(socket_addr) = format!("{}:{}", cfg.host, cfg.port).parse::<std::net::SocketAddr>() {
-                            let slave_id = cfg.protocol_params
-                                .get("slave_id")
-                                .and_then(|v| v.as_u64())
-                                .unwrap_or(1) as u8;
-                            let slave = Slave(slave_id);
-                            if let Ok(new_ctx) = tokio_modbus::client::tcp::connect_slave(socket_addr, slave).await {
-                                let mut ctx_guard = conn.context.lock().await;
-                                *ctx_guard = Some(new_ctx);
-                                *conn.is_connected.lock().await = true;
-                                *conn.last_heartbeat.lock().await = Utc::now();
-                            }
+                        // 串口为独占设备，先释放旧的上下文再重新打开
+                        let mut ctx_guard = conn.context.lock().await;
+                        ctx_guard.take();
+                        if let Ok(new_ctx) = open_modbus_context(&cfg).await {
+                            *ctx_guard = Some(new_ctx);
+                            *conn.is_connected.lock().await = true;
+                            *conn.last_heartbeat.lock().await = Utc::now();
                         }
                     }
                 }
@@ -487,7 +565,7 @@ impl ModbusTcpConnectionPool {
         global_stats.total_connections += 1;
         global_stats.active_connections += 1;
 
-        log::info!("成功创建Modbus TCP连接: {} -> {}:{}", config.id, config.host, config.port);
+        log::info!("成功创建Modbus连接({:?}): {} -> {}", config.protocol, config.id, config.endpoint());
 
         Ok(Arc::new(connection))
     }
@@ -511,7 +589,7 @@ impl ModbusTcpConnectionPool {
             let mut global_stats = self.global_stats.lock().await;
             global_stats.active_connections = global_stats.active_connections.saturating_sub(1);
 
-            log::info!("已移除Modbus TCP连接: {}", connection_id);
+            log::info!("已移除Modbus连接: {}", connection_id);
         }
 
         configs.remove(connection_id);
@@ -538,6 +616,10 @@ impl ModbusTcpConnectionPool {
 pub struct ModbusTcpPlcService {
     /// 连接池
     pool: ModbusTcpConnectionPool,
+    /// 西门子S7连接（按句柄协议分派）
+    s7: S7PlcService,
+    /// OPC UA连接（按句柄协议分派）
+    opcua: OpcUaPlcService,
 
     /// 服务状态
     is_initialized: Arc<Mutex<bool>>,
@@ -550,21 +632,63 @@ pub struct ModbusTcpPlcService {
 }
 
 impl ModbusTcpPlcService {
-    /// 返回最后一次成功建立的默认连接地址，如 127.0.0.1:502
+    /// 返回最后一次成功建立的默认连接地址，如 127.0.0.1:502 或 /dev/ttyUSB0@9600
     pub async fn last_default_address(&self) -> Option<String> {
         let guard = self.last_default_config.lock().await;
-        guard.as_ref().map(|c| format!("{}:{}", c.host, c.port))
+        guard.as_ref().map(|c| c.endpoint())
     }
     /// 创建新的服务实例
     pub fn new() -> Self {
         Self {
             pool: ModbusTcpConnectionPool::new(),
+            s7: S7PlcService::new(),
+            opcua: OpcUaPlcService::new(),
             is_initialized: Arc::new(Mutex::new(false)),
             default_handles: Arc::new(Mutex::new(HashMap::new())),
             default_handle: Arc::new(Mutex::new(None)),
             last_default_config: Arc::new(Mutex::new(None)),
         }
     }
+
+    /// 非Modbus协议的连接交由对应的协议服务处理，Modbus TCP/RTU 返回 None
+    fn protocol_service(&self, protocol: PlcProtocol) -> Option<&dyn IPlcCommunicationService> {
+        match protocol {
+            PlcProtocol::SiemensS7 => Some(&self.s7),
+            PlcProtocol::OpcUa => Some(&self.opcua),
+            _ => None,
+        }
+    }
+
+    /// 寄存器位与带类型后缀的地址经批量路径读取（位提取与类型换算在其中完成）
+    async fn read_via_batch(&self, handle: &ConnectionHandle, address: &str, data_type: PlcDataType) -> AppResult<PlcValue> {
+        let request = ReadRequest { id: address.to_string(), address: address.to_string(), data_type, array_length: None };
+        let result = self.batch_read(handle, std::slice::from_ref(&request)).await?
+            .pop()
+            .ok_or_else(|| AppError::plc_communication_error(format!("地址 {} 未返回读取结果", address)))?;
+        match result.value {
+            Some(value) if result.success => Ok(value),
+            _ => Err(AppError::classified(
+                result.failure_kind.unwrap_or(FailureKind::Communication),
+                result.error_message.unwrap_or_else(|| format!("读取地址 {} 失败", address)),
+            )),
+        }
+    }
+
+    /// 寄存器位（读改写）与带类型后缀的地址经批量路径写入
+    async fn write_via_batch(&self, handle: &ConnectionHandle, address: &str, value: PlcValue) -> AppResult<()> {
+        let request = WriteRequest { id: address.to_string(), address: address.to_string(), value };
+        let result = self.batch_write(handle, std::slice::from_ref(&request)).await?
+            .pop()
+            .ok_or_else(|| AppError::plc_communication_error(format!("地址 {} 未返回写入结果", address)))?;
+        if result.success {
+            Ok(())
+        } else {
+            Err(AppError::classified(
+                result.failure_kind.unwrap_or(FailureKind::Communication),
+                result.error_message.unwrap_or_else(|| format!("写入地址 {} 失败", address)),
+            ))
+        }
+    }
 }
 
 impl Default for ModbusTcpPlcService {
@@ -609,6 +733,8 @@ impl BaseService for ModbusTcpPlcService {
                 log::warn!("关闭连接时出错 {}: {}", connection_id, e);
             }
         }
+        self.s7.shutdown().await?;
+        self.opcua.shutdown().await?;
 
         *is_initialized = false;
         Ok(())
@@ -631,21 +757,24 @@ impl BaseService for ModbusTcpPlcService {
 #[async_trait::async_trait]
 impl IPlcCommunicationService for ModbusTcpPlcService {
     async fn connect(&self, config: &PlcConnectionConfig) -> AppResult<ConnectionHandle> {
-        log::info!("连接到PLC: {} ({}:{})", config.name, config.host, config.port);
+        log::info!("连接到PLC: {} ({})", config.name, config.endpoint());
 
-        let connection = self.pool.get_or_create_connection(config).await?;
+        let handle = match self.protocol_service(config.protocol) {
+            Some(service) => service.connect(config).await?,
+            None => self.pool.get_or_create_connection(config).await?.handle.clone(),
+        };
 
         // 保存/更新连接句柄映射
         {
             // 1. 更新多连接句柄映射（始终保持最新）
             let mut map = self.default_handles.lock().await;
-            map.insert(config.id.clone(), connection.handle.clone());
+            map.insert(config.id.clone(), handle.clone());
 
             // 2. 仅当当前还没有默认句柄时，才设置向后兼容的 default_handle，
             //    避免后续新的连接（如手动测试用连接）覆盖业务逻辑正在使用的默认连接。
             let mut guard = self.default_handle.lock().await;
             if guard.is_none() {
-                *guard = Some(connection.handle.clone());
+                *guard = Some(handle.clone());
 
                 // 同步记录最后一次默认连接配置，便于日志输出
                 let mut cfg_guard = self.last_default_config.lock().await;
@@ -653,22 +782,49 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
             }
         }
 
-        Ok(connection.handle.clone())
+        Ok(handle)
     }
 
     async fn disconnect(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.disconnect(handle).await;
+        }
         log::info!("断开PLC连接: {}", handle.connection_id);
 
         self.pool.remove_connection(&handle.connection_id).await
     }
 
     async fn is_connected(&self, handle: &ConnectionHandle) -> AppResult<bool> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.is_connected(handle).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let is_connected = *connection.is_connected.lock().await;
         Ok(is_connected)
     }
 
+    async fn probe(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.probe(handle).await;
+        }
+        let connection = self.pool.get_connection(handle).await?;
+        // 排队超期说明连接正忙，链路状态由正在执行的请求上报
+        let Ok(_permit) = connection.scheduler.acquire_as(RequestPriority::Monitoring, None).await else {
+            return Ok(());
+        };
+        let mut context_guard = connection.context.lock().await;
+        let context = context_guard.as_mut().ok_or_else(|| AppError::plc_communication_error("连接已断开"))?;
+        // 读取心跳线圈 03001；从站异常应答同样说明链路正常
+        match modbus_call(&connection, ModbusRequestFrame::read(FC_READ_COILS, 3000, 1), "03001", context.read_coils(3000, 1)).await {
+            Ok(_) | Err(AppError::ModbusException { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     async fn read_bool(&self, handle: &ConnectionHandle, address: &str) -> AppResult<bool> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.read_bool(handle, address).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let start_time = Utc::now();
 
@@ -681,11 +837,19 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
         };
 
         // 解析Modbus地址
-        let (register_type, offset) = parse_modbus_address_ex(address, connection.zero_based_address)?;
+        let parsed = connection.parse_address(address)?;
+        if parsed.bit.is_some() {
+            return match self.read_via_batch(handle, address, PlcDataType::Bool).await? {
+                PlcValue::Bool(value) => Ok(value),
+                other => Err(AppError::plc_communication_error(format!("地址 {} 返回了非布尔值: {:?}", address, other))),
+            };
+        }
+        let (register_type, offset) = (parsed.register_type, parsed.offset);
 
         //log::info!("🔍 [PLC_READ_BOOL] 开始读取布尔值: PLC={}({}:{}), 地址={}, 类型={:?}, 偏移={}",
                    //plc_name, plc_host, plc_port, address, register_type, offset);
 
+        let _permit = connection.scheduler.acquire(false).await?;
         let mut context_guard = connection.context.lock().await;
         let context = context_guard.as_mut()
             .ok_or_else(|| {
@@ -694,46 +858,12 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
                 AppError::plc_communication_error("连接已断开".to_string())
             })?;
 
-        let result = match register_type {
+        let response = match register_type {
             ModbusRegisterType::Coil => {
-                match context.read_coils(offset, 1).await {
-                    Ok(Ok(values)) => {
-                        let value = values.first().copied().unwrap_or(false);
-                        //log::info!("✅ [PLC_READ_BOOL] 读取线圈成功: PLC={}, 地址={}, 值={}",
-                                  //plc_name, address, value);
-                        value
-                    },
-                    Ok(Err(exception)) => {
-                        log::error!("❌ [PLC_READ_BOOL] Modbus异常: PLC={}, 地址={}, 异常={:?}",
-                                   plc_name, address, exception);
-                        return Err(AppError::plc_communication_error(format!("Modbus异常: {:?}", exception)));
-                    },
-                    Err(e) => {
-                        log::error!("❌ [PLC_READ_BOOL] 读取线圈失败: PLC={}, 地址={}, 错误={:?}",
-                                   plc_name, address, e);
-                        return Err(AppError::plc_communication_error(format!("读取线圈失败: {:?}", e)));
-                    },
-                }
+                modbus_call(&connection, ModbusRequestFrame::read(FC_READ_COILS, offset, 1), address, context.read_coils(offset, 1)).await
             },
             ModbusRegisterType::DiscreteInput => {
-                match context.read_discrete_inputs(offset, 1).await {
-                    Ok(Ok(values)) => {
-                        let value = values.first().copied().unwrap_or(false);
-                        //log::info!("✅ [PLC_READ_BOOL] 读取离散输入成功: PLC={}, 地址={}, 值={}",
-                                  //plc_name, address, value);
-                        value
-                    },
-                    Ok(Err(exception)) => {
-                        log::error!("❌ [PLC_READ_BOOL] Modbus异常: PLC={}, 地址={}, 异常={:?}",
-                                   plc_name, address, exception);
-                        return Err(AppError::plc_communication_error(format!("Modbus异常: {:?}", exception)));
-                    },
-                    Err(e) => {
-                        log::error!("❌ [PLC_READ_BOOL] 读取离散输入失败: PLC={}, 地址={}, 错误={:?}",
-                                   plc_name, address, e);
-                        return Err(AppError::plc_communication_error(format!("读取离散输入失败: {:?}", e)));
-                    },
-                }
+                modbus_call(&connection, ModbusRequestFrame::read(FC_READ_DISCRETE_INPUTS, offset, 1), address, context.read_discrete_inputs(offset, 1)).await
             },
             _ => {
                 log::error!("❌ [PLC_READ_BOOL] 无效的布尔型地址: PLC={}, 地址={}, 类型={:?}",
@@ -743,6 +873,13 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
                 ));
             },
         };
+        let result = match response {
+            Ok(values) => values.first().copied().unwrap_or(false),
+            Err(e) => {
+                log::error!("❌ [PLC_READ_BOOL] 读取失败: PLC={}, 地址={}, 错误={}", plc_name, address, e);
+                return Err(e);
+            },
+        };
 
         // 更新统计信息
         update_read_stats(&connection.stats, start_time).await;
@@ -751,6 +888,9 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
     }
 
     async fn write_bool(&self, handle: &ConnectionHandle, address: &str, value: bool) -> AppResult<()> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.write_bool(handle, address, value).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let start_time = Utc::now();
 
@@ -762,12 +902,18 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
             config.name.clone()
         };
 
-        // 解析Modbus地址
-        let (register_type, offset) = parse_modbus_address_ex(address, connection.zero_based_address)?;
+        // 解析Modbus地址；寄存器位通过读改写完成
+        let parsed = connection.parse_address(address)?;
+        if parsed.bit.is_some() {
+            log::info!("🔍 [PLC_WRITE_BOOL] 寄存器位读改写: PLC={}, 地址={}, 值={}", plc_name, address, value);
+            return self.write_via_batch(handle, address, PlcValue::Bool(value)).await;
+        }
+        let (register_type, offset) = (parsed.register_type, parsed.offset);
 
         log::info!("🔍 [PLC_WRITE_BOOL] 开始写入布尔值: PLC={}, 地址={}, 类型={:?}, 偏移={}, 值={}",
                    plc_name, address, register_type, offset, value);
 
+        let _permit = connection.scheduler.acquire(true).await?;
         let mut context_guard = connection.context.lock().await;
         let context = context_guard.as_mut()
             .ok_or_else(|| {
@@ -778,15 +924,15 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
 
         match register_type {
             ModbusRegisterType::Coil => {
-                match context.write_single_coil(offset, value).await {
-                    Ok(_) => {
+                match modbus_call(&connection, ModbusRequestFrame::write_bits(FC_WRITE_SINGLE_COIL, offset, &[value]), address, context.write_single_coil(offset, value)).await {
+                    Ok(()) => {
                         log::info!("✅ [PLC_WRITE_BOOL] 写入线圈成功: PLC={}, 地址={}, 值={}",
                                   plc_name, address, value);
                     },
                     Err(e) => {
                         log::error!("❌ [PLC_WRITE_BOOL] 写入线圈失败: PLC={}, 地址={}, 值={}, 错误={}",
                                    plc_name, address, value, e);
-                        return Err(AppError::plc_communication_error(format!("写入线圈失败: {}", e)));
+                        return Err(e);
                     }
                 }
             },
@@ -806,30 +952,33 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
     }
 
     async fn read_f32(&self, handle: &ConnectionHandle, address: &str) -> AppResult<f32> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.read_f32(handle, address).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let start_time = Utc::now();
 
-        // 解析Modbus地址
-        let (register_type, offset) = parse_modbus_address_ex(address, connection.zero_based_address)?;
+        // 解析Modbus地址；寄存器位与类型后缀交由批量路径换算
+        let parsed = connection.parse_address(address)?;
+        if !parsed.is_plain() {
+            return match self.read_via_batch(handle, address, PlcDataType::Float32).await? {
+                PlcValue::Float32(value) => Ok(value),
+                other => Err(AppError::plc_communication_error(format!("地址 {} 返回了非预期的值: {:?}", address, other))),
+            };
+        }
+        let (register_type, offset) = (parsed.register_type, parsed.offset);
 
+        let _permit = connection.scheduler.acquire(false).await?;
         let mut context_guard = connection.context.lock().await;
         let context = context_guard.as_mut()
             .ok_or_else(|| AppError::plc_communication_error("连接已断开".to_string()))?;
 
         let registers = match register_type {
             ModbusRegisterType::HoldingRegister => {
-                match context.read_holding_registers(offset, 2).await {
-                    Ok(Ok(regs)) => regs,
-                    Ok(Err(exception)) => return Err(AppError::plc_communication_error(format!("Modbus异常: {:?}", exception))),
-                    Err(e) => return Err(AppError::plc_communication_error(format!("读取保持寄存器失败: {:?}", e))),
-                }
+                modbus_call(&connection, ModbusRequestFrame::read(FC_READ_HOLDING_REGISTERS, offset, 2), address, context.read_holding_registers(offset, 2)).await?
             },
             ModbusRegisterType::InputRegister => {
-                match context.read_input_registers(offset, 2).await {
-                    Ok(Ok(regs)) => regs,
-                    Ok(Err(exception)) => return Err(AppError::plc_communication_error(format!("Modbus异常: {:?}", exception))),
-                    Err(e) => return Err(AppError::plc_communication_error(format!("读取输入寄存器失败: {:?}", e))),
-                }
+                modbus_call(&connection, ModbusRequestFrame::read(FC_READ_INPUT_REGISTERS, offset, 2), address, context.read_input_registers(offset, 2)).await?
             },
             _ => return Err(AppError::plc_communication_error(
                 format!("地址 {} 不是有效的32位寄存器地址", address)
@@ -852,12 +1001,20 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
     }
 
     async fn write_f32(&self, handle: &ConnectionHandle, address: &str, value: f32) -> AppResult<()> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.write_f32(handle, address, value).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let start_time = Utc::now();
 
-        // 解析Modbus地址
-        let (register_type, offset) = parse_modbus_address_ex(address, connection.zero_based_address)?;
+        // 解析Modbus地址；寄存器位与类型后缀交由批量路径换算
+        let parsed = connection.parse_address(address)?;
+        if !parsed.is_plain() {
+            return self.write_via_batch(handle, address, PlcValue::Float32(value)).await;
+        }
+        let (register_type, offset) = (parsed.register_type, parsed.offset);
 
+        let _permit = connection.scheduler.acquire(true).await?;
         let mut context_guard = connection.context.lock().await;
         let context = context_guard.as_mut()
             .ok_or_else(|| AppError::plc_communication_error("连接已断开".to_string()))?;
@@ -866,8 +1023,7 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
             ModbusRegisterType::HoldingRegister => {
                 let (reg1, reg2) = ByteOrderConverter::float_to_registers(value, connection.byte_order);
                 let registers = [reg1, reg2];
-                context.write_multiple_registers(offset, &registers).await
-                    .map_err(|e| AppError::plc_communication_error(format!("写入保持寄存器失败: {}", e)))?;
+                modbus_call(&connection, ModbusRequestFrame::write_words(FC_WRITE_MULTIPLE_REGISTERS, offset, &registers), address, context.write_multiple_registers(offset, &registers)).await?;
             },
             _ => return Err(AppError::plc_communication_error(
                 format!("地址 {} 不是有效的可写32位寄存器地址", address)
@@ -881,30 +1037,33 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
     }
 
     async fn read_i32(&self, handle: &ConnectionHandle, address: &str) -> AppResult<i32> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.read_i32(handle, address).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let start_time = Utc::now();
 
-        // 解析Modbus地址
-        let (register_type, offset) = parse_modbus_address_ex(address, connection.zero_based_address)?;
+        // 解析Modbus地址；寄存器位与类型后缀交由批量路径换算
+        let parsed = connection.parse_address(address)?;
+        if !parsed.is_plain() {
+            return match self.read_via_batch(handle, address, PlcDataType::Int32).await? {
+                PlcValue::Int32(value) => Ok(value),
+                other => Err(AppError::plc_communication_error(format!("地址 {} 返回了非预期的值: {:?}", address, other))),
+            };
+        }
+        let (register_type, offset) = (parsed.register_type, parsed.offset);
 
+        let _permit = connection.scheduler.acquire(false).await?;
         let mut context_guard = connection.context.lock().await;
         let context = context_guard.as_mut()
             .ok_or_else(|| AppError::plc_communication_error("连接已断开".to_string()))?;
 
         let registers = match register_type {
             ModbusRegisterType::HoldingRegister => {
-                match context.read_holding_registers(offset, 2).await {
-                    Ok(Ok(regs)) => regs,
-                    Ok(Err(exception)) => return Err(AppError::plc_communication_error(format!("Modbus异常: {:?}", exception))),
-                    Err(e) => return Err(AppError::plc_communication_error(format!("读取保持寄存器失败: {:?}", e))),
-                }
+                modbus_call(&connection, ModbusRequestFrame::read(FC_READ_HOLDING_REGISTERS, offset, 2), address, context.read_holding_registers(offset, 2)).await?
             },
             ModbusRegisterType::InputRegister => {
-                match context.read_input_registers(offset, 2).await {
-                    Ok(Ok(regs)) => regs,
-                    Ok(Err(exception)) => return Err(AppError::plc_communication_error(format!("Modbus异常: {:?}", exception))),
-                    Err(e) => return Err(AppError::plc_communication_error(format!("读取输入寄存器失败: {:?}", e))),
-                }
+                modbus_call(&connection, ModbusRequestFrame::read(FC_READ_INPUT_REGISTERS, offset, 2), address, context.read_input_registers(offset, 2)).await?
             },
             _ => return Err(AppError::plc_communication_error(
                 format!("地址 {} 不是有效的32位寄存器地址", address)
@@ -927,12 +1086,20 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
     }
 
     async fn write_i32(&self, handle: &ConnectionHandle, address: &str, value: i32) -> AppResult<()> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.write_i32(handle, address, value).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
         let start_time = Utc::now();
 
-        // 解析Modbus地址
-        let (register_type, offset) = parse_modbus_address_ex(address, connection.zero_based_address)?;
+        // 解析Modbus地址；寄存器位与类型后缀交由批量路径换算
+        let parsed = connection.parse_address(address)?;
+        if !parsed.is_plain() {
+            return self.write_via_batch(handle, address, PlcValue::Int32(value)).await;
+        }
+        let (register_type, offset) = (parsed.register_type, parsed.offset);
 
+        let _permit = connection.scheduler.acquire(true).await?;
         let mut context_guard = connection.context.lock().await;
         let context = context_guard.as_mut()
             .ok_or_else(|| AppError::plc_communication_error("连接已断开".to_string()))?;
@@ -941,8 +1108,7 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
             ModbusRegisterType::HoldingRegister => {
                 let (reg1, reg2) = ByteOrderConverter::i32_to_registers(value, connection.byte_order);
                 let registers = [reg1, reg2];
-                context.write_multiple_registers(offset, &registers).await
-                    .map_err(|e| AppError::plc_communication_error(format!("写入保持寄存器失败: {}", e)))?;
+                modbus_call(&connection, ModbusRequestFrame::write_words(FC_WRITE_MULTIPLE_REGISTERS, offset, &registers), address, context.write_multiple_registers(offset, &registers)).await?;
             },
             _ => return Err(AppError::plc_communication_error(
                 format!("地址 {} 不是有效的可写32位寄存器地址", address)
@@ -955,155 +1121,164 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
         Ok(())
     }
 
+    /// 批量读取：按寄存器类型与地址连续性合并为 FC1/2/3/4 块请求，再按请求拆分结果
+    ///
+    /// 字符串与字节数组按 `array_length`（字符数/字节数）确定读取的寄存器数量
     async fn batch_read(&self, handle: &ConnectionHandle, requests: &[ReadRequest]) -> AppResult<Vec<ReadResult>> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.batch_read(handle, requests).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
-        let mut results = Vec::with_capacity(requests.len());
+        let codec = ModbusValueCodec::new(connection.byte_order, connection.data_settings.clone());
+        let mut results: Vec<Option<ReadResult>> = (0..requests.len()).map(|_| None).collect();
+
+        // 每个请求实际按哪种类型解码（寄存器位为 Bool，带类型后缀时为后缀类型）
+        let mut layouts: Vec<PlcDataType> = requests.iter().map(|r| r.data_type).collect();
+        let mut items = Vec::with_capacity(requests.len());
+        for (index, request) in requests.iter().enumerate() {
+            let planned = connection.parse_address(&request.address)
+                .and_then(|address| {
+                    codec.plan_read(&address, request)
+                        .map(|(data_type, width)| {
+                            layouts[index] = data_type;
+                            (address.register_type, BlockItem { index, offset: address.offset, width, bit: address.bit })
+                        })
+                        .map_err(|e| AppError::validation_error(format!("地址 {} {}", request.address, e)))
+                });
+            match planned {
+                Ok(item) => items.push(item),
+                Err(e) => results[index] = Some(failed_read(request, &e, 0)),
+            }
+        }
 
-        for request in requests {
+        let mut blocks: std::collections::VecDeque<ModbusBlock> = plan_modbus_blocks(items, BLOCK_MAX_GAP, false).into();
+        while let Some(block) = blocks.pop_front() {
             let start_time = Utc::now();
-            let result = match request.data_type {
-                PlcDataType::Bool => {
-                    match self.read_bool(handle, &request.address).await {
-                        Ok(value) => ReadResult {
-                            request_id: request.id.clone(),
-                            success: true,
-                            value: Some(PlcValue::Bool(value)),
-                            error_message: None,
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                        Err(e) => ReadResult {
-                            request_id: request.id.clone(),
-                            success: false,
-                            value: None,
-                            error_message: Some(e.to_string()),
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
+            let data = read_block(&connection, &block).await;
+            let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+            match &data {
+                Ok(_) => update_read_stats(&connection.stats, start_time).await,
+                // 合并块可能跨越从站未定义的空隙地址，异常应答时拆回单点逐个读取，只让真正出错的点失败
+                Err(e @ AppError::ModbusException { .. }) if block.items.len() > 1 => {
+                    log::warn!("⚠️ [PLC_BATCH_READ] 块读取异常应答，拆分为 {} 个单点重读: {:?} 起始{} 数量{} - {}",
+                               block.items.len(), block.register_type, block.start, block.count, e);
+                    for item in block.items.iter().rev() {
+                        blocks.push_front(ModbusBlock::single(block.register_type, *item));
                     }
-                },
-                PlcDataType::Float32 => {
-                    match self.read_f32(handle, &request.address).await {
-                        Ok(value) => ReadResult {
-                            request_id: request.id.clone(),
-                            success: true,
-                            value: Some(PlcValue::Float32(value)),
-                            error_message: None,
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                        Err(e) => ReadResult {
-                            request_id: request.id.clone(),
-                            success: false,
-                            value: None,
-                            error_message: Some(e.to_string()),
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                    }
-                },
-                PlcDataType::Int32 => {
-                    match self.read_i32(handle, &request.address).await {
-                        Ok(value) => ReadResult {
-                            request_id: request.id.clone(),
-                            success: true,
-                            value: Some(PlcValue::Int32(value)),
-                            error_message: None,
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                        Err(e) => ReadResult {
-                            request_id: request.id.clone(),
-                            success: false,
-                            value: None,
-                            error_message: Some(e.to_string()),
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                    }
-                },
-                _ => ReadResult {
-                    request_id: request.id.clone(),
-                    success: false,
-                    value: None,
-                    error_message: Some(format!("不支持的数据类型: {:?}", request.data_type)),
-                    execution_time_ms: 0,
-                },
-            };
+                    continue;
+                }
+                Err(e) => log::warn!("⚠️ [PLC_BATCH_READ] 块读取失败: {:?} 起始{} 数量{} - {}",
+                                     block.register_type, block.start, block.count, e),
+            }
 
-            results.push(result);
+            for item in &block.items {
+                let request = &requests[item.index];
+                let value = data.as_ref()
+                    .map_err(Clone::clone)
+                    .and_then(|data| {
+                        codec.decode(data, block.start, item, layouts[item.index], request.array_length)
+                            .and_then(|value| convert_value(value, request.data_type))
+                            .map_err(|e| AppError::validation_error(format!("地址 {} {}", request.address, e)))
+                    });
+                results[item.index] = Some(match value {
+                    Ok(value) => ReadResult {
+                        request_id: request.id.clone(),
+                        success: true,
+                        value: Some(value),
+                        error_message: None,
+                        failure_kind: None,
+                        execution_time_ms: elapsed,
+                    },
+                    Err(e) => failed_read(request, &e, elapsed),
+                });
+            }
         }
 
-        Ok(results)
+        Ok(results.into_iter().map(|r| r.expect("每个读取请求都已生成结果")).collect())
     }
 
+    /// 批量写入：严格连续的同类地址合并为 FC15/16 块请求，单个线圈仍使用 FC5；
+    /// 寄存器位在块写入之后按请求顺序逐个读改写
     async fn batch_write(&self, handle: &ConnectionHandle, requests: &[WriteRequest]) -> AppResult<Vec<WriteResult>> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.batch_write(handle, requests).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
-        let mut results = Vec::with_capacity(requests.len());
-
-        for request in requests {
-            let start_time = Utc::now();
-            let result = match &request.value {
-                PlcValue::Bool(value) => {
-                    match self.write_bool(handle, &request.address, *value).await {
-                        Ok(_) => WriteResult {
-                            request_id: request.id.clone(),
-                            success: true,
-                            error_message: None,
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                        Err(e) => WriteResult {
-                            request_id: request.id.clone(),
-                            success: false,
-                            error_message: Some(e.to_string()),
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                    }
-                },
-                PlcValue::Float32(value) => {
-                    match self.write_f32(handle, &request.address, *value).await {
-                        Ok(_) => WriteResult {
-                            request_id: request.id.clone(),
-                            success: true,
-                            error_message: None,
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                        Err(e) => WriteResult {
-                            request_id: request.id.clone(),
-                            success: false,
-                            error_message: Some(e.to_string()),
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                    }
-                },
-                PlcValue::Int32(value) => {
-                    match self.write_i32(handle, &request.address, *value).await {
-                        Ok(_) => WriteResult {
-                            request_id: request.id.clone(),
-                            success: true,
-                            error_message: None,
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
-                        Err(e) => WriteResult {
-                            request_id: request.id.clone(),
-                            success: false,
-                            error_message: Some(e.to_string()),
-                            execution_time_ms: Utc::now().signed_duration_since(start_time).num_milliseconds() as u64,
-                        },
+        let codec = ModbusValueCodec::new(connection.byte_order, connection.data_settings.clone());
+        let mut results: Vec<Option<WriteResult>> = (0..requests.len()).map(|_| None).collect();
+        let mut payloads: HashMap<usize, BlockData> = HashMap::new();
+
+        // 按请求顺序排列写入步骤：相邻且首尾相接的块写入合并为一次请求，寄存器位写入单独执行
+        let mut steps = Vec::new();
+        let mut items = Vec::new();
+        for (index, request) in requests.iter().enumerate() {
+            let planned = connection.parse_address(&request.address)
+                .and_then(|address| {
+                    codec.plan_write(&address, &request.value)
+                        .map(|write| (address, write))
+                        .map_err(|e| AppError::validation_error(format!("地址 {} {}", request.address, e)))
+                });
+            match planned {
+                Ok((address, PlannedWrite::Block(data))) => {
+                    items.push((address.register_type, BlockItem { index, offset: address.offset, width: data.len() as u16, bit: None }));
+                    payloads.insert(index, data);
+                }
+                Ok((_, PlannedWrite::RegisterBit { offset, bit, value })) => {
+                    steps.extend(plan_modbus_blocks(std::mem::take(&mut items), 0, true).into_iter().map(WriteStep::Block));
+                    steps.push(WriteStep::RegisterBit { index, offset, bit, value });
+                }
+                Err(e) => results[index] = Some(write_result(request, Err(e), 0)),
+            }
+        }
+        steps.extend(plan_modbus_blocks(items, 0, true).into_iter().map(WriteStep::Block));
+
+        for step in steps {
+            let block = match step {
+                WriteStep::Block(block) => block,
+                WriteStep::RegisterBit { index, offset, bit, value } => {
+                    let start_time = Utc::now();
+                    let outcome = write_register_bit(&connection, &requests[index].address, offset, bit, value).await;
+                    let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+                    match &outcome {
+                        Ok(()) => update_write_stats(&connection.stats, start_time).await,
+                        Err(e) => log::warn!("⚠️ [PLC_BATCH_WRITE] 寄存器位写入失败: 地址={} - {}", requests[index].address, e),
                     }
-                },
-                _ => WriteResult {
-                    request_id: request.id.clone(),
-                    success: false,
-                    error_message: Some(format!("不支持的数据类型: {:?}", request.value)),
-                    execution_time_ms: 0,
-                },
+                    results[index] = Some(write_result(&requests[index], outcome, elapsed));
+                    continue;
+                }
             };
+            let mut payload = match block.register_type {
+                ModbusRegisterType::Coil => BlockData::Bits(Vec::with_capacity(block.count as usize)),
+                _ => BlockData::Words(Vec::with_capacity(block.count as usize)),
+            };
+            for item in &block.items {
+                payload.extend(&payloads[&item.index]);
+            }
 
-            results.push(result);
+            let start_time = Utc::now();
+            let outcome = write_block(&connection, &block, &payload).await;
+            let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+            match &outcome {
+                Ok(()) => update_write_stats(&connection.stats, start_time).await,
+                Err(e) => log::warn!("⚠️ [PLC_BATCH_WRITE] 块写入失败: {:?} 起始{} 数量{} - {}",
+                                     block.register_type, block.start, block.count, e),
+            }
+            for item in &block.items {
+                results[item.index] = Some(write_result(&requests[item.index], outcome.clone(), elapsed));
+            }
         }
 
-        Ok(results)
+        Ok(results.into_iter().map(|r| r.expect("每个写入请求都已生成结果")).collect())
     }
 
     async fn get_connection_stats(&self, handle: &ConnectionHandle) -> AppResult<ConnectionStats> {
+        if let Some(service) = self.protocol_service(handle.protocol) {
+            return service.get_connection_stats(handle).await;
+        }
         let connection = self.pool.get_connection(handle).await?;
-        let stats = connection.stats.lock().await;
-        Ok(stats.clone())
+        let mut stats = connection.stats.lock().await.clone();
+        connection.scheduler.fill_stats(&mut stats);
+        Ok(stats)
     }
 
     async fn default_handle_by_id(&self, connection_id: &str) -> Option<ConnectionHandle> {
@@ -1117,6 +1292,9 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
     }
 
     async fn test_connection(&self, config: &PlcConnectionConfig) -> AppResult<ConnectionTestResult> {
+        if let Some(service) = self.protocol_service(config.protocol) {
+            return service.test_connection(config).await;
+        }
         let start_time = Utc::now();
 
         // 尝试建立临时连接进行测试
@@ -1139,8 +1317,8 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
                     success: test_result,
                     connection_time_ms: connection_time,
                     error_message: if test_result { None } else { Some("测试读取失败".to_string()) },
-                    protocol_info: Some("Modbus TCP".to_string()),
-                    device_info: Some(format!("{}:{}", config.host, config.port)),
+                    protocol_info: Some(protocol_label(config.protocol).to_string()),
+                    device_info: Some(config.endpoint()),
                 })
             },
             Err(e) => {
@@ -1149,16 +1327,26 @@ impl IPlcCommunicationService for ModbusTcpPlcService {
                     success: false,
                     connection_time_ms: connection_time,
                     error_message: Some(e.to_string()),
-                    protocol_info: Some("Modbus TCP".to_string()),
-                    device_info: Some(format!("{}:{}", config.host, config.port)),
+                    protocol_info: Some(protocol_label(config.protocol).to_string()),
+                    device_info: Some(config.endpoint()),
                 })
             }
         }
     }
 }
 
+/// 连接测试结果中的协议描述
+fn protocol_label(protocol: PlcProtocol) -> &'static str {
+    match protocol {
+        PlcProtocol::ModbusRtu => "Modbus RTU",
+        PlcProtocol::SiemensS7 => "Siemens S7",
+        PlcProtocol::OpcUa => "OPC UA",
+        _ => "Modbus TCP",
+    }
+}
+
 /// Modbus寄存器类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ModbusRegisterType {
     Coil,           // 0x 线圈
     DiscreteInput,  // 1x 离散输入
@@ -1166,66 +1354,756 @@ pub enum ModbusRegisterType {
     HoldingRegister,// 4x 保持寄存器
 }
 
-/// 解析Modbus地址
+/// 解析后的Modbus地址
 ///
 /// 支持的格式：
-/// - 0xxxx: 线圈 (Coil)
-/// - 1xxxx: 离散输入 (Discrete Input)
-/// - 3xxxx: 输入寄存器 (Input Register)
-/// - 4xxxx: 保持寄存器 (Holding Register)
-pub fn parse_modbus_address_ex(address: &str, zero_based: bool) -> AppResult<(ModbusRegisterType, u16)> {
-    if address.is_empty() {
-        return Err(AppError::validation_error("地址不能为空".to_string()));
+/// - 0xxxx / 1xxxx / 3xxxx / 4xxxx：线圈、离散输入、输入寄存器、保持寄存器（也可写作 `4X0001`）
+/// - `40001.3`：寄存器中的第3位（0~15，0为最低位），仅适用于输入/保持寄存器
+/// - `40001:F` / `:D` / `:L`：声明寄存器中存放的是 Float32 / Float64 / Int32
+/// - `%MWn` / `%MDn` / `%MXn.b`：M区，映射到保持寄存器，编址方式由 [`ModbusMAreaAddressing`] 决定：
+///   - 按字节编址（默认，西门子风格）：n 为字节地址，对应寄存器 n/2（n 须为偶数），
+///     `%MXn.b` 为第 n 字节的第 b 位（0~7），偶数字节为寄存器高字节
+///   - 按字编址（施耐德/CODESYS）：n 即寄存器号，`%MXn.b` 为寄存器 n 的第 b 位（0~15）
+///
+/// [`ModbusMAreaAddressing`]: crate::models::ModbusMAreaAddressing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModbusAddress {
+    pub register_type: ModbusRegisterType,
+    /// 协议偏移量（0基）
+    pub offset: u16,
+    /// 寄存器内的位号
+    pub bit: Option<u8>,
+    /// 类型后缀声明的数据类型
+    pub data_type: Option<PlcDataType>,
+}
+
+impl ModbusAddress {
+    /// 解析地址；`zero_based` 为 true 时数字地址按0基偏移解释，M区按字节编址
+    pub fn parse(address: &str, zero_based: bool) -> AppResult<Self> {
+        Self::parse_with(address, zero_based, ModbusMAreaAddressing::default())
+    }
+
+    /// 解析地址，M区按 `m_area` 指定的方式编址
+    pub fn parse_with(address: &str, zero_based: bool, m_area: ModbusMAreaAddressing) -> AppResult<Self> {
+        let text = Self::normalize(address);
+        if text.is_empty() {
+            return Err(AppError::validation_error("地址不能为空".to_string()));
+        }
+
+        let (body, data_type) = match text.split_once(':') {
+            Some((body, suffix)) => {
+                let data_type = match suffix {
+                    "F" => PlcDataType::Float32,
+                    "D" => PlcDataType::Float64,
+                    "L" => PlcDataType::Int32,
+                    _ => return Err(AppError::validation_error(
+                        format!("不支持的地址类型后缀: '{}' in '{}'", suffix, address)
+                    )),
+                };
+                (body, Some(data_type))
+            }
+            None => (text.as_str(), None),
+        };
+
+        let parsed = match body.strip_prefix("%M") {
+            Some(rest) => Self::parse_m_area(rest, m_area, address)?,
+            None => Self::parse_numeric(body, zero_based, address)?,
+        };
+
+        if data_type.is_some() {
+            if parsed.bit.is_some() {
+                return Err(AppError::validation_error(format!("位地址不能带类型后缀: {}", address)));
+            }
+            if !parsed.is_register() {
+                return Err(AppError::validation_error(format!("类型后缀仅适用于寄存器地址: {}", address)));
+            }
+        }
+        Ok(Self { data_type, ..parsed })
+    }
+
+    /// 规范化地址文本：去除空白，`4X0001` 写法去掉 X，不足5位的纯数字左补0；
+    /// 位号、类型后缀与 %M 地址保留原有结构
+    pub fn normalize(address: &str) -> String {
+        let text: String = address.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase();
+        if text.starts_with('%') {
+            return text;
+        }
+        let (body, suffix) = match text.split_once(':') {
+            Some((body, suffix)) => (body, Some(suffix)),
+            None => (text.as_str(), None),
+        };
+        let (number, bit) = match body.split_once('.') {
+            Some((number, bit)) => (number, Some(bit)),
+            None => (body, None),
+        };
+        let mut number = match number.split_once('X') {
+            Some((prefix, digits)) if prefix.len() == 1 => format!("{}{:0>4}", prefix, digits),
+            _ => number.to_string(),
+        };
+        if !number.is_empty() && number.len() < 5 && number.chars().all(|c| c.is_ascii_digit()) {
+            number = format!("{:0>5}", number);
+        }
+        if let Some(bit) = bit {
+            number = format!("{}.{}", number, bit);
+        }
+        if let Some(suffix) = suffix {
+            number = format!("{}:{}", number, suffix);
+        }
+        number
+    }
+
+    /// 是否为输入/保持寄存器地址
+    pub fn is_register(&self) -> bool {
+        matches!(self.register_type, ModbusRegisterType::InputRegister | ModbusRegisterType::HoldingRegister)
+    }
+
+    /// 不含位号与类型后缀的普通地址
+    pub fn is_plain(&self) -> bool {
+        self.bit.is_none() && self.data_type.is_none()
     }
 
-    if address.len() < 2 {
-        return Err(AppError::validation_error(
-            format!("地址格式无效: {}", address)
-        ));
+    /// 实际按哪种类型存取寄存器：位地址为 Bool，带后缀时取后缀类型，否则取调用方请求的类型
+    pub fn value_type(&self, requested: PlcDataType) -> PlcDataType {
+        match (self.bit, self.data_type) {
+            (Some(_), _) => PlcDataType::Bool,
+            (None, Some(data_type)) => data_type,
+            (None, None) => requested,
+        }
     }
 
-    // 如果地址长度不足5位，默认认为省略了首位'0'，按线圈(Coils)处理
-    if address.len() < 5 {
-        let offset = address.parse::<u16>()
+    /// 数字形式：首位为类型前缀，`.b` 为寄存器位号
+    fn parse_numeric(body: &str, zero_based: bool, address: &str) -> AppResult<Self> {
+        let (number, bit) = match body.split_once('.') {
+            Some((number, bit)) => {
+                let bit = bit.parse::<u8>().ok().filter(|b| *b < 16)
+                    .ok_or_else(|| AppError::validation_error(format!("无效的寄存器位号: {}", address)))?;
+                (number, Some(bit))
+            }
+            None => (body, None),
+        };
+
+        if number.len() < 2 {
+            return Err(AppError::validation_error(format!("地址格式无效: {}", address)));
+        }
+
+        let first_char = number.chars().next().unwrap();
+        let offset_str = &number[first_char.len_utf8()..];
+        let offset = offset_str.parse::<u16>()
             .map_err(|_| AppError::validation_error(
-                format!("无效的线圈地址: {}", address)
+                format!("无效的地址偏移量: {}", offset_str)
             ))?;
-        let protocol_offset = if zero_based { offset } else { if offset > 0 { offset - 1 } else { 0 } };
-        return Ok((ModbusRegisterType::Coil, protocol_offset));
+        let protocol_offset = if zero_based { offset } else { offset.saturating_sub(1) };
+
+        let register_type = match first_char {
+            '0' => ModbusRegisterType::Coil,
+            '1' => ModbusRegisterType::DiscreteInput,
+            '3' => ModbusRegisterType::InputRegister,
+            '4' => ModbusRegisterType::HoldingRegister,
+            _ => return Err(AppError::validation_error(
+                format!("不支持的地址类型前缀: '{}' in '{}'", first_char, address)
+            )),
+        };
+
+        let parsed = Self { register_type, offset: protocol_offset, bit, data_type: None };
+        if bit.is_some() && !parsed.is_register() {
+            return Err(AppError::validation_error(format!("位号仅适用于寄存器地址: {}", address)));
+        }
+        Ok(parsed)
     }
 
-    let first_char = address.chars().next().unwrap();
-    let offset_str = &address[1..];
+    /// M区形式（已去掉 `%M` 前缀）：W/D 后为字节地址或寄存器号，X 后为 地址.位
+    fn parse_m_area(rest: &str, m_area: ModbusMAreaAddressing, address: &str) -> AppResult<Self> {
+        let invalid = || AppError::validation_error(format!("无效的M区地址: {}", address));
+        let area = rest.chars().next().ok_or_else(invalid)?;
+        let number = &rest[area.len_utf8()..];
+        let (index, bit) = match (area, number.split_once('.')) {
+            ('X', Some((index, bit))) => (index, Some(bit.parse::<u8>().map_err(|_| invalid())?)),
+            ('W' | 'D', None) => (number, None),
+            _ => return Err(invalid()),
+        };
+        let index = index.parse::<u32>().map_err(|_| invalid())?;
 
-    let offset = offset_str.parse::<u16>()
-        .map_err(|_| AppError::validation_error(
-            format!("无效的地址偏移量: {}", offset_str)
-        ))?;
+        let (register, bit) = match m_area {
+            ModbusMAreaAddressing::WordIndex => {
+                if bit.is_some_and(|b| b >= 16) {
+                    return Err(invalid());
+                }
+                (index, bit)
+            }
+            ModbusMAreaAddressing::ByteOffset => {
+                let bit = match bit {
+                    Some(bit) if bit >= 8 => return Err(invalid()),
+                    // 寄存器高字节在前：偶数字节对应第8~15位
+                    Some(bit) if index % 2 == 0 => Some(bit + 8),
+                    Some(bit) => Some(bit),
+                    None if index % 2 != 0 => {
+                        return Err(AppError::validation_error(format!("M区字/双字地址必须从偶数字节开始: {}", address)));
+                    }
+                    None => None,
+                };
+                (index / 2, bit)
+            }
+        };
+        let offset = u16::try_from(register).map_err(|_| invalid())?;
+        Ok(Self { register_type: ModbusRegisterType::HoldingRegister, offset, bit, data_type: None })
+    }
+}
 
-    let protocol_offset = if zero_based { offset } else { if offset > 0 { offset - 1 } else { 0 } };
+/// 解析Modbus字地址，返回寄存器类型与协议偏移量
+///
+/// 地址格式见 [`ModbusAddress`]，M区按 `m_area` 指定的方式编址；位地址需按布尔值访问，此处返回错误
+pub fn parse_modbus_address_ex(address: &str, zero_based: bool, m_area: ModbusMAreaAddressing) -> AppResult<(ModbusRegisterType, u16)> {
+    let parsed = ModbusAddress::parse_with(address, zero_based, m_area)?;
+    if parsed.bit.is_some() {
+        return Err(AppError::validation_error(format!("位地址 {} 需按布尔值访问", address)));
+    }
+    Ok((parsed.register_type, parsed.offset))
+}
 
-    let register_type = match first_char {
-        '0' => ModbusRegisterType::Coil,
-        '1' => ModbusRegisterType::DiscreteInput,
-        '3' => ModbusRegisterType::InputRegister,
-        '4' => ModbusRegisterType::HoldingRegister,
-        _ => return Err(AppError::validation_error(
-            format!("不支持的地址类型前缀: '{}' in '{}'", first_char, address)
-        )),
+/// 兼容旧代码的单参数版本，默认按1基地址（zero_based = false）
+pub fn parse_modbus_address(address: &str) -> AppResult<(ModbusRegisterType, u16)> {
+    parse_modbus_address_ex(address, false, ModbusMAreaAddressing::default())
+}
+
+/// 批量读取合并时允许跨越的最大地址空隙，空隙内的数据随块读回后丢弃
+const BLOCK_MAX_GAP: u16 = 8;
+/// 单次读取的最大寄存器数量（FC3/FC4）
+const MAX_READ_REGISTERS: u16 = 125;
+/// 单次读取的最大线圈/离散输入数量（FC1/FC2）
+const MAX_READ_BITS: u16 = 2000;
+/// 单次写入的最大寄存器数量（FC16）
+const MAX_WRITE_REGISTERS: u16 = 123;
+/// 单次写入的最大线圈数量（FC15）
+const MAX_WRITE_BITS: u16 = 1968;
+
+/// 批量请求在块中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BlockItem {
+    /// 在原请求列表中的下标
+    index: usize,
+    offset: u16,
+    /// 占用的线圈/寄存器数量
+    width: u16,
+    /// 寄存器位地址的位号
+    bit: Option<u8>,
+}
+
+/// 一次块请求覆盖的连续地址区间
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModbusBlock {
+    register_type: ModbusRegisterType,
+    start: u16,
+    count: u16,
+    items: Vec<BlockItem>,
+}
+
+impl ModbusBlock {
+    /// 只含一个请求的块
+    fn single(register_type: ModbusRegisterType, item: BlockItem) -> Self {
+        Self { register_type, start: item.offset, count: item.width, items: vec![item] }
+    }
+}
+
+/// 批量写入中按请求顺序执行的一步
+enum WriteStep {
+    /// 一次 FC5/15/16 块写入
+    Block(ModbusBlock),
+    /// 寄存器位的读-改-写
+    RegisterBit { index: usize, offset: u16, bit: u8, value: bool },
+}
+
+fn block_limit(register_type: ModbusRegisterType, write: bool) -> u16 {
+    match (register_type, write) {
+        (ModbusRegisterType::Coil | ModbusRegisterType::DiscreteInput, false) => MAX_READ_BITS,
+        (ModbusRegisterType::Coil | ModbusRegisterType::DiscreteInput, true) => MAX_WRITE_BITS,
+        (_, false) => MAX_READ_REGISTERS,
+        (_, true) => MAX_WRITE_REGISTERS,
+    }
+}
+
+/// 按寄存器类型与地址连续性将请求合并为块
+///
+/// 读取按类型与地址排序，允许跨越 `max_gap` 以内的空隙并容许地址重叠；
+/// 写入保持请求顺序，只把前后相邻、同类型且首尾相接的请求合并，
+/// 先写设定值再写触发位这类依赖顺序的写入按原顺序生效
+fn plan_modbus_blocks(mut items: Vec<(ModbusRegisterType, BlockItem)>, max_gap: u16, write: bool) -> Vec<ModbusBlock> {
+    if !write {
+        items.sort_by_key(|(register_type, item)| (*register_type as u8, item.offset));
+    }
+
+    let mut blocks: Vec<ModbusBlock> = Vec::new();
+    for (register_type, item) in items {
+        let item_end = item.offset as u32 + item.width as u32;
+        if let Some(block) = blocks.last_mut() {
+            let block_end = block.start as u32 + block.count as u32;
+            let adjacent = if write {
+                item.offset as u32 == block_end
+            } else {
+                item.offset as u32 <= block_end + max_gap as u32
+            };
+            let span = item_end.max(block_end) - block.start as u32;
+            if block.register_type == register_type && adjacent && span <= block_limit(register_type, write) as u32 {
+                block.count = span as u16;
+                block.items.push(item);
+                continue;
+            }
+        }
+        blocks.push(ModbusBlock::single(register_type, item));
+    }
+    blocks
+}
+
+/// 块请求的原始数据
+#[derive(Debug, Clone, PartialEq)]
+enum BlockData {
+    Bits(Vec<bool>),
+    Words(Vec<u16>),
+}
+
+impl BlockData {
+    fn len(&self) -> usize {
+        match self {
+            BlockData::Bits(bits) => bits.len(),
+            BlockData::Words(words) => words.len(),
+        }
+    }
+
+    fn extend(&mut self, other: &BlockData) {
+        match (self, other) {
+            (BlockData::Bits(bits), BlockData::Bits(more)) => bits.extend_from_slice(more),
+            (BlockData::Words(words), BlockData::Words(more)) => words.extend_from_slice(more),
+            _ => unreachable!("同一块内的数据类型一致"),
+        }
+    }
+}
+
+/// Modbus数值与寄存器之间的编解码
+///
+/// 数值类型按连接字节序排列（64位值可再交换高低双字）；字符串按配置的编码、
+/// 填充字符与字内字节顺序存放；字节数组按寄存器高字节在前原样存放
+#[derive(Debug, Clone)]
+struct ModbusValueCodec {
+    order: crate::models::ByteOrder,
+    data: crate::models::ModbusDataSettings,
+}
+
+impl ModbusValueCodec {
+    fn new(order: crate::models::ByteOrder, data: crate::models::ModbusDataSettings) -> Self {
+        Self { order, data }
+    }
+
+    /// 确定读取请求实际存取的类型与占用宽度：寄存器位地址读取1个寄存器，带类型后缀时按后缀类型读取
+    fn plan_read(&self, address: &ModbusAddress, request: &ReadRequest) -> Result<(PlcDataType, u16), String> {
+        let data_type = address.value_type(request.data_type);
+        let width = match address.bit {
+            Some(_) => 1,
+            None => self.read_width(data_type, request.array_length, address.register_type)?,
+        };
+        Ok((data_type, width))
+    }
+
+    /// 将写入值换算为地址声明的类型：寄存器位地址走读改写，其余编码后参与块合并
+    fn plan_write(&self, address: &ModbusAddress, value: &PlcValue) -> Result<PlannedWrite, String> {
+        let requested = plc_value_type(value).ok_or_else(|| format!("不支持的数据类型: {:?}", value))?;
+        let value = convert_value(value.clone(), address.value_type(requested))?;
+        match (address.bit, value) {
+            (Some(bit), PlcValue::Bool(v)) if address.register_type == ModbusRegisterType::HoldingRegister => {
+                Ok(PlannedWrite::RegisterBit { offset: address.offset, bit, value: v })
+            }
+            (Some(_), _) => Err("不是有效的可写位地址".to_string()),
+            (None, value) => Ok(PlannedWrite::Block(self.encode(&value, address.register_type)?)),
+        }
+    }
+
+    /// 读取数据类型在给定寄存器类型中占用的数量；字符串/字节数组的长度取自 `array_length`
+    fn read_width(&self, data_type: PlcDataType, array_length: Option<u32>, register_type: ModbusRegisterType) -> Result<u16, String> {
+        let is_bit = matches!(register_type, ModbusRegisterType::Coil | ModbusRegisterType::DiscreteInput);
+        let width = match data_type {
+            PlcDataType::Bool if is_bit => return Ok(1),
+            PlcDataType::Bool => return Err("不是有效的布尔型地址".to_string()),
+            _ if is_bit => return Err("不是有效的寄存器地址".to_string()),
+            PlcDataType::Int16 => 1,
+            PlcDataType::Int32 | PlcDataType::UInt32 | PlcDataType::Float32 => 2,
+            PlcDataType::Int64 | PlcDataType::UInt64 | PlcDataType::Float64 => 4,
+            PlcDataType::String | PlcDataType::ByteArray => {
+                let length = array_length
+                    .filter(|len| *len > 0)
+                    .ok_or_else(|| format!("读取{:?}需要指定长度", data_type))?;
+                match (data_type, self.data.string_encoding) {
+                    (PlcDataType::String, crate::models::ModbusStringEncoding::Utf16) => length,
+                    _ => length.div_ceil(2),
+                }
+            }
+        };
+        if width > MAX_READ_REGISTERS as u32 {
+            return Err(format!("数据长度超过单次读取上限 {} 个寄存器", MAX_READ_REGISTERS));
+        }
+        Ok(width as u16)
+    }
+
+    /// 将写入值编码为线圈或寄存器数据
+    fn encode(&self, value: &PlcValue, register_type: ModbusRegisterType) -> Result<BlockData, String> {
+        let words = match (value, register_type) {
+            (PlcValue::Bool(v), ModbusRegisterType::Coil) => return Ok(BlockData::Bits(vec![*v])),
+            (PlcValue::Bool(_), _) => return Err("不是有效的可写布尔型地址".to_string()),
+            (PlcValue::Array(_), _) => return Err(format!("不支持的数据类型: {:?}", value)),
+            (_, ModbusRegisterType::HoldingRegister) => self.encode_words(value)?,
+            _ => return Err("不是有效的可写寄存器地址".to_string()),
+        };
+        if words.len() > MAX_WRITE_REGISTERS as usize {
+            return Err(format!("数据长度超过单次写入上限 {} 个寄存器", MAX_WRITE_REGISTERS));
+        }
+        Ok(BlockData::Words(words))
+    }
+
+    fn encode_words(&self, value: &PlcValue) -> Result<Vec<u16>, String> {
+        let swap_64 = self.data.word_swap_64;
+        Ok(match value {
+            PlcValue::Int16(v) => vec![*v as u16],
+            PlcValue::Int32(v) => ByteOrderConverter::bytes_to_registers(&v.to_be_bytes(), self.order, false),
+            PlcValue::UInt32(v) => ByteOrderConverter::bytes_to_registers(&v.to_be_bytes(), self.order, false),
+            PlcValue::Float32(v) => ByteOrderConverter::bytes_to_registers(&v.to_be_bytes(), self.order, false),
+            PlcValue::Int64(v) => ByteOrderConverter::bytes_to_registers(&v.to_be_bytes(), self.order, swap_64),
+            PlcValue::UInt64(v) => ByteOrderConverter::bytes_to_registers(&v.to_be_bytes(), self.order, swap_64),
+            PlcValue::Float64(v) => ByteOrderConverter::bytes_to_registers(&v.to_be_bytes(), self.order, swap_64),
+            PlcValue::String(text) => self.encode_string(text)?,
+            PlcValue::ByteArray(bytes) if bytes.is_empty() => return Err("写入的字节数组不能为空".to_string()),
+            PlcValue::ByteArray(bytes) => bytes
+                .chunks(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+                .collect(),
+            other => return Err(format!("不支持的数据类型: {:?}", other)),
+        })
+    }
+
+    /// 字符串按编码转换后以填充字符补齐到整寄存器（空字符串写入一个填充寄存器）
+    fn encode_string(&self, text: &str) -> Result<Vec<u16>, String> {
+        use crate::models::ModbusStringEncoding;
+        let padding = self.data.string_padding.byte();
+        let mut words = match self.data.string_encoding {
+            ModbusStringEncoding::Utf16 => text.encode_utf16().collect::<Vec<u16>>(),
+            encoding => {
+                if encoding == ModbusStringEncoding::Ascii && !text.is_ascii() {
+                    return Err(format!("字符串包含非ASCII字符: {}", text));
+                }
+                text.as_bytes()
+                    .chunks(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(padding)]))
+                    .collect()
+            }
+        };
+        if words.is_empty() {
+            words.push(u16::from_be_bytes([padding, padding]));
+        }
+        if self.data.string_byte_swap {
+            words.iter_mut().for_each(|w| *w = w.swap_bytes());
+        }
+        Ok(words)
+    }
+
+    /// 从块数据中取出单个请求的值
+    fn decode(&self, data: &BlockData, block_start: u16, item: &BlockItem, data_type: PlcDataType, array_length: Option<u32>) -> Result<PlcValue, String> {
+        let at = (item.offset - block_start) as usize;
+        let missing = || format!("应答数据不足: 偏移 {}", item.offset);
+        let regs = match data {
+            BlockData::Bits(bits) => return bits.get(at).map(|v| PlcValue::Bool(*v)).ok_or_else(missing),
+            BlockData::Words(words) => words.get(at..at + item.width as usize).ok_or_else(missing)?,
+        };
+        if let Some(bit) = item.bit {
+            return Ok(PlcValue::Bool(regs[0] >> bit & 1 == 1));
+        }
+        let swap_64 = self.data.word_swap_64;
+        let bytes = |word_swap_64: bool| ByteOrderConverter::registers_to_bytes(regs, self.order, word_swap_64);
+        Ok(match data_type {
+            PlcDataType::Int16 => PlcValue::Int16(regs[0] as i16),
+            PlcDataType::Int32 => PlcValue::Int32(i32::from_be_bytes(to_array(&bytes(false)))),
+            PlcDataType::UInt32 => PlcValue::UInt32(u32::from_be_bytes(to_array(&bytes(false)))),
+            PlcDataType::Float32 => PlcValue::Float32(f32::from_be_bytes(to_array(&bytes(false)))),
+            PlcDataType::Int64 => PlcValue::Int64(i64::from_be_bytes(to_array(&bytes(swap_64)))),
+            PlcDataType::UInt64 => PlcValue::UInt64(u64::from_be_bytes(to_array(&bytes(swap_64)))),
+            PlcDataType::Float64 => PlcValue::Float64(f64::from_be_bytes(to_array(&bytes(swap_64)))),
+            PlcDataType::String => PlcValue::String(self.decode_string(regs)),
+            PlcDataType::ByteArray => {
+                let mut raw: Vec<u8> = regs.iter().flat_map(|w| w.to_be_bytes()).collect();
+                raw.truncate(array_length.unwrap_or(raw.len() as u32) as usize);
+                PlcValue::ByteArray(raw)
+            }
+            PlcDataType::Bool => return Err("不是有效的布尔型地址".to_string()),
+        })
+    }
+
+    /// 字符串读取到第一个NUL为止，并去除末尾的填充字符
+    fn decode_string(&self, regs: &[u16]) -> String {
+        use crate::models::ModbusStringEncoding;
+        let words: Vec<u16> = regs
+            .iter()
+            .map(|w| if self.data.string_byte_swap { w.swap_bytes() } else { *w })
+            .collect();
+        let text = match self.data.string_encoding {
+            ModbusStringEncoding::Utf16 => {
+                let units: Vec<u16> = words.into_iter().take_while(|w| *w != 0).collect();
+                String::from_utf16_lossy(&units)
+            }
+            encoding => {
+                let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).take_while(|b| *b != 0).collect();
+                match encoding {
+                    ModbusStringEncoding::Ascii => bytes.iter().map(|b| if b.is_ascii() { *b as char } else { '?' }).collect(),
+                    _ => String::from_utf8_lossy(&bytes).into_owned(),
+                }
+            }
+        };
+        let padding = self.data.string_padding.byte() as char;
+        text.trim_end_matches(padding).to_string()
+    }
+}
+
+/// 单个写入请求的执行方式
+#[derive(Debug, Clone, PartialEq)]
+enum PlannedWrite {
+    /// 参与块合并的线圈/寄存器数据
+    Block(BlockData),
+    /// 保持寄存器中单个位的读改写
+    RegisterBit { offset: u16, bit: u8, value: bool },
+}
+
+/// 写入值对应的数据类型
+fn plc_value_type(value: &PlcValue) -> Option<PlcDataType> {
+    Some(match value {
+        PlcValue::Bool(_) => PlcDataType::Bool,
+        PlcValue::Int16(_) => PlcDataType::Int16,
+        PlcValue::Int32(_) => PlcDataType::Int32,
+        PlcValue::UInt32(_) => PlcDataType::UInt32,
+        PlcValue::Int64(_) => PlcDataType::Int64,
+        PlcValue::UInt64(_) => PlcDataType::UInt64,
+        PlcValue::Float32(_) => PlcDataType::Float32,
+        PlcValue::Float64(_) => PlcDataType::Float64,
+        PlcValue::String(_) => PlcDataType::String,
+        PlcValue::ByteArray(_) => PlcDataType::ByteArray,
+        PlcValue::Array(_) => return None,
+    })
+}
+
+/// 在数值类型之间换算：整数目标要求值为整数且不越界，其余类型只允许同类型
+fn convert_value(value: PlcValue, target: PlcDataType) -> Result<PlcValue, String> {
+    if plc_value_type(&value) == Some(target) {
+        return Ok(value);
+    }
+    let mismatch = |value: &PlcValue| format!("无法将 {:?} 按 {:?} 存取", value, target);
+    let number = match value {
+        PlcValue::Int16(v) => Ok(v as i128),
+        PlcValue::Int32(v) => Ok(v as i128),
+        PlcValue::UInt32(v) => Ok(v as i128),
+        PlcValue::Int64(v) => Ok(v as i128),
+        PlcValue::UInt64(v) => Ok(v as i128),
+        PlcValue::Float32(v) => Err(v as f64),
+        PlcValue::Float64(v) => Err(v),
+        ref other => return Err(mismatch(other)),
     };
+    let float = match number {
+        Ok(v) => v as f64,
+        Err(v) => v,
+    };
+    let integer = || match number {
+        Ok(v) => Ok(v),
+        Err(v) if v.is_finite() && v.fract() == 0.0 => Ok(v as i128),
+        Err(v) => Err(format!("{} 不是整数，无法按 {:?} 存取", v, target)),
+    };
+    let out_of_range = |v: i128| format!("{} 超出 {:?} 的范围", v, target);
+    Ok(match target {
+        PlcDataType::Float32 => PlcValue::Float32(float as f32),
+        PlcDataType::Float64 => PlcValue::Float64(float),
+        PlcDataType::Int16 => integer().and_then(|v| i16::try_from(v).map_err(|_| out_of_range(v))).map(PlcValue::Int16)?,
+        PlcDataType::Int32 => integer().and_then(|v| i32::try_from(v).map_err(|_| out_of_range(v))).map(PlcValue::Int32)?,
+        PlcDataType::UInt32 => integer().and_then(|v| u32::try_from(v).map_err(|_| out_of_range(v))).map(PlcValue::UInt32)?,
+        PlcDataType::Int64 => integer().and_then(|v| i64::try_from(v).map_err(|_| out_of_range(v))).map(PlcValue::Int64)?,
+        PlcDataType::UInt64 => integer().and_then(|v| u64::try_from(v).map_err(|_| out_of_range(v))).map(PlcValue::UInt64)?,
+        _ => return Err(format!("无法按 {:?} 存取数值", target)),
+    })
+}
 
-    Ok((register_type, protocol_offset))
+/// 将寄存器字节转换为定长数组，长度由 `read_width` 保证
+fn to_array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(&bytes[..N]);
+    array
 }
 
-/// 兼容旧代码的单参数版本，默认按1基地址（zero_based = false）
-pub fn parse_modbus_address(address: &str) -> AppResult<(ModbusRegisterType, u16)> {
-    parse_modbus_address_ex(address, false)
+fn failed_read(request: &ReadRequest, error: &AppError, execution_time_ms: u64) -> ReadResult {
+    ReadResult {
+        request_id: request.id.clone(),
+        success: false,
+        value: None,
+        error_message: Some(error.to_string()),
+        failure_kind: Some(error.failure_kind()),
+        execution_time_ms,
+    }
+}
+
+fn write_result(request: &WriteRequest, outcome: AppResult<()>, execution_time_ms: u64) -> WriteResult {
+    WriteResult {
+        request_id: request.id.clone(),
+        success: outcome.is_ok(),
+        error_message: outcome.as_ref().err().map(|e| e.to_string()),
+        failure_kind: outcome.err().map(|e| e.failure_kind()),
+        execution_time_ms,
+    }
+}
+
+const FC_READ_COILS: u8 = 0x01;
+const FC_READ_DISCRETE_INPUTS: u8 = 0x02;
+const FC_READ_HOLDING_REGISTERS: u8 = 0x03;
+const FC_READ_INPUT_REGISTERS: u8 = 0x04;
+const FC_WRITE_SINGLE_COIL: u8 = 0x05;
+const FC_WRITE_SINGLE_REGISTER: u8 = 0x06;
+const FC_WRITE_MULTIPLE_COILS: u8 = 0x0F;
+const FC_WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// 在请求超时内执行一次Modbus请求
+///
+/// 从站异常应答转换为 `ModbusException`，超时转换为 `ModbusTimeout`，均带功能码、地址与连接ID；
+/// 传输层错误仍为 `PlcCommunicationError`。请求时延上报给连接管理器的质量统计，
+/// 启用报文录制时请求与应答同时写入批次录制文件
+async fn modbus_call<T: FrameResponse>(
+    connection: &ModbusTcpConnection,
+    request: ModbusRequestFrame,
+    address: &str,
+    call: impl std::future::Future<Output = tokio_modbus::Result<T>>,
+) -> AppResult<T> {
+    let connection_id = &connection.handle.connection_id;
+    let function_code = request.function_code;
+    let sent_at = Utc::now();
+    let started = std::time::Instant::now();
+    let (result, link_ok) = match timeout(connection.request_timeout, call).await {
+        Ok(Ok(Ok(value))) => (Ok(value), true),
+        Ok(Ok(Err(exception))) => (Err(AppError::modbus_exception(u8::from(exception), function_code, address, connection_id)), true),
+        Ok(Err(e)) => (Err(AppError::plc_communication_error(
+            format!("Modbus请求失败: 功能码 0x{:02X}, 地址 {}, 连接 {} - {}", function_code, address, connection_id, e)
+        )), false),
+        Err(_) => (Err(AppError::modbus_timeout(
+            function_code, address, connection_id, connection.request_timeout.as_millis() as u64,
+        )), false),
+    };
+    let elapsed = started.elapsed();
+    // 上报时延与链路结果，用于连接质量统计；从站异常应答说明链路正常
+    if let Some(mgr) = get_global_plc_manager() {
+        mgr.monitor().record_request(connection_id, elapsed, link_ok);
+    }
+    if let Some(recorder) = frame_recorder() {
+        recorder.record(connection_id, request, FrameOutcome::of(&result), sent_at, elapsed);
+    }
+    result
+}
+
+/// 块请求在错误信息中的地址描述，如 "HoldingRegister 100+8"
+fn block_address(block: &ModbusBlock) -> String {
+    format!("{:?} {}+{}", block.register_type, block.start, block.count)
+}
+
+/// 以一次 FC1/2/3/4 请求读取整个块
+async fn read_block(connection: &ModbusTcpConnection, block: &ModbusBlock) -> AppResult<BlockData> {
+    let _permit = connection.scheduler.acquire(false).await?;
+    let mut context_guard = connection.context.lock().await;
+    let context = context_guard.as_mut().ok_or_else(|| AppError::plc_communication_error("连接已断开"))?;
+    let (start, count) = (block.start, block.count);
+    let address = block_address(block);
+    let response = match block.register_type {
+        ModbusRegisterType::Coil => {
+            modbus_call(connection, ModbusRequestFrame::read(FC_READ_COILS, start, count), &address, context.read_coils(start, count)).await.map(BlockData::Bits)
+        }
+        ModbusRegisterType::DiscreteInput => {
+            modbus_call(connection, ModbusRequestFrame::read(FC_READ_DISCRETE_INPUTS, start, count), &address, context.read_discrete_inputs(start, count)).await.map(BlockData::Bits)
+        }
+        ModbusRegisterType::HoldingRegister => {
+            modbus_call(connection, ModbusRequestFrame::read(FC_READ_HOLDING_REGISTERS, start, count), &address, context.read_holding_registers(start, count)).await.map(BlockData::Words)
+        }
+        ModbusRegisterType::InputRegister => {
+            modbus_call(connection, ModbusRequestFrame::read(FC_READ_INPUT_REGISTERS, start, count), &address, context.read_input_registers(start, count)).await.map(BlockData::Words)
+        }
+    };
+    response
+}
+
+/// 以一次 FC5/15/16 请求写入整个块
+async fn write_block(connection: &ModbusTcpConnection, block: &ModbusBlock, payload: &BlockData) -> AppResult<()> {
+    let _permit = connection.scheduler.acquire(true).await?;
+    let mut context_guard = connection.context.lock().await;
+    let context = context_guard.as_mut().ok_or_else(|| AppError::plc_communication_error("连接已断开"))?;
+    let address = block_address(block);
+    let response = match payload {
+        BlockData::Bits(bits) if bits.len() == 1 => {
+            modbus_call(connection, ModbusRequestFrame::write_bits(FC_WRITE_SINGLE_COIL, block.start, &bits[..1]), &address, context.write_single_coil(block.start, bits[0])).await
+        }
+        BlockData::Bits(bits) => {
+            modbus_call(connection, ModbusRequestFrame::write_bits(FC_WRITE_MULTIPLE_COILS, block.start, bits), &address, context.write_multiple_coils(block.start, bits)).await
+        }
+        BlockData::Words(words) => {
+            modbus_call(connection, ModbusRequestFrame::write_words(FC_WRITE_MULTIPLE_REGISTERS, block.start, words), &address, context.write_multiple_registers(block.start, words)).await
+        }
+    };
+    response
+}
+
+/// 寄存器位写入：持有连接锁完成读取、改位、写回，避免与其他请求交错
+async fn write_register_bit(connection: &ModbusTcpConnection, address: &str, offset: u16, bit: u8, value: bool) -> AppResult<()> {
+    let _permit = connection.scheduler.acquire(true).await?;
+    let mut context_guard = connection.context.lock().await;
+    let context = context_guard.as_mut().ok_or_else(|| AppError::plc_communication_error("连接已断开"))?;
+    let regs = modbus_call(connection, ModbusRequestFrame::read(FC_READ_HOLDING_REGISTERS, offset, 1), address, context.read_holding_registers(offset, 1)).await?;
+    let current = regs.first().copied()
+        .ok_or_else(|| AppError::plc_communication_error(format!("应答数据不足: 偏移 {}", offset)))?;
+    let word = set_register_bit(current, bit, value);
+    modbus_call(connection, ModbusRequestFrame::write_words(FC_WRITE_SINGLE_REGISTER, offset, &[word]), address, context.write_single_register(offset, word)).await
+}
+
+fn set_register_bit(word: u16, bit: u8, value: bool) -> u16 {
+    if value {
+        word | (1 << bit)
+    } else {
+        word & !(1 << bit)
+    }
 }
 
 /// 字节序转换工具
-struct ByteOrderConverter;
+pub(crate) struct ByteOrderConverter;
 impl ByteOrderConverter {
-    fn registers_to_float(reg1: u16, reg2: u16, order: crate::models::ByteOrder) -> f32 {
+    /// 将大端字节按字节序排列为寄存器：BADC/DCBA 交换字内字节，CDAB/DCBA 反转字序；
+    /// `word_swap_64` 对64位值再交换高低两个双字
+    pub(crate) fn bytes_to_registers(bytes: &[u8], order: crate::models::ByteOrder, word_swap_64: bool) -> Vec<u16> {
+        use crate::models::ByteOrder;
+        let swap_bytes = matches!(order, ByteOrder::BADC | ByteOrder::DCBA);
+        let mut words: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair.get(1).copied().unwrap_or(0)]))
+            .map(|w| if swap_bytes { w.swap_bytes() } else { w })
+            .collect();
+        if matches!(order, ByteOrder::CDAB | ByteOrder::DCBA) {
+            words.reverse();
+        }
+        if word_swap_64 && words.len() == 4 {
+            words.rotate_left(2);
+        }
+        words
+    }
+
+    /// `bytes_to_registers` 的逆变换，返回大端字节
+    pub(crate) fn registers_to_bytes(registers: &[u16], order: crate::models::ByteOrder, word_swap_64: bool) -> Vec<u8> {
+        use crate::models::ByteOrder;
+        let mut words = registers.to_vec();
+        if word_swap_64 && words.len() == 4 {
+            words.rotate_left(2);
+        }
+        if matches!(order, ByteOrder::CDAB | ByteOrder::DCBA) {
+            words.reverse();
+        }
+        let swap_bytes = matches!(order, ByteOrder::BADC | ByteOrder::DCBA);
+        words
+            .into_iter()
+            .map(|w| if swap_bytes { w.swap_bytes() } else { w })
+            .flat_map(u16::to_be_bytes)
+            .collect()
+    }
+
+    pub(crate) fn registers_to_float(reg1: u16, reg2: u16, order: crate::models::ByteOrder) -> f32 {
         let bytes = match order {
             crate::models::ByteOrder::ABCD => [
                 (reg1 >> 8) as u8,
@@ -1255,7 +2133,7 @@ impl ByteOrderConverter {
         f32::from_be_bytes(bytes)
     }
 
-    fn float_to_registers(value: f32, order: crate::models::ByteOrder) -> (u16, u16) {
+    pub(crate) fn float_to_registers(value: f32, order: crate::models::ByteOrder) -> (u16, u16) {
         let bytes = value.to_be_bytes();
         match order {
             crate::models::ByteOrder::ABCD => {
@@ -1282,7 +2160,7 @@ impl ByteOrderConverter {
     }
 
     // i32 <-> registers
-    fn registers_to_i32(reg1: u16, reg2: u16, order: crate::models::ByteOrder) -> i32 {
+    pub(crate) fn registers_to_i32(reg1: u16, reg2: u16, order: crate::models::ByteOrder) -> i32 {
         let bytes = match order {
             crate::models::ByteOrder::ABCD => [
                 (reg1 >> 8) as u8,
@@ -1312,7 +2190,7 @@ impl ByteOrderConverter {
         i32::from_be_bytes(bytes)
     }
 
-    fn i32_to_registers(value: i32, order: crate::models::ByteOrder) -> (u16, u16) {
+    pub(crate) fn i32_to_registers(value: i32, order: crate::models::ByteOrder) -> (u16, u16) {
         let bytes = value.to_be_bytes();
         match order {
             crate::models::ByteOrder::ABCD => {
@@ -1359,7 +2237,7 @@ pub fn registers_to_f32(registers: &[u16]) -> f32 {
 
 
 /// 更新读取统计信息
-async fn update_read_stats(stats: &Arc<Mutex<ConnectionStats>>, start_time: DateTime<Utc>) {
+pub(crate) async fn update_read_stats(stats: &Arc<Mutex<ConnectionStats>>, start_time: DateTime<Utc>) {
     let mut stats = stats.lock().await;
     let duration = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
 
@@ -1373,7 +2251,7 @@ async fn update_read_stats(stats: &Arc<Mutex<ConnectionStats>>, start_time: Date
 }
 
 /// 更新写入统计信息
-async fn update_write_stats(stats: &Arc<Mutex<ConnectionStats>>, start_time: DateTime<Utc>) {
+pub(crate) async fn update_write_stats(stats: &Arc<Mutex<ConnectionStats>>, start_time: DateTime<Utc>) {
     let mut stats = stats.lock().await;
     let duration = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
 
@@ -1386,4 +2264,569 @@ async fn update_write_stats(stats: &Arc<Mutex<ConnectionStats>>, start_time: Dat
     stats.average_write_time_ms = total_time / stats.successful_writes as f64;
 }
 
+#[cfg(test)]
+mod block_tests {
+    use super::*;
 
+    fn item(register_type: ModbusRegisterType, index: usize, offset: u16, width: u16) -> (ModbusRegisterType, BlockItem) {
+        (register_type, BlockItem { index, offset, width, bit: None })
+    }
+
+    fn spans(blocks: &[ModbusBlock]) -> Vec<(ModbusRegisterType, u16, u16)> {
+        blocks.iter().map(|b| (b.register_type, b.start, b.count)).collect()
+    }
+
+    #[test]
+    fn read_blocks_merge_within_gap_and_split_by_type() {
+        use ModbusRegisterType::*;
+        let blocks = plan_modbus_blocks(vec![
+            item(HoldingRegister, 0, 10, 2),
+            item(Coil, 1, 5, 1),
+            item(HoldingRegister, 2, 0, 2),
+            item(HoldingRegister, 3, 30, 2),
+            item(Coil, 4, 6, 1),
+            item(InputRegister, 5, 0, 2),
+        ], BLOCK_MAX_GAP, false);
+
+        assert_eq!(spans(&blocks), vec![
+            (Coil, 5, 2),
+            (InputRegister, 0, 2),
+            (HoldingRegister, 0, 12),
+            (HoldingRegister, 30, 2),
+        ]);
+        let indices: Vec<usize> = blocks[2].items.iter().map(|i| i.index).collect();
+        assert_eq!(indices, vec![2, 0]);
+    }
+
+    #[test]
+    fn read_blocks_respect_protocol_limits() {
+        use ModbusRegisterType::*;
+        // 63个浮点数共126个寄存器，超过125的上限
+        let registers: Vec<_> = (0..63).map(|i| item(HoldingRegister, i, i as u16 * 2, 2)).collect();
+        assert_eq!(spans(&plan_modbus_blocks(registers, BLOCK_MAX_GAP, false)),
+                   vec![(HoldingRegister, 0, 124), (HoldingRegister, 124, 2)]);
+
+        let coils: Vec<_> = (0..2001).map(|i| item(DiscreteInput, i, i as u16, 1)).collect();
+        assert_eq!(spans(&plan_modbus_blocks(coils, BLOCK_MAX_GAP, false)),
+                   vec![(DiscreteInput, 0, 2000), (DiscreteInput, 2000, 1)]);
+    }
+
+    #[test]
+    fn write_blocks_require_contiguity_and_keep_request_order() {
+        use ModbusRegisterType::*;
+        let blocks = plan_modbus_blocks(vec![
+            item(HoldingRegister, 0, 0, 2),
+            item(HoldingRegister, 1, 2, 2),
+            item(HoldingRegister, 2, 5, 1),
+            item(HoldingRegister, 3, 0, 2),
+        ], BLOCK_MAX_GAP, true);
+
+        assert_eq!(spans(&blocks), vec![
+            (HoldingRegister, 0, 4),
+            (HoldingRegister, 5, 1),
+            (HoldingRegister, 0, 2),
+        ]);
+        // 重复写入同一地址时，后发出的请求位于后执行的块
+        assert_eq!(blocks[2].items[0].index, 3);
+    }
+
+    #[test]
+    fn setpoint_then_trigger_writes_are_not_reordered() {
+        use ModbusRegisterType::*;
+        // 先写两个设定值寄存器，再置位触发线圈，最后写一个低地址寄存器
+        let blocks = plan_modbus_blocks(vec![
+            item(HoldingRegister, 0, 100, 2),
+            item(HoldingRegister, 1, 102, 2),
+            item(Coil, 2, 0, 1),
+            item(HoldingRegister, 3, 10, 1),
+        ], 0, true);
+
+        assert_eq!(spans(&blocks), vec![(HoldingRegister, 100, 4), (Coil, 0, 1), (HoldingRegister, 10, 1)]);
+        let order: Vec<usize> = blocks.iter().flat_map(|b| b.items.iter().map(|i| i.index)).collect();
+        assert_eq!(order, vec![0, 1, 2, 3]);
+    }
+
+    fn codec(order: crate::models::ByteOrder, data: crate::models::ModbusDataSettings) -> ModbusValueCodec {
+        ModbusValueCodec::new(order, data)
+    }
+
+    fn words(data: BlockData) -> Vec<u16> {
+        match data {
+            BlockData::Words(words) => words,
+            other => panic!("期望寄存器数据: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn block_data_round_trips_values() {
+        use crate::models::ByteOrder;
+        let codec = codec(ByteOrder::CDAB, Default::default());
+        let mut words = codec.encode(&PlcValue::Float32(12.5), ModbusRegisterType::HoldingRegister).unwrap();
+        words.extend(&codec.encode(&PlcValue::Int16(-7), ModbusRegisterType::HoldingRegister).unwrap());
+        words.extend(&codec.encode(&PlcValue::Int32(-42), ModbusRegisterType::HoldingRegister).unwrap());
+
+        let at = |index, offset, width| BlockItem { index, offset, width, bit: None };
+        assert!(matches!(codec.decode(&words, 100, &at(0, 100, 2), PlcDataType::Float32, None), Ok(PlcValue::Float32(v)) if v == 12.5));
+        assert!(matches!(codec.decode(&words, 100, &at(1, 102, 1), PlcDataType::Int16, None), Ok(PlcValue::Int16(-7))));
+        assert!(matches!(codec.decode(&words, 100, &at(2, 103, 2), PlcDataType::Int32, None), Ok(PlcValue::Int32(-42))));
+        assert!(codec.decode(&words, 100, &at(3, 104, 2), PlcDataType::Float32, None).is_err());
+
+        assert!(codec.encode(&PlcValue::Bool(true), ModbusRegisterType::DiscreteInput).is_err());
+        assert!(codec.read_width(PlcDataType::Float32, None, ModbusRegisterType::Coil).is_err());
+    }
+
+    #[test]
+    fn generic_layout_matches_32_bit_converters() {
+        use crate::models::ByteOrder;
+        for order in [ByteOrder::ABCD, ByteOrder::CDAB, ByteOrder::BADC, ByteOrder::DCBA] {
+            let (reg1, reg2) = ByteOrderConverter::float_to_registers(-98.7654, order);
+            assert_eq!(ByteOrderConverter::bytes_to_registers(&(-98.7654f32).to_be_bytes(), order, false), vec![reg1, reg2]);
+            let (reg1, reg2) = ByteOrderConverter::i32_to_registers(0x1234_5678, order);
+            assert_eq!(ByteOrderConverter::registers_to_bytes(&[reg1, reg2], order, false), 0x1234_5678i32.to_be_bytes());
+        }
+    }
+
+    #[test]
+    fn encodes_64_bit_values_with_byte_order_and_word_swap() {
+        use crate::models::{ByteOrder, ModbusDataSettings};
+        let value = PlcValue::UInt64(0x1122_3344_5566_7788);
+        let encode = |order, word_swap_64| {
+            words(codec(order, ModbusDataSettings { word_swap_64, ..Default::default() })
+                .encode(&value, ModbusRegisterType::HoldingRegister).unwrap())
+        };
+        assert_eq!(encode(ByteOrder::ABCD, false), vec![0x1122, 0x3344, 0x5566, 0x7788]);
+        assert_eq!(encode(ByteOrder::CDAB, false), vec![0x7788, 0x5566, 0x3344, 0x1122]);
+        assert_eq!(encode(ByteOrder::BADC, false), vec![0x2211, 0x4433, 0x6655, 0x8877]);
+        assert_eq!(encode(ByteOrder::DCBA, false), vec![0x8877, 0x6655, 0x4433, 0x2211]);
+        assert_eq!(encode(ByteOrder::CDAB, true), vec![0x3344, 0x1122, 0x7788, 0x5566]);
+
+        for order in [ByteOrder::ABCD, ByteOrder::CDAB, ByteOrder::BADC, ByteOrder::DCBA] {
+            for word_swap_64 in [false, true] {
+                let codec = codec(order, ModbusDataSettings { word_swap_64, ..Default::default() });
+                for (value, data_type) in [
+                    (PlcValue::Int64(-1_234_567_890_123), PlcDataType::Int64),
+                    (PlcValue::Float64(-0.000_123_456_789), PlcDataType::Float64),
+                    (PlcValue::UInt32(4_000_000_001), PlcDataType::UInt32),
+                ] {
+                    let data = codec.encode(&value, ModbusRegisterType::HoldingRegister).unwrap();
+                    let width = codec.read_width(data_type, None, ModbusRegisterType::InputRegister).unwrap();
+                    let decoded = codec.decode(&data, 0, &BlockItem { index: 0, offset: 0, width, bit: None }, data_type, None).unwrap();
+                    assert_eq!(format!("{:?}", decoded), format!("{:?}", value));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn strings_follow_encoding_padding_and_byte_swap() {
+        use crate::models::{ByteOrder, ModbusDataSettings, ModbusStringEncoding, ModbusStringPadding};
+        let ascii = codec(ByteOrder::CDAB, ModbusDataSettings { string_padding: ModbusStringPadding::Space, ..Default::default() });
+        assert_eq!(words(ascii.encode(&PlcValue::String("PUMP1".to_string()), ModbusRegisterType::HoldingRegister).unwrap()),
+                   vec![0x5055, 0x4D50, 0x3120]);
+        assert!(ascii.encode(&PlcValue::String("泵1".to_string()), ModbusRegisterType::HoldingRegister).is_err());
+
+        // 读取10个字符：去除末尾填充，遇到NUL截止
+        let width = ascii.read_width(PlcDataType::String, Some(10), ModbusRegisterType::HoldingRegister).unwrap();
+        assert_eq!(width, 5);
+        let data = BlockData::Words(vec![0x5055, 0x4D50, 0x3120, 0x2020, 0x0041]);
+        let item = BlockItem { index: 0, offset: 0, width, bit: None };
+        assert!(matches!(ascii.decode(&data, 0, &item, PlcDataType::String, Some(10)), Ok(PlcValue::String(s)) if s == "PUMP1"));
+
+        let swapped = codec(ByteOrder::ABCD, ModbusDataSettings { string_byte_swap: true, ..Default::default() });
+        assert_eq!(words(swapped.encode(&PlcValue::String("ABC".to_string()), ModbusRegisterType::HoldingRegister).unwrap()),
+                   vec![0x4241, 0x0043]);
+
+        let utf16 = codec(ByteOrder::ABCD, ModbusDataSettings { string_encoding: ModbusStringEncoding::Utf16, ..Default::default() });
+        let data = utf16.encode(&PlcValue::String("泵A".to_string()), ModbusRegisterType::HoldingRegister).unwrap();
+        assert_eq!(utf16.read_width(PlcDataType::String, Some(2), ModbusRegisterType::HoldingRegister), Ok(2));
+        let item = BlockItem { index: 0, offset: 0, width: 2, bit: None };
+        assert!(matches!(utf16.decode(&data, 0, &item, PlcDataType::String, Some(2)), Ok(PlcValue::String(s)) if s == "泵A"));
+
+        let utf8 = codec(ByteOrder::ABCD, ModbusDataSettings { string_encoding: ModbusStringEncoding::Utf8, ..Default::default() });
+        let data = utf8.encode(&PlcValue::String("泵A".to_string()), ModbusRegisterType::HoldingRegister).unwrap();
+        assert_eq!(data.len(), 2);
+        let item = BlockItem { index: 0, offset: 0, width: 2, bit: None };
+        assert!(matches!(utf8.decode(&data, 0, &item, PlcDataType::String, Some(4)), Ok(PlcValue::String(s)) if s == "泵A"));
+    }
+
+    #[test]
+    fn byte_arrays_are_stored_high_byte_first() {
+        let codec = codec(crate::models::ByteOrder::DCBA, Default::default());
+        let data = codec.encode(&PlcValue::ByteArray(vec![1, 2, 3]), ModbusRegisterType::HoldingRegister).unwrap();
+        assert_eq!(data, BlockData::Words(vec![0x0102, 0x0300]));
+        assert_eq!(codec.read_width(PlcDataType::ByteArray, Some(3), ModbusRegisterType::InputRegister), Ok(2));
+        assert!(codec.read_width(PlcDataType::ByteArray, None, ModbusRegisterType::InputRegister).is_err());
+        let item = BlockItem { index: 0, offset: 0, width: 2, bit: None };
+        assert!(matches!(codec.decode(&data, 0, &item, PlcDataType::ByteArray, Some(3)), Ok(PlcValue::ByteArray(b)) if b == vec![1, 2, 3]));
+    }
+
+    fn address(text: &str) -> ModbusAddress {
+        ModbusAddress::parse(text, false).unwrap()
+    }
+
+    #[test]
+    fn parses_legacy_bit_suffix_and_m_area_addresses() {
+        use ModbusRegisterType::*;
+        let plain = |register_type, offset| ModbusAddress { register_type, offset, bit: None, data_type: None };
+        assert_eq!(address("40001"), plain(HoldingRegister, 0));
+        assert_eq!(address("30010"), plain(InputRegister, 9));
+        assert_eq!(address("4X0010"), plain(HoldingRegister, 9));
+        assert_eq!(address(" 12 "), plain(Coil, 11));
+        assert_eq!(ModbusAddress::parse("40001", true).unwrap(), plain(HoldingRegister, 1));
+
+        assert_eq!(address("40001.3"), ModbusAddress { bit: Some(3), ..plain(HoldingRegister, 0) });
+        assert_eq!(address("40002:d"), ModbusAddress { data_type: Some(PlcDataType::Float64), ..plain(HoldingRegister, 1) });
+        assert_eq!(address("30001:L").data_type, Some(PlcDataType::Int32));
+
+        assert_eq!(address("%MW100"), plain(HoldingRegister, 50));
+        assert_eq!(address("%MD8:F"), ModbusAddress { data_type: Some(PlcDataType::Float32), ..plain(HoldingRegister, 4) });
+        assert_eq!(address("%MX10.2"), ModbusAddress { bit: Some(10), ..plain(HoldingRegister, 5) });
+        assert_eq!(address("%MX11.2"), ModbusAddress { bit: Some(2), ..plain(HoldingRegister, 5) });
+
+        for invalid in ["", "50001", "40001.16", "00001.1", "40001.1:F", "00001:F", "40001:X", "%MW3", "%MX10.8", "%MB10",
+                        "%Mé", "%MWé", "%MX1é.1", "é0001", "4é001"] {
+            assert!(ModbusAddress::parse(invalid, false).is_err(), "{} 应解析失败", invalid);
+        }
+        assert!(parse_modbus_address_ex("40001.3", false, ModbusMAreaAddressing::default()).is_err());
+        assert_eq!(ModbusAddress::normalize("4x1.3:f"), "40001.3:F");
+    }
+
+    #[test]
+    fn word_indexed_m_area_maps_straight_to_registers() {
+        use crate::models::ModbusMAreaAddressing::WordIndex;
+        let word = |text| ModbusAddress::parse_with(text, false, WordIndex);
+        let register = |offset, bit| ModbusAddress { register_type: ModbusRegisterType::HoldingRegister, offset, bit, data_type: None };
+        assert_eq!(word("%MW100").unwrap(), register(100, None));
+        assert_eq!(word("%MW3").unwrap(), register(3, None));
+        assert_eq!(word("%MD7:F").unwrap(), ModbusAddress { data_type: Some(PlcDataType::Float32), ..register(7, None) });
+        assert_eq!(word("%MX10.12").unwrap(), register(10, Some(12)));
+        for invalid in ["%MX10.16", "%MW70000", "%Mé", "%MB10"] {
+            assert!(word(invalid).is_err(), "{} 应解析失败", invalid);
+        }
+        assert_eq!(parse_modbus_address_ex("%MW100", false, WordIndex).unwrap(), (ModbusRegisterType::HoldingRegister, 100));
+    }
+
+    #[test]
+    fn register_bits_decode_and_write_through_read_modify_write() {
+        use crate::models::ByteOrder;
+        let codec = codec(ByteOrder::CDAB, Default::default());
+        let bit = address("40010.3");
+        let request = ReadRequest { id: "r".to_string(), address: "40010.3".to_string(), data_type: PlcDataType::Bool, array_length: None };
+        assert_eq!(codec.plan_read(&bit, &request), Ok((PlcDataType::Bool, 1)));
+
+        let data = BlockData::Words(vec![0x0000, 0x0008]);
+        let item = BlockItem { index: 0, offset: 10, width: 1, bit: Some(3) };
+        assert!(matches!(codec.decode(&data, 9, &item, PlcDataType::Bool, None), Ok(PlcValue::Bool(true))));
+        assert!(matches!(codec.decode(&data, 10, &item, PlcDataType::Bool, None), Ok(PlcValue::Bool(false))));
+
+        assert_eq!(codec.plan_write(&bit, &PlcValue::Bool(true)), Ok(PlannedWrite::RegisterBit { offset: 9, bit: 3, value: true }));
+        assert!(codec.plan_write(&bit, &PlcValue::Float32(1.0)).is_err());
+        assert!(codec.plan_write(&address("30010.3"), &PlcValue::Bool(true)).is_err());
+        assert_eq!(set_register_bit(0x00F0, 3, true), 0x00F8);
+        assert_eq!(set_register_bit(0x00F8, 7, false), 0x0078);
+    }
+
+    #[test]
+    fn type_suffix_decides_register_layout() {
+        use crate::models::ByteOrder;
+        let codec = codec(ByteOrder::ABCD, Default::default());
+        let double = address("40001:D");
+        let request = ReadRequest { id: "r".to_string(), address: "40001:D".to_string(), data_type: PlcDataType::Float32, array_length: None };
+        assert_eq!(codec.plan_read(&double, &request), Ok((PlcDataType::Float64, 4)));
+
+        // 按 Float32 写入声明为 Float64 的地址时按双精度编码
+        let written = match codec.plan_write(&double, &PlcValue::Float32(2.5)) {
+            Ok(PlannedWrite::Block(data)) => data,
+            other => panic!("期望块写入: {:?}", other),
+        };
+        assert_eq!(words(written.clone()), vec![0x4004, 0x0000, 0x0000, 0x0000]);
+        let item = BlockItem { index: 0, offset: 0, width: 4, bit: None };
+        let value = codec.decode(&written, 0, &item, PlcDataType::Float64, None).and_then(|v| convert_value(v, PlcDataType::Float32));
+        assert!(matches!(value, Ok(PlcValue::Float32(v)) if v == 2.5));
+
+        assert!(matches!(convert_value(PlcValue::Float32(7.0), PlcDataType::Int32), Ok(PlcValue::Int32(7))));
+        assert!(convert_value(PlcValue::Float32(7.5), PlcDataType::Int32).is_err());
+        assert!(convert_value(PlcValue::Int32(70_000), PlcDataType::Int16).is_err());
+        assert!(convert_value(PlcValue::Bool(true), PlcDataType::Int16).is_err());
+    }
+
+    #[test]
+    fn modbus_errors_carry_context_and_classify() {
+        use crate::models::enums::FailureKind;
+        let illegal_address = AppError::modbus_exception(0x02, FC_READ_HOLDING_REGISTERS, "40001", "plc-1");
+        let message = illegal_address.to_string();
+        assert!(message.contains("0x03") && message.contains("40001") && message.contains("plc-1"));
+        assert_eq!(illegal_address.failure_kind(), FailureKind::Configuration);
+        assert_eq!(illegal_address.clone().with_context("设置测试台架输出失败").failure_kind(), FailureKind::Configuration);
+
+        let gateway = AppError::modbus_exception(0x0B, FC_READ_COILS, "00001", "plc-1");
+        assert_eq!(gateway.failure_kind(), FailureKind::Communication);
+
+        let timed_out = AppError::modbus_timeout(FC_WRITE_MULTIPLE_REGISTERS, "40010", "plc-1", 500);
+        assert_eq!(timed_out.error_code(), "MODBUS_TIMEOUT");
+        assert_eq!(timed_out.failure_kind(), FailureKind::Communication);
+        assert_eq!(timed_out.with_context("读取被测PLC值失败").failure_kind(), FailureKind::Communication);
+    }
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+    use std::sync::Mutex as StdMutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 极简 Modbus TCP 从站：`holes` 中的寄存器未定义，读到即返回非法数据地址；
+    /// 未写过的寄存器读回其地址值，写请求按到达顺序记录（功能码、起始地址）
+    async fn run_tcp_slave(listener: TcpListener, holes: Vec<u16>, writes: Arc<StdMutex<Vec<(u8, u16)>>>) {
+        let Ok((mut stream, _)) = listener.accept().await else { return };
+        let mut registers: HashMap<u16, u16> = HashMap::new();
+        loop {
+            let mut header = [0u8; 7];
+            if stream.read_exact(&mut header).await.is_err() {
+                return;
+            }
+            let mut pdu = vec![0u8; (u16::from_be_bytes([header[4], header[5]]) as usize).saturating_sub(1)];
+            if pdu.len() < 5 || stream.read_exact(&mut pdu).await.is_err() {
+                return;
+            }
+            let function = pdu[0];
+            let start = u16::from_be_bytes([pdu[1], pdu[2]]);
+            let field = u16::from_be_bytes([pdu[3], pdu[4]]);
+            let count = if matches!(function, 5 | 6) { 1 } else { field };
+            let response = match function {
+                3 | 4 if (start..start + count).any(|address| holes.contains(&address)) => vec![function | 0x80, 0x02],
+                1 | 2 => {
+                    let bytes = count.div_ceil(8) as usize;
+                    [vec![function, bytes as u8], vec![0; bytes]].concat()
+                }
+                3 | 4 => {
+                    let mut response = vec![function, (count * 2) as u8];
+                    for address in start..start + count {
+                        response.extend_from_slice(&registers.get(&address).copied().unwrap_or(address).to_be_bytes());
+                    }
+                    response
+                }
+                5 | 6 | 15 | 16 => {
+                    writes.lock().unwrap().push((function, start));
+                    if function == 6 {
+                        registers.insert(start, field);
+                    }
+                    if function == 16 {
+                        for (i, chunk) in pdu[6..].chunks(2).enumerate() {
+                            registers.insert(start + i as u16, u16::from_be_bytes([chunk[0], chunk[1]]));
+                        }
+                    }
+                    pdu[..5].to_vec()
+                }
+                _ => vec![function | 0x80, 0x01],
+            };
+            let mut frame = header[..4].to_vec();
+            frame.extend_from_slice(&(response.len() as u16 + 1).to_be_bytes());
+            frame.push(header[6]);
+            frame.extend_from_slice(&response);
+            if stream.write_all(&frame).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn connect_slave(holes: Vec<u16>) -> (ModbusTcpPlcService, ConnectionHandle, Arc<StdMutex<Vec<(u8, u16)>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let writes = Arc::new(StdMutex::new(Vec::new()));
+        tokio::spawn(run_tcp_slave(listener, holes, writes.clone()));
+
+        let config = PlcConnectionConfig {
+            id: format!("batch_slave_{}", port),
+            name: "批量读写测试从站".to_string(),
+            protocol: PlcProtocol::ModbusTcp,
+            host: "127.0.0.1".to_string(),
+            port,
+            timeout_ms: 1000,
+            read_timeout_ms: 1000,
+            write_timeout_ms: 1000,
+            byte_order: "ABCD".to_string(),
+            zero_based_address: false,
+            retry_count: 0,
+            retry_interval_ms: 0,
+            protocol_params: HashMap::new(),
+        };
+        let service = ModbusTcpPlcService::new();
+        let handle = service.connect(&config).await.expect("连接测试从站失败");
+        (service, handle, writes)
+    }
+
+    fn read(id: &str, address: &str) -> ReadRequest {
+        ReadRequest { id: id.to_string(), address: address.to_string(), data_type: PlcDataType::Int16, array_length: None }
+    }
+
+    fn write(id: &str, address: &str, value: PlcValue) -> WriteRequest {
+        WriteRequest { id: id.to_string(), address: address.to_string(), value }
+    }
+
+    #[tokio::test]
+    async fn exception_on_bridged_gap_falls_back_to_single_point_reads() {
+        // 寄存器3未定义：40001/40003/40006 合并为跨越它的一个块，40004 正好落在它上面
+        let (service, handle, _) = connect_slave(vec![3]).await;
+        let results = service.batch_read(&handle, &[
+            read("a", "40001"),
+            read("b", "40003"),
+            read("c", "40006"),
+            read("hole", "40004"),
+        ]).await.unwrap();
+
+        let values: Vec<Option<PlcValue>> = results.iter().map(|r| r.value.clone()).collect();
+        assert!(matches!(values[..3], [Some(PlcValue::Int16(0)), Some(PlcValue::Int16(2)), Some(PlcValue::Int16(5))]), "{:?}", values);
+        assert!(!results[3].success);
+        assert!(results[3].error_message.as_deref().is_some_and(|m| m.contains("非法数据地址")), "{:?}", results[3]);
+        assert_eq!(results[3].failure_kind, Some(FailureKind::Configuration));
+    }
+
+    #[tokio::test]
+    async fn batch_writes_go_out_in_request_order() {
+        let (service, handle, writes) = connect_slave(Vec::new()).await;
+        let results = service.batch_write(&handle, &[
+            write("setpoint_lo", "40101", PlcValue::Float32(12.5)),
+            write("setpoint_hi", "40103", PlcValue::Float32(20.0)),
+            write("trigger", "00001", PlcValue::Bool(true)),
+            write("status_bit", "40011.2", PlcValue::Bool(true)),
+            write("mode", "40002", PlcValue::Int16(3)),
+        ]).await.unwrap();
+        assert!(results.iter().all(|r| r.success), "{:?}", results);
+
+        // 两个设定值合并为一次FC16，随后依次是触发线圈、寄存器位的读改写、低地址寄存器
+        assert_eq!(*writes.lock().unwrap(), vec![(16, 100), (5, 0), (6, 10), (16, 1)]);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod rtu_tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_serial::{SerialPort, SerialStream};
+
+    fn crc16(data: &[u8]) -> u16 {
+        let mut crc = 0xFFFFu16;
+        for byte in data {
+            crc ^= *byte as u16;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xA001 } else { crc >> 1 };
+            }
+        }
+        crc
+    }
+
+    /// 请求帧长度（功能码 15/16 需要读到字节数后才能确定）
+    fn request_len(buf: &[u8]) -> Option<usize> {
+        match buf.get(1)? {
+            15 | 16 => buf.get(6).map(|count| 9 + *count as usize),
+            _ => Some(8),
+        }
+    }
+
+    /// 极简 Modbus RTU 从站：线圈/离散输入恒为0，保持寄存器保存写入值
+    fn respond(frame: &[u8], slave_id: u8, registers: &mut HashMap<u16, u16>) -> Option<Vec<u8>> {
+        let (body, crc) = frame.split_at(frame.len() - 2);
+        if body[0] != slave_id || crc16(body).to_le_bytes() != crc {
+            return None;
+        }
+        let start = u16::from_be_bytes([body[2], body[3]]);
+        let quantity = u16::from_be_bytes([body[4], body[5]]);
+        let mut response = vec![slave_id, body[1]];
+        match body[1] {
+            1 | 2 => {
+                let bytes = quantity.div_ceil(8) as u8;
+                response.push(bytes);
+                response.extend(std::iter::repeat(0).take(bytes as usize));
+            }
+            3 | 4 => {
+                response.push((quantity * 2) as u8);
+                for address in start..start + quantity {
+                    response.extend_from_slice(&registers.get(&address).copied().unwrap_or(0).to_be_bytes());
+                }
+            }
+            16 => {
+                for (i, chunk) in body[7..].chunks(2).enumerate() {
+                    registers.insert(start + i as u16, u16::from_be_bytes([chunk[0], chunk[1]]));
+                }
+                response.extend_from_slice(&body[2..6]);
+            }
+            _ => response.extend_from_slice(&body[2..6]),
+        }
+        response.extend_from_slice(&crc16(&response).to_le_bytes());
+        Some(response)
+    }
+
+    async fn run_rtu_slave(mut port: SerialStream, slave_id: u8) {
+        let mut registers = HashMap::new();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 256];
+        let started = std::time::Instant::now();
+        let mut opened = false;
+        loop {
+            let n = match port.read(&mut chunk).await {
+                Ok(n) if n > 0 => {
+                    opened = true;
+                    n
+                }
+                // 从端尚未被打开时读取主端返回EIO，限定时间内稍后重试
+                _ if !opened && started.elapsed() < Duration::from_secs(5) => {
+                    sleep(Duration::from_millis(10)).await;
+                    continue;
+                }
+                // 从端已关闭或读取出错，结束从站任务
+                _ => return,
+            };
+            buf.extend_from_slice(&chunk[..n]);
+            while let Some(len) = request_len(&buf).filter(|len| buf.len() >= *len) {
+                let frame: Vec<u8> = buf.drain(..len).collect();
+                if let Some(response) = respond(&frame, slave_id, &mut registers) {
+                    if port.write_all(&response).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 通过伪终端对验证RTU传输：地址解析与字节序处理与TCP一致
+    #[tokio::test]
+    async fn modbus_rtu_over_pty_pair() {
+        // 关闭从端，由被测服务按设备路径独占打开
+        let (master, slave) = SerialStream::pair().expect("创建伪终端失败");
+        let device_path = slave.name().expect("伪终端设备名");
+        drop(slave);
+        tokio::spawn(run_rtu_slave(master, 3));
+
+        let serial = crate::models::SerialPortSettings {
+            device_path,
+            slave_id: 3,
+            ..Default::default()
+        };
+        let config = PlcConnectionConfig {
+            id: "rtu_pty".to_string(),
+            name: "RTU伪终端".to_string(),
+            protocol: PlcProtocol::ModbusRtu,
+            host: String::new(),
+            port: 0,
+            timeout_ms: 1000,
+            read_timeout_ms: 1000,
+            write_timeout_ms: 1000,
+            byte_order: "CDAB".to_string(),
+            zero_based_address: false,
+            retry_count: 0,
+            retry_interval_ms: 0,
+            protocol_params: serial.to_protocol_params(),
+        };
+
+        let service = ModbusTcpPlcService::new();
+        let handle = service.connect(&config).await.expect("RTU连接失败");
+
+        service.write_f32(&handle, "40101", 12.5).await.expect("写入浮点数失败");
+        assert_eq!(service.read_f32(&handle, "40101").await.expect("读取浮点数失败"), 12.5);
+        service.write_i32(&handle, "40103", -42).await.expect("写入整数失败");
+        assert_eq!(service.read_i32(&handle, "40103").await.expect("读取整数失败"), -42);
+        assert!(!service.read_bool(&handle, "00001").await.expect("读取线圈失败"));
+    }
+}
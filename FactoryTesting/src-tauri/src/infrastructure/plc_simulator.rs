@@ -0,0 +1,1460 @@
+//! # 内置PLC仿真器模块 (虚拟测试台架)
+//!
+//! ## 业务作用
+//! 在没有实体PLC的环境中（演示、培训、端到端回归测试）同时仿真测试PLC与被测PLC：
+//! - 在本机为两侧各启动一个 Modbus TCP 从站，测试PLC连接与被测PLC连接分别指向它们
+//! - 按测试PLC通道表与通道映射（或批次测试实例的通道分配）建立信号回环，
+//!   测试PLC AO/DO 的写入回环到被测PLC AI/DI，被测PLC AO/DO 的写入回环到测试PLC AI/DI
+//! - 回环信号支持增益、偏置、噪声、延迟，并可按位号注入断线、卡死、反相、偏移、无应答故障
+//! - 根据 SLL/SL/SH/SHH 设定值仿真被测PLC的报警反馈位
+//!
+//! ## 信号约定（与测试执行器一致）
+//! ```text
+//! AI: 测试PLC AO(量程百分比 0~100) → 被测PLC AI(工程值 = 下限 + 百分比 × 量程)
+//! AO: 被测PLC AO(工程值)           → 测试PLC AI(工程值)
+//! DI: 测试PLC DO                   → 被测PLC DI
+//! DO: 被测PLC DO                   → 测试PLC DI
+//! ```
+//!
+//! ## 支持的功能码
+//! 01/02/03/04 读，05/06/15/16 写；其余功能码返回异常码 01（非法功能）
+//!
+//! ## 报文回放
+//! 可载入批次的Modbus录制报文：收到与录制相同的请求时按录制顺序返回当时的应答
+//! （读到的数据、异常码或不应答），录制用尽后恢复正常仿真，用于离线复现一次测试过程
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::domain::services::ITestPlcConfigService;
+use crate::infrastructure::modbus_capture::{FrameOutcome, FrameValues, ReplayScript};
+use crate::infrastructure::plc_communication::{parse_modbus_address_ex, ByteOrderConverter, ModbusRegisterType};
+use crate::infrastructure::IPersistenceService;
+use crate::models::test_plc_config::{ChannelMappingConfig, GetTestPlcChannelsRequest, PlcConnectionConfig, TestPlcChannelConfig};
+use crate::models::{ByteOrder, ChannelPointDefinition, ChannelTestInstance, ModbusMAreaAddressing, ModuleType};
+use crate::utils::config::SimulatorConfig;
+use crate::utils::error::{AppError, AppResult};
+
+/// 断线时模拟量回环值（占量程比例），对应 4-20mA 信号断线后的 0mA
+const OPEN_CIRCUIT_FRACTION: f32 = -0.25;
+/// 量程缺失或无效时使用的默认量程
+const DEFAULT_RANGE: (f32, f32) = (0.0, 100.0);
+/// 单次读取的最大线圈/离散输入数量（Modbus规范）
+const MAX_READ_BITS: u16 = 2000;
+/// 单次读取的最大寄存器数量（Modbus规范）
+const MAX_READ_REGISTERS: u16 = 125;
+
+/// 仿真PLC侧
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SimulatedSide {
+    /// 测试PLC（测试台架）
+    TestRig,
+    /// 被测PLC
+    Target,
+}
+
+/// 仿真PLC的地址编码参数，需与对应PLC连接配置保持一致
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SideLayout {
+    pub byte_order: ByteOrder,
+    pub zero_based_address: bool,
+    pub m_area_addressing: ModbusMAreaAddressing,
+}
+
+impl SideLayout {
+    /// 从PLC连接配置提取字节序与地址模式
+    pub fn from_connection(connection: &PlcConnectionConfig) -> Self {
+        Self {
+            byte_order: ByteOrder::from_str(&connection.byte_order).unwrap_or_default(),
+            zero_based_address: connection.zero_based_address,
+            m_area_addressing: connection.modbus_data_settings.as_ref().map(|d| d.m_area_addressing).unwrap_or_default(),
+        }
+    }
+}
+
+/// 回环信号的换算方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LoopbackKind {
+    /// 源为量程百分比(0~100)，汇为工程值（AI通道）
+    PercentToEngineering { low: f32, high: f32 },
+    /// 源与汇均为工程值（AO通道）
+    Engineering { low: f32, high: f32 },
+    /// 数字量直通（DI/DO通道）
+    Digital,
+}
+
+impl LoopbackKind {
+    fn range(&self) -> (f32, f32) {
+        match *self {
+            LoopbackKind::PercentToEngineering { low, high } | LoopbackKind::Engineering { low, high } => (low, high),
+            LoopbackKind::Digital => DEFAULT_RANGE,
+        }
+    }
+}
+
+/// 一条信号回环：源侧地址的写入经换算后出现在汇侧地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopbackRoute {
+    /// 被测点位位号，用于故障注入
+    pub tag: String,
+    pub source_side: SimulatedSide,
+    pub source_address: String,
+    pub sink_side: SimulatedSide,
+    pub sink_address: String,
+    pub kind: LoopbackKind,
+}
+
+impl LoopbackRoute {
+    /// 按被测点位的模块类型确定回环方向
+    ///
+    /// 返回 `None` 表示该点位没有硬接线回路（如 AINone 等通讯点）
+    pub fn for_definition(definition: &ChannelPointDefinition, test_rig_address: &str) -> Option<Self> {
+        let (low, high) = match (definition.range_low_limit, definition.range_high_limit) {
+            (Some(low), Some(high)) if high > low => (low, high),
+            _ => DEFAULT_RANGE,
+        };
+        let (source_side, source_address, sink_side, sink_address, kind) = match definition.module_type {
+            ModuleType::AI => (
+                SimulatedSide::TestRig, test_rig_address, SimulatedSide::Target, definition.plc_communication_address.as_str(),
+                LoopbackKind::PercentToEngineering { low, high },
+            ),
+            ModuleType::AO => (
+                SimulatedSide::Target, definition.plc_communication_address.as_str(), SimulatedSide::TestRig, test_rig_address,
+                LoopbackKind::Engineering { low, high },
+            ),
+            ModuleType::DI => (
+                SimulatedSide::TestRig, test_rig_address, SimulatedSide::Target, definition.plc_communication_address.as_str(),
+                LoopbackKind::Digital,
+            ),
+            ModuleType::DO => (
+                SimulatedSide::Target, definition.plc_communication_address.as_str(), SimulatedSide::TestRig, test_rig_address,
+                LoopbackKind::Digital,
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            tag: definition.tag.clone(),
+            source_side,
+            source_address: source_address.to_string(),
+            sink_side,
+            sink_address: sink_address.to_string(),
+            kind,
+        })
+    }
+}
+
+/// 可按位号注入的仿真故障
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SimulatedFault {
+    /// 断线：模拟量回环为量程下限以下（-25%），数字量恒为断开
+    OpenCircuit,
+    /// 卡死：模拟量恒为指定工程值，数字量非0即为接通
+    StuckAt { value: f32 },
+    /// 反相：模拟量按量程镜像，数字量取反
+    Inverted,
+    /// 偏移：模拟量叠加指定的量程百分比偏移
+    Offset { percent: f32 },
+    /// 无应答：涉及该点位地址的请求不返回应答，客户端表现为超时
+    NoResponse,
+}
+
+/// 仿真器运行状态快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatorStatus {
+    pub test_rig_endpoint: String,
+    pub target_endpoint: String,
+    pub route_count: usize,
+    pub alarm_count: usize,
+    pub faults: HashMap<String, SimulatedFault>,
+    /// 尚未回放的录制应答数
+    pub replay_remaining: usize,
+}
+
+/// 已解析的寄存器位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Point {
+    side: SimulatedSide,
+    register: ModbusRegisterType,
+    offset: u16,
+}
+
+impl Point {
+    /// 32位数值占两个寄存器，位类型占一个
+    fn width(&self) -> u32 {
+        match self.register {
+            ModbusRegisterType::HoldingRegister | ModbusRegisterType::InputRegister => 2,
+            ModbusRegisterType::Coil | ModbusRegisterType::DiscreteInput => 1,
+        }
+    }
+
+    fn overlaps(&self, side: SimulatedSide, register: ModbusRegisterType, start: u16, count: u16) -> bool {
+        let (begin, end) = (start as u32, start as u32 + count as u32);
+        self.side == side && self.register == register
+            && (self.offset as u32) < end && begin < self.offset as u32 + self.width()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ResolvedRoute {
+    route: LoopbackRoute,
+    source: Point,
+    sink: Point,
+    /// 最近一次源侧写入值（数字量以0/1表示），注入故障后据此立即刷新汇侧
+    last_source: Option<f32>,
+    sequence: u64,
+    applied: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AlarmLevel {
+    LowLow,
+    Low,
+    High,
+    HighHigh,
+}
+
+impl AlarmLevel {
+    fn is_high(&self) -> bool {
+        matches!(self, AlarmLevel::High | AlarmLevel::HighHigh)
+    }
+}
+
+/// 被测PLC报警点：过程值越过设定值时置位反馈位
+#[derive(Debug, Clone)]
+struct AlarmPoint {
+    tag: String,
+    level: AlarmLevel,
+    pv: Point,
+    /// 设定值地址（点表地址与通讯地址），任一地址写入后同步到其余地址
+    setpoints: Vec<Point>,
+    feedbacks: Vec<Point>,
+    initial_setpoint: f32,
+    installed: bool,
+}
+
+/// 仿真模型：两侧地址编码参数、信号回环与报警点
+#[derive(Debug, Clone, Default)]
+pub struct SimulatorModel {
+    test_rig: SideLayout,
+    target: SideLayout,
+    routes: Vec<ResolvedRoute>,
+    alarms: Vec<AlarmPoint>,
+}
+
+impl SimulatorModel {
+    pub fn new(test_rig: SideLayout, target: SideLayout) -> Self {
+        Self { test_rig, target, routes: Vec::new(), alarms: Vec::new() }
+    }
+
+    /// 由测试PLC通道表与通道映射建立回环，并为全部AI点位建立报警仿真
+    ///
+    /// 映射引用的通道或点位不存在、地址无法解析时跳过该映射并记录警告
+    pub fn from_tables(
+        test_rig: SideLayout,
+        target: SideLayout,
+        channels: &[TestPlcChannelConfig],
+        mappings: &[ChannelMappingConfig],
+        definitions: &[ChannelPointDefinition],
+    ) -> Self {
+        let mut model = Self::new(test_rig, target);
+        for mapping in mappings.iter().filter(|m| m.is_active) {
+            let channel = channels.iter().find(|c| c.id.as_deref() == Some(mapping.test_plc_channel_id.as_str()));
+            let definition = definitions.iter().find(|d| d.id == mapping.target_channel_id);
+            let (Some(channel), Some(definition)) = (channel, definition) else {
+                log::warn!("⚠️ [PLC_SIM] 通道映射 {} 引用的通道不存在，已跳过", mapping.id);
+                continue;
+            };
+            model.add_definition(definition, &channel.communication_address);
+        }
+        for definition in definitions {
+            model.add_alarm_points(definition);
+        }
+        model
+    }
+
+    /// 按批次测试实例的通道分配补充回环，返回新增或更新的回环数量
+    pub fn add_instance_routes(&mut self, instances: &[ChannelTestInstance], definitions: &[ChannelPointDefinition]) -> usize {
+        let mut count = 0;
+        for instance in instances {
+            let Some(definition) = definitions.iter().find(|d| d.id == instance.definition_id) else {
+                continue;
+            };
+            if let Some(address) = instance.test_plc_communication_address.as_deref() {
+                if self.add_definition(definition, address) {
+                    count += 1;
+                }
+            }
+            self.add_alarm_points(definition);
+        }
+        count
+    }
+
+    /// 添加一条回环；汇侧地址相同的旧回环被替换
+    pub fn add_route(&mut self, route: LoopbackRoute) -> AppResult<()> {
+        let source = self.resolve(route.source_side, &route.source_address)?;
+        let sink = self.resolve(route.sink_side, &route.sink_address)?;
+        let resolved = ResolvedRoute { route, source, sink, last_source: None, sequence: 0, applied: 0 };
+        match self.routes.iter_mut().find(|r| r.sink == sink) {
+            Some(existing) => *existing = resolved,
+            None => self.routes.push(resolved),
+        }
+        Ok(())
+    }
+
+    pub fn route_count(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn alarm_count(&self) -> usize {
+        self.alarms.len()
+    }
+
+    fn add_definition(&mut self, definition: &ChannelPointDefinition, test_rig_address: &str) -> bool {
+        let Some(route) = LoopbackRoute::for_definition(definition, test_rig_address) else {
+            return false;
+        };
+        match self.add_route(route) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("⚠️ [PLC_SIM] 点位 {} 回环地址无效，已跳过: {}", definition.tag, e);
+                false
+            }
+        }
+    }
+
+    /// 为AI点位的SLL/SL/SH/SHH建立报警仿真，无法解析为Modbus地址的设定点/反馈位被忽略
+    fn add_alarm_points(&mut self, definition: &ChannelPointDefinition) {
+        if definition.module_type != ModuleType::AI {
+            return;
+        }
+        let Ok(pv) = self.resolve(SimulatedSide::Target, &definition.plc_communication_address) else {
+            return;
+        };
+        let levels = [
+            (AlarmLevel::LowLow, definition.sll_set_value, [&definition.sll_set_point_address, &definition.sll_set_point_communication_address], [&definition.sll_feedback_address, &definition.sll_feedback_communication_address]),
+            (AlarmLevel::Low, definition.sl_set_value, [&definition.sl_set_point_address, &definition.sl_set_point_communication_address], [&definition.sl_feedback_address, &definition.sl_feedback_communication_address]),
+            (AlarmLevel::High, definition.sh_set_value, [&definition.sh_set_point_address, &definition.sh_set_point_communication_address], [&definition.sh_feedback_address, &definition.sh_feedback_communication_address]),
+            (AlarmLevel::HighHigh, definition.shh_set_value, [&definition.shh_set_point_address, &definition.shh_set_point_communication_address], [&definition.shh_feedback_address, &definition.shh_feedback_communication_address]),
+        ];
+        for (level, set_value, setpoint_addresses, feedback_addresses) in levels {
+            let Some(set_value) = set_value else { continue };
+            if self.alarms.iter().any(|a| a.tag == definition.tag && a.level == level) {
+                continue;
+            }
+            let setpoints: Vec<Point> = self.resolve_all(&setpoint_addresses)
+                .into_iter()
+                .filter(|p| p.width() == 2)
+                .collect();
+            let feedbacks: Vec<Point> = self.resolve_all(&feedback_addresses);
+            if setpoints.is_empty() || feedbacks.is_empty() {
+                continue;
+            }
+            self.alarms.push(AlarmPoint {
+                tag: definition.tag.clone(),
+                level,
+                pv,
+                setpoints,
+                feedbacks,
+                initial_setpoint: set_value,
+                installed: false,
+            });
+        }
+    }
+
+    fn resolve_all(&self, addresses: &[&Option<String>]) -> Vec<Point> {
+        let mut points: Vec<Point> = addresses.iter()
+            .filter_map(|a| a.as_deref())
+            .filter_map(|a| self.resolve(SimulatedSide::Target, a).ok())
+            .collect();
+        points.dedup();
+        points
+    }
+
+    fn layout(&self, side: SimulatedSide) -> SideLayout {
+        match side {
+            SimulatedSide::TestRig => self.test_rig,
+            SimulatedSide::Target => self.target,
+        }
+    }
+
+    fn resolve(&self, side: SimulatedSide, address: &str) -> AppResult<Point> {
+        let layout = self.layout(side);
+        let (register, offset) = parse_modbus_address_ex(address.trim(), layout.zero_based_address, layout.m_area_addressing)?;
+        Ok(Point { side, register, offset })
+    }
+}
+
+/// 单侧仿真PLC的存储区，未写入的地址读为0
+#[derive(Debug, Default)]
+struct RegisterBank {
+    coils: HashMap<u16, bool>,
+    discrete_inputs: HashMap<u16, bool>,
+    holding_registers: HashMap<u16, u16>,
+    input_registers: HashMap<u16, u16>,
+}
+
+impl RegisterBank {
+    fn bits(&mut self, register: ModbusRegisterType) -> Option<&mut HashMap<u16, bool>> {
+        match register {
+            ModbusRegisterType::Coil => Some(&mut self.coils),
+            ModbusRegisterType::DiscreteInput => Some(&mut self.discrete_inputs),
+            _ => None,
+        }
+    }
+
+    fn words(&mut self, register: ModbusRegisterType) -> Option<&mut HashMap<u16, u16>> {
+        match register {
+            ModbusRegisterType::HoldingRegister => Some(&mut self.holding_registers),
+            ModbusRegisterType::InputRegister => Some(&mut self.input_registers),
+            _ => None,
+        }
+    }
+
+    fn read_bits(&mut self, register: ModbusRegisterType, start: u16, count: u16) -> Vec<bool> {
+        let bits = self.bits(register).expect("位类型存储区");
+        (0..count).map(|i| bits.get(&start.wrapping_add(i)).copied().unwrap_or(false)).collect()
+    }
+
+    fn read_words(&mut self, register: ModbusRegisterType, start: u16, count: u16) -> Vec<u16> {
+        let words = self.words(register).expect("寄存器类型存储区");
+        (0..count).map(|i| words.get(&start.wrapping_add(i)).copied().unwrap_or(0)).collect()
+    }
+
+    fn get_bool(&mut self, point: &Point) -> bool {
+        match point.register {
+            ModbusRegisterType::Coil | ModbusRegisterType::DiscreteInput => self.read_bits(point.register, point.offset, 1)[0],
+            _ => self.read_words(point.register, point.offset, 1)[0] != 0,
+        }
+    }
+
+    fn set_bool(&mut self, point: &Point, value: bool) {
+        if let Some(bits) = self.bits(point.register) {
+            bits.insert(point.offset, value);
+        } else if let Some(words) = self.words(point.register) {
+            words.insert(point.offset, value as u16);
+        }
+    }
+
+    fn get_f32(&mut self, point: &Point, order: ByteOrder) -> f32 {
+        match point.register {
+            ModbusRegisterType::HoldingRegister | ModbusRegisterType::InputRegister => {
+                let regs = self.read_words(point.register, point.offset, 2);
+                ByteOrderConverter::registers_to_float(regs[0], regs[1], order)
+            }
+            _ => if self.get_bool(point) { 1.0 } else { 0.0 },
+        }
+    }
+
+    fn set_f32(&mut self, point: &Point, value: f32, order: ByteOrder) {
+        match self.words(point.register) {
+            Some(words) => {
+                let (reg1, reg2) = ByteOrderConverter::float_to_registers(value, order);
+                words.insert(point.offset, reg1);
+                words.insert(point.offset.wrapping_add(1), reg2);
+            }
+            None => self.set_bool(point, value != 0.0),
+        }
+    }
+}
+
+/// 延迟生效的回环更新
+#[derive(Debug, Clone, Copy)]
+struct SinkUpdate {
+    route: usize,
+    sequence: u64,
+    source_value: f32,
+}
+
+struct SimulatorState {
+    config: SimulatorConfig,
+    model: SimulatorModel,
+    test_rig: RegisterBank,
+    target: RegisterBank,
+    faults: HashMap<String, SimulatedFault>,
+    replay: HashMap<SimulatedSide, ReplayScript>,
+}
+
+/// 读请求的应答PDU：线圈/离散输入按位打包，寄存器按大端
+fn read_response(function: u8, values: &FrameValues) -> Option<Vec<u8>> {
+    let mut response = vec![function];
+    match (function, values) {
+        (0x01 | 0x02, FrameValues::Bits(bits)) => {
+            let mut packed = vec![0u8; bits.len().div_ceil(8)];
+            for (i, bit) in bits.iter().enumerate() {
+                packed[i / 8] |= (*bit as u8) << (i % 8);
+            }
+            response.push(packed.len() as u8);
+            response.extend(packed);
+        }
+        (0x03 | 0x04, FrameValues::Words(words)) => {
+            response.push((words.len() * 2) as u8);
+            response.extend(words.iter().flat_map(|w| w.to_be_bytes()));
+        }
+        _ => return None,
+    }
+    Some(response)
+}
+
+impl SimulatorState {
+    fn new(config: SimulatorConfig, model: SimulatorModel) -> Self {
+        let mut state = Self {
+            config,
+            model,
+            test_rig: RegisterBank::default(),
+            target: RegisterBank::default(),
+            faults: HashMap::new(),
+            replay: HashMap::new(),
+        };
+        state.install_alarms();
+        state
+    }
+
+    fn bank(&mut self, side: SimulatedSide) -> &mut RegisterBank {
+        match side {
+            SimulatedSide::TestRig => &mut self.test_rig,
+            SimulatedSide::Target => &mut self.target,
+        }
+    }
+
+    /// 新加入的报警点写入初始设定值
+    fn install_alarms(&mut self) {
+        let order = self.model.target.byte_order;
+        for alarm in self.model.alarms.iter_mut().filter(|a| !a.installed) {
+            for setpoint in &alarm.setpoints {
+                self.target.set_f32(setpoint, alarm.initial_setpoint, order);
+            }
+            alarm.installed = true;
+        }
+        self.refresh_alarms();
+    }
+
+    /// 按过程值与设定值重新计算全部报警反馈位
+    fn refresh_alarms(&mut self) {
+        let order = self.model.target.byte_order;
+        for alarm in &self.model.alarms {
+            let pv = self.target.get_f32(&alarm.pv, order);
+            let setpoint = self.target.get_f32(&alarm.setpoints[0], order);
+            let active = if alarm.level.is_high() { pv >= setpoint } else { pv <= setpoint };
+            for feedback in &alarm.feedbacks {
+                self.target.set_bool(feedback, active);
+            }
+        }
+    }
+
+    /// 客户端写入后：同步报警设定值，并为以写入地址为源的回环生成更新
+    fn after_write(&mut self, side: SimulatedSide, register: ModbusRegisterType, start: u16, count: u16) -> Vec<SinkUpdate> {
+        if side == SimulatedSide::Target {
+            let order = self.model.target.byte_order;
+            for alarm in &self.model.alarms {
+                let Some(written) = alarm.setpoints.iter().find(|p| p.overlaps(side, register, start, count)) else {
+                    continue;
+                };
+                let value = self.target.get_f32(written, order);
+                for other in alarm.setpoints.iter().filter(|p| *p != written) {
+                    self.target.set_f32(other, value, order);
+                }
+            }
+        }
+
+        let order = self.model.layout(side).byte_order;
+        let mut updates = Vec::new();
+        for index in 0..self.model.routes.len() {
+            let source = self.model.routes[index].source;
+            if !source.overlaps(side, register, start, count) {
+                continue;
+            }
+            let source_value = match self.model.routes[index].route.kind {
+                LoopbackKind::Digital => if self.bank(side).get_bool(&source) { 1.0 } else { 0.0 },
+                _ => self.bank(side).get_f32(&source, order),
+            };
+            let route = &mut self.model.routes[index];
+            route.last_source = Some(source_value);
+            route.sequence += 1;
+            updates.push(SinkUpdate { route: index, sequence: route.sequence, source_value });
+        }
+        updates
+    }
+
+    /// 写入汇侧地址；先于该更新发出的写入已生效时丢弃过期更新
+    fn apply_update(&mut self, update: SinkUpdate) {
+        let Some(route) = self.model.routes.get(update.route) else { return };
+        if update.sequence < route.applied {
+            return;
+        }
+        let (sink, kind) = (route.sink, route.route.kind);
+        let value = self.sink_value(&self.model.routes[update.route], update.source_value);
+        self.model.routes[update.route].applied = update.sequence;
+        let order = self.model.layout(sink.side).byte_order;
+        match kind {
+            LoopbackKind::Digital => self.bank(sink.side).set_bool(&sink, value != 0.0),
+            _ => self.bank(sink.side).set_f32(&sink, value, order),
+        }
+        if sink.side == SimulatedSide::Target {
+            self.refresh_alarms();
+        }
+    }
+
+    /// 按增益、偏置、噪声与注入故障计算汇侧值
+    fn sink_value(&self, route: &ResolvedRoute, source_value: f32) -> f32 {
+        let fault = self.faults.get(&route.route.tag);
+        if route.route.kind == LoopbackKind::Digital {
+            let on = match fault {
+                Some(SimulatedFault::OpenCircuit) => false,
+                Some(SimulatedFault::StuckAt { value }) => *value != 0.0,
+                Some(SimulatedFault::Inverted) => source_value == 0.0,
+                _ => source_value != 0.0,
+            };
+            return if on { 1.0 } else { 0.0 };
+        }
+
+        let (low, high) = route.route.kind.range();
+        let span = high - low;
+        let engineering = match route.route.kind {
+            LoopbackKind::PercentToEngineering { .. } => low + source_value / 100.0 * span,
+            _ => source_value,
+        };
+        let mut value = low + (engineering - low) * self.config.gain + self.config.offset_percent / 100.0 * span;
+        if self.config.noise_percent > 0.0 {
+            let noise = self.config.noise_percent / 100.0 * span;
+            value += rand::thread_rng().gen_range(-noise..=noise);
+        }
+        match fault {
+            Some(SimulatedFault::OpenCircuit) => low + OPEN_CIRCUIT_FRACTION * span,
+            Some(SimulatedFault::StuckAt { value }) => *value,
+            Some(SimulatedFault::Inverted) => low + high - value,
+            Some(SimulatedFault::Offset { percent }) => value + percent / 100.0 * span,
+            _ => value,
+        }
+    }
+
+    /// 故障变化后按最近一次源值立即刷新对应回环
+    fn reapply_tag(&mut self, tag: &str) {
+        for index in 0..self.model.routes.len() {
+            let route = &self.model.routes[index];
+            if route.route.tag != tag {
+                continue;
+            }
+            if let Some(source_value) = route.last_source {
+                let sequence = route.sequence;
+                self.apply_update(SinkUpdate { route: index, sequence, source_value });
+            }
+        }
+    }
+
+    /// 请求是否涉及注入了无应答故障的点位
+    fn is_silenced(&self, side: SimulatedSide, register: ModbusRegisterType, start: u16, count: u16) -> bool {
+        let silenced = |tag: &String| matches!(self.faults.get(tag), Some(SimulatedFault::NoResponse));
+        let route_hit = self.model.routes.iter()
+            .filter(|r| silenced(&r.route.tag))
+            .any(|r| [r.source, r.sink].iter().any(|p| p.overlaps(side, register, start, count)));
+        let alarm_hit = self.model.alarms.iter()
+            .filter(|a| silenced(&a.tag))
+            .any(|a| std::iter::once(&a.pv).chain(&a.setpoints).chain(&a.feedbacks)
+                .any(|p| p.overlaps(side, register, start, count)));
+        route_hit || alarm_hit
+    }
+
+    /// 处理一个Modbus PDU，返回应答PDU（`None` 表示不应答）与待延迟生效的回环更新
+    fn handle_pdu(&mut self, side: SimulatedSide, pdu: &[u8]) -> (Option<Vec<u8>>, Vec<SinkUpdate>) {
+        let function = pdu[0];
+        let exception = |code: u8| (Some(vec![function | 0x80, code]), Vec::new());
+        let register = match function {
+            0x01 | 0x05 | 0x0F => ModbusRegisterType::Coil,
+            0x02 => ModbusRegisterType::DiscreteInput,
+            0x03 | 0x06 | 0x10 => ModbusRegisterType::HoldingRegister,
+            0x04 => ModbusRegisterType::InputRegister,
+            _ => return exception(0x01),
+        };
+        if pdu.len() < 5 {
+            return exception(0x03);
+        }
+        let start = u16::from_be_bytes([pdu[1], pdu[2]]);
+        let field = u16::from_be_bytes([pdu[3], pdu[4]]);
+        let count = match function {
+            0x05 | 0x06 => 1,
+            _ => field,
+        };
+        if start as u32 + count as u32 > 0x1_0000 {
+            return exception(0x02);
+        }
+        if self.is_silenced(side, register, start, count) {
+            return (None, Vec::new());
+        }
+        // 回放录制应答；写请求的录制应答为正常时照常写入，保持回环与报警仿真
+        match self.replay.get_mut(&side).and_then(|script| script.next_outcome(function, start, count)) {
+            Some(FrameOutcome::Exception(code)) => return exception(code),
+            Some(FrameOutcome::Timeout | FrameOutcome::LinkError(_)) => return (None, Vec::new()),
+            Some(FrameOutcome::Response(values)) => {
+                if let Some(response) = read_response(function, &values) {
+                    return (Some(response), Vec::new());
+                }
+            }
+            None => {}
+        }
+
+        let mut response = vec![function];
+        let mut updates = Vec::new();
+        match function {
+            0x01 | 0x02 => {
+                if count == 0 || count > MAX_READ_BITS {
+                    return exception(0x03);
+                }
+                let bits = self.bank(side).read_bits(register, start, count);
+                response = read_response(function, &FrameValues::Bits(bits)).expect("位读取应答");
+            }
+            0x03 | 0x04 => {
+                if count == 0 || count > MAX_READ_REGISTERS {
+                    return exception(0x03);
+                }
+                let words = self.bank(side).read_words(register, start, count);
+                response = read_response(function, &FrameValues::Words(words)).expect("寄存器读取应答");
+            }
+            0x05 => {
+                let value = match field {
+                    0xFF00 => true,
+                    0x0000 => false,
+                    _ => return exception(0x03),
+                };
+                self.bank(side).set_bool(&Point { side, register, offset: start }, value);
+                updates = self.after_write(side, register, start, 1);
+                response.extend_from_slice(&pdu[1..5]);
+            }
+            0x06 => {
+                self.bank(side).words(register).expect("保持寄存器").insert(start, field);
+                updates = self.after_write(side, register, start, 1);
+                response.extend_from_slice(&pdu[1..5]);
+            }
+            0x0F => {
+                let data = &pdu[5.min(pdu.len())..];
+                if count == 0 || data.is_empty() || data[0] as usize != (count as usize).div_ceil(8) || data.len() < 1 + data[0] as usize {
+                    return exception(0x03);
+                }
+                let bits = self.bank(side).bits(register).expect("线圈");
+                for i in 0..count {
+                    let byte = data[1 + (i / 8) as usize];
+                    bits.insert(start + i, byte & (1 << (i % 8)) != 0);
+                }
+                updates = self.after_write(side, register, start, count);
+                response.extend_from_slice(&pdu[1..5]);
+            }
+            0x10 => {
+                let data = &pdu[5.min(pdu.len())..];
+                if count == 0 || data.is_empty() || data[0] as usize != count as usize * 2 || data.len() < 1 + data[0] as usize {
+                    return exception(0x03);
+                }
+                let words = self.bank(side).words(register).expect("保持寄存器");
+                for i in 0..count {
+                    let at = 1 + i as usize * 2;
+                    words.insert(start + i, u16::from_be_bytes([data[at], data[at + 1]]));
+                }
+                updates = self.after_write(side, register, start, count);
+                response.extend_from_slice(&pdu[1..5]);
+            }
+            _ => unreachable!(),
+        }
+
+        if side == SimulatedSide::Target && matches!(function, 0x05 | 0x06 | 0x0F | 0x10) {
+            self.refresh_alarms();
+        }
+        if self.config.delay_ms == 0 {
+            for update in updates.drain(..) {
+                self.apply_update(update);
+            }
+        }
+        (Some(response), updates)
+    }
+}
+
+type SharedState = Arc<Mutex<SimulatorState>>;
+
+fn lock(state: &SharedState) -> MutexGuard<'_, SimulatorState> {
+    state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// 内置PLC仿真器
+///
+/// 启动后测试PLC与被测PLC各监听一个本机端口，停止时（或被释放时）关闭监听
+pub struct PlcSimulator {
+    state: SharedState,
+    test_rig_endpoint: SocketAddr,
+    target_endpoint: SocketAddr,
+    listeners: Vec<JoinHandle<()>>,
+}
+
+impl PlcSimulator {
+    /// 按配置端口启动两侧Modbus TCP从站
+    pub async fn start(config: SimulatorConfig, model: SimulatorModel) -> AppResult<Self> {
+        let bind = |port: u16| async move {
+            TcpListener::bind(("127.0.0.1", port)).await
+                .map_err(|e| AppError::plc_communication_error(format!("PLC仿真器监听端口{}失败: {}", port, e)))
+        };
+        let test_rig_listener = bind(config.test_rig_port).await?;
+        let target_listener = bind(config.target_port).await?;
+        let test_rig_endpoint = test_rig_listener.local_addr()
+            .map_err(|e| AppError::plc_communication_error(format!("获取仿真器地址失败: {}", e)))?;
+        let target_endpoint = target_listener.local_addr()
+            .map_err(|e| AppError::plc_communication_error(format!("获取仿真器地址失败: {}", e)))?;
+
+        log::info!("🧪 [PLC_SIM] 仿真器已启动 - 测试PLC: {}, 被测PLC: {}, 回环 {} 条, 报警点 {} 个",
+                   test_rig_endpoint, target_endpoint, model.route_count(), model.alarm_count());
+
+        let state = Arc::new(Mutex::new(SimulatorState::new(config, model)));
+        let listeners = vec![
+            tokio::spawn(accept_loop(test_rig_listener, SimulatedSide::TestRig, state.clone())),
+            tokio::spawn(accept_loop(target_listener, SimulatedSide::Target, state.clone())),
+        ];
+        Ok(Self { state, test_rig_endpoint, target_endpoint, listeners })
+    }
+
+    pub fn endpoint(&self, side: SimulatedSide) -> SocketAddr {
+        match side {
+            SimulatedSide::TestRig => self.test_rig_endpoint,
+            SimulatedSide::Target => self.target_endpoint,
+        }
+    }
+
+    /// 按批次测试实例补充回环与报警点，返回新增或更新的回环数量
+    pub fn add_instance_routes(&self, instances: &[ChannelTestInstance], definitions: &[ChannelPointDefinition]) -> usize {
+        let mut state = lock(&self.state);
+        let count = state.model.add_instance_routes(instances, definitions);
+        state.install_alarms();
+        count
+    }
+
+    /// 对指定位号注入故障，已有回环按最近一次源值立即刷新
+    pub fn set_fault(&self, tag: &str, fault: SimulatedFault) {
+        log::info!("🧪 [PLC_SIM] 注入故障: {} → {:?}", tag, fault);
+        let mut state = lock(&self.state);
+        state.faults.insert(tag.to_string(), fault);
+        state.reapply_tag(tag);
+    }
+
+    pub fn clear_fault(&self, tag: &str) {
+        let mut state = lock(&self.state);
+        if state.faults.remove(tag).is_some() {
+            state.reapply_tag(tag);
+        }
+    }
+
+    pub fn clear_faults(&self) {
+        let mut state = lock(&self.state);
+        let tags: Vec<String> = state.faults.drain().map(|(tag, _)| tag).collect();
+        for tag in tags {
+            state.reapply_tag(&tag);
+        }
+    }
+
+    /// 载入一侧的录制报文，替换该侧尚未回放完的报文；返回载入的应答数
+    pub fn load_replay(&self, side: SimulatedSide, script: ReplayScript) -> usize {
+        let count = script.remaining();
+        log::info!("🧪 [PLC_SIM] {:?} 载入录制报文 {} 帧", side, count);
+        lock(&self.state).replay.insert(side, script);
+        count
+    }
+
+    /// 清除全部回放报文，恢复正常仿真
+    pub fn clear_replay(&self) {
+        lock(&self.state).replay.clear();
+    }
+
+    pub fn status(&self) -> SimulatorStatus {
+        let state = lock(&self.state);
+        SimulatorStatus {
+            test_rig_endpoint: self.test_rig_endpoint.to_string(),
+            target_endpoint: self.target_endpoint.to_string(),
+            route_count: state.model.route_count(),
+            alarm_count: state.model.alarm_count(),
+            faults: state.faults.clone(),
+            replay_remaining: state.replay.values().map(ReplayScript::remaining).sum(),
+        }
+    }
+
+    /// 停止监听；已建立的连接在客户端断开后结束
+    pub fn shutdown(&self) {
+        for listener in &self.listeners {
+            listener.abort();
+        }
+    }
+}
+
+impl Drop for PlcSimulator {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+async fn accept_loop(listener: TcpListener, side: SimulatedSide, state: SharedState) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                log::debug!("🧪 [PLC_SIM] {:?} 接受连接: {}", side, peer);
+                tokio::spawn(serve_connection(stream, side, state.clone()));
+            }
+            Err(e) => {
+                log::warn!("⚠️ [PLC_SIM] {:?} 接受连接失败: {}", side, e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+/// 处理单个客户端连接上的 MBAP 帧
+async fn serve_connection(mut stream: TcpStream, side: SimulatedSide, state: SharedState) {
+    let mut header = [0u8; 7];
+    loop {
+        if stream.read_exact(&mut header).await.is_err() {
+            return;
+        }
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        if !(2..=254).contains(&length) {
+            return;
+        }
+        let mut pdu = vec![0u8; length - 1];
+        if stream.read_exact(&mut pdu).await.is_err() {
+            return;
+        }
+
+        let (response, updates, delay_ms) = {
+            let mut guard = lock(&state);
+            let (response, updates) = guard.handle_pdu(side, &pdu);
+            (response, updates, guard.config.delay_ms)
+        };
+        for update in updates {
+            let state = state.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                lock(&state).apply_update(update);
+            });
+        }
+
+        let Some(response) = response else { continue };
+        let mut frame = Vec::with_capacity(7 + response.len());
+        frame.extend_from_slice(&header[..4]);
+        frame.extend_from_slice(&(response.len() as u16 + 1).to_be_bytes());
+        frame.push(header[6]);
+        frame.extend(response);
+        if stream.write_all(&frame).await.is_err() {
+            return;
+        }
+    }
+}
+
+/// 从测试PLC通道表、通道映射与已导入点表构建仿真模型
+pub async fn load_simulator_model(
+    test_plc_config_service: &Arc<dyn ITestPlcConfigService>,
+    persistence_service: &Arc<dyn IPersistenceService>,
+    test_rig: SideLayout,
+    target: SideLayout,
+) -> AppResult<SimulatorModel> {
+    let channels = test_plc_config_service.get_test_plc_channels(GetTestPlcChannelsRequest {
+        channel_type_filter: None,
+        enabled_only: Some(true),
+    }).await?;
+    let mappings = test_plc_config_service.get_channel_mappings().await.unwrap_or_default();
+    let definitions = persistence_service.load_all_channel_definitions().await?;
+    Ok(SimulatorModel::from_tables(test_rig, target, &channels, &mappings, &definitions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PointDataType;
+
+    /// 极简 Modbus TCP 客户端，直接收发原始PDU
+    struct RawClient {
+        stream: TcpStream,
+        transaction: u16,
+    }
+
+    impl RawClient {
+        async fn connect(endpoint: SocketAddr) -> Self {
+            Self { stream: TcpStream::connect(endpoint).await.expect("连接仿真器失败"), transaction: 0 }
+        }
+
+        async fn request(&mut self, pdu: &[u8]) -> Option<Vec<u8>> {
+            self.transaction += 1;
+            let mut frame = self.transaction.to_be_bytes().to_vec();
+            frame.extend_from_slice(&[0, 0]);
+            frame.extend_from_slice(&(pdu.len() as u16 + 1).to_be_bytes());
+            frame.push(1);
+            frame.extend_from_slice(pdu);
+            self.stream.write_all(&frame).await.unwrap();
+
+            let mut header = [0u8; 7];
+            let read = tokio::time::timeout(Duration::from_millis(300), self.stream.read_exact(&mut header)).await;
+            if read.is_err() {
+                return None;
+            }
+            assert_eq!(u16::from_be_bytes([header[0], header[1]]), self.transaction);
+            let mut body = vec![0u8; u16::from_be_bytes([header[4], header[5]]) as usize - 1];
+            self.stream.read_exact(&mut body).await.unwrap();
+            Some(body)
+        }
+
+        async fn write_f32(&mut self, offset: u16, value: f32) {
+            let (reg1, reg2) = ByteOrderConverter::float_to_registers(value, ByteOrder::CDAB);
+            let mut pdu = vec![0x10];
+            pdu.extend(offset.to_be_bytes());
+            pdu.extend([0, 2, 4]);
+            pdu.extend(reg1.to_be_bytes());
+            pdu.extend(reg2.to_be_bytes());
+            self.request(&pdu).await.expect("写入无应答");
+        }
+
+        async fn read_f32(&mut self, function: u8, offset: u16) -> f32 {
+            let mut pdu = vec![function];
+            pdu.extend(offset.to_be_bytes());
+            pdu.extend([0, 2]);
+            let body = self.request(&pdu).await.expect("读取无应答");
+            ByteOrderConverter::registers_to_float(
+                u16::from_be_bytes([body[2], body[3]]),
+                u16::from_be_bytes([body[4], body[5]]),
+                ByteOrder::CDAB,
+            )
+        }
+
+        async fn write_coil(&mut self, offset: u16, value: bool) -> Option<Vec<u8>> {
+            let mut pdu = vec![0x05];
+            pdu.extend(offset.to_be_bytes());
+            pdu.extend(if value { [0xFF, 0x00] } else { [0x00, 0x00] });
+            self.request(&pdu).await
+        }
+
+        async fn read_bit(&mut self, function: u8, offset: u16) -> Option<bool> {
+            let mut pdu = vec![function];
+            pdu.extend(offset.to_be_bytes());
+            pdu.extend([0, 1]);
+            self.request(&pdu).await.map(|body| body[2] & 1 != 0)
+        }
+    }
+
+    fn definition(tag: &str, module_type: ModuleType, address: &str) -> ChannelPointDefinition {
+        let data_type = if matches!(module_type, ModuleType::AI | ModuleType::AO) { PointDataType::Float } else { PointDataType::Bool };
+        let mut definition = ChannelPointDefinition::new(
+            tag.to_string(), tag.to_string(), String::new(), "站1".to_string(), "M1".to_string(),
+            module_type, "1".to_string(), data_type, address.to_string(),
+        );
+        definition.range_low_limit = Some(0.0);
+        definition.range_high_limit = Some(200.0);
+        definition
+    }
+
+    fn instance(definition: &ChannelPointDefinition, test_rig_address: &str) -> ChannelTestInstance {
+        let mut instance = ChannelTestInstance::new(definition.id.clone(), "batch".to_string());
+        instance.test_plc_communication_address = Some(test_rig_address.to_string());
+        instance
+    }
+
+    async fn start(config: SimulatorConfig, definitions: &[ChannelPointDefinition], instances: &[ChannelTestInstance]) -> PlcSimulator {
+        let mut model = SimulatorModel::new(SideLayout::default(), SideLayout::default());
+        model.add_instance_routes(instances, definitions);
+        PlcSimulator::start(config, model).await.expect("启动仿真器失败")
+    }
+
+    fn immediate() -> SimulatorConfig {
+        SimulatorConfig { delay_ms: 0, ..SimulatorConfig::default() }
+    }
+
+    #[tokio::test]
+    async fn loops_analog_and_digital_signals_between_sides() {
+        let ai = definition("TT101", ModuleType::AI, "30001");
+        let ao = definition("FV101", ModuleType::AO, "40011");
+        let di = definition("XS101", ModuleType::DI, "10001");
+        let instances = [instance(&ai, "40101"), instance(&ao, "30101"), instance(&di, "00101")];
+        let simulator = start(immediate(), &[ai, ao, di], &instances).await;
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let mut target = RawClient::connect(simulator.endpoint(SimulatedSide::Target)).await;
+
+        // AI: 测试PLC输出25% → 被测PLC读到 0 + 25% × 200 = 50
+        rig.write_f32(100, 25.0).await;
+        assert_eq!(target.read_f32(0x04, 0).await, 50.0);
+
+        // AO: 被测PLC输出工程值 → 测试PLC读到相同工程值
+        target.write_f32(10, 120.0).await;
+        assert_eq!(rig.read_f32(0x04, 100).await, 120.0);
+
+        // DI: 测试PLC DO → 被测PLC DI
+        rig.write_coil(100, true).await.unwrap();
+        assert_eq!(target.read_bit(0x02, 0).await, Some(true));
+        rig.write_coil(100, false).await.unwrap();
+        assert_eq!(target.read_bit(0x02, 0).await, Some(false));
+    }
+
+    #[tokio::test]
+    async fn applies_gain_offset_and_delay() {
+        let ai = definition("TT102", ModuleType::AI, "40001");
+        let instances = [instance(&ai, "40101")];
+        let config = SimulatorConfig { gain: 0.5, offset_percent: 10.0, delay_ms: 100, ..SimulatorConfig::default() };
+        let simulator = start(config, &[ai], &instances).await;
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let mut target = RawClient::connect(simulator.endpoint(SimulatedSide::Target)).await;
+
+        rig.write_f32(100, 100.0).await;
+        assert_eq!(target.read_f32(0x03, 0).await, 0.0, "延迟期内回环尚未生效");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // (100% × 0.5 + 10%) × 200 = 120
+        assert!((target.read_f32(0x03, 0).await - 120.0).abs() < 1e-3);
+    }
+
+    #[tokio::test]
+    async fn injected_faults_change_loopback_and_silence_requests() {
+        let ai = definition("TT103", ModuleType::AI, "30001");
+        let di = definition("XS103", ModuleType::DI, "10001");
+        let instances = [instance(&ai, "40101"), instance(&di, "00101")];
+        let simulator = start(immediate(), &[ai, di], &instances).await;
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let mut target = RawClient::connect(simulator.endpoint(SimulatedSide::Target)).await;
+
+        rig.write_f32(100, 50.0).await;
+        simulator.set_fault("TT103", SimulatedFault::OpenCircuit);
+        assert_eq!(target.read_f32(0x04, 0).await, -50.0);
+        simulator.set_fault("TT103", SimulatedFault::Offset { percent: 5.0 });
+        assert!((target.read_f32(0x04, 0).await - 110.0).abs() < 1e-3);
+        simulator.clear_fault("TT103");
+        assert_eq!(target.read_f32(0x04, 0).await, 100.0);
+
+        simulator.set_fault("XS103", SimulatedFault::Inverted);
+        rig.write_coil(100, true).await.unwrap();
+        assert_eq!(target.read_bit(0x02, 0).await, Some(false));
+
+        simulator.set_fault("XS103", SimulatedFault::NoResponse);
+        assert_eq!(target.read_bit(0x02, 0).await, None, "无应答故障下请求应超时");
+        assert_eq!(simulator.status().faults.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn emulates_alarm_feedback_from_setpoints() {
+        let mut ai = definition("PT104", ModuleType::AI, "40001");
+        ai.sh_set_value = Some(150.0);
+        ai.sh_set_point_address = Some("40201".to_string());
+        ai.sh_set_point_communication_address = Some("40301".to_string());
+        ai.sh_feedback_address = Some("00201".to_string());
+        ai.sl_set_value = Some(20.0);
+        ai.sl_set_point_address = Some("40203".to_string());
+        ai.sl_feedback_address = Some("00202".to_string());
+        let instances = [instance(&ai, "40101")];
+        let simulator = start(immediate(), &[ai], &instances).await;
+        assert_eq!(simulator.status().alarm_count, 2);
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let mut target = RawClient::connect(simulator.endpoint(SimulatedSide::Target)).await;
+
+        // 初始过程值为0：低报激活，高报未激活
+        assert_eq!(target.read_f32(0x03, 200).await, 150.0);
+        assert_eq!(target.read_bit(0x01, 201).await, Some(true));
+        assert_eq!(target.read_bit(0x01, 200).await, Some(false));
+
+        // 80% → 160，越过高报设定值150
+        rig.write_f32(100, 80.0).await;
+        assert_eq!(target.read_bit(0x01, 200).await, Some(true));
+        assert_eq!(target.read_bit(0x01, 201).await, Some(false));
+
+        // 通过通讯地址提高设定值后报警复位，点表地址同步为新设定值
+        target.write_f32(300, 170.0).await;
+        assert_eq!(target.read_bit(0x01, 200).await, Some(false));
+        assert_eq!(target.read_f32(0x03, 200).await, 170.0);
+    }
+
+    #[tokio::test]
+    async fn builds_routes_from_channel_mappings() {
+        use crate::models::test_plc_config::{MappingType, TestPlcChannelType};
+
+        let di = definition("XS105", ModuleType::DI, "10005");
+        let channel = TestPlcChannelConfig {
+            id: Some("rig_do_1".to_string()),
+            channel_address: "DO1_1".to_string(),
+            channel_type: TestPlcChannelType::DO,
+            communication_address: "00105".to_string(),
+            power_supply_type: String::new(),
+            description: None,
+            is_enabled: true,
+            created_at: None,
+            updated_at: None,
+        };
+        let mapping = ChannelMappingConfig {
+            id: "m1".to_string(),
+            target_channel_id: di.id.clone(),
+            test_plc_channel_id: "rig_do_1".to_string(),
+            mapping_type: MappingType::Direct,
+            is_active: true,
+            notes: None,
+            created_at: chrono::Utc::now(),
+        };
+        let model = SimulatorModel::from_tables(SideLayout::default(), SideLayout::default(), &[channel], &[mapping], &[di]);
+        assert_eq!(model.route_count(), 1);
+
+        let simulator = PlcSimulator::start(immediate(), model).await.unwrap();
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let mut target = RawClient::connect(simulator.endpoint(SimulatedSide::Target)).await;
+        rig.write_coil(104, true).await.unwrap();
+        assert_eq!(target.read_bit(0x02, 4).await, Some(true));
+        assert_eq!(target.request(&[0x2B, 0x0E, 0x01, 0x00, 0x00]).await, Some(vec![0xAB, 0x01]));
+    }
+
+    /// 用系统实际使用的Modbus客户端连接仿真器两侧，连接ID分别为 sim_rig / sim_target
+    async fn connect_executor_plc(simulator: &PlcSimulator) -> Arc<dyn crate::domain::services::plc_communication_service::IPlcCommunicationService> {
+        use crate::domain::services::plc_communication_service::{
+            IPlcCommunicationService, PlcConnectionConfig as ServiceConnectionConfig, PlcProtocol,
+        };
+        use crate::infrastructure::plc_communication::ModbusTcpPlcService;
+
+        let service = Arc::new(ModbusTcpPlcService::new());
+        for (id, side) in [("sim_rig", SimulatedSide::TestRig), ("sim_target", SimulatedSide::Target)] {
+            let endpoint = simulator.endpoint(side);
+            let config = ServiceConnectionConfig {
+                id: id.to_string(),
+                name: id.to_string(),
+                protocol: PlcProtocol::ModbusTcp,
+                host: endpoint.ip().to_string(),
+                port: endpoint.port(),
+                timeout_ms: 1000,
+                read_timeout_ms: 1000,
+                write_timeout_ms: 1000,
+                byte_order: "CDAB".to_string(),
+                zero_based_address: false,
+                retry_count: 0,
+                retry_interval_ms: 0,
+                protocol_params: HashMap::new(),
+            };
+            service.connect(&config).await.expect("连接仿真器失败");
+        }
+        service
+    }
+
+    /// 通过系统实际使用的Modbus客户端与DI硬点测试执行器验证端到端回环
+    #[tokio::test]
+    async fn di_hard_point_test_runs_against_simulator() {
+        use crate::domain::impls::specific_test_executors::{DIHardPointTestExecutor, ISpecificTestStepExecutor};
+
+        let di = definition("XS106", ModuleType::DI, "10006");
+        let instance = instance(&di, "00106");
+        let simulator = start(immediate(), std::slice::from_ref(&di), std::slice::from_ref(&instance)).await;
+        let plc = connect_executor_plc(&simulator).await;
+        let executor = DIHardPointTestExecutor::new(1000);
+
+        let outcome = executor.execute(&instance, &di, "sim_rig", "sim_target", plc.clone(), plc.clone()).await
+            .expect("DI硬点测试执行失败");
+        assert!(outcome.success, "{:?}", outcome.message);
+
+        simulator.set_fault("XS106", SimulatedFault::StuckAt { value: 0.0 });
+        let outcome = executor.execute(&instance, &di, "sim_rig", "sim_target", plc.clone(), plc).await
+            .expect("DI硬点测试执行失败");
+        assert!(!outcome.success, "卡死故障下DI硬点测试应判定失败");
+    }
+
+    /// 串扰检查出错时保留多点测试结果，并在结果中记录检查异常
+    #[tokio::test]
+    async fn crosstalk_check_errors_are_recorded_on_the_ai_outcome() {
+        use crate::domain::impls::specific_test_executors::{
+            AIHardPointPercentExecutor, CrosstalkNeighbor, ISpecificTestStepExecutor,
+        };
+        use crate::models::AnalogTestSettings;
+
+        let ai = definition("TT107", ModuleType::AI, "30001");
+        let neighbor = definition("TT108", ModuleType::AI, "30011");
+        let instances = [instance(&ai, "40101"), instance(&neighbor, "40111")];
+        let simulator = start(immediate(), &[ai.clone(), neighbor.clone()], &instances).await;
+        let plc = connect_executor_plc(&simulator).await;
+        let settings = AnalogTestSettings {
+            test_points: vec![0.0, 0.5, 1.0],
+            crosstalk_threshold_percent: Some(1.0),
+            ..AnalogTestSettings::default()
+        };
+        let executor = AIHardPointPercentExecutor::with_settings(settings)
+            .with_crosstalk_neighbors(vec![CrosstalkNeighbor::from_definition(&neighbor)]);
+
+        let outcome = executor.execute(&instances[0], &ai, "sim_rig", "sim_target", plc.clone(), plc.clone()).await
+            .expect("AI硬点测试执行失败");
+        assert!(outcome.success, "{:?}", outcome.message);
+        assert_eq!(outcome.details["crosstalk"]["neighbors"][0]["flagged"], false);
+
+        simulator.set_fault("TT108", SimulatedFault::NoResponse);
+        let outcome = executor.execute(&instances[0], &ai, "sim_rig", "sim_target", plc.clone(), plc).await
+            .expect("串扰检查出错不应丢弃AI硬点测试结果");
+        assert!(!outcome.success);
+        assert_eq!(outcome.readings.as_ref().map(Vec::len), Some(3), "多点测试读数应保留");
+        assert!(outcome.details["crosstalk"]["error"].as_str().is_some_and(|e| e.contains("TT108")), "{:?}", outcome.details);
+        assert!(outcome.message.as_deref().is_some_and(|m| m.contains("串扰检查未完成")), "{:?}", outcome.message);
+    }
+
+    fn high_alarm_definition(tag: &str) -> ChannelPointDefinition {
+        let mut ai = definition(tag, ModuleType::AI, "30021");
+        ai.sh_set_value = Some(150.0);
+        ai.sh_set_point_address = Some("40221".to_string());
+        ai.sh_feedback_address = Some("00221".to_string());
+        ai
+    }
+
+    fn fast_ramp() -> crate::models::AlarmRampConfig {
+        crate::models::AlarmRampConfig { dwell_ms: 20, step_percent: 0.5, trip_tolerance_percent: 1.0, reset_delay_ms: 1000, ..Default::default() }
+    }
+
+    /// 触发点搜索找到设定值附近的触发点与恢复点，结束后测试PLC AO复位
+    #[tokio::test]
+    async fn trip_point_ramp_finds_the_setpoint_and_resets_the_rig() {
+        use crate::domain::impls::specific_test_executors::{AIAlarmTestExecutor, ISpecificTestStepExecutor};
+        use crate::models::enums::SubTestItem;
+
+        let ai = high_alarm_definition("PT110");
+        let instance = instance(&ai, "40121");
+        let simulator = start(immediate(), std::slice::from_ref(&ai), std::slice::from_ref(&instance)).await;
+        let plc = connect_executor_plc(&simulator).await;
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let executor = AIAlarmTestExecutor::with_trip_point_ramp(SubTestItem::HighAlarm, fast_ramp());
+
+        let outcome = executor.execute(&instance, &ai, "sim_rig", "sim_target", plc.clone(), plc).await
+            .expect("触发点搜索执行失败");
+        assert!(outcome.success, "{:?}", outcome.message);
+        let trip = outcome.details["trip_point"].as_f64().unwrap();
+        assert!((trip - 150.0).abs() <= 2.0, "触发点 {}", trip);
+        assert!(outcome.details["clear_point"].as_f64().is_some_and(|clear| clear <= trip));
+        assert_eq!(rig.read_f32(0x03, 120).await, 0.0, "搜索结束后测试PLC AO应复位");
+    }
+
+    /// 搜索中途出错时同样复位测试PLC AO
+    #[tokio::test]
+    async fn trip_point_ramp_resets_the_rig_when_the_search_fails() {
+        use crate::domain::impls::specific_test_executors::{AIAlarmTestExecutor, ISpecificTestStepExecutor};
+        use crate::models::enums::SubTestItem;
+
+        let mut ai = high_alarm_definition("PT111");
+        // 反馈地址无法解析：输入已推到起点后读取反馈失败
+        ai.sh_feedback_address = Some("无效地址".to_string());
+        let instance = instance(&ai, "40121");
+        let simulator = start(immediate(), std::slice::from_ref(&ai), std::slice::from_ref(&instance)).await;
+        let plc = connect_executor_plc(&simulator).await;
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        let executor = AIAlarmTestExecutor::with_trip_point_ramp(SubTestItem::HighAlarm, fast_ramp());
+
+        let result = executor.execute(&instance, &ai, "sim_rig", "sim_target", plc.clone(), plc).await;
+        assert!(result.is_err());
+        assert_eq!(rig.read_f32(0x03, 120).await, 0.0, "出错退出时测试PLC AO也应复位");
+    }
+
+    /// 接线检查只驱动本批次的测试PLC输出，结束后全部复位
+    #[tokio::test]
+    async fn wiring_sweep_only_drives_batch_outputs_and_resets_them() {
+        use crate::domain::impls::{WiringDiscoverySweep, WiringRigOutput, WiringTargetChannel};
+        use crate::models::{WiringCheckSettings, WiringStatus};
+
+        let di = definition("XS109", ModuleType::DI, "10009");
+        let instance = instance(&di, "00109");
+        let simulator = start(immediate(), std::slice::from_ref(&di), std::slice::from_ref(&instance)).await;
+        let plc = connect_executor_plc(&simulator).await;
+        let mut rig = RawClient::connect(simulator.endpoint(SimulatedSide::TestRig)).await;
+        // 其他批次正在使用的输出
+        rig.write_coil(198, true).await.unwrap();
+
+        let targets = [WiringTargetChannel {
+            instance_id: instance.instance_id.clone(),
+            tag: di.tag.clone(),
+            module_type: ModuleType::DI,
+            target_address: di.plc_communication_address.clone(),
+            expected_test_rig_channel: Some("DO1_9".to_string()),
+            range_low: 0.0,
+            range_high: 100.0,
+        }];
+        let output = |tag: &str, address: &str| WiringRigOutput { channel_tag: tag.to_string(), address: address.to_string(), is_analog: false };
+        let outputs = [output("DO1_9", "00109"), output("DO9_9", "00199")];
+        let settings = WiringCheckSettings { dwell_ms: 50, ..WiringCheckSettings::default() };
+        let sweep = WiringDiscoverySweep::new(plc, "sim_rig".to_string(), "sim_target".to_string(), settings);
+
+        let report = sweep.run("batch", &targets, &outputs).await.expect("接线检查失败");
+        assert_eq!(report.channels[0].status, WiringStatus::Correct);
+        assert_eq!(rig.read_bit(0x01, 108).await, Some(false), "扫描后输出应复位");
+        assert_eq!(rig.read_bit(0x01, 198).await, Some(true), "不属于本批次的输出不应被驱动或复位");
+    }
+
+    /// 测试执行引擎在仿真台架上完成一个批次：各类型点位全部通过，注入故障的点位重试后判定失败
+    #[tokio::test]
+    async fn execution_engine_runs_a_batch_against_the_simulator() {
+        use crate::domain::impls::test_execution_engine::{ITestExecutionEngine, TestExecutionEngine, TestRetryPolicy};
+        use crate::models::{AnalogTestProfile, AnalogTestSettings, RawTestOutcome};
+
+        let definitions = [
+            definition("TT112", ModuleType::AI, "30031"),
+            definition("FV112", ModuleType::AO, "40041"),
+            definition("XS112", ModuleType::DI, "10012"),
+            definition("XV112", ModuleType::DO, "00012"),
+            definition("XS113", ModuleType::DI, "10013"),
+        ];
+        let instances = [
+            instance(&definitions[0], "40131"),
+            instance(&definitions[1], "30141"),
+            instance(&definitions[2], "00112"),
+            instance(&definitions[3], "10112"),
+            instance(&definitions[4], "00113"),
+        ];
+        let simulator = start(immediate(), &definitions, &instances).await;
+        simulator.set_fault("XS113", SimulatedFault::StuckAt { value: 0.0 });
+        let plc = connect_executor_plc(&simulator).await;
+
+        let engine = TestExecutionEngine::new(2, plc.clone(), plc, "sim_rig".to_string(), "sim_target".to_string())
+            .with_retry_policy(TestRetryPolicy { max_retries: 1, backoff_ms: 10 });
+        let settings = AnalogTestSettings { test_points: vec![0.0, 0.5, 1.0], settle_time_ms: 500, ..AnalogTestSettings::default() };
+        engine.set_batch_analog_test_profile("batch", AnalogTestProfile { default_settings: settings, ..AnalogTestProfile::default() })
+            .await.unwrap();
+
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(32);
+        for (instance, definition) in instances.iter().zip(&definitions) {
+            engine.submit_test_instance(instance.clone(), definition.clone(), sender.clone()).await.unwrap();
+        }
+        drop(sender);
+
+        let mut outcomes: HashMap<String, Vec<RawTestOutcome>> = HashMap::new();
+        while let Some(outcome) = tokio::time::timeout(Duration::from_secs(30), receiver.recv()).await.expect("批次测试超时") {
+            outcomes.entry(outcome.channel_instance_id.clone()).or_default().push(outcome);
+        }
+
+        for (instance, definition) in instances.iter().zip(&definitions).take(4) {
+            let results = &outcomes[&instance.instance_id];
+            assert_eq!(results.len(), 1, "{} 不应重试", definition.tag);
+            assert!(results[0].success, "{}: {:?}", definition.tag, results[0].message);
+        }
+        let stuck = &outcomes[&instances[4].instance_id];
+        assert_eq!(stuck.iter().map(|o| (o.attempt, o.retry_pending, o.success)).collect::<Vec<_>>(),
+                   vec![(1, true, false), (2, false, false)]);
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_responses_before_simulating() {
+        use crate::infrastructure::modbus_capture::{CapturedFrame, ModbusRequestFrame};
+
+        let recorded = |request: ModbusRequestFrame, outcome: FrameOutcome| CapturedFrame {
+            timestamp: chrono::Utc::now(),
+            connection_id: "target".to_string(),
+            instance_id: Some("inst".to_string()),
+            request,
+            outcome,
+            latency_us: 0,
+        };
+        let (reg1, reg2) = ByteOrderConverter::float_to_registers(42.5, ByteOrder::CDAB);
+        let frames = [
+            recorded(ModbusRequestFrame::read(0x03, 0, 2), FrameOutcome::Response(FrameValues::Words(vec![reg1, reg2]))),
+            recorded(ModbusRequestFrame::read(0x02, 5, 1), FrameOutcome::Timeout),
+            recorded(ModbusRequestFrame::read(0x02, 5, 1), FrameOutcome::Exception(0x04)),
+        ];
+        let simulator = start(immediate(), &[], &[]).await;
+        assert_eq!(simulator.load_replay(SimulatedSide::Target, ReplayScript::from_frames(&frames)), 3);
+        let mut target = RawClient::connect(simulator.endpoint(SimulatedSide::Target)).await;
+
+        assert_eq!(target.read_f32(0x03, 0).await, 42.5);
+        assert_eq!(target.read_bit(0x02, 5).await, None, "录制为超时的请求不应答");
+        assert_eq!(target.request(&[0x02, 0, 5, 0, 1]).await, Some(vec![0x82, 0x04]));
+        assert_eq!(simulator.status().replay_remaining, 0);
+        // 录制用尽后恢复正常仿真
+        assert_eq!(target.read_f32(0x03, 0).await, 0.0);
+    }
+}
@@ -0,0 +1,1184 @@
+//! # 西门子S7通信服务实现模块 (ISO-on-TCP)
+//!
+//! ## 业务作用
+//! 为S7-300/400/1200/1500等西门子PLC提供原生通信实现，无需在PLC侧额外组态Modbus：
+//! - 通过 TPKT/COTP 建立ISO-on-TCP连接（默认端口102），再协商S7通信参数（PDU大小）
+//! - 按点表中的 `plc_absolute_address` 格式读写数据，如 `DB1.DBD0`、`%MD100`、`%I0.0`
+//! - 批量读写合并为多变量请求，按协商得到的PDU大小自动分组
+//!
+//! ## 协议分层
+//! ```text
+//! TPKT (RFC1006, 4字节) → COTP (ISO 8073: CR/CC 建连, DT 传输数据) → S7 PDU (Job / Ack_Data)
+//! ```
+//!
+//! ## 地址格式
+//! - 数据块: `DB1.DBX0.0`(位)、`DB1.DBB2`(字节)、`DB1.DBW4`(字)、`DB1.DBD8`(双字)
+//! - 输入/输出/位存储区: `%I0.0`、`%IW64`、`%Q1.7`、`%QD4`、`%M10.1`、`%MB1`、`%MD100`，`%` 可省略
+//!
+//! S7数据统一为大端字节序，连接配置中的 `byte_order` 对S7连接不生效
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use crate::domain::services::{
+    BaseService, ConnectionHandle, ConnectionStats, ConnectionTestResult, PlcConnectionConfig,
+    PlcDataType, PlcProtocol, PlcValue, ReadRequest, ReadResult, WriteRequest, WriteResult,
+};
+use crate::domain::services::plc_communication_service::IPlcCommunicationService;
+use crate::infrastructure::plc_communication::{update_read_stats, update_write_stats};
+use crate::utils::error::{AppError, AppResult};
+
+/// 请求的PDU大小，实际大小以PLC应答为准（S7-300通常为240，S7-1500为960）
+const REQUESTED_PDU_SIZE: u16 = 480;
+/// 单个读写请求最多包含的变量数（PLC侧限制）
+const MAX_ITEMS_PER_REQUEST: usize = 20;
+/// Job请求头(10) + 功能码/变量数(2)
+const REQUEST_OVERHEAD: usize = 12;
+/// Ack_Data应答头(12) + 功能码/变量数(2)
+const RESPONSE_OVERHEAD: usize = 14;
+/// 单个变量描述(Any指针)长度
+const ITEM_SPEC_LEN: usize = 12;
+/// 单个数据项头: 返回码、传输类型、长度
+const DATA_ITEM_HEADER_LEN: usize = 4;
+
+/// COTP 数据传输(DT)头
+const COTP_DT_HEADER: [u8; 3] = [0x02, 0xF0, 0x80];
+
+/// S7存储区
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum S7Area {
+    /// 过程映像输入 (I)
+    Inputs,
+    /// 过程映像输出 (Q)
+    Outputs,
+    /// 位存储区 (M)
+    Merker,
+    /// 数据块 (DBn)
+    DataBlock(u16),
+}
+
+impl S7Area {
+    fn code(&self) -> u8 {
+        match self {
+            S7Area::Inputs => 0x81,
+            S7Area::Outputs => 0x82,
+            S7Area::Merker => 0x83,
+            S7Area::DataBlock(_) => 0x84,
+        }
+    }
+
+    fn db_number(&self) -> u16 {
+        match self {
+            S7Area::DataBlock(number) => *number,
+            _ => 0,
+        }
+    }
+}
+
+/// S7地址宽度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum S7Width {
+    Bit(u8),
+    Byte,
+    Word,
+    DWord,
+}
+
+impl S7Width {
+    /// 宽度对应的字节数（位按1字节计）
+    pub fn byte_len(&self) -> u16 {
+        match self {
+            S7Width::Bit(_) | S7Width::Byte => 1,
+            S7Width::Word => 2,
+            S7Width::DWord => 4,
+        }
+    }
+}
+
+/// 解析后的S7地址
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7Address {
+    pub area: S7Area,
+    /// 字节偏移
+    pub byte_offset: u32,
+    pub width: S7Width,
+}
+
+impl S7Address {
+    /// 解析点表中的S7地址，如 `DB1.DBD0`、`%MD100`、`%I0.0`
+    pub fn parse(address: &str) -> AppResult<Self> {
+        let normalized = address.trim().trim_start_matches('%').to_ascii_uppercase();
+        let invalid = |reason: &str| AppError::validation_error(format!("无效的S7地址 '{}': {}", address, reason));
+
+        let (area, field) = if let Some(rest) = normalized.strip_prefix("DB") {
+            let (number, field) = rest.split_once('.').ok_or_else(|| invalid("数据块地址缺少 '.DBx' 部分"))?;
+            let number: u16 = number.parse().map_err(|_| invalid("数据块编号无效"))?;
+            if number == 0 {
+                return Err(invalid("数据块编号必须大于0"));
+            }
+            let field = field.strip_prefix("DB").ok_or_else(|| invalid("数据块地址应为 DBn.DBX/DBB/DBW/DBD 格式"))?;
+            // 数据块没有隐式位地址，必须写明宽度
+            if !field.starts_with(['X', 'B', 'W', 'D']) {
+                return Err(invalid("缺少宽度标识 X/B/W/D"));
+            }
+            (S7Area::DataBlock(number), field)
+        } else {
+            let mut chars = normalized.chars();
+            let area = match chars.next() {
+                Some('I') | Some('E') => S7Area::Inputs,
+                Some('Q') | Some('A') => S7Area::Outputs,
+                Some('M') => S7Area::Merker,
+                _ => return Err(invalid("不支持的存储区，应为 DB/I/Q/M")),
+            };
+            (area, chars.as_str())
+        };
+
+        let (width_char, offset) = match field.chars().next() {
+            Some(c @ ('X' | 'B' | 'W' | 'D')) => (c, &field[1..]),
+            Some(c) if c.is_ascii_digit() => ('X', field),
+            _ => return Err(invalid("缺少字节偏移")),
+        };
+
+        let address = match width_char {
+            'X' => {
+                let (byte, bit) = offset.split_once('.').ok_or_else(|| invalid("位地址应为 字节.位 格式"))?;
+                let bit: u8 = bit.parse().map_err(|_| invalid("位号无效"))?;
+                if bit > 7 {
+                    return Err(invalid("位号必须在0-7之间"));
+                }
+                Self { area, byte_offset: byte.parse().map_err(|_| invalid("字节偏移无效"))?, width: S7Width::Bit(bit) }
+            }
+            _ => {
+                let width = match width_char {
+                    'B' => S7Width::Byte,
+                    'W' => S7Width::Word,
+                    _ => S7Width::DWord,
+                };
+                Self { area, byte_offset: offset.parse().map_err(|_| invalid("字节偏移无效"))?, width }
+            }
+        };
+
+        // Any指针的地址字段为24位位地址
+        if address.byte_offset > 0x1F_FFFF {
+            return Err(invalid("字节偏移超出范围"));
+        }
+        Ok(address)
+    }
+
+    /// 编码变量描述(Any指针)，`byte_len` 为要读写的字节数，位地址固定读写1位
+    fn item_spec(&self, byte_len: u16) -> [u8; ITEM_SPEC_LEN] {
+        let (transport_size, length, bit) = match self.width {
+            S7Width::Bit(bit) => (0x01, 1u16, bit as u32),
+            _ => (0x02, byte_len, 0),
+        };
+        let bit_address = (self.byte_offset << 3) | bit;
+        let db = self.area.db_number().to_be_bytes();
+        let len = length.to_be_bytes();
+        [
+            0x12, 0x0A, 0x10, transport_size,
+            len[0], len[1],
+            db[0], db[1],
+            self.area.code(),
+            (bit_address >> 16) as u8, (bit_address >> 8) as u8, bit_address as u8,
+        ]
+    }
+
+    fn is_bit(&self) -> bool {
+        matches!(self.width, S7Width::Bit(_))
+    }
+}
+
+/// S7连接参数，来自 `protocol_params` 的 rack / slot / connection_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S7ConnectParams {
+    pub rack: u16,
+    pub slot: u16,
+    /// 连接类型: 1=PG, 2=OP, 3=S7 Basic
+    pub connection_type: u8,
+}
+
+impl Default for S7ConnectParams {
+    fn default() -> Self {
+        Self { rack: 0, slot: 1, connection_type: 1 }
+    }
+}
+
+impl S7ConnectParams {
+    pub fn from_protocol_params(params: &HashMap<String, serde_json::Value>) -> Self {
+        let defaults = Self::default();
+        let read_u64 = |key: &str, default: u64| params.get(key).and_then(|v| v.as_u64()).unwrap_or(default);
+        Self {
+            rack: read_u64("rack", defaults.rack as u64) as u16,
+            slot: read_u64("slot", defaults.slot as u64) as u16,
+            connection_type: read_u64("connection_type", defaults.connection_type as u64) as u8,
+        }
+    }
+
+    /// 远端TSAP: 高字节为连接类型，低字节为 机架×32 + 插槽
+    fn remote_tsap(&self) -> u16 {
+        ((self.connection_type as u16) << 8) | (self.rack * 0x20 + self.slot)
+    }
+}
+
+/// S7变量返回码描述
+fn return_code_message(code: u8) -> String {
+    match code {
+        0x01 => "硬件故障".to_string(),
+        0x03 => "不允许访问该对象".to_string(),
+        0x05 => "地址超出范围".to_string(),
+        0x06 => "不支持的数据类型".to_string(),
+        0x07 => "数据类型不一致".to_string(),
+        0x0A => "对象不存在".to_string(),
+        other => format!("未知返回码 0x{:02X}", other),
+    }
+}
+
+/// 单个变量的返回码错误：硬件故障属于通信链路问题，其余为地址或数据类型配置问题
+fn return_code_error(code: u8) -> AppError {
+    let message = format!("S7变量访问失败: {}", return_code_message(code));
+    match code {
+        0x01 => AppError::plc_communication_error(message),
+        _ => AppError::configuration_error(message),
+    }
+}
+
+/// 变量未得到应答数据
+fn missing_response() -> AppError {
+    AppError::plc_communication_error("S7无应答数据")
+}
+
+/// 按PDU大小对变量分组，返回每组在原序列中的下标范围
+///
+/// `costs` 为每个变量在请求和应答中占用的字节数
+fn plan_batches(costs: &[(usize, usize)], pdu_size: usize) -> Vec<Range<usize>> {
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut request_len = REQUEST_OVERHEAD;
+    let mut response_len = RESPONSE_OVERHEAD;
+
+    for (index, (request_cost, response_cost)) in costs.iter().enumerate() {
+        let count = index - start;
+        if count > 0
+            && (count >= MAX_ITEMS_PER_REQUEST
+                || request_len + request_cost > pdu_size
+                || response_len + response_cost > pdu_size)
+        {
+            batches.push(start..index);
+            start = index;
+            request_len = REQUEST_OVERHEAD;
+            response_len = RESPONSE_OVERHEAD;
+        }
+        request_len += request_cost;
+        response_len += response_cost;
+    }
+    if start < costs.len() {
+        batches.push(start..costs.len());
+    }
+    batches
+}
+
+/// 数据项按偶数字节对齐（最后一项除外）
+fn padded(len: usize) -> usize {
+    len + (len % 2)
+}
+
+/// 单个S7 ISO-on-TCP连接
+#[derive(Debug)]
+pub struct S7Client {
+    stream: TcpStream,
+    pdu_size: u16,
+    pdu_ref: u16,
+    io_timeout: Duration,
+}
+
+impl S7Client {
+    /// 建立TCP连接，完成COTP连接请求与S7通信参数协商
+    pub async fn connect(host: &str, port: u16, params: S7ConnectParams, timeout_ms: u64) -> AppResult<Self> {
+        let io_timeout = Duration::from_millis(timeout_ms.max(100));
+        let endpoint = format!("{}:{}", host, port);
+        let stream = timeout(io_timeout, TcpStream::connect(&endpoint))
+            .await
+            .map_err(|_| AppError::timeout_error("S7连接", format!("连接 {} 超时", endpoint)))?
+            .map_err(|e| AppError::plc_communication_error(format!("S7 TCP连接失败 {}: {}", endpoint, e)))?;
+        let _ = stream.set_nodelay(true);
+
+        let mut client = Self { stream, pdu_size: REQUESTED_PDU_SIZE, pdu_ref: 0, io_timeout };
+        client.connect_cotp(params).await?;
+        client.setup_communication().await?;
+        log::info!("✅ S7连接成功: {} (机架{} 插槽{}, PDU {})", endpoint, params.rack, params.slot, client.pdu_size);
+        Ok(client)
+    }
+
+    /// 协商得到的PDU大小
+    pub fn pdu_size(&self) -> u16 {
+        self.pdu_size
+    }
+
+    async fn connect_cotp(&mut self, params: S7ConnectParams) -> AppResult<()> {
+        let remote = params.remote_tsap().to_be_bytes();
+        let connect_request = [
+            0x11, 0xE0, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0xC0, 0x01, 0x0A, // TPDU大小 1024
+            0xC1, 0x02, 0x01, 0x00, // 本地TSAP
+            0xC2, 0x02, remote[0], remote[1], // 远端TSAP
+        ];
+        self.send_tpkt(&connect_request).await?;
+        let response = self.recv_tpkt().await?;
+        if response.len() < 2 || response[1] != 0xD0 {
+            return Err(AppError::plc_communication_error(format!(
+                "S7 COTP连接被拒绝，请检查机架/插槽 (rack={}, slot={})",
+                params.rack, params.slot
+            )));
+        }
+        Ok(())
+    }
+
+    async fn setup_communication(&mut self) -> AppResult<()> {
+        let pdu = REQUESTED_PDU_SIZE.to_be_bytes();
+        let parameter = [0xF0, 0x00, 0x00, 0x01, 0x00, 0x01, pdu[0], pdu[1]];
+        let response = self.exchange(&parameter, &[]).await?;
+        let negotiated = response
+            .get(RESPONSE_OVERHEAD - 2 + 6..RESPONSE_OVERHEAD - 2 + 8)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .ok_or_else(|| AppError::plc_communication_error("S7通信参数协商应答不完整"))?;
+        if (negotiated as usize) < REQUEST_OVERHEAD + ITEM_SPEC_LEN {
+            return Err(AppError::plc_communication_error(format!("S7 PDU大小无效: {}", negotiated)));
+        }
+        self.pdu_size = negotiated;
+        Ok(())
+    }
+
+    async fn send_tpkt(&mut self, payload: &[u8]) -> AppResult<()> {
+        let total = (payload.len() + 4) as u16;
+        let mut frame = Vec::with_capacity(total as usize);
+        frame.extend_from_slice(&[0x03, 0x00]);
+        frame.extend_from_slice(&total.to_be_bytes());
+        frame.extend_from_slice(payload);
+        timeout(self.io_timeout, self.stream.write_all(&frame))
+            .await
+            .map_err(|_| AppError::timeout_error("S7写入", "发送请求超时"))?
+            .map_err(|e| AppError::plc_communication_error(format!("S7发送失败: {}", e)))
+    }
+
+    /// 接收一个TPKT帧，返回去掉TPKT头后的COTP数据
+    async fn recv_tpkt(&mut self) -> AppResult<Vec<u8>> {
+        let io_timeout = self.io_timeout;
+        let stream = &mut self.stream;
+        timeout(io_timeout, async move {
+            let mut header = [0u8; 4];
+            stream.read_exact(&mut header).await?;
+            if header[0] != 0x03 {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "无效的TPKT版本"));
+            }
+            let total = u16::from_be_bytes([header[2], header[3]]) as usize;
+            if total < 7 {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "TPKT长度无效"));
+            }
+            let mut payload = vec![0u8; total - 4];
+            stream.read_exact(&mut payload).await?;
+            Ok(payload)
+        })
+        .await
+        .map_err(|_| AppError::timeout_error("S7读取", "等待应答超时"))?
+        .map_err(|e| AppError::plc_communication_error(format!("S7接收失败: {}", e)))
+    }
+
+    /// 发送一个S7 Job请求并返回Ack_Data应答（S7 PDU，含12字节应答头）
+    async fn exchange(&mut self, parameter: &[u8], data: &[u8]) -> AppResult<Vec<u8>> {
+        self.pdu_ref = self.pdu_ref.wrapping_add(1);
+        let pdu_ref = self.pdu_ref.to_be_bytes();
+        let param_len = (parameter.len() as u16).to_be_bytes();
+        let data_len = (data.len() as u16).to_be_bytes();
+
+        let mut payload = Vec::with_capacity(COTP_DT_HEADER.len() + 10 + parameter.len() + data.len());
+        payload.extend_from_slice(&COTP_DT_HEADER);
+        payload.extend_from_slice(&[0x32, 0x01, 0x00, 0x00]);
+        payload.extend_from_slice(&pdu_ref);
+        payload.extend_from_slice(&param_len);
+        payload.extend_from_slice(&data_len);
+        payload.extend_from_slice(parameter);
+        payload.extend_from_slice(data);
+        self.send_tpkt(&payload).await?;
+
+        let response = self.recv_tpkt().await?;
+        let cotp_len = response[0] as usize + 1;
+        let pdu = response
+            .get(cotp_len..)
+            .filter(|pdu| pdu.len() >= 12 && pdu[0] == 0x32)
+            .ok_or_else(|| AppError::plc_communication_error("S7应答格式无效"))?;
+        if pdu[1] != 0x03 {
+            return Err(AppError::plc_communication_error(format!("S7应答类型无效: 0x{:02X}", pdu[1])));
+        }
+        if pdu[10] != 0 || pdu[11] != 0 {
+            return Err(AppError::plc_communication_error(format!(
+                "S7请求被拒绝: 错误类别 0x{:02X} 错误码 0x{:02X}",
+                pdu[10], pdu[11]
+            )));
+        }
+        if pdu[4..6] != pdu_ref {
+            return Err(AppError::plc_communication_error("S7应答序号不匹配"));
+        }
+        Ok(pdu.to_vec())
+    }
+
+    /// 读取多个变量，`items` 为 (地址, 字节数)，按PDU大小自动拆分为多次多变量请求
+    ///
+    /// 外层错误表示通信失败（连接需重建），内层错误为单个变量的读取失败
+    pub async fn read_items(&mut self, items: &[(S7Address, u16)]) -> AppResult<Vec<AppResult<Vec<u8>>>> {
+        let pdu_size = self.pdu_size as usize;
+        let mut results: Vec<AppResult<Vec<u8>>> = vec![Err(missing_response()); items.len()];
+
+        // 单个变量超过PDU容量时无法读取
+        let mut sendable = Vec::with_capacity(items.len());
+        for (index, (_, len)) in items.iter().enumerate() {
+            if RESPONSE_OVERHEAD + DATA_ITEM_HEADER_LEN + *len as usize > pdu_size {
+                results[index] = Err(AppError::configuration_error(format!("读取长度 {} 字节超过PDU容量 {}", len, pdu_size)));
+            } else {
+                sendable.push(index);
+            }
+        }
+
+        let costs: Vec<(usize, usize)> = sendable
+            .iter()
+            .map(|&i| (ITEM_SPEC_LEN, padded(DATA_ITEM_HEADER_LEN + items[i].1 as usize)))
+            .collect();
+        for batch in plan_batches(&costs, pdu_size) {
+            let indices = &sendable[batch];
+            let mut parameter = vec![0x04, indices.len() as u8];
+            for &i in indices {
+                let (address, len) = &items[i];
+                parameter.extend_from_slice(&address.item_spec(*len));
+            }
+
+            let pdu = self.exchange(&parameter, &[]).await?;
+            let values = Self::parse_read_response(&pdu, indices.len())?;
+            for (&i, value) in indices.iter().zip(values) {
+                results[i] = value;
+            }
+        }
+        Ok(results)
+    }
+
+    fn parse_read_response(pdu: &[u8], expected: usize) -> AppResult<Vec<AppResult<Vec<u8>>>> {
+        let incomplete = || AppError::plc_communication_error("S7读取应答数据不完整");
+        let param_len = u16::from_be_bytes([pdu[6], pdu[7]]) as usize;
+        let data = pdu.get(12 + param_len..).ok_or_else(incomplete)?;
+        if pdu.get(13).copied() != Some(expected as u8) {
+            return Err(AppError::plc_communication_error("S7读取应答变量数不一致"));
+        }
+
+        let mut values = Vec::with_capacity(expected);
+        let mut offset = 0;
+        for index in 0..expected {
+            let header = data.get(offset..offset + DATA_ITEM_HEADER_LEN).ok_or_else(incomplete)?;
+            let (code, transport_size) = (header[0], header[1]);
+            let raw_len = u16::from_be_bytes([header[2], header[3]]) as usize;
+            // BIT/BYTE/INT 传输类型的长度单位为位
+            let len = if matches!(transport_size, 0x03..=0x05) { raw_len.div_ceil(8) } else { raw_len };
+            offset += DATA_ITEM_HEADER_LEN;
+
+            if code == 0xFF {
+                let bytes = data.get(offset..offset + len).ok_or_else(incomplete)?;
+                values.push(Ok(bytes.to_vec()));
+            } else {
+                values.push(Err(return_code_error(code)));
+            }
+            offset += len;
+            if index + 1 < expected {
+                offset = padded(offset);
+            }
+        }
+        Ok(values)
+    }
+
+    /// 写入多个变量，`items` 为 (地址, 数据)，按PDU大小自动拆分
+    pub async fn write_items(&mut self, items: &[(S7Address, Vec<u8>)]) -> AppResult<Vec<AppResult<()>>> {
+        let pdu_size = self.pdu_size as usize;
+        let mut results: Vec<AppResult<()>> = vec![Err(missing_response()); items.len()];
+
+        let mut sendable = Vec::with_capacity(items.len());
+        for (index, (_, data)) in items.iter().enumerate() {
+            if REQUEST_OVERHEAD + ITEM_SPEC_LEN + DATA_ITEM_HEADER_LEN + data.len() > pdu_size {
+                results[index] = Err(AppError::configuration_error(format!("写入长度 {} 字节超过PDU容量 {}", data.len(), pdu_size)));
+            } else {
+                sendable.push(index);
+            }
+        }
+
+        let costs: Vec<(usize, usize)> = sendable
+            .iter()
+            .map(|&i| (ITEM_SPEC_LEN + padded(DATA_ITEM_HEADER_LEN + items[i].1.len()), 1))
+            .collect();
+        for batch in plan_batches(&costs, pdu_size) {
+            let indices = &sendable[batch];
+            let mut parameter = vec![0x05, indices.len() as u8];
+            let mut data = Vec::new();
+            for (position, &i) in indices.iter().enumerate() {
+                let (address, bytes) = &items[i];
+                parameter.extend_from_slice(&address.item_spec(bytes.len() as u16));
+                let (transport_size, bit_len) = if address.is_bit() {
+                    (0x03u8, 1u16)
+                } else {
+                    (0x04u8, bytes.len() as u16 * 8)
+                };
+                data.extend_from_slice(&[0x00, transport_size]);
+                data.extend_from_slice(&bit_len.to_be_bytes());
+                data.extend_from_slice(bytes);
+                if position + 1 < indices.len() && bytes.len() % 2 == 1 {
+                    data.push(0x00);
+                }
+            }
+
+            let pdu = self.exchange(&parameter, &data).await?;
+            let param_len = u16::from_be_bytes([pdu[6], pdu[7]]) as usize;
+            let codes = pdu
+                .get(12 + param_len..12 + param_len + indices.len())
+                .ok_or_else(|| AppError::plc_communication_error("S7写入应答数据不完整"))?;
+            for (&i, &code) in indices.iter().zip(codes) {
+                results[i] = if code == 0xFF { Ok(()) } else { Err(return_code_error(code)) };
+            }
+        }
+        Ok(results)
+    }
+}
+
+/// 按请求的数据类型确定读取长度
+fn read_item_for(address: S7Address, request: &ReadRequest) -> Result<(S7Address, u16), String> {
+    let expect = |width: S7Width, type_name: &str| {
+        if address.width == width {
+            Ok((address, width.byte_len()))
+        } else {
+            Err(format!("{} 需要{}地址，实际为 {:?}", request.address, type_name, address.width))
+        }
+    };
+    match request.data_type {
+        PlcDataType::Bool if address.is_bit() => Ok((address, 1)),
+        PlcDataType::Bool => Err(format!("{} 不是位地址", request.address)),
+        PlcDataType::Int16 => expect(S7Width::Word, "字(W)"),
+        PlcDataType::Int32 | PlcDataType::Float32 => expect(S7Width::DWord, "双字(D)"),
+        PlcDataType::ByteArray if !address.is_bit() => {
+            Ok((address, request.array_length.unwrap_or(address.width.byte_len() as u32) as u16))
+        }
+        _ => Err(format!("不支持的数据类型: {:?}", request.data_type)),
+    }
+}
+
+/// 将读取的字节按数据类型解码（大端）
+fn decode_value(data_type: &PlcDataType, bytes: &[u8]) -> Result<PlcValue, String> {
+    let too_short = || format!("读取数据长度不足: {} 字节", bytes.len());
+    match data_type {
+        PlcDataType::Bool => bytes.first().map(|b| PlcValue::Bool(b & 0x01 != 0)).ok_or_else(too_short),
+        PlcDataType::Int16 => bytes
+            .get(..2)
+            .map(|b| PlcValue::Int16(i16::from_be_bytes([b[0], b[1]])))
+            .ok_or_else(too_short),
+        PlcDataType::Int32 => bytes
+            .get(..4)
+            .map(|b| PlcValue::Int32(i32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+            .ok_or_else(too_short),
+        PlcDataType::Float32 => bytes
+            .get(..4)
+            .map(|b| PlcValue::Float32(f32::from_be_bytes([b[0], b[1], b[2], b[3]])))
+            .ok_or_else(too_short),
+        PlcDataType::ByteArray => Ok(PlcValue::ByteArray(bytes.to_vec())),
+        other => Err(format!("不支持的数据类型: {:?}", other)),
+    }
+}
+
+/// 将写入值按地址宽度编码（大端）
+fn encode_value(address: &S7Address, value: &PlcValue) -> Result<Vec<u8>, String> {
+    match (value, address.width) {
+        (PlcValue::Bool(v), S7Width::Bit(_)) => Ok(vec![*v as u8]),
+        (PlcValue::Int16(v), S7Width::Word) => Ok(v.to_be_bytes().to_vec()),
+        (PlcValue::Int32(v), S7Width::DWord) => Ok(v.to_be_bytes().to_vec()),
+        (PlcValue::Float32(v), S7Width::DWord) => Ok(v.to_be_bytes().to_vec()),
+        (PlcValue::ByteArray(bytes), width) if !matches!(width, S7Width::Bit(_)) && !bytes.is_empty() => Ok(bytes.clone()),
+        (value, width) => Err(format!("写入值 {:?} 与地址宽度 {:?} 不匹配", value, width)),
+    }
+}
+
+/// 单个S7连接的状态
+#[derive(Debug)]
+struct S7Connection {
+    config: PlcConnectionConfig,
+    params: S7ConnectParams,
+    handle: ConnectionHandle,
+    /// 通信失败后置为None，下次读写时自动重连
+    client: Mutex<Option<S7Client>>,
+    stats: Arc<Mutex<ConnectionStats>>,
+}
+
+impl S7Connection {
+    async fn ensure_client<'a>(&self, guard: &'a mut Option<S7Client>) -> AppResult<&'a mut S7Client> {
+        if guard.is_none() {
+            log::info!("🔗 S7重新连接: {} ({})", self.config.name, self.config.endpoint());
+            match S7Client::connect(&self.config.host, self.config.port, self.params, self.config.timeout_ms).await {
+                Ok(client) => *guard = Some(client),
+                Err(e) => {
+                    self.stats.lock().await.connection_errors += 1;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(guard.as_mut().expect("S7客户端已建立"))
+    }
+}
+
+/// 西门子S7 PLC通信服务
+#[derive(Debug, Default)]
+pub struct S7PlcService {
+    connections: RwLock<HashMap<String, Arc<S7Connection>>>,
+    default_handle: Mutex<Option<ConnectionHandle>>,
+}
+
+impl S7PlcService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    async fn get_connection(&self, handle: &ConnectionHandle) -> AppResult<Arc<S7Connection>> {
+        self.connections
+            .read()
+            .await
+            .get(&handle.connection_id)
+            .cloned()
+            .ok_or_else(|| AppError::not_found_error("S7连接", &handle.connection_id))
+    }
+
+    async fn read_items(&self, handle: &ConnectionHandle, items: &[(S7Address, u16)]) -> AppResult<Vec<AppResult<Vec<u8>>>> {
+        let connection = self.get_connection(handle).await?;
+        let start_time = Utc::now();
+        let mut guard = connection.client.lock().await;
+        let client = connection.ensure_client(&mut guard).await?;
+        match client.read_items(items).await {
+            Ok(values) => {
+                update_read_stats(&connection.stats, start_time).await;
+                Ok(values)
+            }
+            Err(e) => {
+                log::warn!("❌ S7读取失败，连接将重建: {} - {}", connection.config.name, e);
+                *guard = None;
+                connection.stats.lock().await.connection_errors += 1;
+                Err(e)
+            }
+        }
+    }
+
+    async fn write_items(&self, handle: &ConnectionHandle, items: &[(S7Address, Vec<u8>)]) -> AppResult<Vec<AppResult<()>>> {
+        let connection = self.get_connection(handle).await?;
+        let start_time = Utc::now();
+        let mut guard = connection.client.lock().await;
+        let client = connection.ensure_client(&mut guard).await?;
+        match client.write_items(items).await {
+            Ok(results) => {
+                update_write_stats(&connection.stats, start_time).await;
+                Ok(results)
+            }
+            Err(e) => {
+                log::warn!("❌ S7写入失败，连接将重建: {} - {}", connection.config.name, e);
+                *guard = None;
+                connection.stats.lock().await.connection_errors += 1;
+                Err(e)
+            }
+        }
+    }
+
+    async fn read_single(&self, handle: &ConnectionHandle, address: &str, data_type: PlcDataType) -> AppResult<PlcValue> {
+        let request = ReadRequest { id: address.to_string(), address: address.to_string(), data_type, array_length: None };
+        let item = read_item_for(S7Address::parse(address)?, &request).map_err(AppError::validation_error)?;
+        let bytes = self
+            .read_items(handle, &[item])
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(missing_response()))
+            .map_err(|e| e.with_context(format!("S7读取 {} 失败", address)))?;
+        decode_value(&request.data_type, &bytes).map_err(AppError::validation_error)
+    }
+
+    async fn write_single(&self, handle: &ConnectionHandle, address: &str, value: PlcValue) -> AppResult<()> {
+        let parsed = S7Address::parse(address)?;
+        let bytes = encode_value(&parsed, &value).map_err(AppError::validation_error)?;
+        self.write_items(handle, &[(parsed, bytes)])
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(missing_response()))
+            .map_err(|e| e.with_context(format!("S7写入 {} 失败", address)))
+    }
+}
+
+#[async_trait::async_trait]
+impl BaseService for S7PlcService {
+    fn service_name(&self) -> &'static str {
+        "S7PlcService"
+    }
+
+    async fn initialize(&mut self) -> AppResult<()> {
+        log::info!("初始化西门子S7 PLC通信服务");
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> AppResult<()> {
+        log::info!("关闭西门子S7 PLC通信服务");
+        self.connections.write().await.clear();
+        *self.default_handle.lock().await = None;
+        Ok(())
+    }
+
+    async fn health_check(&self) -> AppResult<()> {
+        log::debug!("S7服务健康检查: 连接数 = {}", self.connections.read().await.len());
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl IPlcCommunicationService for S7PlcService {
+    async fn connect(&self, config: &PlcConnectionConfig) -> AppResult<ConnectionHandle> {
+        if let Some(existing) = self.connections.read().await.get(&config.id) {
+            if existing.client.lock().await.is_some() {
+                return Ok(existing.handle.clone());
+            }
+        }
+
+        log::info!("连接到S7 PLC: {} ({})", config.name, config.endpoint());
+        let params = S7ConnectParams::from_protocol_params(&config.protocol_params);
+        let client = S7Client::connect(&config.host, config.port, params, config.timeout_ms).await?;
+
+        let now = Utc::now();
+        let handle = ConnectionHandle {
+            connection_id: config.id.clone(),
+            handle_id: Uuid::new_v4().to_string(),
+            protocol: PlcProtocol::SiemensS7,
+            created_at: now,
+            last_activity: now,
+        };
+        let connection = S7Connection {
+            config: config.clone(),
+            params,
+            handle: handle.clone(),
+            client: Mutex::new(Some(client)),
+            stats: Arc::new(Mutex::new(ConnectionStats {
+                connection_id: config.id.clone(),
+                total_reads: 0,
+                total_writes: 0,
+                successful_reads: 0,
+                successful_writes: 0,
+                average_read_time_ms: 0.0,
+                average_write_time_ms: 0.0,
+                connection_established_at: now,
+                last_communication: now,
+                connection_errors: 0,
+                queue_depth: 0,
+                max_queue_depth: 0,
+                average_queue_wait_ms: 0.0,
+                max_queue_wait_ms: 0.0,
+                expired_requests: 0,
+            })),
+        };
+        self.connections.write().await.insert(config.id.clone(), Arc::new(connection));
+        self.default_handle.lock().await.get_or_insert_with(|| handle.clone());
+        Ok(handle)
+    }
+
+    async fn disconnect(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        log::info!("断开S7连接: {}", handle.connection_id);
+        self.connections.write().await.remove(&handle.connection_id);
+        Ok(())
+    }
+
+    async fn is_connected(&self, handle: &ConnectionHandle) -> AppResult<bool> {
+        let connection = self.get_connection(handle).await?;
+        let connected = connection.client.lock().await.is_some();
+        Ok(connected)
+    }
+
+    async fn probe(&self, handle: &ConnectionHandle) -> AppResult<()> {
+        // 读取MB0：所有CPU都有M区，变量级错误（如无访问权限）同样说明链路正常
+        let address = S7Address::parse("MB0")?;
+        self.read_items(handle, &[(address, address.width.byte_len())]).await.map(|_| ())
+    }
+
+    async fn read_bool(&self, handle: &ConnectionHandle, address: &str) -> AppResult<bool> {
+        match self.read_single(handle, address, PlcDataType::Bool).await? {
+            PlcValue::Bool(value) => Ok(value),
+            other => Err(AppError::plc_communication_error(format!("S7读取类型不匹配: {:?}", other))),
+        }
+    }
+
+    async fn write_bool(&self, handle: &ConnectionHandle, address: &str, value: bool) -> AppResult<()> {
+        self.write_single(handle, address, PlcValue::Bool(value)).await
+    }
+
+    async fn read_f32(&self, handle: &ConnectionHandle, address: &str) -> AppResult<f32> {
+        match self.read_single(handle, address, PlcDataType::Float32).await? {
+            PlcValue::Float32(value) => Ok(value),
+            other => Err(AppError::plc_communication_error(format!("S7读取类型不匹配: {:?}", other))),
+        }
+    }
+
+    async fn write_f32(&self, handle: &ConnectionHandle, address: &str, value: f32) -> AppResult<()> {
+        self.write_single(handle, address, PlcValue::Float32(value)).await
+    }
+
+    async fn read_i32(&self, handle: &ConnectionHandle, address: &str) -> AppResult<i32> {
+        match self.read_single(handle, address, PlcDataType::Int32).await? {
+            PlcValue::Int32(value) => Ok(value),
+            other => Err(AppError::plc_communication_error(format!("S7读取类型不匹配: {:?}", other))),
+        }
+    }
+
+    async fn write_i32(&self, handle: &ConnectionHandle, address: &str, value: i32) -> AppResult<()> {
+        self.write_single(handle, address, PlcValue::Int32(value)).await
+    }
+
+    async fn batch_read(&self, handle: &ConnectionHandle, requests: &[ReadRequest]) -> AppResult<Vec<ReadResult>> {
+        let start_time = Utc::now();
+        let planned: Vec<AppResult<(S7Address, u16)>> = requests
+            .iter()
+            .map(|r| S7Address::parse(&r.address).and_then(|a| read_item_for(a, r).map_err(AppError::validation_error)))
+            .collect();
+        let items: Vec<(S7Address, u16)> = planned.iter().filter_map(|p| p.as_ref().ok().copied()).collect();
+        let mut values = self.read_items(handle, &items).await?.into_iter();
+        let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+
+        Ok(requests
+            .iter()
+            .zip(planned)
+            .map(|(request, plan)| {
+                let value = plan.and_then(|_| values.next().unwrap_or_else(|| Err(missing_response())))
+                    .and_then(|bytes| decode_value(&request.data_type, &bytes).map_err(AppError::validation_error));
+                match value {
+                    Ok(value) => ReadResult {
+                        request_id: request.id.clone(),
+                        success: true,
+                        value: Some(value),
+                        error_message: None,
+                        failure_kind: None,
+                        execution_time_ms: elapsed,
+                    },
+                    Err(e) => ReadResult {
+                        request_id: request.id.clone(),
+                        success: false,
+                        value: None,
+                        error_message: Some(e.to_string()),
+                        failure_kind: Some(e.failure_kind()),
+                        execution_time_ms: elapsed,
+                    },
+                }
+            })
+            .collect())
+    }
+
+    async fn batch_write(&self, handle: &ConnectionHandle, requests: &[WriteRequest]) -> AppResult<Vec<WriteResult>> {
+        let start_time = Utc::now();
+        let planned: Vec<AppResult<(S7Address, Vec<u8>)>> = requests
+            .iter()
+            .map(|r| {
+                let address = S7Address::parse(&r.address)?;
+                encode_value(&address, &r.value)
+                    .map(|bytes| (address, bytes))
+                    .map_err(AppError::validation_error)
+            })
+            .collect();
+        let items: Vec<(S7Address, Vec<u8>)> = planned.iter().filter_map(|p| p.as_ref().ok().cloned()).collect();
+        let mut outcomes = self.write_items(handle, &items).await?.into_iter();
+        let elapsed = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+
+        Ok(requests
+            .iter()
+            .zip(planned)
+            .map(|(request, plan)| {
+                let outcome = plan.and_then(|_| outcomes.next().unwrap_or_else(|| Err(missing_response())));
+                WriteResult {
+                    request_id: request.id.clone(),
+                    success: outcome.is_ok(),
+                    error_message: outcome.as_ref().err().map(|e| e.to_string()),
+                    failure_kind: outcome.err().map(|e| e.failure_kind()),
+                    execution_time_ms: elapsed,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_connection_stats(&self, handle: &ConnectionHandle) -> AppResult<ConnectionStats> {
+        let connection = self.get_connection(handle).await?;
+        let stats = connection.stats.lock().await.clone();
+        Ok(stats)
+    }
+
+    async fn test_connection(&self, config: &PlcConnectionConfig) -> AppResult<ConnectionTestResult> {
+        let start_time = Utc::now();
+        let params = S7ConnectParams::from_protocol_params(&config.protocol_params);
+        let result = S7Client::connect(&config.host, config.port, params, config.timeout_ms).await;
+        let connection_time = Utc::now().signed_duration_since(start_time).num_milliseconds() as u64;
+        let device_info = Some(format!("{} (机架{} 插槽{})", config.endpoint(), params.rack, params.slot));
+
+        Ok(match result {
+            Ok(client) => ConnectionTestResult {
+                success: true,
+                connection_time_ms: connection_time,
+                error_message: None,
+                protocol_info: Some(format!("Siemens S7 (PDU {})", client.pdu_size())),
+                device_info,
+            },
+            Err(e) => ConnectionTestResult {
+                success: false,
+                connection_time_ms: connection_time,
+                error_message: Some(e.to_string()),
+                protocol_info: Some("Siemens S7".to_string()),
+                device_info,
+            },
+        })
+    }
+
+    async fn default_handle_by_id(&self, connection_id: &str) -> Option<ConnectionHandle> {
+        self.connections.read().await.get(connection_id).map(|c| c.handle.clone())
+    }
+
+    async fn default_handle(&self) -> Option<ConnectionHandle> {
+        self.default_handle.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_point_table_addresses() {
+        let db = S7Address::parse("DB1.DBD0").unwrap();
+        assert_eq!(db, S7Address { area: S7Area::DataBlock(1), byte_offset: 0, width: S7Width::DWord });
+
+        let bit = S7Address::parse("DB10.DBX4.3").unwrap();
+        assert_eq!(bit.width, S7Width::Bit(3));
+        assert_eq!(bit.item_spec(1)[9..], [0x00, 0x00, 0x23]);
+
+        assert_eq!(S7Address::parse("%MD100").unwrap().area, S7Area::Merker);
+        assert_eq!(S7Address::parse("%I0.7").unwrap().width, S7Width::Bit(7));
+        assert_eq!(S7Address::parse("qw64").unwrap().width, S7Width::Word);
+
+        assert!(S7Address::parse("%I0.8").is_err());
+        assert!(S7Address::parse("DB1.DBD").is_err());
+        assert!(S7Address::parse("40001").is_err());
+    }
+
+    #[test]
+    fn splits_items_by_pdu_size() {
+        // 240字节PDU下每个读取项请求占12字节: 12 + 19*12 = 240，第20项会使请求超长
+        let costs = vec![(ITEM_SPEC_LEN, 8); 45];
+        let batches = plan_batches(&costs, 240);
+        assert_eq!(batches, vec![0..19, 19..38, 38..45]);
+
+        // 大数据项受应答长度限制
+        let costs = vec![(ITEM_SPEC_LEN, 104); 5];
+        assert_eq!(plan_batches(&costs, 240), vec![0..2, 2..4, 4..5]);
+    }
+
+    /// 本机ISO-on-TCP替身服务器：应答COTP连接、通信参数协商与读写变量请求
+    /// 只有DB1可访问，其余存储区返回"对象不存在"
+    struct StandInPlc {
+        port: u16,
+        db1: Arc<std::sync::Mutex<Vec<u8>>>,
+        sessions: Arc<std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>>,
+    }
+
+    impl StandInPlc {
+        async fn start() -> Self {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let db1 = Arc::new(std::sync::Mutex::new(vec![0u8; 64]));
+            let sessions = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let (memory, tasks) = (db1.clone(), sessions.clone());
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let session = tokio::spawn(Self::serve(stream, memory.clone()));
+                    tasks.lock().unwrap().push(session);
+                }
+            });
+            Self { port, db1, sessions }
+        }
+
+        /// 断开所有已建立的会话，模拟PLC重启或网线断开
+        fn drop_sessions(&self) {
+            for session in self.sessions.lock().unwrap().drain(..) {
+                session.abort();
+            }
+        }
+
+        async fn serve(mut stream: TcpStream, db1: Arc<std::sync::Mutex<Vec<u8>>>) {
+            loop {
+                let mut header = [0u8; 4];
+                if stream.read_exact(&mut header).await.is_err() {
+                    return;
+                }
+                let mut payload = vec![0u8; u16::from_be_bytes([header[2], header[3]]) as usize - 4];
+                if stream.read_exact(&mut payload).await.is_err() {
+                    return;
+                }
+                let reply = if payload[1] == 0xE0 {
+                    vec![0x06, 0xD0, 0x00, 0x01, 0x00, 0x01, 0x00]
+                } else {
+                    Self::handle_job(&payload[COTP_DT_HEADER.len()..], &db1)
+                };
+                let mut frame = vec![0x03, 0x00];
+                frame.extend_from_slice(&((reply.len() + 4) as u16).to_be_bytes());
+                frame.extend_from_slice(&reply);
+                if stream.write_all(&frame).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        fn handle_job(job: &[u8], db1: &std::sync::Mutex<Vec<u8>>) -> Vec<u8> {
+            let param_len = u16::from_be_bytes([job[6], job[7]]) as usize;
+            let parameter = &job[10..10 + param_len];
+            let mut data = &job[10 + param_len..];
+            let mut memory = db1.lock().unwrap();
+            let (reply_param, reply_data) = match parameter[0] {
+                0xF0 => (vec![0xF0, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0xF0], Vec::new()),
+                function @ (0x04 | 0x05) => {
+                    let count = parameter[1] as usize;
+                    let mut reply_data = Vec::new();
+                    for (index, spec) in parameter[2..].chunks(ITEM_SPEC_LEN).take(count).enumerate() {
+                        let is_bit = spec[3] == 0x01;
+                        let len = u16::from_be_bytes([spec[4], spec[5]]) as usize;
+                        let db = u16::from_be_bytes([spec[6], spec[7]]);
+                        let bit_address = u32::from_be_bytes([0, spec[9], spec[10], spec[11]]) as usize;
+                        let (offset, bit) = (bit_address >> 3, bit_address & 0x07);
+                        let accessible = spec[8] == 0x84 && db == 1 && offset + len <= memory.len();
+
+                        if function == 0x05 {
+                            let bit_len = u16::from_be_bytes([data[2], data[3]]) as usize;
+                            let byte_len = if data[1] == 0x03 { 1 } else { bit_len / 8 };
+                            let bytes = &data[4..4 + byte_len];
+                            if accessible && is_bit {
+                                memory[offset] = (memory[offset] & !(1 << bit)) | ((bytes[0] & 1) << bit);
+                            } else if accessible {
+                                memory[offset..offset + len].copy_from_slice(bytes);
+                            }
+                            data = &data[(4 + byte_len + byte_len % 2).min(data.len())..];
+                            reply_data.push(if accessible { 0xFF } else { 0x0A });
+                            continue;
+                        }
+
+                        if !accessible {
+                            reply_data.extend_from_slice(&[0x0A, 0x00, 0x00, 0x00]);
+                        } else if is_bit {
+                            reply_data.extend_from_slice(&[0xFF, 0x03, 0x00, 0x01, (memory[offset] >> bit) & 1]);
+                        } else {
+                            reply_data.extend_from_slice(&[0xFF, 0x04]);
+                            reply_data.extend_from_slice(&((len * 8) as u16).to_be_bytes());
+                            reply_data.extend_from_slice(&memory[offset..offset + len]);
+                        }
+                        if index + 1 < count && reply_data.len() % 2 == 1 {
+                            reply_data.push(0x00);
+                        }
+                    }
+                    (vec![function, count as u8], reply_data)
+                }
+                other => panic!("替身PLC不支持的功能码: 0x{:02X}", other),
+            };
+
+            let mut reply = COTP_DT_HEADER.to_vec();
+            reply.extend_from_slice(&[0x32, 0x03, 0x00, 0x00, job[4], job[5]]);
+            reply.extend_from_slice(&(reply_param.len() as u16).to_be_bytes());
+            reply.extend_from_slice(&(reply_data.len() as u16).to_be_bytes());
+            reply.extend_from_slice(&[0x00, 0x00]);
+            reply.extend_from_slice(&reply_param);
+            reply.extend_from_slice(&reply_data);
+            reply
+        }
+    }
+
+    fn connection_config(port: u16) -> PlcConnectionConfig {
+        PlcConnectionConfig {
+            id: "s7_loopback".to_string(),
+            name: "S7替身".to_string(),
+            protocol: PlcProtocol::SiemensS7,
+            host: "127.0.0.1".to_string(),
+            port,
+            timeout_ms: 1000,
+            read_timeout_ms: 1000,
+            write_timeout_ms: 1000,
+            byte_order: "ABCD".to_string(),
+            zero_based_address: false,
+            retry_count: 0,
+            retry_interval_ms: 0,
+            protocol_params: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_and_writes_through_an_iso_on_tcp_server() {
+        let plc = StandInPlc::start().await;
+        plc.db1.lock().unwrap()[0..4].copy_from_slice(&12.5f32.to_be_bytes());
+        let service = S7PlcService::new();
+        let handle = service.connect(&connection_config(plc.port)).await.unwrap();
+
+        assert_eq!(service.read_f32(&handle, "DB1.DBD0").await.unwrap(), 12.5);
+        service.write_f32(&handle, "DB1.DBD8", -3.25).await.unwrap();
+        assert_eq!(plc.db1.lock().unwrap()[8..12], (-3.25f32).to_be_bytes());
+        service.write_bool(&handle, "DB1.DBX4.3", true).await.unwrap();
+        assert_eq!(plc.db1.lock().unwrap()[4], 0x08);
+        assert!(service.read_bool(&handle, "DB1.DBX4.3").await.unwrap());
+
+        let request = |id: &str, address: &str, data_type: PlcDataType| ReadRequest {
+            id: id.to_string(), address: address.to_string(), data_type, array_length: None,
+        };
+        let results = service.batch_read(&handle, &[
+            request("flow", "DB1.DBD8", PlcDataType::Float32),
+            request("missing", "DB2.DBD0", PlcDataType::Float32),
+            request("bit", "DB1.DBX4.3", PlcDataType::Bool),
+        ]).await.unwrap();
+        assert!(matches!(results[0].value, Some(PlcValue::Float32(v)) if v == -3.25));
+        assert!(!results[1].success);
+        assert_eq!(results[1].failure_kind, Some(crate::models::FailureKind::Configuration));
+        assert!(matches!(results[2].value, Some(PlcValue::Bool(true))));
+    }
+
+    #[tokio::test]
+    async fn reconnects_after_the_server_drops_the_session() {
+        let plc = StandInPlc::start().await;
+        plc.db1.lock().unwrap()[0..4].copy_from_slice(&7.0f32.to_be_bytes());
+        let service = S7PlcService::new();
+        let handle = service.connect(&connection_config(plc.port)).await.unwrap();
+        assert_eq!(service.read_f32(&handle, "DB1.DBD0").await.unwrap(), 7.0);
+
+        plc.drop_sessions();
+        let error = service.read_f32(&handle, "DB1.DBD0").await.unwrap_err();
+        assert_eq!(error.failure_kind(), crate::models::FailureKind::Communication);
+        assert!(!service.is_connected(&handle).await.unwrap(), "通信失败后连接应标记为待重建");
+
+        // 下次读写时自动重连
+        assert_eq!(service.read_f32(&handle, "DB1.DBD0").await.unwrap(), 7.0);
+        assert!(service.is_connected(&handle).await.unwrap());
+        assert_eq!(service.get_connection_stats(&handle).await.unwrap().connection_errors, 1);
+    }
+
+    #[tokio::test]
+    async fn probe_detects_a_session_dropped_while_idle() {
+        let plc = StandInPlc::start().await;
+        let service = S7PlcService::new();
+        let handle = service.connect(&connection_config(plc.port)).await.unwrap();
+        service.probe(&handle).await.unwrap();
+
+        // 空闲期间会话被断开，仅凭连接状态无法发现
+        plc.drop_sessions();
+        assert!(service.is_connected(&handle).await.unwrap());
+        let error = service.probe(&handle).await.unwrap_err();
+        assert_eq!(error.failure_kind(), crate::models::FailureKind::Communication);
+        assert!(!service.is_connected(&handle).await.unwrap());
+
+        // 下次探测时自动重连
+        service.probe(&handle).await.unwrap();
+        assert!(service.is_connected(&handle).await.unwrap());
+    }
+}
@@ -39,7 +39,9 @@
 
 use tauri::State;
 use serde::{Deserialize, Serialize};
-use crate::models::structs::{ChannelPointDefinition, TestBatchInfo};
+use crate::models::structs::{ChannelPointDefinition, ChannelTestInstance, RawTestOutcome, TestBatchInfo};
+use crate::infrastructure::{BackupInfo, ExtendedPersistenceService, QueryCriteria, QueryResult};
+use crate::domain::services::persistence_service::{CleanupResult, RetentionPolicy, TransactionOperation};
 use crate::application::services::data_import_service::{DataImportService, ImportResult};
 use crate::application::services::batch_allocation_service::{BatchAllocationService, AllocationStrategy, AllocationResult as BatchAllocationResult};
 use crate::infrastructure::excel::ExcelImporter;
@@ -236,8 +238,19 @@ pub async fn create_test_batch(
     // Rust知识点：&state 获取State的引用，避免所有权转移
     let persistence_service = &state.persistence_service;
 
-    // 保存批次信息
-    match persistence_service.save_batch_info(&test_batch).await {
+    // 🔥 通道定义必须关联到批次，建立一对多关系
+    // 业务说明：批次与通道定义在同一事务内保存，避免留下没有通道定义的空批次
+    let mut operations = vec![TransactionOperation::SaveBatchInfo(test_batch.clone())];
+    for definition in &batch_data.preview_data {
+        let mut definition = definition.clone();
+        definition.batch_id = Some(test_batch.batch_id.clone());
+        info!("🔗 为通道定义 {} 设置批次ID: {}", definition.tag, test_batch.batch_id);
+        operations.push(TransactionOperation::SaveChannelDefinition(definition));
+    }
+    let saved_count = batch_data.preview_data.len();
+
+    // 保存批次信息和通道定义
+    match persistence_service.execute_transaction(operations).await {
         Ok(_) => {
             info!("测试批次创建成功: {}", test_batch.batch_id);
 
@@ -250,29 +263,6 @@ pub async fn create_test_batch(
                 info!("批次 {} 已添加到当前会话跟踪", test_batch.batch_id);
             }
 
-            // 🔥 保存通道定义（设置批次ID）
-            // 业务说明：通道定义必须关联到批次，建立一对多关系
-            let mut saved_count = 0;
-            let mut updated_definitions = batch_data.preview_data.clone();
-
-            // 为每个通道定义设置批次ID
-            // Rust知识点：&mut 可变引用，允许修改数据
-            for definition in &mut updated_definitions {
-                definition.batch_id = Some(test_batch.batch_id.clone());
-                info!("🔗 为通道定义 {} 设置批次ID: {}", definition.tag, test_batch.batch_id);
-            }
-
-            // 批量保存通道定义
-            for definition in &updated_definitions {
-                match persistence_service.save_channel_definition(definition).await {
-                    Ok(_) => saved_count += 1,
-                    Err(e) => {
-                        // 单个保存失败不影响整体流程，记录错误继续
-                        error!("保存通道定义失败: {} - {}", definition.tag, e);
-                    }
-                }
-            }
-
             info!("成功保存{}个通道定义", saved_count);
 
             Ok(CreateBatchResponse {
@@ -1309,72 +1299,44 @@ pub async fn parse_excel_and_create_batch_cmd(
     // 获取持久化服务
     let persistence_service = &state.persistence_service;
 
-    // 第三步：保存批次信息
-    match persistence_service.save_batch_info(&test_batch).await {
-        Ok(_) => {
-            info!("测试批次创建成功: {}", test_batch.batch_id);
-
-            // 将批次ID添加到当前会话跟踪中
-            {
-                let mut session_batch_ids = state.session_batch_ids.lock().await;
-                session_batch_ids.insert(test_batch.batch_id.clone());
-                info!("批次 {} 已添加到当前会话跟踪", test_batch.batch_id);
-            }
-        }
-        Err(e) => {
-            error!("创建测试批次失败: {}", e);
-            return Ok(ParseExcelAndCreateBatchResponse {
-                success: false,
-                message: format!("创建批次失败: {}", e),
-                batch_id: None,
-                definitions_count: definitions.len(),
-                batch_info: None,
-            });
-        }
-    }
-
-    // 🔥 第四步：为通道定义设置批次ID并保存
-    let mut saved_count = 0;
-    let mut errors = Vec::new();
-
-    // 为每个通道定义设置批次ID
-    let mut updated_definitions = definitions.clone();
-    for definition in &mut updated_definitions {
+    // 🔥 第三步：为通道定义设置批次ID，与批次信息在同一事务内保存
+    let mut operations = vec![TransactionOperation::SaveBatchInfo(test_batch.clone())];
+    for definition in &definitions {
+        let mut definition = definition.clone();
         definition.batch_id = Some(test_batch.batch_id.clone());
         info!("🔗 为通道定义 {} 设置批次ID: {}", definition.tag, test_batch.batch_id);
+        operations.push(TransactionOperation::SaveChannelDefinition(definition));
     }
 
-    for definition in &updated_definitions {
-        match persistence_service.save_channel_definition(definition).await {
-            Ok(_) => saved_count += 1,
-            Err(e) => {
-                let error_msg = format!("保存通道定义失败: {} - {}", definition.tag, e);
-                error!("{}", error_msg);
-                errors.push(error_msg);
-            }
-        }
+    if let Err(e) = persistence_service.execute_transaction(operations).await {
+        error!("创建测试批次失败: {}", e);
+        return Ok(ParseExcelAndCreateBatchResponse {
+            success: false,
+            message: format!("创建批次失败: {}", e),
+            batch_id: None,
+            definitions_count: definitions.len(),
+            batch_info: None,
+        });
     }
+    info!("测试批次创建成功: {}", test_batch.batch_id);
 
-    // 第五步：返回结果
-    let success = saved_count > 0;
-    let message = if success {
-        if errors.is_empty() {
-            format!("成功创建批次并保存{}个通道定义", saved_count)
-        } else {
-            format!("批次创建成功，保存{}个通道定义，{}个失败", saved_count, errors.len())
-        }
-    } else {
-        format!("批次创建失败，无法保存任何通道定义。错误: {}", errors.join("; "))
-    };
+    // 将批次ID添加到当前会话跟踪中
+    {
+        let mut session_batch_ids = state.session_batch_ids.lock().await;
+        session_batch_ids.insert(test_batch.batch_id.clone());
+        info!("批次 {} 已添加到当前会话跟踪", test_batch.batch_id);
+    }
 
+    // 第四步：返回结果
+    let message = format!("成功创建批次并保存{}个通道定义", definitions.len());
     info!("{}", message);
 
     Ok(ParseExcelAndCreateBatchResponse {
-        success,
+        success: true,
         message,
-        batch_id: if success { Some(test_batch.batch_id.clone()) } else { None },
+        batch_id: Some(test_batch.batch_id.clone()),
         definitions_count: definitions.len(),
-        batch_info: if success { Some(test_batch) } else { None },
+        batch_info: Some(test_batch),
     })
 }
 
@@ -1954,26 +1916,14 @@ pub async fn create_test_batch_with_definitions_cmd(
         return Err("没有提供任何通道定义".to_string());
     }
 
-    // 第一步：保存通道定义到数据库
+    // 第一步：在一个事务内批量保存通道定义到数据库
     let persistence_service = &state.persistence_service;
 
-    let mut saved_count = 0;
-    for definition in &definitions {
-        match persistence_service.save_channel_definition(definition).await {
-            Ok(_) => {
-                saved_count += 1;
-                debug!("成功保存通道定义: {}", definition.id);
-            }
-            Err(e) => {
-                error!("保存通道定义失败: {} - {}", definition.id, e);
-                // 继续保存其他定义，不中断整个过程
-            }
-        }
-    }
-
-    if saved_count == 0 {
-        return Err("没有成功保存任何通道定义".to_string());
+    if let Err(e) = persistence_service.save_channel_definitions(&definitions).await {
+        error!("保存通道定义失败: {}", e);
+        return Err(format!("保存通道定义失败: {}", e));
     }
+    debug!("成功保存{}个通道定义", definitions.len());
 
     // 第二步：创建测试批次
     let db = persistence_service.get_database_connection();
@@ -1991,23 +1941,7 @@ pub async fn create_test_batch_with_definitions_cmd(
         info!("🔧 从通道定义中获取站场名称: {:?}", updated_batch_info.station_name);
     }
 
-    // 第三步：保存通道定义到数据库
-    let mut saved_count = 0;
-    let mut failed_count = 0;
-
-    for definition in definitions.iter() {
-        match state.persistence_service.save_channel_definition(definition).await {
-            Ok(_) => {
-                saved_count += 1;
-            }
-            Err(e) => {
-                failed_count += 1;
-                warn!("保存通道定义失败: {} - {}", definition.tag, e);
-            }
-        }
-    }
-
-    // 第四步：创建测试批次
+    // 第三步：创建测试批次
     match allocation_service.create_test_batch(
         updated_batch_info.batch_name.clone(),
         updated_batch_info.product_model.clone(),
@@ -2167,20 +2101,11 @@ async fn execute_batch_allocation(
 ) -> Result<AllocationResult, String> {
     // 1. 首先保存通道定义到数据库
     // 业务说明：确保所有通道定义都持久化，即使后续分配失败也能保留数据
-    let mut saved_definitions_count = 0;
-    let mut failed_definitions_count = 0;
-
-    for definition in definitions.iter() {
-        match state.persistence_service.save_channel_definition(definition).await {
-            Ok(_) => {
-                saved_definitions_count += 1;
-            }
-            Err(e) => {
-                failed_definitions_count += 1;
-                warn!("保存通道定义失败: {} - {}", definition.tag, e);
-            }
-        }
-    }
+    // 批量保存在一个事务内完成，要么全部保存要么全部回滚
+    state.persistence_service.save_channel_definitions(definitions).await.map_err(|e| {
+        error!("保存通道定义失败: {}", e);
+        format!("保存通道定义失败: {}", e)
+    })?;
 
     // 2. 获取测试PLC配置
     // 业务说明：测试PLC配置定义了物理测试通道的能力和约束
@@ -2247,43 +2172,14 @@ async fn execute_batch_allocation(
 /// 业务说明：
 /// 负责将批次分配的结果存储到内存状态管理器中
 /// 包括：
-/// 1. 保存通道定义（如果还未保存）
-/// 2. 存储分配结果到状态管理器
-/// 3. 更新会话批次跟踪
-/// 
-/// Rust知识点：
-/// - if let Some(ref x) 模式匹配，ref避免移动所有权
+/// 1. 在一个事务内保存通道定义、批次和测试实例，并写入状态管理器缓存
+/// 2. 更新会话批次跟踪
 async fn store_allocation_to_state_manager(
     allocation_result: &AllocationResult,
     state: &AppState,
 ) -> Result<(), String> {
-    // 保存通道定义到数据库
-    if let Some(ref channel_definitions) = allocation_result.channel_definitions {
-        let mut saved_count = 0;
-        let mut failed_count = 0;
-
-        for definition in channel_definitions.iter() {
-            match state.persistence_service.save_channel_definition(definition).await {
-                Ok(_) => {
-                    saved_count += 1;
-                }
-                Err(e) => {
-                    failed_count += 1;
-                    error!("保存通道定义失败: ID={}, Tag={} - {}",
-                        definition.id, definition.tag, e);
-                }
-            }
-        }
-
-        if failed_count > 0 {
-            error!("通道定义保存完成: 成功={}, 失败={}", saved_count, failed_count);
-        }
-    } else {
-        warn!("分配结果中没有通道定义数据！");
-    }
-
     // 1. 存储批次分配结果到状态管理器
-    // 业务说明：状态管理器维护测试状态的内存缓存
+    // 业务说明：状态管理器维护测试状态的内存缓存，通道定义、批次与实例由它在同一事务内持久化
     match state.channel_state_manager.store_batch_allocation_result(allocation_result.clone()).await {
         Ok(_) => {
             // 存储成功
@@ -2481,3 +2377,157 @@ pub async fn restore_session_cmd(
     info!("恢复完成，会话键={}，加载 {} 个批次", target_key, target_batches.len());
     Ok(target_batches)
 }
+
+// ============================================================================
+// 条件查询命令
+// ============================================================================
+// 领域层接口也有同名的 query_* 方法，这里显式调用 ExtendedPersistenceService 上返回总数的版本
+
+/// 按条件分页查询通道定义
+///
+/// 业务说明：站场、批次、模块类型、位号模式、时间范围在数据库端过滤，返回本页数据与总数
+#[tauri::command]
+pub async fn query_channel_definitions_cmd(
+    criteria: QueryCriteria,
+    state: State<'_, AppState>,
+) -> Result<QueryResult<ChannelPointDefinition>, String> {
+    debug!("条件查询通道定义: {:?}", criteria);
+    ExtendedPersistenceService::query_channel_definitions(state.persistence_service.as_ref(), &criteria)
+        .await
+        .map_err(|e| {
+            error!("条件查询通道定义失败: {}", e);
+            format!("查询失败: {}", e)
+        })
+}
+
+/// 按条件分页查询测试实例
+///
+/// 业务说明：站场、模块类型、位号经通道定义关联过滤，状态对应实例的整体测试状态
+#[tauri::command]
+pub async fn query_test_instances_cmd(
+    criteria: QueryCriteria,
+    state: State<'_, AppState>,
+) -> Result<QueryResult<ChannelTestInstance>, String> {
+    debug!("条件查询测试实例: {:?}", criteria);
+    ExtendedPersistenceService::query_test_instances(state.persistence_service.as_ref(), &criteria)
+        .await
+        .map_err(|e| {
+            error!("条件查询测试实例失败: {}", e);
+            format!("查询失败: {}", e)
+        })
+}
+
+/// 按条件分页查询测试批次
+#[tauri::command]
+pub async fn query_test_batches_cmd(
+    criteria: QueryCriteria,
+    state: State<'_, AppState>,
+) -> Result<QueryResult<TestBatchInfo>, String> {
+    debug!("条件查询测试批次: {:?}", criteria);
+    ExtendedPersistenceService::query_test_batches(state.persistence_service.as_ref(), &criteria)
+        .await
+        .map_err(|e| {
+            error!("条件查询测试批次失败: {}", e);
+            format!("查询失败: {}", e)
+        })
+}
+
+/// 按条件分页查询原始测试结果
+#[tauri::command]
+pub async fn query_test_outcomes_cmd(
+    criteria: QueryCriteria,
+    state: State<'_, AppState>,
+) -> Result<QueryResult<RawTestOutcome>, String> {
+    debug!("条件查询测试结果: {:?}", criteria);
+    ExtendedPersistenceService::query_test_outcomes(state.persistence_service.as_ref(), &criteria)
+        .await
+        .map_err(|e| {
+            error!("条件查询测试结果失败: {}", e);
+            format!("查询失败: {}", e)
+        })
+}
+
+// ============================================================================
+// 数据库备份命令
+// ============================================================================
+
+/// 立即备份数据库
+///
+/// 业务说明：使用 `VACUUM INTO` 热备份，测试进行中也可执行；成功后记录最近备份时间
+#[tauri::command]
+pub async fn create_database_backup_cmd(
+    backup_name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<BackupInfo, String> {
+    let name = backup_name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "manual".to_string());
+    info!("手动备份数据库: {}", name);
+    let backup = state.persistence_service.backup(&name).await.map_err(|e| {
+        error!("备份数据库失败: {}", e);
+        format!("备份失败: {}", e)
+    })?;
+    crate::infrastructure::record_last_backup_time(state.app_settings_service.as_ref(), backup.created_at).await;
+    Ok(backup)
+}
+
+/// 列出全部数据库备份（按时间倒序）
+#[tauri::command]
+pub async fn list_database_backups_cmd(
+    state: State<'_, AppState>,
+) -> Result<Vec<BackupInfo>, String> {
+    state.persistence_service.list_backups().await.map_err(|e| {
+        error!("读取备份列表失败: {}", e);
+        format!("读取备份列表失败: {}", e)
+    })
+}
+
+/// 从备份恢复数据库
+///
+/// 业务说明：备份先经完整性与结构版本校验，覆盖前自动保留一份 pre_restore 备份；
+/// 恢复后清空内存缓存与会话批次，前端需重新加载批次数据
+#[tauri::command]
+pub async fn restore_database_backup_cmd(
+    backup_path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    info!("从备份恢复数据库: {}", backup_path);
+    state.persistence_service
+        .restore_from_backup(&std::path::PathBuf::from(&backup_path))
+        .await
+        .map_err(|e| {
+            error!("恢复数据库失败: {}", e);
+            format!("恢复失败: {}", e)
+        })?;
+
+    state.channel_state_manager.clear_caches().await;
+    state.session_batch_ids.lock().await.clear();
+    Ok(())
+}
+
+/// 按保留策略清理过期测试数据
+///
+/// 业务说明：未传入策略时使用配置文件中的保留策略；有数据被删除或精简时按配置执行VACUUM，
+/// 返回各表删除数、归档数与回收的磁盘空间。有批次正在测试时拒绝执行
+#[tauri::command]
+pub async fn cleanup_expired_data_cmd(
+    policy: Option<RetentionPolicy>,
+    state: State<'_, AppState>,
+) -> Result<CleanupResult, String> {
+    let config = crate::utils::config::effective_persistence_config().retention;
+    let policy = policy.unwrap_or_else(|| RetentionPolicy::from(&config));
+    info!("清理过期测试数据: {:?}", policy);
+    crate::infrastructure::persistence::data_retention::run_cleanup(
+        state.persistence_service.as_ref(),
+        &policy,
+        config.compact_after_cleanup,
+        &crate::application::services::test_coordination_service::batches_running_probe(
+            state.test_coordination_service.clone(),
+        ),
+    )
+    .await
+    .map_err(|e| {
+        error!("清理过期测试数据失败: {}", e);
+        format!("清理失败: {}", e)
+    })
+}
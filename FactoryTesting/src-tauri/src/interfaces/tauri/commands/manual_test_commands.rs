@@ -58,6 +58,8 @@ use rand::Rng;
 // === 领域服务导入 ===
 use crate::domain::services::plc_comm_extension::PlcServiceLegacyExt;  // PLC服务遗留扩展
 use crate::domain::services::plc_communication_service::IPlcCommunicationService; // PLC通信服务接口
+use crate::infrastructure::modbus_scheduler::{with_request_source, RequestSource}; // 手动命令按手动优先级排队
+use crate::infrastructure::modbus_capture::with_capture_instance; // 手动采集的报文录制标记为对应测试实例
 
 // ==================== 常量 ====================
 /// AO 手动采集允许的百分比偏差
@@ -91,6 +93,7 @@ use crate::application::services::ITestCoordinationService;  // 测试协调服
 // === 基础设施层服务导入 ===
 use crate::infrastructure::IPlcMonitoringService;            // PLC监控服务接口
 use crate::infrastructure::plc_communication::global_plc_service; // 全局PLC服务实例
+use crate::infrastructure::plc_communication::ModbusAddress;      // Modbus地址模型
 
 // === PLC配置相关导入 ===
 use crate::domain::services::plc_communication_service::{PlcConnectionConfig, PlcProtocol};
@@ -422,6 +425,28 @@ pub struct AiAlarmTestResponse {
     pub test_plc_address: String,
 }
 
+/// AI报警触发点搜索请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiAlarmTripPointTestRequest {
+    pub instance_id: String,
+    pub alarm_type: String,  // "LL", "L", "H", "HH"
+    /// 搜索参数，未提供时使用默认值
+    #[serde(default)]
+    pub config: Option<crate::models::AlarmRampConfig>,
+}
+
+/// AI报警触发点搜索响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiAlarmTripPointTestResponse {
+    pub success: bool,
+    pub message: String,
+    pub setpoint: Option<f64>,           // 点表配置的报警设定值
+    pub trip_point: Option<f64>,         // 实际触发点（工程值）
+    pub clear_point: Option<f64>,        // 实际恢复点（工程值）
+    pub deadband: Option<f64>,           // 死区（触发点与恢复点之差）
+    pub trip_error_percent: Option<f64>, // 触发点相对设定值的偏差（量程百分比）
+}
+
 /// AI手动测试维护功能请求
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiMaintenanceTestRequest {
@@ -716,6 +741,89 @@ pub async fn ai_alarm_test_cmd(
     }
 }
 
+/// AI手动测试 - 报警触发点搜索命令
+/// 
+/// 业务说明：
+/// 通过测试PLC逐步改变AI输入信号穿越报警设定值，搜索实际的报警触发点与恢复点
+/// 与 ai_alarm_test_cmd 只下发单个测试值不同，本命令可验证报警逻辑在正确的过程值处动作，
+/// 并得到报警死区
+/// 
+/// 判定标准：
+/// - 触发点与点表设定值的偏差 ≤ 允许偏差（量程百分比）
+/// - 反向回退后报警能够复位
+/// 
+/// 参数说明：
+/// - request: 包含实例ID、报警类型和可选的搜索参数
+/// - app_state: 应用状态
+/// 
+/// 调用链：
+/// 前端报警测试 -> ai_alarm_trip_point_test_cmd -> AIAlarmTestExecutor(触发点搜索) -> ChannelStateManager
+#[tauri::command]
+pub async fn ai_alarm_trip_point_test_cmd(
+    request: AiAlarmTripPointTestRequest,
+    app_state: State<'_, crate::tauri_commands::AppState>,
+) -> Result<AiAlarmTripPointTestResponse, String> {
+    use crate::domain::impls::specific_test_executors::ISpecificTestStepExecutor;
+
+    info!("🎯 [AI_MANUAL_TEST] {}报警触发点搜索: {}", request.alarm_type, request.instance_id);
+
+    let alarm_type = match request.alarm_type.as_str() {
+        "LL" => crate::models::enums::SubTestItem::LowLowAlarm,
+        "L" => crate::models::enums::SubTestItem::LowAlarm,
+        "H" => crate::models::enums::SubTestItem::HighAlarm,
+        "HH" => crate::models::enums::SubTestItem::HighHighAlarm,
+        _ => {
+            error!("❌ [AI_MANUAL_TEST] 无效的报警类型: {}", request.alarm_type);
+            return Err("无效的报警类型".to_string());
+        }
+    };
+
+    let (mut instance, definition) = get_instance_and_definition(&app_state, &request.instance_id).await?;
+    let test_plc_address = get_test_plc_address(&app_state, &instance).await?;
+    instance.test_plc_communication_address = Some(test_plc_address);
+
+    let config = request.config.unwrap_or_default();
+    let executor = crate::domain::impls::AIAlarmTestExecutor::with_trip_point_ramp(alarm_type, config);
+    if !executor.supports_definition(&definition) {
+        return Err(format!("点位 {} 未配置{}报警设定地址", definition.tag, request.alarm_type));
+    }
+
+    let plc_service: std::sync::Arc<dyn IPlcCommunicationService + Send + Sync> = global_plc_service();
+    let search = executor.execute(
+        &instance,
+        &definition,
+        &app_state.test_rig_connection_id,
+        &app_state.target_connection_id,
+        plc_service.clone(),
+        plc_service,
+    );
+    let outcome = with_capture_instance(&instance.test_batch_id, &instance.instance_id, with_request_source(RequestSource::Manual, search))
+        .await
+        .map_err(|e| {
+            error!("❌ [AI_MANUAL_TEST] {}报警触发点搜索失败: {}", request.alarm_type, e);
+            format!("报警触发点搜索失败: {}", e)
+        })?;
+
+    let detail = |key: &str| outcome.details.get(key).and_then(|v| v.as_f64());
+    let response = AiAlarmTripPointTestResponse {
+        success: outcome.success,
+        message: outcome.message.clone().unwrap_or_default(),
+        setpoint: detail("setpoint"),
+        trip_point: detail("trip_point"),
+        clear_point: detail("clear_point"),
+        deadband: detail("deadband"),
+        trip_error_percent: detail("trip_error_percent"),
+    };
+
+    app_state
+        .channel_state_manager
+        .update_test_result(outcome)
+        .await
+        .map_err(|e| format!("保存测试结果失败: {}", e))?;
+
+    Ok(response)
+}
+
 /// AI手动测试 - 维护功能测试命令
 /// 
 /// 业务说明：
@@ -932,8 +1040,8 @@ pub async fn capture_ao_point_cmd(
     let plc_service: std::sync::Arc<dyn IPlcCommunicationService + Send + Sync> = plc_service_arc;
     let conn_id = &app_state.test_rig_connection_id;
     info!("🔌 [AO_CMD] 读取 PLC 地址 {}", test_plc_address);
-    let actual_value = plc_service
-        .read_float32_by_id(conn_id, &test_plc_address)
+    let read = plc_service.read_float32_by_id(conn_id, &test_plc_address);
+    let actual_value = with_capture_instance(&instance.test_batch_id, &instance.instance_id, with_request_source(RequestSource::Manual, read))
         .await
         .map_err(|e| format!("读取测试PLC失败: {}", e))? as f64;
 
@@ -1124,8 +1232,7 @@ async fn write_to_test_plc(
     }
 
     // 写入百分比
-    match plc_service
-        .write_float32_by_id(&connection_id, address, percentage as f32)
+    match with_request_source(RequestSource::Manual, plc_service.write_float32_by_id(&connection_id, address, percentage as f32))
         .await
     {
         Ok(_) => {
@@ -1219,8 +1326,7 @@ async fn write_bool_to_target_plc(
     }
 
     // 写入布尔值
-    match plc_service
-        .write_bool_by_id(&connection_id, &fixed_address, value)
+    match with_request_source(RequestSource::Manual, plc_service.write_bool_by_id(&connection_id, &fixed_address, value))
         .await
     {
         Ok(_) => {
@@ -1244,26 +1350,16 @@ async fn write_bool_to_target_plc(
 /// 业务说明：
 /// - Modbus地址需要规范化为5位数字格式
 /// - 例如："123" -> "00123"，"0X456" -> "00456"
-/// - 仅保留数字部分，忽略前缀
+/// - 位地址（40001.3）、类型后缀（40001:F）与 %M 地址保持原有结构
+/// - 具体规则由 `ModbusAddress::normalize` 统一实现，与通信层解析保持一致
 /// 
 /// 参数：
 /// - address: 原始地址字符串
 /// 
 /// 返回：
-/// - 规范化后的5位地址字符串
-/// 
-/// Rust知识点：
-/// - chars() 将字符串转换为字符迭代器
-/// - filter() 过滤满足条件的元素
-/// - collect() 将迭代器收集为指定类型
-/// - format! 宏用于格式化字符串，{:0>5} 表示右对齐，左侧补0，总宽度5
+/// - 规范化后的地址字符串
 fn normalize_modbus_address(address: &str) -> String {
-    // 仅保留数字字符
-    let digits: String = address.chars().filter(|c| c.is_ascii_digit()).collect();
-    if digits.is_empty() {
-        return address.to_string(); // 返回原样，后续写入会报错指出
-    }
-    format!("{:0>5}", digits)
+    ModbusAddress::normalize(address)
 }
 
 /// ==================== DI 手动测试专用命令 ====================
@@ -1435,8 +1531,7 @@ async fn write_bool_to_test_plc(
         return Err(format!("PLC连接失败: {}", e));
     }
 
-    match plc_service
-        .write_bool_by_id(&connection_id, &fixed_address, value)
+    match with_request_source(RequestSource::Manual, plc_service.write_bool_by_id(&connection_id, &fixed_address, value))
         .await
     {
         Ok(_) => {
@@ -1541,8 +1636,8 @@ pub async fn capture_do_state_cmd(
     let conn_id = &app_state.test_rig_connection_id;
     
     info!("🔌 [DO_CMD] 读取测试PLC DI地址 {}", test_plc_address);
-    let actual_state = plc_service
-        .read_bool_by_id(conn_id, &test_plc_address)
+    let read = plc_service.read_bool_by_id(conn_id, &test_plc_address);
+    let actual_state = with_capture_instance(&instance.test_batch_id, &instance.instance_id, with_request_source(RequestSource::Manual, read))
         .await
         .map_err(|e| format!("读取测试PLC失败: {}", e))?;
 
@@ -190,6 +190,9 @@ pub async fn execute_manual_sub_test_cmd(
         test_result_75_percent: None,                       // 75%量程测试结果
         test_result_100_percent: None,                      // 100%量程测试结果
         details: args.params.unwrap_or_default(),           // 附加参数
+        attempt: 1,                                         // 手动测试不自动重试
+        retry_pending: false,
+        failure_kind: None,                                 // 手动测试默认成功，无失败分类
     };
     
     // 更新测试实例状态
@@ -865,3 +868,16 @@ pub async fn get_plc_connection_status_cmd(
         last_check_time: crate::utils::time_utils::format_bj(chrono::Utc::now(), "%Y-%m-%d %H:%M:%S"), // 格式化的检查时间
     })
 }
+
+/// 获取PLC连接质量历史命令
+///
+/// **业务作用**:
+/// - 返回每个PLC连接最近的连接/断开/重连失败事件
+/// - 附带请求时延直方图、最近窗口内的错误率和累计掉线时长
+/// - 批次中途失败时，用于判断当时连接是否掉线以及掉线多久
+#[tauri::command]
+pub async fn get_plc_connection_history_cmd(
+    state: State<'_, AppState>
+) -> Result<Vec<crate::domain::impls::ConnectionQualitySnapshot>, String> {
+    Ok(state.inner().plc_connection_manager.connection_history().await)
+}
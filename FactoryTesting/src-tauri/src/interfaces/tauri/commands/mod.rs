@@ -58,7 +58,15 @@ pub use data_management::{
     parse_excel_without_persistence_cmd,       // 解析Excel但不持久化
     create_batch_and_persist_data_cmd,         // 创建批次并持久化数据
     delete_batch_cmd,                          // 删除批次
-    restore_session_cmd                        // 恢复会话
+    restore_session_cmd,                       // 恢复会话
+    query_channel_definitions_cmd,             // 条件查询通道定义
+    query_test_instances_cmd,                  // 条件查询测试实例
+    query_test_batches_cmd,                    // 条件查询测试批次
+    query_test_outcomes_cmd,                   // 条件查询测试结果
+    create_database_backup_cmd,                // 立即备份数据库
+    list_database_backups_cmd,                 // 列出数据库备份
+    restore_database_backup_cmd,               // 从备份恢复数据库
+    cleanup_expired_data_cmd                   // 清理过期测试数据
 };
 
 // === PLC配置管理命令重导出 ===
@@ -73,6 +81,11 @@ pub use test_plc_config::{
     test_plc_connection_cmd,                   // 测试PLC连接
     get_channel_mappings_cmd,                  // 获取通道映射
     generate_channel_mappings_cmd,             // 生成通道映射
+    run_wiring_check_cmd,                      // 测试前接线检查
+    get_simulator_status_cmd,                  // 获取PLC仿真器状态
+    set_simulator_fault_cmd,                   // 仿真器故障注入
+    export_modbus_capture_csv_cmd,             // 导出Modbus录制报文CSV
+    replay_modbus_capture_cmd,                 // 回放Modbus录制报文到仿真器
     initialize_default_test_plc_channels_cmd,  // 初始化默认测试PLC通道
 };
 
@@ -85,7 +98,8 @@ pub use manual_testing::{
     write_channel_value_cmd,                   // 写入通道值
     connect_plc_cmd,                          // 连接PLC
     start_batch_auto_test_cmd,                 // 启动批次自动测试
-    get_plc_connection_status_cmd              // 获取PLC连接状态
+    get_plc_connection_status_cmd,             // 获取PLC连接状态
+    get_plc_connection_history_cmd             // 获取PLC连接质量历史
 };
 
 // === 手动测试命令重导出（新版） ===
@@ -51,6 +51,7 @@ use log::{debug, info, warn, error, trace};
 use crate::{log_test_failure, log_user_operation, log_communication_failure};
 use crate::tauri_commands::AppState;
 use crate::models::test_plc_config::*;
+use crate::models::{WiringCheckSettings, WiringCheckReport};
 use crate::utils::error::AppResult;
 use crate::models::entities::test_plc_channel_config;
 use chrono::Utc;
@@ -474,6 +475,221 @@ pub async fn generate_channel_mappings_cmd(
     }
 }
 
+/// 执行测试前接线检查
+/// 
+/// 业务说明：
+/// - 逐个驱动本批次通道期望连接的测试PLC DO/AO通道，批量读取批次内全部被测DI/AI通道
+/// - 期望连接关系优先取通道映射配置，未配置映射时使用批次分配的测试PLC通道
+/// - 返回期望通道与实际响应通道对照表及互换建议
+/// - mark_instances 为 true（默认）时，接线有问题的实例置为待接线确认并记录建议
+/// 
+/// 调用链：
+/// 前端接线确认页面 -> run_wiring_check_cmd -> WiringDiscoverySweep -> ChannelStateManager
+#[tauri::command]
+pub async fn run_wiring_check_cmd(
+    batch_id: String,
+    settings: Option<WiringCheckSettings>,
+    mark_instances: Option<bool>,
+    state: State<'_, AppState>
+) -> Result<WiringCheckReport, String> {
+    use crate::domain::impls::{WiringDiscoverySweep, WiringRigOutput, WiringTargetChannel};
+    use crate::models::ModuleType;
+
+    info!("🔌 接线检查命令: 批次 {}", batch_id);
+
+    let instances = state.persistence_service
+        .load_test_instances_by_batch(&batch_id)
+        .await
+        .map_err(|e| format!("加载批次测试实例失败: {}", e))?;
+
+    let rig_channels = state.test_plc_config_service
+        .get_test_plc_channels(GetTestPlcChannelsRequest {
+            channel_type_filter: None,
+            enabled_only: Some(true),
+        })
+        .await
+        .map_err(|e| format!("加载测试PLC通道配置失败: {}", e))?;
+
+    // 被测通道ID -> 测试PLC通道位号（仅激活的映射）
+    let mapped_rig_channels: std::collections::HashMap<String, String> = state.test_plc_config_service
+        .get_channel_mappings()
+        .await
+        .map_err(|e| format!("获取通道映射配置失败: {}", e))?
+        .into_iter()
+        .filter(|m| m.is_active)
+        .filter_map(|m| {
+            rig_channels
+                .iter()
+                .find(|c| c.id.as_deref() == Some(m.test_plc_channel_id.as_str()))
+                .map(|c| (m.target_channel_id, c.channel_address.clone()))
+        })
+        .collect();
+
+    let mut targets = Vec::new();
+    for instance in &instances {
+        let definition = match state.persistence_service.load_channel_definition(&instance.definition_id).await {
+            Ok(Some(definition)) => definition,
+            _ => {
+                warn!("接线检查跳过无通道定义的实例: {}", instance.instance_id);
+                continue;
+            }
+        };
+        if !matches!(definition.module_type, ModuleType::DI | ModuleType::DINone | ModuleType::AI | ModuleType::AINone) {
+            continue;
+        }
+        targets.push(WiringTargetChannel {
+            instance_id: instance.instance_id.clone(),
+            tag: definition.tag.clone(),
+            module_type: definition.module_type.clone(),
+            target_address: definition.plc_communication_address.clone(),
+            expected_test_rig_channel: mapped_rig_channels
+                .get(&definition.id)
+                .cloned()
+                .or_else(|| instance.test_plc_channel_tag.clone()),
+            range_low: definition.range_low_limit.unwrap_or(0.0) as f64,
+            range_high: definition.range_high_limit.unwrap_or(100.0) as f64,
+        });
+    }
+
+    let rig_outputs: Vec<WiringRigOutput> = rig_channels
+        .iter()
+        .filter_map(|c| {
+            let is_analog = match c.channel_type {
+                TestPlcChannelType::AO | TestPlcChannelType::AONone => true,
+                TestPlcChannelType::DO | TestPlcChannelType::DONone => false,
+                _ => return None,
+            };
+            Some(WiringRigOutput {
+                channel_tag: c.channel_address.clone(),
+                address: c.communication_address.clone(),
+                is_analog,
+            })
+        })
+        .collect();
+
+    let sweep = WiringDiscoverySweep::new(
+        crate::infrastructure::plc_communication::global_plc_service(),
+        state.test_rig_connection_id.clone(),
+        state.target_connection_id.clone(),
+        settings.unwrap_or_default(),
+    );
+    let report = sweep
+        .run(&batch_id, &targets, &rig_outputs)
+        .await
+        .map_err(|e| {
+            log_communication_failure!("接线检查失败: {}", e);
+            format!("接线检查失败: {}", e)
+        })?;
+
+    if mark_instances.unwrap_or(true) {
+        state.channel_state_manager
+            .apply_wiring_check(&report)
+            .await
+            .map_err(|e| format!("更新接线确认状态失败: {}", e))?;
+    }
+
+    Ok(report)
+}
+
+/// 获取内置PLC仿真器状态
+/// 
+/// 业务说明：
+/// - 仅Mock模式下存在仿真器，未启用时返回 None
+/// - 返回两侧仿真端点、回环与报警点数量以及当前注入的故障
+/// 
+/// 调用链：
+/// 前端仿真器面板 -> get_simulator_status_cmd -> PlcSimulator
+#[tauri::command]
+pub async fn get_simulator_status_cmd(
+    state: State<'_, AppState>
+) -> Result<Option<crate::infrastructure::SimulatorStatus>, String> {
+    Ok(state.plc_simulator.as_ref().map(|simulator| simulator.status()))
+}
+
+/// 向内置PLC仿真器注入故障
+/// 
+/// 业务说明：
+/// - 按被测点位位号注入断线、卡死、反相、偏移、无应答等故障，用于演示和培训
+/// - fault 为 None 时清除该位号的故障；tag 为空时清除全部故障
+/// 
+/// 调用链：
+/// 前端仿真器面板 -> set_simulator_fault_cmd -> PlcSimulator
+#[tauri::command]
+pub async fn set_simulator_fault_cmd(
+    tag: String,
+    fault: Option<crate::infrastructure::SimulatedFault>,
+    state: State<'_, AppState>
+) -> Result<(), String> {
+    let simulator = state.plc_simulator.as_ref()
+        .ok_or_else(|| "PLC仿真器未启用（需开启Mock模式）".to_string())?;
+    log_user_operation!("仿真器故障设置: {} → {:?}", tag, fault);
+    match (tag.is_empty(), fault) {
+        (true, _) => simulator.clear_faults(),
+        (false, Some(fault)) => simulator.set_fault(&tag, fault),
+        (false, None) => simulator.clear_fault(&tag),
+    }
+    Ok(())
+}
+
+/// 导出批次的Modbus录制报文为CSV
+/// 
+/// 业务说明：
+/// - 读取批次录制文件（需在PLC配置中开启 frame_capture），逐帧输出时间、连接、测试实例、
+///   功能码、地址、请求/应答数据、错误与耗时，用于与客户核对争议点位
+/// - target_path 为空时写入录制目录，与录制文件同名
+/// 
+/// 调用链：
+/// 前端批次详情 -> export_modbus_capture_csv_cmd -> modbus_capture
+#[tauri::command]
+pub async fn export_modbus_capture_csv_cmd(
+    batch_id: String,
+    target_path: Option<String>,
+) -> Result<String, String> {
+    use crate::infrastructure::modbus_capture::{batch_capture_path, export_capture_csv, read_capture};
+
+    let capture_path = batch_capture_path(&batch_id);
+    let frames = read_capture(&capture_path)
+        .map_err(|e| format!("读取Modbus录制文件失败: {}", e))?;
+    let target = target_path
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| capture_path.with_extension("csv"));
+    export_capture_csv(&frames, &target)
+        .map_err(|e| format!("导出Modbus录制报文失败: {}", e))?;
+    info!("导出批次 {} 的Modbus录制报文 {} 帧: {:?}", batch_id, frames.len(), target);
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// 将批次的Modbus录制报文载入内置PLC仿真器回放
+/// 
+/// 业务说明：
+/// - 按连接ID把录制报文分配到测试PLC/被测PLC两侧仿真器
+/// - 之后重新执行该批次测试，仿真器对相同请求按录制顺序返回当时的应答，离线复现测试过程
+/// - 仅Mock模式下可用；返回载入的帧数
+/// 
+/// 调用链：
+/// 前端仿真器面板 -> replay_modbus_capture_cmd -> modbus_capture -> PlcSimulator
+#[tauri::command]
+pub async fn replay_modbus_capture_cmd(
+    batch_id: String,
+    state: State<'_, AppState>
+) -> Result<usize, String> {
+    use crate::infrastructure::modbus_capture::{batch_capture_path, read_capture};
+    use crate::infrastructure::{ReplayScript, SimulatedSide};
+
+    let simulator = state.plc_simulator.as_ref()
+        .ok_or_else(|| "PLC仿真器未启用（需开启Mock模式）".to_string())?;
+    let frames = read_capture(&batch_capture_path(&batch_id))
+        .map_err(|e| format!("读取Modbus录制文件失败: {}", e))?;
+    log_user_operation!("回放批次 {} 的Modbus录制报文", batch_id);
+
+    let mut loaded = 0;
+    for (connection_id, side) in [(&state.test_rig_connection_id, SimulatedSide::TestRig), (&state.target_connection_id, SimulatedSide::Target)] {
+        let script = ReplayScript::from_frames(frames.iter().filter(|frame| &frame.connection_id == connection_id));
+        loaded += simulator.load_replay(side, script);
+    }
+    Ok(loaded)
+}
+
 /// 初始化默认测试PLC通道配置
 /// 
 /// 业务说明：
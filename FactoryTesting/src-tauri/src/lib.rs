@@ -44,13 +44,16 @@ use commands::data_management::{
     import_excel_and_allocate_channels_cmd, clear_session_data,
     parse_excel_without_persistence_cmd, create_batch_and_persist_data_cmd,
     import_excel_and_create_batch_cmd, create_test_batch_with_definitions_cmd, delete_batch_cmd,
-    restore_session_cmd
+    restore_session_cmd, query_channel_definitions_cmd, query_test_instances_cmd,
+    query_test_batches_cmd, query_test_outcomes_cmd, create_database_backup_cmd,
+    list_database_backups_cmd, restore_database_backup_cmd, cleanup_expired_data_cmd
 };
 
 // 手动测试相关命令 - 处理手动测试执行、PLC读写、连接管理等
 use commands::manual_testing::{
     execute_manual_sub_test_cmd, read_channel_value_cmd, write_channel_value_cmd,
-    connect_plc_cmd, start_batch_auto_test_cmd, get_plc_connection_status_cmd
+    connect_plc_cmd, start_batch_auto_test_cmd, get_plc_connection_status_cmd,
+    get_plc_connection_history_cmd
 };
 
 // 手动测试命令 - 新版手动测试流程，包含AI/AO/DI/DO等测试类型
@@ -58,7 +61,7 @@ use commands::manual_test_commands::{
     start_manual_test_cmd, update_manual_test_subitem_cmd, get_manual_test_status_cmd,
     start_plc_monitoring_cmd, stop_plc_monitoring_cmd,
     // AI手动测试专用命令 - 模拟量输入测试
-    generate_random_display_value_cmd, ai_show_value_test_cmd, ai_alarm_test_cmd,
+    generate_random_display_value_cmd, ai_show_value_test_cmd, ai_alarm_test_cmd, ai_alarm_trip_point_test_cmd,
     ai_maintenance_test_cmd, ai_reset_to_display_value_cmd, complete_manual_test_subitem_cmd,
     capture_ao_point_cmd,
     // DI/DO手动测试命令 - 数字量输入/输出测试
@@ -76,7 +79,9 @@ use commands::global_function_test_commands::{
 use commands::test_plc_config::{
     get_test_plc_channels_cmd, save_test_plc_channel_cmd, delete_test_plc_channel_cmd,
     get_plc_connections_cmd, save_plc_connection_cmd, test_plc_connection_cmd, test_temp_plc_connection_cmd,
-    test_address_read_cmd, get_channel_mappings_cmd, generate_channel_mappings_cmd, 
+    test_address_read_cmd, get_channel_mappings_cmd, generate_channel_mappings_cmd, run_wiring_check_cmd,
+    get_simulator_status_cmd, set_simulator_fault_cmd,
+    export_modbus_capture_csv_cmd, replay_modbus_capture_cmd,
     initialize_default_test_plc_channels_cmd, restore_default_test_plc_channels_cmd,
     restore_default_channels_from_sql_cmd
 };
@@ -272,6 +277,10 @@ pub fn run() {
                 tauri_commands::delete_channel_definition,
                 tauri_commands::get_all_batch_info,
                 tauri_commands::save_batch_info,
+                tauri_commands::get_batch_analog_test_profile_cmd,
+                tauri_commands::save_batch_analog_test_profile_cmd,
+                tauri_commands::get_batch_communication_test_config_cmd,
+                tauri_commands::save_batch_communication_test_config_cmd,
                 tauri_commands::get_batch_test_instances,
                 
                 // === 通道状态管理相关命令 ===
@@ -317,6 +326,14 @@ pub fn run() {
                 create_test_batch_with_definitions_cmd,
                 delete_batch_cmd,
                 restore_session_cmd,
+                query_channel_definitions_cmd,
+                query_test_instances_cmd,
+                query_test_batches_cmd,
+                query_test_outcomes_cmd,
+                create_database_backup_cmd,
+                list_database_backups_cmd,
+                restore_database_backup_cmd,
+                cleanup_expired_data_cmd,
                 
                 // === 手动测试命令 ===
                 // 业务说明：手动测试执行、PLC直接读写操作
@@ -326,6 +343,7 @@ pub fn run() {
                 connect_plc_cmd,
                 start_batch_auto_test_cmd,
                 get_plc_connection_status_cmd,
+                get_plc_connection_history_cmd,
                 
                 // === 新版手动测试命令 ===
                 // 业务说明：改进的手动测试流程，支持分步骤测试
@@ -340,6 +358,7 @@ pub fn run() {
                 generate_random_display_value_cmd,
                 ai_show_value_test_cmd,
                 ai_alarm_test_cmd,
+                ai_alarm_trip_point_test_cmd,
                 ai_maintenance_test_cmd,
                 ai_reset_to_display_value_cmd,
                 complete_manual_test_subitem_cmd,
@@ -368,6 +387,11 @@ pub fn run() {
                 test_address_read_cmd,
                 get_channel_mappings_cmd,
                 generate_channel_mappings_cmd,
+                run_wiring_check_cmd,
+                get_simulator_status_cmd,
+                set_simulator_fault_cmd,
+                export_modbus_capture_csv_cmd,
+                replay_modbus_capture_cmd,
                 initialize_default_test_plc_channels_cmd,
                 restore_default_test_plc_channels_cmd,
                 restore_default_channels_from_sql_cmd,
@@ -7,7 +7,7 @@ use sea_orm::ActiveValue::Set;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use crate::models::structs::{default_id, SubTestExecutionResult, AnalogReadingPoint, DigitalTestStep}; // 引入所需结构体
+use crate::models::structs::{default_id, SubTestExecutionResult, AnalogReadingPoint, AnalogAccuracyMetrics, DigitalTestStep}; // 引入所需结构体
 use crate::models::enums::{OverallTestStatus, SubTestItem}; // 引入所需枚举
 
 /// 通道测试实例实体
@@ -129,6 +129,8 @@ pub struct Model {
     #[sea_orm(column_type = "Text", nullable)]
     pub hardpoint_readings_json: Option<String>, // 硬点读数JSON
     #[sea_orm(column_type = "Text", nullable)]
+    pub analog_accuracy_json: Option<String>, // 模拟量精度指标JSON
+    #[sea_orm(column_type = "Text", nullable)]
     pub digital_test_steps_json: Option<String>, // 数字量测试步骤JSON
     #[sea_orm(column_type = "Text", nullable)]
     pub transient_data_json: Option<String>, // 临时数据JSON
@@ -240,6 +242,11 @@ impl From<&crate::models::structs::ChannelTestInstance> for ActiveModel {
             None => None,
         };
 
+        let analog_accuracy_json = match &original.analog_accuracy {
+            Some(metrics) => serde_json::to_string(metrics).ok(),
+            None => None,
+        };
+
         let digital_test_steps_json = match &original.digital_test_steps {
             Some(steps) => serde_json::to_string(steps).ok(),
             None => None,
@@ -316,6 +323,7 @@ impl From<&crate::models::structs::ChannelTestInstance> for ActiveModel {
 
             sub_test_results_json: Set(sub_test_results_json),
             hardpoint_readings_json: Set(hardpoint_readings_json),
+            analog_accuracy_json: Set(analog_accuracy_json),
             digital_test_steps_json: Set(digital_test_steps_json),
             transient_data_json: Set(transient_data_json),
         }
@@ -339,6 +347,9 @@ impl From<&Model> for crate::models::structs::ChannelTestInstance {
                 }
             });
 
+        let analog_accuracy: Option<AnalogAccuracyMetrics> = model.analog_accuracy_json.as_ref()
+            .and_then(|json_str| serde_json::from_str(json_str).ok());
+
         // 🔧 性能优化：简化数字量测试步骤转换，移除详细日志
         let digital_test_steps: Option<Vec<DigitalTestStep>> = model.digital_test_steps_json.as_ref()
             .and_then(|json_str| {
@@ -385,6 +396,7 @@ impl From<&Model> for crate::models::structs::ChannelTestInstance {
             total_test_duration_ms: model.total_test_duration_ms,
             sub_test_results,
             hardpoint_readings,
+            analog_accuracy,
             digital_test_steps: digital_test_steps.clone(),
             manual_test_current_value_input: None, // 新实体结构中没有这个字段
             manual_test_current_value_output: None, // 新实体结构中没有这个字段
@@ -472,6 +484,7 @@ impl Model {
             hmi_configuration_error_notes: None,
             sub_test_results_json: None,
             hardpoint_readings_json: None,
+            analog_accuracy_json: None,
             digital_test_steps_json: None,
             transient_data_json: None,
         }
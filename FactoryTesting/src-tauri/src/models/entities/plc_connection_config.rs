@@ -71,7 +71,7 @@ pub struct Model {
 
     /// PLC设备类型
     /// **业务含义**: 指定PLC的协议类型
-    /// **支持类型**: ModbusTcp, SiemensS7, OpcUa, Mock
+    /// **支持类型**: ModbusTcp, ModbusRtu, SiemensS7, OpcUa, Mock
     /// **扩展性**: 可以添加新的PLC协议类型
     /// **验证**: 应该是预定义的有效类型之一
     pub plc_type: String,
@@ -151,6 +151,36 @@ pub struct Model {
     /// **监控依据**: 系统监控的重要指标
     pub connection_status: String,
 
+    /// 串口参数（JSON）
+    /// **业务含义**: Modbus RTU连接的串口设备、波特率、校验位、停止位和从站地址
+    /// **可选字段**: TCP连接为空
+    #[sea_orm(nullable)]
+    pub serial_settings: Option<String>,
+
+    /// S7连接参数（JSON）
+    /// **业务含义**: 西门子S7连接的机架号、插槽号和连接类型
+    /// **可选字段**: 非S7连接为空
+    #[sea_orm(nullable)]
+    pub s7_settings: Option<String>,
+
+    /// OPC UA连接参数（JSON）
+    /// **业务含义**: OPC UA端点路径与认证方式（匿名/用户名）
+    /// **可选字段**: 非OPC UA连接为空
+    #[sea_orm(nullable)]
+    pub opcua_settings: Option<String>,
+
+    /// Modbus数据参数（JSON）
+    /// **业务含义**: 64位数值字序、字符串编码/填充方式
+    /// **可选字段**: 非Modbus连接或使用默认参数时为空
+    #[sea_orm(nullable)]
+    pub modbus_data_settings: Option<String>,
+
+    /// Modbus请求调度参数（JSON）
+    /// **业务含义**: 在途请求上限、帧间隔、排队期限
+    /// **可选字段**: 非Modbus连接或使用默认参数时为空
+    #[sea_orm(nullable)]
+    pub modbus_scheduler_settings: Option<String>,
+
     /// 配置创建时间
     /// **业务含义**: 记录配置首次创建的时间
     /// **审计价值**: 用于配置变更的审计跟踪
@@ -266,6 +296,17 @@ impl From<&crate::models::test_plc_config::PlcConnectionConfig> for ActiveModel
             is_enabled: Set(config.is_enabled),
             last_connected: Set(config.last_connected),
             connection_status: Set(format!("{:?}", config.connection_status)),
+            serial_settings: Set(config.serial_settings.as_ref().and_then(|s| serde_json::to_string(s).ok())),
+            s7_settings: Set(config.s7_settings.as_ref().and_then(|s| serde_json::to_string(s).ok())),
+            // 密码加密后写入；加密失败时不保存密码（密码字段不参与普通序列化），绝不落明文
+            opcua_settings: Set(config.opcua_settings.as_ref().and_then(|s| {
+                s.to_storage_json()
+                    .map_err(|e| log::error!("OPC UA密码加密失败，本次不保存密码: {}", e))
+                    .or_else(|_| serde_json::to_string(s))
+                    .ok()
+            })),
+            modbus_data_settings: Set(config.modbus_data_settings.as_ref().and_then(|s| serde_json::to_string(s).ok())),
+            modbus_scheduler_settings: Set(config.modbus_scheduler_settings.as_ref().and_then(|s| serde_json::to_string(s).ok())),
             created_at: Set(Utc::now()),
             updated_at: Set(Utc::now()),
         }
@@ -280,6 +321,7 @@ impl From<&Model> for crate::models::test_plc_config::PlcConnectionConfig {
             name: model.name.clone(),
             plc_type: match model.plc_type.as_str() {
                 "ModbusTcp" => crate::models::test_plc_config::PlcType::ModbusTcp,
+                "ModbusRtu" => crate::models::test_plc_config::PlcType::ModbusRtu,
                 "SiemensS7" => crate::models::test_plc_config::PlcType::SiemensS7,
                 "OpcUa" => crate::models::test_plc_config::PlcType::OpcUa,
                 _ => crate::models::test_plc_config::PlcType::ModbusTcp, // 默认值
@@ -302,6 +344,26 @@ impl From<&Model> for crate::models::test_plc_config::PlcConnectionConfig {
                 "Timeout" => crate::models::test_plc_config::ConnectionStatus::Timeout,
                 _ => crate::models::test_plc_config::ConnectionStatus::Disconnected, // 默认值
             },
+            serial_settings: model.serial_settings
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+            s7_settings: model.s7_settings
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+            opcua_settings: model.opcua_settings
+                .as_deref()
+                .and_then(|json| {
+                    crate::models::test_plc_config::OpcUaConnectionSettings::from_storage_json(json)
+                        .map_err(|e| log::warn!("OPC UA连接参数解析失败（密码需重新填写）: {}", e))
+                        .or_else(|_| serde_json::from_str(json))
+                        .ok()
+                }),
+            modbus_data_settings: model.modbus_data_settings
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
+            modbus_scheduler_settings: model.modbus_scheduler_settings
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok()),
         }
     }
 } 
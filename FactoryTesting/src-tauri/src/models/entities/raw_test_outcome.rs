@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use crate::models::structs::{AnalogReadingPoint}; // 引入所需结构体
-use crate::models::enums::SubTestItem; // 修正路径：引入所需枚举
+use crate::models::enums::{FailureKind, SubTestItem}; // 修正路径：引入所需枚举
 
 // RawTestOutcome 结构体也需要一个唯一ID作为主键，即使原始结构体没有
 // 如果 RawTestOutcome 总是与 ChannelTestInstance 关联，并且其生命周期依赖于此，
@@ -60,6 +60,20 @@ pub struct Model {
     // HashMap<String, serde_json::Value> 序列化为 JSON 字符串存储
     #[sea_orm(column_type = "Text", nullable)]
     pub details_json: Option<String>,
+
+    // 第几次执行（自动重试时大于1）
+    #[serde(default = "default_attempt")]
+    pub attempt: u32,
+    // 本次失败后引擎是否进行了自动重试
+    #[serde(default)]
+    pub retry_pending: bool,
+    // 失败原因分类（Configuration/Communication/Signal）
+    #[sea_orm(nullable)]
+    pub failure_kind: Option<String>,
+}
+
+fn default_attempt() -> u32 {
+    1
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -102,6 +116,9 @@ impl From<&crate::models::structs::RawTestOutcome> for ActiveModel {
             test_result_75_percent: Set(original.test_result_75_percent),
             test_result_100_percent: Set(original.test_result_100_percent),
             details_json: Set(details_json),
+            attempt: Set(original.attempt),
+            retry_pending: Set(original.retry_pending),
+            failure_kind: Set(original.failure_kind.map(|kind| format!("{:?}", kind))),
             ..Default::default()
         }
     }
@@ -145,6 +162,14 @@ impl From<&Model> for crate::models::structs::RawTestOutcome {
             test_result_75_percent: model.test_result_75_percent,
             test_result_100_percent: model.test_result_100_percent,
             details,
+            attempt: model.attempt,
+            retry_pending: model.retry_pending,
+            failure_kind: match model.failure_kind.as_deref() {
+                Some("Configuration") => Some(FailureKind::Configuration),
+                Some("Communication") => Some(FailureKind::Communication),
+                Some("Signal") => Some(FailureKind::Signal),
+                _ => None,
+            },
         }
     }
 } 
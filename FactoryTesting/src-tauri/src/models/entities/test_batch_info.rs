@@ -73,6 +73,8 @@ pub struct Model {
     #[sea_orm(nullable)]
     pub test_configuration: Option<String>, // 测试配置（JSON）
     #[sea_orm(nullable)]
+    pub communication_test_config: Option<String>, // 通信点测试配置（JSON，源连接密码为密文）
+    #[sea_orm(nullable)]
     pub import_source: Option<String>,      // 导入源（如Excel文件名）
 
     // 自定义数据（JSON存储）
@@ -145,7 +147,12 @@ impl From<&crate::models::structs::TestBatchInfo> for ActiveModel {
             not_tested_points: Set(0), // 新字段，计算得出
             progress_percentage: Set(0.0), // 新字段，计算得出
             current_testing_channel: Set(None), // 新字段，原结构体没有
-            test_configuration: Set(None), // 新字段，原结构体没有
+            test_configuration: Set(original.analog_test_profile.as_ref()
+                .and_then(|profile| serde_json::to_string(profile).ok())), // 模拟量测试配置方案（JSON）
+            communication_test_config: Set(original.communication_test_config.as_ref()
+                .and_then(|config| config.to_storage_json()
+                    .map_err(|e| log::warn!("通信点测试配置序列化失败: {} - {}", original.batch_id, e))
+                    .ok())),
             import_source: Set(None), // 新字段，原结构体没有
             custom_data_json: Set(Some(custom_data_json)),
         }
@@ -177,6 +184,12 @@ impl From<&Model> for crate::models::structs::TestBatchInfo {
             overall_status: model.overall_status.parse().unwrap_or_default(),
             custom_data: custom_data_map,
             import_time: Some(crate::utils::time_utils::format_bj(model.created_time, "%Y-%m-%d %H:%M:%S")),
+            analog_test_profile: model.test_configuration.as_ref()
+                .and_then(|json_str| serde_json::from_str(json_str).ok()),
+            communication_test_config: model.communication_test_config.as_ref()
+                .and_then(|json_str| crate::models::structs::CommunicationTestConfig::from_storage_json(json_str)
+                    .map_err(|e| log::warn!("通信点测试配置解析失败: {} - {}", model.batch_id, e))
+                    .ok()),
         }
     }
 }
@@ -216,6 +229,7 @@ impl Model {
             progress_percentage: 0.0,
             current_testing_channel: None,
             test_configuration: None,
+            communication_test_config: None,
             import_source: None,
             custom_data_json: None,
         }
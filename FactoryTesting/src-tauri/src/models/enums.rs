@@ -151,6 +151,29 @@ impl Default for SubTestStatus {
     }
 }
 
+/// 测试失败的原因分类
+/// 用于区分点表/地址配置问题、通信链路问题与现场信号问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FailureKind {
+    /// 配置错误：非法地址/功能码/数据值、点表或连接参数错误
+    Configuration,
+    /// 通信故障：超时、连接断开、网关目标无响应等
+    Communication,
+    /// 信号异常：通信正常但读回的值不符合预期
+    Signal,
+}
+
+impl Display for FailureKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            FailureKind::Configuration => "配置错误",
+            FailureKind::Communication => "通信故障",
+            FailureKind::Signal => "信号异常",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 /// 模块类型枚举
 /// 表示不同类型的PLC模块
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -6,7 +6,7 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
 use super::enums::{
-    ModuleType, OverallTestStatus, PointDataType, SubTestItem, SubTestStatus
+    FailureKind, ModuleType, OverallTestStatus, PointDataType, SubTestItem, SubTestStatus
 };
 
 /// 生成默认UUID字符串的辅助函数
@@ -484,6 +484,9 @@ pub struct SubTestExecutionResult {
     pub actual_value: Option<String>,
     /// 时间戳
     pub timestamp: DateTime<Utc>,
+    /// 失败原因分类（仅失败时有值）
+    #[serde(default)]
+    pub failure_kind: Option<FailureKind>,
 }
 
 impl SubTestExecutionResult {
@@ -495,6 +498,7 @@ impl SubTestExecutionResult {
             expected_value: expected,
             actual_value: actual,
             timestamp: Utc::now(),
+            failure_kind: None,
         }
     }
 }
@@ -507,6 +511,7 @@ impl Default for SubTestExecutionResult {
             expected_value: None,
             actual_value: None,
             timestamp: Utc::now(),
+            failure_kind: None,
         }
     }
 }
@@ -586,6 +591,10 @@ pub struct ChannelTestInstance {
     /// 硬接线测试中的特定数据（模拟量测试）
     pub hardpoint_readings: Option<Vec<AnalogReadingPoint>>,
 
+    /// 模拟量硬点测试精度指标（最大误差、线性拟合、回差）
+    #[serde(default)]
+    pub analog_accuracy: Option<AnalogAccuracyMetrics>,
+
     /// 数字量测试步骤数据（数字量测试）
     pub digital_test_steps: Option<Vec<DigitalTestStep>>,
 
@@ -643,6 +652,7 @@ impl ChannelTestInstance {
             total_test_duration_ms: None,
             sub_test_results: HashMap::new(),
             hardpoint_readings: None,
+            analog_accuracy: None,
             digital_test_steps: None,
             manual_test_current_value_input: None,
             manual_test_current_value_output: None,
@@ -702,6 +712,12 @@ pub struct TestBatchInfo {
     /// 导入时间（北京时间字符串），供前端直接使用
     #[serde(skip_serializing_if = "Option::is_none", rename = "import_time")]
     pub import_time: Option<String>,
+    /// 模拟量测试配置方案（测试点、容差、稳定时间），为空时使用系统默认方案
+    #[serde(default)]
+    pub analog_test_profile: Option<AnalogTestProfile>,
+    /// 通信点测试配置（源连接、缩放与容差），为空时通信模块点位不参与自动测试
+    #[serde(default)]
+    pub communication_test_config: Option<CommunicationTestConfig>,
 }
 
 impl TestBatchInfo {
@@ -727,6 +743,8 @@ impl TestBatchInfo {
             batch_name: String::new(),
             custom_data: HashMap::new(),
             import_time: None,
+            analog_test_profile: None,
+            communication_test_config: None,
         }
     }
 }
@@ -764,6 +782,19 @@ pub struct RawTestOutcome {
     /// 更多细节
     #[serde(default)]
     pub details: HashMap<String, serde_json::Value>,
+    /// 第几次执行（1 为首次执行，大于 1 为自动重试）
+    #[serde(default = "default_attempt")]
+    pub attempt: u32,
+    /// 本次执行失败且引擎将自动重试，不作为该测试项的最终结果
+    #[serde(default)]
+    pub retry_pending: bool,
+    /// 失败原因分类；执行器判定失败时填写，执行器返回错误时由引擎按错误类型填写
+    #[serde(default)]
+    pub failure_kind: Option<FailureKind>,
+}
+
+fn default_attempt() -> u32 {
+    1
 }
 
 impl RawTestOutcome {
@@ -791,6 +822,9 @@ impl RawTestOutcome {
             test_result_75_percent: None,
             test_result_100_percent: None,
             details: HashMap::new(),
+            attempt: 1,
+            retry_pending: false,
+            failure_kind: None,
         }
     }
 
@@ -802,7 +836,7 @@ impl RawTestOutcome {
         Self::new(channel_instance_id, sub_test_item, true)
     }
 
-    /// 创建失败的测试结果
+    /// 创建失败的测试结果；执行器判定读数不符时使用，失败分类为信号异常
     pub fn failure(
         channel_instance_id: String,
         sub_test_item: SubTestItem,
@@ -810,8 +844,33 @@ impl RawTestOutcome {
     ) -> Self {
         let mut outcome = Self::new(channel_instance_id, sub_test_item, false);
         outcome.message = Some(message);
+        outcome.failure_kind = Some(FailureKind::Signal);
+        outcome
+    }
+
+    /// 创建因点表配置问题无法执行的测试结果
+    pub fn configuration_failure(
+        channel_instance_id: String,
+        sub_test_item: SubTestItem,
+        message: String,
+    ) -> Self {
+        let mut outcome = Self::failure(channel_instance_id, sub_test_item, message);
+        outcome.failure_kind = Some(FailureKind::Configuration);
         outcome
     }
+
+    /// 自动重试说明，例如"第2次重试通过"；首次执行的结果返回 None
+    pub fn retry_note(&self) -> Option<String> {
+        if self.attempt <= 1 {
+            return None;
+        }
+        let retry = self.attempt - 1;
+        Some(if self.success {
+            format!("第{}次重试通过", retry)
+        } else {
+            format!("第{}次重试仍失败", retry)
+        })
+    }
 }
 
 impl Default for RawTestOutcome {
@@ -833,6 +892,9 @@ impl Default for RawTestOutcome {
             test_result_75_percent: None,
             test_result_100_percent: None,
             details: HashMap::new(),
+            attempt: 1,
+            retry_pending: false,
+            failure_kind: None,
         }
     }
 }
@@ -878,3 +940,18 @@ impl Default for AppSettings {
 pub mod manual_test;
 pub use manual_test::*;
 
+// 重新导出模拟量测试配置方案相关结构体
+pub mod analog_test_profile;
+pub use analog_test_profile::*;
+
+// 重新导出模拟量精度指标相关结构体
+pub mod analog_accuracy;
+pub use analog_accuracy::*;
+
+// 重新导出通信点测试配置
+pub mod communication_test;
+pub use communication_test::*;
+
+// 重新导出接线检查模型
+pub mod wiring_check;
+pub use wiring_check::*;
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::structs::AnalogReadingPoint;
+
+/// 单个测试点的回差（上行/下行读数之差）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalogHysteresisPoint {
+    /// 设定百分比（0.0 ~ 1.0）
+    pub set_percentage: f32,
+    /// 上行扫描读数（工程量）
+    pub ascending_value: f32,
+    /// 下行扫描读数（工程量）
+    pub descending_value: f32,
+    /// 回差（占量程百分比）
+    pub hysteresis_percent: f32,
+}
+
+/// AI串扰检查中单个邻近通道的读数
+///
+/// 被测通道由0%驱动到100%时，同模块其他AI通道的读数偏移
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalogCrosstalkReading {
+    /// 邻近通道位号
+    pub tag: String,
+    /// 邻近通道在模块内的通道号
+    pub channel_tag_in_module: String,
+    /// 被测通道0%时的读数（工程量）
+    pub baseline_value: f32,
+    /// 被测通道100%时的读数（工程量）
+    pub driven_value: f32,
+    /// 读数偏移（占邻近通道量程百分比）
+    pub shift_percent: f32,
+    /// 是否超过串扰阈值
+    pub flagged: bool,
+}
+
+/// 模拟量通道精度指标
+///
+/// 由一次AI/AO硬点扫描的全部读数计算得出：
+/// - 最大误差：所有测试点相对量程的最大偏差
+/// - 线性拟合：实际值 = 增益 × 设定值 + 零点偏移，及决定系数 R²
+/// - 回差：上行/下行扫描同一测试点的读数差（仅双向扫描时有效）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalogAccuracyMetrics {
+    /// 是否为上行+下行双向扫描
+    pub bidirectional: bool,
+    /// 参与计算的读数数量
+    pub sample_count: usize,
+    /// 最大误差（占量程百分比）
+    pub max_error_percent: f32,
+    /// 最大误差出现的设定百分比
+    pub max_error_at_percentage: f32,
+    /// 线性拟合增益（理想值 1.0）
+    pub gain: f64,
+    /// 线性拟合零点偏移（工程量，理想值 0.0）
+    pub offset: f64,
+    /// 线性拟合决定系数 R²（理想值 1.0）
+    pub r_squared: f64,
+    /// 最大回差（占量程百分比），单向扫描时为 None
+    pub max_hysteresis_percent: Option<f32>,
+    /// 各测试点回差
+    #[serde(default)]
+    pub hysteresis_points: Vec<AnalogHysteresisPoint>,
+}
+
+impl AnalogAccuracyMetrics {
+    /// 根据扫描读数计算精度指标
+    ///
+    /// `readings` 需按执行顺序排列：同一设定百分比第一次出现视为上行读数，
+    /// 再次出现视为下行读数。有效读数少于2个或量程无效时返回 None。
+    pub fn compute(readings: &[AnalogReadingPoint], range_span: f32) -> Option<Self> {
+        if range_span <= 0.0 {
+            return None;
+        }
+
+        let points: Vec<(f32, f32, f32)> = readings
+            .iter()
+            .filter_map(|r| r.actual_reading_eng.map(|actual| (r.set_percentage, r.set_value_eng, actual)))
+            .collect();
+        if points.len() < 2 {
+            return None;
+        }
+
+        // 最大误差
+        let (max_error_at_percentage, max_error_percent) = points
+            .iter()
+            .map(|(pct, expected, actual)| (*pct, (actual - expected).abs() / range_span * 100.0))
+            .fold((0.0f32, 0.0f32), |acc, item| if item.1 > acc.1 { item } else { acc });
+
+        // 最小二乘线性拟合: actual = gain * expected + offset
+        let n = points.len() as f64;
+        let sum_x: f64 = points.iter().map(|p| p.1 as f64).sum();
+        let sum_y: f64 = points.iter().map(|p| p.2 as f64).sum();
+        let sum_xy: f64 = points.iter().map(|p| p.1 as f64 * p.2 as f64).sum();
+        let sum_x2: f64 = points.iter().map(|p| (p.1 as f64).powi(2)).sum();
+        let denominator = n * sum_x2 - sum_x * sum_x;
+        let (gain, offset) = if denominator.abs() < f64::EPSILON {
+            // 所有设定值相同，无法拟合
+            (1.0, sum_y / n - sum_x / n)
+        } else {
+            let gain = (n * sum_xy - sum_x * sum_y) / denominator;
+            (gain, (sum_y - gain * sum_x) / n)
+        };
+
+        let mean_y = sum_y / n;
+        let ss_tot: f64 = points.iter().map(|p| (p.2 as f64 - mean_y).powi(2)).sum();
+        let ss_res: f64 = points
+            .iter()
+            .map(|p| (p.2 as f64 - (gain * p.1 as f64 + offset)).powi(2))
+            .sum();
+        let r_squared = if ss_tot.abs() < f64::EPSILON { 1.0 } else { 1.0 - ss_res / ss_tot };
+
+        // 回差：按出现顺序配对上行/下行读数
+        let mut ascending: Vec<(f32, f32)> = Vec::new();
+        let mut hysteresis_points = Vec::new();
+        for (pct, _, actual) in &points {
+            match ascending.iter().find(|(p, _)| (p - pct).abs() < 1e-4) {
+                Some((_, up)) => hysteresis_points.push(AnalogHysteresisPoint {
+                    set_percentage: *pct,
+                    ascending_value: *up,
+                    descending_value: *actual,
+                    hysteresis_percent: (actual - up).abs() / range_span * 100.0,
+                }),
+                None => ascending.push((*pct, *actual)),
+            }
+        }
+        let bidirectional = !hysteresis_points.is_empty();
+        let max_hysteresis_percent = hysteresis_points
+            .iter()
+            .map(|h| h.hysteresis_percent)
+            .fold(None, |acc: Option<f32>, v| Some(acc.map_or(v, |a| a.max(v))));
+
+        Some(Self {
+            bidirectional,
+            sample_count: points.len(),
+            max_error_percent,
+            max_error_at_percentage,
+            gain,
+            offset,
+            r_squared,
+            max_hysteresis_percent,
+            hysteresis_points,
+        })
+    }
+
+    /// 精度指标摘要，用于日志与测试结果消息
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "最大误差{:.3}%@{}%, 增益{:.4}, 偏移{:.3}, R²={:.5}",
+            self.max_error_percent,
+            (self.max_error_at_percentage * 100.0).round(),
+            self.gain,
+            self.offset,
+            self.r_squared
+        );
+        if let Some(h) = self.max_hysteresis_percent {
+            summary.push_str(&format!(", 最大回差{:.3}%", h));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SubTestStatus;
+
+    fn reading(set_percentage: f32, set_value_eng: f32, actual: Option<f32>) -> AnalogReadingPoint {
+        AnalogReadingPoint {
+            set_percentage,
+            set_value_eng,
+            expected_reading_raw: None,
+            actual_reading_raw: actual,
+            actual_reading_eng: actual,
+            status: SubTestStatus::NotTested,
+            error_percentage: None,
+        }
+    }
+
+    /// (最大误差%, 最大误差位置, 增益, 偏移, R², 最大回差%)
+    type Expected = (f32, f32, f64, f64, f64, Option<f32>);
+
+    #[test]
+    fn computes_metrics_for_edge_case_sweeps() {
+        let cases: Vec<(&str, Vec<AnalogReadingPoint>, f32, Option<Expected>)> = vec![
+            ("无读数", vec![], 100.0, None),
+            ("单个测试点", vec![reading(0.5, 50.0, Some(50.2))], 100.0, None),
+            ("有效读数不足两个", vec![reading(0.0, 0.0, Some(0.1)), reading(1.0, 100.0, None)], 100.0, None),
+            ("量程为零", vec![reading(0.0, 0.0, Some(0.0)), reading(1.0, 0.0, Some(0.0))], 0.0, None),
+            ("量程为负", vec![reading(0.0, 0.0, Some(0.0)), reading(1.0, 100.0, Some(100.0))], -100.0, None),
+            (
+                "理想读数",
+                vec![reading(0.0, 0.0, Some(0.0)), reading(0.5, 50.0, Some(50.0)), reading(1.0, 100.0, Some(100.0))],
+                100.0,
+                Some((0.0, 0.0, 1.0, 0.0, 1.0, None)),
+            ),
+            (
+                "零点偏移",
+                vec![reading(0.0, 0.0, Some(2.0)), reading(1.0, 200.0, Some(202.0))],
+                200.0,
+                Some((1.0, 0.0, 1.0, 2.0, 1.0, None)),
+            ),
+            (
+                "增益误差",
+                vec![reading(0.0, 0.0, Some(0.0)), reading(0.5, 50.0, Some(51.0)), reading(1.0, 100.0, Some(102.0))],
+                100.0,
+                Some((2.0, 1.0, 1.02, 0.0, 1.0, None)),
+            ),
+            (
+                "设定值相同无法拟合",
+                vec![reading(0.5, 50.0, Some(49.0)), reading(0.5, 50.0, Some(51.0))],
+                100.0,
+                Some((1.0, 0.5, 1.0, 0.0, 0.0, Some(2.0))),
+            ),
+            (
+                "双向扫描回差",
+                vec![
+                    reading(0.0, 0.0, Some(0.0)), reading(0.5, 50.0, Some(49.5)), reading(1.0, 100.0, Some(100.0)),
+                    reading(0.5, 50.0, Some(50.5)), reading(0.0, 0.0, Some(0.0)),
+                ],
+                100.0,
+                Some((0.5, 0.5, 1.0, 0.0, 0.99993, Some(1.0))),
+            ),
+        ];
+
+        for (name, readings, span, expected) in cases {
+            let metrics = AnalogAccuracyMetrics::compute(&readings, span);
+            let Some((max_error, at, gain, offset, r_squared, hysteresis)) = expected else {
+                assert!(metrics.is_none(), "{}: {:?}", name, metrics);
+                continue;
+            };
+            let metrics = metrics.unwrap_or_else(|| panic!("{}: 应得到精度指标", name));
+            assert_eq!(metrics.sample_count, readings.iter().filter(|r| r.actual_reading_eng.is_some()).count(), "{}", name);
+            assert!((metrics.max_error_percent - max_error).abs() < 1e-4, "{}: 最大误差 {}", name, metrics.max_error_percent);
+            assert_eq!(metrics.max_error_at_percentage, at, "{}", name);
+            assert!((metrics.gain - gain).abs() < 1e-6, "{}: 增益 {}", name, metrics.gain);
+            assert!((metrics.offset - offset).abs() < 1e-4, "{}: 偏移 {}", name, metrics.offset);
+            assert!((metrics.r_squared - r_squared).abs() < 1e-5, "{}: R² {}", name, metrics.r_squared);
+            assert_eq!(metrics.bidirectional, hysteresis.is_some(), "{}", name);
+            match (metrics.max_hysteresis_percent, hysteresis) {
+                (Some(actual), Some(expected)) => assert!((actual - expected).abs() < 1e-4, "{}: 回差 {}", name, actual),
+                (actual, expected) => assert_eq!(actual, expected, "{}", name),
+            }
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::models::enums::ModuleType;
+use crate::models::structs::ChannelPointDefinition;
+use crate::utils::config::TestConfig;
+
+/// 模拟量测试容差模式
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum AnalogToleranceMode {
+    /// 按量程百分比计算（例如：3.0 表示 ±3% 量程）
+    #[default]
+    PercentOfSpan,
+    /// 按工程量绝对值计算（例如：0.5 表示 ±0.5 工程单位）
+    Absolute,
+}
+
+/// 模拟量硬点测试参数
+/// 描述一次AI/AO硬点测试使用的测试点、容差与稳定时间
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalogTestSettings {
+    /// 测试点百分比列表（0.0 ~ 1.0，例如：[0.0, 0.5, 1.0]）
+    pub test_points: Vec<f32>,
+    /// 容差模式
+    #[serde(default)]
+    pub tolerance_mode: AnalogToleranceMode,
+    /// 容差值（含义取决于 tolerance_mode）
+    pub tolerance_value: f32,
+    /// 每个测试点写入后等待读数稳定的上限时间（毫秒），读数提前稳定时立即进入下一个测试点
+    pub settle_time_ms: u64,
+    /// 是否执行上行+下行双向扫描（用于计算回差）
+    #[serde(default)]
+    pub bidirectional_sweep: bool,
+    /// AI串扰检查阈值（占邻近通道量程百分比），None 表示不执行串扰检查
+    /// 被测通道输出100%时，同模块其他AI通道读数偏移超过阈值即判定为串扰
+    #[serde(default)]
+    pub crosstalk_threshold_percent: Option<f32>,
+    /// AI报警触发点搜索参数，配置后自动测试在硬点测试之后对已配置的报警项搜索触发点与恢复点
+    /// None 表示自动测试不执行报警测试（仍可在手动测试中单独执行）
+    #[serde(default)]
+    pub alarm_trip_point_ramp: Option<AlarmRampConfig>,
+}
+
+impl Default for AnalogTestSettings {
+    fn default() -> Self {
+        Self {
+            test_points: vec![0.0, 0.25, 0.5, 0.75, 1.0],
+            tolerance_mode: AnalogToleranceMode::PercentOfSpan,
+            tolerance_value: 3.0,
+            settle_time_ms: 2000,
+            bidirectional_sweep: false,
+            crosstalk_threshold_percent: None,
+            alarm_trip_point_ramp: None,
+        }
+    }
+}
+
+impl AnalogTestSettings {
+    /// 计算测试点的误差百分比（相对量程）
+    pub fn error_percentage(&self, expected: f32, actual: f32, range_span: f32) -> f32 {
+        if range_span <= 0.0 {
+            return 100.0;
+        }
+        ((actual - expected).abs() / range_span) * 100.0
+    }
+
+    /// 判断读数是否在容差范围内
+    pub fn is_within_tolerance(&self, expected: f32, actual: f32, range_span: f32) -> bool {
+        match self.tolerance_mode {
+            AnalogToleranceMode::PercentOfSpan => {
+                self.error_percentage(expected, actual, range_span) <= self.tolerance_value
+            }
+            AnalogToleranceMode::Absolute => (actual - expected).abs() <= self.tolerance_value,
+        }
+    }
+
+    /// 容差带宽度（工程单位），读数与期望值的偏差不超过此值即为合格
+    pub fn tolerance_band(&self, range_span: f32) -> f64 {
+        let tolerance = match self.tolerance_mode {
+            AnalogToleranceMode::PercentOfSpan => self.tolerance_value * range_span.max(0.0) / 100.0,
+            AnalogToleranceMode::Absolute => self.tolerance_value,
+        };
+        tolerance as f64
+    }
+
+    /// 判定读数稳定时相邻两次采样允许的最大变化量（工程单位），取容差带的五分之一
+    pub fn settle_band(&self, range_span: f32) -> f64 {
+        self.tolerance_band(range_span) / 5.0
+    }
+
+    /// 容差的可读描述，用于日志与报告
+    pub fn tolerance_description(&self) -> String {
+        match self.tolerance_mode {
+            AnalogToleranceMode::PercentOfSpan => format!("±{}%量程", self.tolerance_value),
+            AnalogToleranceMode::Absolute => format!("±{}工程单位", self.tolerance_value),
+        }
+    }
+
+    /// 实际扫描顺序
+    /// 双向扫描时先按配置顺序上行，再去掉折返点后逆序下行（例如：0→25→50→75→100→75→50→25→0）
+    pub fn sweep_points(&self) -> Vec<f32> {
+        let mut points = self.test_points.clone();
+        if self.bidirectional_sweep && self.test_points.len() > 1 {
+            points.extend(self.test_points.iter().rev().skip(1));
+        }
+        points
+    }
+
+    /// 测试点的可读描述（例如："0/25/50/75/100%"）
+    pub fn test_points_description(&self) -> String {
+        let points: Vec<String> = self.test_points
+            .iter()
+            .map(|p| format!("{}", (p * 100.0).round()))
+            .collect();
+        let description = format!("{}%", points.join("/"));
+        if self.bidirectional_sweep {
+            format!("{} (双向)", description)
+        } else {
+            description
+        }
+    }
+}
+
+/// 模拟量测试配置方案
+///
+/// 随批次保存，按 点位 → 模块类型 → 默认 的优先级解析实际使用的测试参数
+/// - `module_type_overrides` 的键为模块类型名称（如 "AI"、"AO"、"AINone"）
+/// - `point_overrides` 的键为点位位号（tag）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct AnalogTestProfile {
+    /// 方案名称（例如："标准5点"、"客户A-3点"）
+    #[serde(default)]
+    pub name: String,
+    /// 默认测试参数
+    #[serde(default)]
+    pub default_settings: AnalogTestSettings,
+    /// 按模块类型覆盖的测试参数
+    #[serde(default)]
+    pub module_type_overrides: HashMap<String, AnalogTestSettings>,
+    /// 按点位覆盖的测试参数
+    #[serde(default)]
+    pub point_overrides: HashMap<String, AnalogTestSettings>,
+}
+
+impl AnalogTestProfile {
+    /// 由全局测试配置生成默认方案
+    /// 读取 `analog_tolerance_percent` 与 `default_stabilization_time_ms`
+    pub fn from_test_config(test_config: &TestConfig) -> Self {
+        Self {
+            name: "默认".to_string(),
+            default_settings: AnalogTestSettings {
+                tolerance_value: test_config.analog_tolerance_percent,
+                settle_time_ms: test_config.default_stabilization_time_ms,
+                ..AnalogTestSettings::default()
+            },
+            ..Self::default()
+        }
+    }
+
+    /// 获取系统默认方案：优先读取全局配置，未初始化时使用内置默认值
+    pub fn system_default() -> Self {
+        crate::utils::config::get_global_config()
+            .map(|config| Self::from_test_config(&config.test_config))
+            .unwrap_or_else(|_| Self {
+                name: "默认".to_string(),
+                ..Self::default()
+            })
+    }
+
+    /// 解析指定点位实际使用的测试参数
+    pub fn resolve(&self, definition: &ChannelPointDefinition) -> AnalogTestSettings {
+        if let Some(settings) = self.point_overrides.get(&definition.tag) {
+            return settings.clone();
+        }
+
+        if let Some(settings) = self.module_type_overrides.get(&definition.module_type.to_string()) {
+            return settings.clone();
+        }
+
+        // 无源模块未单独配置时，沿用对应有源模块类型的配置
+        let base_module_type = match definition.module_type {
+            ModuleType::AINone => Some(ModuleType::AI),
+            ModuleType::AONone => Some(ModuleType::AO),
+            _ => None,
+        };
+        if let Some(settings) = base_module_type
+            .and_then(|t| self.module_type_overrides.get(&t.to_string()))
+        {
+            return settings.clone();
+        }
+
+        self.default_settings.clone()
+    }
+
+    /// 校验方案参数的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        let all_settings = std::iter::once(("默认".to_string(), &self.default_settings))
+            .chain(self.module_type_overrides.iter().map(|(k, v)| (format!("模块类型 {}", k), v)))
+            .chain(self.point_overrides.iter().map(|(k, v)| (format!("点位 {}", k), v)));
+
+        for (scope, settings) in all_settings {
+            if settings.test_points.is_empty() {
+                return Err(format!("{}: 测试点列表不能为空", scope));
+            }
+            if settings.test_points.iter().any(|p| !(0.0..=1.0).contains(p)) {
+                return Err(format!("{}: 测试点必须在 0% ~ 100% 之间", scope));
+            }
+            if settings.tolerance_value <= 0.0 {
+                return Err(format!("{}: 容差必须大于0", scope));
+            }
+            if let Some(ramp) = &settings.alarm_trip_point_ramp {
+                ramp.validate().map_err(|e| format!("{}: {}", scope, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// AI报警触发点搜索参数
+///
+/// 通过测试PLC AO逐步改变输入信号穿越报警设定值，搜索实际触发点与恢复点
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlarmRampConfig {
+    /// 搜索范围：设定值两侧各扩展的量程百分比
+    pub search_margin_percent: f32,
+    /// 每步信号变化量（量程百分比），决定触发点的分辨率
+    pub step_percent: f32,
+    /// 每步写入后的停留时间（毫秒），需覆盖PLC报警判断周期与滤波时间
+    pub dwell_ms: u64,
+    /// 触发点与设定值的允许偏差（量程百分比）
+    pub trip_tolerance_percent: f32,
+    /// 搜索前等待报警处于复位状态、搜索后等待报警恢复的上限时间（毫秒）
+    #[serde(default = "default_alarm_reset_delay_ms")]
+    pub reset_delay_ms: u64,
+}
+
+fn default_alarm_reset_delay_ms() -> u64 {
+    3000
+}
+
+impl Default for AlarmRampConfig {
+    fn default() -> Self {
+        Self {
+            search_margin_percent: 5.0,
+            step_percent: 0.2,
+            dwell_ms: 300,
+            trip_tolerance_percent: 0.5,
+            reset_delay_ms: default_alarm_reset_delay_ms(),
+        }
+    }
+}
+
+impl AlarmRampConfig {
+    /// 校验搜索参数的有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.step_percent <= 0.0 {
+            return Err("报警搜索步长必须大于0".to_string());
+        }
+        if self.search_margin_percent < self.step_percent {
+            return Err("报警搜索范围不能小于步长".to_string());
+        }
+        if self.trip_tolerance_percent < self.step_percent {
+            return Err("触发点允许偏差不能小于搜索步长".to_string());
+        }
+        Ok(())
+    }
+}
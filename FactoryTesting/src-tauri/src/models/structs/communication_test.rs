@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::domain::services::plc_communication_service::PlcConnectionConfig;
+use crate::utils::credential_cipher;
+use crate::utils::error::AppResult;
+
+/// 源连接协议参数中的密码键（OPC UA 用户名登录）
+const PASSWORD_PARAM: &str = "password";
+/// 落库时保存密码密文的协议参数键
+const ENCRYPTED_PASSWORD_PARAM: &str = "encrypted_password";
+
+/// 通信点测试配置
+///
+/// 通信模块点位没有硬接线，测试时通过"源连接"（例如Modbus网关）写入测试数据，
+/// 再从被测PLC的点位通信地址读回，验证数据类型、字节序与缩放是否一致。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommunicationTestConfig {
+    /// 源连接配置（写入测试数据的一侧）
+    pub source_connection: PlcConnectionConfig,
+    /// 按点位位号(tag)指定源侧写入地址；未指定时使用点位的通信地址
+    #[serde(default)]
+    pub source_address_overrides: HashMap<String, String>,
+    /// 缩放系数：被测PLC读数期望值 = 源写入值 × scale + offset
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+    /// 零点偏移
+    #[serde(default)]
+    pub offset: f64,
+    /// 数值比较允许偏差（绝对值）
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+    /// 等待数据到达被测PLC的上限时间（毫秒）
+    #[serde(default = "default_settle_timeout_ms")]
+    pub settle_timeout_ms: u64,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+fn default_tolerance() -> f64 {
+    0.001
+}
+
+fn default_settle_timeout_ms() -> u64 {
+    3000
+}
+
+impl CommunicationTestConfig {
+    /// 获取点位在源侧的写入地址
+    pub fn source_address_for(&self, tag: &str, plc_communication_address: &str) -> String {
+        self.source_address_overrides
+            .get(tag)
+            .cloned()
+            .unwrap_or_else(|| plc_communication_address.to_string())
+    }
+
+    /// 根据源写入值计算被测PLC的期望读数
+    pub fn expected_value(&self, written: f64) -> f64 {
+        written * self.scale + self.offset
+    }
+
+    /// 序列化为随批次落库的JSON，源连接协议参数中的密码以密文保存
+    pub fn to_storage_json(&self) -> AppResult<String> {
+        let mut stored = self.clone();
+        let params = &mut stored.source_connection.protocol_params;
+        if let Some(password) = params.remove(PASSWORD_PARAM) {
+            if let Some(password) = password.as_str().filter(|p| !p.is_empty()) {
+                params.insert(ENCRYPTED_PASSWORD_PARAM.to_string(), serde_json::json!(credential_cipher::encrypt(password)?));
+            }
+        }
+        Ok(serde_json::to_string(&stored)?)
+    }
+
+    /// 解析落库的JSON并解密源连接密码
+    pub fn from_storage_json(json: &str) -> AppResult<Self> {
+        let mut config: Self = serde_json::from_str(json)?;
+        let params = &mut config.source_connection.protocol_params;
+        if let Some(encrypted) = params.remove(ENCRYPTED_PASSWORD_PARAM) {
+            if let Some(encrypted) = encrypted.as_str() {
+                params.insert(PASSWORD_PARAM.to_string(), serde_json::json!(credential_cipher::decrypt(encrypted)?));
+            }
+        }
+        Ok(config)
+    }
+
+    /// 校验配置有效性
+    pub fn validate(&self) -> Result<(), String> {
+        if self.source_connection.id.trim().is_empty() {
+            return Err("源连接ID不能为空".to_string());
+        }
+        if self.source_connection.host.trim().is_empty() {
+            return Err("源连接地址不能为空".to_string());
+        }
+        if self.scale == 0.0 {
+            return Err("缩放系数不能为0".to_string());
+        }
+        if self.tolerance < 0.0 {
+            return Err("允许偏差不能为负数".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::plc_communication_service::PlcProtocol;
+
+    #[test]
+    fn storage_json_keeps_source_password_encrypted() {
+        credential_cipher::init_key_file(&std::env::temp_dir().join(format!("communication_test_{}", uuid::Uuid::new_v4()))).unwrap();
+        let config = CommunicationTestConfig {
+            source_connection: PlcConnectionConfig {
+                id: "gateway".to_string(),
+                name: "OPC UA 网关".to_string(),
+                protocol: PlcProtocol::OpcUa,
+                host: "192.168.1.20".to_string(),
+                port: 4840,
+                timeout_ms: 2000,
+                read_timeout_ms: 2000,
+                write_timeout_ms: 2000,
+                byte_order: "ABCD".to_string(),
+                zero_based_address: false,
+                retry_count: 0,
+                retry_interval_ms: 0,
+                protocol_params: [
+                    ("auth_mode".to_string(), serde_json::json!("UserName")),
+                    ("password".to_string(), serde_json::json!("网关密码")),
+                ].into_iter().collect(),
+            },
+            source_address_overrides: HashMap::from([("TT101".to_string(), "ns=2;s=TT101".to_string())]),
+            scale: 10.0,
+            offset: -5.0,
+            tolerance: 0.01,
+            settle_timeout_ms: 1500,
+        };
+
+        let stored = config.to_storage_json().unwrap();
+        assert!(!stored.contains("网关密码"), "{}", stored);
+        assert!(stored.contains(ENCRYPTED_PASSWORD_PARAM));
+        assert_eq!(CommunicationTestConfig::from_storage_json(&stored).unwrap(), config);
+    }
+}
@@ -0,0 +1,285 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::models::enums::ModuleType;
+
+/// 接线检查参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WiringCheckSettings {
+    /// 每个测试PLC输出通道驱动后的等待时间（毫秒）
+    #[serde(default = "default_dwell_ms")]
+    pub dwell_ms: u64,
+    /// 模拟量响应判定容差（占量程百分比）
+    #[serde(default = "default_analog_tolerance_percent")]
+    pub analog_tolerance_percent: f64,
+}
+
+fn default_dwell_ms() -> u64 {
+    500
+}
+
+fn default_analog_tolerance_percent() -> f64 {
+    5.0
+}
+
+impl Default for WiringCheckSettings {
+    fn default() -> Self {
+        Self {
+            dwell_ms: default_dwell_ms(),
+            analog_tolerance_percent: default_analog_tolerance_percent(),
+        }
+    }
+}
+
+/// 单个被测通道的接线检查结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WiringStatus {
+    /// 仅响应期望的测试PLC通道
+    Correct,
+    /// 响应了其他测试PLC通道（接线交叉）
+    CrossWired,
+    /// 同时响应多个测试PLC通道（疑似短接）
+    MultipleResponses,
+    /// 未响应任何测试PLC通道（断线或未接线）
+    NoResponse,
+    /// 未分配测试PLC通道，仅记录实际响应
+    Unmapped,
+}
+
+/// 单个被测通道的接线检查结果：期望的测试PLC通道与实际响应的测试PLC通道
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WiringChannelResult {
+    pub instance_id: String,
+    /// 被测点位位号
+    pub tag: String,
+    pub module_type: ModuleType,
+    /// 期望连接的测试PLC通道位号（来自通道映射或批次分配）
+    pub expected_test_rig_channel: Option<String>,
+    /// 驱动后实际响应的测试PLC通道位号
+    pub responding_test_rig_channels: Vec<String>,
+    pub status: WiringStatus,
+}
+
+/// 接线调整建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WiringSwapSuggestion {
+    pub instance_id: String,
+    pub tag: String,
+    pub expected_test_rig_channel: String,
+    pub actual_test_rig_channel: String,
+    /// 与之互换的被测点位（两根线互相接反时给出）
+    pub swap_with_instance_id: Option<String>,
+    pub swap_with_tag: Option<String>,
+    /// 建议描述
+    pub description: String,
+}
+
+/// 接线检查报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WiringCheckReport {
+    pub batch_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub channels: Vec<WiringChannelResult>,
+    pub swap_suggestions: Vec<WiringSwapSuggestion>,
+    pub correct_count: usize,
+    pub problem_count: usize,
+}
+
+impl WiringChannelResult {
+    /// 根据期望通道与实际响应通道判定接线结论
+    pub fn classify(expected: Option<&str>, responding: &[String]) -> WiringStatus {
+        match (expected, responding) {
+            (None, _) => WiringStatus::Unmapped,
+            (Some(_), []) => WiringStatus::NoResponse,
+            (Some(expected), [only]) if only == expected => WiringStatus::Correct,
+            (Some(_), [_]) => WiringStatus::CrossWired,
+            (Some(_), _) => WiringStatus::MultipleResponses,
+        }
+    }
+
+    /// 是否需要现场确认接线
+    pub fn needs_attention(&self) -> bool {
+        !matches!(self.status, WiringStatus::Correct | WiringStatus::Unmapped)
+    }
+
+    /// 结果描述，用于界面提示和接线确认
+    pub fn describe(&self) -> String {
+        let expected = self.expected_test_rig_channel.as_deref().unwrap_or("-");
+        match self.status {
+            WiringStatus::Correct => format!("{} 接线正确 ({})", self.tag, expected),
+            WiringStatus::CrossWired => format!(
+                "{} 接线交叉: 期望 {}，实际响应 {}",
+                self.tag, expected, self.responding_test_rig_channels.join(",")
+            ),
+            WiringStatus::MultipleResponses => format!(
+                "{} 同时响应多个通道 {}，疑似短接（期望 {}）",
+                self.tag, self.responding_test_rig_channels.join(","), expected
+            ),
+            WiringStatus::NoResponse => format!("{} 无响应，请检查与 {} 的接线", self.tag, expected),
+            WiringStatus::Unmapped => format!(
+                "{} 未分配测试PLC通道，实际响应 {}",
+                self.tag,
+                if self.responding_test_rig_channels.is_empty() { "-".to_string() } else { self.responding_test_rig_channels.join(",") }
+            ),
+        }
+    }
+}
+
+impl WiringCheckReport {
+    /// 汇总各通道结果并生成接线调整建议
+    pub fn new(batch_id: String, started_at: DateTime<Utc>, channels: Vec<WiringChannelResult>) -> Self {
+        let swap_suggestions = Self::suggest_swaps(&channels);
+        let correct_count = channels.iter().filter(|c| c.status == WiringStatus::Correct).count();
+        let problem_count = channels.iter().filter(|c| c.needs_attention()).count();
+        Self {
+            batch_id,
+            started_at,
+            finished_at: Utc::now(),
+            channels,
+            swap_suggestions,
+            correct_count,
+            problem_count,
+        }
+    }
+
+    /// 为接线交叉的通道生成调整建议
+    ///
+    /// A 期望 X 实际响应 Y、B 期望 Y 实际响应 X 时，建议 A 与 B 互换接线；
+    /// 否则建议将 A 的接线从 Y 改接到 X。
+    pub fn suggest_swaps(channels: &[WiringChannelResult]) -> Vec<WiringSwapSuggestion> {
+        let mut suggestions: Vec<WiringSwapSuggestion> = Vec::new();
+
+        for channel in channels.iter().filter(|c| c.status == WiringStatus::CrossWired) {
+            let (Some(expected), Some(actual)) = (
+                channel.expected_test_rig_channel.as_ref(),
+                channel.responding_test_rig_channels.first(),
+            ) else {
+                continue;
+            };
+
+            // 互换的另一方已经生成过建议
+            if suggestions.iter().any(|s| s.swap_with_instance_id.as_deref() == Some(channel.instance_id.as_str())) {
+                continue;
+            }
+
+            let partner = channels.iter().find(|other| {
+                other.instance_id != channel.instance_id
+                    && other.expected_test_rig_channel.as_ref() == Some(actual)
+                    && other.responding_test_rig_channels.as_slice() == [expected.clone()]
+            });
+
+            let description = match partner {
+                Some(partner) => format!(
+                    "{} 与 {} 接线互换: {} 应接 {}，{} 应接 {}",
+                    channel.tag, partner.tag, channel.tag, expected, partner.tag, actual
+                ),
+                None => format!("{} 当前接在 {}，应改接到 {}", channel.tag, actual, expected),
+            };
+
+            suggestions.push(WiringSwapSuggestion {
+                instance_id: channel.instance_id.clone(),
+                tag: channel.tag.clone(),
+                expected_test_rig_channel: expected.clone(),
+                actual_test_rig_channel: actual.clone(),
+                swap_with_instance_id: partner.map(|p| p.instance_id.clone()),
+                swap_with_tag: partner.map(|p| p.tag.clone()),
+                description,
+            });
+        }
+
+        suggestions
+    }
+
+    /// 指定被测实例的接线提示（优先使用调整建议）
+    pub fn hint_for(&self, instance_id: &str) -> Option<String> {
+        self.swap_suggestions
+            .iter()
+            .find(|s| s.instance_id == instance_id || s.swap_with_instance_id.as_deref() == Some(instance_id))
+            .map(|s| s.description.clone())
+            .or_else(|| {
+                self.channels
+                    .iter()
+                    .find(|c| c.instance_id == instance_id && c.needs_attention())
+                    .map(|c| c.describe())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(instance_id: &str, expected: Option<&str>, responding: &[&str]) -> WiringChannelResult {
+        let responding: Vec<String> = responding.iter().map(|c| c.to_string()).collect();
+        WiringChannelResult {
+            instance_id: instance_id.to_string(),
+            tag: format!("TAG_{}", instance_id),
+            module_type: ModuleType::DI,
+            status: WiringChannelResult::classify(expected, &responding),
+            expected_test_rig_channel: expected.map(str::to_string),
+            responding_test_rig_channels: responding,
+        }
+    }
+
+    #[test]
+    fn classify_covers_every_status() {
+        let cases: [(Option<&str>, &[&str], WiringStatus); 6] = [
+            (Some("DO1_1"), &["DO1_1"], WiringStatus::Correct),
+            (Some("DO1_1"), &["DO1_2"], WiringStatus::CrossWired),
+            (Some("DO1_1"), &["DO1_1", "DO1_2"], WiringStatus::MultipleResponses),
+            (Some("DO1_1"), &[], WiringStatus::NoResponse),
+            (None, &["DO1_2"], WiringStatus::Unmapped),
+            (None, &[], WiringStatus::Unmapped),
+        ];
+        for (expected, responding, status) in cases {
+            let responding: Vec<String> = responding.iter().map(|c| c.to_string()).collect();
+            assert_eq!(WiringChannelResult::classify(expected, &responding), status, "{:?} {:?}", expected, responding);
+        }
+    }
+
+    #[test]
+    fn crossed_pair_yields_a_single_swap_suggestion() {
+        let channels = [
+            channel("a", Some("DO1_1"), &["DO1_2"]),
+            channel("b", Some("DO1_2"), &["DO1_1"]),
+            channel("c", Some("DO1_3"), &["DO1_3"]),
+        ];
+        let suggestions = WiringCheckReport::suggest_swaps(&channels);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].instance_id, "a");
+        assert_eq!(suggestions[0].swap_with_instance_id.as_deref(), Some("b"));
+        assert_eq!(suggestions[0].expected_test_rig_channel, "DO1_1");
+        assert_eq!(suggestions[0].actual_test_rig_channel, "DO1_2");
+    }
+
+    #[test]
+    fn one_sided_cross_suggests_moving_the_wire() {
+        // b 无响应，a 接到了 b 的通道上：只能建议 a 改接，不能建议互换
+        let channels = [
+            channel("a", Some("DO1_1"), &["DO1_2"]),
+            channel("b", Some("DO1_2"), &[]),
+        ];
+        let suggestions = WiringCheckReport::suggest_swaps(&channels);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].swap_with_instance_id, None);
+        assert!(suggestions[0].description.contains("应改接到 DO1_1"), "{}", suggestions[0].description);
+    }
+
+    #[test]
+    fn shorted_or_silent_channels_get_no_swap_suggestion() {
+        let channels = [
+            channel("a", Some("DO1_1"), &["DO1_1", "DO1_2"]),
+            channel("b", Some("DO1_2"), &[]),
+            channel("c", None, &["DO1_3"]),
+        ];
+        assert!(WiringCheckReport::suggest_swaps(&channels).is_empty());
+
+        let report = WiringCheckReport::new("batch".to_string(), Utc::now(), channels.to_vec());
+        assert_eq!(report.correct_count, 0);
+        assert_eq!(report.problem_count, 2, "未分配通道只记录，不计为问题");
+        assert!(report.hint_for("a").is_some_and(|h| h.contains("疑似短接")));
+    }
+}
@@ -3,6 +3,13 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use crate::domain::services::plc_communication_service::{
+    PlcConnectionConfig as ServicePlcConnectionConfig, PlcProtocol,
+};
+use crate::utils::credential_cipher;
+use crate::utils::error::AppResult;
 
 /// 通道类型枚举 - 对应数据库中的ChannelType字段
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,11 +51,411 @@ pub struct TestPlcChannelConfig {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PlcType {
     ModbusTcp,
+    ModbusRtu,                 // Modbus RTU（RS-485/RS-232 串口）
     SiemensS7,
     OpcUa,
 
 }
 
+impl PlcType {
+    /// 对应的通信协议
+    pub fn protocol(&self) -> PlcProtocol {
+        match self {
+            PlcType::ModbusTcp => PlcProtocol::ModbusTcp,
+            PlcType::ModbusRtu => PlcProtocol::ModbusRtu,
+            PlcType::SiemensS7 => PlcProtocol::SiemensS7,
+            PlcType::OpcUa => PlcProtocol::OpcUa,
+        }
+    }
+}
+
+/// 串口校验位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+/// 串口参数（Modbus RTU）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerialPortSettings {
+    #[serde(rename = "devicePath")]
+    pub device_path: String,                   // 串口设备 (如: /dev/ttyUSB0, COM3)
+    #[serde(rename = "baudRate")]
+    pub baud_rate: u32,                        // 波特率
+    #[serde(rename = "dataBits", default = "default_data_bits")]
+    pub data_bits: u8,                         // 数据位 (5-8)
+    #[serde(default)]
+    pub parity: SerialParity,                  // 校验位
+    #[serde(rename = "stopBits", default = "default_stop_bits")]
+    pub stop_bits: u8,                         // 停止位 (1或2)
+    #[serde(rename = "slaveId", default = "default_slave_id")]
+    pub slave_id: u8,                          // Modbus从站地址
+}
+
+fn default_data_bits() -> u8 {
+    8
+}
+
+fn default_stop_bits() -> u8 {
+    1
+}
+
+fn default_slave_id() -> u8 {
+    1
+}
+
+impl Default for SerialPortSettings {
+    fn default() -> Self {
+        Self {
+            device_path: String::new(),
+            baud_rate: 9600,
+            data_bits: default_data_bits(),
+            parity: SerialParity::None,
+            stop_bits: default_stop_bits(),
+            slave_id: default_slave_id(),
+        }
+    }
+}
+
+impl SerialPortSettings {
+    /// 校验串口参数
+    pub fn validate(&self) -> Result<(), String> {
+        if self.device_path.trim().is_empty() {
+            return Err("串口设备不能为空".to_string());
+        }
+        if self.baud_rate == 0 {
+            return Err("波特率必须大于0".to_string());
+        }
+        if !(5..=8).contains(&self.data_bits) {
+            return Err(format!("不支持的数据位: {}", self.data_bits));
+        }
+        if !matches!(self.stop_bits, 1 | 2) {
+            return Err(format!("不支持的停止位: {}", self.stop_bits));
+        }
+        if self.slave_id == 0 || self.slave_id > 247 {
+            return Err(format!("从站地址超出范围(1-247): {}", self.slave_id));
+        }
+        Ok(())
+    }
+
+    /// 转换为通信服务的协议参数
+    pub fn to_protocol_params(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert("device_path".to_string(), serde_json::json!(self.device_path));
+        params.insert("baud_rate".to_string(), serde_json::json!(self.baud_rate));
+        params.insert("data_bits".to_string(), serde_json::json!(self.data_bits));
+        params.insert("parity".to_string(), serde_json::json!(self.parity));
+        params.insert("stop_bits".to_string(), serde_json::json!(self.stop_bits));
+        params.insert("slave_id".to_string(), serde_json::json!(self.slave_id));
+        params
+    }
+
+    /// 从通信服务的协议参数还原，缺失项使用默认值
+    pub fn from_protocol_params(params: &HashMap<String, serde_json::Value>) -> Result<Self, String> {
+        let defaults = Self::default();
+        let device_path = params
+            .get("device_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| "缺少串口设备参数 device_path".to_string())?
+            .to_string();
+        let read_u64 = |key: &str, default: u64| params.get(key).and_then(|v| v.as_u64()).unwrap_or(default);
+        let parity = match params.get("parity") {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| format!("无效的校验位: {}", e))?,
+            None => defaults.parity,
+        };
+
+        let settings = Self {
+            device_path,
+            baud_rate: read_u64("baud_rate", defaults.baud_rate as u64) as u32,
+            data_bits: read_u64("data_bits", defaults.data_bits as u64) as u8,
+            parity,
+            stop_bits: read_u64("stop_bits", defaults.stop_bits as u64) as u8,
+            slave_id: read_u64("slave_id", defaults.slave_id as u64) as u8,
+        };
+        settings.validate()?;
+        Ok(settings)
+    }
+}
+
+/// S7连接类型，决定远端TSAP的高字节
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum S7ConnectionType {
+    #[default]
+    Pg,                        // 编程设备连接
+    Op,                        // 操作面板连接
+    Basic,                     // S7 Basic连接
+}
+
+impl S7ConnectionType {
+    fn code(&self) -> u8 {
+        match self {
+            S7ConnectionType::Pg => 1,
+            S7ConnectionType::Op => 2,
+            S7ConnectionType::Basic => 3,
+        }
+    }
+}
+
+/// 西门子S7连接参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct S7ConnectionSettings {
+    #[serde(default)]
+    pub rack: u16,                             // 机架号
+    #[serde(default = "default_s7_slot")]
+    pub slot: u16,                             // CPU插槽号 (S7-300为2，S7-1200/1500为1)
+    #[serde(rename = "connectionType", default)]
+    pub connection_type: S7ConnectionType,     // 连接类型
+}
+
+fn default_s7_slot() -> u16 {
+    1
+}
+
+impl Default for S7ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            rack: 0,
+            slot: default_s7_slot(),
+            connection_type: S7ConnectionType::default(),
+        }
+    }
+}
+
+impl S7ConnectionSettings {
+    /// 校验机架/插槽范围
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rack > 7 {
+            return Err(format!("机架号超出范围(0-7): {}", self.rack));
+        }
+        if self.slot > 31 {
+            return Err(format!("插槽号超出范围(0-31): {}", self.slot));
+        }
+        Ok(())
+    }
+
+    /// 转换为通信服务的协议参数
+    pub fn to_protocol_params(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert("rack".to_string(), serde_json::json!(self.rack));
+        params.insert("slot".to_string(), serde_json::json!(self.slot));
+        params.insert("connection_type".to_string(), serde_json::json!(self.connection_type.code()));
+        params
+    }
+}
+
+/// OPC UA 用户认证方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OpcUaAuthMode {
+    #[default]
+    Anonymous,                 // 匿名登录
+    UserName,                  // 用户名/密码登录
+}
+
+/// OPC UA 连接参数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct OpcUaConnectionSettings {
+    #[serde(rename = "endpointPath", default)]
+    pub endpoint_path: String,                 // 端点路径，如 "/freeopcua/server/"，拼接在 opc.tcp://IP:端口 之后
+    #[serde(rename = "authMode", default)]
+    pub auth_mode: OpcUaAuthMode,              // 认证方式
+    #[serde(default)]
+    pub username: Option<String>,              // 用户名（仅用户名登录）
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,              // 密码（仅用户名登录），只接收不回传，落库时加密
+    #[serde(rename = "passwordSaved", default)]
+    pub password_saved: bool,                  // 是否已保存密码，供界面提示“留空沿用已保存密码”
+    #[serde(rename = "allowPlaintextPassword", default)]
+    pub allow_plaintext_password: bool,        // 用户确认允许在未加密通道（SecurityPolicy None）上明文发送密码
+}
+
+/// 数据库中保存密码密文的字段名
+const ENCRYPTED_PASSWORD_KEY: &str = "encryptedPassword";
+
+impl OpcUaConnectionSettings {
+    /// 校验端点路径与认证参数
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.endpoint_path.is_empty() && !self.endpoint_path.starts_with('/') {
+            return Err(format!("端点路径必须以 '/' 开头: {}", self.endpoint_path));
+        }
+        if self.auth_mode == OpcUaAuthMode::UserName
+            && self.username.as_deref().map_or(true, |u| u.trim().is_empty())
+        {
+            return Err("用户名登录方式需要填写用户名".to_string());
+        }
+        if self.auth_mode == OpcUaAuthMode::UserName && !self.allow_plaintext_password {
+            return Err("当前仅支持无加密的安全策略(None)，用户名登录会以明文发送密码；请确认网络可信并勾选允许明文密码".to_string());
+        }
+        Ok(())
+    }
+
+    /// 生成落库用的JSON：密码以密文保存在 `encryptedPassword` 字段
+    pub fn to_storage_json(&self) -> AppResult<String> {
+        let mut value = serde_json::to_value(self)?;
+        if let (Some(password), Some(object)) = (self.password.as_deref(), value.as_object_mut()) {
+            object.insert(ENCRYPTED_PASSWORD_KEY.to_string(), serde_json::json!(credential_cipher::encrypt(password)?));
+        }
+        Ok(value.to_string())
+    }
+
+    /// 解析落库的JSON并解密密码；旧版本以明文保存的 `password` 字段照常读取，下次保存时转为密文
+    pub fn from_storage_json(json: &str) -> AppResult<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let encrypted = value.get(ENCRYPTED_PASSWORD_KEY).and_then(|v| v.as_str()).map(str::to_string);
+        let mut settings: Self = serde_json::from_value(value)?;
+        if let Some(encrypted) = encrypted {
+            settings.password = Some(credential_cipher::decrypt(&encrypted)?);
+        }
+        settings.password_saved = settings.password.is_some();
+        Ok(settings)
+    }
+
+    /// 转换为通信服务的协议参数
+    pub fn to_protocol_params(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert("endpoint_path".to_string(), serde_json::json!(self.endpoint_path));
+        params.insert("auth_mode".to_string(), serde_json::json!(self.auth_mode));
+        if self.auth_mode == OpcUaAuthMode::UserName {
+            params.insert("username".to_string(), serde_json::json!(self.username.clone().unwrap_or_default()));
+            params.insert("password".to_string(), serde_json::json!(self.password.clone().unwrap_or_default()));
+            params.insert("allow_plaintext_password".to_string(), serde_json::json!(self.allow_plaintext_password));
+        }
+        params
+    }
+}
+
+/// Modbus字符串编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ModbusStringEncoding {
+    #[default]
+    Ascii,                     // 每个寄存器2个ASCII字符
+    Utf8,                      // UTF-8字节流
+    Utf16,                     // 每个寄存器1个UTF-16编码单元
+}
+
+/// Modbus字符串填充字符，写入时补齐到整寄存器，读取时去除
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ModbusStringPadding {
+    #[default]
+    Null,                      // 0x00
+    Space,                     // 0x20
+}
+
+impl ModbusStringPadding {
+    pub fn byte(&self) -> u8 {
+        match self {
+            ModbusStringPadding::Null => 0x00,
+            ModbusStringPadding::Space => b' ',
+        }
+    }
+}
+
+/// `%MW`/`%MD`/`%MX` 地址的编址方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ModbusMAreaAddressing {
+    #[default]
+    ByteOffset,                // 按字节编址（西门子风格）：%MW100 为第100字节，即保持寄存器50
+    WordIndex,                 // 按字编址（施耐德/CODESYS）：%MW100 即保持寄存器100
+}
+
+/// Modbus多寄存器数据参数（64位数值字序、字符串编码与填充、M区编址），数值字节序沿用连接的 `byte_order`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ModbusDataSettings {
+    #[serde(rename = "wordSwap64", default)]
+    pub word_swap_64: bool,                    // 64位数值交换高低两个双字
+    #[serde(rename = "stringEncoding", default)]
+    pub string_encoding: ModbusStringEncoding, // 字符串编码
+    #[serde(rename = "stringPadding", default)]
+    pub string_padding: ModbusStringPadding,   // 字符串填充字符
+    #[serde(rename = "stringByteSwap", default)]
+    pub string_byte_swap: bool,                // 字符串在寄存器内低字节在前
+    #[serde(rename = "mAreaAddressing", default)]
+    pub m_area_addressing: ModbusMAreaAddressing, // %M 地址编址方式
+}
+
+impl ModbusDataSettings {
+    /// 转换为通信服务的协议参数
+    pub fn to_protocol_params(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert("word_swap_64".to_string(), serde_json::json!(self.word_swap_64));
+        params.insert("string_encoding".to_string(), serde_json::json!(self.string_encoding));
+        params.insert("string_padding".to_string(), serde_json::json!(self.string_padding));
+        params.insert("string_byte_swap".to_string(), serde_json::json!(self.string_byte_swap));
+        params.insert("m_area_addressing".to_string(), serde_json::json!(self.m_area_addressing));
+        params
+    }
+
+    /// 从通信服务的协议参数还原，缺失或无法识别的项使用默认值
+    pub fn from_protocol_params(params: &HashMap<String, serde_json::Value>) -> Self {
+        let read_bool = |key: &str| params.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        Self {
+            word_swap_64: read_bool("word_swap_64"),
+            string_encoding: params
+                .get("string_encoding")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            string_padding: params
+                .get("string_padding")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+            string_byte_swap: read_bool("string_byte_swap"),
+            m_area_addressing: params
+                .get("m_area_addressing")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Modbus连接请求调度参数（在途上限、帧间隔、排队期限）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModbusSchedulerSettings {
+    #[serde(rename = "maxInFlight", default = "default_max_in_flight")]
+    pub max_in_flight: u32,                    // 同时在途的最大请求数
+    #[serde(rename = "interFrameGapMs", default)]
+    pub inter_frame_gap_ms: u64,               // 相邻两帧之间的最小间隔(ms)
+    #[serde(rename = "queueDeadlineMs", default)]
+    pub queue_deadline_ms: u64,                // 请求排队期限(ms)，0表示使用连接超时
+}
+
+fn default_max_in_flight() -> u32 {
+    1
+}
+
+impl Default for ModbusSchedulerSettings {
+    fn default() -> Self {
+        Self {
+            max_in_flight: default_max_in_flight(),
+            inter_frame_gap_ms: 0,
+            queue_deadline_ms: 0,
+        }
+    }
+}
+
+impl ModbusSchedulerSettings {
+    /// 转换为通信服务的协议参数
+    pub fn to_protocol_params(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert("max_in_flight".to_string(), serde_json::json!(self.max_in_flight));
+        params.insert("inter_frame_gap_ms".to_string(), serde_json::json!(self.inter_frame_gap_ms));
+        params.insert("queue_deadline_ms".to_string(), serde_json::json!(self.queue_deadline_ms));
+        params
+    }
+
+    /// 从通信服务的协议参数还原，缺失或无法识别的项使用默认值
+    pub fn from_protocol_params(params: &HashMap<String, serde_json::Value>) -> Self {
+        let read_u64 = |key: &str| params.get(key).and_then(|v| v.as_u64());
+        let defaults = Self::default();
+        Self {
+            max_in_flight: read_u64("max_in_flight").map(|v| v.clamp(1, u32::MAX as u64) as u32).unwrap_or(defaults.max_in_flight),
+            inter_frame_gap_ms: read_u64("inter_frame_gap_ms").unwrap_or(defaults.inter_frame_gap_ms),
+            queue_deadline_ms: read_u64("queue_deadline_ms").unwrap_or(defaults.queue_deadline_ms),
+        }
+    }
+}
+
 /// 连接状态枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConnectionStatus {
@@ -85,6 +492,93 @@ pub struct PlcConnectionConfig {
     pub last_connected: Option<DateTime<Utc>>, // 最后连接时间
     #[serde(rename = "connectionStatus")]
     pub connection_status: ConnectionStatus,   // 连接状态
+    #[serde(rename = "serialSettings", default)]
+    pub serial_settings: Option<SerialPortSettings>, // 串口参数（仅Modbus RTU）
+    #[serde(rename = "s7Settings", default)]
+    pub s7_settings: Option<S7ConnectionSettings>, // 机架/插槽参数（仅西门子S7）
+    #[serde(rename = "opcUaSettings", default)]
+    pub opcua_settings: Option<OpcUaConnectionSettings>, // 端点路径/认证参数（仅OPC UA）
+    #[serde(rename = "modbusDataSettings", default)]
+    pub modbus_data_settings: Option<ModbusDataSettings>, // 64位字序/字符串编码参数（仅Modbus）
+    #[serde(rename = "modbusSchedulerSettings", default)]
+    pub modbus_scheduler_settings: Option<ModbusSchedulerSettings>, // 请求调度参数（仅Modbus）
+}
+
+impl PlcConnectionConfig {
+    /// 是否为串口连接
+    pub fn is_serial(&self) -> bool {
+        self.plc_type == PlcType::ModbusRtu
+    }
+
+    /// 是否为Modbus TCP连接（其余协议的连接由全局PLC服务持有）
+    pub fn is_modbus_tcp(&self) -> bool {
+        self.plc_type == PlcType::ModbusTcp
+    }
+
+    /// 连接端点描述，用于日志与界面提示
+    pub fn endpoint(&self) -> String {
+        match (&self.serial_settings, self.is_serial()) {
+            (Some(serial), true) => format!("{}@{}", serial.device_path, serial.baud_rate),
+            _ if self.plc_type == PlcType::OpcUa => format!(
+                "opc.tcp://{}:{}{}",
+                self.ip_address,
+                self.port,
+                self.opcua_settings.as_ref().map(|s| s.endpoint_path.as_str()).unwrap_or("")
+            ),
+            _ => format!("{}:{}", self.ip_address, self.port),
+        }
+    }
+
+    /// 校验连接参数：TCP连接需要IP地址，串口连接需要完整的串口参数，S7连接校验机架/插槽，OPC UA校验认证参数
+    pub fn validate_endpoint(&self) -> Result<(), String> {
+        if self.is_serial() {
+            self.serial_settings
+                .as_ref()
+                .ok_or_else(|| "Modbus RTU连接缺少串口参数".to_string())?
+                .validate()
+        } else if self.ip_address.is_empty() {
+            Err("IP地址不能为空".to_string())
+        } else if let (PlcType::SiemensS7, Some(s7)) = (self.plc_type, &self.s7_settings) {
+            s7.validate()
+        } else if let (PlcType::OpcUa, Some(opcua)) = (self.plc_type, &self.opcua_settings) {
+            opcua.validate()
+        } else {
+            Ok(())
+        }
+    }
+
+    /// 转换为通信服务使用的连接配置
+    pub fn to_service_config(&self) -> ServicePlcConnectionConfig {
+        let mut protocol_params = match self.plc_type {
+            PlcType::ModbusRtu => self.serial_settings.as_ref().map(|s| s.to_protocol_params()).unwrap_or_default(),
+            PlcType::SiemensS7 => self.s7_settings.clone().unwrap_or_default().to_protocol_params(),
+            PlcType::OpcUa => self.opcua_settings.clone().unwrap_or_default().to_protocol_params(),
+            _ => HashMap::new(),
+        };
+        if matches!(self.plc_type, PlcType::ModbusTcp | PlcType::ModbusRtu) {
+            if let Some(data) = &self.modbus_data_settings {
+                protocol_params.extend(data.to_protocol_params());
+            }
+            if let Some(scheduler) = &self.modbus_scheduler_settings {
+                protocol_params.extend(scheduler.to_protocol_params());
+            }
+        }
+        ServicePlcConnectionConfig {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            protocol: self.plc_type.protocol(),
+            host: self.ip_address.clone(),
+            port: self.port as u16,
+            timeout_ms: self.timeout as u64,
+            read_timeout_ms: self.timeout as u64,
+            write_timeout_ms: self.timeout as u64,
+            retry_count: self.retry_count as u32,
+            retry_interval_ms: 500,
+            byte_order: self.byte_order.clone(),
+            zero_based_address: self.zero_based_address,
+            protocol_params,
+        }
+    }
 }
 
 /// 映射类型枚举
@@ -209,6 +703,11 @@ impl Default for PlcConnectionConfig {
             is_enabled: true,
             last_connected: None,
             connection_status: ConnectionStatus::Disconnected,
+            serial_settings: None,
+            s7_settings: None,
+            opcua_settings: None,
+            modbus_data_settings: None,
+            modbus_scheduler_settings: None,
         }
     }
 }
@@ -268,3 +767,38 @@ impl From<i32> for TestPlcChannelType {
 }
 
 fn default_byte_order() -> String { "CDAB".to_string() } 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opcua_password_is_write_only_and_stored_encrypted() {
+        let dir = std::env::temp_dir().join(format!("opcua_settings_{}", uuid::Uuid::new_v4()));
+        credential_cipher::init_key_file(&dir).unwrap();
+
+        let mut settings: OpcUaConnectionSettings = serde_json::from_str(
+            r#"{"endpointPath":"/server/","authMode":"UserName","username":"operator","password":"secret"}"#,
+        ).unwrap();
+        assert_eq!(settings.password.as_deref(), Some("secret"));
+        assert!(settings.validate().is_err(), "未允许明文密码时应拒绝用户名登录");
+        settings.allow_plaintext_password = true;
+        assert!(settings.validate().is_ok());
+
+        // 返回界面的JSON不含密码
+        assert!(!serde_json::to_string(&settings).unwrap().contains("secret"));
+
+        let stored = settings.to_storage_json().unwrap();
+        assert!(!stored.contains("secret"));
+        let loaded = OpcUaConnectionSettings::from_storage_json(&stored).unwrap();
+        assert_eq!(loaded.password.as_deref(), Some("secret"));
+        assert!(loaded.password_saved);
+        assert!(loaded.allow_plaintext_password);
+
+        // 旧版本明文保存的密码仍可读取
+        let legacy = OpcUaConnectionSettings::from_storage_json(
+            r#"{"endpointPath":"","authMode":"UserName","username":"operator","password":"old"}"#,
+        ).unwrap();
+        assert_eq!(legacy.password.as_deref(), Some("old"));
+    }
+}
@@ -30,6 +30,7 @@ use crate::domain::services::{
     ITestPlcConfigService, TestPlcConfigService,
     PlcConnectionManager
 };
+use crate::domain::services::persistence_service::TransactionOperation;
 use crate::infrastructure::{
     IPersistenceService, SqliteOrmPersistenceService,
     excel::ExcelImporter,
@@ -108,6 +109,9 @@ pub struct AppState {
     /// PLC监控服务 - 实时监控PLC通道值
     pub plc_monitoring_service: Arc<dyn crate::infrastructure::IPlcMonitoringService>,
 
+    /// 内置PLC仿真器 - 仅Mock模式下启动，同时仿真测试PLC与被测PLC
+    pub plc_simulator: Option<Arc<crate::infrastructure::PlcSimulator>>,
+
     // === 状态缓存 ===
     
     /// 全局功能测试状态缓存
@@ -192,6 +196,9 @@ impl AppState {
             )?;
         }
 
+        // 加载连接凭据密钥（与数据库同目录，首次运行时生成），PLC连接配置中的密码以此加密保存
+        crate::utils::credential_cipher::init_key_file(&config.storage_root_dir)?;
+
         let sqlite_persistence_service = SqliteOrmPersistenceService::new(config.clone(), Some(&db_file_path)).await?;
 
         // 执行数据库迁移
@@ -201,6 +208,8 @@ impl AppState {
             return Err(e);
         }
 
+        // 保留一份具体类型的句柄，用于启动定时自动备份与过期数据清理
+        let maintenance_service = sqlite_persistence_service.clone();
         let persistence_service: Arc<dyn IPersistenceService> = Arc::new(sqlite_persistence_service);
 
         // 加载全部全局功能测试状态
@@ -222,6 +231,10 @@ impl AppState {
             service.initialize().await?;
         }
 
+        // 按持久化配置定时备份数据库并记录最近备份时间
+        let persistence_config = crate::utils::config::effective_persistence_config();
+        maintenance_service.spawn_auto_backup(app_settings_service.clone(), &persistence_config);
+
         // 创建测试PLC配置服务（需要先创建，因为后面要用到）
         let test_plc_config_service: Arc<dyn ITestPlcConfigService> = Arc::new(
             TestPlcConfigService::new(persistence_service.clone())
@@ -345,10 +358,47 @@ impl AppState {
             test_plc_config_service.clone(),
         ));
 
-        // 设置全局PLC连接管理器，让ModbusPlcService能够访问
+        // 设置全局PLC连接管理器，供通信服务按连接ID获取连接
         //crate::infrastructure::plc_communication::set_global_plc_manager(plc_connection_manager.clone());
         crate::domain::services::plc_communication_service::set_global_plc_manager(plc_connection_manager.clone());
 
+        // 定时清理过期测试数据，有批次正在测试时推迟
+        maintenance_service.spawn_retention_cleanup(
+            &persistence_config.retention,
+            crate::application::services::test_coordination_service::batches_running_probe(
+                test_coordination_service.clone(),
+            ),
+        );
+
+        // PLC掉线时自动暂停运行中的批次，恢复后自动继续
+        crate::application::services::test_coordination_service::spawn_connection_auto_pause(
+            test_coordination_service.clone(),
+            plc_connection_manager.clone(),
+        );
+
+        // Mock模式：启动内置PLC仿真器，测试PLC与被测PLC连接改为指向仿真器
+        let plc_config = crate::utils::config::effective_plc_config();
+        let plc_simulator = if plc_config.mock_mode {
+            use crate::infrastructure::plc_simulator::{load_simulator_model, SideLayout};
+            use crate::infrastructure::{PlcSimulator, SimulatedSide};
+
+            let model = load_simulator_model(
+                &test_plc_config_service,
+                &persistence_service,
+                SideLayout::from_connection(test_plc_connection),
+                SideLayout::from_connection(target_plc_connection),
+            ).await?;
+            let simulator = PlcSimulator::start(plc_config.simulator.clone(), model).await?;
+            for (connection_id, side) in [(&test_rig_connection_id, SimulatedSide::TestRig), (&target_connection_id, SimulatedSide::Target)] {
+                let endpoint = simulator.endpoint(side);
+                plc_connection_manager.override_endpoint(connection_id, &endpoint.ip().to_string(), endpoint.port()).await;
+            }
+            log::info!("🧪 Mock模式已启用，PLC连接由内置仿真器接管");
+            Some(Arc::new(simulator))
+        } else {
+            None
+        };
+
         // 创建PLC监控服务 - 使用真实的PLC监控服务
         let plc_monitoring_service: Arc<dyn crate::infrastructure::IPlcMonitoringService> = Arc::new(
             crate::infrastructure::plc_monitoring_service::PlcMonitoringService::new(
@@ -370,6 +420,7 @@ impl AppState {
             channel_allocation_service,
             plc_connection_manager,
             plc_monitoring_service,
+            plc_simulator,
 
             // 新增连接ID
             test_rig_connection_id,
@@ -463,6 +514,16 @@ pub async fn start_batch_testing(
     state: State<'_, AppState>,
     batch_id: String,
 ) -> Result<(), String> {
+    // Mock模式：按本批次的通道分配补充仿真器回环
+    if let Some(simulator) = &state.plc_simulator {
+        let instances = state.persistence_service.load_test_instances_by_batch(&batch_id).await
+            .map_err(|e| e.to_string())?;
+        let definitions = state.persistence_service.load_all_channel_definitions().await
+            .map_err(|e| e.to_string())?;
+        let count = simulator.add_instance_routes(&instances, &definitions);
+        log::info!("🧪 批次 {} 已同步 {} 条仿真回环", batch_id, count);
+    }
+
     state.test_coordination_service
         .start_batch_testing(&batch_id)
         .await
@@ -873,12 +934,8 @@ pub async fn create_test_data(
     log::info!("[CreateTestData] 创建了 {} 个测试通道定义", definitions.len());
 
     // 保存到数据库
-    for def in &definitions {
-        if let Err(e) = state.persistence_service.save_channel_definition(def).await {
-            log::error!("[CreateTestData] 保存通道定义失败: {} - {}", def.id, e);
-        } else {
-            log::debug!("[CreateTestData] 保存通道定义成功: {} - {}", def.id, def.tag);
-        }
+    if let Err(e) = state.persistence_service.save_channel_definitions(&definitions).await {
+        log::error!("[CreateTestData] 保存通道定义失败: {}", e);
     }
 
     log::info!("[CreateTestData] 所有测试数据创建完成");
@@ -926,10 +983,9 @@ pub async fn import_excel_file(
 /// - 这是旧版API，新版本应使用 submit_test_execution
 /// 
 /// 执行流程：
-/// 1. 保存批次信息到数据库
-/// 2. 为所有通道定义设置批次ID
-/// 3. 保存所有通道定义
-/// 4. 为每个定义创建测试实例
+/// 1. 为所有通道定义设置批次ID
+/// 2. 为每个定义创建测试实例
+/// 3. 批次信息、通道定义、测试实例在同一事务内保存
 /// 
 /// 参数：
 /// - state: 应用状态
@@ -956,35 +1012,25 @@ pub async fn create_test_batch_with_definitions(
     batch_info: TestBatchInfo,
     definitions: Vec<ChannelPointDefinition>,
 ) -> Result<String, String> {
-    // 保存批次信息
-    state.persistence_service
-        .save_batch_info(&batch_info)
-        .await
-        .map_err(|e| e.to_string())?;
-
     // 🔥 保存通道定义（设置批次ID）
     let mut updated_definitions = definitions;
     for definition in &mut updated_definitions {
         definition.batch_id = Some(batch_info.batch_id.clone());
     }
 
-    for definition in &updated_definitions {
-        state.persistence_service
-            .save_channel_definition(definition)
-            .await
-            .map_err(|e| e.to_string())?;
+    // 为每个定义创建测试实例
+    let instances: Vec<ChannelTestInstance> = updated_definitions.iter()
+        .map(|definition| ChannelTestInstance::new(definition.id.clone(), batch_info.batch_id.clone()))
+        .collect();
 
-        // 为每个定义创建测试实例
-        let instance = ChannelTestInstance::new(
-            definition.id.clone(),
-            batch_info.batch_id.clone(),
-        );
-
-        state.persistence_service
-            .save_test_instance(&instance)
-            .await
-            .map_err(|e| e.to_string())?;
-    }
+    // 批次、定义、实例一起提交，任一失败都不会留下残缺批次
+    let mut operations = vec![TransactionOperation::SaveBatchInfo(batch_info.clone())];
+    operations.extend(updated_definitions.into_iter().map(TransactionOperation::SaveChannelDefinition));
+    operations.extend(instances.into_iter().map(TransactionOperation::SaveTestInstance));
+    state.persistence_service
+        .execute_transaction(operations)
+        .await
+        .map_err(|e| e.to_string())?;
 
     Ok(batch_info.batch_id)
 }
@@ -1125,6 +1171,144 @@ pub async fn save_batch_info(
         .map_err(|e| e.to_string())
 }
 
+/// 获取批次的模拟量测试配置方案
+/// 
+/// 业务说明：
+/// - 返回批次保存的测试点、容差和稳定时间配置
+/// - 批次未配置时返回系统默认方案（来自TestConfig）
+/// 
+/// 参数：
+/// - state: 应用状态
+/// - batch_id: 批次ID
+/// 
+/// 调用链：
+/// 前端批次配置页面 -> get_batch_analog_test_profile_cmd -> PersistenceService
+#[tauri::command]
+pub async fn get_batch_analog_test_profile_cmd(
+    state: State<'_, AppState>,
+    batch_id: String,
+) -> Result<crate::models::AnalogTestProfile, String> {
+    let batch_info = state.persistence_service
+        .load_batch_info(&batch_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("批次不存在: {}", batch_id))?;
+
+    Ok(batch_info.analog_test_profile
+        .unwrap_or_else(crate::models::AnalogTestProfile::system_default))
+}
+
+/// 保存批次的模拟量测试配置方案
+/// 
+/// 业务说明：
+/// - 方案随批次保存，并在导出的测试报告中打印
+/// - 同时下发到测试执行引擎，后续提交的硬点测试立即生效
+/// 
+/// 参数：
+/// - state: 应用状态
+/// - batch_id: 批次ID
+/// - profile: 模拟量测试配置方案
+/// 
+/// 调用链：
+/// 前端批次配置页面 -> save_batch_analog_test_profile_cmd -> PersistenceService / TestExecutionEngine
+#[tauri::command]
+pub async fn save_batch_analog_test_profile_cmd(
+    state: State<'_, AppState>,
+    batch_id: String,
+    profile: crate::models::AnalogTestProfile,
+) -> Result<(), String> {
+    profile.validate()?;
+
+    let mut batch_info = state.persistence_service
+        .load_batch_info(&batch_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("批次不存在: {}", batch_id))?;
+
+    batch_info.analog_test_profile = Some(profile.clone());
+    state.persistence_service
+        .save_batch_info(&batch_info)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    state.test_execution_engine
+        .set_batch_analog_test_profile(&batch_id, profile)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    log::info!("✅ [CMD] 批次模拟量测试方案已保存: {}", batch_id);
+    Ok(())
+}
+
+/// 获取批次的通信点测试配置
+/// 
+/// 返回：
+/// - Some: 批次保存的源连接与缩放配置
+/// - None: 未配置，该批次的通信模块点位不参与自动测试
+/// 
+/// 调用链：
+/// 前端批次配置页面 -> get_batch_communication_test_config_cmd -> PersistenceService
+#[tauri::command]
+pub async fn get_batch_communication_test_config_cmd(
+    state: State<'_, AppState>,
+    batch_id: String,
+) -> Result<Option<crate::models::CommunicationTestConfig>, String> {
+    let batch_info = state.persistence_service
+        .load_batch_info(&batch_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("批次不存在: {}", batch_id))?;
+
+    Ok(batch_info.communication_test_config)
+}
+
+/// 保存批次的通信点测试配置
+/// 
+/// 业务说明：
+/// - 通信模块点位通过源连接（例如Modbus网关）写入测试数据，再从被测PLC读回
+/// - 配置随批次保存（源连接密码以密文落库），程序重启后启动测试时自动恢复
+/// - 保存前先建立源连接，连接失败时不更新配置
+/// - 传入 None 时清除配置，该批次的通信模块点位不再参与自动测试
+/// 
+/// 调用链：
+/// 前端批次配置页面 -> save_batch_communication_test_config_cmd -> PLC服务 / PersistenceService / TestExecutionEngine
+#[tauri::command]
+pub async fn save_batch_communication_test_config_cmd(
+    state: State<'_, AppState>,
+    batch_id: String,
+    config: Option<crate::models::CommunicationTestConfig>,
+) -> Result<(), String> {
+    if let Some(config) = &config {
+        config.validate()?;
+
+        let plc_service = crate::infrastructure::plc_communication::global_plc_service();
+        plc_service
+            .connect(&config.source_connection)
+            .await
+            .map_err(|e| format!("通信测试源连接失败: {}", e))?;
+    }
+
+    let mut batch_info = state.persistence_service
+        .load_batch_info(&batch_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("批次不存在: {}", batch_id))?;
+
+    batch_info.communication_test_config = config.clone();
+    state.persistence_service
+        .save_batch_info(&batch_info)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    state.test_execution_engine
+        .set_batch_communication_test_config(&batch_id, config)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    log::info!("✅ [CMD] 批次通信点测试配置已保存: {}", batch_id);
+    Ok(())
+}
+
 /// 获取批次测试实例
 /// 
 /// 业务说明：
@@ -1612,8 +1796,12 @@ pub async fn load_app_settings_cmd(
 #[tauri::command]
 pub async fn save_app_settings_cmd(
     state: State<'_, AppState>,
-    settings: AppSettings,
+    mut settings: AppSettings,
 ) -> Result<(), String> {
+    // 最近备份时间由备份任务维护，前端持有的旧值不能覆盖它
+    if let Ok(Some(stored)) = state.app_settings_service.load_settings().await {
+        settings.last_backup_time = settings.last_backup_time.max(stored.last_backup_time);
+    }
     state.app_settings_service
         .save_settings(&settings)
         .await
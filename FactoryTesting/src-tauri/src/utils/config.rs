@@ -54,6 +54,10 @@ fn default_byte_order() -> String {
     "CDAB".to_string()
 }
 
+fn default_auto_retry_backoff_ms() -> u64 {
+    500
+}
+
 /// 应用程序主配置结构
 /// 包含应用程序运行所需的所有配置信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -187,6 +191,96 @@ pub struct PlcConfig {
     /// **环境隔离**: 避免开发测试影响生产设备
     #[serde(default)]
     pub mock_mode: bool,
+
+    /// 内置PLC仿真器参数
+    /// **业务含义**: Mock模式下测试PLC与被测PLC均由本地Modbus TCP仿真器代替
+    /// **serde属性**: 配置文件中缺失时使用默认仿真参数
+    #[serde(default)]
+    pub simulator: SimulatorConfig,
+
+    /// Modbus报文录制参数
+    /// **业务含义**: 按批次记录每个Modbus请求与应答，用于复核争议点位和离线回放
+    /// **serde属性**: 配置文件中缺失时默认不录制
+    #[serde(default)]
+    pub frame_capture: FrameCaptureConfig,
+}
+
+/// Modbus报文录制配置
+///
+/// **业务作用**:
+/// - 客户对失败点位有异议时，日志只有“读[40001]=…”这类结论，无法还原当时的通信过程
+/// - 开启后按批次把每个请求/应答（时间、连接、测试实例、功能码、数据、耗时）写入录制文件
+/// - 录制文件可导出为CSV查看，也可回放到内置仿真器中离线复现测试过程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrameCaptureConfig {
+    /// 是否启用录制
+    #[serde(default)]
+    pub enabled: bool,
+    /// 录制文件目录，每个批次一个文件
+    #[serde(default = "default_frame_capture_dir")]
+    pub directory: PathBuf,
+}
+
+fn default_frame_capture_dir() -> PathBuf {
+    PathBuf::from("captures")
+}
+
+impl Default for FrameCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: default_frame_capture_dir(),
+        }
+    }
+}
+
+/// 内置PLC仿真器配置
+///
+/// **业务作用**:
+/// - Mock模式下在本机同时仿真测试PLC和被测PLC，用于演示、培训和端到端回归测试
+/// - 测试PLC的AO/DO写入按通道映射回环到被测PLC的AI/DI（反向同理）
+/// - 回环信号支持增益、偏置、噪声和延迟，便于复现现场误差
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatorConfig {
+    /// 测试PLC仿真端口，0表示由系统分配
+    #[serde(default)]
+    pub test_rig_port: u16,
+    /// 被测PLC仿真端口，0表示由系统分配
+    #[serde(default)]
+    pub target_port: u16,
+    /// 模拟量回环增益（1.0为理想回环）
+    #[serde(default = "default_simulator_gain")]
+    pub gain: f32,
+    /// 模拟量回环偏置（占量程的百分比）
+    #[serde(default)]
+    pub offset_percent: f32,
+    /// 模拟量读取噪声幅值（占量程的百分比，均匀分布）
+    #[serde(default)]
+    pub noise_percent: f32,
+    /// 写入到回环生效的延迟（毫秒）
+    #[serde(default = "default_simulator_delay_ms")]
+    pub delay_ms: u64,
+}
+
+fn default_simulator_gain() -> f32 {
+    1.0
+}
+
+fn default_simulator_delay_ms() -> u64 {
+    50
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            test_rig_port: 0,
+            target_port: 0,
+            gain: default_simulator_gain(),
+            offset_percent: 0.0,
+            noise_percent: 0.0,
+            delay_ms: default_simulator_delay_ms(),
+        }
+    }
 }
 
 /// 测试配置
@@ -206,6 +300,9 @@ pub struct TestConfig {
     pub auto_retry_on_failure: bool,
     /// 自动重试次数
     pub auto_retry_count: u32,
+    /// 首次自动重试前的等待时间（毫秒），之后每次翻倍
+    #[serde(default = "default_auto_retry_backoff_ms")]
+    pub auto_retry_backoff_ms: u64,
     /// 批量测试的批次大小
     pub batch_test_size: usize,
 }
@@ -240,6 +337,55 @@ pub struct PersistenceConfig {
     pub backup_interval_hours: u64,
     /// 保留的备份文件数量
     pub max_backups: usize,
+    /// 过期测试数据的定时清理
+    #[serde(default)]
+    pub retention: DataRetentionConfig,
+}
+
+/// 过期测试数据清理配置
+///
+/// **业务作用**:
+/// - 长期运行的测试站数据库会持续增长，按天数清理旧的原始结果、采样数据和已完成批次
+/// - 各项天数为0表示不清理该类数据
+/// - 默认只保留过期批次的最终结果，并在删除前归档，避免误删验收依据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DataRetentionConfig {
+    /// 是否启用定时清理
+    pub enabled: bool,
+    /// 清理间隔（小时）
+    pub cleanup_interval_hours: u64,
+    /// 原始测试结果保留天数
+    pub test_outcomes_retention_days: u32,
+    /// 监控采样数据（读数序列、临时数据）保留天数
+    pub monitoring_samples_retention_days: u32,
+    /// 已完成批次保留天数
+    pub batch_retention_days: u32,
+    /// 保留失败的测试结果及其采样数据
+    pub keep_failed_tests: bool,
+    /// 过期批次只保留最终结果，不整体删除
+    pub keep_final_results_only: bool,
+    /// 删除前归档到独立的SQLite文件
+    pub archive_before_delete: bool,
+    /// 清理后执行VACUUM回收磁盘空间
+    pub compact_after_cleanup: bool,
+}
+
+impl Default for DataRetentionConfig {
+    fn default() -> Self {
+        Self {
+            // 清理会删除历史数据，需在配置中显式开启
+            enabled: false,
+            cleanup_interval_hours: 24,
+            test_outcomes_retention_days: 180,
+            monitoring_samples_retention_days: 30,
+            batch_retention_days: 365,
+            keep_failed_tests: true,
+            keep_final_results_only: true,
+            archive_before_delete: true,
+            compact_after_cleanup: false,
+        }
+    }
 }
 
 impl Default for AppConfig {
@@ -282,6 +428,8 @@ impl Default for PlcConfig {
             byte_order: "CDAB".to_string(),
             zero_based_address: false,
             mock_mode: false,
+            simulator: SimulatorConfig::default(),
+            frame_capture: FrameCaptureConfig::default(),
         }
     }
 }
@@ -290,12 +438,13 @@ impl Default for TestConfig {
     fn default() -> Self {
         Self {
             default_stabilization_time_ms: 2000,
-            analog_tolerance_percent: 1.0,
+            analog_tolerance_percent: 3.0,
             digital_stabilization_time_ms: 500,
             alarm_test_wait_time_ms: 5000,
             auto_skip_not_applicable: true,
             auto_retry_on_failure: false,
             auto_retry_count: 1,
+            auto_retry_backoff_ms: default_auto_retry_backoff_ms(),
             batch_test_size: 20,
         }
     }
@@ -322,6 +471,7 @@ impl Default for PersistenceConfig {
             auto_backup: true,
             backup_interval_hours: 24,
             max_backups: 7,
+            retention: DataRetentionConfig::default(),
         }
     }
 }
@@ -444,6 +594,13 @@ impl ConfigManager {
             self.config.plc_config.mock_mode = mock_mode.to_lowercase() == "true";
         }
 
+        // Modbus报文录制开关覆盖
+        // **环境变量**: PLC_FRAME_CAPTURE
+        // **用途**: 现场排查时临时开启录制，无需修改配置文件
+        if let Ok(capture) = std::env::var("PLC_FRAME_CAPTURE") {
+            self.config.plc_config.frame_capture.enabled = capture.to_lowercase() == "true";
+        }
+
         // 应用程序设置
         if let Ok(env) = std::env::var("APP_ENVIRONMENT") {
             self.config.app_settings.environment = env;
@@ -558,6 +715,33 @@ pub fn get_global_config() -> AppResult<AppConfig> {
     Ok(config_manager.get_config().clone())
 }
 
+/// 获取当前生效的PLC配置
+///
+/// 优先读取全局配置；全局配置未初始化时使用默认值并应用环境变量覆盖，
+/// 保证 `PLC_MOCK_MODE` 等开关在未加载配置文件时同样生效。
+pub fn effective_plc_config() -> PlcConfig {
+    get_global_config()
+        .map(|config| config.plc_config)
+        .unwrap_or_else(|_| {
+            let mut manager = ConfigManager::new(PathBuf::from("config/app_config.json"));
+            manager.override_from_env();
+            manager.get_config().plc_config.clone()
+        })
+}
+
+/// 获取当前生效的持久化配置（自动备份计划等）
+///
+/// 全局配置未初始化时使用默认值，与 [`effective_plc_config`] 保持一致。
+pub fn effective_persistence_config() -> PersistenceConfig {
+    get_global_config()
+        .map(|config| config.persistence_config)
+        .unwrap_or_else(|_| {
+            let mut manager = ConfigManager::new(PathBuf::from("config/app_config.json"));
+            manager.override_from_env();
+            manager.get_config().persistence_config.clone()
+        })
+}
+
 /// 更新全局配置
 pub async fn update_global_config<F>(updater: F) -> AppResult<()>
 where
@@ -0,0 +1,140 @@
+//! # 凭据加密模块 (Credential Cipher)
+//!
+//! ## 业务说明
+//! PLC连接配置中的密码（如OPC UA用户名登录）不能以明文写入数据库：数据库会被备份、
+//! 导出并随测试记录归档。本模块以 AES-256-GCM 加密凭据，密钥保存在数据目录下的
+//! `credential.key` 文件中（Unix 下权限为 0600），与数据库文件分开存放，
+//! 单独拷走数据库或备份文件无法还原密码。
+//!
+//! ## 使用方式
+//! ```ignore
+//! credential_cipher::init_key_file(&data_dir)?;       // 启动时初始化一次
+//! let stored = credential_cipher::encrypt("secret")?;  // "v1:<base64(nonce|密文)>"
+//! let plain = credential_cipher::decrypt(&stored)?;
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use once_cell::sync::OnceCell;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::utils::error::{AppError, AppResult};
+
+/// 密钥文件名
+pub const KEY_FILE_NAME: &str = "credential.key";
+/// 密文格式版本前缀
+const CIPHER_PREFIX: &str = "v1:";
+/// AES-256 密钥长度
+const KEY_LEN: usize = 32;
+
+static KEY: OnceCell<LessSafeKey> = OnceCell::new();
+
+/// 加载数据目录下的密钥文件，不存在时生成新密钥；重复调用时沿用首次加载的密钥
+pub fn init_key_file(dir: &Path) -> AppResult<()> {
+    if KEY.get().is_some() {
+        return Ok(());
+    }
+    let path = dir.join(KEY_FILE_NAME);
+    let key_bytes = if path.exists() {
+        let encoded = std::fs::read_to_string(&path)
+            .map_err(|e| AppError::io_error(format!("读取凭据密钥文件失败: {}", path.display()), e.kind().to_string()))?;
+        let bytes = BASE64.decode(encoded.trim())
+            .map_err(|e| AppError::configuration_error(format!("凭据密钥文件格式错误: {}", e)))?;
+        if bytes.len() != KEY_LEN {
+            return Err(AppError::configuration_error(format!("凭据密钥长度错误: {} 字节", bytes.len())));
+        }
+        bytes
+    } else {
+        create_key_file(&path)?
+    };
+
+    let key = UnboundKey::new(&AES_256_GCM, &key_bytes)
+        .map_err(|_| AppError::configuration_error("凭据密钥无效"))?;
+    let _ = KEY.set(LessSafeKey::new(key));
+    Ok(())
+}
+
+fn create_key_file(path: &PathBuf) -> AppResult<Vec<u8>> {
+    let mut bytes = vec![0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| AppError::generic("生成凭据密钥失败"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::io_error(format!("创建数据目录失败: {}", parent.display()), e.kind().to_string()))?;
+    }
+    std::fs::write(path, BASE64.encode(&bytes))
+        .map_err(|e| AppError::io_error(format!("写入凭据密钥文件失败: {}", path.display()), e.kind().to_string()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600));
+    }
+    log::info!("已生成凭据密钥文件: {}", path.display());
+    Ok(bytes)
+}
+
+fn key() -> AppResult<&'static LessSafeKey> {
+    KEY.get().ok_or_else(|| AppError::configuration_error("凭据密钥未初始化"))
+}
+
+/// 加密凭据，返回带版本前缀的 Base64 文本
+pub fn encrypt(plain: &str) -> AppResult<String> {
+    let key = key()?;
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce_bytes)
+        .map_err(|_| AppError::generic("生成随机数失败"))?;
+
+    let mut sealed = plain.as_bytes().to_vec();
+    key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::empty(), &mut sealed)
+        .map_err(|_| AppError::generic("凭据加密失败"))?;
+
+    let mut payload = nonce_bytes.to_vec();
+    payload.extend_from_slice(&sealed);
+    Ok(format!("{}{}", CIPHER_PREFIX, BASE64.encode(payload)))
+}
+
+/// 解密 `encrypt` 生成的凭据
+pub fn decrypt(stored: &str) -> AppResult<String> {
+    let key = key()?;
+    let encoded = stored
+        .strip_prefix(CIPHER_PREFIX)
+        .ok_or_else(|| AppError::validation_error("不支持的凭据密文格式"))?;
+    let mut payload = BASE64.decode(encoded)
+        .map_err(|e| AppError::validation_error(format!("凭据密文格式错误: {}", e)))?;
+    if payload.len() < NONCE_LEN {
+        return Err(AppError::validation_error("凭据密文长度错误"));
+    }
+
+    let mut sealed = payload.split_off(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(&payload)
+        .map_err(|_| AppError::validation_error("凭据密文长度错误"))?;
+    let plain = key.open_in_place(nonce, Aad::empty(), &mut sealed)
+        .map_err(|_| AppError::validation_error("凭据解密失败，密钥文件可能已更换"))?;
+    String::from_utf8(plain.to_vec()).map_err(|_| AppError::validation_error("凭据解密结果不是有效文本"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_tampering() {
+        let dir = std::env::temp_dir().join(format!("credential_cipher_{}", uuid::Uuid::new_v4()));
+        init_key_file(&dir).unwrap();
+
+        let stored = encrypt("操作员密码").unwrap();
+        assert!(stored.starts_with(CIPHER_PREFIX));
+        assert!(!stored.contains("操作员密码"));
+        assert_ne!(stored, encrypt("操作员密码").unwrap(), "每次加密使用新的随机数");
+        assert_eq!(decrypt(&stored).unwrap(), "操作员密码");
+
+        let mut payload = BASE64.decode(&stored[CIPHER_PREFIX.len()..]).unwrap();
+        *payload.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&format!("{}{}", CIPHER_PREFIX, BASE64.encode(payload))).is_err());
+        assert!(decrypt("明文").is_err());
+    }
+}
@@ -51,6 +51,7 @@
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use crate::models::enums::FailureKind;
 use tokio_modbus;
 use rust_xlsxwriter;
 
@@ -106,6 +107,27 @@ pub enum AppError {
     #[error("PLC通信错误: {message}")]
     PlcCommunicationError { message: String },
 
+    /// Modbus从站返回的异常应答
+    ///
+    /// **业务含义**: 请求已送达从站，但被从站拒绝；非法地址/功能码/数据值通常是点表配置问题，
+    /// 网关路径不可用、网关目标无响应、从站忙等则属于通信链路问题
+    #[error("Modbus异常: {} (异常码 0x{exception_code:02X}, 功能码 0x{function_code:02X}, 地址 {address}, 连接 {connection_id})", modbus_exception_name(.exception_code))]
+    ModbusException {
+        exception_code: u8,
+        function_code: u8,
+        address: String,
+        connection_id: String,
+    },
+
+    /// Modbus请求在超时时间内未收到应答
+    #[error("Modbus超时: 功能码 0x{function_code:02X}, 地址 {address}, 连接 {connection_id}, 超时 {timeout_ms}ms")]
+    ModbusTimeout {
+        function_code: u8,
+        address: String,
+        connection_id: String,
+        timeout_ms: u64,
+    },
+
     /// 数据序列化/反序列化错误
     #[error("序列化错误: {message}")]
     SerializationError { message: String },
@@ -288,6 +310,36 @@ impl AppError {
         }
     }
 
+    /// 创建Modbus异常应答错误
+    pub fn modbus_exception(
+        exception_code: u8,
+        function_code: u8,
+        address: impl Into<String>,
+        connection_id: impl Into<String>,
+    ) -> Self {
+        Self::ModbusException {
+            exception_code,
+            function_code,
+            address: address.into(),
+            connection_id: connection_id.into(),
+        }
+    }
+
+    /// 创建Modbus请求超时错误
+    pub fn modbus_timeout(
+        function_code: u8,
+        address: impl Into<String>,
+        connection_id: impl Into<String>,
+        timeout_ms: u64,
+    ) -> Self {
+        Self::ModbusTimeout {
+            function_code,
+            address: address.into(),
+            connection_id: connection_id.into(),
+            timeout_ms,
+        }
+    }
+
     /// 创建序列化错误
     pub fn serialization_error(message: impl Into<String>) -> Self {
         Self::SerializationError {
@@ -482,6 +534,8 @@ impl AppError {
             AppError::IoError { .. } => "IO_ERROR",
             AppError::PersistenceError { .. } => "PERSISTENCE_ERROR",
             AppError::PlcCommunicationError { .. } => "PLC_COMMUNICATION_ERROR",
+            AppError::ModbusException { .. } => "MODBUS_EXCEPTION",
+            AppError::ModbusTimeout { .. } => "MODBUS_TIMEOUT",
             AppError::SerializationError { .. } => "SERIALIZATION_ERROR",
             AppError::ConfigurationError { .. } => "CONFIGURATION_ERROR",
             AppError::ValidationError { .. } => "VALIDATION_ERROR",
@@ -512,6 +566,57 @@ impl AppError {
             AppError::DependencyInjectionError(..) => "DEPENDENCY_INJECTION_ERROR",
         }
     }
+
+    /// 测试失败时的原因分类
+    ///
+    /// - 非法功能码/地址/数据值的异常应答、配置与验证错误 → 配置错误
+    /// - 超时、网络/IO错误、其余异常应答与PLC通信错误 → 通信故障
+    /// - 其他错误视为测试过程中的信号异常
+    pub fn failure_kind(&self) -> FailureKind {
+        match self {
+            AppError::ModbusException { exception_code: 0x01..=0x03, .. }
+            | AppError::ConfigurationError { .. }
+            | AppError::ValidationError { .. }
+            | AppError::NotFoundError { .. } => FailureKind::Configuration,
+            AppError::ModbusException { .. }
+            | AppError::ModbusTimeout { .. }
+            | AppError::PlcCommunicationError { .. }
+            | AppError::TimeoutError { .. }
+            | AppError::NetworkError { .. }
+            | AppError::IoError { .. } => FailureKind::Communication,
+            _ => FailureKind::Signal,
+        }
+    }
+
+    /// 按失败分类构造错误，用于还原批量读写结果中逐项记录的失败
+    pub fn classified(kind: FailureKind, message: impl Into<String>) -> Self {
+        match kind {
+            FailureKind::Configuration => AppError::configuration_error(message),
+            FailureKind::Communication => AppError::plc_communication_error(message),
+            FailureKind::Signal => AppError::generic(message),
+        }
+    }
+
+    /// 为底层错误附加上下文说明，包装后的错误保持原有的失败分类
+    pub fn with_context(self, context: impl std::fmt::Display) -> Self {
+        Self::classified(self.failure_kind(), format!("{}: {}", context, self))
+    }
+}
+
+/// Modbus异常码的中文名称
+pub fn modbus_exception_name(exception_code: &u8) -> &'static str {
+    match exception_code {
+        0x01 => "非法功能码",
+        0x02 => "非法数据地址",
+        0x03 => "非法数据值",
+        0x04 => "从站设备故障",
+        0x05 => "请求已确认",
+        0x06 => "从站设备忙",
+        0x08 => "存储奇偶性差错",
+        0x0A => "网关路径不可用",
+        0x0B => "网关目标设备无响应",
+        _ => "未知异常",
+    }
 }
 
 /// 标准 I/O 错误到 AppError 的转换
@@ -28,11 +28,16 @@ pub mod config;
 /// 时间工具模块（UTC ↔ 北京时间转换）
 pub mod time_utils;
 
+/// 连接凭据加密模块
+pub mod credential_cipher;
+
 
 
 // 重新导出常用类型，方便使用
 pub use error::{AppError, AppResult};
 pub use config::{
-    AppConfig, AppSettings, PlcConfig, TestConfig, LoggingConfig, PersistenceConfig,
-    ConfigManager, init_global_config, get_global_config, update_global_config,
+    AppConfig, AppSettings, PlcConfig, SimulatorConfig, FrameCaptureConfig, TestConfig, LoggingConfig, PersistenceConfig,
+    DataRetentionConfig,
+    ConfigManager, init_global_config, get_global_config, update_global_config, effective_plc_config,
+    effective_persistence_config,
 }; 
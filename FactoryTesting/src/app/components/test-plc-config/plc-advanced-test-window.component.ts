@@ -592,6 +592,8 @@ export class PlcAdvancedTestWindowComponent implements OnInit, OnDestroy, OnChan
     switch (plcType) {
       case PlcType.ModbusTcp:
         return 'Modbus TCP';
+      case PlcType.ModbusRtu:
+        return 'Modbus RTU';
       case PlcType.SiemensS7:
         return 'Siemens S7';
       case PlcType.OpcUa:
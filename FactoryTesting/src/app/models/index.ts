@@ -107,6 +107,12 @@ export enum SubTestStatus {
   Skipped = 'Skipped'        // 跳过
 }
 
+export enum FailureKind {
+  Configuration = 'Configuration', // 配置错误
+  Communication = 'Communication', // 通信故障
+  Signal = 'Signal'                // 信号异常
+}
+
 // ============================================================================
 // 核心数据模型 - 业务实体
 // ============================================================================
@@ -207,6 +213,7 @@ export interface SubTestExecutionResult {
   expected_value?: string;
   actual_value?: string;
   timestamp: string;
+  failure_kind?: FailureKind;              // 失败时的故障类型
 }
 
 /**
@@ -285,6 +292,71 @@ export interface RawTestOutcome {
   end_time: string;
   readings?: AnalogReadingPoint[];
   details?: { [key: string]: any };
+  attempt?: number;                        // 第几次执行（自动重试时大于1）
+  retry_pending?: boolean;                 // 本次失败后引擎将自动重试
+  failure_kind?: FailureKind;              // 失败时的故障类型
+}
+
+/**
+ * 条件查询参数，对应后端 QueryCriteria；未填写的条件不参与过滤
+ * tag_pattern 支持 * 与 ? 通配符，不含通配符时按包含匹配
+ */
+export interface QueryCriteria {
+  station_name?: string;
+  batch_id?: string;
+  module_type?: ModuleType;
+  status?: OverallTestStatus;
+  tag_pattern?: string;
+  created_after?: string;
+  created_before?: string;
+  updated_after?: string;
+  updated_before?: string;
+  limit?: number;
+  offset?: number;
+  sort_by?: string;
+  sort_desc?: boolean;
+}
+
+/**
+ * 条件查询结果，total_count 为满足条件的总记录数
+ */
+export interface QueryResult<T> {
+  items: T[];
+  total_count: number;
+  has_more: boolean;
+}
+
+export interface BackupInfo {
+  name: string;
+  path: string;
+  created_at: string;
+  size_bytes: number;
+  description?: string;
+  is_auto_backup: boolean;
+  schema_version: number;
+  record_counts: Record<string, number>;
+}
+
+export interface RetentionPolicy {
+  test_outcomes_retention_days: number;
+  batch_info_retention_days: number;
+  logs_retention_days: number;
+  keep_failed_tests: boolean;
+  max_storage_size_bytes?: number;
+  monitoring_samples_retention_days: number;
+  keep_final_results_only: boolean;
+  archive_before_delete: boolean;
+}
+
+export interface CleanupResult {
+  deleted_records: number;
+  deleted_by_table: Record<string, number>;
+  freed_space_bytes: number;
+  duration_ms: number;
+  cleanup_time: string;
+  archived_records: number;
+  cleared_samples: number;
+  archive_path?: string;
 }
 
 export interface AnalogReadingPoint {
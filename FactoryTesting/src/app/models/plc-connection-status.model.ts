@@ -13,6 +13,48 @@ export interface PlcConnectionStatus {
   lastCheckTime: string;          // 最后检查时间
 }
 
+/**
+ * PLC连接事件类型
+ */
+export type ConnectionEventKind = 'Connected' | 'Disconnected' | 'ReconnectFailed';
+
+/**
+ * PLC连接事件
+ */
+export interface ConnectionEvent {
+  connection_id: string;
+  timestamp: string;
+  kind: ConnectionEventKind;
+  message?: string;
+}
+
+/**
+ * 请求时延直方图的一个桶
+ */
+export interface LatencyBucket {
+  upper_ms?: number;              // 桶上界（毫秒），溢出桶为空
+  count: number;
+}
+
+/**
+ * 单个PLC连接的质量快照
+ */
+export interface ConnectionQualitySnapshot {
+  connection_id: string;
+  connection_name: string;
+  state: 'Disconnected' | 'Connecting' | 'Connected' | 'Reconnecting' | 'Error';
+  events: ConnectionEvent[];      // 最近的连接事件
+  latency_histogram: LatencyBucket[];
+  window_seconds: number;         // 错误率统计窗口（秒）
+  window_requests: number;
+  window_errors: number;
+  error_rate: number;             // 窗口内错误率（0~1）
+  total_requests: number;
+  total_errors: number;
+  disconnect_count: number;
+  total_downtime_ms: number;      // 累计掉线时长
+}
+
 /**
  * PLC连接状态枚举
  */
@@ -49,6 +49,115 @@ export interface PlcConnectionConfig {
   isEnabled: boolean;                    // 是否启用
   lastConnected?: Date;                  // 最后连接时间
   connectionStatus: ConnectionStatus;    // 连接状态
+  serialSettings?: SerialPortSettings;   // 串口参数（仅Modbus RTU）
+  s7Settings?: S7ConnectionSettings;     // 机架/插槽参数（仅西门子S7）
+  opcUaSettings?: OpcUaConnectionSettings; // 端点路径/认证参数（仅OPC UA）
+  modbusDataSettings?: ModbusDataSettings; // 64位字序/字符串编码参数（仅Modbus）
+  modbusSchedulerSettings?: ModbusSchedulerSettings; // 请求调度参数（仅Modbus）
+}
+
+/**
+ * 串口参数（Modbus RTU）
+ */
+export interface SerialPortSettings {
+  devicePath: string;                    // 串口设备 (如: /dev/ttyUSB0, COM3)
+  baudRate: number;                      // 波特率
+  dataBits: number;                      // 数据位
+  parity: SerialParity;                  // 校验位
+  stopBits: number;                      // 停止位
+  slaveId: number;                       // Modbus从站地址
+}
+
+/**
+ * 串口校验位
+ */
+export enum SerialParity {
+  None = 'None',
+  Odd = 'Odd',
+  Even = 'Even'
+}
+
+/**
+ * 西门子S7连接参数
+ */
+export interface S7ConnectionSettings {
+  rack: number;                          // 机架号
+  slot: number;                          // CPU插槽号
+  connectionType: S7ConnectionType;      // 连接类型
+}
+
+/**
+ * S7连接类型
+ */
+export enum S7ConnectionType {
+  Pg = 'Pg',
+  Op = 'Op',
+  Basic = 'Basic'
+}
+
+/**
+ * OPC UA连接参数
+ */
+export interface OpcUaConnectionSettings {
+  endpointPath: string;                  // 端点路径，如 /freeopcua/server/
+  authMode: OpcUaAuthMode;               // 认证方式
+  username?: string;                     // 用户名（仅用户名登录）
+  password?: string;                     // 密码（仅用户名登录），只随保存请求提交，后端不会回传；留空表示沿用已保存的密码
+  passwordSaved?: boolean;               // 后端是否已保存密码（只读）
+  allowPlaintextPassword?: boolean;      // 允许在未加密通道上以明文发送密码（需确认网络可信）
+}
+
+/**
+ * OPC UA认证方式
+ */
+export enum OpcUaAuthMode {
+  Anonymous = 'Anonymous',
+  UserName = 'UserName'
+}
+
+/**
+ * Modbus多寄存器数据参数
+ */
+export interface ModbusDataSettings {
+  wordSwap64: boolean;                   // 64位数值交换高低两个双字
+  stringEncoding: ModbusStringEncoding;  // 字符串编码
+  stringPadding: ModbusStringPadding;    // 字符串填充字符
+  stringByteSwap: boolean;               // 字符串在寄存器内低字节在前
+  mAreaAddressing?: ModbusMAreaAddressing; // %M 地址编址方式，缺省按字节编址
+}
+
+/**
+ * %MW/%MD/%MX 地址编址方式
+ */
+export enum ModbusMAreaAddressing {
+  ByteOffset = 'ByteOffset',             // 按字节编址（西门子风格）：%MW100 即保持寄存器50
+  WordIndex = 'WordIndex'                // 按字编址（施耐德/CODESYS）：%MW100 即保持寄存器100
+}
+
+/**
+ * Modbus请求调度参数
+ */
+export interface ModbusSchedulerSettings {
+  maxInFlight: number;                   // 同时在途的最大请求数
+  interFrameGapMs: number;               // 相邻两帧之间的最小间隔(ms)
+  queueDeadlineMs: number;               // 请求排队期限(ms)，0表示使用连接超时
+}
+
+/**
+ * Modbus字符串编码
+ */
+export enum ModbusStringEncoding {
+  Ascii = 'Ascii',
+  Utf8 = 'Utf8',
+  Utf16 = 'Utf16'
+}
+
+/**
+ * Modbus字符串填充字符
+ */
+export enum ModbusStringPadding {
+  Null = 'Null',
+  Space = 'Space'
 }
 
 /**
@@ -56,6 +165,7 @@ export interface PlcConnectionConfig {
  */
 export enum PlcType {
   ModbusTcp = 'ModbusTcp',
+  ModbusRtu = 'ModbusRtu',
   SiemensS7 = 'SiemensS7',
   OpcUa = 'OpcUa'
 }
@@ -159,6 +269,7 @@ export const TestPlcChannelTypeLabels: Record<TestPlcChannelType, string> = {
  */
 export const PlcTypeLabels: Record<PlcType, string> = {
   [PlcType.ModbusTcp]: 'Modbus TCP',
+  [PlcType.ModbusRtu]: 'Modbus RTU',
   [PlcType.SiemensS7]: 'Siemens S7',
   [PlcType.OpcUa]: 'OPC UA'
 };
@@ -58,9 +58,14 @@ import {
   BatchDetailsPayload,
   ImportExcelAndCreateBatchResponse,
   DashboardBatchInfo,
-  DeleteBatchResponse
+  DeleteBatchResponse,
+  QueryCriteria,
+  QueryResult,
+  BackupInfo,
+  RetentionPolicy,
+  CleanupResult
 } from '../models';
-import { PlcConnectionStatus } from '../models/plc-connection-status.model';
+import { PlcConnectionStatus, ConnectionQualitySnapshot } from '../models/plc-connection-status.model';
 
 /**
  * Tauri API服务类
@@ -343,6 +348,18 @@ export class TauriApiService {
     );
   }
 
+  /**
+   * 获取PLC连接质量历史（连接事件、时延分布、错误率）
+   */
+  getPlcConnectionHistory(): Observable<ConnectionQualitySnapshot[]> {
+    return from(invoke<ConnectionQualitySnapshot[]>('get_plc_connection_history_cmd')).pipe(
+      catchError(error => {
+        console.error('❌ [TAURI_API] 获取PLC连接质量历史失败:', error);
+        throw error;
+      })
+    );
+  }
+
   // ============================================================================
   // 数据管理相关命令
   // ============================================================================
@@ -505,6 +522,62 @@ export class TauriApiService {
     );
   }
 
+  /**
+   * 按条件分页查询通道定义
+   */
+  queryChannelDefinitions(criteria: QueryCriteria): Observable<QueryResult<ChannelPointDefinition>> {
+    return from(invoke<QueryResult<ChannelPointDefinition>>('query_channel_definitions_cmd', { criteria }));
+  }
+
+  /**
+   * 按条件分页查询测试实例
+   */
+  queryTestInstances(criteria: QueryCriteria): Observable<QueryResult<ChannelTestInstance>> {
+    return from(invoke<QueryResult<ChannelTestInstance>>('query_test_instances_cmd', { criteria }));
+  }
+
+  /**
+   * 按条件分页查询测试批次
+   */
+  queryTestBatches(criteria: QueryCriteria): Observable<QueryResult<TestBatchInfo>> {
+    return from(invoke<QueryResult<TestBatchInfo>>('query_test_batches_cmd', { criteria }));
+  }
+
+  /**
+   * 按条件分页查询原始测试结果
+   */
+  queryTestOutcomes(criteria: QueryCriteria): Observable<QueryResult<RawTestOutcome>> {
+    return from(invoke<QueryResult<RawTestOutcome>>('query_test_outcomes_cmd', { criteria }));
+  }
+
+  /**
+   * 立即备份数据库
+   */
+  createDatabaseBackup(backupName?: string): Observable<BackupInfo> {
+    return from(invoke<BackupInfo>('create_database_backup_cmd', { backupName }));
+  }
+
+  /**
+   * 列出数据库备份（按时间倒序）
+   */
+  listDatabaseBackups(): Observable<BackupInfo[]> {
+    return from(invoke<BackupInfo[]>('list_database_backups_cmd'));
+  }
+
+  /**
+   * 从备份恢复数据库，成功后需重新加载批次数据
+   */
+  restoreDatabaseBackup(backupPath: string): Observable<void> {
+    return from(invoke<void>('restore_database_backup_cmd', { backupPath }));
+  }
+
+  /**
+   * 按保留策略清理过期测试数据，不传策略时使用配置文件中的策略
+   */
+  cleanupExpiredData(policy?: RetentionPolicy): Observable<CleanupResult> {
+    return from(invoke<CleanupResult>('cleanup_expired_data_cmd', { policy }));
+  }
+
   /**
    * 获取批次的通道定义列表
    */